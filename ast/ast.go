@@ -84,24 +84,33 @@ func (*PackageClause) node()     {}
 func (*ImportDeclaration) node() {}
 func (*Block) node()             {}
 
-func (*BadStatement) node()        {}
-func (*ExpressionStatement) node() {}
-func (*ReturnStatement) node()     {}
-func (*OptionStatement) node()     {}
-func (*BuiltinStatement) node()    {}
-func (*TestStatement) node()       {}
-func (*TestCaseStatement) node()   {}
-func (*VariableAssignment) node()  {}
-func (*MemberAssignment) node()    {}
+func (*BadStatement) node()            {}
+func (*ExpressionStatement) node()     {}
+func (*ReturnStatement) node()         {}
+func (*OptionStatement) node()         {}
+func (*BuiltinStatement) node()        {}
+func (*TypeAliasStatement) node()      {}
+func (*TestStatement) node()           {}
+func (*TestCaseStatement) node()       {}
+func (*ExportStatement) node()         {}
+func (*VariableAssignment) node()      {}
+func (*MemberAssignment) node()        {}
+func (*DestructuringAssignment) node() {}
 
 func (*StringExpression) node()      {}
 func (*ParenExpression) node()       {}
 func (*ArrayExpression) node()       {}
 func (*DictExpression) node()        {}
+func (*TupleExpression) node()       {}
+func (*TupleIndexExpression) node()  {}
+func (*BlockExpression) node()       {}
+func (*MatchExpression) node()       {}
 func (*FunctionExpression) node()    {}
 func (*BinaryExpression) node()      {}
 func (*CallExpression) node()        {}
 func (*ConditionalExpression) node() {}
+func (*TernaryExpression) node()     {}
+func (*SpreadExpression) node()      {}
 func (*LogicalExpression) node()     {}
 func (*MemberExpression) node()      {}
 func (*IndexExpression) node()       {}
@@ -132,6 +141,7 @@ func (*StreamType) node()     {}
 func (*DictType) node()       {}
 func (*RecordType) node()     {}
 func (*FunctionType) node()   {}
+func (*AppliedType) node()    {}
 func (*PropertyType) node()   {}
 func (*ParameterType) node()  {}
 func (*TypeConstraint) node() {}
@@ -246,6 +256,7 @@ func (StreamType) monotype()   {}
 func (DictType) monotype()     {}
 func (RecordType) monotype()   {}
 func (FunctionType) monotype() {}
+func (AppliedType) monotype()  {}
 
 type NamedType struct {
 	BaseNode
@@ -375,8 +386,10 @@ func (c *RecordType) Copy() Node {
 
 type PropertyType struct {
 	BaseNode
-	Name *Identifier `json:"name"`
-	Ty   MonoType    `json:"monotype"`
+	Name     *Identifier `json:"name"`
+	Ty       MonoType    `json:"monotype"`
+	// Optional reports whether the property was written as `?name: type`, records may omit it.
+	Optional bool        `json:"optional,omitempty"`
 }
 
 func (PropertyType) Type() string {
@@ -420,6 +433,35 @@ func (c *FunctionType) Copy() Node {
 	return nc
 }
 
+// AppliedType is a parameterized type alias applied to type arguments, e.g. `Pair[int]`.
+type AppliedType struct {
+	BaseNode
+	ID        *Identifier `json:"name"`
+	Arguments []MonoType  `json:"arguments"`
+}
+
+func (AppliedType) Type() string {
+	return "AppliedType"
+}
+func (c *AppliedType) Copy() Node {
+	if c == nil {
+		return c
+	}
+	nc := new(AppliedType)
+	*nc = *c
+	nc.BaseNode = c.BaseNode.Copy()
+
+	nc.ID = c.ID.Copy().(*Identifier)
+	if len(c.Arguments) > 0 {
+		nc.Arguments = make([]MonoType, len(c.Arguments))
+		for i, arg := range c.Arguments {
+			nc.Arguments[i] = arg.Copy().(MonoType)
+		}
+	}
+
+	return nc
+}
+
 type ParameterKind string
 
 const (
@@ -596,15 +638,18 @@ type Statement interface {
 	stmt()
 }
 
-func (*BadStatement) stmt()        {}
-func (*VariableAssignment) stmt()  {}
-func (*MemberAssignment) stmt()    {}
-func (*ExpressionStatement) stmt() {}
-func (*ReturnStatement) stmt()     {}
-func (*OptionStatement) stmt()     {}
-func (*BuiltinStatement) stmt()    {}
-func (*TestStatement) stmt()       {}
-func (*TestCaseStatement) stmt()   {}
+func (*BadStatement) stmt()            {}
+func (*VariableAssignment) stmt()      {}
+func (*MemberAssignment) stmt()        {}
+func (*DestructuringAssignment) stmt() {}
+func (*ExpressionStatement) stmt()     {}
+func (*ReturnStatement) stmt()         {}
+func (*OptionStatement) stmt()         {}
+func (*BuiltinStatement) stmt()        {}
+func (*TypeAliasStatement) stmt()      {}
+func (*TestStatement) stmt()           {}
+func (*TestCaseStatement) stmt()       {}
+func (*ExportStatement) stmt()         {}
 
 type Assignment interface {
 	Statement
@@ -706,6 +751,31 @@ func (s *OptionStatement) Copy() Node {
 	return ns
 }
 
+// ExportStatement marks a top-level binding as exported by its package, e.g. `export x = 1`.
+type ExportStatement struct {
+	BaseNode
+	Statement Statement `json:"statement"`
+}
+
+// Type is the abstract type
+func (*ExportStatement) Type() string { return "ExportStatement" }
+
+// Copy returns a deep copy of an ExportStatement Node
+func (s *ExportStatement) Copy() Node {
+	if s == nil {
+		return s
+	}
+	ns := new(ExportStatement)
+	*ns = *s
+	ns.BaseNode = s.BaseNode.Copy()
+
+	if s.Statement != nil {
+		ns.Statement = s.Statement.Copy().(Statement)
+	}
+
+	return ns
+}
+
 // BuiltinStatement declares a builtin identifier and its type
 type BuiltinStatement struct {
 	BaseNode
@@ -731,6 +801,40 @@ func (s *BuiltinStatement) Copy() Node {
 	return ns
 }
 
+// TypeAliasStatement declares a name for a type, e.g. `type Point = {x: float, y: float}`
+type TypeAliasStatement struct {
+	BaseNode
+	ID     *Identifier   `json:"id"`
+	Params []*Identifier `json:"params,omitempty"`
+	Ty     MonoType      `json:"ty"`
+}
+
+// Type is the abstract type
+func (*TypeAliasStatement) Type() string { return "TypeAliasStatement" }
+
+// Copy returns a deep copy of a TypeAliasStatement Node
+func (s *TypeAliasStatement) Copy() Node {
+	if s == nil {
+		return s
+	}
+	ns := new(TypeAliasStatement)
+	*ns = *s
+	ns.BaseNode = s.BaseNode.Copy()
+
+	ns.ID = s.ID.Copy().(*Identifier)
+	if len(s.Params) > 0 {
+		ns.Params = make([]*Identifier, len(s.Params))
+		for i, param := range s.Params {
+			ns.Params[i] = param.Copy().(*Identifier)
+		}
+	}
+	if s.Ty != nil {
+		ns.Ty = s.Ty.Copy().(MonoType)
+	}
+
+	return ns
+}
+
 // TestStatement declares a Flux test case
 type TestStatement struct {
 	BaseNode
@@ -783,11 +887,14 @@ func (s *TestCaseStatement) Copy() Node {
 	return ns
 }
 
-// VariableAssignment represents the declaration of a variable
+// VariableAssignment represents the declaration of a variable.
+// The variable may be annotated with a type, such as `x : [int] = [1, 2]`.
 type VariableAssignment struct {
 	BaseNode
-	ID   *Identifier `json:"id"`
-	Init Expression  `json:"init"`
+	ID    *Identifier     `json:"id"`
+	Colon []Comment       `json:"colon,omitempty"`
+	Ty    *TypeExpression `json:"ty,omitempty"`
+	Init  Expression      `json:"init"`
 }
 
 // Type is the abstract type
@@ -834,6 +941,33 @@ func (a *MemberAssignment) Copy() Node {
 	return na
 }
 
+// DestructuringAssignment binds several properties of a record at once, such as `{a, b: c} = r`.
+type DestructuringAssignment struct {
+	BaseNode
+	Pattern *ObjectExpression `json:"pattern"`
+	Init    Expression        `json:"init"`
+}
+
+func (*DestructuringAssignment) Type() string { return "DestructuringAssignment" }
+
+func (a *DestructuringAssignment) Copy() Node {
+	if a == nil {
+		return a
+	}
+	na := new(DestructuringAssignment)
+	*na = *a
+	na.BaseNode = a.BaseNode.Copy()
+
+	if a.Pattern != nil {
+		na.Pattern = a.Pattern.Copy().(*ObjectExpression)
+	}
+	if a.Init != nil {
+		na.Init = a.Init.Copy().(Expression)
+	}
+
+	return na
+}
+
 // Expression represents an action that can be performed by InfluxDB that can be evaluated to a value.
 type Expression interface {
 	Node
@@ -844,11 +978,17 @@ func (*StringExpression) expression()       {}
 func (*ParenExpression) expression()        {}
 func (*ArrayExpression) expression()        {}
 func (*DictExpression) expression()         {}
+func (*TupleExpression) expression()        {}
+func (*TupleIndexExpression) expression()   {}
+func (*BlockExpression) expression()        {}
+func (*MatchExpression) expression()        {}
 func (*FunctionExpression) expression()     {}
 func (*BinaryExpression) expression()       {}
 func (*BooleanLiteral) expression()         {}
 func (*CallExpression) expression()         {}
 func (*ConditionalExpression) expression()  {}
+func (*TernaryExpression) expression()      {}
+func (*SpreadExpression) expression()       {}
 func (*DateTimeLiteral) expression()        {}
 func (*DurationLiteral) expression()        {}
 func (*FloatLiteral) expression()           {}
@@ -1027,6 +1167,8 @@ type MemberExpression struct {
 	Object   Expression  `json:"object"`
 	Lbrack   []Comment   `json:"lbrack,omitempty"`
 	Property PropertyKey `json:"property"`
+	// Optional is true for a guarded access, r?.a.
+	Optional bool        `json:"optional,omitempty"`
 	Rbrack   []Comment   `json:"rbrack,omitempty"`
 }
 
@@ -1142,6 +1284,8 @@ const (
 	NotEqualOperator
 	RegexpMatchOperator
 	NotRegexpMatchOperator
+	NullCoalescingOperator
+	ExponentiationOperator
 	opEnd
 )
 
@@ -1359,6 +1503,140 @@ func (e *DictExpression) Copy() Node {
 	return ne
 }
 
+// TupleExpression is used to create a fixed-size tuple such as `(1, "a")`.
+type TupleExpression struct {
+	BaseNode
+	Lparen   []Comment    `json:"lparen,omitempty"`
+	Elements []Expression `json:"elements"`
+	Rparen   []Comment    `json:"rparen,omitempty"`
+}
+
+// Type is the abstract type
+func (*TupleExpression) Type() string { return "TupleExpression" }
+
+func (e *TupleExpression) Copy() Node {
+	if e == nil {
+		return e
+	}
+	ne := new(TupleExpression)
+	*ne = *e
+	ne.BaseNode = e.BaseNode.Copy()
+
+	if len(e.Elements) > 0 {
+		ne.Elements = make([]Expression, len(e.Elements))
+		for i, el := range e.Elements {
+			ne.Elements[i] = el.Copy().(Expression)
+		}
+	}
+
+	return ne
+}
+
+// TupleIndexExpression represents positional access into a tuple such as `t.0`.
+type TupleIndexExpression struct {
+	BaseNode
+	Tuple Expression      `json:"tuple"`
+	Dot   []Comment       `json:"dot,omitempty"`
+	Index *IntegerLiteral `json:"index"`
+}
+
+// Type is the abstract type
+func (*TupleIndexExpression) Type() string { return "TupleIndexExpression" }
+
+func (e *TupleIndexExpression) Copy() Node {
+	if e == nil {
+		return e
+	}
+	ne := new(TupleIndexExpression)
+	*ne = *e
+	ne.BaseNode = e.BaseNode.Copy()
+
+	if e.Tuple != nil {
+		ne.Tuple = e.Tuple.Copy().(Expression)
+	}
+	ne.Index = e.Index.Copy().(*IntegerLiteral)
+
+	return ne
+}
+
+// BlockExpression is a sequence of statements which evaluates to its trailing expression such as
+// `{ a = 1; a + 1 }`.
+type BlockExpression struct {
+	BaseNode
+	Lbrace []Comment   `json:"lbrace,omitempty"`
+	Body   []Statement `json:"body"`
+	Rbrace []Comment   `json:"rbrace,omitempty"`
+}
+
+// Type is the abstract type
+func (*BlockExpression) Type() string { return "BlockExpression" }
+
+func (e *BlockExpression) Copy() Node {
+	if e == nil {
+		return e
+	}
+	ne := new(BlockExpression)
+	*ne = *e
+	ne.BaseNode = e.BaseNode.Copy()
+
+	if len(e.Body) > 0 {
+		ne.Body = make([]Statement, len(e.Body))
+		for i, stmt := range e.Body {
+			ne.Body[i] = stmt.Copy().(Statement)
+		}
+	}
+	return ne
+}
+
+// MatchExpression evaluates to the body of the first arm whose pattern matches its scrutinee
+// such as `match x { 1 => "one", _ => "many" }`.
+type MatchExpression struct {
+	BaseNode
+	Scrutinee Expression  `json:"scrutinee"`
+	Lbrace    []Comment   `json:"lbrace,omitempty"`
+	Arms      []*MatchArm `json:"arms"`
+	Rbrace    []Comment   `json:"rbrace,omitempty"`
+}
+
+// Type is the abstract type
+func (*MatchExpression) Type() string { return "MatchExpression" }
+
+func (e *MatchExpression) Copy() Node {
+	if e == nil {
+		return e
+	}
+	ne := new(MatchExpression)
+	*ne = *e
+	ne.BaseNode = e.BaseNode.Copy()
+
+	if e.Scrutinee != nil {
+		ne.Scrutinee = e.Scrutinee.Copy().(Expression)
+	}
+	if len(e.Arms) > 0 {
+		ne.Arms = make([]*MatchArm, len(e.Arms))
+		for i, arm := range e.Arms {
+			ne.Arms[i] = &MatchArm{
+				BaseNode: arm.BaseNode.Copy(),
+				Pattern:  arm.Pattern.Copy().(Expression),
+				Arrow:    arm.Arrow,
+				Body:     arm.Body.Copy().(Expression),
+				Comma:    arm.Comma,
+			}
+		}
+	}
+	return ne
+}
+
+// MatchArm is an arm of a match expression, `pattern => body`. The pattern is a literal or the
+// wildcard `_` matching every value.
+type MatchArm struct {
+	BaseNode
+	Pattern Expression `json:"pattern"`
+	Arrow   []Comment  `json:"arrow,omitempty"`
+	Body    Expression `json:"body"`
+	Comma   []Comment  `json:"comma,omitempty"`
+}
+
 // DictItem represents a key value pair of a dictionary literal
 type DictItem struct {
 	Key   Expression `json:"key"`
@@ -1438,6 +1716,69 @@ func (e *ConditionalExpression) Copy() Node {
 	return ne
 }
 
+// TernaryExpression is the shorthand `test ? consequent : alternate` of a ConditionalExpression.
+type TernaryExpression struct {
+	BaseNode
+	Test        Expression `json:"test"`
+	Tk_question []Comment  `json:"tk_question,omitempty"`
+	Consequent  Expression `json:"consequent"`
+	Tk_colon    []Comment  `json:"tk_colon,omitempty"`
+	Alternate   Expression `json:"alternate"`
+}
+
+// Type is the abstract type
+func (*TernaryExpression) Type() string { return "TernaryExpression" }
+
+func (e *TernaryExpression) Copy() Node {
+	if e == nil {
+		return e
+	}
+	ne := new(TernaryExpression)
+	*ne = *e
+	ne.BaseNode = e.BaseNode.Copy()
+
+	if e.Test != nil {
+		ne.Test = e.Test.Copy().(Expression)
+	}
+	if e.Alternate != nil {
+		ne.Alternate = e.Alternate.Copy().(Expression)
+	}
+	if e.Consequent != nil {
+		ne.Consequent = e.Consequent.Copy().(Expression)
+	}
+	return ne
+}
+
+// SpreadExpression expands the elements of an array, `[...xs, 4]`, or the properties of a record,
+// `{...r, x: 1}`, into the literal it is part of.
+type SpreadExpression struct {
+	BaseNode
+	Argument Expression `json:"argument"`
+}
+
+// Type is the abstract type
+func (*SpreadExpression) Type() string { return "SpreadExpression" }
+
+// SpreadExpressions are valid object keys. They stand for all the properties of the spread
+// record, so they have no key of their own.
+func (*SpreadExpression) Key() string {
+	return ""
+}
+
+func (e *SpreadExpression) Copy() Node {
+	if e == nil {
+		return e
+	}
+	ne := new(SpreadExpression)
+	*ne = *e
+	ne.BaseNode = e.BaseNode.Copy()
+
+	if e.Argument != nil {
+		ne.Argument = e.Argument.Copy().(Expression)
+	}
+	return ne
+}
+
 // PropertyKey represents an object key
 type PropertyKey interface {
 	Node
@@ -1809,6 +2150,8 @@ var OperatorTokens = map[OperatorKind]string{
 	NotEqualOperator:         "!=",
 	RegexpMatchOperator:      "=~",
 	NotRegexpMatchOperator:   "!~",
+	NullCoalescingOperator:   "??",
+	ExponentiationOperator:   "**",
 }
 
 // LogicalOperatorTokens converts LogicalOperatorKind to string
@@ -212,6 +212,37 @@ func (s *OptionStatement) UnmarshalJSON(data []byte) error {
 	s.Assignment = a
 	return nil
 }
+func (s *ExportStatement) MarshalJSON() ([]byte, error) {
+	type Alias ExportStatement
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  s.Type(),
+		Alias: (*Alias)(s),
+	}
+	return json.Marshal(raw)
+}
+func (s *ExportStatement) UnmarshalJSON(data []byte) error {
+	type Alias ExportStatement
+	raw := struct {
+		*Alias
+		Statement json.RawMessage `json:"statement"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*s = *(*ExportStatement)(raw.Alias)
+	}
+
+	st, err := unmarshalStatement(raw.Statement)
+	if err != nil {
+		return err
+	}
+	s.Statement = st
+	return nil
+}
 func (s *BuiltinStatement) MarshalJSON() ([]byte, error) {
 	type Alias BuiltinStatement
 	raw := struct {
@@ -244,6 +275,37 @@ func (d *BuiltinStatement) UnmarshalJSON(data []byte) error {
 	d.Ty = *e
 	return nil
 }
+func (s *TypeAliasStatement) MarshalJSON() ([]byte, error) {
+	type Alias TypeAliasStatement
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  s.Type(),
+		Alias: (*Alias)(s),
+	}
+	return json.Marshal(raw)
+}
+func (s *TypeAliasStatement) UnmarshalJSON(data []byte) error {
+	type Alias TypeAliasStatement
+	raw := struct {
+		*Alias
+		Ty json.RawMessage `json:"ty"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*s = *(*TypeAliasStatement)(raw.Alias)
+	}
+
+	ty, err := unmarshalMonotype(raw.Ty)
+	if err != nil {
+		return err
+	}
+	s.Ty = ty
+	return nil
+}
 func (s *TestStatement) MarshalJSON() ([]byte, error) {
 	type Alias TestStatement
 	raw := struct {
@@ -328,6 +390,37 @@ func (a *MemberAssignment) UnmarshalJSON(data []byte) error {
 	a.Init = e
 	return nil
 }
+func (a *DestructuringAssignment) MarshalJSON() ([]byte, error) {
+	type Alias DestructuringAssignment
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  a.Type(),
+		Alias: (*Alias)(a),
+	}
+	return json.Marshal(raw)
+}
+func (a *DestructuringAssignment) UnmarshalJSON(data []byte) error {
+	type Alias DestructuringAssignment
+	raw := struct {
+		*Alias
+		Init json.RawMessage `json:"init"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*a = *(*DestructuringAssignment)(raw.Alias)
+	}
+
+	e, err := unmarshalExpression(raw.Init)
+	if err != nil {
+		return err
+	}
+	a.Init = e
+	return nil
+}
 func (e *CallExpression) MarshalJSON() ([]byte, error) {
 	type Alias CallExpression
 	raw := struct {
@@ -776,6 +869,174 @@ func (e *ArrayExpression) UnmarshalJSON(data []byte) error {
 	}
 	return nil
 }
+func (e *TupleExpression) MarshalJSON() ([]byte, error) {
+	type Alias TupleExpression
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  e.Type(),
+		Alias: (*Alias)(e),
+	}
+	return json.Marshal(raw)
+}
+func (e *TupleExpression) UnmarshalJSON(data []byte) error {
+	type Alias TupleExpression
+	raw := struct {
+		*Alias
+		Elements []json.RawMessage `json:"elements"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*e = *(*TupleExpression)(raw.Alias)
+	}
+
+	e.Elements = make([]Expression, len(raw.Elements))
+	for i, r := range raw.Elements {
+		expr, err := unmarshalExpression(r)
+		if err != nil {
+			return err
+		}
+		e.Elements[i] = expr
+	}
+	return nil
+}
+func (e *TupleIndexExpression) MarshalJSON() ([]byte, error) {
+	type Alias TupleIndexExpression
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  e.Type(),
+		Alias: (*Alias)(e),
+	}
+	return json.Marshal(raw)
+}
+func (e *TupleIndexExpression) UnmarshalJSON(data []byte) error {
+	type Alias TupleIndexExpression
+	raw := struct {
+		*Alias
+		Tuple json.RawMessage `json:"tuple"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*e = *(*TupleIndexExpression)(raw.Alias)
+	}
+
+	tuple, err := unmarshalExpression(raw.Tuple)
+	if err != nil {
+		return err
+	}
+	e.Tuple = tuple
+	return nil
+}
+func (e *BlockExpression) MarshalJSON() ([]byte, error) {
+	type Alias BlockExpression
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  e.Type(),
+		Alias: (*Alias)(e),
+	}
+	return json.Marshal(raw)
+}
+func (e *BlockExpression) UnmarshalJSON(data []byte) error {
+	type Alias BlockExpression
+	raw := struct {
+		*Alias
+		Body []json.RawMessage `json:"body"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*e = *(*BlockExpression)(raw.Alias)
+	}
+
+	e.Body = make([]Statement, len(raw.Body))
+	for i, r := range raw.Body {
+		stmt, err := unmarshalStatement(r)
+		if err != nil {
+			return err
+		}
+		e.Body[i] = stmt
+	}
+	return nil
+}
+func (e *MatchExpression) MarshalJSON() ([]byte, error) {
+	type Alias MatchExpression
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  e.Type(),
+		Alias: (*Alias)(e),
+	}
+	return json.Marshal(raw)
+}
+func (e *MatchExpression) UnmarshalJSON(data []byte) error {
+	type Alias MatchExpression
+	raw := struct {
+		*Alias
+		Scrutinee json.RawMessage   `json:"scrutinee"`
+		Arms      []json.RawMessage `json:"arms"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*e = *(*MatchExpression)(raw.Alias)
+	}
+
+	scrutinee, err := unmarshalExpression(raw.Scrutinee)
+	if err != nil {
+		return err
+	}
+	e.Scrutinee = scrutinee
+
+	e.Arms = make([]*MatchArm, len(raw.Arms))
+	for i, r := range raw.Arms {
+		arm := new(MatchArm)
+		if err := arm.UnmarshalJSON(r); err != nil {
+			return err
+		}
+		e.Arms[i] = arm
+	}
+	return nil
+}
+func (arm *MatchArm) UnmarshalJSON(data []byte) error {
+	type Alias MatchArm
+	raw := struct {
+		*Alias
+		Pattern json.RawMessage `json:"pattern"`
+		Body    json.RawMessage `json:"body"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*arm = *(*MatchArm)(raw.Alias)
+	}
+
+	pattern, err := unmarshalExpression(raw.Pattern)
+	if err != nil {
+		return err
+	}
+	arm.Pattern = pattern
+
+	body, err := unmarshalExpression(raw.Body)
+	if err != nil {
+		return err
+	}
+	arm.Body = body
+
+	return nil
+}
 func (e *DictExpression) MarshalJSON() ([]byte, error) {
 	type Alias DictExpression
 	raw := struct {
@@ -902,6 +1163,82 @@ func (e *ConditionalExpression) UnmarshalJSON(data []byte) error {
 	e.Consequent = consequent
 	return nil
 }
+func (e *TernaryExpression) MarshalJSON() ([]byte, error) {
+	type Alias TernaryExpression
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  e.Type(),
+		Alias: (*Alias)(e),
+	}
+	return json.Marshal(raw)
+}
+func (e *TernaryExpression) UnmarshalJSON(data []byte) error {
+	type Alias TernaryExpression
+	raw := struct {
+		*Alias
+		Test       json.RawMessage `json:"test"`
+		Alternate  json.RawMessage `json:"alternate"`
+		Consequent json.RawMessage `json:"consequent"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*e = *(*TernaryExpression)(raw.Alias)
+	}
+
+	test, err := unmarshalExpression(raw.Test)
+	if err != nil {
+		return err
+	}
+	e.Test = test
+
+	alternate, err := unmarshalExpression(raw.Alternate)
+	if err != nil {
+		return err
+	}
+	e.Alternate = alternate
+
+	consequent, err := unmarshalExpression(raw.Consequent)
+	if err != nil {
+		return err
+	}
+	e.Consequent = consequent
+	return nil
+}
+func (e *SpreadExpression) MarshalJSON() ([]byte, error) {
+	type Alias SpreadExpression
+	raw := struct {
+		Type string `json:"type"`
+		*Alias
+	}{
+		Type:  e.Type(),
+		Alias: (*Alias)(e),
+	}
+	return json.Marshal(raw)
+}
+func (e *SpreadExpression) UnmarshalJSON(data []byte) error {
+	type Alias SpreadExpression
+	raw := struct {
+		*Alias
+		Argument json.RawMessage `json:"argument"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*e = *(*SpreadExpression)(raw.Alias)
+	}
+
+	argument, err := unmarshalExpression(raw.Argument)
+	if err != nil {
+		return err
+	}
+	e.Argument = argument
+	return nil
+}
 func (p *Property) MarshalJSON() ([]byte, error) {
 	type Alias Property
 	raw := struct {
@@ -1209,6 +1546,41 @@ func (nt *ArrayType) UnmarshalJSON(data []byte) error {
 	return nil
 }
 
+func (at AppliedType) MarshalJSON() ([]byte, error) {
+	type Alias AppliedType
+	raw := struct {
+		Type string `json:"type"`
+		Alias
+	}{
+		Type:  at.Type(),
+		Alias: (Alias)(at),
+	}
+	return json.Marshal(raw)
+}
+func (at *AppliedType) UnmarshalJSON(data []byte) error {
+	type Alias AppliedType
+	raw := struct {
+		*Alias
+		Arguments []json.RawMessage `json:"arguments"`
+	}{}
+	if err := json.Unmarshal(data, &raw); err != nil {
+		return err
+	}
+	if raw.Alias != nil {
+		*at = *(*AppliedType)(raw.Alias)
+	}
+
+	at.Arguments = make([]MonoType, len(raw.Arguments))
+	for i, r := range raw.Arguments {
+		arg, err := unmarshalMonotype(r)
+		if err != nil {
+			return err
+		}
+		at.Arguments[i] = arg
+	}
+	return nil
+}
+
 func (arr StreamType) MarshalJSON() ([]byte, error) {
 	type Alias StreamType
 	raw := struct {
@@ -1589,6 +1961,8 @@ func unmarshalNode(msg json.RawMessage) (Node, error) {
 		node = new(TvarType)
 	case "NamedType":
 		node = new(NamedType)
+	case "AppliedType":
+		node = new(AppliedType)
 	case "Package":
 		node = new(Package)
 	case "File":
@@ -1603,8 +1977,12 @@ func unmarshalNode(msg json.RawMessage) (Node, error) {
 		node = new(Block)
 	case "OptionStatement":
 		node = new(OptionStatement)
+	case "ExportStatement":
+		node = new(ExportStatement)
 	case "BuiltinStatement":
 		node = new(BuiltinStatement)
+	case "TypeAliasStatement":
+		node = new(TypeAliasStatement)
 	case "TestStatement":
 		node = new(TestStatement)
 	case "TestCaseStatement":
@@ -1617,6 +1995,8 @@ func unmarshalNode(msg json.RawMessage) (Node, error) {
 		node = new(VariableAssignment)
 	case "MemberAssignment":
 		node = new(MemberAssignment)
+	case "DestructuringAssignment":
+		node = new(DestructuringAssignment)
 	case "CallExpression":
 		node = new(CallExpression)
 	case "PipeExpression":
@@ -1635,10 +2015,22 @@ func unmarshalNode(msg json.RawMessage) (Node, error) {
 		node = new(ObjectExpression)
 	case "ConditionalExpression":
 		node = new(ConditionalExpression)
+	case "TernaryExpression":
+		node = new(TernaryExpression)
+	case "SpreadExpression":
+		node = new(SpreadExpression)
 	case "ArrayExpression":
 		node = new(ArrayExpression)
 	case "DictExpression":
 		node = new(DictExpression)
+	case "TupleExpression":
+		node = new(TupleExpression)
+	case "TupleIndexExpression":
+		node = new(TupleIndexExpression)
+	case "BlockExpression":
+		node = new(BlockExpression)
+	case "MatchExpression":
+		node = new(MatchExpression)
 	case "Identifier":
 		node = new(Identifier)
 	case "PipeLiteral":
@@ -166,6 +166,20 @@ func TestJSONMarshal(t *testing.T) {
 			},
 			want: `{"type":"BuiltinStatement","id":{"type":"Identifier","name":"task"},"ty":{"type":"TypeExpression","monotype":{"type":"NamedType","name":{"type":"Identifier","name":"int"}},"constraints":null}}`,
 		},
+		{
+			name: "type alias statement",
+			node: &ast.TypeAliasStatement{
+				ID:     &ast.Identifier{Name: "Pair"},
+				Params: []*ast.Identifier{{Name: "A"}},
+				Ty: &ast.AppliedType{
+					ID: &ast.Identifier{Name: "Point"},
+					Arguments: []ast.MonoType{
+						&ast.TvarType{ID: &ast.Identifier{Name: "A"}},
+					},
+				},
+			},
+			want: `{"type":"TypeAliasStatement","id":{"type":"Identifier","name":"Pair"},"params":[{"type":"Identifier","name":"A"}],"ty":{"type":"AppliedType","name":{"type":"Identifier","name":"Point"},"arguments":[{"type":"TvarType","name":{"type":"Identifier","name":"A"}}]}}`,
+		},
 		{
 			name: "NamedType",
 			node: &ast.NamedType{
@@ -233,6 +247,19 @@ func TestJSONMarshal(t *testing.T) {
 			},
 			want: `{"type":"RecordType","properties":[{"type":"PropertyType","name":{"type":"Identifier","name":"A"},"monotype":{"type":"NamedType","name":{"type":"Identifier","name":"int"}}}],"tvar":{"type":"Identifier","name":"A"}}`,
 		},
+		{
+			name: "RecordType with an optional property",
+			node: &ast.RecordType{
+				Properties: []*ast.PropertyType{
+					{
+						Name:     &ast.Identifier{Name: "b"},
+						Ty:       &ast.NamedType{ID: &ast.Identifier{Name: "string"}},
+						Optional: true,
+					},
+				},
+			},
+			want: `{"type":"RecordType","properties":[{"type":"PropertyType","name":{"type":"Identifier","name":"b"},"monotype":{"type":"NamedType","name":{"type":"Identifier","name":"string"}},"optional":true}]}`,
+		},
 		{
 			name: "RecordType_NoTvar_NoProp",
 			node: &ast.RecordType{
@@ -543,6 +570,41 @@ func TestJSONMarshal(t *testing.T) {
 			},
 			want: `{"type":"VariableAssignment","id":{"type":"Identifier","name":"a"},"init":{"type":"StringLiteral","value":"hello"}}`,
 		},
+		{
+			name: "export statement",
+			node: &ast.ExportStatement{
+				Statement: &ast.VariableAssignment{
+					ID:   &ast.Identifier{Name: "a"},
+					Init: &ast.IntegerLiteral{Value: 1},
+				},
+			},
+			want: `{"type":"ExportStatement","statement":{"type":"VariableAssignment","id":{"type":"Identifier","name":"a"},"init":{"type":"IntegerLiteral","value":"1"}}}`,
+		},
+		{
+			name: "destructuring assignment",
+			node: &ast.DestructuringAssignment{
+				Pattern: &ast.ObjectExpression{
+					Properties: []*ast.Property{{
+						Key: &ast.Identifier{Name: "a"},
+					}},
+				},
+				Init: &ast.Identifier{Name: "r"},
+			},
+			want: `{"type":"DestructuringAssignment","pattern":{"type":"ObjectExpression","properties":[{"type":"Property","key":{"type":"Identifier","name":"a"},"value":null}]},"init":{"type":"Identifier","name":"r"}}`,
+		},
+		{
+			name: "annotated variable assignment",
+			node: &ast.VariableAssignment{
+				ID: &ast.Identifier{Name: "a"},
+				Ty: &ast.TypeExpression{
+					Ty: &ast.NamedType{
+						ID: &ast.Identifier{Name: "string"},
+					},
+				},
+				Init: &ast.StringLiteral{Value: "hello"},
+			},
+			want: `{"type":"VariableAssignment","id":{"type":"Identifier","name":"a"},"ty":{"type":"TypeExpression","monotype":{"type":"NamedType","name":{"type":"Identifier","name":"string"}},"constraints":null},"init":{"type":"StringLiteral","value":"hello"}}`,
+		},
 		{
 			name: "call expression",
 			node: &ast.CallExpression{
@@ -578,6 +640,15 @@ func TestJSONMarshal(t *testing.T) {
 			},
 			want: `{"type":"MemberExpression","object":{"type":"Identifier","name":"a"},"property":{"type":"StringLiteral","value":"b"}}`,
 		},
+		{
+			name: "guarded member expression",
+			node: &ast.MemberExpression{
+				Object:   &ast.Identifier{Name: "a"},
+				Property: &ast.Identifier{Name: "b"},
+				Optional: true,
+			},
+			want: `{"type":"MemberExpression","object":{"type":"Identifier","name":"a"},"property":{"type":"Identifier","name":"b"},"optional":true}`,
+		},
 		{
 			name: "index expression",
 			node: &ast.IndexExpression{
@@ -603,6 +674,15 @@ func TestJSONMarshal(t *testing.T) {
 			},
 			want: `{"type":"BinaryExpression","operator":"+","left":{"type":"StringLiteral","value":"hello"},"right":{"type":"StringLiteral","value":"world"}}`,
 		},
+		{
+			name: "exponentiation expression",
+			node: &ast.BinaryExpression{
+				Operator: ast.ExponentiationOperator,
+				Left:     &ast.IntegerLiteral{Value: 2},
+				Right:    &ast.IntegerLiteral{Value: 10},
+			},
+			want: `{"type":"BinaryExpression","operator":"**","left":{"type":"IntegerLiteral","value":"2"},"right":{"type":"IntegerLiteral","value":"10"}}`,
+		},
 		{
 			name: "unary expression",
 			node: &ast.UnaryExpression{
@@ -634,6 +714,47 @@ func TestJSONMarshal(t *testing.T) {
 			},
 			want: `{"type":"DictExpression","elements":[{"type":"DictItem","key":{"type":"StringLiteral","value":"a"},"val":{"type":"IntegerLiteral","value":"0"}},{"type":"DictItem","key":{"type":"StringLiteral","value":"b"},"val":{"type":"IntegerLiteral","value":"1"}},{"type":"DictItem","key":{"type":"StringLiteral","value":"c"},"val":{"type":"IntegerLiteral","value":"2"}}]}`,
 		},
+		{
+			name: "tuple expression",
+			node: &ast.TupleExpression{
+				Elements: []ast.Expression{&ast.IntegerLiteral{Value: 1}, &ast.StringLiteral{Value: "a"}},
+			},
+			want: `{"type":"TupleExpression","elements":[{"type":"IntegerLiteral","value":"1"},{"type":"StringLiteral","value":"a"}]}`,
+		},
+		{
+			name: "tuple index expression",
+			node: &ast.TupleIndexExpression{
+				Tuple: &ast.Identifier{Name: "t"},
+				Index: &ast.IntegerLiteral{Value: 0},
+			},
+			want: `{"type":"TupleIndexExpression","tuple":{"type":"Identifier","name":"t"},"index":{"type":"IntegerLiteral","value":"0"}}`,
+		},
+		{
+			name: "block expression",
+			node: &ast.BlockExpression{
+				Body: []ast.Statement{
+					&ast.VariableAssignment{
+						ID:   &ast.Identifier{Name: "a"},
+						Init: &ast.IntegerLiteral{Value: 1},
+					},
+					&ast.ExpressionStatement{
+						Expression: &ast.Identifier{Name: "a"},
+					},
+				},
+			},
+			want: `{"type":"BlockExpression","body":[{"type":"VariableAssignment","id":{"type":"Identifier","name":"a"},"init":{"type":"IntegerLiteral","value":"1"}},{"type":"ExpressionStatement","expression":{"type":"Identifier","name":"a"}}]}`,
+		},
+		{
+			name: "match expression",
+			node: &ast.MatchExpression{
+				Scrutinee: &ast.Identifier{Name: "x"},
+				Arms: []*ast.MatchArm{
+					{Pattern: &ast.IntegerLiteral{Value: 1}, Body: &ast.StringLiteral{Value: "one"}},
+					{Pattern: &ast.Identifier{Name: "_"}, Body: &ast.StringLiteral{Value: "many"}},
+				},
+			},
+			want: `{"type":"MatchExpression","scrutinee":{"type":"Identifier","name":"x"},"arms":[{"pattern":{"type":"IntegerLiteral","value":"1"},"body":{"type":"StringLiteral","value":"one"}},{"pattern":{"type":"Identifier","name":"_"},"body":{"type":"StringLiteral","value":"many"}}]}`,
+		},
 		{
 			name: "object expression",
 			node: &ast.ObjectExpression{
@@ -672,6 +793,40 @@ func TestJSONMarshal(t *testing.T) {
 			},
 			want: `{"type":"ConditionalExpression","test":{"type":"BooleanLiteral","value":true},"consequent":{"type":"StringLiteral","value":"true"},"alternate":{"type":"StringLiteral","value":"false"}}`,
 		},
+		{
+			name: "array spread",
+			node: &ast.ArrayExpression{
+				Elements: []ast.Expression{
+					&ast.SpreadExpression{Argument: &ast.Identifier{Name: "xs"}},
+					&ast.IntegerLiteral{Value: 4},
+				},
+			},
+			want: `{"type":"ArrayExpression","elements":[{"type":"SpreadExpression","argument":{"type":"Identifier","name":"xs"}},{"type":"IntegerLiteral","value":"4"}]}`,
+		},
+		{
+			name: "record spread",
+			node: &ast.ObjectExpression{
+				Properties: []*ast.Property{
+					{
+						Key: &ast.SpreadExpression{Argument: &ast.Identifier{Name: "r"}},
+					},
+					{
+						Key:   &ast.Identifier{Name: "x"},
+						Value: &ast.IntegerLiteral{Value: 1},
+					},
+				},
+			},
+			want: `{"type":"ObjectExpression","properties":[{"type":"Property","key":{"type":"SpreadExpression","argument":{"type":"Identifier","name":"r"}},"value":null},{"type":"Property","key":{"type":"Identifier","name":"x"},"value":{"type":"IntegerLiteral","value":"1"}}]}`,
+		},
+		{
+			name: "ternary expression",
+			node: &ast.TernaryExpression{
+				Test:       &ast.BooleanLiteral{Value: true},
+				Alternate:  &ast.StringLiteral{Value: "false"},
+				Consequent: &ast.StringLiteral{Value: "true"},
+			},
+			want: `{"type":"TernaryExpression","test":{"type":"BooleanLiteral","value":true},"consequent":{"type":"StringLiteral","value":"true"},"alternate":{"type":"StringLiteral","value":"false"}}`,
+		},
 		{
 			name: "property",
 			node: &ast.Property{
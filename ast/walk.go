@@ -118,6 +118,14 @@ func walk(v Visitor, node Node) {
 		if w != nil && n.Assignment != nil {
 			walk(w, n.Assignment)
 		}
+	case *ExportStatement:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil && n.Statement != nil {
+			walk(w, n.Statement)
+		}
 	case *BuiltinStatement:
 		if n == nil {
 			return
@@ -126,6 +134,14 @@ func walk(v Visitor, node Node) {
 		if w != nil {
 			walk(w, n.ID)
 		}
+	case *TypeAliasStatement:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil {
+			walk(w, n.ID)
+		}
 	case *TestStatement:
 		if n == nil {
 			return
@@ -177,6 +193,15 @@ func walk(v Visitor, node Node) {
 			walk(w, n.Member)
 			walk(w, n.Init)
 		}
+	case *DestructuringAssignment:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil {
+			walk(w, n.Pattern)
+			walk(w, n.Init)
+		}
 	case *CallExpression:
 		if n == nil {
 			return
@@ -262,6 +287,24 @@ func walk(v Visitor, node Node) {
 			walk(w, n.Alternate)
 			walk(w, n.Consequent)
 		}
+	case *TernaryExpression:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil {
+			walk(w, n.Test)
+			walk(w, n.Alternate)
+			walk(w, n.Consequent)
+		}
+	case *SpreadExpression:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil {
+			walk(w, n.Argument)
+		}
 	case *ArrayExpression:
 		if n == nil {
 			return
@@ -272,6 +315,47 @@ func walk(v Visitor, node Node) {
 				walk(w, e)
 			}
 		}
+	case *TupleExpression:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil {
+			for _, e := range n.Elements {
+				walk(w, e)
+			}
+		}
+	case *TupleIndexExpression:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil {
+			walk(w, n.Tuple)
+			walk(w, n.Index)
+		}
+	case *BlockExpression:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil {
+			for _, s := range n.Body {
+				walk(w, s)
+			}
+		}
+	case *MatchExpression:
+		if n == nil {
+			return
+		}
+		w := v.Visit(n)
+		if w != nil {
+			walk(w, n.Scrutinee)
+			for _, arm := range n.Arms {
+				walk(w, arm.Pattern)
+				walk(w, arm.Body)
+			}
+		}
 	case *DictExpression:
 		if n == nil {
 			return
@@ -1,6 +1,7 @@
 package compiler
 
 import (
+	"github.com/influxdata/flux/ast"
 	"github.com/influxdata/flux/codes"
 	"github.com/influxdata/flux/internal/errors"
 	"github.com/influxdata/flux/semantic"
@@ -525,6 +526,7 @@ func compile(n semantic.Node, subst semantic.Substitutor) (Evaluator, error) {
 			object:   object,
 			property: n.Property.Name(),
 			nullable: isNullable(t),
+			optional: n.Optional,
 		}, nil
 	case *semantic.IndexExpression:
 		arr, err := compile(n.Array, subst)
@@ -653,6 +655,13 @@ func compile(n semantic.Node, subst semantic.Substitutor) (Evaluator, error) {
 			return nil, err
 		}
 		rt := r.Type().Nature()
+		if n.Operator == ast.NullCoalescingOperator {
+			return &nullCoalescingEvaluator{
+				t:     apply(subst, nil, n.TypeOf()),
+				left:  l,
+				right: r,
+			}, nil
+		}
 		if lt == semantic.Invalid {
 			lt = rt
 		} else if rt == semantic.Invalid {
@@ -10,8 +10,11 @@ import (
 	"github.com/influxdata/flux"
 	"github.com/influxdata/flux/codes"
 	"github.com/influxdata/flux/compiler"
+	"github.com/influxdata/flux/execute/executetest"
 	_ "github.com/influxdata/flux/fluxinit/static"
 	"github.com/influxdata/flux/internal/errors"
+	fluxfeature "github.com/influxdata/flux/internal/feature"
+	"github.com/influxdata/flux/internal/pkg/feature"
 	"github.com/influxdata/flux/runtime"
 	"github.com/influxdata/flux/semantic"
 	"github.com/influxdata/flux/semantic/semantictest"
@@ -395,6 +398,50 @@ func TestCompileAndEval(t *testing.T) {
 			}),
 			wantEvalErr: true,
 		},
+		{
+			name: "string access",
+			fn:   `(s) => s[1]`,
+			inType: semantic.NewObjectType([]semantic.PropertyType{
+				{Key: []byte("s"), Value: semantic.BasicString},
+			}),
+			input: values.NewObjectWithValues(map[string]values.Value{
+				"s": values.NewString("añb"),
+			}),
+			want: values.NewString("ñ"),
+		},
+		{
+			name: "string access out of bounds",
+			fn:   `(s) => s[3]`,
+			inType: semantic.NewObjectType([]semantic.PropertyType{
+				{Key: []byte("s"), Value: semantic.BasicString},
+			}),
+			input: values.NewObjectWithValues(map[string]values.Value{
+				"s": values.NewString("añb"),
+			}),
+			wantEvalErr: true,
+		},
+		{
+			name: "bytes access",
+			fn:   `(b) => b[1]`,
+			inType: semantic.NewObjectType([]semantic.PropertyType{
+				{Key: []byte("b"), Value: semantic.BasicBytes},
+			}),
+			input: values.NewObjectWithValues(map[string]values.Value{
+				"b": values.NewBytes([]byte("abc")),
+			}),
+			want: values.NewInt(98),
+		},
+		{
+			name: "bytes access out of bounds",
+			fn:   `(b) => b[-1]`,
+			inType: semantic.NewObjectType([]semantic.PropertyType{
+				{Key: []byte("b"), Value: semantic.BasicBytes},
+			}),
+			input: values.NewObjectWithValues(map[string]values.Value{
+				"b": values.NewBytes([]byte("abc")),
+			}),
+			wantEvalErr: true,
+		},
 		{
 			name: "logical expression",
 			fn:   `(a, b) => a or b`,
@@ -408,6 +455,32 @@ func TestCompileAndEval(t *testing.T) {
 			}),
 			want: values.NewBool(true),
 		},
+		{
+			name: "null coalescing expression",
+			fn:   `(a, b) => a ?? b`,
+			inType: semantic.NewObjectType([]semantic.PropertyType{
+				{Key: []byte("a"), Value: semantic.BasicInt},
+				{Key: []byte("b"), Value: semantic.BasicInt},
+			}),
+			input: values.NewObjectWithValues(map[string]values.Value{
+				"a": values.NewNull(semantic.BasicInt),
+				"b": values.NewInt(2),
+			}),
+			want: values.NewInt(2),
+		},
+		{
+			name: "null coalescing expression with a value",
+			fn:   `(a, b) => a ?? b`,
+			inType: semantic.NewObjectType([]semantic.PropertyType{
+				{Key: []byte("a"), Value: semantic.BasicInt},
+				{Key: []byte("b"), Value: semantic.BasicInt},
+			}),
+			input: values.NewObjectWithValues(map[string]values.Value{
+				"a": values.NewInt(1),
+				"b": values.NewInt(2),
+			}),
+			want: values.NewInt(1),
+		},
 		{
 			name: "call with nonexistant value",
 			fn:   `(r) => r.a + r.b`,
@@ -609,6 +682,80 @@ func TestCompileAndEval(t *testing.T) {
 	}
 }
 
+func TestCompileAndEval_GuardedAccess(t *testing.T) {
+	testCases := []struct {
+		name   string
+		fn     string
+		inType semantic.MonoType
+		input  values.Object
+		want   values.Value
+	}{
+		{
+			name: "present member",
+			fn:   `(r) => r?.a?.b`,
+			inType: semantic.NewObjectType([]semantic.PropertyType{
+				{Key: []byte("r"), Value: semantic.NewObjectType([]semantic.PropertyType{
+					{Key: []byte("a"), Value: semantic.NewObjectType([]semantic.PropertyType{
+						{Key: []byte("b"), Value: semantic.BasicInt},
+					})},
+				})},
+			}),
+			input: values.NewObjectWithValues(map[string]values.Value{
+				"r": values.NewObjectWithValues(map[string]values.Value{
+					"a": values.NewObjectWithValues(map[string]values.Value{
+						"b": values.NewInt(2),
+					}),
+				}),
+			}),
+			want: values.NewInt(2),
+		},
+		{
+			name: "absent member",
+			fn:   `(r) => r?.a?.b`,
+			inType: semantic.NewObjectType([]semantic.PropertyType{
+				{Key: []byte("r"), Value: semantic.NewObjectType([]semantic.PropertyType{
+					{Key: []byte("x"), Value: semantic.BasicInt},
+				})},
+			}),
+			input: values.NewObjectWithValues(map[string]values.Value{
+				"r": values.NewObjectWithValues(map[string]values.Value{
+					"x": values.NewInt(1),
+				}),
+			}),
+			want: values.Null,
+		},
+	}
+
+	for _, tc := range testCases {
+		tc := tc
+		t.Run(tc.name, func(t *testing.T) {
+			ctx := feature.Inject(context.Background(), executetest.TestFlagger{
+				fluxfeature.GuardedAccess().Key(): true,
+			})
+			pkg, err := runtime.AnalyzeSource(ctx, tc.fn)
+			if err != nil {
+				t.Fatalf("unexpected error: %s", err)
+			}
+
+			stmt := pkg.Files[0].Body[0].(*semantic.ExpressionStatement)
+			fn := stmt.Expression.(*semantic.FunctionExpression)
+			f, err := compiler.Compile(nil, fn, tc.inType)
+			if err != nil {
+				t.Fatalf("unexpected error: %s", err)
+			}
+
+			got, err := f.Eval(context.TODO(), tc.input)
+			if err != nil {
+				t.Fatalf("unexpected error: %s", err)
+			}
+
+			if !cmp.Equal(tc.want, got, CmpOptions...) {
+				t.Errorf("unexpected value -want/+got\n%s", cmp.Diff(tc.want, got, CmpOptions...))
+			}
+		})
+	}
+}
+
 func TestRuntimeTypeErrors(t *testing.T) {
 
 	pkg, err := runtime.StdLib().ImportPackageObject("internal/testutil")
@@ -642,11 +789,11 @@ func TestRuntimeTypeErrors(t *testing.T) {
 			fn: `
 				import "internal/testutil"
 				() => {
-						a = testutil.makeAny(typ: "string")
+						a = testutil.makeAny(typ: "int")
 						return a[0]
  				}
  			`,
-			err: "cannot index into a value of type string",
+			err: "cannot index into a value of type int",
 		},
 		{
 			name: "index with null value",
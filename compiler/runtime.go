@@ -355,6 +355,27 @@ func (e *conditionalEvaluator) Eval(ctx context.Context, scope Scope) (values.Va
 	}
 }
 
+type nullCoalescingEvaluator struct {
+	t           semantic.MonoType
+	left, right Evaluator
+}
+
+func (e *nullCoalescingEvaluator) Type() semantic.MonoType {
+	return e.t
+}
+
+func (e *nullCoalescingEvaluator) Eval(ctx context.Context, scope Scope) (values.Value, error) {
+	l, err := eval(ctx, e.left, scope)
+	if err != nil {
+		return nil, err
+	}
+	if !l.IsNull() {
+		return l, nil
+	}
+	l.Release()
+	return eval(ctx, e.right, scope)
+}
+
 type binaryEvaluator struct {
 	t           semantic.MonoType
 	left, right Evaluator
@@ -583,6 +604,9 @@ type memberEvaluator struct {
 	object   Evaluator
 	property string
 	nullable bool
+	// optional is set for a guarded access, which yields null
+	// instead of an error when the object is null or lacks the member.
+	optional bool
 }
 
 func (e *memberEvaluator) Type() semantic.MonoType {
@@ -596,6 +620,9 @@ func (e *memberEvaluator) Eval(ctx context.Context, scope Scope) (values.Value,
 	}
 	defer o.Release()
 	if o.IsNull() {
+		if e.optional {
+			return values.Null, nil
+		}
 		return nil, errors.Newf(codes.Invalid, "cannot access property of a null value; expected record")
 	}
 	if typ := o.Type().Nature(); typ != semantic.Object {
@@ -603,7 +630,7 @@ func (e *memberEvaluator) Eval(ctx context.Context, scope Scope) (values.Value,
 	}
 
 	v, ok := o.Object().Get(e.property)
-	if !ok && !e.nullable {
+	if !ok && !e.nullable && !e.optional {
 		return nil, errors.Newf(codes.Invalid, "member %q with type %s is not in the record", e.property, e.t.Nature())
 	}
 	v.Retain()
@@ -629,8 +656,10 @@ func (e *arrayIndexEvaluator) Eval(ctx context.Context, scope Scope) (values.Val
 	if a.IsNull() {
 		return nil, errors.New(codes.Invalid, "cannot index into a null value; expected an array")
 	}
-	if typ := a.Type().Nature(); typ != semantic.Array {
-		return nil, errors.Newf(codes.Invalid, "cannot index into a value of type %s; expected an array", typ)
+	switch typ := a.Type().Nature(); typ {
+	case semantic.Array, semantic.String, semantic.Bytes:
+	default:
+		return nil, errors.Newf(codes.Invalid, "cannot index into a value of type %s; expected an array, a string or bytes", typ)
 	}
 	i, err := e.index.Eval(ctx, scope)
 	if err != nil {
@@ -644,6 +673,20 @@ func (e *arrayIndexEvaluator) Eval(ctx context.Context, scope Scope) (values.Val
 		return nil, errors.Newf(codes.Invalid, "cannot index into an array with value of type %s; expected an int", typ)
 	}
 	ix := int(i.Int())
+	switch a.Type().Nature() {
+	case semantic.String:
+		s := []rune(a.Str())
+		if ix < 0 || ix >= len(s) {
+			return nil, errors.Newf(codes.OutOfRange, "cannot access character %v of string of length %v", ix, len(s))
+		}
+		return values.NewString(string(s[ix])), nil
+	case semantic.Bytes:
+		b := a.Bytes()
+		if ix < 0 || ix >= len(b) {
+			return nil, errors.Newf(codes.OutOfRange, "cannot access byte %v of bytes of length %v", ix, len(b))
+		}
+		return values.NewInt(int64(b[ix])), nil
+	}
 	l := a.Array().Len()
 	if ix < 0 || ix >= l {
 		return nil, errors.Newf(codes.OutOfRange, "cannot access element %v of array of length %v", ix, l)
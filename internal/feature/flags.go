@@ -185,6 +185,18 @@ func LabelPolymorphism() BoolFlag {
 	return labelPolymorphism
 }
 
+var guardedAccess = feature.MakeBoolFlag(
+	"Guarded access",
+	"guardedAccess",
+	"Markus Westerlind",
+	false,
+)
+
+// GuardedAccess - Enables guarded member access, r?.a, in the type system
+func GuardedAccess() BoolFlag {
+	return guardedAccess
+}
+
 // Inject will inject the Flagger into the context.
 func Inject(ctx context.Context, flagger Flagger) context.Context {
 	return feature.Inject(ctx, flagger)
@@ -205,6 +217,7 @@ var all = []Flag{
 	vectorizeAddition,
 	vectorizeOperators,
 	labelPolymorphism,
+	guardedAccess,
 }
 
 var byKey = map[string]Flag{
@@ -222,6 +235,7 @@ var byKey = map[string]Flag{
 	"vectorizeAddition":                vectorizeAddition,
 	"vectorizeOperators":               vectorizeOperators,
 	"labelPolymorphism":                labelPolymorphism,
+	"guardedAccess":                    guardedAccess,
 }
 
 // Flags returns all feature flags.
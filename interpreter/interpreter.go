@@ -390,6 +390,20 @@ func (itrp *Interpreter) doExpression(ctx context.Context, expr semantic.Express
 			return nil, err
 		}
 		ix := int(idx.Int())
+		switch arr.Type().Nature() {
+		case semantic.String:
+			s := []rune(arr.Str())
+			if ix < 0 || ix >= len(s) {
+				return nil, errors.Newf(codes.Invalid, "cannot access character %v of string of length %v", ix, len(s))
+			}
+			return values.NewString(string(s[ix])), nil
+		case semantic.Bytes:
+			b := arr.Bytes()
+			if ix < 0 || ix >= len(b) {
+				return nil, errors.Newf(codes.Invalid, "cannot access byte %v of bytes of length %v", ix, len(b))
+			}
+			return values.NewInt(int64(b[ix])), nil
+		}
 		l := arr.Array().Len()
 		if ix < 0 || ix >= l {
 			return nil, errors.Newf(codes.Invalid, "cannot access element %v of array of length %v", ix, l)
@@ -430,6 +444,14 @@ func (itrp *Interpreter) doExpression(ctx context.Context, expr semantic.Express
 			return nil, err
 		}
 
+		// The fallback of a null-coalescing expression is only evaluated when it is used.
+		if e.Operator == ast.NullCoalescingOperator {
+			if !l.IsNull() {
+				return l, nil
+			}
+			return itrp.doExpression(ctx, e.Right, scope)
+		}
+
 		r, err := itrp.doExpression(ctx, e.Right, scope)
 		if err != nil {
 			return nil, err
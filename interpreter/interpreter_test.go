@@ -339,6 +339,21 @@ func TestEval(t *testing.T) {
 				z == 3 or fail()
 			`,
 		},
+		{
+			name: "string index expression",
+			query: `
+				s = "añb"
+				s[1] == "ñ" or fail()
+			`,
+		},
+		{
+			name: "string index expression out of bounds",
+			query: `
+				s = "abc"
+				x = s[3]
+			`,
+			wantErr: any,
+		},
 		{
 			name: "short circuit logical and",
 			query: `
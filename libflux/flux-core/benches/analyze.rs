@@ -0,0 +1,224 @@
+#[macro_use]
+extern crate criterion;
+extern crate fluxcore;
+
+use std::fmt::Write;
+
+use criterion::{black_box, Criterion};
+use fluxcore::{
+    ast, parser,
+    semantic::{
+        convert::convert_polytype, env::Environment, import::Packages, sub::Substitution,
+        types::PolyTypeMap, Analyzer, AnalyzerConfig, Feature,
+    },
+};
+
+/// Parses the types of the builtins available to a benchmark.
+fn prelude(builtins: &[(&str, &str)]) -> PolyTypeMap {
+    builtins
+        .iter()
+        .map(|(name, typ)| {
+            let mut parser = parser::Parser::new(typ);
+            let typ = parser.parse_type_expression();
+            let typ = convert_polytype(&typ, &mut Substitution::default()).expect("valid type");
+            (name.to_string(), typ)
+        })
+        .collect()
+}
+
+/// Benchmarks the analysis of `src` with the builtins of `prelude`.
+fn bench_analyze(
+    c: &mut Criterion,
+    name: &str,
+    src: &str,
+    prelude: &PolyTypeMap,
+    config: AnalyzerConfig,
+) {
+    let file = parser::parse_string(format!("{}.flux", name), src);
+    let pkg = ast::Package::from(file);
+    c.bench_function(&format!("analyze.{}", name), |b| {
+        b.iter(|| {
+            let mut analyzer = Analyzer::new(
+                Environment::from(prelude.clone()),
+                Packages::default(),
+                config.clone(),
+            );
+            black_box(analyzer.analyze_ast(&pkg).expect("analysis must succeed"));
+        });
+    });
+}
+
+/// Generates a package with `n` groups of record-heavy functions and the bindings that call them,
+/// so most of the time is spent creating, unifying and substituting into types.
+fn synthetic_package(n: usize) -> String {
+    let mut src = String::new();
+    for i in 0..n {
+        writeln!(
+            src,
+            r#"f{0} = (r) => ({{r with a{0}: r.x + {0}, b{0}: "${{r.y}}"}})
+x{0} = f{0}(r: {{x: {0}, y: "v{0}", z: [1, 2, 3]}})
+y{0} = if x{0}.a{0} > 0 then x{0}.b{0} else "none"
+z{0} = [x{0}] |> (tables=<-) => tables"#,
+            i
+        )
+        .unwrap();
+    }
+    src
+}
+
+fn analyze_synthetic_package(c: &mut Criterion) {
+    let src = synthetic_package(200);
+    let file = parser::parse_string("synthetic.flux".to_string(), &src);
+    let pkg = ast::Package::from(file);
+    c.bench_function("analyze.synthetic_package", |b| {
+        b.iter(|| {
+            let mut analyzer =
+                Analyzer::new_with_defaults(Environment::default(), Packages::default());
+            black_box(analyzer.analyze_ast(&pkg).expect("analysis must succeed"));
+        });
+    });
+}
+
+/// Generates a script which calls the builtin `map` `n` times, stressing instantiation.
+fn many_map_calls(n: usize) -> String {
+    let mut src = String::from("tables = [{_value: 1.0, tag: \"a\"}]\n");
+    for i in 0..n {
+        writeln!(
+            src,
+            r#"t{0} = tables |> map(fn: (r) => ({{r with v{0}: r._value * {0}.0}}))"#,
+            i
+        )
+        .unwrap();
+    }
+    src
+}
+
+const MAP: (&str, &str) = (
+    "map",
+    "(<-tables: [A], fn: (r: A) => B) => [B] where A: Record, B: Record",
+);
+
+fn analyze_many_map_calls(c: &mut Criterion) {
+    bench_analyze(
+        c,
+        "many_map_calls",
+        &many_map_calls(500),
+        &prelude(&[MAP]),
+        AnalyzerConfig::default(),
+    );
+}
+
+/// Generates a script which builds a record with `n` fields and accesses and updates each of
+/// them, stressing the unification of large records.
+fn wide_records(n: usize) -> String {
+    let fields: Vec<String> = (0..n)
+        .map(|i| match i % 3 {
+            0 => format!("f{}: {}", i, i),
+            1 => format!("f{}: {}.0", i, i),
+            _ => format!(r#"f{}: "{}""#, i, i),
+        })
+        .collect();
+    let mut src = format!(
+        "r = {{{}}}
+",
+        fields.join(", ")
+    );
+    for i in 0..n {
+        writeln!(
+            src,
+            "x{0} = r.f{0}
+s{0} = {{r with f{0}: x{0}}}",
+            i
+        )
+        .unwrap();
+    }
+    src.push_str(
+        "rows = [r, s0, s1]
+",
+    );
+    src
+}
+
+fn analyze_wide_records(c: &mut Criterion) {
+    bench_analyze(
+        c,
+        "wide_records",
+        &wide_records(300),
+        &PolyTypeMap::new(),
+        AnalyzerConfig::default(),
+    );
+}
+
+/// Generates a single pipeline of `n` calls to `map`, each adding a column to the rows of
+/// the previous one, stressing records which grow through a long chain of calls.
+fn deep_pipeline(n: usize) -> String {
+    let mut src = String::from("x = [{_value: 1.0, tag: \"a\"}]\n");
+    for i in 0..n {
+        writeln!(
+            src,
+            "    |> map(fn: (r) => ({{r with c{0}: r._value + {0}.0}}))",
+            i
+        )
+        .unwrap();
+    }
+    src
+}
+
+fn analyze_deep_pipeline(c: &mut Criterion) {
+    bench_analyze(
+        c,
+        "deep_pipeline",
+        &deep_pipeline(200),
+        &prelude(&[MAP]),
+        AnalyzerConfig::default(),
+    );
+}
+
+/// Generates a script which makes `n` calls to functions whose columns are given as labels.
+fn label_polymorphic_calls(n: usize) -> String {
+    let columns: Vec<String> = (0..10).map(|i| format!("c{}: {}", i, i)).collect();
+    let mut src = format!("tables = [{{{}}}]\n", columns.join(", "));
+    for i in 0..n {
+        writeln!(
+            src,
+            r#"t{0} = tables |> fill(column: "c{1}", value: "v{0}") |> drop(column: "c{2}")"#,
+            i,
+            i % 10,
+            (i + 1) % 10
+        )
+        .unwrap();
+    }
+    src
+}
+
+fn analyze_label_polymorphic_calls(c: &mut Criterion) {
+    bench_analyze(
+        c,
+        "label_polymorphic_calls",
+        &label_polymorphic_calls(300),
+        &prelude(&[
+            (
+                "fill",
+                "(<-tables: [{A with B: C}], ?column: B, ?value: D) => [{A with B: D}] where B: Label",
+            ),
+            (
+                "drop",
+                "(<-tables: [{A with B: C}], column: B) => [A] where B: Label",
+            ),
+        ]),
+        AnalyzerConfig {
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+    );
+}
+
+criterion_group!(
+    analyze,
+    analyze_synthetic_package,
+    analyze_many_map_calls,
+    analyze_wide_records,
+    analyze_deep_pipeline,
+    analyze_label_polymorphic_calls
+);
+criterion_main!(analyze);
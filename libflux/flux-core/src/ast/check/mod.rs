@@ -1,17 +1,24 @@
 //! Checking the AST.
 
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
 use codespan_reporting::diagnostic;
 use thiserror::Error;
 
 use crate::{
-    ast::{walk, PropertyKey},
-    errors::{located, AsDiagnostic, Errors, Located},
+    ast::{walk, Expression, Identifier, ObjectExpr, Property, PropertyKey, SourceLocation},
+    errors::{located, AsDiagnostic, Errors, Located, Severity},
 };
 
+/// Comment which, when placed before a binding, suppresses the shadowing warning for it.
+pub const ALLOW_SHADOWING: &str = "flux:allow-shadowing";
+
 /// Inspects an AST node and returns a list of found AST errors plus
 /// any errors existed before `ast.check()` is performed.
 pub fn check(node: walk::Node) -> Result<(), Errors<Error>> {
     let mut errors = Errors::new();
+    // The records which hold the arguments of a call, their duplicates are reported as arguments
+    let mut arguments: HashSet<*const ObjectExpr> = HashSet::new();
     walk::walk(
         &mut |n: walk::Node| {
             // collect any errors we found prior to ast.check().
@@ -37,11 +44,21 @@ pub fn check(node: walk::Node) -> Result<(), Errors<Error>> {
                         message: format!("invalid expression: {}", n.text),
                     },
                 )),
+                walk::Node::CallExpr(n) => {
+                    if let Some(Expression::Object(obj)) = n.arguments.first() {
+                        arguments.insert(&**obj);
+                        check_duplicate_keys(&obj.properties, "argument", &mut errors);
+                    }
+                }
                 walk::Node::ObjectExpr(n) => {
+                    if !arguments.contains(&(n as *const ObjectExpr)) {
+                        check_duplicate_keys(&n.properties, "record label", &mut errors);
+                    }
                     let mut has_implicit = false;
                     let mut has_explicit = false;
+                    // Spreads are neither implicit nor explicit properties
                     for p in n.properties.iter() {
-                        if p.base.errors.is_empty() {
+                        if p.base.errors.is_empty() && !matches!(p.key, PropertyKey::Spread(_)) {
                             match p.value {
                                 None => {
                                     has_implicit = true;
@@ -86,6 +103,37 @@ pub fn check(node: walk::Node) -> Result<(), Errors<Error>> {
     }
 }
 
+// Reports every property whose key was already used by an earlier property, along with the
+// location of that earlier property.
+fn check_duplicate_keys(properties: &[Property], what: &str, errors: &mut Errors<Error>) {
+    let mut seen: HashMap<&str, &SourceLocation> = HashMap::new();
+    for p in properties {
+        let (name, location) = match &p.key {
+            PropertyKey::Identifier(id) => (id.name.as_str(), &id.base.location),
+            PropertyKey::StringLit(s) => (s.value.as_str(), &s.base.location),
+            // The fields of a spread may be overridden by the properties which follow it
+            PropertyKey::Spread(_) => continue,
+        };
+        match seen.entry(name) {
+            Entry::Occupied(first) => errors.push(located(
+                location.clone(),
+                ErrorKind {
+                    message: format!(
+                        "duplicate {} {}, previously declared at {}:{}",
+                        what,
+                        name,
+                        first.get().start.line,
+                        first.get().start.column
+                    ),
+                },
+            )),
+            Entry::Vacant(entry) => {
+                entry.insert(location);
+            }
+        }
+    }
+}
+
 /// An error that can be returned while checking the AST.
 pub type Error = Located<ErrorKind>;
 
@@ -97,11 +145,223 @@ pub struct ErrorKind {
     pub message: String,
 }
 
+impl Severity for ErrorKind {}
+
 impl AsDiagnostic for ErrorKind {
     fn as_diagnostic(&self, _source: &dyn crate::semantic::Source) -> diagnostic::Diagnostic<()> {
         diagnostic::Diagnostic::error().with_message(self.to_string())
     }
 }
 
+/// Inspects an AST node and returns a warning for every binding in an inner scope which shadows
+/// a binding (including a builtin or an import) from an enclosing scope.
+///
+/// `prelude` names the bindings which are in scope before the node, such as the builtins of the
+/// prelude. They form the outermost scope, so even a top-level binding may shadow them.
+///
+/// Function parameters, blocks and block expressions each introduce a new scope. The warning for
+/// a binding can be suppressed with a comment containing [`ALLOW_SHADOWING`] placed before it.
+pub fn check_shadowing<'a>(
+    node: walk::Node<'a>,
+    prelude: impl IntoIterator<Item = &'a str>,
+) -> Errors<Warning> {
+    let mut v = Shadowing {
+        scopes: vec![
+            prelude.into_iter().map(|name| (name, None)).collect(),
+            HashMap::new(),
+        ],
+        warnings: Errors::new(),
+    };
+    walk::walk(&mut v, node);
+    v.warnings
+}
+
+struct Shadowing<'a> {
+    // Bindings of the prelude have no location
+    scopes: Vec<HashMap<&'a str, Option<&'a SourceLocation>>>,
+    warnings: Errors<Warning>,
+}
+
+impl<'a> Shadowing<'a> {
+    fn declare_identifier(&mut self, id: &'a Identifier) {
+        let allowed = id
+            .base
+            .comments
+            .iter()
+            .any(|comment| comment.text.contains(ALLOW_SHADOWING));
+        self.declare(&id.name, &id.base.location, allowed);
+    }
+
+    fn declare(&mut self, name: &'a str, location: &'a SourceLocation, allowed: bool) {
+        let (scope, outer) = self
+            .scopes
+            .split_last_mut()
+            .expect("the root scope is never exited");
+        let original = outer.iter().rev().find_map(|scope| scope.get(name));
+        if let (Some(original), false) = (original, allowed) {
+            let name = name.to_string();
+            self.warnings.push(located(
+                location.clone(),
+                match original {
+                    Some(original) => WarningKind::Shadowed {
+                        name,
+                        original: (*original).clone(),
+                    },
+                    None => WarningKind::ShadowedPrelude { name },
+                },
+            ));
+        }
+        scope.insert(name, Some(location));
+    }
+}
+
+impl<'a> walk::Visitor<'a> for Shadowing<'a> {
+    fn visit(&mut self, node: walk::Node<'a>) -> bool {
+        match node {
+            walk::Node::ImportDeclaration(n) => match &n.alias {
+                Some(alias) => self.declare_identifier(alias),
+                None => {
+                    let name = n.path.value.rsplit('/').next().unwrap_or_default();
+                    self.declare(name, &n.base.location, false);
+                }
+            },
+            walk::Node::VariableAssgn(n) => self.declare_identifier(&n.id),
+            walk::Node::DestructuringAssgn(n) => {
+                for (_, id) in n.bindings() {
+                    self.declare_identifier(id);
+                }
+            }
+            walk::Node::BuiltinStmt(n) => self.declare_identifier(&n.id),
+            walk::Node::FunctionExpr(n) => {
+                self.scopes.push(HashMap::new());
+                for param in &n.params {
+                    if let PropertyKey::Identifier(id) = &param.key {
+                        self.declare_identifier(id);
+                    }
+                }
+            }
+            walk::Node::Block(_) | walk::Node::BlockExpr(_) => self.scopes.push(HashMap::new()),
+            _ => {}
+        }
+        true
+    }
+
+    fn done(&mut self, node: walk::Node<'a>) {
+        if let walk::Node::FunctionExpr(_) | walk::Node::Block(_) | walk::Node::BlockExpr(_) = node
+        {
+            self.scopes.pop();
+        }
+    }
+}
+
+/// Inspects an AST node and returns a warning for every top-level or local binding which is never
+/// referenced.
+///
+/// Options, tests, exported bindings and function parameters whose name starts with an underscore
+/// are exempt.
+pub fn check_unused(node: walk::Node) -> Errors<Warning> {
+    let mut v = Unused {
+        used: HashSet::new(),
+        exempt: HashSet::new(),
+        warnings: Errors::new(),
+    };
+    walk::walk_scopes(&mut v, node);
+    v.warnings
+}
+
+struct Unused {
+    used: HashSet<*const Identifier>,
+    exempt: HashSet<*const Identifier>,
+    warnings: Errors<Warning>,
+}
+
+impl<'a> walk::ScopeVisitor<'a> for Unused {
+    fn declare(&mut self, id: &'a Identifier, kind: walk::BindingKind) {
+        let exempt = match kind {
+            walk::BindingKind::Public => true,
+            walk::BindingKind::Parameter => id.name.starts_with('_'),
+            walk::BindingKind::Variable => false,
+        };
+        if exempt {
+            self.exempt.insert(id);
+        }
+    }
+
+    fn reference(&mut self, _id: &'a Identifier, binding: Option<&'a Identifier>) {
+        if let Some(binding) = binding {
+            self.used.insert(binding);
+        }
+    }
+
+    fn exit_scope(&mut self, bindings: &[&'a Identifier]) {
+        for &id in bindings {
+            let id_ptr = id as *const Identifier;
+            if !self.used.contains(&id_ptr) && !self.exempt.contains(&id_ptr) {
+                self.warnings.push(located(
+                    id.base.location.clone(),
+                    WarningKind::Unused {
+                        name: id.name.clone(),
+                    },
+                ));
+            }
+        }
+    }
+}
+
+/// A warning that can be returned while checking the AST.
+pub type Warning = Located<WarningKind>;
+
+/// A warning that can be returned while checking the AST.
+#[derive(Error, Debug, PartialEq)]
+pub enum WarningKind {
+    /// A binding which shadows a binding from an enclosing scope.
+    #[error(
+        "{name} shadows the binding declared at {}:{}",
+        .original.start.line,
+        .original.start.column
+    )]
+    Shadowed {
+        /// Name of the binding.
+        name: String,
+        /// Location of the binding being shadowed.
+        original: SourceLocation,
+    },
+    /// A binding which shadows a builtin of the prelude.
+    #[error("{name} shadows a builtin of the prelude")]
+    ShadowedPrelude {
+        /// Name of the binding.
+        name: String,
+    },
+    /// A binding which is never referenced.
+    #[error("{name} is declared but never used")]
+    Unused {
+        /// Name of the binding.
+        name: String,
+    },
+}
+
+impl Severity for WarningKind {
+    fn severity() -> &'static str {
+        "warning"
+    }
+}
+
+impl AsDiagnostic for WarningKind {
+    fn as_diagnostic(&self, source: &dyn crate::semantic::Source) -> diagnostic::Diagnostic<()> {
+        match self {
+            Self::Shadowed { original, .. } => diagnostic::Diagnostic::warning()
+                .with_message(self.to_string())
+                .with_labels(vec![diagnostic::Label::secondary(
+                    (),
+                    source.codespan_range(original),
+                )
+                .with_message("originally declared here")]),
+            Self::ShadowedPrelude { .. } | Self::Unused { .. } => {
+                diagnostic::Diagnostic::warning().with_message(self.to_string())
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -28,6 +28,42 @@ fn test_object_check() {
     assert_eq!(want, got);
 }
 
+fn check_errors(src: &str) -> Vec<String> {
+    let file = parse_string("check_test".to_string(), src);
+    match check(walk::Node::File(&file)) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.into_iter().map(|err| err.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_duplicate_record_labels() {
+    assert_eq!(
+        check_errors(
+            "a = {b: 1, c: 2, b: 3}
+d = {a with \"e\": 1, e: 2}"
+        ),
+        vec![
+            "error check_test@1:18-1:19: duplicate record label b, previously declared at 1:6",
+            "error check_test@2:21-2:22: duplicate record label e, previously declared at 2:13",
+        ],
+    );
+}
+
+#[test]
+fn test_duplicate_arguments() {
+    assert_eq!(
+        check_errors(
+            "f(x: 1, y: {x: 1}, x: 2)
+1 |> f(y: 1, y: 2)"
+        ),
+        vec![
+            "error check_test@1:20-1:21: duplicate argument x, previously declared at 1:3",
+            "error check_test@2:14-2:15: duplicate argument y, previously declared at 2:8",
+        ],
+    );
+}
+
 #[test]
 fn test_bad_stmt() {
     let file = parse_string("bad_stmt_test".to_string(), "a = 1\nb = \nc=2");
@@ -125,6 +161,8 @@ fn test_check_collect_existing_error() {
                 },
                 value: 1,
             }),
+            colon: vec![],
+            ty: None,
         }))],
         eof: vec![],
     };
@@ -134,3 +172,115 @@ fn test_check_collect_existing_error() {
         assert_eq!(err.error.message, format!("error {}", i + 1));
     }
 }
+
+fn shadowing_warnings(src: &str) -> Vec<String> {
+    shadowing_warnings_with_prelude(src, &[])
+}
+
+fn shadowing_warnings_with_prelude(src: &str, prelude: &[&str]) -> Vec<String> {
+    let file = parse_string("shadowing_test".to_string(), src);
+    check_shadowing(walk::Node::File(&file), prelude.iter().copied())
+        .into_iter()
+        .map(|warning| warning.to_string())
+        .collect()
+}
+
+#[test]
+fn test_shadowing_function_parameter() {
+    assert_eq!(
+        shadowing_warnings("x = 1\nf = (x) => x + 1"),
+        vec!["warning shadowing_test@2:6-2:7: x shadows the binding declared at 1:1"],
+    );
+}
+
+#[test]
+fn test_shadowing_block() {
+    assert_eq!(
+        shadowing_warnings("x = 1\nf = () => {\n    x = 2\n    return x\n}"),
+        vec!["warning shadowing_test@3:5-3:6: x shadows the binding declared at 1:1"],
+    );
+}
+
+#[test]
+fn test_shadowing_builtin_and_import() {
+    assert_eq!(
+        shadowing_warnings("import \"strings\"\nbuiltin y : int\nf = (strings, y) => 1"),
+        vec![
+            "warning shadowing_test@3:6-3:13: strings shadows the binding declared at 1:1",
+            "warning shadowing_test@3:15-3:16: y shadows the binding declared at 2:9",
+        ],
+    );
+}
+
+#[test]
+fn test_shadowing_prelude() {
+    assert_eq!(
+        shadowing_warnings_with_prelude("map = 1\nf = (filter) => filter", &["map", "filter"]),
+        vec![
+            "warning shadowing_test@1:1-1:4: map shadows a builtin of the prelude",
+            "warning shadowing_test@2:6-2:12: filter shadows a builtin of the prelude",
+        ],
+    );
+}
+
+#[test]
+fn test_shadowing_sibling_scopes() {
+    assert!(shadowing_warnings("f = (x) => x\ng = (x) => x").is_empty());
+}
+
+#[test]
+fn test_shadowing_suppressed() {
+    assert!(shadowing_warnings(&format!(
+        "x = 1\nf = (\n    // {}\n    x,\n) => x",
+        ALLOW_SHADOWING
+    ))
+    .is_empty());
+}
+
+fn unused_warnings(src: &str) -> Vec<String> {
+    let file = parse_string("unused_test".to_string(), src);
+    check_unused(walk::Node::File(&file))
+        .into_iter()
+        .map(|warning| warning.to_string())
+        .collect()
+}
+
+#[test]
+fn test_unused_top_level() {
+    assert_eq!(
+        unused_warnings("x = 1\ny = 2\nz = y"),
+        vec![
+            "warning unused_test@1:1-1:2: x is declared but never used",
+            "warning unused_test@3:1-3:2: z is declared but never used",
+        ],
+    );
+}
+
+#[test]
+fn test_unused_local() {
+    assert_eq!(
+        unused_warnings("f = (r, _s) => {\n    a = 1\n    b = r\n    return b\n}\nf(r: 1, _s: 2)"),
+        vec!["warning unused_test@2:5-2:6: a is declared but never used"],
+    );
+}
+
+#[test]
+fn test_unused_initializer_refers_to_outer_binding() {
+    assert!(unused_warnings("x = 1\nf = () => {\n    x = x + 1\n    return x\n}\nf()").is_empty());
+}
+
+#[test]
+fn test_unused_exemptions() {
+    assert!(unused_warnings(
+        "option now = () => 2020-01-01T00:00:00Z\ntest t = () => ({})\na = 1\n{a}"
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_unused_exported() {
+    assert_eq!(
+        unused_warnings("export x = 1\nexport {a, b} = {a: 1, b: 2}\ny = 2"),
+        vec!["warning unused_test@3:1-3:2: y is declared but never used"],
+    );
+}
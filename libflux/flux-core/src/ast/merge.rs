@@ -0,0 +1,232 @@
+//! Merging the files of a package which were parsed separately.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+};
+
+use codespan_reporting::diagnostic;
+
+use crate::{
+    ast::{BaseNode, File, Identifier, Package, SourceLocation, Statement},
+    errors::{located, AsDiagnostic, Errors, Located, Severity},
+};
+
+/// An error that can be returned while merging the files of a package, see [`Package::merge`].
+pub type MergeError = Located<MergeErrorKind>;
+
+/// An error that can be returned while merging the files of a package, see [`Package::merge`].
+#[derive(Debug, PartialEq)]
+pub enum MergeErrorKind {
+    /// No file was given.
+    NoFiles,
+    /// A file which declares another package than the first file.
+    ConflictingPackage {
+        /// Name of the package declared by the first file.
+        expected: String,
+        /// Name of the package declared by the file.
+        found: String,
+        /// Location of the package clause of the first file.
+        original: SourceLocation,
+    },
+    /// A top-level identifier which is already declared by another file.
+    DuplicateIdentifier {
+        /// Name of the identifier.
+        name: String,
+        /// Location of the identifier in the other file.
+        original: SourceLocation,
+    },
+}
+
+impl Severity for MergeErrorKind {}
+
+impl fmt::Display for MergeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoFiles => write!(f, "a package must have at least one file"),
+            Self::ConflictingPackage {
+                expected,
+                found,
+                original,
+            } => write!(
+                f,
+                "expected package {} but found package {}, package {} is declared at {}",
+                expected, found, expected, original
+            ),
+            Self::DuplicateIdentifier { name, original } => {
+                write!(f, "{} is already declared at {}", name, original)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeErrorKind {}
+
+impl AsDiagnostic for MergeErrorKind {
+    // The original declaration is in another file so it can't be labelled in the source
+    fn as_diagnostic(&self, _source: &dyn crate::semantic::Source) -> diagnostic::Diagnostic<()> {
+        diagnostic::Diagnostic::error().with_message(self.to_string())
+    }
+}
+
+impl Package {
+    /// Merges files of the same package, parsed separately, into a single package.
+    ///
+    /// The files are ordered by their name so that the statements of the package are always in
+    /// the same order, whatever the order of `files`. Every file must declare the same package
+    /// as the first one, a file without a package clause belongs to the `main` package. A
+    /// top-level identifier may not be declared by more than one of the files. Every conflict
+    /// is reported, at its location in the later file.
+    pub fn merge(mut files: Vec<File>) -> Result<Package, Errors<MergeError>> {
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let first = match files.first() {
+            Some(first) => first,
+            None => {
+                return Err(Errors::from(vec![located(
+                    SourceLocation::default(),
+                    MergeErrorKind::NoFiles,
+                )]))
+            }
+        };
+        let package = first.get_package().to_string();
+
+        let mut errors = Errors::new();
+        for file in &files[1..] {
+            if file.get_package() != package {
+                errors.push(located(
+                    package_location(file).clone(),
+                    MergeErrorKind::ConflictingPackage {
+                        expected: package.clone(),
+                        found: file.get_package().to_string(),
+                        original: package_location(first).clone(),
+                    },
+                ));
+            }
+        }
+
+        // The file declaring each identifier, along with its declaration
+        let mut declared: HashMap<&str, (usize, &Identifier)> = HashMap::new();
+        for (i, file) in files.iter().enumerate() {
+            for id in file.body.iter().flat_map(top_level_identifiers) {
+                match declared.entry(id.name.as_str()) {
+                    // Redeclaring an identifier within a file is left to the analysis
+                    Entry::Occupied(original) if original.get().0 != i => {
+                        errors.push(located(
+                            id.base.location.clone(),
+                            MergeErrorKind::DuplicateIdentifier {
+                                name: id.name.clone(),
+                                original: original.get().1.base.location.clone(),
+                            },
+                        ));
+                    }
+                    Entry::Occupied(_) => (),
+                    Entry::Vacant(entry) => {
+                        entry.insert((i, id));
+                    }
+                }
+            }
+        }
+
+        if errors.has_errors() {
+            return Err(errors);
+        }
+        Ok(Package {
+            base: BaseNode::default(),
+            path: String::new(),
+            package,
+            files,
+        })
+    }
+}
+
+fn package_location(file: &File) -> &SourceLocation {
+    match &file.package {
+        Some(clause) => &clause.base.location,
+        None => &file.base.location,
+    }
+}
+
+// Returns the identifiers declared by a top-level statement.
+fn top_level_identifiers(stmt: &Statement) -> Vec<&Identifier> {
+    match stmt {
+        Statement::Variable(stmt) => vec![&stmt.id],
+        Statement::Builtin(stmt) => vec![&stmt.id],
+        Statement::TypeAlias(stmt) => vec![&stmt.id],
+        Statement::Test(stmt) => vec![&stmt.assignment.id],
+        Statement::TestCase(stmt) => vec![&stmt.id],
+        Statement::Destructuring(stmt) => stmt.bindings().map(|(_, id)| id).collect(),
+        Statement::Export(stmt) => top_level_identifiers(&stmt.statement),
+        Statement::Expr(_) | Statement::Option(_) | Statement::Return(_) | Statement::Bad(_) => {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn parse(files: &[(&str, &str)]) -> Vec<File> {
+        files
+            .iter()
+            .map(|(name, src)| parser::parse_string(name.to_string(), src))
+            .collect()
+    }
+
+    #[test]
+    fn merge_files_in_order_of_their_names() {
+        let pkg = Package::merge(parse(&[
+            ("b.flux", "package a\ny = x + 1"),
+            ("a.flux", "package a\nx = 1"),
+        ]))
+        .unwrap();
+        assert_eq!("a", pkg.package);
+        assert_eq!(
+            vec!["a.flux", "b.flux"],
+            pkg.files
+                .iter()
+                .map(|file| file.name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn conflicting_packages() {
+        let err = Package::merge(parse(&[
+            ("a.flux", "package a\nx = 1"),
+            ("b.flux", "package b\ny = 1"),
+            ("c.flux", "z = 2"),
+        ]))
+        .unwrap_err();
+        expect_test::expect![[r#"
+            error b.flux@1:1-1:10: expected package a but found package b, package a is declared at a.flux@1:1-1:10
+
+            error c.flux@1:1-1:6: expected package a but found package main, package a is declared at a.flux@1:1-1:10"#]]
+        .assert_eq(&err.to_string());
+    }
+
+    #[test]
+    fn duplicate_identifiers() {
+        let err = Package::merge(parse(&[
+            ("a.flux", "package a\nx = 1\nx = 2\nbuiltin y : int"),
+            ("b.flux", "package a\n{x, z} = {x: 1, z: 2}\ny = 3"),
+        ]))
+        .unwrap_err();
+        expect_test::expect![[r#"
+            error b.flux@2:2-2:3: x is already declared at a.flux@2:1-2:2
+
+            error b.flux@3:1-3:2: y is already declared at a.flux@4:9-4:10"#]]
+        .assert_eq(&err.to_string());
+    }
+
+    #[test]
+    fn no_files() {
+        let err = Package::merge(Vec::new()).unwrap_err();
+        assert_eq!(
+            MergeErrorKind::NoFiles,
+            err.into_iter().next().unwrap().error
+        );
+    }
+}
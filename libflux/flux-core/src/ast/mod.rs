@@ -1,6 +1,7 @@
 //! Abstract syntax tree (AST).
 
 pub mod check;
+pub mod merge;
 pub mod walk;
 
 use std::{collections::HashMap, fmt, str::FromStr, vec::Vec};
@@ -21,7 +22,7 @@ pub const DEFAULT_PACKAGE_NAME: &str = "main";
 
 /// Position is the AST counterpart of [`scanner::Position`].
 /// It adds serde capabilities.
-#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct Position {
     pub line: u32,
@@ -48,15 +49,6 @@ impl From<&scanner::Position> for Position {
     }
 }
 
-impl From<&Position> for scanner::Position {
-    fn from(item: &Position) -> Self {
-        scanner::Position {
-            line: item.line,
-            column: item.column,
-        }
-    }
-}
-
 impl Default for Position {
     fn default() -> Self {
         Self::invalid()
@@ -99,6 +91,10 @@ impl SourceLocation {
     pub fn is_multiline(&self) -> bool {
         self.start.line != self.end.line
     }
+    /// Returns true if `pos` lies within this location. The end position is exclusive.
+    pub fn contains(&self, pos: Position) -> bool {
+        self.start <= pos && pos < self.end
+    }
 }
 
 #[cfg(feature = "lsp")]
@@ -115,6 +111,10 @@ fn skip_string_option(opt_str: &Option<String>) -> bool {
     opt_str.is_none() || opt_str.as_ref().unwrap().is_empty()
 }
 
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
 impl fmt::Display for SourceLocation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let fname = match &self.file {
@@ -168,10 +168,22 @@ pub enum Expression {
     Call(Box<CallExpr>),
     #[serde(rename = "ConditionalExpression")]
     Conditional(Box<ConditionalExpr>),
+    #[serde(rename = "TernaryExpression")]
+    Ternary(Box<TernaryExpr>),
     #[serde(rename = "StringExpression")]
     StringExpr(Box<StringExpr>),
     #[serde(rename = "ParenExpression")]
     Paren(Box<ParenExpr>),
+    #[serde(rename = "TupleExpression")]
+    Tuple(Box<TupleExpr>),
+    #[serde(rename = "TupleIndexExpression")]
+    TupleIndex(Box<TupleIndexExpr>),
+    #[serde(rename = "BlockExpression")]
+    Block(Box<BlockExpr>),
+    #[serde(rename = "MatchExpression")]
+    Match(Box<MatchExpr>),
+    #[serde(rename = "SpreadExpression")]
+    Spread(Box<SpreadExpr>),
 
     #[serde(rename = "IntegerLiteral")]
     Integer(IntegerLit),
@@ -213,6 +225,7 @@ impl Expression {
             Expression::PipeExpr(wrapped) => &wrapped.base,
             Expression::Call(wrapped) => &wrapped.base,
             Expression::Conditional(wrapped) => &wrapped.base,
+            Expression::Ternary(wrapped) => &wrapped.base,
             Expression::Integer(wrapped) => &wrapped.base,
             Expression::Float(wrapped) => &wrapped.base,
             Expression::StringLit(wrapped) => &wrapped.base,
@@ -225,6 +238,11 @@ impl Expression {
             Expression::Bad(wrapped) => &wrapped.base,
             Expression::StringExpr(wrapped) => &wrapped.base,
             Expression::Paren(wrapped) => &wrapped.base,
+            Expression::Tuple(wrapped) => &wrapped.base,
+            Expression::TupleIndex(wrapped) => &wrapped.base,
+            Expression::Block(wrapped) => &wrapped.base,
+            Expression::Match(wrapped) => &wrapped.base,
+            Expression::Spread(wrapped) => &wrapped.base,
         }
     }
 }
@@ -249,6 +267,12 @@ pub enum Statement {
     TestCase(Box<TestCaseStmt>),
     #[serde(rename = "BuiltinStatement")]
     Builtin(Box<BuiltinStmt>),
+    #[serde(rename = "TypeAliasStatement")]
+    TypeAlias(Box<TypeAliasStmt>),
+    #[serde(rename = "DestructuringAssignment")]
+    Destructuring(Box<DestructuringAssgn>),
+    #[serde(rename = "ExportStatement")]
+    Export(Box<ExportStmt>),
 }
 
 impl Statement {
@@ -263,6 +287,9 @@ impl Statement {
             Statement::Test(wrapped) => &wrapped.base,
             Statement::TestCase(wrapped) => &wrapped.base,
             Statement::Builtin(wrapped) => &wrapped.base,
+            Statement::TypeAlias(wrapped) => &wrapped.base,
+            Statement::Destructuring(wrapped) => &wrapped.base,
+            Statement::Export(wrapped) => &wrapped.base,
         }
     }
 
@@ -277,6 +304,9 @@ impl Statement {
             Statement::Test(_) => 5,
             Statement::TestCase(_) => 7,
             Statement::Builtin(_) => 6,
+            Statement::TypeAlias(_) => 8,
+            Statement::Destructuring(_) => 9,
+            Statement::Export(_) => 10,
         }
     }
     /// Returns the name of the type of statement.
@@ -290,6 +320,9 @@ impl Statement {
             Statement::Test(_) => "test",
             Statement::TestCase(_) => "testcase",
             Statement::Builtin(_) => "builtin",
+            Statement::TypeAlias(_) => "type alias",
+            Statement::Destructuring(_) => "destructuring",
+            Statement::Export(_) => "export",
         }
     }
 }
@@ -321,6 +354,10 @@ pub enum PropertyKey {
     Identifier(Identifier),
     #[serde(rename = "StringLiteral")]
     StringLit(StringLit),
+    /// A record spread into a record literal, `{...r}`. It stands for all the properties of the
+    /// record, so the property has no value.
+    #[serde(rename = "SpreadExpression")]
+    Spread(Box<SpreadExpr>),
 }
 
 impl From<Identifier> for PropertyKey {
@@ -341,6 +378,7 @@ impl PropertyKey {
         match self {
             PropertyKey::Identifier(wrapped) => &wrapped.base,
             PropertyKey::StringLit(wrapped) => &wrapped.base,
+            PropertyKey::Spread(wrapped) => &wrapped.base,
         }
     }
 }
@@ -384,6 +422,10 @@ where
 #[allow(missing_docs)]
 pub struct Comment {
     pub text: String,
+    /// Whether the comment started on the same line as the token preceding it.
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub trailing: bool,
 }
 
 /// BaseNode holds the attributes every expression or statement must have.
@@ -591,6 +633,73 @@ pub struct BuiltinStmt {
     pub ty: TypeExpression,
 }
 
+/// Declares a name for a type, e.g. `type Point = {x: float, y: float}`.
+///
+/// A parameterized alias lists its type parameters before the aliased type,
+/// e.g. `type Pair = (A) => {first: A, second: A}`, and is applied as `Pair[int]`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct TypeAliasStmt {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub id: Identifier,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub params: Vec<Identifier>,
+    pub ty: MonoType,
+}
+
+/// Binds several fields of a record at once, e.g. `{a, b} = r` or `{a: x, b: y} = r`.
+///
+/// The pattern is a record literal whose properties name the fields being bound. A property
+/// without a value binds the field to a variable of the same name, `a: x` binds the field `a`
+/// to `x`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct DestructuringAssgn {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub pattern: ObjectExpr,
+    pub init: Expression,
+}
+
+impl DestructuringAssgn {
+    /// Returns the label and the identifier bound to it for every property of the pattern.
+    /// Properties which do not name a label or which are not bound to an identifier are skipped.
+    pub fn bindings(&self) -> impl Iterator<Item = (&PropertyKey, &Identifier)> {
+        self.pattern
+            .properties
+            .iter()
+            .filter_map(|prop| match (&prop.key, &prop.value) {
+                (PropertyKey::Identifier(id), None) => Some((&prop.key, id)),
+                (
+                    PropertyKey::Identifier(_) | PropertyKey::StringLit(_),
+                    Some(Expression::Identifier(id)),
+                ) => Some((&prop.key, id)),
+                _ => None,
+            })
+    }
+}
+
+/// Marks a top-level binding as exported by its package, e.g. `export x = 1`.
+///
+/// Only variable assignments, builtin statements and destructuring assignments can be exported.
+/// The modifier only matters when the `StrictPackageExports` feature is enabled, otherwise every
+/// top-level binding of a package is exported.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct ExportStmt {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub statement: Statement,
+}
+
 /// A monotype.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -610,6 +719,8 @@ pub enum MonoType {
     Record(RecordType),
     #[serde(rename = "FunctionType")]
     Function(Box<FunctionType>),
+    #[serde(rename = "AppliedType")]
+    Applied(Box<AppliedType>),
 }
 
 impl MonoType {
@@ -623,6 +734,7 @@ impl MonoType {
             MonoType::Dict(t) => &t.base,
             MonoType::Record(t) => &t.base,
             MonoType::Function(t) => &t.base,
+            MonoType::Applied(t) => &t.base,
         }
     }
 }
@@ -637,6 +749,18 @@ pub struct NamedType {
     pub name: Identifier,
 }
 
+/// A parameterized type alias applied to type arguments, e.g. `Pair[int]`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct AppliedType {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub name: Identifier,
+    pub arguments: Vec<MonoType>,
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct TvarType {
@@ -753,6 +877,10 @@ pub struct TypeConstraint {
     pub base: BaseNode,
     pub tvar: Identifier,
     pub kinds: Vec<Identifier>,
+    /// The labels a `Label` kind is restricted to, as in `where L: Label in {"a", "b"}`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub labels: Vec<StringLit>,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -776,6 +904,10 @@ pub struct PropertyType {
     pub base: BaseNode,
     pub name: PropertyKey,
     pub monotype: MonoType,
+    /// Whether the property was written as `?name: type`, records may omit it.
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub optional: bool,
 }
 
 /// TestStmt declares a Flux test case.
@@ -806,6 +938,9 @@ pub struct TestCaseStmt {
 }
 
 /// Represents the declaration of a variable.
+///
+/// The variable may be annotated with a type, e.g. `x : [int] = [1, 2]`, in which case `ty`
+/// holds the annotation and `colon` the comments before the `:`.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct VariableAssgn {
@@ -814,6 +949,12 @@ pub struct VariableAssgn {
     #[serde(flatten)]
     pub base: BaseNode,
     pub id: Identifier,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub colon: Vec<Comment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub ty: Option<TypeExpression>,
     pub init: Expression,
 }
 
@@ -935,6 +1076,10 @@ pub struct MemberExpr {
     #[serde(default)]
     pub lbrack: Vec<Comment>,
     pub property: PropertyKey,
+    /// `true` for a guarded access, `r?.a`.
+    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default)]
+    pub optional: bool,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
     pub rbrack: Vec<Comment>,
@@ -996,6 +1141,8 @@ pub enum Operator {
     ModuloOperator,
     #[display(fmt = "^")]
     PowerOperator,
+    #[display(fmt = "**")]
+    ExponentiationOperator,
     #[display(fmt = "+")]
     AdditionOperator,
     #[display(fmt = "-")]
@@ -1028,6 +1175,8 @@ pub enum Operator {
     RegexpMatchOperator,
     #[display(fmt = "!~")]
     NotRegexpMatchOperator,
+    #[display(fmt = "??")]
+    NullCoalescingOperator,
 
     // this is necessary for bad binary expressions.
     #[display(fmt = "<INVALID_OP>")]
@@ -1052,6 +1201,7 @@ impl FromStr for Operator {
             "/" => Ok(Operator::DivisionOperator),
             "%" => Ok(Operator::ModuloOperator),
             "^" => Ok(Operator::PowerOperator),
+            "**" => Ok(Operator::ExponentiationOperator),
             "+" => Ok(Operator::AdditionOperator),
             "-" => Ok(Operator::SubtractionOperator),
             "<=" => Ok(Operator::LessThanEqualOperator),
@@ -1068,6 +1218,7 @@ impl FromStr for Operator {
             "!=" => Ok(Operator::NotEqualOperator),
             "=~" => Ok(Operator::RegexpMatchOperator),
             "!~" => Ok(Operator::NotRegexpMatchOperator),
+            "??" => Ok(Operator::NullCoalescingOperator),
             "<INVALID_OP>" => Ok(Operator::InvalidOperator),
             _ => Err(format!("unknown operator: {}", s)),
         }
@@ -1245,6 +1396,95 @@ pub struct ArrayExpr {
     pub rbrack: Vec<Comment>,
 }
 
+/// TupleExpr is used to create a fixed-size tuple such as `(1, "a")`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct TupleExpr {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub lparen: Vec<Comment>,
+    #[serde(deserialize_with = "deserialize_default_from_null")]
+    pub elements: Vec<ArrayItem>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub rparen: Vec<Comment>,
+}
+
+/// BlockExpr is a sequence of statements which evaluates to its trailing expression such as
+/// `{ a = 1; a + 1 }`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct BlockExpr {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub lbrace: Vec<Comment>,
+    pub body: Vec<Statement>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub rbrace: Vec<Comment>,
+}
+
+/// MatchExpr evaluates to the body of the first arm whose pattern matches the value of its
+/// scrutinee such as `match x { 1 => "one", _ => "many" }`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct MatchExpr {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub scrutinee: Expression,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub lbrace: Vec<Comment>,
+    pub arms: Vec<MatchArm>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub rbrace: Vec<Comment>,
+}
+
+/// MatchArm is an arm of a [`MatchExpr`], `pattern => body`. The pattern is a literal or the
+/// wildcard `_` matching every value.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct MatchArm {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub pattern: Expression,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub arrow: Vec<Comment>,
+    pub body: Expression,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub comma: Vec<Comment>,
+}
+
+/// Represents positional access into a tuple such as `t.0`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct TupleIndexExpr {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub tuple: Expression,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub dot: Vec<Comment>,
+    pub index: IntegerLit,
+}
+
 /// DictExpr represents a dictionary literal
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -1328,6 +1568,37 @@ pub struct ConditionalExpr {
     pub alternate: Expression,
 }
 
+/// TernaryExpr is the shorthand `test ? consequent : alternate` of a [`ConditionalExpr`].
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct TernaryExpr {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub test: Expression,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub tk_question: Vec<Comment>,
+    pub consequent: Expression,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub tk_colon: Vec<Comment>,
+    pub alternate: Expression,
+}
+
+/// SpreadExpr expands the elements of an array, `[...xs, 4]`, or the properties of a record,
+/// `{...r, x: 1}`, into the literal it is part of.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct SpreadExpr {
+    #[serde(skip_serializing_if = "BaseNode::is_empty")]
+    #[serde(default)]
+    #[serde(flatten)]
+    pub base: BaseNode,
+    pub argument: Expression,
+}
+
 /// BadExpr is a malformed expression that contains the reason why in `text`.
 /// It can contain another expression, so that the parser can make a chained list of bad expressions.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -514,6 +514,7 @@ fn test_json_type_expression() {
                     name: "Divisible".to_string(),
                 },
             ],
+            labels: vec![],
         }],
     };
     let serialized = serde_json::to_string(&n).unwrap();
@@ -595,6 +596,7 @@ fn test_json_record() {
                     name: "int".to_string(),
                 },
             }),
+            optional: false,
         }],
     });
     let serialized = serde_json::to_string(&n).unwrap();
@@ -636,6 +638,7 @@ fn test_json_record_no_tvar() {
                     name: "int".to_string(),
                 },
             }),
+            optional: false,
         }],
     });
     let serialized = serde_json::to_string(&n).unwrap();
@@ -882,6 +885,8 @@ fn test_json_option_statement() {
                 ],
                 rbrace: vec![],
             })),
+            colon: vec![],
+            ty: None,
         })),
     }));
     let serialized = serde_json::to_string(&n).unwrap();
@@ -940,6 +945,7 @@ fn test_json_builtin_statement_comments() {
         },
         colon: vec![Comment {
             text: String::from("// colon comment\n"),
+            trailing: false,
         }],
         ty: TypeExpression {
             base: BaseNode::default(),
@@ -1036,6 +1042,8 @@ fn test_json_test_statement() {
                 ],
                 rbrace: vec![],
             })),
+            colon: vec![],
+            ty: None,
         },
     }));
     let serialized = serde_json::to_string(&n).unwrap();
@@ -1069,6 +1077,8 @@ fn test_json_test_case_statement() {
                     base: Default::default(),
                     value: 1,
                 }),
+                colon: vec![],
+                ty: None,
             }))],
             rbrace: vec![],
         },
@@ -1121,6 +1131,7 @@ fn test_json_qualified_option_statement() {
                     base: BaseNode::default(),
                     name: "state".to_string(),
                 }),
+                optional: false,
                 rbrack: vec![],
             },
             init: Expression::StringLit(StringLit {
@@ -1159,6 +1170,8 @@ fn test_json_variable_assignment() {
             base: BaseNode::default(),
             value: "hello".to_string(),
         }),
+        colon: vec![],
+        ty: None,
     }));
     let serialized = serde_json::to_string(&n).unwrap();
     assert_eq!(
@@ -1297,6 +1310,7 @@ fn test_json_member_expression_with_identifier() {
             base: BaseNode::default(),
             name: "b".to_string(),
         }),
+        optional: false,
         rbrack: vec![],
     }));
     let serialized = serde_json::to_string(&n).unwrap();
@@ -1330,6 +1344,7 @@ fn test_json_member_expression_with_string_literal() {
             base: BaseNode::default(),
             value: "b".to_string(),
         }),
+        optional: false,
         rbrack: vec![],
     }));
     let serialized = serde_json::to_string(&n).unwrap();
@@ -2173,8 +2188,19 @@ impl<'a> Locator<'a> {
     }
 
     fn get_src(&self, sl: u32, sc: u32, el: u32, ec: u32) -> &str {
-        let start_offset = self.lines.get(sl as usize - 1).expect("line not found") + sc - 1;
-        let end_offset = self.lines.get(el as usize - 1).expect("line not found") + ec - 1;
-        return &self.source[start_offset as usize..end_offset as usize];
+        let start_offset = self.offset(sl, sc);
+        let end_offset = self.offset(el, ec);
+        return &self.source[start_offset..end_offset];
+    }
+
+    // Columns count characters rather than bytes, so they are mapped back to byte offsets in
+    // their line.
+    fn offset(&self, line: u32, column: u32) -> usize {
+        let start = *self.lines.get(line as usize - 1).expect("line not found") as usize;
+        let end = self
+            .lines
+            .get(line as usize)
+            .map_or(self.source.len(), |end| *end as usize);
+        start + crate::scanner::column_offset(&self.source[start..end], column)
     }
 }
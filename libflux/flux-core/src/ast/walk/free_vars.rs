@@ -0,0 +1,31 @@
+//! Collection of the free variables of an expression.
+
+use std::collections::BTreeSet;
+
+use super::{walk_scopes, Node, ScopeVisitor};
+use crate::ast::*;
+
+/// Returns the names of the identifiers referenced by `expr` which are not bound within it.
+///
+/// Function parameters are in scope in the body of their function, but not in the parameter
+/// defaults, and a variable assignment is in scope in the rest of its block after its
+/// initializer. A reference to a name that is shadowed by one of those bindings is not free.
+pub fn free_vars(expr: &Expression) -> BTreeSet<String> {
+    let mut v = FreeVars {
+        free: BTreeSet::new(),
+    };
+    walk_scopes(&mut v, Node::from_expr(expr));
+    v.free
+}
+
+struct FreeVars {
+    free: BTreeSet<String>,
+}
+
+impl<'a> ScopeVisitor<'a> for FreeVars {
+    fn reference(&mut self, id: &'a Identifier, binding: Option<&'a Identifier>) {
+        if binding.is_none() {
+            self.free.insert(id.name.clone());
+        }
+    }
+}
@@ -1,10 +1,15 @@
 //! Walking the AST.
 
+mod free_vars;
+mod scope;
 #[cfg(test)]
 mod tests;
+mod walk_mut;
 
 use derive_more::Display;
 
+pub(crate) use self::scope::{walk_scopes, BindingKind, ScopeVisitor};
+pub use self::{free_vars::free_vars, walk_mut::*};
 use crate::ast::*;
 
 /// Node represents any structure that can appear in the AST.
@@ -48,10 +53,22 @@ pub enum Node<'a> {
     CallExpr(&'a CallExpr),
     #[display(fmt = "ConditionalExpr")]
     ConditionalExpr(&'a ConditionalExpr),
+    #[display(fmt = "TernaryExpr")]
+    TernaryExpr(&'a TernaryExpr),
     #[display(fmt = "StringExpr")]
     StringExpr(&'a StringExpr),
     #[display(fmt = "ParenExpr")]
     ParenExpr(&'a ParenExpr),
+    #[display(fmt = "TupleExpr")]
+    TupleExpr(&'a TupleExpr),
+    #[display(fmt = "TupleIndexExpr")]
+    TupleIndexExpr(&'a TupleIndexExpr),
+    #[display(fmt = "BlockExpr")]
+    BlockExpr(&'a BlockExpr),
+    #[display(fmt = "MatchExpr")]
+    MatchExpr(&'a MatchExpr),
+    #[display(fmt = "SpreadExpr")]
+    SpreadExpr(&'a SpreadExpr),
 
     #[display(fmt = "IntegerLit")]
     IntegerLit(&'a IntegerLit),
@@ -90,6 +107,12 @@ pub enum Node<'a> {
     TestCaseStmt(&'a TestCaseStmt),
     #[display(fmt = "BuiltinStmt")]
     BuiltinStmt(&'a BuiltinStmt),
+    #[display(fmt = "TypeAliasStmt")]
+    TypeAliasStmt(&'a TypeAliasStmt),
+    #[display(fmt = "DestructuringAssgn")]
+    DestructuringAssgn(&'a DestructuringAssgn),
+    #[display(fmt = "ExportStmt")]
+    ExportStmt(&'a ExportStmt),
 
     // FunctionBlock
     #[display(fmt = "Block")]
@@ -144,8 +167,14 @@ impl<'a> Node<'a> {
             Node::PipeExpr(n) => &n.base,
             Node::CallExpr(n) => &n.base,
             Node::ConditionalExpr(n) => &n.base,
+            Node::TernaryExpr(n) => &n.base,
             Node::StringExpr(n) => &n.base,
             Node::ParenExpr(n) => &n.base,
+            Node::TupleExpr(n) => &n.base,
+            Node::TupleIndexExpr(n) => &n.base,
+            Node::BlockExpr(n) => &n.base,
+            Node::MatchExpr(n) => &n.base,
+            Node::SpreadExpr(n) => &n.base,
             Node::IntegerLit(n) => &n.base,
             Node::FloatLit(n) => &n.base,
             Node::StringLit(n) => &n.base,
@@ -163,6 +192,9 @@ impl<'a> Node<'a> {
             Node::TestStmt(n) => &n.base,
             Node::TestCaseStmt(n) => &n.base,
             Node::BuiltinStmt(n) => &n.base,
+            Node::TypeAliasStmt(n) => &n.base,
+            Node::DestructuringAssgn(n) => &n.base,
+            Node::ExportStmt(n) => &n.base,
             Node::Block(n) => &n.base,
             Node::Property(n) => &n.base,
             Node::TextPart(n) => &n.base,
@@ -195,8 +227,14 @@ impl<'a> Node<'a> {
             Expression::PipeExpr(e) => Node::PipeExpr(e),
             Expression::Call(e) => Node::CallExpr(e),
             Expression::Conditional(e) => Node::ConditionalExpr(e),
+            Expression::Ternary(e) => Node::TernaryExpr(e),
             Expression::StringExpr(e) => Node::StringExpr(e),
             Expression::Paren(e) => Node::ParenExpr(e),
+            Expression::Tuple(e) => Node::TupleExpr(e),
+            Expression::TupleIndex(e) => Node::TupleIndexExpr(e),
+            Expression::Block(e) => Node::BlockExpr(e),
+            Expression::Match(e) => Node::MatchExpr(e),
+            Expression::Spread(e) => Node::SpreadExpr(e),
             Expression::Integer(e) => Node::IntegerLit(e),
             Expression::Float(e) => Node::FloatLit(e),
             Expression::StringLit(e) => Node::StringLit(e),
@@ -220,6 +258,9 @@ impl<'a> Node<'a> {
             Statement::Test(s) => Node::TestStmt(s),
             Statement::TestCase(s) => Node::TestCaseStmt(s),
             Statement::Builtin(s) => Node::BuiltinStmt(s),
+            Statement::TypeAlias(s) => Node::TypeAliasStmt(s),
+            Statement::Destructuring(s) => Node::DestructuringAssgn(s),
+            Statement::Export(s) => Node::ExportStmt(s),
         }
     }
     fn from_function_body(fb: &FunctionBody) -> Node {
@@ -232,6 +273,7 @@ impl<'a> Node<'a> {
         match pk {
             PropertyKey::Identifier(i) => Node::Identifier(i),
             PropertyKey::StringLit(s) => Node::StringLit(s),
+            PropertyKey::Spread(s) => Node::SpreadExpr(s),
         }
     }
     fn from_string_expr_part(sp: &StringExprPart) -> Node {
@@ -369,6 +411,11 @@ where
                 walk(v, Node::from_expr(&n.consequent));
                 walk(v, Node::from_expr(&n.alternate));
             }
+            Node::TernaryExpr(n) => {
+                walk(v, Node::from_expr(&n.test));
+                walk(v, Node::from_expr(&n.consequent));
+                walk(v, Node::from_expr(&n.alternate));
+            }
             Node::StringExpr(n) => {
                 for part in n.parts.iter() {
                     walk(v, Node::from_string_expr_part(part));
@@ -377,6 +424,30 @@ where
             Node::ParenExpr(n) => {
                 walk(v, Node::from_expr(&n.expression));
             }
+            Node::TupleExpr(n) => {
+                for element in n.elements.iter() {
+                    walk(v, Node::from_expr(&element.expression));
+                }
+            }
+            Node::TupleIndexExpr(n) => {
+                walk(v, Node::from_expr(&n.tuple));
+                walk(v, Node::IntegerLit(&n.index));
+            }
+            Node::BlockExpr(n) => {
+                for s in n.body.iter() {
+                    walk(v, Node::from_stmt(s));
+                }
+            }
+            Node::MatchExpr(n) => {
+                walk(v, Node::from_expr(&n.scrutinee));
+                for arm in n.arms.iter() {
+                    walk(v, Node::from_expr(&arm.pattern));
+                    walk(v, Node::from_expr(&arm.body));
+                }
+            }
+            Node::SpreadExpr(n) => {
+                walk(v, Node::from_expr(&n.argument));
+            }
             Node::IntegerLit(_) => {}
             Node::FloatLit(_) => {}
             Node::StringLit(_) => {}
@@ -412,6 +483,13 @@ where
                 walk(v, Node::Identifier(&n.id));
                 walk(v, Node::TypeExpression(&n.ty));
             }
+            Node::TypeAliasStmt(n) => {
+                walk(v, Node::Identifier(&n.id));
+                for param in &n.params {
+                    walk(v, Node::Identifier(param));
+                }
+                walk(v, Node::MonoType(&n.ty));
+            }
             Node::Block(n) => {
                 for s in n.body.iter() {
                     walk(v, Node::from_stmt(s));
@@ -427,8 +505,18 @@ where
             Node::InterpolatedPart(n) => {
                 walk(v, Node::from_expr(&n.expression));
             }
+            Node::DestructuringAssgn(n) => {
+                walk(v, Node::ObjectExpr(&n.pattern));
+                walk(v, Node::from_expr(&n.init));
+            }
+            Node::ExportStmt(n) => {
+                walk(v, Node::from_stmt(&n.statement));
+            }
             Node::VariableAssgn(n) => {
                 walk(v, Node::Identifier(&n.id));
+                if let Some(ty) = &n.ty {
+                    walk(v, Node::TypeExpression(ty));
+                }
                 walk(v, Node::from_expr(&n.init));
             }
             Node::MemberAssgn(n) => {
@@ -466,6 +554,12 @@ where
 
                     walk(v, Node::MonoType(&f.monotype));
                 }
+                MonoType::Applied(a) => {
+                    walk(v, Node::Identifier(&a.name));
+                    for arg in &a.arguments {
+                        walk(v, Node::MonoType(arg));
+                    }
+                }
             },
             Node::PropertyType(n) => {
                 walk(v, Node::from_property_key(&n.name));
@@ -492,6 +586,9 @@ where
                 for id in &n.kinds {
                     walk(v, Node::Identifier(id));
                 }
+                for label in &n.labels {
+                    walk(v, Node::StringLit(label));
+                }
             }
         }
     }
@@ -0,0 +1,205 @@
+//! Walking the AST while resolving identifiers to the bindings they refer to.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{walk, Node, Visitor};
+use crate::ast::*;
+
+/// The kind of statement or expression which declares a binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BindingKind {
+    /// A function parameter.
+    Parameter,
+    /// A variable assignment or a destructuring assignment.
+    Variable,
+    /// An option, a test or an exported binding, which is visible from outside of the package.
+    Public,
+}
+
+/// Receives the bindings and references found by [`walk_scopes`].
+pub(crate) trait ScopeVisitor<'a> {
+    /// Called when `id` comes into scope.
+    fn declare(&mut self, _id: &'a Identifier, _kind: BindingKind) {}
+
+    /// Called for every identifier which refers to a binding, with the binding it resolves to or
+    /// `None` if it is not bound within the walked node.
+    fn reference(&mut self, _id: &'a Identifier, _binding: Option<&'a Identifier>) {}
+
+    /// Called when a scope ends with the bindings it declared, in declaration order.
+    fn exit_scope(&mut self, _bindings: &[&'a Identifier]) {}
+}
+
+/// Walks `node`, reporting to `v` when bindings come into and go out of scope and what each
+/// reference resolves to.
+///
+/// Function parameters are in scope in the body of their function, but not in the parameter
+/// defaults, and an assignment is in scope in the rest of its block after its initializer, so
+/// that `x = x + 1` refers to the outer `x`. Type expressions and type aliases only refer to
+/// types and are skipped.
+pub(crate) fn walk_scopes<'a>(v: &mut impl ScopeVisitor<'a>, node: Node<'a>) {
+    let mut scoped = Scoped {
+        v,
+        scopes: vec![Vec::new()],
+        not_references: HashSet::new(),
+        kinds: HashMap::new(),
+    };
+    walk(&mut scoped, node);
+    scoped.exit_scope();
+}
+
+struct Scoped<'a, 'v, V> {
+    v: &'v mut V,
+    scopes: Vec<Vec<&'a Identifier>>,
+    // Identifiers which name a binding or a property instead of referring to a binding.
+    not_references: HashSet<*const Identifier>,
+    // Bindings which are not plain variables.
+    kinds: HashMap<*const Identifier, BindingKind>,
+}
+
+impl<'a, V: ScopeVisitor<'a>> Scoped<'a, '_, V> {
+    fn declare(&mut self, id: &'a Identifier) {
+        let kind = self
+            .kinds
+            .get(&(id as *const Identifier))
+            .copied()
+            .unwrap_or(BindingKind::Variable);
+        self.not_references.insert(id);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(id);
+        }
+        self.v.declare(id, kind);
+    }
+
+    fn reference(&mut self, id: &'a Identifier) {
+        let binding = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().rev().find(|b| b.name == id.name))
+            .copied();
+        self.v.reference(id, binding);
+    }
+
+    fn exit_scope(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            self.v.exit_scope(&scope);
+        }
+    }
+}
+
+impl<'a, V: ScopeVisitor<'a>> Visitor<'a> for Scoped<'a, '_, V> {
+    fn visit(&mut self, node: Node<'a>) -> bool {
+        match node {
+            Node::PackageClause(n) => {
+                self.not_references.insert(&n.name);
+            }
+            Node::ImportDeclaration(n) => {
+                if let Some(alias) = &n.alias {
+                    self.not_references.insert(alias);
+                }
+            }
+            Node::OptionStmt(n) => {
+                if let Assignment::Variable(assgn) = &n.assignment {
+                    self.kinds.insert(&assgn.id, BindingKind::Public);
+                }
+            }
+            Node::TestStmt(n) => {
+                self.kinds.insert(&n.assignment.id, BindingKind::Public);
+            }
+            Node::TestCaseStmt(n) => {
+                self.not_references.insert(&n.id);
+            }
+            Node::ExportStmt(n) => match &n.statement {
+                Statement::Variable(assgn) => {
+                    self.kinds.insert(&assgn.id, BindingKind::Public);
+                }
+                Statement::Destructuring(assgn) => {
+                    for (_, id) in assgn.bindings() {
+                        self.kinds.insert(id, BindingKind::Public);
+                    }
+                }
+                _ => {}
+            },
+            Node::BuiltinStmt(n) => {
+                self.not_references.insert(&n.id);
+            }
+            Node::TypeExpression(_) | Node::TypeAliasStmt(_) => return false,
+            Node::VariableAssgn(n) => {
+                self.not_references.insert(&n.id);
+            }
+            Node::DestructuringAssgn(n) => {
+                for (_, id) in n.bindings() {
+                    self.not_references.insert(id);
+                }
+            }
+            Node::FunctionExpr(n) => {
+                // The defaults are evaluated outside of the scope of the parameters
+                for param in &n.params {
+                    if let Some(default) = &param.value {
+                        walk(self, Node::from_expr(default));
+                    }
+                }
+                self.scopes.push(Vec::new());
+                for param in &n.params {
+                    if let PropertyKey::Identifier(id) = &param.key {
+                        self.kinds.insert(id, BindingKind::Parameter);
+                        self.declare(id);
+                    }
+                }
+                match &n.body {
+                    FunctionBody::Block(block) => walk(self, Node::Block(block)),
+                    FunctionBody::Expr(expr) => walk(self, Node::from_expr(expr)),
+                }
+                self.exit_scope();
+                return false;
+            }
+            Node::Block(_) | Node::BlockExpr(_) => self.scopes.push(Vec::new()),
+            Node::ObjectExpr(n) => {
+                for prop in &n.properties {
+                    if let PropertyKey::Identifier(id) = &prop.key {
+                        // `{a}` is shorthand for `{a: a}`, except in a destructuring pattern
+                        // where it declares `a`.
+                        if self.not_references.insert(id) && prop.value.is_none() {
+                            self.reference(id);
+                        }
+                    }
+                }
+            }
+            Node::MemberExpr(n) => {
+                if let PropertyKey::Identifier(id) = &n.property {
+                    self.not_references.insert(id);
+                }
+            }
+            Node::MatchExpr(n) => {
+                for arm in &n.arms {
+                    // The wildcard pattern `_` matches every value
+                    if let Expression::Identifier(id) = &arm.pattern {
+                        if id.name == "_" {
+                            self.not_references.insert(id);
+                        }
+                    }
+                }
+            }
+            Node::Identifier(n) => {
+                if !self.not_references.contains(&(n as *const Identifier)) {
+                    self.reference(n);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn done(&mut self, node: Node<'a>) {
+        match node {
+            Node::VariableAssgn(n) => self.declare(&n.id),
+            Node::DestructuringAssgn(n) => {
+                for (_, id) in n.bindings() {
+                    self.declare(id);
+                }
+            }
+            Node::Block(_) | Node::BlockExpr(_) => self.exit_scope(),
+            _ => {}
+        }
+    }
+}
@@ -1,5 +1,7 @@
 // NOTE: These test cases directly match ast/json_test.go.
 // Every test is preceded by the correspondent test case in golang.
+use std::collections::BTreeSet;
+
 use super::*;
 use crate::parser::parse_string;
 
@@ -289,6 +291,19 @@ fn test_builtin_stmt() {
     )
 }
 #[test]
+fn test_export_stmt() {
+    test_walk(
+        "export a = b",
+        vec![
+            "File",
+            "ExportStmt",
+            "VariableAssgn",
+            "Identifier",
+            "Identifier",
+        ],
+    )
+}
+#[test]
 fn test_variable_assgn() {
     test_walk(
         "a = b",
@@ -310,3 +325,146 @@ fn test_member_assgn() {
         ],
     )
 }
+
+fn assert_rewritten(v: &mut impl VisitorMut, source: &str, want: &str) {
+    let mut file = parse_string("test_walk_mut".to_string(), source);
+    walk_mut(v, &mut file);
+    let want = parse_string("test_walk_mut".to_string(), want);
+    assert_eq!(
+        crate::formatter::convert_to_string(&want).unwrap(),
+        crate::formatter::convert_to_string(&file).unwrap(),
+    );
+}
+
+struct Rename;
+
+impl VisitorMut for Rename {
+    fn visit_identifier_mut(&mut self, id: &mut Identifier) {
+        if id.name == "old" {
+            id.name = "new".to_string();
+        }
+    }
+}
+
+#[test]
+fn test_walk_mut_rename_identifiers() {
+    assert_rewritten(
+        &mut Rename,
+        r#"import old "strings"
+
+option old = {old with a: old}
+old = (old, x=old.a) => {
+    y = if old then [old, old[0]] else {old: old}
+    return "${old}" + old(old: old)
+}
+old
+    |> old(fn: (r) => ({r with old: old.old}))
+    |> filter(fn: old)
+"#,
+        r#"import new "strings"
+
+option new = {new with a: new}
+new = (new, x=new.a) => {
+    y = if new then [new, new[0]] else {new: new}
+    return "${new}" + new(new: new)
+}
+new
+    |> new(fn: (r) => ({r with new: new.new}))
+    |> filter(fn: new)
+"#,
+    );
+}
+
+struct InlineConstant;
+
+impl VisitorMut for InlineConstant {
+    fn visit_expression_mut(&mut self, expr: &mut Expression) {
+        match expr {
+            Expression::Identifier(id) if id.name == "limit" => {
+                *expr = Expression::Integer(IntegerLit {
+                    base: id.base.clone(),
+                    value: 10,
+                });
+            }
+            _ => walk_expression_mut(self, expr),
+        }
+    }
+}
+
+#[test]
+fn test_walk_mut_replace_expression() {
+    assert_rewritten(
+        &mut InlineConstant,
+        "from(bucket: \"b\") |> limit(n: limit) |> map(fn: (r) => ({r with x: r.x * limit}))",
+        "from(bucket: \"b\") |> limit(n: 10) |> map(fn: (r) => ({r with x: r.x * 10}))",
+    );
+}
+
+fn test_free_vars(source: &str, want: Vec<&str>) {
+    let file = parse_string("test_free_vars".to_string(), source);
+    let expr = match &file.body[..] {
+        [Statement::Expr(stmt)] => &stmt.expression,
+        _ => panic!("expected a single expression statement"),
+    };
+    assert_eq!(
+        free_vars(expr),
+        want.into_iter().map(String::from).collect::<BTreeSet<_>>()
+    );
+}
+
+#[test]
+fn test_free_vars_expression() {
+    test_free_vars(
+        "a + b.c * f(x: d, y) + {e, z: 1}.z",
+        vec!["a", "b", "d", "e", "f", "y"],
+    );
+}
+
+#[test]
+fn test_free_vars_function() {
+    test_free_vars(
+        "(r, n=limit) => r._value * n + offset",
+        vec!["limit", "offset"],
+    );
+    test_free_vars("(n=n) => n", vec!["n"]);
+}
+
+#[test]
+fn test_free_vars_match() {
+    test_free_vars(
+        "match x { 1 => a, true => b, _ => c }",
+        vec!["a", "b", "c", "true", "x"],
+    );
+}
+
+#[test]
+fn test_free_vars_nested_functions() {
+    test_free_vars(
+        "(tables=<-) => tables |> map(fn: (r) => ({r with x: r.x * factor + (() => r.y)()}))",
+        vec!["factor", "map"],
+    );
+    test_free_vars("(x) => (y) => (z) => x + y + z + w", vec!["w"]);
+}
+
+#[test]
+fn test_free_vars_block_scopes() {
+    test_free_vars(
+        "(x) => {
+    y = x + a
+    a = y * 2
+    f = () => {
+        x = b
+        return x + a + y
+    }
+    return f() + x
+}",
+        vec!["a", "b"],
+    );
+    test_free_vars(
+        "(r) => {
+    {a, b: c} = r
+    return a + b + c
+}",
+        vec!["b"],
+    );
+}
@@ -0,0 +1,330 @@
+//! Mutable walking of the AST.
+//!
+//! [`VisitorMut`] has one `visit_*_mut` hook per kind of node that can be rewritten. Each hook
+//! defaults to calling the matching `walk_*_mut` function, which recurses into the children of the
+//! node. Overriding a hook and then calling the `walk_*_mut` function (or not) decides whether the
+//! children are visited, and since the hooks receive `&mut` references nodes can be replaced in
+//! place, e.g. `*expr = Expression::Identifier(..)`.
+
+use crate::ast::*;
+
+/// A visitor which may mutate the nodes of the AST while walking it.
+#[allow(missing_docs)]
+pub trait VisitorMut {
+    fn visit_package_mut(&mut self, pkg: &mut Package) {
+        walk_package_mut(self, pkg)
+    }
+    fn visit_file_mut(&mut self, file: &mut File) {
+        walk_file_mut(self, file)
+    }
+    fn visit_statement_mut(&mut self, stmt: &mut Statement) {
+        walk_statement_mut(self, stmt)
+    }
+    fn visit_variable_assgn_mut(&mut self, assgn: &mut VariableAssgn) {
+        walk_variable_assgn_mut(self, assgn)
+    }
+    fn visit_member_assgn_mut(&mut self, assgn: &mut MemberAssgn) {
+        walk_member_assgn_mut(self, assgn)
+    }
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        walk_block_mut(self, block)
+    }
+    fn visit_expression_mut(&mut self, expr: &mut Expression) {
+        walk_expression_mut(self, expr)
+    }
+    fn visit_function_expr_mut(&mut self, func: &mut FunctionExpr) {
+        walk_function_expr_mut(self, func)
+    }
+    fn visit_member_expr_mut(&mut self, member: &mut MemberExpr) {
+        walk_member_expr_mut(self, member)
+    }
+    fn visit_call_expr_mut(&mut self, call: &mut CallExpr) {
+        walk_call_expr_mut(self, call)
+    }
+    fn visit_pipe_expr_mut(&mut self, pipe: &mut PipeExpr) {
+        walk_pipe_expr_mut(self, pipe)
+    }
+    fn visit_property_mut(&mut self, prop: &mut Property) {
+        walk_property_mut(self, prop)
+    }
+    fn visit_property_key_mut(&mut self, key: &mut PropertyKey) {
+        walk_property_key_mut(self, key)
+    }
+    fn visit_string_expr_part_mut(&mut self, part: &mut StringExprPart) {
+        walk_string_expr_part_mut(self, part)
+    }
+    fn visit_identifier_mut(&mut self, _id: &mut Identifier) {}
+    fn visit_string_lit_mut(&mut self, _lit: &mut StringLit) {}
+}
+
+/// Walks the file, calling the hooks of `v` for every node which is visited.
+pub fn walk_mut<V>(v: &mut V, file: &mut File)
+where
+    V: VisitorMut + ?Sized,
+{
+    v.visit_file_mut(file)
+}
+
+#[allow(missing_docs)]
+pub fn walk_package_mut<V>(v: &mut V, pkg: &mut Package)
+where
+    V: VisitorMut + ?Sized,
+{
+    for file in pkg.files.iter_mut() {
+        v.visit_file_mut(file);
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_file_mut<V>(v: &mut V, file: &mut File)
+where
+    V: VisitorMut + ?Sized,
+{
+    if let Some(pkg) = &mut file.package {
+        v.visit_identifier_mut(&mut pkg.name);
+    }
+    for imp in file.imports.iter_mut() {
+        if let Some(alias) = &mut imp.alias {
+            v.visit_identifier_mut(alias);
+        }
+        v.visit_string_lit_mut(&mut imp.path);
+    }
+    for stmt in file.body.iter_mut() {
+        v.visit_statement_mut(stmt);
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_statement_mut<V>(v: &mut V, stmt: &mut Statement)
+where
+    V: VisitorMut + ?Sized,
+{
+    match stmt {
+        Statement::Expr(n) => v.visit_expression_mut(&mut n.expression),
+        Statement::Variable(n) => v.visit_variable_assgn_mut(n),
+        Statement::Option(n) => match &mut n.assignment {
+            Assignment::Variable(n) => v.visit_variable_assgn_mut(n),
+            Assignment::Member(n) => v.visit_member_assgn_mut(n),
+        },
+        Statement::Return(n) => v.visit_expression_mut(&mut n.argument),
+        Statement::Bad(_) => {}
+        Statement::Test(n) => v.visit_variable_assgn_mut(&mut n.assignment),
+        Statement::TestCase(n) => {
+            v.visit_identifier_mut(&mut n.id);
+            if let Some(extends) = &mut n.extends {
+                v.visit_string_lit_mut(extends);
+            }
+            v.visit_block_mut(&mut n.block);
+        }
+        // Type expressions are not rewritten, only the names being declared.
+        Statement::Builtin(n) => v.visit_identifier_mut(&mut n.id),
+        Statement::TypeAlias(n) => v.visit_identifier_mut(&mut n.id),
+        Statement::Destructuring(n) => {
+            for prop in n.pattern.properties.iter_mut() {
+                v.visit_property_mut(prop);
+            }
+            v.visit_expression_mut(&mut n.init);
+        }
+        Statement::Export(n) => v.visit_statement_mut(&mut n.statement),
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_variable_assgn_mut<V>(v: &mut V, assgn: &mut VariableAssgn)
+where
+    V: VisitorMut + ?Sized,
+{
+    v.visit_identifier_mut(&mut assgn.id);
+    v.visit_expression_mut(&mut assgn.init);
+}
+
+#[allow(missing_docs)]
+pub fn walk_member_assgn_mut<V>(v: &mut V, assgn: &mut MemberAssgn)
+where
+    V: VisitorMut + ?Sized,
+{
+    v.visit_member_expr_mut(&mut assgn.member);
+    v.visit_expression_mut(&mut assgn.init);
+}
+
+#[allow(missing_docs)]
+pub fn walk_block_mut<V>(v: &mut V, block: &mut Block)
+where
+    V: VisitorMut + ?Sized,
+{
+    for stmt in block.body.iter_mut() {
+        v.visit_statement_mut(stmt);
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_expression_mut<V>(v: &mut V, expr: &mut Expression)
+where
+    V: VisitorMut + ?Sized,
+{
+    match expr {
+        Expression::Identifier(n) => v.visit_identifier_mut(n),
+        Expression::Array(n) => {
+            for element in n.elements.iter_mut() {
+                v.visit_expression_mut(&mut element.expression);
+            }
+        }
+        Expression::Dict(n) => {
+            for element in n.elements.iter_mut() {
+                v.visit_expression_mut(&mut element.key);
+                v.visit_expression_mut(&mut element.val);
+            }
+        }
+        Expression::Function(n) => v.visit_function_expr_mut(n),
+        Expression::Logical(n) => {
+            v.visit_expression_mut(&mut n.left);
+            v.visit_expression_mut(&mut n.right);
+        }
+        Expression::Object(n) => {
+            if let Some(ws) = &mut n.with {
+                v.visit_identifier_mut(&mut ws.source);
+            }
+            for prop in n.properties.iter_mut() {
+                v.visit_property_mut(prop);
+            }
+        }
+        Expression::Member(n) => v.visit_member_expr_mut(n),
+        Expression::Index(n) => {
+            v.visit_expression_mut(&mut n.array);
+            v.visit_expression_mut(&mut n.index);
+        }
+        Expression::Binary(n) => {
+            v.visit_expression_mut(&mut n.left);
+            v.visit_expression_mut(&mut n.right);
+        }
+        Expression::Unary(n) => v.visit_expression_mut(&mut n.argument),
+        Expression::PipeExpr(n) => v.visit_pipe_expr_mut(n),
+        Expression::Call(n) => v.visit_call_expr_mut(n),
+        Expression::Conditional(n) => {
+            v.visit_expression_mut(&mut n.test);
+            v.visit_expression_mut(&mut n.consequent);
+            v.visit_expression_mut(&mut n.alternate);
+        }
+        Expression::Ternary(n) => {
+            v.visit_expression_mut(&mut n.test);
+            v.visit_expression_mut(&mut n.consequent);
+            v.visit_expression_mut(&mut n.alternate);
+        }
+        Expression::StringExpr(n) => {
+            for part in n.parts.iter_mut() {
+                v.visit_string_expr_part_mut(part);
+            }
+        }
+        Expression::Paren(n) => v.visit_expression_mut(&mut n.expression),
+        Expression::Tuple(n) => {
+            for element in n.elements.iter_mut() {
+                v.visit_expression_mut(&mut element.expression);
+            }
+        }
+        Expression::TupleIndex(n) => v.visit_expression_mut(&mut n.tuple),
+        Expression::Block(n) => {
+            for stmt in n.body.iter_mut() {
+                v.visit_statement_mut(stmt);
+            }
+        }
+        Expression::Match(n) => {
+            v.visit_expression_mut(&mut n.scrutinee);
+            for arm in n.arms.iter_mut() {
+                v.visit_expression_mut(&mut arm.pattern);
+                v.visit_expression_mut(&mut arm.body);
+            }
+        }
+        Expression::Spread(n) => v.visit_expression_mut(&mut n.argument),
+        Expression::StringLit(n) => v.visit_string_lit_mut(n),
+        Expression::Integer(_)
+        | Expression::Float(_)
+        | Expression::Duration(_)
+        | Expression::Uint(_)
+        | Expression::Boolean(_)
+        | Expression::DateTime(_)
+        | Expression::Regexp(_)
+        | Expression::PipeLit(_) => {}
+        Expression::Bad(n) => {
+            if let Some(e) = &mut n.expression {
+                v.visit_expression_mut(e);
+            }
+        }
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_function_expr_mut<V>(v: &mut V, func: &mut FunctionExpr)
+where
+    V: VisitorMut + ?Sized,
+{
+    for param in func.params.iter_mut() {
+        v.visit_property_mut(param);
+    }
+    match &mut func.body {
+        FunctionBody::Block(block) => v.visit_block_mut(block),
+        FunctionBody::Expr(expr) => v.visit_expression_mut(expr),
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_member_expr_mut<V>(v: &mut V, member: &mut MemberExpr)
+where
+    V: VisitorMut + ?Sized,
+{
+    v.visit_expression_mut(&mut member.object);
+    v.visit_property_key_mut(&mut member.property);
+}
+
+#[allow(missing_docs)]
+pub fn walk_call_expr_mut<V>(v: &mut V, call: &mut CallExpr)
+where
+    V: VisitorMut + ?Sized,
+{
+    v.visit_expression_mut(&mut call.callee);
+    for arg in call.arguments.iter_mut() {
+        v.visit_expression_mut(arg);
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_pipe_expr_mut<V>(v: &mut V, pipe: &mut PipeExpr)
+where
+    V: VisitorMut + ?Sized,
+{
+    v.visit_expression_mut(&mut pipe.argument);
+    v.visit_call_expr_mut(&mut pipe.call);
+}
+
+#[allow(missing_docs)]
+pub fn walk_property_mut<V>(v: &mut V, prop: &mut Property)
+where
+    V: VisitorMut + ?Sized,
+{
+    v.visit_property_key_mut(&mut prop.key);
+    if let Some(value) = &mut prop.value {
+        v.visit_expression_mut(value);
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_property_key_mut<V>(v: &mut V, key: &mut PropertyKey)
+where
+    V: VisitorMut + ?Sized,
+{
+    match key {
+        PropertyKey::Identifier(id) => v.visit_identifier_mut(id),
+        PropertyKey::StringLit(lit) => v.visit_string_lit_mut(lit),
+        PropertyKey::Spread(spread) => v.visit_expression_mut(&mut spread.argument),
+    }
+}
+
+#[allow(missing_docs)]
+pub fn walk_string_expr_part_mut<V>(v: &mut V, part: &mut StringExprPart)
+where
+    V: VisitorMut + ?Sized,
+{
+    match part {
+        StringExprPart::Text(_) => {}
+        StringExprPart::Interpolated(n) => v.visit_expression_mut(&mut n.expression),
+    }
+}
@@ -475,6 +475,18 @@ fn parse_package_values(
                     _ => None,
                 }
             }
+            // The comment of an exported value precedes the `export` keyword.
+            ast::Statement::Export(s) => match &s.statement {
+                ast::Statement::Variable(v) => {
+                    let comment = comments_to_string(&s.base.comments);
+                    Some((v.id.name.clone(), comment, &s.base.location, false))
+                }
+                ast::Statement::Builtin(b) => {
+                    let comment = comments_to_string(&s.base.comments);
+                    Some((b.id.name.clone(), comment, &s.base.location, false))
+                }
+                _ => None,
+            },
             // Other statements do not assign any value and therefore are not exported from a
             // package.
             _ => None,
@@ -484,7 +496,7 @@ fn parse_package_values(
                     let doc = parse_any_value(&name, &comment, typ, loc, diagnostics, is_option)?;
                     members.insert(name.clone(), doc);
                 }
-            } else {
+            } else if !pkgtypes.is_private(&name) {
                 bail!("type of value {} not found in environment", &name);
             }
         }
@@ -670,6 +682,8 @@ fn comments_to_string(comments: &[ast::Comment]) -> String {
             } else if let Some(t) = text.strip_prefix("//") {
                 // An empty comment line will not have the extra space.
                 s.push_str(t);
+            } else if text.starts_with("/*") {
+                // Block comments are not part of the documentation
             } else {
                 panic!("found invalid comment, all comments must start with //")
             }
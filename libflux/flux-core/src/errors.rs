@@ -1,18 +1,22 @@
 /// An error type which can represent multiple errors.
 use std::{
     any::Any,
+    cell::RefCell,
     error::Error as StdError,
     fmt,
-    ops::{Index, IndexMut},
+    ops::{Index, IndexMut, Range},
     slice, vec,
 };
 
 use codespan_reporting::diagnostic;
-use derive_more::Display;
+use serde_json::json;
 
 use crate::{
     ast,
-    semantic::sub::{Substitutable, Substituter},
+    semantic::{
+        self,
+        sub::{Substitutable, Substituter},
+    },
 };
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -169,8 +173,7 @@ impl<T: fmt::Display + fmt::Debug + Any> StdError for Errors<T> {
 }
 
 /// An error with an attached location
-#[derive(Debug, Display, PartialEq)]
-#[display(fmt = "error {}: {}", location, error)]
+#[derive(Debug, PartialEq)]
 pub struct Located<E> {
     /// The location where the error occured
     pub location: ast::SourceLocation,
@@ -196,7 +199,21 @@ impl<E> Located<E> {
     }
 }
 
-impl<T: StdError> StdError for Located<T> {
+/// The severity of a diagnostic, which prefixes it when it is displayed along with its location.
+pub trait Severity {
+    /// Returns the word which prefixes the diagnostic.
+    fn severity() -> &'static str {
+        "error"
+    }
+}
+
+impl<E: fmt::Display + Severity> fmt::Display for Located<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: {}", E::severity(), self.location, self.error)
+    }
+}
+
+impl<T: StdError + Severity> StdError for Located<T> {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         self.error.source()
     }
@@ -237,6 +254,101 @@ where
     }
 }
 
+/// Returns `errors` as a JSON array of diagnostics in the format of the Language Server Protocol.
+///
+/// Each diagnostic has a zero-based `range`, a `severity`, a `code` naming the step of the
+/// analysis which reported it and a `message`. The secondary locations of an error are listed in
+/// its `relatedInformation`. Columns are copied from the `SourceLocation` of the error as is.
+pub fn diagnostics_json(errors: &[semantic::Error]) -> String {
+    serde_json::Value::Array(
+        errors
+            .iter()
+            .map(|err| {
+                let code = match &err.error {
+                    semantic::ErrorKind::InvalidAST(_) => "invalid-ast",
+                    semantic::ErrorKind::Convert(_) => "convert",
+                    semantic::ErrorKind::InvalidSemantic(_) => "invalid-semantic",
+                    semantic::ErrorKind::Inference(_) => "inference",
+                    semantic::ErrorKind::Warning(_) => "warning",
+                };
+                diagnostic_json(err, code)
+            })
+            .collect(),
+    )
+    .to_string()
+}
+
+pub(crate) fn diagnostic_json<E>(err: &Located<E>, code: &str) -> serde_json::Value
+where
+    E: AsDiagnostic,
+{
+    let locations = LocationTable::default();
+    let diagnostic = err.as_diagnostic(&locations);
+    let locations = locations.locations.into_inner();
+
+    let mut range = range_json(&err.location);
+    let mut related = Vec::new();
+    for label in &diagnostic.labels {
+        let location = &locations[label.range.start];
+        match label.style {
+            diagnostic::LabelStyle::Primary => range = range_json(location),
+            diagnostic::LabelStyle::Secondary => related.push(json!({
+                "location": {
+                    "uri": location.file.as_deref().unwrap_or_default(),
+                    "range": range_json(location),
+                },
+                "message": label.message,
+            })),
+        }
+    }
+
+    let severity = match diagnostic.severity {
+        diagnostic::Severity::Bug | diagnostic::Severity::Error => 1,
+        diagnostic::Severity::Warning => 2,
+        diagnostic::Severity::Note => 3,
+        diagnostic::Severity::Help => 4,
+    };
+    let mut json = json!({
+        "range": range,
+        "severity": severity,
+        "code": diagnostic.code.as_deref().unwrap_or(code),
+        "message": diagnostic.message,
+    });
+    if !related.is_empty() {
+        json["relatedInformation"] = serde_json::Value::Array(related);
+    }
+    json
+}
+
+fn range_json(location: &ast::SourceLocation) -> serde_json::Value {
+    let position = |pos: &ast::Position| {
+        json!({
+            "line": pos.line.saturating_sub(1),
+            "character": pos.column.saturating_sub(1),
+        })
+    };
+    json!({
+        "start": position(&location.start),
+        "end": position(&location.end),
+    })
+}
+
+/// Records the locations given to the labels of a diagnostic. The range returned for a location
+/// is its index in the table so that the location of each label can be looked up afterwards.
+#[derive(Default)]
+struct LocationTable {
+    locations: RefCell<Vec<ast::SourceLocation>>,
+}
+
+impl semantic::Source for LocationTable {
+    fn codespan_range(&self, location: &ast::SourceLocation) -> Range<usize> {
+        let mut locations = self.locations.borrow_mut();
+        locations.push(location.clone());
+        let index = locations.len() - 1;
+        index..index
+    }
+}
+
 pub type SalvageResult<T, E> = std::result::Result<T, Salvage<T, E>>;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -290,3 +402,49 @@ impl<T, E> From<E> for Salvage<T, E> {
         Salvage { value: None, error }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::semantic::nodes;
+
+    fn loc(start: (u32, u32), end: (u32, u32)) -> ast::SourceLocation {
+        ast::SourceLocation {
+            file: Some("main".into()),
+            start: ast::Position {
+                line: start.0,
+                column: start.1,
+            },
+            end: ast::Position {
+                line: end.0,
+                column: end.1,
+            },
+            source: None,
+        }
+    }
+
+    #[test]
+    fn diagnostics_json_ranges() {
+        let errors = vec![located(
+            loc((2, 5), (2, 6)),
+            semantic::ErrorKind::Inference(nodes::ErrorKind::UndefinedIdentifier("x".into())),
+        )];
+        expect![[r#"[{"code":"inference","message":"undefined identifier x","range":{"end":{"character":5,"line":1},"start":{"character":4,"line":1}},"severity":1}]"#]]
+            .assert_eq(&diagnostics_json(&errors));
+    }
+
+    #[test]
+    fn diagnostics_json_related_information() {
+        let warning = located(
+            loc((3, 5), (3, 6)),
+            ast::check::WarningKind::Shadowed {
+                name: "x".into(),
+                original: loc((1, 1), (1, 2)),
+            },
+        );
+        expect![[r#"{"code":"lint","message":"x shadows the binding declared at 1:1","range":{"end":{"character":5,"line":2},"start":{"character":4,"line":2}},"relatedInformation":[{"location":{"range":{"end":{"character":1,"line":0},"start":{"character":0,"line":0}},"uri":"main"},"message":"originally declared here"}],"severity":2}"#]]
+            .assert_eq(&diagnostic_json(&warning, "lint").to_string());
+    }
+}
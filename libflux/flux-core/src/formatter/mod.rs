@@ -1,17 +1,60 @@
 //! Source code formatter.
 
+use std::borrow::Cow;
+
 use anyhow::{anyhow, Error, Result};
 use chrono::SecondsFormat;
 use pretty::{docs, DocAllocator};
 
 use crate::{
     ast::{self, walk::Node, File, Statement},
-    parser::parse_string,
+    parser::{parse_string, strconv},
 };
 
+/// Options that control the layout of formatted Flux code.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatterConfig {
+    /// The number of columns of each level of indentation.
+    pub indent: usize,
+    /// Indent with tabs instead of spaces. Each tab is counted as `indent` columns
+    /// when deciding where to wrap lines.
+    pub use_tabs: bool,
+    /// The number of columns after which expressions, such as long pipelines, are wrapped
+    /// across multiple lines. Default is 120.
+    pub max_width: usize,
+    /// How string literals are quoted.
+    pub string_style: StringStyle,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        FormatterConfig {
+            indent: 4,
+            use_tabs: false,
+            max_width: 120,
+            string_style: StringStyle::Preserve,
+        }
+    }
+}
+
+/// How the formatter writes string literals. Either way the value of a string is never changed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StringStyle {
+    /// Keeps string literals as they were written, with their delimiters and escapes.
+    Preserve,
+    /// Writes every string literal between double quotes, escaping only `"`, `\` and `${`.
+    /// For example `r"C:\dir"` becomes `"C:\\dir"` and `"\x41"` becomes `"A"`.
+    Normalize,
+}
+
 /// Format a [`File`].
 pub fn convert_to_string(file: &File) -> Result<String> {
-    format_to_string(file, true)
+    convert_to_string_with_config(file, &FormatterConfig::default())
+}
+
+/// Format a [`File`] using the given configuration.
+pub fn convert_to_string_with_config(file: &File, config: &FormatterConfig) -> Result<String> {
+    format_to_string(file, true, config)
 }
 
 /// Format a string of Flux code.
@@ -25,10 +68,27 @@ pub fn convert_to_string(file: &File) -> Result<String> {
 /// assert_eq!(formatted, "(r) => r.user == \"user1\"");
 /// ```
 pub fn format(contents: &str) -> Result<String> {
+    format_with_config(contents, &FormatterConfig::default())
+}
+
+/// Format a string of Flux code using the given configuration.
+///
+/// # Example
+///
+/// ```rust
+/// # use fluxcore::formatter::{format_with_config, FormatterConfig};
+/// let config = FormatterConfig {
+///     indent: 2,
+///     ..FormatterConfig::default()
+/// };
+/// let formatted = format_with_config("f = () => {\nreturn 1\n}", &config).unwrap();
+/// assert_eq!(formatted, "f = () => {\n  return 1\n}");
+/// ```
+pub fn format_with_config(contents: &str, config: &FormatterConfig) -> Result<String> {
     let file = parse_string("".to_string(), contents);
     let node = ast::walk::Node::File(&file);
     ast::check::check(node)?;
-    convert_to_string(&file)
+    convert_to_string_with_config(&file, config)
 }
 
 const MULTILINE: usize = 4;
@@ -141,6 +201,7 @@ fn comma_list_without_trailing_comma<'doc>(
 /// course still be on a single line)
 fn format_hang_doc<'doc>(
     arena: &'doc Arena<'doc>,
+    indent: isize,
     surrounding: &[Affixes<'doc>],
     body: Doc<'doc>,
 ) -> Doc<'doc> {
@@ -181,12 +242,12 @@ fn format_hang_doc<'doc>(
                                 arena.nil()
                             },
                         ]
-                        .nest(INDENT)
+                        .nest(indent)
                         .append(arena.concat(after.iter().map(|affixes| affixes.suffix.clone()))),
                         |acc, affixes| {
                             let mut doc = affixes.prefix.append(acc);
                             if affixes.nest {
-                                doc = doc.nest(INDENT);
+                                doc = doc.nest(indent);
                             }
                             doc.group()
                         },
@@ -241,33 +302,64 @@ impl<'doc> HangDoc<'doc> {
         }
     }
 
-    fn format(mut self) -> Doc<'doc> {
+    fn format(mut self, indent: isize) -> Doc<'doc> {
         self.affixes.reverse();
-        format_hang_doc(self.body.0, &self.affixes, self.body)
+        format_hang_doc(self.body.0, indent, &self.affixes, self.body)
     }
 }
 
-fn format_to_string(file: &File, include_pkg: bool) -> Result<String> {
+fn format_to_string(file: &File, include_pkg: bool, config: &FormatterConfig) -> Result<String> {
     let arena = Arena::new();
     let mut formatter = Formatter {
         arena: &arena,
+        indent: config.indent as isize,
+        use_tabs: config.use_tabs,
+        string_style: config.string_style,
         err: None,
     };
     let doc = formatter.format_file(file, include_pkg).group().1;
     if let Some(err) = formatter.err {
         return Err(err);
     }
-    let formatted = doc.pretty(120).to_string();
-    // Remove indentation from whitespace only lines
+    let formatted = doc.pretty(config.max_width).to_string();
     Ok(formatted
         .split('\n')
-        .map(|s| s.trim_end())
+        .map(|line| {
+            // Remove indentation from whitespace only lines
+            let line = line.trim_end();
+            match line.strip_prefix(VERBATIM_LINE) {
+                Some(line) => line.to_string(),
+                None if config.use_tabs => indent_with_tabs(line, config.indent),
+                None => line.to_string(),
+            }
+        })
         .collect::<Vec<_>>()
         .join("\n"))
 }
 
+// Marks the start of a line inside a multiline string so that its leading whitespace is
+// not mistaken for indentation.
+const VERBATIM_LINE: char = '\u{E000}';
+
+fn indent_with_tabs(line: &str, indent: usize) -> String {
+    if indent == 0 {
+        return line.to_string();
+    }
+    let content = line.trim_start_matches(' ');
+    let spaces = line.len() - content.len();
+    format!(
+        "{}{}{}",
+        "\t".repeat(spaces / indent),
+        " ".repeat(spaces % indent),
+        content
+    )
+}
+
 struct Formatter<'doc> {
     arena: &'doc Arena<'doc>,
+    indent: isize,
+    use_tabs: bool,
+    string_style: StringStyle,
     err: Option<Error>,
 }
 
@@ -335,9 +427,7 @@ impl<'doc> Formatter<'doc> {
         // format the file statements
         doc = doc.append(self.format_statement_list(&file.body));
 
-        if !file.eof.is_empty() {
-            doc = doc.append(self.format_comments(&file.eof));
-        }
+        doc = doc.append(self.format_end_comments(&file.body, &file.eof));
 
         doc
     }
@@ -348,16 +438,23 @@ impl<'doc> Formatter<'doc> {
         if !comments.is_empty() {
             doc = arena.line();
         }
-        docs![arena, doc, self.format_comments(comments)].nest(INDENT)
+        docs![arena, doc, self.format_comments(comments)].nest(self.indent)
     }
 
     fn format_comments(&mut self, comments: &'doc [ast::Comment]) -> Doc<'doc> {
         let arena = self.arena;
         arena.concat(comments.iter().map(|c| {
-            arena.intersperse(
+            let doc = arena.intersperse(
                 c.text.split('\n').map(|part| arena.text(part)),
                 arena.hardline(),
-            )
+            );
+            // A block comment which is not followed by a newline stays on the line of the
+            // next token
+            if c.text.starts_with("/*") && !c.text.ends_with('\n') {
+                doc.append(arena.text(" "))
+            } else {
+                doc
+            }
         }))
     }
 
@@ -381,6 +478,7 @@ impl<'doc> Formatter<'doc> {
                             self.format_identifier(&c.tvar),
                             ": ",
                             self.format_kinds(&c.kinds),
+                            self.format_labels(&c.labels),
                         ]
                         .group()),
                         line,
@@ -400,6 +498,22 @@ impl<'doc> Formatter<'doc> {
         )
     }
 
+    fn format_labels(&mut self, n: &'doc [ast::StringLit]) -> Doc<'doc> {
+        let arena = self.arena;
+        if n.is_empty() {
+            return arena.nil();
+        }
+        docs![
+            arena,
+            " in {",
+            arena.intersperse(
+                n.iter().map(|l| self.format_string_literal(l)),
+                arena.text(", "),
+            ),
+            "}",
+        ]
+    }
+
     fn format_monotype(&mut self, n: &'doc ast::MonoType) -> Doc<'doc> {
         let arena = self.arena;
         match n {
@@ -449,6 +563,7 @@ impl<'doc> Formatter<'doc> {
                             n.properties.iter().map(|p| {
                                 docs![
                                     arena,
+                                    if p.optional { "?" } else { "" },
                                     self.format_property_key(&p.name),
                                     ": ",
                                     self.format_monotype(&p.monotype),
@@ -458,7 +573,7 @@ impl<'doc> Formatter<'doc> {
                             line,
                         ),
                     ]
-                    .nest(INDENT),
+                    .nest(self.indent),
                     line_,
                     "}",
                 ]
@@ -481,13 +596,26 @@ impl<'doc> Formatter<'doc> {
                             line,
                         ),
                     ]
-                    .nest(INDENT),
+                    .nest(self.indent),
                     line_.clone(),
                     ")",
                     " => ",
                     self.format_monotype(&n.monotype),
                 ]
             }
+            ast::MonoType::Applied(n) => {
+                docs![
+                    arena,
+                    self.format_identifier(&n.name),
+                    "[",
+                    comma_list_without_trailing_comma(
+                        arena,
+                        n.arguments.iter().map(|arg| self.format_monotype(arg)),
+                        arena.text(" "),
+                    ),
+                    "]",
+                ]
+            }
         }
         .group()
     }
@@ -559,10 +687,19 @@ impl<'doc> Formatter<'doc> {
     }
 
     fn format_block(&mut self, n: &'doc ast::Block) -> HangDoc<'doc> {
+        self.format_braced_statements(&n.lbrace, &n.body, &n.rbrace)
+    }
+
+    fn format_braced_statements(
+        &mut self,
+        lbrace: &'doc [ast::Comment],
+        body: &'doc [Statement],
+        rbrace: &'doc [ast::Comment],
+    ) -> HangDoc<'doc> {
         let arena = self.arena;
         HangDoc {
             affixes: vec![affixes(
-                docs![arena, self.format_comments(&n.lbrace), "{"],
+                docs![arena, self.format_comments(lbrace), "{"],
                 docs![arena, arena.hardline(), "}"],
             )
             .nest()],
@@ -570,8 +707,8 @@ impl<'doc> Formatter<'doc> {
                 arena,
                 arena.hardline(),
                 // format the block statements
-                self.format_statement_list(&n.body),
-                self.format_comments(&n.rbrace),
+                self.format_statement_list(body),
+                self.format_end_comments(body, rbrace),
             ],
         }
     }
@@ -581,27 +718,48 @@ impl<'doc> Formatter<'doc> {
 
         let mut prev: i8 = -1;
         let mut previous_location: i32 = -1;
-        arena.intersperse(
-            s.iter().enumerate().map(|(i, stmt)| {
-                let mut extra_line = arena.nil();
-
-                let cur = stmt.typ();
-                if i != 0 {
+        arena.concat(s.iter().enumerate().map(|(i, stmt)| {
+            let mut separator = arena.nil();
+
+            let cur = stmt.typ();
+            if i != 0 {
+                let comments = leading_comments(Node::from_stmt(stmt));
+                if trailing_comment(comments) {
+                    // keep the comment on the line of the previous statement
+                    separator = arena.text(" ");
+                } else {
+                    separator = arena.hardline();
                     let current_location: i32 = stmt.base().location.start.line as i32;
                     //compare the line position of adjacent lines to preserve formatted double new lines
                     let line_gap = current_location - previous_location;
                     // separate different statements with double newline or statements with comments
-                    if line_gap > 1 || cur != prev || starts_with_comment(Node::from_stmt(stmt)) {
-                        extra_line = arena.hardline();
+                    if line_gap > 1 || cur != prev || !comments.is_empty() {
+                        separator = separator.append(arena.hardline());
                     }
                 }
-                previous_location = stmt.base().location.end.line as i32;
-                prev = cur;
+            }
+            previous_location = stmt.base().location.end.line as i32;
+            prev = cur;
 
-                extra_line.append(self.format_statement(stmt))
-            }),
-            arena.hardline(),
-        )
+            separator.append(self.format_statement(stmt))
+        }))
+    }
+
+    // format_end_comments formats the comments that follow the last statement of a list.
+    fn format_end_comments(
+        &mut self,
+        statements: &'doc [Statement],
+        comments: &'doc [ast::Comment],
+    ) -> Doc<'doc> {
+        let arena = self.arena;
+        let separator = if statements.is_empty() || comments.is_empty() {
+            arena.nil()
+        } else if trailing_comment(comments) {
+            arena.text(" ")
+        } else {
+            arena.hardline()
+        };
+        separator.append(self.format_comments(comments))
     }
 
     fn format_assignment(&mut self, n: &'doc ast::Assignment) -> Doc<'doc> {
@@ -622,7 +780,7 @@ impl<'doc> Formatter<'doc> {
                     )
                     .nest(),
                 );
-                hang_doc.format()
+                hang_doc.format(self.indent)
             }
             ast::Assignment::Member(n) => {
                 let mut hang_doc = self.hang_expression(&n.init);
@@ -639,7 +797,7 @@ impl<'doc> Formatter<'doc> {
                     )
                     .nest(),
                 );
-                hang_doc.format()
+                hang_doc.format(self.indent)
             }
         }
     }
@@ -649,6 +807,7 @@ impl<'doc> Formatter<'doc> {
         match s {
             Statement::Expr(s) => self.format_expression(&s.expression),
             Statement::Variable(s) => self.format_variable_assignment(s),
+            Statement::Destructuring(s) => self.format_destructuring_assignment(s),
             Statement::Option(s) => {
                 docs![
                     arena,
@@ -662,7 +821,7 @@ impl<'doc> Formatter<'doc> {
                 let mut hang_doc = self.hang_expression(&s.argument);
                 hang_doc.add_prefix(arena.line());
                 hang_doc.affixes.push(affixes(prefix, arena.nil()).nest());
-                hang_doc.format()
+                hang_doc.format(self.indent)
             }
             Statement::Bad(s) => {
                 self.err = Some(anyhow!("bad statement"));
@@ -697,7 +856,7 @@ impl<'doc> Formatter<'doc> {
                     // Do not put the leading comment into the hang_doc so that
                     // the comment size doesn't affect the hang layout.
                     comment,
-                    hang_doc.format(),
+                    hang_doc.format(self.indent),
                 ]
             }
             Statement::Builtin(n) => docs![
@@ -721,9 +880,42 @@ impl<'doc> Formatter<'doc> {
                     ": ",
                     self.format_type_expression(&n.ty),
                 ]
-                .nest(INDENT)
+                .nest(self.indent)
                 .group()
             ],
+            Statement::TypeAlias(n) => docs![
+                arena,
+                self.format_comments(&n.base.comments),
+                docs![
+                    arena,
+                    "type ",
+                    self.format_identifier(&n.id),
+                    " = ",
+                    if n.params.is_empty() {
+                        arena.nil()
+                    } else {
+                        docs![
+                            arena,
+                            "(",
+                            comma_list_without_trailing_comma(
+                                arena,
+                                n.params.iter().map(|p| self.format_identifier(p)),
+                                arena.text(" "),
+                            ),
+                            ") => ",
+                        ]
+                    },
+                    self.format_monotype(&n.ty),
+                ]
+                .nest(self.indent)
+                .group()
+            ],
+            Statement::Export(n) => docs![
+                arena,
+                self.format_comments(&n.base.comments),
+                "export ",
+                self.format_statement(&n.statement),
+            ],
         }
         .group()
     }
@@ -809,7 +1001,7 @@ impl<'doc> Formatter<'doc> {
         child: ChildNode<'doc>,
     ) -> Doc<'doc> {
         let (pvp, pvc) = get_precedences(&parent, &child.as_node());
-        if needs_parenthesis(pvp, pvc, true) {
+        if needs_parenthesis(pvp, pvc, true) && !(pvp == pvc && is_right_associative(&parent)) {
             self.format_node_with_parens(child)
         } else {
             self.format_childnode(child)
@@ -823,7 +1015,9 @@ impl<'doc> Formatter<'doc> {
         child: ChildNode<'doc>,
     ) -> Doc<'doc> {
         let (pvp, pvc) = get_precedences(&parent, &child.as_node());
-        if needs_parenthesis(pvp, pvc, false) {
+        if needs_parenthesis(pvp, pvc, false)
+            || (pvp != 0 && pvp == pvc && is_right_associative(&child.as_node()))
+        {
             self.format_node_with_parens(child)
         } else {
             self.format_childnode(child)
@@ -879,7 +1073,7 @@ impl<'doc> Formatter<'doc> {
                     line
                 ),
             ]
-            .nest(INDENT),
+            .nest(self.indent),
             line_,
             self.format_comments(&n.rparen),
             ")",
@@ -946,7 +1140,7 @@ impl<'doc> Formatter<'doc> {
             let mut hang_doc = self.hang_expression(v);
             hang_doc.add_prefix(arena.line());
             hang_doc.affixes.push(affixes(prefix, arena.nil()).nest());
-            hang_doc.format()
+            hang_doc.format(self.indent)
         } else {
             self.format_property_key(&n.key)
         }
@@ -964,7 +1158,7 @@ impl<'doc> Formatter<'doc> {
 
             let mut hang_doc = self.hang_expression(v);
             hang_doc.affixes.push(affixes(prefix, arena.nil()).nest());
-            hang_doc.format()
+            hang_doc.format(self.indent)
         } else {
             self.format_property_key(&n.key)
         }
@@ -974,12 +1168,23 @@ impl<'doc> Formatter<'doc> {
         match n {
             ast::PropertyKey::StringLit(m) => self.format_string_literal(m),
             ast::PropertyKey::Identifier(m) => self.format_identifier(m),
+            ast::PropertyKey::Spread(m) => self.format_spread_expression(m),
         }
     }
 
+    fn format_spread_expression(&mut self, n: &'doc ast::SpreadExpr) -> Doc<'doc> {
+        let arena = self.arena;
+        docs![
+            arena,
+            self.format_comments(&n.base.comments),
+            "...",
+            self.format_expression(&n.argument),
+        ]
+    }
+
     fn format_string_literal(&mut self, n: &'doc ast::StringLit) -> Doc<'doc> {
         let hang_doc = self.hang_string_literal(n);
-        hang_doc.format()
+        hang_doc.format(self.indent)
     }
 
     fn hang_string_literal(&mut self, n: &'doc ast::StringLit) -> HangDoc<'doc> {
@@ -987,12 +1192,12 @@ impl<'doc> Formatter<'doc> {
 
         let doc = self.format_comments(&n.base.comments);
 
-        if let Some(src) = &n.base.location.source {
+        if let (Some(src), StringStyle::Preserve) = (&n.base.location.source, self.string_style) {
             if !src.is_empty() {
                 // Preserve the exact literal if we have it
                 return HangDoc {
                     affixes: Vec::new(),
-                    body: docs![arena, doc, src],
+                    body: docs![arena, doc, self.format_verbatim(src.into())],
                 };
             }
         }
@@ -1003,11 +1208,21 @@ impl<'doc> Formatter<'doc> {
             body: docs![
                 arena,
                 // Write out escaped string value
-                escaped_string,
+                self.format_verbatim(escaped_string.into()),
             ],
         }
     }
 
+    // Marks the lines of a multiline string as verbatim when indenting with tabs
+    fn format_verbatim(&self, text: Cow<'doc, str>) -> Doc<'doc> {
+        if self.use_tabs && text.contains('\n') {
+            self.arena
+                .text(text.replace('\n', &format!("\n{}", VERBATIM_LINE)))
+        } else {
+            self.arena.text(text)
+        }
+    }
+
     fn format_identifier(&mut self, id: &'doc ast::Identifier) -> Doc<'doc> {
         let (x, y) = self.format_split_identifier(id);
         x.append(y)
@@ -1023,16 +1238,50 @@ impl<'doc> Formatter<'doc> {
     fn format_variable_assignment(&mut self, n: &'doc ast::VariableAssgn) -> Doc<'doc> {
         let arena = self.arena;
         let (comment, id) = self.format_split_identifier(&n.id);
+        let annotation = match &n.ty {
+            Some(ty) => docs![
+                arena,
+                if n.colon.is_empty() {
+                    arena.text(" ")
+                } else {
+                    arena.line()
+                },
+                self.format_comments(&n.colon),
+                ": ",
+                self.format_type_expression(ty),
+            ],
+            None => arena.nil(),
+        };
         let prefix = docs![
             arena,
             id,
+            annotation,
+            self.format_append_comments(&n.base.comments),
+            " =",
+        ];
+        let mut hang_doc = self.hang_expression(&n.init);
+        hang_doc.add_prefix(arena.line());
+        hang_doc.affixes.push(affixes(prefix, arena.nil()).nest());
+        docs![arena, comment, hang_doc.format(self.indent)]
+    }
+
+    fn format_destructuring_assignment(&mut self, n: &'doc ast::DestructuringAssgn) -> Doc<'doc> {
+        let arena = self.arena;
+        let (prefix, body, suffix) = self.format_record_expression_braces(&n.pattern, true);
+        let pattern = HangDoc {
+            affixes: vec![affixes(prefix, suffix).nest()],
+            body,
+        };
+        let prefix = docs![
+            arena,
+            pattern.format(self.indent),
             self.format_append_comments(&n.base.comments),
             " =",
         ];
         let mut hang_doc = self.hang_expression(&n.init);
         hang_doc.add_prefix(arena.line());
         hang_doc.affixes.push(affixes(prefix, arena.nil()).nest());
-        docs![arena, comment, hang_doc.format()]
+        hang_doc.format(self.indent)
     }
 
     fn format_date_time_literal(&mut self, n: &'doc ast::DateTimeLit) -> Doc<'doc> {
@@ -1077,7 +1326,7 @@ impl<'doc> Formatter<'doc> {
                     docs![
                         arena,
                         self.format_append_comments(&n.lbrack),
-                        ".",
+                        if n.optional { "?." } else { "." },
                         self.format_identifier(m),
                     ]
                 }
@@ -1091,13 +1340,68 @@ impl<'doc> Formatter<'doc> {
                         "]",
                     ]
                 }
+                // The parser never produces a spread as the property of a member expression
+                ast::PropertyKey::Spread(m) => self.format_spread_expression(m),
             }
         ]
     }
 
+    // The arms of a match expression are always written one per line
+    fn format_match_expression(&mut self, n: &'doc ast::MatchExpr) -> HangDoc<'doc> {
+        let arena = self.arena;
+        let arms = arena.concat(n.arms.iter().map(|arm| {
+            docs![
+                arena,
+                arena.hardline(),
+                self.format_expression(&arm.pattern),
+                self.format_append_comments(&arm.arrow),
+                " => ",
+                self.format_expression(&arm.body).nest(self.indent),
+                self.format_append_comments(&arm.comma),
+                ",",
+            ]
+        }));
+        let rbrace = if n.rbrace.is_empty() {
+            arena.nil()
+        } else {
+            docs![arena, arena.hardline(), self.format_comments(&n.rbrace)]
+        };
+        HangDoc {
+            affixes: vec![affixes(
+                docs![
+                    arena,
+                    self.format_comments(&n.base.comments),
+                    "match ",
+                    self.format_expression(&n.scrutinee).nest(self.indent),
+                    " ",
+                    self.format_comments(&n.lbrace),
+                    "{",
+                ],
+                docs![arena, arena.hardline(), "}"],
+            )
+            .nest()],
+            body: docs![arena, arms, rbrace],
+        }
+    }
+
+    fn format_tuple_index_expression(&mut self, n: &'doc ast::TupleIndexExpr) -> Doc<'doc> {
+        let arena = self.arena;
+        docs![
+            arena,
+            self.format_child_with_parens(Node::TupleIndexExpr(n), ChildNode::Expr(&n.tuple)),
+            self.format_append_comments(&n.dot),
+            ".",
+            n.index.value.to_string(),
+        ]
+    }
+
     fn hang_expression(&mut self, expr: &'doc ast::Expression) -> HangDoc<'doc> {
         let arena = self.arena;
         match expr {
+            ast::Expression::Block(n) => {
+                self.format_braced_statements(&n.lbrace, &n.body, &n.rbrace)
+            }
+            ast::Expression::Match(n) => self.format_match_expression(n),
             ast::Expression::Array(n) => {
                 let (prefix, body, suffix) = format_item_list(
                     arena,
@@ -1119,6 +1423,35 @@ impl<'doc> Formatter<'doc> {
                     body,
                 }
             }
+            ast::Expression::Tuple(n) => {
+                // A single element tuple always needs its comma, otherwise it would be read back
+                // as a parenthesized expression. `comma_list_with` only adds it when broken.
+                let single = n.elements.len() == 1;
+                let (prefix, body, suffix) = format_item_list(
+                    arena,
+                    ("(", ")"),
+                    self.format_comments(&n.rparen),
+                    n.elements.iter().map(|item| {
+                        docs![
+                            arena,
+                            self.format_expression(&item.expression),
+                            if single {
+                                arena.nil().flat_alt(arena.text(","))
+                            } else {
+                                arena.nil()
+                            },
+                            self.format_comments(&item.comma),
+                        ]
+                    }),
+                );
+                HangDoc {
+                    affixes: vec![
+                        affixes(prefix, suffix),
+                        affixes(self.format_comments(&n.lparen), arena.nil()).nest(),
+                    ],
+                    body,
+                }
+            }
 
             ast::Expression::Object(expr) => {
                 let (prefix, body, suffix) = self.format_record_expression_braces(expr, true);
@@ -1180,13 +1513,16 @@ impl<'doc> Formatter<'doc> {
         let parent = expr;
         match expr {
             ast::Expression::Array(_)
+            | ast::Expression::Tuple(_)
+            | ast::Expression::Block(_)
+            | ast::Expression::Match(_)
             | ast::Expression::Object(_)
             | ast::Expression::StringExpr(_)
             | ast::Expression::Function(_)
             | ast::Expression::StringLit(_)
             | ast::Expression::Index(_) => {
                 let hang_doc = self.hang_expression(expr);
-                hang_doc.format()
+                hang_doc.format(self.indent)
             }
             ast::Expression::Identifier(expr) => self.format_identifier(expr),
             ast::Expression::Dict(n) => {
@@ -1218,7 +1554,7 @@ impl<'doc> Formatter<'doc> {
                         },
                         self.format_comments(&n.rbrack),
                     ]
-                    .nest(INDENT),
+                    .nest(self.indent),
                     arena.line_(),
                     "]",
                 ]
@@ -1230,6 +1566,7 @@ impl<'doc> Formatter<'doc> {
                 &expr.right,
             ),
             ast::Expression::Member(n) => self.format_member_expression(n),
+            ast::Expression::TupleIndex(n) => self.format_tuple_index_expression(n),
             ast::Expression::Binary(expr) => self.format_binary_expression(
                 parent,
                 &expr.left,
@@ -1261,7 +1598,7 @@ impl<'doc> Formatter<'doc> {
                     docs![
                         arena,
                         "if ",
-                        self.format_expression(&n.test).nest(INDENT),
+                        self.format_expression(&n.test).nest(self.indent),
                         self.format_comments(&n.tk_then),
                         if n.tk_then.is_empty() {
                             arena.line()
@@ -1271,7 +1608,8 @@ impl<'doc> Formatter<'doc> {
                         "then",
                     ]
                     .group(),
-                    docs![arena, line.clone(), self.format_expression(&n.consequent)].nest(INDENT),
+                    docs![arena, line.clone(), self.format_expression(&n.consequent)]
+                        .nest(self.indent),
                     line.clone(),
                     self.format_comments(&n.tk_else),
                 ];
@@ -1283,11 +1621,11 @@ impl<'doc> Formatter<'doc> {
                                 doc,
                                 self.format_comments(&n.tk_if),
                                 "else if ",
-                                self.format_expression(&n.test).nest(INDENT),
+                                self.format_expression(&n.test).nest(self.indent),
                                 self.format_comments(&n.tk_then),
                                 " then",
                                 docs![arena, line.clone(), self.format_expression(&n.consequent)]
-                                    .nest(INDENT),
+                                    .nest(self.indent),
                                 line.clone(),
                                 self.format_comments(&n.tk_else),
                             ];
@@ -1298,7 +1636,8 @@ impl<'doc> Formatter<'doc> {
                                 arena,
                                 doc,
                                 "else",
-                                docs![arena, line, self.format_expression(alternate)].nest(INDENT),
+                                docs![arena, line, self.format_expression(alternate)]
+                                    .nest(self.indent),
                             ];
                             break;
                         }
@@ -1306,31 +1645,73 @@ impl<'doc> Formatter<'doc> {
                 }
                 docs![arena, self.format_comments(&n.tk_if), doc.group()]
             }
+            ast::Expression::Ternary(n) => {
+                let line = self.base_multiline(&n.base);
+                docs![
+                    arena,
+                    self.format_left_child_with_parens(
+                        Node::TernaryExpr(n),
+                        ChildNode::Expr(&n.test)
+                    ),
+                    docs![
+                        arena,
+                        line.clone(),
+                        self.format_comments(&n.tk_question),
+                        "? ",
+                        self.format_expression(&n.consequent),
+                        line,
+                        self.format_comments(&n.tk_colon),
+                        ": ",
+                        self.format_expression(&n.alternate),
+                    ]
+                    .nest(self.indent),
+                ]
+                .group()
+            }
             ast::Expression::Integer(expr) => {
                 docs![
                     arena,
                     self.format_comments(&expr.base.comments),
-                    format!("{}", expr.value),
+                    // Preserve the radix and digit grouping the literal was written with
+                    match &expr.base.location.source {
+                        Some(src) if strconv::parse_int(src).ok() == Some(expr.value) => {
+                            arena.text(src.as_str())
+                        }
+                        _ => arena.text(format!("{}", expr.value)),
+                    }
                 ]
             }
             ast::Expression::Float(expr) => {
-                docs![arena, self.format_comments(&expr.base.comments), {
-                    let mut s = format!("{}", expr.value);
-                    if !s.contains('.') {
-                        s.push_str(".0");
+                docs![
+                    arena,
+                    self.format_comments(&expr.base.comments),
+                    match digit_grouped_source(&expr.base, |lit| lit.parse::<f64>()
+                        == Ok(expr.value))
+                    {
+                        Some(src) => arena.text(src),
+                        None => {
+                            let mut s = format!("{}", expr.value);
+                            if !s.contains('.') {
+                                s.push_str(".0");
+                            }
+                            arena.text(s)
+                        }
                     }
-                    s
-                }]
+                ]
             }
             ast::Expression::Duration(n) => {
+                let values = n
+                    .values
+                    .iter()
+                    .map(|d| format!("{}{}", d.magnitude, d.unit))
+                    .collect::<String>();
                 docs![
                     arena,
                     self.format_comments(&n.base.comments),
-                    arena.concat(
-                        n.values
-                            .iter()
-                            .map(|d| { docs![arena, format!("{}", d.magnitude), &d.unit,] })
-                    )
+                    match digit_grouped_source(&n.base, |lit| lit == values) {
+                        Some(src) => arena.text(src),
+                        None => arena.text(values),
+                    }
                 ]
             }
             ast::Expression::Uint(n) => {
@@ -1349,6 +1730,7 @@ impl<'doc> Formatter<'doc> {
             ast::Expression::PipeLit(expr) => {
                 docs![arena, self.format_comments(&expr.base.comments), "<-"]
             }
+            ast::Expression::Spread(expr) => self.format_spread_expression(expr),
             ast::Expression::Bad(expr) => {
                 self.err = Some(anyhow!("bad expression"));
                 arena.nil()
@@ -1375,11 +1757,16 @@ impl<'doc> Formatter<'doc> {
 
     fn format_text_part(&mut self, n: &'doc ast::TextPart) -> Doc<'doc> {
         let arena = self.arena;
+        let use_tabs = self.use_tabs;
 
         arena.intersperse(
-            n.value.split('\n').map(|s| {
+            n.value.split('\n').enumerate().map(move |(i, s)| {
                 let escaped_string = escape_string(s);
-                arena.text(escaped_string)
+                if use_tabs && i > 0 {
+                    arena.text(format!("{}{}", VERBATIM_LINE, escaped_string))
+                } else {
+                    arena.text(escaped_string)
+                }
             }),
             arena.nesting(move |indentation| {
                 arena.hardline().nest(-(indentation as isize)).into_doc()
@@ -1413,7 +1800,7 @@ impl<'doc> Formatter<'doc> {
                         doc += line.clone();
                     }
                 }
-                _ => return docs![arena, first, doc.nest(INDENT)].group(),
+                _ => return docs![arena, first, doc.nest(self.indent)].group(),
             }
         }
     }
@@ -1476,7 +1863,12 @@ impl<'doc> Formatter<'doc> {
                     docs![
                         arena,
                         "(",
-                        format_hang_doc(arena, &[affixes(prefix, suffix).nest()], body),
+                        format_hang_doc(
+                            arena,
+                            self.indent,
+                            &[affixes(prefix, suffix).nest()],
+                            body
+                        ),
                         self.format_comments(&n.rparen),
                         ")"
                     ]
@@ -1488,7 +1880,7 @@ impl<'doc> Formatter<'doc> {
                         self.format_comments(&n.rparen),
                         n.arguments.iter().map(|c| self.format_expression(c)),
                     );
-                    format_hang_doc(arena, &[affixes(prefix, suffix).nest()], body)
+                    format_hang_doc(arena, self.indent, &[affixes(prefix, suffix).nest()], body)
                 }
             },
         ]
@@ -1550,7 +1942,7 @@ impl<'doc> Formatter<'doc> {
                 }
             }
         }
-        docs![arena, l, doc.nest(INDENT)].group()
+        docs![arena, l, doc.nest(self.indent)].group()
     }
 
     fn format_regexp_literal(&mut self, n: &'doc ast::RegexpLit) -> Doc<'doc> {
@@ -1565,13 +1957,29 @@ impl<'doc> Formatter<'doc> {
     }
 }
 
+// Returns the source of a numeric literal if it uses digit separators and still spells the
+// literal's value, so that the grouping the user chose is preserved.
+fn digit_grouped_source<'a>(
+    base: &'a ast::BaseNode,
+    spells_value: impl FnOnce(&str) -> bool,
+) -> Option<&'a str> {
+    let src = base.location.source.as_deref()?;
+    if src.contains('_') && spells_value(&src.replace('_', "")) {
+        Some(src)
+    } else {
+        None
+    }
+}
+
 fn escape_string(s: &str) -> String {
-    if !(s.contains('\"') || s.contains('\\')) {
+    if !(s.contains('\"') || s.contains('\\') || s.contains("${")) {
         return s.to_string();
     }
     let mut escaped = String::with_capacity(s.len() * 2);
-    for r in s.chars() {
-        if r == '"' || r == '\\' {
+    let mut chars = s.chars().peekable();
+    while let Some(r) = chars.next() {
+        // `${` would start an interpolation
+        if r == '"' || r == '\\' || (r == '$' && chars.peek() == Some(&'{')) {
             escaped.push('\\')
         }
         escaped.push(r)
@@ -1593,10 +2001,6 @@ impl<'doc> ChildNode<'doc> {
     }
 }
 
-// INDENT_BYTES is 4 spaces as a constant byte slice
-const INDENT_BYTES: &str = "    ";
-const INDENT: isize = INDENT_BYTES.len() as isize;
-
 fn get_precedences(parent: &Node, child: &Node) -> (u32, u32) {
     let pvp: u32 = match parent {
         Node::BinaryExpr(p) => Operator::new(&p.operator).get_precedence(),
@@ -1607,8 +2011,10 @@ fn get_precedences(parent: &Node, child: &Node) -> (u32, u32) {
         Node::CallExpr(_) => 1,
         Node::MemberExpr(_) => 1,
         Node::IndexExpr(_) => 1,
+        Node::TupleIndexExpr(_) => 1,
         Node::ParenExpr(p) => return get_precedences(&(Node::from_expr(&p.expression)), child),
-        Node::ConditionalExpr(_) => 11,
+        Node::ConditionalExpr(_) => 12,
+        Node::TernaryExpr(_) => 12,
         _ => 0,
     };
 
@@ -1621,8 +2027,10 @@ fn get_precedences(parent: &Node, child: &Node) -> (u32, u32) {
         Node::CallExpr(_) => 1,
         Node::MemberExpr(_) => 1,
         Node::IndexExpr(_) => 1,
+        Node::TupleIndexExpr(_) => 1,
         Node::ParenExpr(p) => return get_precedences(parent, &(Node::from_expr(&p.expression))),
-        Node::ConditionalExpr(_) => 11,
+        Node::ConditionalExpr(_) => 12,
+        Node::TernaryExpr(_) => 12,
         _ => 0,
     };
 
@@ -1656,6 +2064,7 @@ impl<'a> Operator<'a> {
         if !self.is_logical {
             return match self.op.unwrap() {
                 ast::Operator::PowerOperator => 4,
+                ast::Operator::ExponentiationOperator => 4,
                 ast::Operator::MultiplicationOperator => 5,
                 ast::Operator::DivisionOperator => 5,
                 ast::Operator::ModuloOperator => 5,
@@ -1675,6 +2084,7 @@ impl<'a> Operator<'a> {
                 ast::Operator::NotRegexpMatchOperator => 7,
                 ast::Operator::NotOperator => 8,
                 ast::Operator::ExistsOperator => 8,
+                ast::Operator::NullCoalescingOperator => 11,
                 ast::Operator::InvalidOperator => 0,
             };
         }
@@ -1708,6 +2118,19 @@ fn needs_parenthesis(pvp: u32, pvc: u32, is_right: bool) -> bool {
     par && ((!is_right && pvc > pvp) || (is_right && pvc >= pvp))
 }
 
+// is_right_associative reports whether the node is a binary expression whose operator groups to
+// the right, in which case the rules above are mirrored for children of the same precedence.
+// Conditional and ternary expressions extend as far to the right as they can, so they too need
+// parens to be the test of a ternary expression.
+fn is_right_associative(node: &Node) -> bool {
+    match node {
+        Node::BinaryExpr(n) => n.operator == ast::Operator::ExponentiationOperator,
+        Node::ConditionalExpr(_) | Node::TernaryExpr(_) => true,
+        Node::ParenExpr(p) => is_right_associative(&Node::from_expr(&p.expression)),
+        _ => false,
+    }
+}
+
 // has_parens reports whether the node will be formatted with parens.
 //
 // Only format parens if they have associated comments.
@@ -1731,68 +2154,87 @@ fn strip_parens(n: &ast::Expression) -> &ast::Expression {
     n
 }
 
+// trailing_comment reports if the first comment started on the line of the preceding token.
+fn trailing_comment(comments: &[ast::Comment]) -> bool {
+    comments.first().map_or(false, |c| c.trailing)
+}
+
 // starts_with_comment reports if the node has a comment that it would format before anything else as part
 // of the node.
 fn starts_with_comment(n: Node) -> bool {
+    !leading_comments(n).is_empty()
+}
+
+// leading_comments returns the comments that the node would format before anything else.
+fn leading_comments<'a>(n: Node<'a>) -> &'a [ast::Comment] {
     match n {
-        Node::Package(n) => !n.base.comments.is_empty(),
+        Node::Package(n) => &n.base.comments,
         Node::File(n) => {
             if let Some(pkg) = &n.package {
-                return starts_with_comment(Node::PackageClause(pkg));
+                return leading_comments(Node::PackageClause(pkg));
             }
-            if let Some(imp) = &n.imports.first() {
-                return starts_with_comment(Node::ImportDeclaration(imp));
+            if let Some(imp) = n.imports.first() {
+                return leading_comments(Node::ImportDeclaration(imp));
             }
-            if let Some(stmt) = &n.body.first() {
-                return starts_with_comment(Node::from_stmt(stmt));
+            if let Some(stmt) = n.body.first() {
+                return leading_comments(Node::from_stmt(stmt));
             }
-            !n.eof.is_empty()
+            &n.eof
         }
-        Node::PackageClause(n) => !n.base.comments.is_empty(),
-        Node::ImportDeclaration(n) => !n.base.comments.is_empty(),
-        Node::Identifier(n) => !n.base.comments.is_empty(),
-        Node::ArrayExpr(n) => !n.lbrack.is_empty(),
-        Node::DictExpr(n) => !n.lbrack.is_empty(),
-        Node::FunctionExpr(n) => !n.lparen.is_empty(),
-        Node::LogicalExpr(n) => starts_with_comment(Node::from_expr(&n.left)),
-        Node::ObjectExpr(n) => !n.lbrace.is_empty(),
-        Node::MemberExpr(n) => starts_with_comment(Node::from_expr(&n.object)),
-        Node::IndexExpr(n) => starts_with_comment(Node::from_expr(&n.array)),
-        Node::BinaryExpr(n) => starts_with_comment(Node::from_expr(&n.left)),
-        Node::UnaryExpr(n) => !n.base.comments.is_empty(),
-        Node::PipeExpr(n) => starts_with_comment(Node::from_expr(&n.argument)),
-        Node::CallExpr(n) => starts_with_comment(Node::from_expr(&n.callee)),
-        Node::ConditionalExpr(n) => !n.tk_if.is_empty(),
-        Node::StringExpr(n) => !n.base.comments.is_empty(),
-        Node::ParenExpr(n) => !n.lparen.is_empty(),
-        Node::IntegerLit(n) => !n.base.comments.is_empty(),
-        Node::FloatLit(n) => !n.base.comments.is_empty(),
-        Node::StringLit(n) => !n.base.comments.is_empty(),
-        Node::DurationLit(n) => !n.base.comments.is_empty(),
-        Node::UintLit(n) => !n.base.comments.is_empty(),
-        Node::BooleanLit(n) => !n.base.comments.is_empty(),
-        Node::DateTimeLit(n) => !n.base.comments.is_empty(),
-        Node::RegexpLit(n) => !n.base.comments.is_empty(),
-        Node::PipeLit(n) => !n.base.comments.is_empty(),
-        Node::BadExpr(_) => false,
-        Node::ExprStmt(n) => starts_with_comment(Node::from_expr(&n.expression)),
-        Node::OptionStmt(n) => !n.base.comments.is_empty(),
-        Node::ReturnStmt(n) => !n.base.comments.is_empty(),
-        Node::BadStmt(_) => false,
-        Node::TestStmt(n) => !n.base.comments.is_empty(),
-        Node::TestCaseStmt(n) => !n.base.comments.is_empty(),
-        Node::BuiltinStmt(n) => !n.base.comments.is_empty(),
-        Node::Block(n) => !n.lbrace.is_empty(),
-        Node::Property(_) => false,
-        Node::TextPart(_) => false,
-        Node::InterpolatedPart(_) => false,
-        Node::VariableAssgn(n) => starts_with_comment(Node::Identifier(&n.id)),
-        Node::MemberAssgn(n) => starts_with_comment(Node::MemberExpr(&n.member)),
-        Node::TypeExpression(n) => !n.base.comments.is_empty(),
-        Node::MonoType(n) => !n.base().comments.is_empty(),
-        Node::ParameterType(n) => !n.base().comments.is_empty(),
-        Node::PropertyType(n) => !n.base.comments.is_empty(),
-        Node::TypeConstraint(n) => !n.base.comments.is_empty(),
+        Node::PackageClause(n) => &n.base.comments,
+        Node::ImportDeclaration(n) => &n.base.comments,
+        Node::Identifier(n) => &n.base.comments,
+        Node::ArrayExpr(n) => &n.lbrack,
+        Node::DictExpr(n) => &n.lbrack,
+        Node::FunctionExpr(n) => &n.lparen,
+        Node::LogicalExpr(n) => leading_comments(Node::from_expr(&n.left)),
+        Node::ObjectExpr(n) => &n.lbrace,
+        Node::MemberExpr(n) => leading_comments(Node::from_expr(&n.object)),
+        Node::IndexExpr(n) => leading_comments(Node::from_expr(&n.array)),
+        Node::BinaryExpr(n) => leading_comments(Node::from_expr(&n.left)),
+        Node::UnaryExpr(n) => &n.base.comments,
+        Node::PipeExpr(n) => leading_comments(Node::from_expr(&n.argument)),
+        Node::CallExpr(n) => leading_comments(Node::from_expr(&n.callee)),
+        Node::ConditionalExpr(n) => &n.tk_if,
+        Node::TernaryExpr(n) => leading_comments(Node::from_expr(&n.test)),
+        Node::StringExpr(n) => &n.base.comments,
+        Node::ParenExpr(n) => &n.lparen,
+        Node::TupleExpr(n) => &n.lparen,
+        Node::TupleIndexExpr(n) => leading_comments(Node::from_expr(&n.tuple)),
+        Node::BlockExpr(n) => &n.lbrace,
+        Node::MatchExpr(n) => &n.base.comments,
+        Node::SpreadExpr(n) => &n.base.comments,
+        Node::IntegerLit(n) => &n.base.comments,
+        Node::FloatLit(n) => &n.base.comments,
+        Node::StringLit(n) => &n.base.comments,
+        Node::DurationLit(n) => &n.base.comments,
+        Node::UintLit(n) => &n.base.comments,
+        Node::BooleanLit(n) => &n.base.comments,
+        Node::DateTimeLit(n) => &n.base.comments,
+        Node::RegexpLit(n) => &n.base.comments,
+        Node::PipeLit(n) => &n.base.comments,
+        Node::BadExpr(_) => &[],
+        Node::ExprStmt(n) => leading_comments(Node::from_expr(&n.expression)),
+        Node::OptionStmt(n) => &n.base.comments,
+        Node::ReturnStmt(n) => &n.base.comments,
+        Node::BadStmt(_) => &[],
+        Node::TestStmt(n) => &n.base.comments,
+        Node::TestCaseStmt(n) => &n.base.comments,
+        Node::BuiltinStmt(n) => &n.base.comments,
+        Node::TypeAliasStmt(n) => &n.base.comments,
+        Node::Block(n) => &n.lbrace,
+        Node::Property(_) => &[],
+        Node::TextPart(_) => &[],
+        Node::InterpolatedPart(_) => &[],
+        Node::VariableAssgn(n) => leading_comments(Node::Identifier(&n.id)),
+        Node::MemberAssgn(n) => leading_comments(Node::MemberExpr(&n.member)),
+        Node::DestructuringAssgn(n) => leading_comments(Node::ObjectExpr(&n.pattern)),
+        Node::ExportStmt(n) => &n.base.comments,
+        Node::TypeExpression(n) => &n.base.comments,
+        Node::MonoType(n) => &n.base().comments,
+        Node::ParameterType(n) => &n.base().comments,
+        Node::PropertyType(n) => &n.base.comments,
+        Node::TypeConstraint(n) => &n.base.comments,
     }
 }
 
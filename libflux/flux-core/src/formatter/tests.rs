@@ -43,6 +43,42 @@ fn binary_op() {
     assert_unchanged("1 * (1 / 2)");
 }
 
+#[test]
+fn null_coalescing_op() {
+    assert_unchanged("a ?? b");
+    assert_unchanged("a ?? b ?? c");
+    assert_unchanged("a ?? b or c");
+    assert_unchanged("(a ?? b) or c");
+    assert_unchanged("(if a then b else c) ?? d");
+    assert_format("a??b", "a ?? b");
+}
+
+#[test]
+fn ternary_op() {
+    assert_unchanged("a ? b : c");
+    assert_unchanged("x = a == 1 ? b : c ? d : e");
+    assert_unchanged("(a ? b : c) ? d : e");
+    assert_unchanged("(if a then b else c) ? d : e");
+    assert_unchanged("(a ? b : c) + 1");
+    assert_unchanged("a ?? b ? c : d");
+    assert_format("a?b:c", "a ? b : c");
+}
+
+#[test]
+fn spreads() {
+    assert_unchanged("{...r, a: 1, ...s}");
+    assert_unchanged("{r with a: 1, ...s}");
+    assert_unchanged("[...xs, 4, ...f(x: 1)]");
+    assert_format("[ ...xs,4 ]", "[...xs, 4]");
+}
+
+#[test]
+fn guarded_access() {
+    assert_unchanged("r?.a");
+    assert_unchanged("r?.a?.b.c");
+    assert_format("r ?.a", "r?.a");
+}
+
 #[test]
 fn funcs() {
     assert_format(
@@ -80,6 +116,134 @@ fn funcs() {
     );
 }
 
+#[track_caller]
+fn assert_unchanged_with_config(script: &str, config: FormatterConfig) {
+    let _ = env_logger::try_init();
+    let output = format_with_config(script, &config).unwrap();
+    assert_eq!(
+        script, output,
+        "\n EXPECTED: \n {} \n OUTPUT: \n {} \n",
+        script, output
+    );
+}
+
+#[test]
+fn configured_indentation() {
+    let two_spaces = FormatterConfig {
+        indent: 2,
+        ..FormatterConfig::default()
+    };
+    assert_unchanged_with_config(
+        r#"(tables) =>
+  tables
+    |> a()
+    |> b()"#,
+        two_spaces.clone(),
+    );
+    assert_unchanged_with_config(
+        r#"f = () => {
+  x = 1
+
+  return x
+}"#,
+        two_spaces.clone(),
+    );
+    assert_eq!(
+        format_with_config("f = () => {\n    return 1\n}", &two_spaces).unwrap(),
+        "f = () => {\n  return 1\n}"
+    );
+
+    let tabs = FormatterConfig {
+        use_tabs: true,
+        ..FormatterConfig::default()
+    };
+    assert_unchanged_with_config(
+        "(tables) =>\n\ttables\n\t\t|> a()\n\t\t|> b()",
+        tabs.clone(),
+    );
+    // Whitespace inside multiline strings is not indentation
+    assert_unchanged_with_config(
+        "f = () => {\n\tx = \"a\n    b\"\n\ty = \"${x}\n    c\"\n\n\treturn y\n}",
+        tabs,
+    );
+}
+
+#[test]
+fn configured_max_width() {
+    let narrow = FormatterConfig {
+        max_width: 40,
+        ..FormatterConfig::default()
+    };
+    let pipeline = r#"from(bucket: "b") |> range(start: -1h) |> count()"#;
+    let wrapped = r#"from(bucket: "b")
+    |> range(start: -1h)
+    |> count()"#;
+    assert_eq!(format_with_config(pipeline, &narrow).unwrap(), wrapped);
+    assert_unchanged_with_config(wrapped, narrow.clone());
+    assert_unchanged_with_config(r#"from(bucket: "b") |> count()"#, narrow);
+    assert_unchanged(pipeline);
+}
+
+#[test]
+fn max_width_80() {
+    let config = FormatterConfig {
+        max_width: 80,
+        ..FormatterConfig::default()
+    };
+    let pipeline = r#"from(bucket: "telegraf") |> range(start: -1h) |> filter(fn: (r) => r._measurement == "cpu") |> count()"#;
+    let wrapped = r#"from(bucket: "telegraf")
+    |> range(start: -1h)
+    |> filter(fn: (r) => r._measurement == "cpu")
+    |> count()"#;
+    assert_eq!(format_with_config(pipeline, &config).unwrap(), wrapped);
+    assert_unchanged_with_config(wrapped, config.clone());
+    assert_unchanged_with_config(
+        r#"from(bucket: "telegraf") |> range(start: -1h) |> count()"#,
+        config,
+    );
+
+    // The default width of 120 columns keeps the pipeline on one line
+    assert_unchanged(pipeline);
+}
+
+#[test]
+fn configured_string_style() {
+    let normalize = FormatterConfig {
+        string_style: StringStyle::Normalize,
+        ..FormatterConfig::default()
+    };
+    let cases = [
+        (r#"x = "a\x41\$b""#, r#"x = "aA$b""#),
+        (r#"x = r"C:\dir""#, r#"x = "C:\\dir""#),
+        (r##"x = r#"say "hi""#"##, r#"x = "say \"hi\"""#),
+        (r#"x = "\${a}""#, r#"x = "\${a}""#),
+        (r#"x = r"${a}""#, r#"x = "\${a}""#),
+        (r#"x = "tab\tnewline\n""#, "x = \"tab\tnewline\n\""),
+    ];
+    let value = |script: &str| match &parse_string("".to_string(), script).body[..] {
+        [Statement::Variable(assgn)] => match &assgn.init {
+            ast::Expression::StringLit(lit) => lit.value.clone(),
+            expr => panic!("expected a string literal, found {:?}", expr),
+        },
+        body => panic!("expected a variable assignment, found {:?}", body),
+    };
+    for (script, expected) in cases {
+        let output = format_with_config(script, &normalize).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(
+            value(script),
+            value(&output),
+            "normalizing must not change the value of the string"
+        );
+        assert_unchanged_with_config(expected, normalize.clone());
+    }
+
+    // String literals are kept as written by default
+    for (script, _) in cases {
+        assert_unchanged(script);
+    }
+}
+
 #[test]
 fn call_expr() {
     // call function
@@ -159,6 +323,27 @@ a[i]"#,
     );
 }
 
+#[test]
+fn trailing_commas() {
+    assert_format("[1, 2,]", "[1, 2]");
+    assert_format("{a: 1,}", "{a: 1}");
+    assert_format("f(a: 1, b: 2,)", "f(a: 1, b: 2)");
+    assert_format("(a, b=1,) => a + b", "(a, b=1) => a + b");
+    assert_unchanged("[]");
+    assert_unchanged("{}");
+    assert_unchanged("f()");
+    assert_unchanged("() => 1");
+    assert_unchanged(
+        "{
+    a: 1,
+    b: 2,
+    c: 3,
+    d: 4,
+    e: 5,
+}",
+    );
+}
+
 #[test]
 fn dict_object() {
     // TODO assert_unchanged(r#"["a": 0, "b": 1]"#);
@@ -230,6 +415,44 @@ fn vars() {
     assert_unchanged(r#"/^http:\/\/\w+\.com$/"#); // regexp_escape
 }
 
+#[test]
+fn digit_separators() {
+    assert_unchanged("1_000_000");
+    assert_unchanged("10_00");
+    assert_unchanged("1_000.000_1");
+    assert_unchanged("1_000.50");
+    assert_unchanged("1_000ms");
+    assert_unchanged("1d1_000s");
+    assert_format("1000.50", "1000.5");
+}
+
+#[test]
+fn radix_literals() {
+    assert_unchanged("0xFF");
+    assert_unchanged("0Xff");
+    assert_unchanged("0b1010");
+    assert_unchanged("0xFFFF_FFFF");
+    assert_unchanged("0b1010 + 0x0A");
+}
+
+#[test]
+fn match_expression() {
+    assert_unchanged(
+        r#"x = match y {
+    1 => "one",
+    2 => "two",
+    _ => "many",
+}"#,
+    );
+    assert_format(
+        r#"b = match a.b { true => 1 false => 0 }"#,
+        r#"b = match a.b {
+    true => 1,
+    false => 0,
+}"#,
+    );
+}
+
 #[test]
 fn block() {
     assert_unchanged(
@@ -506,6 +729,13 @@ fn comments1() {
     assert_format("1 * //comment\n-1", "1 * (//comment\n    -1)");
 }
 
+#[test]
+fn block_comments() {
+    assert_unchanged("/* attach to id */\nid");
+    assert_unchanged("/* one\n   two */\nx = 1");
+    assert_unchanged("f(a: /* b */ 1)");
+}
+
 #[test]
 fn comments2() {
     assert_unchanged("i =\n    //comment\n    not true");
@@ -753,18 +983,46 @@ fn comments2() {
     // Comments around braces needs some work.
     assert_unchanged(
         r"fn = (a) => {
-    return a// ending
+    return a // ending
 
 }",
     );
 }
 
+#[test]
+fn comments_round_trip() {
+    // Comments on their own line before a statement
+    assert_unchanged("a = 1\n\n// own line\nb = 2");
+    assert_unchanged("a = 1\n// end");
+
+    // Inline comments after a statement stay on the line of that statement
+    assert_unchanged("a = 1 // trailing\nb = 2");
+    assert_unchanged("a = 1 // trailing\n// own line\nb = 2");
+    assert_unchanged("a = 1 // trailing");
+    assert_format("a = 1// trailing\nb = 2", "a = 1 // trailing\nb = 2");
+
+    // Comments between pipeline stages
+    assert_unchanged(
+        r#"from(bucket: "b")
+    // first
+    |> range(start: -1h)
+    // second
+    |> count()"#,
+    );
+
+    // Formatting is idempotent
+    let src = "x = 1  // one\n\n\n// two\ny = from(bucket: \"b\")\n// three\n|> count() // four";
+    let formatted = format(src).unwrap();
+    assert_eq!(formatted, format(&formatted).unwrap());
+}
+
 #[test]
 fn builtin() {
     assert_unchanged("builtin foo : [int]");
     assert_unchanged("builtin foo : A");
     assert_unchanged("builtin foo : (A: int, B: int) => int");
     assert_unchanged("builtin foo : {A: int, B: int} where A: Addable, B: Divisible");
+    assert_unchanged("builtin foo : (r: {a: int, ?b: string}) => int");
     assert_unchanged(
         "builtin foo : int
 
@@ -776,6 +1034,56 @@ x = 1",
     // assert_unchanged("builtin foo : \n    // comment\n    int");
 }
 
+#[test]
+fn raw_strings() {
+    assert_unchanged(r#"x = r"C:\path\to\file""#);
+    assert_unchanged(r##"x = r#"say "hi""#"##);
+}
+
+#[test]
+fn type_alias() {
+    assert_unchanged("type Point = {x: float, y: float}");
+    assert_unchanged("type Pair = (A, B) => {first: A, second: B}");
+    assert_unchanged("type F = (a: int) => int");
+    assert_unchanged("// comment\ntype Id = int");
+    assert_unchanged("builtin foo : Pair[int, string]");
+    assert_format("type Pair=(A,B)=>[A:B]", "type Pair = (A, B) => [A:B]");
+}
+
+#[test]
+fn annotated_variable() {
+    assert_unchanged("x : [int] = [1, 2]");
+    assert_unchanged("f : (x: A) => A where A: Addable = (x) => x + x");
+    assert_format("x:int=1", "x : int = 1");
+}
+
+#[test]
+fn named_type_parameters() {
+    assert_unchanged(
+        "builtin fill : (<-tables: [{Row with Col: A}], column: Col, value: Val) => [{Row with Col: Val}] where Col: Label, Val: Equatable",
+    );
+}
+
+#[test]
+fn destructuring_assignment() {
+    assert_unchanged("{a, b} = r");
+    assert_unchanged("{a: x, \"b c\": y} = r");
+    assert_unchanged("// comment\n{a, b} = f()");
+    assert_unchanged("f = () => {\n    {a, b} = r\n\n    return a + b\n}");
+    assert_format("{a,b}=r", "{a, b} = r");
+    assert_format("{a:x,b:y}={a:1,b:2}", "{a: x, b: y} = {a: 1, b: 2}");
+}
+
+#[test]
+fn export_statement() {
+    assert_unchanged("export x = 1\nexport y = 2");
+    assert_unchanged("export builtin foo : int");
+    assert_unchanged("export {a, b} = r");
+    assert_unchanged("// comment\nexport x : int = 1");
+    assert_unchanged("export x = 1\n\ny = 2");
+    assert_format("export   x=1", "export x = 1");
+}
+
 #[test]
 fn parens() {
     // test parens are preserved when comments are present
@@ -792,6 +1100,11 @@ fn parens() {
     assert_unchanged("(4 / 2) ^ 2");
     assert_unchanged("4 % 2 ^ 2");
     assert_unchanged("(4 % 2) ^ 2");
+    assert_unchanged("2 ** 3 ** 2");
+    assert_unchanged("(2 ** 3) ** 2");
+    assert_format("2 ** (3 ** 2)", "2 ** 3 ** 2");
+    assert_unchanged("2 * 3 ** 2");
+    assert_format("(2 ** 3) * 2", "2 ** 3 * 2");
     assert_unchanged("1 + 2 * 3");
     assert_unchanged("(1 + 2) * 3");
     assert_unchanged("1 - 2 * 3");
@@ -1922,7 +2235,8 @@ fn astutil_test_format_with_comments() {
         // list
         // of
         // comments
-        j// not lost"#]]
+        j
+        // not lost"#]]
     .assert_eq(&format(src).unwrap());
 }
 
@@ -36,6 +36,7 @@ use std::hash::BuildHasherDefault;
 
 use anyhow::{bail, Result};
 pub use ast::DEFAULT_PACKAGE_NAME;
+pub use errors::diagnostics_json;
 use fnv::FnvHasher;
 
 type DefaultHasher = BuildHasherDefault<FnvHasher>;
@@ -2,10 +2,18 @@
 
 use std::{collections::HashMap, mem, str};
 
+use thiserror::Error;
+
 use super::DefaultHasher;
-use crate::{ast, ast::*, scanner, scanner::*};
+use crate::{
+    ast,
+    ast::*,
+    errors::{located, Errors},
+    scanner,
+    scanner::*,
+};
 
-mod strconv;
+pub(crate) mod strconv;
 
 /// Parses a string of Flux source code.
 ///
@@ -16,10 +24,47 @@ pub fn parse_string(name: String, s: &str) -> File {
     p.parse_file(name)
 }
 
+/// Parses a single Flux expression, such as `a + 1`, which is not part of a file.
+///
+/// The locations of the nodes are relative to the start of `s`. See
+/// [`Parser::parse_single_expression`].
+pub fn parse_expression(s: &str) -> Expression {
+    let mut p = Parser::new(s);
+    p.parse_single_expression()
+}
+
+/// Parses a string of Flux source code, recovering from syntax errors.
+///
+/// A package is always returned. Statements which fail to parse are kept in it, with `Bad`
+/// placeholder nodes standing in for the parts that could not be parsed, and the errors of each of
+/// those statements are returned alongside the package. See [`Parser::parse_file_with_recovery`].
+pub fn parse_with_recovery(src: &str) -> (Package, Vec<ParseError>) {
+    let mut p = Parser::new(src);
+    let (file, errors) = p.parse_file_with_recovery("".to_string());
+    (file.into(), errors)
+}
+
+/// An error returned by [`Parser::next_statement`] for a statement which failed to parse.
+#[derive(Error, Debug, PartialEq)]
+#[error("{errors}")]
+pub struct ParseError {
+    /// Location of the statement which failed to parse.
+    pub location: SourceLocation,
+    /// The errors found in the statement.
+    pub errors: Errors<ast::check::Error>,
+}
+
 struct TokenError {
     pub token: Token,
 }
 
+// An element of a parenthesized, comma separated list. Whether the list is a tuple literal or
+// the parameters of a function is only known once the closing paren has been parsed.
+enum ParenItem {
+    Param(Property),
+    Expr(ArrayItem),
+}
+
 /// Represents a Flux parser and its state.
 pub struct Parser<'input> {
     s: Scanner<'input>,
@@ -31,6 +76,8 @@ pub struct Parser<'input> {
 
     fname: String,
     source: &'input str,
+    // max_errors is the number of errors parse_file_with_recovery returns at most.
+    max_errors: usize,
 }
 
 impl<'input> Parser<'input> {
@@ -44,9 +91,20 @@ impl<'input> Parser<'input> {
             blocks: HashMap::default(),
             fname: "".to_string(),
             source: src,
+            max_errors: usize::MAX,
         }
     }
 
+    /// Limits the number of errors returned by [`Parser::parse_file_with_recovery`] to `max`.
+    ///
+    /// Once the limit is reached the remaining statements are still parsed, but their errors are
+    /// dropped and a single "too many errors" error is returned after the first `max`. By
+    /// default the number of errors is unlimited.
+    pub fn with_max_errors(mut self, max: usize) -> Self {
+        self.max_errors = max;
+        self
+    }
+
     // scan will read the next token from the Scanner. If peek has been used,
     // this will return the peeked token and consume it.
     fn scan(&mut self) -> Token {
@@ -58,7 +116,7 @@ impl<'input> Parser<'input> {
 
     // peek will read the next token from the Scanner and then buffer it.
     // It will return information about the token.
-    fn peek(&mut self) -> &Token {
+    pub(crate) fn peek(&mut self) -> &Token {
         match self.t {
             Some(ref t) => t,
             None => {
@@ -139,7 +197,7 @@ impl<'input> Parser<'input> {
                 self.errs.push(format!("expected {}, got EOF", one_of(exp)));
             }
             _ => {
-                let pos = ast::Position::from(&t.start_pos);
+                let pos = ast::Position::from(&t.start);
                 self.errs.push(format!(
                     "expected {}, got {} ({}) at {}:{}",
                     one_of(exp),
@@ -164,7 +222,7 @@ impl<'input> Parser<'input> {
                 self.t = Some(t.clone());
             }
             _ => {
-                let pos = ast::Position::from(&t.start_pos);
+                let pos = ast::Position::from(&t.start);
                 self.errs.push(format!(
                     "expected {}, got {} ({}) at {}:{}",
                     exp, t.tok, t.lit, pos.line, pos.column,
@@ -257,21 +315,17 @@ impl<'input> Parser<'input> {
     }
 
     fn base_node_from_tokens(&mut self, start: &Token, end: &Token) -> BaseNode {
-        let start = ast::Position::from(&start.start_pos);
-        let end = ast::Position::from(&end.end_pos);
+        let start = ast::Position::from(&start.start);
+        let end = ast::Position::from(&end.end);
         self.base_node(self.source_location(&start, &end))
     }
 
     fn base_node_from_other_start(&mut self, start: &BaseNode, end: &Token) -> BaseNode {
-        self.base_node(
-            self.source_location(&start.location.start, &ast::Position::from(&end.end_pos)),
-        )
+        self.base_node(self.source_location(&start.location.start, &ast::Position::from(&end.end)))
     }
 
     fn base_node_from_other_end(&mut self, start: &Token, end: &BaseNode) -> BaseNode {
-        self.base_node(
-            self.source_location(&ast::Position::from(&start.start_pos), &end.location.end),
-        )
+        self.base_node(self.source_location(&ast::Position::from(&start.start), &end.location.end))
     }
 
     fn base_node_from_other_end_c(
@@ -280,9 +334,8 @@ impl<'input> Parser<'input> {
         end: &BaseNode,
         comments_from: &Token,
     ) -> BaseNode {
-        let mut base = self.base_node(
-            self.source_location(&ast::Position::from(&start.start_pos), &end.location.end),
-        );
+        let mut base = self
+            .base_node(self.source_location(&ast::Position::from(&start.start), &end.location.end));
         base.set_comments(comments_from.comments.clone());
         base
     }
@@ -311,8 +364,8 @@ impl<'input> Parser<'input> {
             return SourceLocation::default();
         }
 
-        let s_off = self.s.offset(&scanner::Position::from(start)) as usize;
-        let e_off = self.s.offset(&scanner::Position::from(end)) as usize;
+        let s_off = self.s.offset(start) as usize;
+        let e_off = self.s.end_offset(end) as usize;
 
         SourceLocation {
             file: if self.fname.is_empty() {
@@ -341,8 +394,109 @@ impl<'input> Parser<'input> {
 
     /// Parses a file of Flux source code, returning a [`File`].
     pub fn parse_file(&mut self, fname: String) -> File {
+        self.parse_file_with(fname, Self::parse_statement_list)
+    }
+
+    /// Parses an expression which makes up the whole input, returning an [`Expression`].
+    ///
+    /// When tokens are left after the expression the expression is wrapped in a [`BadExpr`]
+    /// located at the first of those tokens.
+    pub fn parse_single_expression(&mut self) -> Expression {
+        let expr = self.parse_expression();
+        let t = self.peek();
+        if t.tok == TokenType::Eof {
+            return expr;
+        }
+        let t = t.clone();
+        Expression::Bad(Box::new(BadExpr {
+            base: BaseNode {
+                location: self
+                    .source_location(&ast::Position::from(&t.start), &ast::Position::from(&t.end)),
+                ..BaseNode::default()
+            },
+            text: format!("expected end of expression, got {}", t.tok),
+            expression: Some(expr),
+        }))
+    }
+
+    /// Parses a file of Flux source code, returning a [`File`] and the errors of every statement
+    /// which failed to parse.
+    ///
+    /// Parsing resumes at the next statement after an error. The tokens left on the line where
+    /// the broken statement ended are discarded, as is done by [`Parser::next_statement`], and a
+    /// closing bracket without a matching opening bracket becomes a statement of its own.
+    ///
+    /// At most the number of errors set with [`Parser::with_max_errors`] is returned.
+    pub fn parse_file_with_recovery(&mut self, fname: String) -> (File, Vec<ParseError>) {
+        let mut errors = Vec::new();
+        // Location of the first statement whose errors were dropped
+        let mut dropped = None;
+        let file = self.parse_file_with(fname, |p| {
+            let mut stmts = Vec::new();
+            while p.peek().tok != TokenType::Eof {
+                let stmt = p.parse_statement();
+                if let Err(errs) = ast::check::check(ast::walk::Node::from_stmt(&stmt)) {
+                    let location = stmt.base().location.clone();
+                    p.skip_line(location.end.line);
+                    if errors.len() < p.max_errors {
+                        errors.push(ParseError {
+                            location,
+                            errors: errs,
+                        });
+                    } else if dropped.is_none() {
+                        dropped = Some(location);
+                    }
+                }
+                stmts.push(stmt);
+            }
+            stmts
+        });
+        let mut header = Vec::new();
+        if let Some(pkg) = &file.package {
+            header.push((
+                pkg.base.location.clone(),
+                ast::walk::Node::PackageClause(pkg),
+            ));
+        }
+        for imp in &file.imports {
+            header.push((
+                imp.base.location.clone(),
+                ast::walk::Node::ImportDeclaration(imp),
+            ));
+        }
+        let header = header.into_iter().filter_map(|(location, node)| {
+            ast::check::check(node)
+                .err()
+                .map(|errors| ParseError { location, errors })
+        });
+        let mut errors: Vec<_> = header.chain(errors).collect();
+        if errors.len() > self.max_errors {
+            dropped = errors
+                .drain(self.max_errors..)
+                .next()
+                .map(|err| err.location);
+        }
+        if let Some(location) = dropped {
+            errors.push(ParseError {
+                location: location.clone(),
+                errors: Errors::from(located(
+                    location,
+                    ast::check::ErrorKind {
+                        message: "too many errors".to_string(),
+                    },
+                )),
+            });
+        }
+        (file, errors)
+    }
+
+    fn parse_file_with(
+        &mut self,
+        fname: String,
+        parse_body: impl FnOnce(&mut Self) -> Vec<Statement>,
+    ) -> File {
         self.fname = fname;
-        let start_pos = ast::Position::from(&self.peek().start_pos);
+        let start_pos = ast::Position::from(&self.peek().start);
         let mut end = ast::Position::invalid();
         let pkg = self.parse_package_clause();
         if let Some(pkg) = &pkg {
@@ -352,7 +506,7 @@ impl<'input> Parser<'input> {
         if let Some(import) = imports.last() {
             end = import.base.location.end;
         }
-        let body = self.parse_statement_list();
+        let body = parse_body(self);
         if let Some(stmt) = body.last() {
             end = stmt.base().location.end;
         }
@@ -371,6 +525,37 @@ impl<'input> Parser<'input> {
         }
     }
 
+    /// Parses the next top-level statement of the input.
+    ///
+    /// Returns `None` once the input is exhausted. When the statement contains syntax errors the
+    /// remaining tokens on the line it ended on are discarded, so that the next call resumes at
+    /// the following statement.
+    pub fn next_statement(&mut self) -> Option<Result<Statement, ParseError>> {
+        if self.peek().tok == TokenType::Eof {
+            return None;
+        }
+        let stmt = self.parse_statement();
+        match ast::check::check(ast::walk::Node::from_stmt(&stmt)) {
+            Ok(()) => Some(Ok(stmt)),
+            Err(errors) => {
+                let location = stmt.base().location.clone();
+                self.skip_line(location.end.line);
+                Some(Err(ParseError { location, errors }))
+            }
+        }
+    }
+
+    // skip_line discards the buffered and upcoming tokens which start on the given line.
+    fn skip_line(&mut self, line: u32) {
+        loop {
+            let t = self.peek();
+            if t.tok == TokenType::Eof || t.start.line != line {
+                return;
+            }
+            self.consume();
+        }
+    }
+
     fn parse_package_clause(&mut self) -> Option<PackageClause> {
         let t = self.peek();
         if t.tok == TokenType::Package {
@@ -432,19 +617,27 @@ impl<'input> Parser<'input> {
             | TokenType::PipeReceive
             | TokenType::LParen
             | TokenType::LBrack
-            | TokenType::LBrace
             | TokenType::Add
             | TokenType::Sub
             | TokenType::Not
             | TokenType::If
             | TokenType::Exists
             | TokenType::Quote => self.parse_expression_statement(),
+            TokenType::LBrace => self.parse_brace_statement(),
             TokenType::Ident => self.parse_ident_statement(),
             TokenType::Option => self.parse_option_assignment(),
             TokenType::Builtin => self.parse_builtin_statement(),
             TokenType::Test => self.parse_test_statement(),
             TokenType::TestCase => self.parse_testcase_statement(),
             TokenType::Return => self.parse_return_statement(),
+            TokenType::Illegal if invalid_identifier(t).is_some() || is_unterminated_comment(t) => {
+                let t = self.consume();
+                let expr = self.create_bad_expression(t);
+                Statement::Expr(Box::new(ExprStmt {
+                    base: self.base_node(expr.base().location.clone()),
+                    expression: expr,
+                }))
+            }
             _ => {
                 let t = self.consume();
                 Statement::Bad(Box::new(BadStmt {
@@ -479,6 +672,8 @@ impl<'input> Parser<'input> {
                     base: self.base_node_from_others_c(&id.base, init.base(), &t),
                     id,
                     init,
+                    colon: vec![],
+                    ty: None,
                 })))
             }
             TokenType::Dot => {
@@ -493,6 +688,7 @@ impl<'input> Parser<'input> {
                         object: Expression::Identifier(id),
                         lbrack: t.comments,
                         property: PropertyKey::Identifier(prop),
+                        optional: false,
                         rbrack: vec![],
                     },
                     init,
@@ -514,9 +710,82 @@ impl<'input> Parser<'input> {
         }))
     }
 
+    // TypeAliasStatement = "type" identifier "=" [ "(" identifier { "," identifier } ")" "=>" ] MonoType
+    fn parse_type_alias_statement(&mut self, keyword: Identifier) -> Statement {
+        let id = self.parse_identifier();
+        self.expect(TokenType::Assign);
+        let mut params = Vec::new();
+        let ty = if self.peek().tok == TokenType::LParen {
+            // Both the parameters of the alias and a function type start with `(`, they are told
+            // apart by the `:` which follows the name of a function parameter.
+            let lparen = self.open(TokenType::LParen, TokenType::RParen);
+            let mut parameter = None;
+            while self.peek().tok == TokenType::Ident {
+                let name = self.parse_identifier();
+                if self.peek().tok == TokenType::Colon {
+                    parameter = Some(self.parse_required_parameter_suffix(name));
+                    break;
+                }
+                params.push(name);
+                if self.peek().tok != TokenType::Comma {
+                    break;
+                }
+                self.consume();
+            }
+            match parameter {
+                Some(parameter) => {
+                    if !params.is_empty() {
+                        self.errs
+                            .push("type alias parameters must be followed by `=>`".to_string());
+                        params.clear();
+                    }
+                    if self.peek().tok == TokenType::Comma {
+                        self.consume();
+                    }
+                    self.parse_function_type_suffix(lparen, vec![parameter])
+                }
+                None if params.is_empty() => self.parse_function_type_suffix(lparen, Vec::new()),
+                None => {
+                    self.close(TokenType::RParen);
+                    self.expect(TokenType::Arrow);
+                    self.parse_monotype()
+                }
+            }
+        } else {
+            self.parse_monotype()
+        };
+        let mut base = self.base_node_from_others(&keyword.base, ty.base());
+        base.set_comments(keyword.base.comments);
+        Statement::TypeAlias(Box::new(TypeAliasStmt {
+            base,
+            id,
+            params,
+            ty,
+        }))
+    }
+
+    fn parse_export_statement(&mut self, keyword: Identifier) -> Statement {
+        let statement = self.parse_statement();
+        if !matches!(
+            statement,
+            Statement::Variable(_) | Statement::Builtin(_) | Statement::Destructuring(_)
+        ) {
+            self.errs.push(format!(
+                "only assignments and builtin statements can be exported, found {} statement",
+                statement.type_name()
+            ));
+        }
+        let mut base = self.base_node_from_others(&keyword.base, statement.base());
+        base.set_comments(keyword.base.comments);
+        Statement::Export(Box::new(ExportStmt { base, statement }))
+    }
+
     /// Parses a type expression.
+    ///
+    /// Type variables are named with a single letter, `A`, unless they are constrained in the
+    /// `where` clause, which allows longer names such as `Col` in `(c: Col) => Col where Col: Label`.
     pub fn parse_type_expression(&mut self) -> TypeExpression {
-        let monotype = self.parse_monotype(); // monotype
+        let mut monotype = self.parse_monotype(); // monotype
         let t = self.peek();
         let mut base = monotype.base().clone();
         let mut constraints = Vec::new();
@@ -524,6 +793,14 @@ impl<'input> Parser<'input> {
             self.consume();
             constraints = self.parse_constraints();
             base = self.base_node_from_others(&base, &constraints[constraints.len() - 1].base);
+            let named: Vec<&str> = constraints
+                .iter()
+                .map(|con| con.tvar.name.as_str())
+                .filter(|name| name.chars().count() > 1)
+                .collect();
+            if !named.is_empty() {
+                name_type_parameters(&mut monotype, &named);
+            }
         }
         TypeExpression {
             base,
@@ -584,9 +861,25 @@ impl<'input> Parser<'input> {
 
     fn parse_basic_type(&mut self) -> MonoType {
         let t = self.peek().clone();
+        let name = self.parse_identifier();
+        if self.peek().tok == TokenType::LBrack {
+            // A parameterized type alias applied to its arguments, `Name[MonoType, ...]`
+            self.open(TokenType::LBrack, TokenType::RBrack);
+            let mut arguments = vec![self.parse_monotype()];
+            while self.peek().tok == TokenType::Comma {
+                self.consume();
+                arguments.push(self.parse_monotype());
+            }
+            let end = self.close(TokenType::RBrack);
+            return MonoType::Applied(Box::new(AppliedType {
+                base: self.base_node_from_tokens(&t, &end),
+                name,
+                arguments,
+            }));
+        }
         MonoType::Basic(NamedType {
             base: self.base_node_from_token(&t),
-            name: self.parse_identifier(),
+            name,
         })
     }
 
@@ -600,21 +893,27 @@ impl<'input> Parser<'input> {
 
     // "(" [Parameters] ")" "=>" MonoType
     fn parse_function_type(&mut self) -> MonoType {
-        let _lparen = self.open(TokenType::LParen, TokenType::RParen);
+        let lparen = self.open(TokenType::LParen, TokenType::RParen);
+        self.parse_function_type_suffix(lparen, Vec::new())
+    }
 
-        let params = if self.peek().tok == TokenType::PipeReceive
+    // Parses the rest of a function type, following the `(` and any parameters in `params`.
+    fn parse_function_type_suffix(
+        &mut self,
+        lparen: Token,
+        mut params: Vec<ParameterType>,
+    ) -> MonoType {
+        if self.peek().tok == TokenType::PipeReceive
             || self.peek().tok == TokenType::QuestionMark
             || self.peek().tok == TokenType::Ident
         {
-            self.parse_parameters()
-        } else {
-            Vec::<ParameterType>::new()
-        };
+            params.extend(self.parse_parameters());
+        }
         let _rparen = self.close(TokenType::RParen);
         self.expect(TokenType::Arrow);
         let mt = self.parse_monotype();
         MonoType::Function(Box::new(FunctionType {
-            base: self.base_node_from_other_end(&_lparen, mt.base()),
+            base: self.base_node_from_other_end(&lparen, mt.base()),
             parameters: params,
             monotype: mt,
         }))
@@ -675,17 +974,21 @@ impl<'input> Parser<'input> {
             _ => {
                 // Required
                 let id = self.parse_identifier();
-                self.expect(TokenType::Colon);
-                let mt = self.parse_monotype();
-                ParameterType::Required {
-                    base: self.base_node_from_others(&id.base, mt.base()),
-                    name: id,
-                    monotype: mt,
-                }
+                self.parse_required_parameter_suffix(id)
             }
         }
     }
 
+    fn parse_required_parameter_suffix(&mut self, id: Identifier) -> ParameterType {
+        self.expect(TokenType::Colon);
+        let mt = self.parse_monotype();
+        ParameterType::Required {
+            base: self.base_node_from_others(&id.base, mt.base()),
+            name: id,
+            monotype: mt,
+        }
+    }
+
     fn parse_constraints(&mut self) -> Vec<TypeConstraint> {
         let mut constraints = vec![self.parse_constraint()];
         while self.peek().tok == TokenType::Comma {
@@ -706,10 +1009,27 @@ impl<'input> Parser<'input> {
             let identifier = self.parse_identifier();
             id.push(identifier);
         }
+        let mut labels = Vec::new();
+        let end = if self.peek().tok == TokenType::In {
+            self.consume();
+            self.open(TokenType::LBrace, TokenType::RBrace);
+            while self.peek().tok == TokenType::String {
+                labels.push(self.parse_string_literal());
+                if self.peek().tok != TokenType::Comma {
+                    break;
+                }
+                self.consume();
+            }
+            let end = self.close(TokenType::RBrace);
+            self.base_node_from_token(&end)
+        } else {
+            id[id.len() - 1].base.clone()
+        };
         TypeConstraint {
-            base: self.base_node_from_others(&_tvar.base, &id[id.len() - 1].base),
+            base: self.base_node_from_others(&_tvar.base, &end),
             tvar: _tvar,
             kinds: id,
+            labels,
         }
     }
 
@@ -722,6 +1042,7 @@ impl<'input> Parser<'input> {
 
         let t = self.peek();
         let properties = match t.tok {
+            TokenType::QuestionMark => self.parse_property_type_list(),
             TokenType::Ident | TokenType::String => {
                 let property_key = self.parse_property_key();
 
@@ -742,7 +1063,9 @@ impl<'input> Parser<'input> {
                             _ => vec![],
                         }
                     }
-                    PropertyKey::StringLit(_) => self.parse_property_type_list_suffix(property_key),
+                    PropertyKey::StringLit(_) | PropertyKey::Spread(_) => {
+                        self.parse_property_type_list_suffix(property_key)
+                    }
                 }
             }
             // The record is empty
@@ -758,12 +1081,15 @@ impl<'input> Parser<'input> {
         })
     }
     fn parse_property_type_list(&mut self) -> Vec<PropertyType> {
-        let id = self.parse_property_key();
-        self.parse_property_type_list_suffix(id)
+        let p = self.parse_property_type();
+        self.parse_property_type_list_rest(p)
     }
     fn parse_property_type_list_suffix(&mut self, id: PropertyKey) -> Vec<PropertyType> {
-        let mut properties = Vec::<PropertyType>::with_capacity(5);
         let p = self.parse_property_type_suffix(id);
+        self.parse_property_type_list_rest(p)
+    }
+    fn parse_property_type_list_rest(&mut self, p: PropertyType) -> Vec<PropertyType> {
+        let mut properties = Vec::<PropertyType>::with_capacity(5);
         properties.push(p);
         if self.peek().tok == TokenType::Comma {
             self.consume();
@@ -777,7 +1103,17 @@ impl<'input> Parser<'input> {
         }
         properties
     }
+    // ("?" PropertyKey | PropertyKey) ":" MonoType
     fn parse_property_type(&mut self) -> PropertyType {
+        if self.peek().tok == TokenType::QuestionMark {
+            let symbol = self.expect(TokenType::QuestionMark);
+            let key = self.parse_property_key();
+            let mut property = self.parse_property_type_suffix(key);
+            let _base = self.base_node_from_token(&symbol);
+            property.base = self.base_node_from_others(&_base, &property.base);
+            property.optional = true;
+            return property;
+        }
         let key = self.parse_property_key();
         self.parse_property_type_suffix(key)
     }
@@ -788,6 +1124,7 @@ impl<'input> Parser<'input> {
             base: self.base_node_from_others(name.base(), monotype.base()),
             name,
             monotype,
+            optional: false,
         }
     }
 
@@ -802,6 +1139,8 @@ impl<'input> Parser<'input> {
                 base: self.base_node_from_others_c(&id.base, assignment.base(), &assign),
                 id,
                 init: assignment,
+                colon: vec![],
+                ty: None,
             },
         }))
     }
@@ -831,8 +1170,18 @@ impl<'input> Parser<'input> {
 
     fn parse_ident_statement(&mut self) -> Statement {
         let id = self.parse_identifier();
+        self.parse_ident_statement_suffix(id)
+    }
+    fn parse_ident_statement_suffix(&mut self, id: Identifier) -> Statement {
         let t = self.peek();
         match t.tok {
+            // `type` is only a keyword when it is followed by the name of an alias, it remains
+            // usable as an identifier everywhere else.
+            TokenType::Ident if id.name == "type" => self.parse_type_alias_statement(id),
+            // Likewise `export` is only a keyword in front of the statement being exported.
+            TokenType::Ident | TokenType::Builtin | TokenType::LBrace if id.name == "export" => {
+                self.parse_export_statement(id)
+            }
             TokenType::Assign => {
                 let t = t.clone();
                 let init = self.parse_assign_statement();
@@ -840,10 +1189,27 @@ impl<'input> Parser<'input> {
                     base: self.base_node_from_others_c(&id.base, init.base(), &t),
                     id,
                     init,
+                    colon: vec![],
+                    ty: None,
+                }))
+            }
+            // An annotated variable declaration, e.g. `x : [int] = [1, 2]`
+            TokenType::Colon => {
+                let colon = self.expect(TokenType::Colon);
+                let ty = self.parse_type_expression();
+                let t = self.peek().clone();
+                let init = self.parse_assign_statement();
+                Statement::Variable(Box::new(VariableAssgn {
+                    base: self.base_node_from_others_c(&id.base, init.base(), &t),
+                    id,
+                    colon: colon.comments,
+                    ty: Some(ty),
+                    init,
                 }))
             }
             _ => {
-                let expr = self.parse_expression_suffix(Expression::Identifier(id));
+                let expr = self.parse_identifier_or_match(id);
+                let expr = self.parse_expression_suffix(expr);
                 Statement::Expr(Box::new(ExprStmt {
                     base: self.base_node(expr.base().location.clone()),
                     expression: expr,
@@ -871,6 +1237,33 @@ impl<'input> Parser<'input> {
         };
         Statement::Expr(Box::new(stmt))
     }
+    // A statement starting with `{` is a record expression, unless the record is followed by `=`
+    // in which case it is the pattern of a destructuring assignment, e.g. `{a: x, b: y} = r`.
+    fn parse_brace_statement(&mut self) -> Statement {
+        let expr = self.parse_expression();
+        match expr {
+            Expression::Object(pattern)
+                if pattern.with.is_none() && self.peek().tok == TokenType::Assign =>
+            {
+                let t = self.peek().clone();
+                let init = self.parse_assign_statement();
+                for prop in &pattern.properties {
+                    if let Some(err) = invalid_destructuring_property(prop) {
+                        self.errs.push(err);
+                    }
+                }
+                Statement::Destructuring(Box::new(DestructuringAssgn {
+                    base: self.base_node_from_others_c(&pattern.base, init.base(), &t),
+                    pattern: *pattern,
+                    init,
+                }))
+            }
+            expr => Statement::Expr(Box::new(ExprStmt {
+                base: self.base_node(expr.base().location.clone()),
+                expression: expr,
+            })),
+        }
+    }
     fn parse_block(&mut self) -> Block {
         let start = self.open(TokenType::LBrace, TokenType::RBrace);
         let stmts = self.parse_statement_list();
@@ -913,8 +1306,8 @@ impl<'input> Parser<'input> {
                 //  an operator and create a binary expression. For now, skip past it.
                 let invalid_t = self.scan();
                 let loc = self.source_location(
-                    &ast::Position::from(&invalid_t.start_pos),
-                    &ast::Position::from(&invalid_t.end_pos),
+                    &ast::Position::from(&invalid_t.start),
+                    &ast::Position::from(&invalid_t.end),
                 );
                 self.errs
                     .push(format!("invalid expression {}: {}", loc, invalid_t.lit));
@@ -944,7 +1337,9 @@ impl<'input> Parser<'input> {
         let expr = self.parse_additive_expression_suffix(expr);
         let expr = self.parse_comparison_expression_suffix(expr);
         let expr = self.parse_logical_and_expression_suffix(expr);
-        self.parse_logical_or_expression_suffix(expr)
+        let expr = self.parse_logical_or_expression_suffix(expr);
+        let expr = self.parse_null_coalescing_expression_suffix(expr);
+        self.parse_ternary_expression_suffix(expr)
     }
     fn parse_conditional_expression(&mut self) -> Expression {
         let t = self.peek();
@@ -977,7 +1372,51 @@ impl<'input> Parser<'input> {
                 alternate: alt,
             }));
         }
-        self.parse_logical_or_expression()
+        self.parse_ternary_expression()
+    }
+    fn parse_ternary_expression(&mut self) -> Expression {
+        let expr = self.parse_null_coalescing_expression();
+        self.parse_ternary_expression_suffix(expr)
+    }
+    fn parse_ternary_expression_suffix(&mut self, test: Expression) -> Expression {
+        if self.peek().tok != TokenType::QuestionMark {
+            return test;
+        }
+        let question_tok = self.scan();
+        // Both branches are full expressions, so `a ? b : c ? d : e` groups to the right.
+        let cons = self.parse_expression();
+        let colon_tok = self.expect_or_skip(TokenType::Colon);
+        let alt = if colon_tok.tok == TokenType::Colon {
+            self.parse_expression()
+        } else {
+            self.create_placeholder_expression(colon_tok.clone())
+        };
+        Expression::Ternary(Box::new(TernaryExpr {
+            base: self.base_node_from_others(test.base(), alt.base()),
+            test,
+            tk_question: question_tok.comments,
+            consequent: cons,
+            tk_colon: colon_tok.comments,
+            alternate: alt,
+        }))
+    }
+    fn parse_null_coalescing_expression(&mut self) -> Expression {
+        let expr = self.parse_logical_or_expression();
+        self.parse_null_coalescing_expression_suffix(expr)
+    }
+    fn parse_null_coalescing_expression_suffix(&mut self, expr: Expression) -> Expression {
+        let mut res = expr;
+        while self.peek().tok == TokenType::NullCoalesce {
+            let t = self.scan();
+            let rhs = self.parse_logical_or_expression();
+            res = Expression::Binary(Box::new(BinaryExpr {
+                base: self.base_node_from_others_c(res.base(), rhs.base(), &t),
+                operator: Operator::NullCoalescingOperator,
+                left: res,
+                right: rhs,
+            }));
+        }
+        res
     }
     fn parse_logical_or_expression(&mut self) -> Expression {
         let expr = self.parse_logical_and_expression();
@@ -1190,7 +1629,12 @@ impl<'input> Parser<'input> {
             match op {
                 Some(op) => {
                     let t = self.scan();
-                    let rhs = self.parse_pipe_expression();
+                    // `**` is right associative so its right operand extends as far as possible.
+                    let rhs = if op == Operator::ExponentiationOperator {
+                        self.parse_exponent_expression()
+                    } else {
+                        self.parse_pipe_expression()
+                    };
                     self.base_node_from_others_c(res.base(), rhs.base(), &t);
                     res = Expression::Binary(Box::new(BinaryExpr {
                         base: self.base_node_from_others_c(res.base(), rhs.base(), &t),
@@ -1209,8 +1653,10 @@ impl<'input> Parser<'input> {
         let t = self.peek().tok;
         let mut res = None;
 
-        if let TokenType::Pow = t {
-            res = Some(Operator::PowerOperator)
+        match t {
+            TokenType::Pow => res = Some(Operator::PowerOperator),
+            TokenType::Exp => res = Some(Operator::ExponentiationOperator),
+            _ => (),
         }
         res
     }
@@ -1307,11 +1753,22 @@ impl<'input> Parser<'input> {
     // If it fails to find a postix operator, it returns Result::Err(expr) containing the original
     // expression passed. This allows for further reuse of the given `expr`.
     fn parse_postfix_operator(&mut self, expr: Expression) -> Result<Expression, Expression> {
+        // `?.` is scanned as a question mark directly followed by a dot, unless the dot starts a
+        // float such as the `.5` of `x ?.5 : 1`
+        let end = self.peek().end.offset as usize;
+        let mut rest = self.source[end..].chars();
+        let guarded =
+            rest.next() == Some('.') && !rest.next().map_or(false, |c| c.is_ascii_digit());
+
         let t = self.peek();
         match t.tok {
             TokenType::Dot => Ok(self.parse_dot_expression(expr)),
+            TokenType::QuestionMark if guarded => Ok(self.parse_guarded_dot_expression(expr)),
             TokenType::LParen => Ok(self.parse_call_expression(expr)),
             TokenType::LBrack => Ok(self.parse_index_expression(expr)),
+            TokenType::Float if t.lit.starts_with('.') => {
+                Ok(self.parse_tuple_index_expression(expr))
+            }
             _ => Err(expr),
         }
     }
@@ -1323,9 +1780,45 @@ impl<'input> Parser<'input> {
             object: expr,
             lbrack: dot.comments,
             property: PropertyKey::Identifier(id),
+            optional: false,
+            rbrack: vec![],
+        }))
+    }
+    fn parse_guarded_dot_expression(&mut self, expr: Expression) -> Expression {
+        let question = self.expect(TokenType::QuestionMark);
+        self.expect(TokenType::Dot);
+        let id = self.parse_identifier();
+        Expression::Member(Box::new(MemberExpr {
+            base: self.base_node_from_others(expr.base(), &id.base),
+            object: expr,
+            lbrack: question.comments,
+            property: PropertyKey::Identifier(id),
+            optional: true,
             rbrack: vec![],
         }))
     }
+    fn parse_tuple_index_expression(&mut self, expr: Expression) -> Expression {
+        // The scanner reads `.0` as a float literal so the dot and the index are a single token.
+        let t = self.expect(TokenType::Float);
+        let value = match t.lit[1..].parse::<i64>() {
+            Ok(value) => value,
+            Err(_) => {
+                self.errs
+                    .push(format!("invalid tuple index \"{}\"", &t.lit[1..]));
+                -1
+            }
+        };
+        let index = IntegerLit {
+            base: self.base_node_from_tokens(&t, &t),
+            value,
+        };
+        Expression::TupleIndex(Box::new(TupleIndexExpr {
+            base: self.base_node_from_others(expr.base(), &index.base),
+            tuple: expr,
+            dot: t.comments,
+            index,
+        }))
+    }
     fn parse_call_expression(&mut self, expr: Expression) -> Expression {
         let lparen = self.open(TokenType::LParen, TokenType::RParen);
         let params = self.parse_property_list();
@@ -1361,6 +1854,7 @@ impl<'input> Parser<'input> {
                 object: expr,
                 lbrack: start.comments,
                 property: PropertyKey::StringLit(sl),
+                optional: false,
                 rbrack: end.comments,
             })),
             Some(e) => Expression::Index(Box::new(IndexExpr {
@@ -1393,13 +1887,30 @@ impl<'input> Parser<'input> {
             // Do not use `self.base_node_*` in order not to steal errors.
             // The BadExpr is an error per se. We want to leave errors to parents.
             base: BaseNode {
-                location: self.source_location(
-                    &ast::Position::from(&t.start_pos),
-                    &ast::Position::from(&t.end_pos),
-                ),
+                location: self
+                    .source_location(&ast::Position::from(&t.start), &ast::Position::from(&t.end)),
                 ..BaseNode::default()
             },
-            text: format!("invalid token for primary expression: {}", t.tok),
+            text: if t.tok == TokenType::Illegal && t.lit.starts_with(|c: char| c.is_ascii_digit())
+            {
+                format!(
+                    "invalid number literal \"{}\": {}",
+                    t.lit,
+                    invalid_number_reason(&t.lit)
+                )
+            } else if let Some((offset, c)) = invalid_identifier(&t) {
+                format!(
+                    "invalid identifier \"{}\": character {:?} at byte offset {} cannot be part of an identifier",
+                    t.lit,
+                    c,
+                    t.start.offset as usize + offset
+                )
+            } else if is_unterminated_comment(&t) {
+                "unterminated block comment, expected */ to close it before the end of the file"
+                    .to_string()
+            } else {
+                format!("invalid token for primary expression: {}", t.tok)
+            },
             expression: None,
         }))
     }
@@ -1409,10 +1920,8 @@ impl<'input> Parser<'input> {
             // Do not use `self.base_node_*` in order not to steal errors.
             // The BadExpr is an error per se. We want to leave errors to parents.
             base: BaseNode {
-                location: self.source_location(
-                    &ast::Position::from(&t.start_pos),
-                    &ast::Position::from(&t.end_pos),
-                ),
+                location: self
+                    .source_location(&ast::Position::from(&t.start), &ast::Position::from(&t.end)),
                 ..BaseNode::default()
             },
             text: "".to_string(),
@@ -1423,7 +1932,10 @@ impl<'input> Parser<'input> {
     fn parse_primary_expression(&mut self) -> Expression {
         let t = self.peek_with_regex();
         match t.tok {
-            TokenType::Ident => Expression::Identifier(self.parse_identifier()),
+            TokenType::Ident => {
+                let id = self.parse_identifier();
+                self.parse_identifier_or_match(id)
+            }
             TokenType::Int => Expression::Integer(self.parse_int_literal()),
             TokenType::Float => {
                 let lit = self.parse_float_literal();
@@ -1432,12 +1944,29 @@ impl<'input> Parser<'input> {
                     Err(terr) => self.create_bad_expression(terr.token),
                 }
             }
-            TokenType::String => Expression::StringLit(self.parse_string_literal()),
+            TokenType::String => {
+                let t = self.expect(TokenType::String);
+                match strconv::parse_string(t.lit.as_str()) {
+                    Ok(value) => Expression::StringLit(StringLit {
+                        base: self.base_node_from_token(&t),
+                        value,
+                    }),
+                    // Unlike a string literal, a string expression can hold the invalid part of
+                    // the string in a node of its own
+                    Err(err) => {
+                        let part = self.invalid_text_part(&t, err);
+                        Expression::StringExpr(Box::new(StringExpr {
+                            base: self.base_node_from_token(&t),
+                            parts: vec![StringExprPart::Text(part)],
+                        }))
+                    }
+                }
+            }
             TokenType::Quote => {
-                let lit = self.parse_string_expression();
-                match lit {
-                    Ok(lit) => Expression::StringExpr(Box::new(lit)),
-                    Err(terr) => self.create_bad_expression(terr.token),
+                let expr = self.parse_string_expression();
+                match text_of_string_expression(expr) {
+                    Ok(lit) => Expression::StringLit(lit),
+                    Err(expr) => Expression::StringExpr(Box::new(expr)),
                 }
             }
             TokenType::Regex => Expression::Regexp(self.parse_regexp_literal()),
@@ -1461,7 +1990,7 @@ impl<'input> Parser<'input> {
                 let start = self.open(TokenType::LBrack, TokenType::RBrack);
                 self.parse_array_or_dict(&start)
             }
-            TokenType::LBrace => Expression::Object(Box::new(self.parse_object_literal())),
+            TokenType::LBrace => self.parse_object_literal_or_block(),
             TokenType::LParen => self.parse_paren_expression(),
             // We got a bad token, do not consume it, but use it in the message.
             // Other methods will match BadExpr and consume the token if needed.
@@ -1471,24 +2000,83 @@ impl<'input> Parser<'input> {
             }
         }
     }
-    fn parse_string_expression(&mut self) -> Result<StringExpr, TokenError> {
+    // `match` is only a keyword when it is followed by its scrutinee on the same line,
+    // `match x { ... }`, it remains usable as an identifier everywhere else.
+    fn parse_identifier_or_match(&mut self, id: Identifier) -> Expression {
+        if id.name != "match" {
+            return Expression::Identifier(id);
+        }
+        let t = self.peek();
+        let starts_scrutinee = matches!(
+            t.tok,
+            TokenType::Ident
+                | TokenType::Int
+                | TokenType::Float
+                | TokenType::String
+                | TokenType::Quote
+                | TokenType::Time
+                | TokenType::Duration
+        );
+        if !starts_scrutinee || t.start.line != id.base.location.end.line {
+            return Expression::Identifier(id);
+        }
+
+        let scrutinee = self.parse_expression();
+        let start = self.open(TokenType::LBrace, TokenType::RBrace);
+        let mut arms = Vec::new();
+        while self.more() {
+            let pattern = self.parse_expression();
+            let arrow = self.expect(TokenType::Arrow);
+            let body = self.parse_expression();
+            let comma = if self.peek().tok == TokenType::Comma {
+                self.consume().comments
+            } else {
+                Vec::new()
+            };
+            arms.push(MatchArm {
+                base: self.base_node_from_others(pattern.base(), body.base()),
+                pattern,
+                arrow: arrow.comments,
+                body,
+                comma,
+            });
+        }
+        let end = self.close(TokenType::RBrace);
+        let mut base =
+            self.base_node_from_pos(&id.base.location.start, &ast::Position::from(&end.end));
+        base.comments = id.base.comments;
+        Expression::Match(Box::new(MatchExpr {
+            base,
+            scrutinee,
+            lbrace: start.comments,
+            arms,
+            rbrace: end.comments,
+        }))
+    }
+    fn parse_string_expression(&mut self) -> StringExpr {
         let start = self.expect(TokenType::Quote);
         let mut parts = Vec::new();
         loop {
             let t = self.s.scan_string_expr();
             match t.tok {
-                TokenType::Text => {
-                    let value = strconv::parse_text(t.lit.as_str());
-                    match value {
-                        Ok(value) => {
-                            parts.push(StringExprPart::Text(TextPart {
-                                base: self.base_node_from_token(&t),
-                                value,
-                            }));
+                TokenType::Text => match strconv::parse_text(t.lit.as_str()) {
+                    Ok(value) => {
+                        let base = self.base_node_from_token(&t);
+                        match parts.last_mut() {
+                            // Escape sequences which the scanner does not know are scanned as a
+                            // text of their own, the valid ones are joined with the text around
+                            // them
+                            Some(StringExprPart::Text(last)) if last.base.errors.is_empty() => {
+                                let mut joined = self.base_node_from_others(&last.base, &base);
+                                joined.errors = base.errors;
+                                last.base = joined;
+                                last.value.push_str(&value);
+                            }
+                            _ => parts.push(StringExprPart::Text(TextPart { base, value })),
                         }
-                        Err(_) => return Err(TokenError { token: t }),
                     }
-                }
+                    Err(err) => parts.push(StringExprPart::Text(self.invalid_text_part(&t, err))),
+                },
                 TokenType::StringExpr => {
                     let expr = self.parse_expression();
                     let end = self.expect(TokenType::RBrace);
@@ -1498,15 +2086,15 @@ impl<'input> Parser<'input> {
                     }));
                 }
                 TokenType::Quote => {
-                    return Ok(StringExpr {
+                    return StringExpr {
                         base: self.base_node_from_tokens(&start, &t),
                         parts,
-                    })
+                    }
                 }
                 _ => {
                     let loc = self.source_location(
-                        &ast::Position::from(&t.start_pos),
-                        &ast::Position::from(&t.end_pos),
+                        &ast::Position::from(&t.start),
+                        &ast::Position::from(&t.end),
                     );
                     self.errs.push(format!(
                         "got unexpected token in string expression {}@{}:{}-{}:{}: {}",
@@ -1517,10 +2105,10 @@ impl<'input> Parser<'input> {
                         loc.end.column,
                         t.tok
                     ));
-                    return Ok(StringExpr {
+                    return StringExpr {
                         base: self.base_node_from_tokens(&start, &t),
                         parts: Vec::new(),
-                    });
+                    };
                 }
             }
         }
@@ -1544,8 +2132,9 @@ impl<'input> Parser<'input> {
     }
     fn parse_int_literal(&mut self) -> IntegerLit {
         let t = self.expect(TokenType::Int);
+        let lit = strconv::strip_digit_separators(&t.lit);
 
-        if t.lit.starts_with('0') && t.lit.len() > 1 {
+        if strconv::split_radix(&lit).0 == 10 && lit.starts_with('0') && lit.len() > 1 {
             self.errs.push(format!(
                 "invalid integer literal \"{}\": nonzero value cannot start with 0",
                 t.lit
@@ -1556,7 +2145,7 @@ impl<'input> Parser<'input> {
             };
         }
 
-        match (&t.lit).parse::<i64>() {
+        match strconv::parse_int(&lit) {
             Err(_e) => {
                 self.errs.push(format!(
                     "invalid integer literal \"{}\": value out of range",
@@ -1576,7 +2165,7 @@ impl<'input> Parser<'input> {
     fn parse_float_literal(&mut self) -> Result<FloatLit, TokenError> {
         let t = self.expect(TokenType::Float);
 
-        let value = (&t.lit).parse::<f64>();
+        let value = strconv::strip_digit_separators(&t.lit).parse::<f64>();
 
         match value {
             Ok(value) => Ok(FloatLit {
@@ -1586,6 +2175,29 @@ impl<'input> Parser<'input> {
             Err(_) => Err(TokenError { token: t }),
         }
     }
+    // Returns a text part for the string token `t`, which could not be decoded because of `err`.
+    // The part is located at just the invalid part of the text, such as an escape sequence, so
+    // that the error is reported there.
+    fn invalid_text_part(&self, t: &Token, err: strconv::StringError) -> TextPart {
+        let (start, end) = string_error_positions(t, &err);
+        TextPart {
+            base: BaseNode {
+                location: SourceLocation {
+                    file: if self.fname.is_empty() {
+                        None
+                    } else {
+                        Some(self.fname.clone())
+                    },
+                    start,
+                    end,
+                    source: Some(t.lit[err.span.clone()].to_string()),
+                },
+                errors: vec![err.message],
+                ..BaseNode::default()
+            },
+            value: String::new(),
+        }
+    }
     fn parse_string_literal(&mut self) -> StringLit {
         let t = self.expect(TokenType::String);
         self.new_string_literal(t)
@@ -1597,7 +2209,7 @@ impl<'input> Parser<'input> {
                 value,
             },
             Err(err) => {
-                self.errs.push(err);
+                self.errs.push(string_error_message(&t, err));
                 StringLit {
                     base: self.base_node_from_token(&t),
                     value: "".to_string(),
@@ -1635,7 +2247,7 @@ impl<'input> Parser<'input> {
     }
     fn parse_duration_literal(&mut self) -> Result<DurationLit, TokenError> {
         let t = self.expect(TokenType::Duration);
-        let values = strconv::parse_duration(t.lit.as_str());
+        let values = strconv::parse_duration(&strconv::strip_digit_separators(&t.lit));
 
         match values {
             Ok(values) => Ok(DurationLit {
@@ -1682,6 +2294,11 @@ impl<'input> Parser<'input> {
                     rbrack,
                 }))
             }
+            // array starting with a spread [...xs]
+            TokenType::Dot => {
+                let spread = self.parse_array_element();
+                self.parse_array_items_rest(start, spread)
+            }
             _ => {
                 let expr = self.parse_expression();
                 match self.peek().tok {
@@ -1718,9 +2335,9 @@ impl<'input> Parser<'input> {
                     comma: comma.comments,
                 }];
                 // keep track of the last token's byte offsets
-                let mut last = self.peek().start_offset;
+                let mut last = self.peek().start.offset;
                 while self.more() {
-                    let expression = self.parse_expression();
+                    let expression = self.parse_array_element();
                     let comma = match self.peek().tok {
                         TokenType::Comma => {
                             let comma = self.scan();
@@ -1733,7 +2350,7 @@ impl<'input> Parser<'input> {
                     // If we parse the same token twice in a row,
                     // it means we've hit a parse error, and that
                     // we're now in an infinite loop.
-                    let this = self.peek().start_offset;
+                    let this = self.peek().start.offset;
                     if last == this {
                         break;
                     }
@@ -1749,6 +2366,35 @@ impl<'input> Parser<'input> {
             }
         }
     }
+    // Parses an element of an array literal, which may spread another array into it, `...xs`.
+    fn parse_array_element(&mut self) -> Expression {
+        if self.peek().tok == TokenType::Dot {
+            Expression::Spread(Box::new(self.parse_spread_expression()))
+        } else {
+            self.parse_expression()
+        }
+    }
+    // Parses a spread, `...expr`. The scanner has no token for `...`, so it is read as three
+    // adjacent dots.
+    fn parse_spread_expression(&mut self) -> SpreadExpr {
+        let start = self.expect(TokenType::Dot);
+        let mut end = start.end.offset;
+        for _ in 0..2 {
+            let t = self.peek();
+            if t.tok == TokenType::Dot && t.start.offset == end {
+                end = self.consume().end.offset;
+            } else {
+                let err = format!("expected ... in spread, got {}", t.tok);
+                self.errs.push(err);
+                break;
+            }
+        }
+        let argument = self.parse_expression();
+        SpreadExpr {
+            base: self.base_node_from_other_end_c(&start, argument.base(), &start),
+            argument,
+        }
+    }
     fn parse_dict_items_rest(
         &mut self,
         start: &Token,
@@ -1799,14 +2445,46 @@ impl<'input> Parser<'input> {
             }
         }
     }
-    fn parse_object_literal(&mut self) -> ObjectExpr {
+    // A brace starts a block expression when it is followed by an assignment, `{ a = 1; a + 1 }`,
+    // and an object literal otherwise.
+    fn parse_object_literal_or_block(&mut self) -> Expression {
         let start = self.open(TokenType::LBrace, TokenType::RBrace);
-        let mut obj = self.parse_object_body();
+        let mut obj = if self.peek().tok == TokenType::Ident {
+            let ident = self.parse_identifier();
+            if self.peek().tok == TokenType::Assign {
+                let first = self.parse_ident_statement_suffix(ident);
+                return Expression::Block(Box::new(
+                    self.parse_block_expression_suffix(start, first),
+                ));
+            }
+            self.parse_object_body_suffix(ident)
+        } else {
+            self.parse_object_body()
+        };
         let end = self.close(TokenType::RBrace);
         obj.base = self.base_node_from_tokens(&start, &end);
         obj.lbrace = start.comments;
         obj.rbrace = end.comments;
-        obj
+        Expression::Object(Box::new(obj))
+    }
+    // Parses the statements of a block expression after its first one. The statements may be
+    // separated by semicolons.
+    fn parse_block_expression_suffix(&mut self, start: Token, first: Statement) -> BlockExpr {
+        let mut body = vec![first];
+        while self.more() {
+            if self.peek().tok == TokenType::Semicolon {
+                self.consume();
+                continue;
+            }
+            body.push(self.parse_statement());
+        }
+        let end = self.close(TokenType::RBrace);
+        BlockExpr {
+            base: self.base_node_from_tokens(&start, &end),
+            lbrace: start.comments,
+            body,
+            rbrace: end.comments,
+        }
     }
     fn parse_paren_expression(&mut self) -> Expression {
         let lparen = self.open(TokenType::LParen, TokenType::RParen);
@@ -1825,7 +2503,7 @@ impl<'input> Parser<'input> {
             }
             _ => {
                 let t = t.clone();
-                let mut expr = self.parse_expression_while_more(None, &[]);
+                let mut expr = self.parse_expression_while_more(None, &[TokenType::Comma]);
                 match expr {
                     None => {
                         expr = Some(Expression::Bad(Box::new(BadExpr {
@@ -1833,8 +2511,8 @@ impl<'input> Parser<'input> {
                             // The BadExpr is an error per se. We want to leave errors to parents.
                             base: BaseNode {
                                 location: self.source_location(
-                                    &ast::Position::from(&t.start_pos),
-                                    &ast::Position::from(&t.end_pos),
+                                    &ast::Position::from(&t.start),
+                                    &ast::Position::from(&t.end),
                                 ),
                                 ..BaseNode::default()
                             },
@@ -1844,11 +2522,21 @@ impl<'input> Parser<'input> {
                     }
                     Some(_) => (),
                 };
+                let expr = expr.expect("must be Some at this point");
+                if self.peek().tok == TokenType::Comma {
+                    let comma = self.scan();
+                    let items = self.parse_paren_item_list(ParenItem::Expr(ArrayItem {
+                        expression: expr,
+                        comma: comma.comments,
+                    }));
+                    let rparen = self.close(TokenType::RParen);
+                    return self.parse_tuple_expression(lparen, rparen, items);
+                }
                 let rparen = self.close(TokenType::RParen);
                 Expression::Paren(Box::new(ParenExpr {
                     base: self.base_node_from_tokens(&lparen, &rparen),
                     lparen: lparen.comments,
-                    expression: expr.expect("must be Some at this point"),
+                    expression: expr,
                     rparen: rparen.comments,
                 }))
             }
@@ -1900,27 +2588,63 @@ impl<'input> Parser<'input> {
             }
             TokenType::Comma => {
                 let t = self.consume();
-                let mut params = vec![Property {
-                    base: self.base_node(key.base.location.clone()),
-                    key: PropertyKey::Identifier(key),
-                    value: None,
-                    separator: vec![],
+                let items = self.parse_paren_item_list(ParenItem::Expr(ArrayItem {
+                    expression: Expression::Identifier(key),
                     comma: t.comments,
-                }];
-                let others = &mut self.parse_parameter_list();
-                params.append(others);
+                }));
                 let rparen = self.close(TokenType::RParen);
-                self.parse_function_expression(lparen, rparen, params)
+                // A list of bare identifiers directly followed by something that could only be a
+                // function body is still reported as a function with a missing arrow.
+                let next = self.peek();
+                let missing_arrow = matches!(next.tok, TokenType::Ident | TokenType::LBrace)
+                    && next.start.line == rparen.end.line
+                    && items.iter().all(|item| {
+                        matches!(
+                            item,
+                            ParenItem::Expr(ArrayItem {
+                                expression: Expression::Identifier(_),
+                                ..
+                            })
+                        )
+                    });
+                let is_function = next.tok == TokenType::Arrow
+                    || missing_arrow
+                    || items.iter().any(|item| matches!(item, ParenItem::Param(_)));
+                if is_function {
+                    let mut params = Vec::with_capacity(items.len());
+                    for item in items {
+                        match item {
+                            ParenItem::Param(param) => params.push(param),
+                            ParenItem::Expr(ArrayItem {
+                                expression: Expression::Identifier(id),
+                                comma,
+                            }) => params.push(Property {
+                                base: self.base_node(id.base.location.clone()),
+                                key: PropertyKey::Identifier(id),
+                                value: None,
+                                separator: vec![],
+                                comma,
+                            }),
+                            ParenItem::Expr(item) => self.errs.push(format!(
+                                "invalid function parameter at {}",
+                                item.expression.base().location
+                            )),
+                        }
+                    }
+                    self.parse_function_expression(lparen, rparen, params)
+                } else {
+                    self.parse_tuple_expression(lparen, rparen, items)
+                }
             }
             _ => {
                 let mut expr = self.parse_expression_suffix(Expression::Identifier(key));
-                while self.more() {
+                while self.more() && self.peek().tok != TokenType::Comma {
                     let rhs = self.parse_expression();
                     if let Expression::Bad(_) = rhs {
                         let invalid_t = self.scan();
                         let loc = self.source_location(
-                            &ast::Position::from(&invalid_t.start_pos),
-                            &ast::Position::from(&invalid_t.end_pos),
+                            &ast::Position::from(&invalid_t.start),
+                            &ast::Position::from(&invalid_t.end),
                         );
                         self.errs
                             .push(format!("invalid expression {}: {}", loc, invalid_t.lit));
@@ -1933,6 +2657,15 @@ impl<'input> Parser<'input> {
                         right: rhs,
                     }));
                 }
+                if self.peek().tok == TokenType::Comma {
+                    let comma = self.scan();
+                    let items = self.parse_paren_item_list(ParenItem::Expr(ArrayItem {
+                        expression: expr,
+                        comma: comma.comments,
+                    }));
+                    let rparen = self.close(TokenType::RParen);
+                    return self.parse_tuple_expression(lparen, rparen, items);
+                }
                 let rparen = self.close(TokenType::RParen);
                 Expression::Paren(Box::new(ParenExpr {
                     base: self.base_node_from_tokens(&lparen, &rparen),
@@ -1943,6 +2676,87 @@ impl<'input> Parser<'input> {
             }
         }
     }
+    // parse_paren_item_list parses the rest of a comma separated list inside parens. Its elements
+    // may be parameters with default values or arbitrary expressions.
+    fn parse_paren_item_list(&mut self, first: ParenItem) -> Vec<ParenItem> {
+        let mut items = vec![first];
+        while self.more() {
+            let mut item = if self.peek().tok == TokenType::Ident {
+                let key = self.parse_identifier();
+                if self.peek().tok == TokenType::Assign {
+                    let t = self.scan();
+                    let value = self.parse_expression();
+                    ParenItem::Param(Property {
+                        base: self.base_node_from_others(&key.base, value.base()),
+                        key: PropertyKey::Identifier(key),
+                        value: Some(value),
+                        separator: t.comments,
+                        comma: vec![],
+                    })
+                } else {
+                    let expr = self.parse_expression_suffix(Expression::Identifier(key));
+                    ParenItem::Expr(ArrayItem {
+                        expression: expr,
+                        comma: vec![],
+                    })
+                }
+            } else {
+                match self.parse_expression_while_more(None, &[TokenType::Comma]) {
+                    Some(expr) => ParenItem::Expr(ArrayItem {
+                        expression: expr,
+                        comma: vec![],
+                    }),
+                    None => {
+                        // An empty element such as `(a, , b)`, skip past the extra comma.
+                        let t = self.scan();
+                        self.errs
+                            .push(format!("expected expression, got {}", t.lit));
+                        continue;
+                    }
+                }
+            };
+            if self.peek().tok == TokenType::Comma {
+                let t = self.scan();
+                match &mut item {
+                    ParenItem::Param(param) => param.comma = t.comments,
+                    ParenItem::Expr(item) => item.comma = t.comments,
+                }
+            }
+            items.push(item);
+        }
+        items
+    }
+    fn parse_tuple_expression(
+        &mut self,
+        lparen: Token,
+        rparen: Token,
+        items: Vec<ParenItem>,
+    ) -> Expression {
+        let mut elements = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                ParenItem::Expr(item) => elements.push(item),
+                ParenItem::Param(param) => {
+                    self.errs.push(format!(
+                        "unexpected default value in tuple at {}",
+                        param.base.location
+                    ));
+                    if let Some(value) = param.value {
+                        elements.push(ArrayItem {
+                            expression: value,
+                            comma: param.comma,
+                        });
+                    }
+                }
+            }
+        }
+        Expression::Tuple(Box::new(TupleExpr {
+            base: self.base_node_from_tokens(&lparen, &rparen),
+            lparen: lparen.comments,
+            elements,
+            rparen: rparen.comments,
+        }))
+    }
     fn parse_object_body(&mut self) -> ObjectExpr {
         let t = self.peek();
         match t.tok {
@@ -2033,6 +2847,7 @@ impl<'input> Parser<'input> {
             let mut p: Property = match t.tok {
                 TokenType::Ident => self.parse_ident_property(),
                 TokenType::String => self.parse_string_property(),
+                TokenType::Dot => self.parse_spread_property(),
                 _ => self.parse_invalid_property(),
             };
             if self.more() {
@@ -2058,6 +2873,16 @@ impl<'input> Parser<'input> {
         let key = self.parse_identifier();
         self.parse_property_suffix(PropertyKey::Identifier(key))
     }
+    fn parse_spread_property(&mut self) -> Property {
+        let spread = self.parse_spread_expression();
+        Property {
+            base: self.base_node(spread.base.location.clone()),
+            key: PropertyKey::Spread(Box::new(spread)),
+            value: None,
+            comma: vec![],
+            separator: vec![],
+        }
+    }
     fn parse_property_suffix(&mut self, key: PropertyKey) -> Property {
         let mut value = None;
         let mut separator = vec![];
@@ -2108,13 +2933,13 @@ impl<'input> Parser<'input> {
             }
         }
         self.errs.append(&mut errs);
-        let end_start_pos = ast::Position::from(&self.peek().start_pos);
+        let end_start_pos = ast::Position::from(&self.peek().start);
         Property {
-            base: self.base_node_from_pos(&ast::Position::from(&t.start_pos), &end_start_pos),
+            base: self.base_node_from_pos(&ast::Position::from(&t.start), &end_start_pos),
             key: PropertyKey::StringLit(StringLit {
                 base: self.base_node_from_pos(
-                    &ast::Position::from(&t.start_pos),
-                    &ast::Position::from(&t.start_pos),
+                    &ast::Position::from(&t.start),
+                    &ast::Position::from(&t.start),
                 ),
                 value: "<invalid>".to_string(),
             }),
@@ -2209,5 +3034,150 @@ impl<'input> Parser<'input> {
     }
 }
 
+// Returns true if `t` is a block comment which the end of the file was reached in.
+fn is_unterminated_comment(t: &Token) -> bool {
+    t.tok == TokenType::Illegal && t.lit.starts_with("/*")
+}
+
+// Returns the string literal that a string expression without any interpolation is equivalent
+// to. These are the strings containing escape sequences which the scanner does not know, other
+// strings without interpolation are scanned as a string literal right away.
+//
+// A text with an invalid escape sequence is kept as a part of its own, so that its error stays
+// located at the escape sequence.
+fn text_of_string_expression(mut expr: StringExpr) -> Result<StringLit, StringExpr> {
+    match expr.parts.pop() {
+        Some(StringExprPart::Text(text))
+            if expr.parts.is_empty() && text.base.errors.is_empty() =>
+        {
+            Ok(StringLit {
+                base: expr.base,
+                value: text.value,
+            })
+        }
+        Some(part) => {
+            expr.parts.push(part);
+            Err(expr)
+        }
+        None => Err(expr),
+    }
+}
+
+// Returns the positions of the start and end of the invalid part of the text of the string token
+// `t`.
+fn string_error_positions(t: &Token, err: &strconv::StringError) -> (ast::Position, ast::Position) {
+    let position = |offset: usize| {
+        let before = &t.lit[..offset];
+        match before.rfind('\n') {
+            Some(newline) => ast::Position {
+                line: t.start.line + before.matches('\n').count() as u32,
+                column: scanner::columns(before[newline + 1..].as_bytes()) + 1,
+            },
+            None => ast::Position {
+                line: t.start.line,
+                column: t.start.column + scanner::columns(before.as_bytes()),
+            },
+        }
+    };
+    (position(err.span.start), position(err.span.end))
+}
+
+// Describes an error in the text of the string token `t`, including the positions of the start
+// and end of the invalid part of the text. Used where the string can only be a string literal,
+// whose error is located at the whole string.
+fn string_error_message(t: &Token, err: strconv::StringError) -> String {
+    let (start, end) = string_error_positions(t, &err);
+    format!(
+        "{} at {}:{}-{}:{}",
+        err.message, start.line, start.column, end.line, end.column
+    )
+}
+
+// Describes why `prop` can't be part of a destructuring pattern, which may only name labels and
+// the identifiers they are bound to.
+fn invalid_destructuring_property(prop: &Property) -> Option<String> {
+    let label = match &prop.key {
+        PropertyKey::Identifier(id) => &id.name,
+        PropertyKey::StringLit(lit) => &lit.value,
+        PropertyKey::Spread(_) => {
+            return Some("a destructuring pattern cannot contain a spread".to_string())
+        }
+    };
+    match &prop.value {
+        None | Some(Expression::Identifier(_)) => None,
+        Some(_) => Some(format!(
+            "label {} of a destructuring pattern must be bound to an identifier",
+            label
+        )),
+    }
+}
+
+// Returns the first invalid character of `t`, along with its byte offset in `t`, if `t` is an
+// identifier which contains characters that cannot be part of an identifier.
+fn invalid_identifier(t: &Token) -> Option<(usize, char)> {
+    if t.tok != TokenType::Illegal {
+        return None;
+    }
+    match scanner::invalid_identifier_char(&t.lit) {
+        Some((offset, c)) if offset > 0 => Some((offset, c)),
+        _ => None,
+    }
+}
+
+// Explains why the scanner rejected a number literal.
+fn invalid_number_reason(lit: &str) -> &'static str {
+    match strconv::split_radix(lit) {
+        (10, _) => "underscores must separate digits",
+        (_, digits) if digits.trim_matches('_').is_empty() => {
+            "missing digits after the radix prefix"
+        }
+        (radix, digits) if !digits.chars().all(|c| c == '_' || c.is_digit(radix)) => {
+            "invalid digit for the radix"
+        }
+        _ => "underscores must separate digits",
+    }
+}
+
+// Turns the named types of `ty` which are one of the type parameters in `names` into type
+// variables, the parser reads them as named types since it has not seen the `where` clause yet.
+fn name_type_parameters(ty: &mut MonoType, names: &[&str]) {
+    match ty {
+        MonoType::Basic(basic) if names.contains(&basic.name.name.as_str()) => {
+            let name = basic.name.clone();
+            *ty = MonoType::Tvar(TvarType {
+                base: name.base.clone(),
+                name,
+            });
+        }
+        MonoType::Basic(_) | MonoType::Tvar(_) => (),
+        MonoType::Array(arr) => name_type_parameters(&mut arr.element, names),
+        MonoType::Stream(stream) => name_type_parameters(&mut stream.element, names),
+        MonoType::Dict(dict) => {
+            name_type_parameters(&mut dict.key, names);
+            name_type_parameters(&mut dict.val, names);
+        }
+        MonoType::Record(rec) => {
+            for prop in &mut rec.properties {
+                name_type_parameters(&mut prop.monotype, names);
+            }
+        }
+        MonoType::Function(func) => {
+            for param in &mut func.parameters {
+                match param {
+                    ParameterType::Required { monotype, .. }
+                    | ParameterType::Optional { monotype, .. }
+                    | ParameterType::Pipe { monotype, .. } => name_type_parameters(monotype, names),
+                }
+            }
+            name_type_parameters(&mut func.monotype, names);
+        }
+        MonoType::Applied(app) => {
+            for arg in &mut app.arguments {
+                name_type_parameters(arg, names);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,5 +1,7 @@
 use std::{
+    borrow::Cow,
     iter::Peekable,
+    ops::Range,
     str::{CharIndices, Chars},
 };
 
@@ -8,21 +10,60 @@ use regex::Regex;
 
 use crate::ast;
 
-pub fn parse_string(lit: &str) -> Result<String, String> {
+/// An error in the text of a string literal.
+#[derive(Debug, PartialEq)]
+pub struct StringError {
+    /// The byte offsets of the invalid part of the literal, such as an escape sequence.
+    pub span: Range<usize>,
+    /// Describes what is wrong with that part of the literal.
+    pub message: String,
+}
+
+impl StringError {
+    fn new(span: Range<usize>, message: impl Into<String>) -> Self {
+        StringError {
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+pub fn parse_string(lit: &str) -> Result<String, StringError> {
+    if let Some(raw) = lit.strip_prefix('r') {
+        return parse_raw_string(raw)
+            .ok_or_else(|| StringError::new(0..lit.len(), "invalid raw string literal"));
+    }
     if lit.len() < 2 || !lit.starts_with('"') || !lit.ends_with('"') {
-        return Err("invalid string literal".to_string());
+        return Err(StringError::new(0..lit.len(), "invalid string literal"));
     }
-    parse_text(&lit[1..lit.len() - 1])
+    // Offsets of errors in the text are relative to the literal, which starts with a quote
+    parse_text(&lit[1..lit.len() - 1]).map_err(|err| StringError {
+        span: err.span.start + 1..err.span.end + 1,
+        ..err
+    })
+}
+
+// Raw strings, `r"..."` or `r#"..."#`, contain no escapes.
+fn parse_raw_string(lit: &str) -> Option<String> {
+    let delimiter = if lit.starts_with('#') { "#" } else { "" };
+    lit.strip_prefix(delimiter)
+        .and_then(|lit| lit.strip_prefix('"'))
+        .and_then(|lit| lit.strip_suffix(delimiter))
+        .and_then(|lit| lit.strip_suffix('"'))
+        .map(String::from)
 }
 
-pub fn parse_text(lit: &str) -> Result<String, String> {
+/// Parses the text of a string, decoding its escape sequences. An invalid escape sequence is
+/// reported with its byte offsets in `lit`.
+pub fn parse_text(lit: &str) -> Result<String, StringError> {
     let mut s = Vec::with_capacity(lit.len());
     let mut chars = lit.char_indices();
-    while let Some((_, c)) = chars.next() {
+    while let Some((i, c)) = chars.next() {
         match c {
             '\\' => {
-                if let Err(e) = push_unescaped(&mut s, &mut chars) {
-                    return Err(e);
+                if let Err(message) = push_unescaped(&mut s, &mut chars) {
+                    let end = lit.len() - chars.as_str().len();
+                    return Err(StringError::new(i..end, message));
                 }
             }
             // this char can have any byte length
@@ -32,7 +73,7 @@ pub fn parse_text(lit: &str) -> Result<String, String> {
     let converted = std::str::from_utf8(&s);
     match converted {
         Ok(s) => Ok(s.to_string()),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(StringError::new(0..lit.len(), e.to_string())),
     }
 }
 
@@ -45,6 +86,34 @@ fn push_unescaped(s: &mut Vec<u8>, chars: &mut CharIndices) -> Result<(), String
             '\\' => s.push(b'\\'),
             '"' => s.push(b'"'),
             '$' => s.push(b'$'),
+            'u' => {
+                let digits: String = chars
+                    .clone()
+                    .map(|(_, c)| c)
+                    .take(4)
+                    .take_while(char::is_ascii_hexdigit)
+                    .collect();
+                // The digits belong to the escape sequence even when there are too few of them
+                for _ in 0..digits.len() {
+                    chars.next();
+                }
+                if digits.len() < 4 {
+                    return Err(format!(
+                        r#"invalid unicode escape \u{}, expected 4 hexadecimal digits"#,
+                        digits
+                    ));
+                }
+                let value = u32::from_str_radix(&digits, 16).expect("four hexadecimal digits");
+                match char::from_u32(value) {
+                    Some(c) => s.extend_from_slice(c.to_string().as_bytes()),
+                    None => {
+                        return Err(format!(
+                            r#"invalid unicode escape \u{}, which is not a unicode scalar value"#,
+                            digits
+                        ))
+                    }
+                }
+            }
             'x' => {
                 let ch1 = match chars.next() {
                     Some((_, c)) => c,
@@ -62,7 +131,7 @@ fn push_unescaped(s: &mut Vec<u8>, chars: &mut CharIndices) -> Result<(), String
                 let b = (b1.unwrap() << 4) | b2.unwrap();
                 s.push(b);
             }
-            _ => return Err(format!("invalid escape character {}", c)),
+            _ => return Err(format!(r#"invalid escape sequence \{}"#, c)),
         },
         None => return Err("invalid escape sequence".to_string()),
     };
@@ -129,6 +198,29 @@ pub fn parse_time(lit: &str) -> Result<DateTime<FixedOffset>, String> {
     }
 }
 
+pub fn strip_digit_separators(lit: &str) -> Cow<'_, str> {
+    if lit.contains('_') {
+        Cow::Owned(lit.replace('_', ""))
+    } else {
+        Cow::Borrowed(lit)
+    }
+}
+
+// Splits a integer literal into its radix and digits.
+pub fn split_radix(lit: &str) -> (u32, &str) {
+    match lit.get(..2) {
+        Some("0x") | Some("0X") => (16, &lit[2..]),
+        Some("0b") | Some("0B") => (2, &lit[2..]),
+        _ => (10, lit),
+    }
+}
+
+pub fn parse_int(lit: &str) -> Result<i64, std::num::ParseIntError> {
+    let lit = strip_digit_separators(lit);
+    let (radix, digits) = split_radix(&lit);
+    i64::from_str_radix(digits, radix)
+}
+
 pub fn parse_duration(lit: &str) -> Result<Vec<ast::Duration>, String> {
     let mut values = Vec::new();
     let mut chars = lit.chars().peekable();
@@ -8,12 +8,15 @@ use crate::{
 };
 
 mod arrow_function;
+mod blocks;
 mod errors;
 mod from;
 mod literals;
+mod matches;
 mod objects;
 mod operator_precedence;
 mod strings;
+mod tuples;
 mod types;
 
 /// Parsed ast roundtrips across the serde boundary and generates the same ast.
@@ -71,7 +74,7 @@ fn parse_invalid_unicode_bare() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 16),
+                location: loc.get(1, 1, 1, 14),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -81,19 +84,19 @@ fn parse_invalid_unicode_bare() {
             body: vec![
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 1, 1, 3),
+                        location: loc.get(1, 1, 1, 2),
                         ..BaseNode::default()
                     },
                     text: "®".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 3, 1, 7),
+                        location: loc.get(1, 2, 1, 6),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 3, 1, 7),
+                            location: loc.get(1, 2, 1, 6),
                             ..BaseNode::default()
                         },
                         name: "some".to_string(),
@@ -101,12 +104,12 @@ fn parse_invalid_unicode_bare() {
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 8, 1, 14),
+                        location: loc.get(1, 7, 1, 13),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 8, 1, 14),
+                            location: loc.get(1, 7, 1, 13),
                             ..BaseNode::default()
                         },
                         name: "string".to_string(),
@@ -114,7 +117,7 @@ fn parse_invalid_unicode_bare() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 14, 1, 16),
+                        location: loc.get(1, 13, 1, 14),
                         ..BaseNode::default()
                     },
                     text: "®".to_string(),
@@ -134,7 +137,7 @@ fn parse_invalid_unicode_paren_wrapped() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 20),
+                location: loc.get(1, 1, 1, 16),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -143,33 +146,33 @@ fn parse_invalid_unicode_paren_wrapped() {
             imports: vec![],
             body: vec![Statement::Expr(Box::new(ExprStmt {
                 base: BaseNode {
-                    location: loc.get(1, 1, 1, 20),
+                    location: loc.get(1, 1, 1, 16),
                     ..BaseNode::default()
                 },
                 expression: Expression::Paren(Box::new(ParenExpr {
                     base: BaseNode {
-                        location: loc.get(1, 1, 1, 20),
-                        errors: vec!["invalid expression @1:16-1:19: ‛".to_string()],
+                        location: loc.get(1, 1, 1, 16),
+                        errors: vec!["invalid expression @1:14-1:15: ‛".to_string()],
                         ..BaseNode::default()
                     },
                     lparen: vec![],
                     expression: Expression::Binary(Box::new(BinaryExpr {
                         base: BaseNode {
-                            location: loc.get(1, 5, 1, 16),
+                            location: loc.get(1, 3, 1, 14),
                             ..BaseNode::default()
                         },
                         operator: Operator::InvalidOperator,
                         left: Expression::Identifier(Identifier {
                             base: BaseNode {
-                                location: loc.get(1, 5, 1, 9),
-                                errors: vec!["invalid expression @1:2-1:5: ‛".to_string()],
+                                location: loc.get(1, 3, 1, 7),
+                                errors: vec!["invalid expression @1:2-1:3: ‛".to_string()],
                                 ..BaseNode::default()
                             },
                             name: "some".to_string(),
                         }),
                         right: Expression::Identifier(Identifier {
                             base: BaseNode {
-                                location: loc.get(1, 10, 1, 16),
+                                location: loc.get(1, 8, 1, 14),
                                 ..BaseNode::default()
                             },
                             name: "string".to_string(),
@@ -192,7 +195,7 @@ fn parse_invalid_unicode_interspersed() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 19),
+                location: loc.get(1, 1, 1, 13),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -202,19 +205,19 @@ fn parse_invalid_unicode_interspersed() {
             body: vec![
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 1, 1, 3),
+                        location: loc.get(1, 1, 1, 2),
                         ..BaseNode::default()
                     },
                     text: "®".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 3, 1, 4),
+                        location: loc.get(1, 2, 1, 3),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 3, 1, 4),
+                            location: loc.get(1, 2, 1, 3),
                             ..BaseNode::default()
                         },
                         name: "s".to_string(),
@@ -222,19 +225,19 @@ fn parse_invalid_unicode_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 4, 1, 6),
+                        location: loc.get(1, 3, 1, 4),
                         ..BaseNode::default()
                     },
                     text: "®".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 6, 1, 7),
+                        location: loc.get(1, 4, 1, 5),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 6, 1, 7),
+                            location: loc.get(1, 4, 1, 5),
                             ..BaseNode::default()
                         },
                         name: "t".to_string(),
@@ -242,19 +245,19 @@ fn parse_invalid_unicode_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 7, 1, 9),
+                        location: loc.get(1, 5, 1, 6),
                         ..BaseNode::default()
                     },
                     text: "®".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 9, 1, 10),
+                        location: loc.get(1, 6, 1, 7),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 9, 1, 10),
+                            location: loc.get(1, 6, 1, 7),
                             ..BaseNode::default()
                         },
                         name: "r".to_string(),
@@ -262,19 +265,19 @@ fn parse_invalid_unicode_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 10, 1, 12),
+                        location: loc.get(1, 7, 1, 8),
                         ..BaseNode::default()
                     },
                     text: "®".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 12, 1, 13),
+                        location: loc.get(1, 8, 1, 9),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 12, 1, 13),
+                            location: loc.get(1, 8, 1, 9),
                             ..BaseNode::default()
                         },
                         name: "i".to_string(),
@@ -282,19 +285,19 @@ fn parse_invalid_unicode_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 13, 1, 15),
+                        location: loc.get(1, 9, 1, 10),
                         ..BaseNode::default()
                     },
                     text: "®".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 15, 1, 16),
+                        location: loc.get(1, 10, 1, 11),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 15, 1, 16),
+                            location: loc.get(1, 10, 1, 11),
                             ..BaseNode::default()
                         },
                         name: "n".to_string(),
@@ -302,19 +305,19 @@ fn parse_invalid_unicode_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 16, 1, 18),
+                        location: loc.get(1, 11, 1, 12),
                         ..BaseNode::default()
                     },
                     text: "®".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 18, 1, 19),
+                        location: loc.get(1, 12, 1, 13),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 18, 1, 19),
+                            location: loc.get(1, 12, 1, 13),
                             ..BaseNode::default()
                         },
                         name: "g".to_string(),
@@ -335,7 +338,7 @@ fn parse_greedy_quotes_paren_wrapped() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 20),
+                location: loc.get(1, 1, 1, 16),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -344,33 +347,33 @@ fn parse_greedy_quotes_paren_wrapped() {
             imports: vec![],
             body: vec![Statement::Expr(Box::new(ExprStmt {
                 base: BaseNode {
-                    location: loc.get(1, 1, 1, 20),
+                    location: loc.get(1, 1, 1, 16),
                     ..BaseNode::default()
                 },
                 expression: Expression::Paren(Box::new(ParenExpr {
                     base: BaseNode {
-                        location: loc.get(1, 1, 1, 20),
-                        errors: vec!["invalid expression @1:16-1:19: ”".to_string()],
+                        location: loc.get(1, 1, 1, 16),
+                        errors: vec!["invalid expression @1:14-1:15: ”".to_string()],
                         ..BaseNode::default()
                     },
                     lparen: vec![],
                     expression: Expression::Binary(Box::new(BinaryExpr {
                         base: BaseNode {
-                            location: loc.get(1, 5, 1, 16),
+                            location: loc.get(1, 3, 1, 14),
                             ..BaseNode::default()
                         },
                         operator: Operator::InvalidOperator,
                         left: Expression::Identifier(Identifier {
                             base: BaseNode {
-                                location: loc.get(1, 5, 1, 9),
-                                errors: vec!["invalid expression @1:2-1:5: “".to_string()],
+                                location: loc.get(1, 3, 1, 7),
+                                errors: vec!["invalid expression @1:2-1:3: “".to_string()],
                                 ..BaseNode::default()
                             },
                             name: "some".to_string(),
                         }),
                         right: Expression::Identifier(Identifier {
                             base: BaseNode {
-                                location: loc.get(1, 10, 1, 16),
+                                location: loc.get(1, 8, 1, 14),
                                 ..BaseNode::default()
                             },
                             name: "string".to_string(),
@@ -393,7 +396,7 @@ fn parse_greedy_quotes_bare() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 18),
+                location: loc.get(1, 1, 1, 14),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -403,19 +406,19 @@ fn parse_greedy_quotes_bare() {
             body: vec![
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 1, 1, 4),
+                        location: loc.get(1, 1, 1, 2),
                         ..BaseNode::default()
                     },
                     text: "“".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 4, 1, 8),
+                        location: loc.get(1, 2, 1, 6),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 4, 1, 8),
+                            location: loc.get(1, 2, 1, 6),
                             ..BaseNode::default()
                         },
                         name: "some".to_string(),
@@ -423,12 +426,12 @@ fn parse_greedy_quotes_bare() {
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 9, 1, 15),
+                        location: loc.get(1, 7, 1, 13),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 9, 1, 15),
+                            location: loc.get(1, 7, 1, 13),
                             ..BaseNode::default()
                         },
                         name: "string".to_string(),
@@ -436,7 +439,7 @@ fn parse_greedy_quotes_bare() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 15, 1, 18),
+                        location: loc.get(1, 13, 1, 14),
                         ..BaseNode::default()
                     },
                     text: "”".to_string(),
@@ -456,7 +459,7 @@ fn parse_greedy_quotes_interspersed() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 25),
+                location: loc.get(1, 1, 1, 13),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -466,19 +469,19 @@ fn parse_greedy_quotes_interspersed() {
             body: vec![
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 1, 1, 4),
+                        location: loc.get(1, 1, 1, 2),
                         ..BaseNode::default()
                     },
                     text: "“".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 4, 1, 5),
+                        location: loc.get(1, 2, 1, 3),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 4, 1, 5),
+                            location: loc.get(1, 2, 1, 3),
                             ..BaseNode::default()
                         },
                         name: "s".to_string(),
@@ -486,19 +489,19 @@ fn parse_greedy_quotes_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 5, 1, 8),
+                        location: loc.get(1, 3, 1, 4),
                         ..BaseNode::default()
                     },
                     text: "”".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 8, 1, 9),
+                        location: loc.get(1, 4, 1, 5),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 8, 1, 9),
+                            location: loc.get(1, 4, 1, 5),
                             ..BaseNode::default()
                         },
                         name: "t".to_string(),
@@ -506,19 +509,19 @@ fn parse_greedy_quotes_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 9, 1, 12),
+                        location: loc.get(1, 5, 1, 6),
                         ..BaseNode::default()
                     },
                     text: "“".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 12, 1, 13),
+                        location: loc.get(1, 6, 1, 7),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 12, 1, 13),
+                            location: loc.get(1, 6, 1, 7),
                             ..BaseNode::default()
                         },
                         name: "r".to_string(),
@@ -526,19 +529,19 @@ fn parse_greedy_quotes_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 13, 1, 16),
+                        location: loc.get(1, 7, 1, 8),
                         ..BaseNode::default()
                     },
                     text: "”".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 16, 1, 17),
+                        location: loc.get(1, 8, 1, 9),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 16, 1, 17),
+                            location: loc.get(1, 8, 1, 9),
                             ..BaseNode::default()
                         },
                         name: "i".to_string(),
@@ -546,19 +549,19 @@ fn parse_greedy_quotes_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 17, 1, 20),
+                        location: loc.get(1, 9, 1, 10),
                         ..BaseNode::default()
                     },
                     text: "“".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 20, 1, 21),
+                        location: loc.get(1, 10, 1, 11),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 20, 1, 21),
+                            location: loc.get(1, 10, 1, 11),
                             ..BaseNode::default()
                         },
                         name: "n".to_string(),
@@ -566,19 +569,19 @@ fn parse_greedy_quotes_interspersed() {
                 })),
                 Statement::Bad(Box::new(BadStmt {
                     base: BaseNode {
-                        location: loc.get(1, 21, 1, 24),
+                        location: loc.get(1, 11, 1, 12),
                         ..BaseNode::default()
                     },
                     text: "”".to_string(),
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
-                        location: loc.get(1, 24, 1, 25),
+                        location: loc.get(1, 12, 1, 13),
                         ..BaseNode::default()
                     },
                     expression: Expression::Identifier(Identifier {
                         base: BaseNode {
-                            location: loc.get(1, 24, 1, 25),
+                            location: loc.get(1, 12, 1, 13),
                             ..BaseNode::default()
                         },
                         name: "g".to_string(),
@@ -1163,7 +1166,9 @@ fn optional_query_metadata() {
                             }
                         ],
                         rbrace: vec![],
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 }))
             }))],
             eof: vec![],
@@ -1253,6 +1258,7 @@ fn optional_query_metadata_preceding_query_text() {
                                             location: loc.get(3, 6, 3, 11),
                                             comments: vec![ast::Comment {
                                                 text: String::from("// Name of task\n"),
+                                                trailing: true,
                                             }],
                                             ..BaseNode::default()
                                         },
@@ -1274,8 +1280,11 @@ fn optional_query_metadata_preceding_query_text() {
                             ],
                             rbrace: vec![ast::Comment {
                                 text: String::from("// Execution frequency of task\n"),
+                                trailing: true,
                             }],
-                        }))
+                        })),
+                        colon: vec![],
+                        ty: None
                     }))
                 })),
                 Statement::Expr(Box::new(ExprStmt {
@@ -1300,6 +1309,7 @@ fn optional_query_metadata_preceding_query_text() {
                                         text: String::from(
                                             "// Task will execute the following query\n"
                                         ),
+                                        trailing: false,
                                     }],
                                     ..BaseNode::default()
                                 },
@@ -1379,6 +1389,7 @@ fn qualified_option() {
                             },
                             name: "state".to_string()
                         }),
+                        optional: false,
                         rbrack: vec![],
                     },
                     init: Expression::StringLit(StringLit {
@@ -1450,6 +1461,60 @@ fn builtin() {
     )
 }
 
+#[test]
+fn export_statements() {
+    let mut p = Parser::new("export x = 1\nexport builtin y : int\nexport {a, b} = r");
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    match &parsed.body[..] {
+        [Statement::Export(x), Statement::Export(y), Statement::Export(ab)] => {
+            assert_eq!(x.base.location, loc.get(1, 1, 1, 13));
+            assert!(
+                matches!(&x.statement, Statement::Variable(v) if v.id.name == "x"),
+                "{:?}",
+                x.statement
+            );
+            assert!(
+                matches!(&y.statement, Statement::Builtin(b) if b.id.name == "y"),
+                "{:?}",
+                y.statement
+            );
+            assert!(
+                matches!(ab.statement, Statement::Destructuring(_)),
+                "{:?}",
+                ab.statement
+            );
+        }
+        body => panic!("expected three export statements, got {:?}", body),
+    }
+}
+
+#[test]
+fn export_as_identifier() {
+    let mut p = Parser::new("export = 1\nexport + 1");
+    let parsed = p.parse_file("".to_string());
+    assert!(matches!(parsed.body[0], Statement::Variable(_)));
+    assert!(matches!(parsed.body[1], Statement::Expr(_)));
+}
+
+#[test]
+fn export_type_alias() {
+    let mut p = Parser::new("export type T = int");
+    let parsed = p.parse_file("".to_string());
+    match &parsed.body[..] {
+        [Statement::Export(stmt)] => {
+            assert_eq!(
+                stmt.base.errors,
+                vec![
+                    "only assignments and builtin statements can be exported, found type alias statement"
+                        .to_string()
+                ]
+            );
+        }
+        body => panic!("expected a single export statement, got {:?}", body),
+    }
+}
+
 #[test]
 fn test_statement() {
     let mut p = Parser::new(r#"test mean = {want: 0, got: 0}"#);
@@ -1537,7 +1602,9 @@ fn test_statement() {
                             }
                         ],
                         rbrace: vec![],
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 }
             }))],
             eof: vec![],
@@ -1579,6 +1646,7 @@ fn comment() {
                             location: loc.get(2, 4, 2, 8),
                             comments: vec![ast::Comment {
                                 text: String::from("// Comment\n"),
+                                trailing: false,
                             }],
                             ..BaseNode::default()
                         },
@@ -1619,6 +1687,7 @@ builtin foo
                     location: loc.get(2, 1, 4, 6),
                     comments: vec![ast::Comment {
                         text: String::from("// Comment\n"),
+                        trailing: false,
                     }],
                     ..BaseNode::default()
                 },
@@ -1631,6 +1700,7 @@ builtin foo
                 },
                 colon: vec![ast::Comment {
                     text: String::from("// colon comment\n"),
+                    trailing: false,
                 }],
                 ty: TypeExpression {
                     base: BaseNode {
@@ -1726,6 +1796,7 @@ fn comment_function_body() {
                         },
                         lparen: vec![ast::Comment {
                             text: String::from("// comment\n"),
+                            trailing: false,
                         }],
                         expression: Expression::Identifier(Identifier {
                             base: BaseNode {
@@ -1736,7 +1807,9 @@ fn comment_function_body() {
                         }),
                         rparen: vec![],
                     })))
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -1896,7 +1969,9 @@ fn declare_variable_as_an_int() {
                         ..BaseNode::default()
                     },
                     value: 1
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -1937,7 +2012,9 @@ fn declare_variable_as_a_float() {
                         ..BaseNode::default()
                     },
                     value: 1.1
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -2021,7 +2098,9 @@ fn declare_variable_as_an_array() {
                         }
                     ],
                     rbrack: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -2064,7 +2143,9 @@ fn declare_variable_as_an_empty_array() {
                     lbrack: vec![],
                     elements: vec![],
                     rbrack: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -2179,6 +2260,39 @@ fn parse_multi_element_dict() {
     )
 }
 
+#[test]
+fn parse_trailing_commas() {
+    let mut p = Parser::new(
+        r#"a = [1, 2,]
+b = {a: 1, b: 2,}
+c = f(a: 1,)
+d = (x, y=1,) => x + y
+e = [1,
+    2,
+]"#,
+    );
+    let parsed = p.parse_file("".to_string());
+    ast::check::check(ast::walk::Node::File(&parsed)).unwrap_or_else(|err| panic!("{}", err));
+    let lens: Vec<usize> = parsed
+        .body
+        .iter()
+        .map(|stmt| match stmt {
+            Statement::Variable(assgn) => match &assgn.init {
+                Expression::Array(array) => array.elements.len(),
+                Expression::Object(obj) => obj.properties.len(),
+                Expression::Call(call) => match &call.arguments[..] {
+                    [Expression::Object(obj)] => obj.properties.len(),
+                    _ => panic!("expected a single object argument"),
+                },
+                Expression::Function(func) => func.params.len(),
+                _ => panic!("unexpected expression"),
+            },
+            _ => panic!("unexpected statement"),
+        })
+        .collect();
+    assert_eq!(lens, vec![2, 2, 1, 2, 2]);
+}
+
 #[test]
 fn parse_dict_trailing_comma0() {
     let mut p = Parser::new(r#"["a": 0, ]"#);
@@ -2388,7 +2502,9 @@ fn use_variable_to_declare_something() {
                             ..BaseNode::default()
                         },
                         value: 1
-                    })
+                    }),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -2466,6 +2582,8 @@ fn variable_is_from_statement() {
                         arguments: vec![],
                         rparen: vec![],
                     })),
+                    colon: vec![],
+                    ty: None,
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -2497,6 +2615,7 @@ fn variable_is_from_statement() {
                                 },
                                 name: "count".to_string()
                             }),
+                            optional: false,
                             rbrack: vec![],
                         })),
                         lparen: vec![],
@@ -2633,6 +2752,7 @@ fn pipe_expression_to_member_expression_function() {
                                 },
                                 name: "c".to_string()
                             }),
+                            optional: false,
                             rbrack: vec![],
                         })),
                         lparen: vec![],
@@ -2778,6 +2898,7 @@ fn member_expression_pipe_expression() {
                             },
                             name: "bar".to_string()
                         }),
+                        optional: false,
                         rbrack: vec![],
                     })),
                     call: CallExpr {
@@ -3028,6 +3149,8 @@ fn two_variables_for_two_froms() {
                         arguments: vec![],
                         rparen: vec![],
                     })),
+                    colon: vec![],
+                    ty: None,
                 })),
                 Statement::Variable(Box::new(VariableAssgn {
                     base: BaseNode {
@@ -3057,6 +3180,8 @@ fn two_variables_for_two_froms() {
                         arguments: vec![],
                         rparen: vec![],
                     })),
+                    colon: vec![],
+                    ty: None,
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -3351,6 +3476,7 @@ fn index_with_member_expressions() {
                             },
                             name: "b".to_string()
                         }),
+                        optional: false,
                         rbrack: vec![],
                     })),
                     lbrack: vec![],
@@ -3361,6 +3487,7 @@ fn index_with_member_expressions() {
                         },
                         value: "c".to_string()
                     }),
+                    optional: false,
                     rbrack: vec![],
                 }))
             }))],
@@ -3420,6 +3547,7 @@ fn index_with_member_with_call_expression() {
                                 },
                                 name: "b".to_string()
                             }),
+                            optional: false,
                             rbrack: vec![],
                         })),
                         lparen: vec![],
@@ -3434,6 +3562,7 @@ fn index_with_member_with_call_expression() {
                         },
                         value: "c".to_string()
                     }),
+                    optional: false,
                     rbrack: vec![],
                 }))
             }))],
@@ -3499,7 +3628,9 @@ fn expressions_with_function_calls() {
                         },
                         value: 10
                     })
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -3563,7 +3694,9 @@ fn conditional() {
                     tk_if: vec![],
                     tk_then: vec![],
                     tk_else: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -3749,7 +3882,9 @@ fn conditional_with_unary_logical_operators() {
                     tk_if: vec![],
                     tk_then: vec![],
                     tk_else: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -3971,6 +4106,8 @@ fn parse_testcase() {
                     },
                     value: 1,
                 }),
+                colon: vec![],
+                ty: None,
             }))],
             rbrace: vec![],
         },
@@ -4028,6 +4165,8 @@ fn parse_testcase_extends() {
                     },
                     value: 1,
                 }),
+                colon: vec![],
+                ty: None,
             }))],
             rbrace: vec![],
         },
@@ -4035,3 +4174,156 @@ fn parse_testcase_extends() {
 
     assert_eq!(expected, parsed.body);
 }
+
+#[test]
+fn next_statement_yields_each_statement() {
+    let src = "a = 1\nb = a + 2\n";
+    let mut p = Parser::new(src);
+    let loc = Locator::new(src);
+
+    let a = p.next_statement().unwrap().unwrap();
+    assert!(matches!(a, Variable(_)));
+    assert_eq!(loc.get(1, 1, 1, 6), a.base().location);
+
+    let b = p.next_statement().unwrap().unwrap();
+    assert!(matches!(b, Variable(_)));
+    assert_eq!(loc.get(2, 1, 2, 10), b.base().location);
+
+    assert!(p.next_statement().is_none());
+}
+
+#[test]
+fn next_statement_recovers_after_error() {
+    let src = "a = 1\nb = @ 2\nc = 3";
+    let mut p = Parser::new(src);
+    let loc = Locator::new(src);
+
+    assert!(p.next_statement().unwrap().is_ok());
+
+    let err = p.next_statement().unwrap().unwrap_err();
+    assert_eq!(2, err.location.start.line);
+    assert!(!err.errors.is_empty());
+
+    let c = p.next_statement().unwrap().unwrap();
+    assert_eq!(loc.get(3, 1, 3, 6), c.base().location);
+
+    assert!(p.next_statement().is_none());
+}
+
+#[test]
+fn invalid_identifier_character() {
+    let src = "x = 1\nab\u{200b}cd = 2";
+    let parsed = parse_string("".to_string(), src);
+    let err = ast::check::check(ast::walk::Node::File(&parsed))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains(
+            "invalid identifier \"ab\u{200b}cd\": character '\\u{200b}' at byte offset 8 cannot be part of an identifier"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn identifiers_compare_equal_after_normalization() {
+    let parsed = parse_string("".to_string(), "e\u{301}te = 1\n\u{e9}te");
+    match (&parsed.body[0], &parsed.body[1]) {
+        (Variable(assgn), Statement::Expr(stmt)) => match &stmt.expression {
+            Expression::Identifier(id) => assert_eq!(assgn.id.name, id.name),
+            expr => panic!("expected an identifier, found {:?}", expr),
+        },
+        stmts => panic!("unexpected statements {:?}", stmts),
+    }
+}
+
+#[test]
+fn parse_with_recovery_collects_errors() {
+    let src = "a = 1\nb = @ 2\nc = 3)\nd = a + 4";
+    let loc = Locator::new(src);
+    let (pkg, errors) = parse_with_recovery(src);
+
+    assert_eq!(
+        vec![2, 3],
+        errors
+            .iter()
+            .map(|err| err.location.start.line)
+            .collect::<Vec<_>>()
+    );
+    assert!(errors.iter().all(|err| !err.errors.is_empty()));
+
+    let body = &pkg.files[0].body;
+    assert_eq!(5, body.len());
+    assert!(matches!(body[2], Variable(_)));
+    assert!(matches!(body[3], Statement::Bad(_)));
+    assert_eq!(loc.get(4, 1, 4, 10), body[4].base().location);
+}
+
+#[test]
+fn parse_with_recovery_without_errors() {
+    let src = "import \"array\"\na = 1\n";
+    let (pkg, errors) = parse_with_recovery(src);
+    assert!(errors.is_empty());
+    assert_eq!(parse_string("".to_string(), src), pkg.files[0]);
+}
+
+#[test]
+fn parse_with_recovery_limits_errors() {
+    let src = "a = @\nb = @\nc = 1\nd = @\ne = @";
+    let (file, errors) = Parser::new(src)
+        .with_max_errors(2)
+        .parse_file_with_recovery("".to_string());
+
+    assert_eq!(
+        vec![1, 2, 4],
+        errors
+            .iter()
+            .map(|err| err.location.start.line)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["too many errors"],
+        errors[2]
+            .errors
+            .iter()
+            .map(|err| err.error.message.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(5, file.body.len(), "every statement is still parsed");
+
+    let (_, errors) = Parser::new(src)
+        .with_max_errors(4)
+        .parse_file_with_recovery("".to_string());
+    assert_eq!(4, errors.len());
+}
+
+#[test]
+fn parse_single_expression() {
+    let expr = parse_expression("a + 1");
+    match &expr {
+        Expression::Binary(binary) => {
+            assert_eq!(binary.operator, Operator::AdditionOperator);
+            assert_eq!(
+                (1, 1, 1, 6),
+                (
+                    expr.base().location.start.line,
+                    expr.base().location.start.column,
+                    expr.base().location.end.line,
+                    expr.base().location.end.column,
+                )
+            );
+        }
+        expr => panic!("expected a binary expression, got {:?}", expr),
+    }
+
+    let expr = parse_expression("a + 1 b");
+    expect_test::expect![[
+        r#"error @1:7-1:8: invalid expression: expected end of expression, got IDENT"#
+    ]]
+    .assert_eq(
+        &ast::check::check(ast::walk::Node::from_expr(&expr))
+            .unwrap_err()
+            .to_string(),
+    );
+}
@@ -80,7 +80,9 @@ fn arrow_function_called() {
                                 value: 1
                             })
                         }))),
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -231,7 +233,9 @@ fn arrow_function_return_map() {
                         })),
                         rparen: vec![],
                     }))),
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -426,7 +430,9 @@ fn arrow_function_with_default_arg() {
                             name: "n".to_string()
                         })
                     }))),
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -511,7 +517,9 @@ fn arrow_function_called_in_binary_expression() {
                                 value: 1
                             })
                         }))),
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -686,6 +694,7 @@ fn arrow_function_as_single_expression() {
                                 },
                                 value: "_measurement".to_string()
                             }),
+                            optional: false,
                             rbrack: vec![],
                         })),
                         right: Expression::StringLit(StringLit {
@@ -696,7 +705,9 @@ fn arrow_function_as_single_expression() {
                             value: "cpu".to_string()
                         })
                     }))),
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -799,8 +810,11 @@ fn arrow_function_as_block() {
                                         },
                                         value: "_measurement".to_string()
                                     }),
+                                    optional: false,
                                     rbrack: vec![],
-                                }))
+                                })),
+                                colon: vec![],
+                                ty: None
                             })),
                             Statement::Return(Box::new(ReturnStmt {
                                 base: BaseNode {
@@ -832,7 +846,9 @@ fn arrow_function_as_block() {
                         ],
                         rbrace: vec![],
                     }),
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
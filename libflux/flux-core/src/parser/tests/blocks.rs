@@ -0,0 +1,138 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::ast::tests::Locator;
+
+#[test]
+fn block_expression() {
+    let mut p = Parser::new(r#"x = { a = 1; a + 1 }"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 21),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Variable(Box::new(VariableAssgn {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 21),
+                    ..BaseNode::default()
+                },
+                id: Identifier {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 2),
+                        ..BaseNode::default()
+                    },
+                    name: "x".to_string()
+                },
+                init: Expression::Block(Box::new(BlockExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 5, 1, 21),
+                        ..BaseNode::default()
+                    },
+                    lbrace: vec![],
+                    body: vec![
+                        Statement::Variable(Box::new(VariableAssgn {
+                            base: BaseNode {
+                                location: loc.get(1, 7, 1, 12),
+                                ..BaseNode::default()
+                            },
+                            id: Identifier {
+                                base: BaseNode {
+                                    location: loc.get(1, 7, 1, 8),
+                                    ..BaseNode::default()
+                                },
+                                name: "a".to_string()
+                            },
+                            init: Expression::Integer(IntegerLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 11, 1, 12),
+                                    ..BaseNode::default()
+                                },
+                                value: 1
+                            }),
+                            colon: vec![],
+                            ty: None
+                        })),
+                        Statement::Expr(Box::new(ExprStmt {
+                            base: BaseNode {
+                                location: loc.get(1, 14, 1, 19),
+                                ..BaseNode::default()
+                            },
+                            expression: Expression::Binary(Box::new(BinaryExpr {
+                                base: BaseNode {
+                                    location: loc.get(1, 14, 1, 19),
+                                    ..BaseNode::default()
+                                },
+                                operator: Operator::AdditionOperator,
+                                left: Expression::Identifier(Identifier {
+                                    base: BaseNode {
+                                        location: loc.get(1, 14, 1, 15),
+                                        ..BaseNode::default()
+                                    },
+                                    name: "a".to_string()
+                                }),
+                                right: Expression::Integer(IntegerLit {
+                                    base: BaseNode {
+                                        location: loc.get(1, 18, 1, 19),
+                                        ..BaseNode::default()
+                                    },
+                                    value: 1
+                                })
+                            }))
+                        })),
+                    ],
+                    rbrace: vec![],
+                })),
+                colon: vec![],
+                ty: None
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
+#[test]
+fn block_expression_on_several_lines() {
+    let mut p = Parser::new(
+        r#"x = {
+    a = 1
+    b = a * 2
+    a + b
+}"#,
+    );
+    let parsed = p.parse_file("".to_string());
+    assert!(ast::check::check(ast::walk::Node::File(&parsed)).is_ok());
+    match &parsed.body[..] {
+        [Statement::Variable(assgn)] => match &assgn.init {
+            Expression::Block(block) => assert_eq!(3, block.body.len()),
+            init => panic!("expected a block expression, got {:?}", init),
+        },
+        body => panic!("expected a variable assignment, got {:?}", body),
+    }
+}
+
+#[test]
+fn braces_without_assignment_are_objects() {
+    for src in ["{a: 1}", "{a}", "{r with a: 1}", "{}"] {
+        let mut p = Parser::new(src);
+        let parsed = p.parse_file("".to_string());
+        match &parsed.body[..] {
+            [Statement::Expr(stmt)] => {
+                assert!(
+                    matches!(stmt.expression, Expression::Object(_)),
+                    "{}: {:?}",
+                    src,
+                    stmt.expression
+                )
+            }
+            body => panic!("{}: expected an expression statement, got {:?}", src, body),
+        }
+    }
+}
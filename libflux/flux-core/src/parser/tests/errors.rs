@@ -156,6 +156,7 @@ fn function_call_with_unbalanced_braces() {
                                                     },
                                                     name: "_value".to_string()
                                                 }),
+                                                optional: false,
                                                 rbrack: vec![],
                                             }))
                                         }))],
@@ -915,3 +916,18 @@ map(fn: (r) => ({ r with _value: if true and false then 1}) )
             .to_string(),
     );
 }
+
+#[test]
+fn unterminated_block_comment() {
+    let mut p = Parser::new(
+        r#"a = 1
+/* the comment
+b = 2"#,
+    );
+    let parsed = p.parse_file("".to_string());
+    expect_test::expect![[r#"error @2:1-3:6: invalid expression: unterminated block comment, expected */ to close it before the end of the file"#]].assert_eq(
+        &ast::check::check(ast::walk::Node::File(&parsed))
+            .unwrap_err()
+            .to_string(),
+    );
+}
@@ -159,6 +159,7 @@ fn from_with_filter_with_no_parens() {
                             },
                             name: "filter".to_string()
                         }),
+                        optional: false,
                         lbrack: vec![],
                         object: Expression::Call(Box::new(CallExpr {
                             base: BaseNode {
@@ -292,6 +293,7 @@ fn from_with_filter_with_no_parens() {
                                                         },
                                                         value: "other".to_string()
                                                     }),
+                                                    optional: false,
                                                     rbrack: vec![],
                                                 })),
                                                 right: Expression::StringLit(StringLit {
@@ -328,6 +330,7 @@ fn from_with_filter_with_no_parens() {
                                                         },
                                                         value: "this".to_string()
                                                     }),
+                                                    optional: false,
                                                     rbrack: vec![],
                                                 })),
                                                 right: Expression::StringLit(StringLit {
@@ -365,6 +368,7 @@ fn from_with_filter_with_no_parens() {
                                                     },
                                                     value: "these".to_string()
                                                 }),
+                                                optional: false,
                                                 rbrack: vec![],
                                             })),
                                             right: Expression::StringLit(StringLit {
@@ -1285,7 +1289,9 @@ join(tables:[a,b], on:["host"], fn: (a,b) => a["_field"] + b["_field"])"#,
                             }))],
                             rparen: vec![],
                         }
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Variable(Box::new(VariableAssgn {
                     base: BaseNode {
@@ -1406,7 +1412,9 @@ join(tables:[a,b], on:["host"], fn: (a,b) => a["_field"] + b["_field"])"#,
                             }))],
                             rparen: vec![],
                         }
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -1594,6 +1602,7 @@ join(tables:[a,b], on:["host"], fn: (a,b) => a["_field"] + b["_field"])"#,
                                                         },
                                                         value: "_field".to_string()
                                                     }),
+                                                    optional: false,
                                                     rbrack: vec![],
                                                 })),
                                                 right: Expression::Member(Box::new(MemberExpr {
@@ -1616,6 +1625,7 @@ join(tables:[a,b], on:["host"], fn: (a,b) => a["_field"] + b["_field"])"#,
                                                         },
                                                         value: "_field".to_string()
                                                     }),
+                                                    optional: false,
                                                     rbrack: vec![],
                                                 }))
                                             }
@@ -1804,6 +1814,7 @@ join(tables:[a,b], on:["t1"], fn: (a,b) => (a["_field"] - b["_field"]) / b["_fie
                                                 },
                                                 value: "_measurement".to_string(),
                                             }),
+                                            optional: false,
                                             rbrack: vec![],
                                         })),
                                         right: Expression::StringLit(StringLit {
@@ -1880,6 +1891,8 @@ join(tables:[a,b], on:["t1"], fn: (a,b) => (a["_field"] - b["_field"]) / b["_fie
                 rparen: vec![],
             },
         })),
+        colon: vec![],
+        ty: None,
     }));
 
     let stmt_b = Statement::Variable(Box::new(VariableAssgn {
@@ -2034,6 +2047,7 @@ join(tables:[a,b], on:["t1"], fn: (a,b) => (a["_field"] - b["_field"]) / b["_fie
                                                 },
                                                 value: "_measurement".to_string(),
                                             }),
+                                            optional: false,
                                             rbrack: vec![],
                                         })),
                                         right: Expression::StringLit(StringLit {
@@ -2110,6 +2124,8 @@ join(tables:[a,b], on:["t1"], fn: (a,b) => (a["_field"] - b["_field"]) / b["_fie
                 rparen: vec![],
             },
         })),
+        colon: vec![],
+        ty: None,
     }));
 
     let stmt_expr = Statement::Expr(Box::new(ExprStmt {
@@ -2309,6 +2325,7 @@ join(tables:[a,b], on:["t1"], fn: (a,b) => (a["_field"] - b["_field"]) / b["_fie
                                                 },
                                                 value: "_field".to_string(),
                                             }),
+                                            optional: false,
                                             rbrack: vec![],
                                         })),
                                         right: Expression::Member(Box::new(MemberExpr {
@@ -2331,6 +2348,7 @@ join(tables:[a,b], on:["t1"], fn: (a,b) => (a["_field"] - b["_field"]) / b["_fie
                                                 },
                                                 value: "_field".to_string(),
                                             }),
+                                            optional: false,
                                             rbrack: vec![],
                                         })),
                                     })),
@@ -2356,6 +2374,7 @@ join(tables:[a,b], on:["t1"], fn: (a,b) => (a["_field"] - b["_field"]) / b["_fie
                                         },
                                         value: "_field".to_string(),
                                     }),
+                                    optional: false,
                                     rbrack: vec![],
                                 })),
                             }))),
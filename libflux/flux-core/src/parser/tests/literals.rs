@@ -118,7 +118,7 @@ fn duration_literal_all_units() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 34),
+                location: loc.get(1, 1, 1, 33),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -127,7 +127,7 @@ fn duration_literal_all_units() {
             imports: vec![],
             body: vec![Statement::Variable(Box::new(VariableAssgn {
                 base: BaseNode {
-                    location: loc.get(1, 1, 1, 34),
+                    location: loc.get(1, 1, 1, 33),
                     ..BaseNode::default()
                 },
                 id: Identifier {
@@ -139,7 +139,7 @@ fn duration_literal_all_units() {
                 },
                 init: Expression::Duration(DurationLit {
                     base: BaseNode {
-                        location: loc.get(1, 7, 1, 34),
+                        location: loc.get(1, 7, 1, 33),
                         ..BaseNode::default()
                     },
                     values: vec![
@@ -184,7 +184,9 @@ fn duration_literal_all_units() {
                             unit: "ns".to_string()
                         }
                     ]
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -200,7 +202,7 @@ fn duration_literal_leading_zero() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 43),
+                location: loc.get(1, 1, 1, 42),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -209,7 +211,7 @@ fn duration_literal_leading_zero() {
             imports: vec![],
             body: vec![Statement::Variable(Box::new(VariableAssgn {
                 base: BaseNode {
-                    location: loc.get(1, 1, 1, 43),
+                    location: loc.get(1, 1, 1, 42),
                     ..BaseNode::default()
                 },
                 id: Identifier {
@@ -221,7 +223,7 @@ fn duration_literal_leading_zero() {
                 },
                 init: Expression::Duration(DurationLit {
                     base: BaseNode {
-                        location: loc.get(1, 7, 1, 43),
+                        location: loc.get(1, 7, 1, 42),
                         ..BaseNode::default()
                     },
                     values: vec![
@@ -266,7 +268,9 @@ fn duration_literal_leading_zero() {
                             unit: "ns".to_string()
                         }
                     ]
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -310,7 +314,9 @@ fn duration_literal_months() {
                         magnitude: 6,
                         unit: "mo".to_string()
                     }]
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -354,7 +360,9 @@ fn duration_literal_milliseconds() {
                         magnitude: 500,
                         unit: "ms".to_string()
                     }]
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -408,7 +416,68 @@ fn duration_literal_months_minutes_milliseconds() {
                             unit: "ms".to_string()
                         }
                     ]
-                })
+                }),
+                colon: vec![],
+                ty: None
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
+#[test]
+fn negative_duration_literal() {
+    let mut p = Parser::new(r#"dur = -1h30m"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 13),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Variable(Box::new(VariableAssgn {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 13),
+                    ..BaseNode::default()
+                },
+                id: Identifier {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 4),
+                        ..BaseNode::default()
+                    },
+                    name: "dur".to_string()
+                },
+                init: Expression::Unary(Box::new(UnaryExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 7, 1, 13),
+                        ..BaseNode::default()
+                    },
+                    operator: Operator::SubtractionOperator,
+                    argument: Expression::Duration(DurationLit {
+                        base: BaseNode {
+                            location: loc.get(1, 8, 1, 13),
+                            ..BaseNode::default()
+                        },
+                        values: vec![
+                            Duration {
+                                magnitude: 1,
+                                unit: "h".to_string()
+                            },
+                            Duration {
+                                magnitude: 30,
+                                unit: "m".to_string()
+                            }
+                        ]
+                    })
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -449,7 +518,9 @@ fn date_literal_in_the_default_location() {
                         ..BaseNode::default()
                     },
                     value: chrono::DateTime::parse_from_rfc3339("2018-11-29T00:00:00Z").unwrap()
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -562,7 +633,9 @@ fn date_time_literal() {
                         ..BaseNode::default()
                     },
                     value: chrono::DateTime::parse_from_rfc3339("2018-11-29T09:00:00Z").unwrap()
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -604,7 +677,9 @@ fn date_time_literal_with_fractional_seconds() {
                     },
                     value: chrono::DateTime::parse_from_rfc3339("2018-11-29T09:00:00.100000000Z")
                         .unwrap()
-                })
+                }),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -644,3 +719,193 @@ fn integer_literal_overflow() {
         },
     )
 }
+
+#[test]
+fn digit_separators() {
+    let mut p = Parser::new(r#"[1_000_000, 0.000_1, 1_000ms]"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 30),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Expr(Box::new(ExprStmt {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 30),
+                    ..BaseNode::default()
+                },
+                expression: Expression::Array(Box::new(ArrayExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 30),
+                        ..BaseNode::default()
+                    },
+                    lbrack: vec![],
+                    elements: vec![
+                        ArrayItem {
+                            expression: Expression::Integer(IntegerLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 2, 1, 11),
+                                    ..BaseNode::default()
+                                },
+                                value: 1000000
+                            }),
+                            comma: vec![],
+                        },
+                        ArrayItem {
+                            expression: Expression::Float(FloatLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 13, 1, 20),
+                                    ..BaseNode::default()
+                                },
+                                value: 0.0001
+                            }),
+                            comma: vec![],
+                        },
+                        ArrayItem {
+                            expression: Expression::Duration(DurationLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 22, 1, 29),
+                                    ..BaseNode::default()
+                                },
+                                values: vec![Duration {
+                                    magnitude: 1000,
+                                    unit: "ms".to_string()
+                                }]
+                            }),
+                            comma: vec![],
+                        },
+                    ],
+                    rbrack: vec![],
+                }))
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
+#[test]
+fn misplaced_digit_separators() {
+    for (src, lit) in [
+        ("x = 1_", "1_"),
+        ("x = 1__000", "1__000"),
+        ("x = 1_.5", "1_.5"),
+        ("x = 1._5", "1._5"),
+        ("x = 1.5_", "1.5_"),
+        ("x = 1_ms", "1_"),
+    ] {
+        let mut p = Parser::new(src);
+        let parsed = p.parse_file("".to_string());
+        let err = ast::check::check(ast::walk::Node::File(&parsed))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(&format!(
+                "invalid number literal \"{}\": underscores must separate digits",
+                lit
+            )),
+            "{}: {}",
+            src,
+            err
+        );
+    }
+}
+
+#[test]
+fn hex_and_binary_integer_literals() {
+    let mut p = Parser::new(r#"[0xFF, 0b1010]"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 15),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Expr(Box::new(ExprStmt {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 15),
+                    ..BaseNode::default()
+                },
+                expression: Expression::Array(Box::new(ArrayExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 15),
+                        ..BaseNode::default()
+                    },
+                    lbrack: vec![],
+                    elements: vec![
+                        ArrayItem {
+                            expression: Expression::Integer(IntegerLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 2, 1, 6),
+                                    ..BaseNode::default()
+                                },
+                                value: 255
+                            }),
+                            comma: vec![],
+                        },
+                        ArrayItem {
+                            expression: Expression::Integer(IntegerLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 8, 1, 14),
+                                    ..BaseNode::default()
+                                },
+                                value: 10
+                            }),
+                            comma: vec![],
+                        },
+                    ],
+                    rbrack: vec![],
+                }))
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
+#[test]
+fn hex_integer_literal_overflow() {
+    let mut p = Parser::new(r#"0x8000000000000000"#);
+    let parsed = p.parse_file("".to_string());
+    expect_test::expect![[
+        r#"error @1:1-1:19: invalid integer literal "0x8000000000000000": value out of range"#
+    ]]
+    .assert_eq(
+        &ast::check::check(ast::walk::Node::File(&parsed))
+            .unwrap_err()
+            .to_string(),
+    );
+}
+
+#[test]
+fn invalid_radix_literals() {
+    for (src, lit, reason) in [
+        ("x = 0x", "0x", "missing digits after the radix prefix"),
+        ("x = 0b102", "0b102", "invalid digit for the radix"),
+        ("x = 0xFG", "0xFG", "invalid digit for the radix"),
+        ("x = 0xF_", "0xF_", "underscores must separate digits"),
+    ] {
+        let mut p = Parser::new(src);
+        let parsed = p.parse_file("".to_string());
+        let err = ast::check::check(ast::walk::Node::File(&parsed))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(&format!("invalid number literal \"{}\": {}", lit, reason)),
+            "{}: {}",
+            src,
+            err
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::ast::tests::Locator;
+
+#[test]
+fn match_expression() {
+    let mut p = Parser::new(r#"x = match y { 1 => "one", _ => "many" }"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 40),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Variable(Box::new(VariableAssgn {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 40),
+                    ..BaseNode::default()
+                },
+                id: Identifier {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 2),
+                        ..BaseNode::default()
+                    },
+                    name: "x".to_string()
+                },
+                init: Expression::Match(Box::new(MatchExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 5, 1, 40),
+                        ..BaseNode::default()
+                    },
+                    scrutinee: Expression::Identifier(Identifier {
+                        base: BaseNode {
+                            location: loc.get(1, 11, 1, 12),
+                            ..BaseNode::default()
+                        },
+                        name: "y".to_string()
+                    }),
+                    lbrace: vec![],
+                    arms: vec![
+                        MatchArm {
+                            base: BaseNode {
+                                location: loc.get(1, 15, 1, 25),
+                                ..BaseNode::default()
+                            },
+                            pattern: Expression::Integer(IntegerLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 15, 1, 16),
+                                    ..BaseNode::default()
+                                },
+                                value: 1
+                            }),
+                            arrow: vec![],
+                            body: Expression::StringLit(StringLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 20, 1, 25),
+                                    ..BaseNode::default()
+                                },
+                                value: "one".to_string()
+                            }),
+                            comma: vec![],
+                        },
+                        MatchArm {
+                            base: BaseNode {
+                                location: loc.get(1, 27, 1, 38),
+                                ..BaseNode::default()
+                            },
+                            pattern: Expression::Identifier(Identifier {
+                                base: BaseNode {
+                                    location: loc.get(1, 27, 1, 28),
+                                    ..BaseNode::default()
+                                },
+                                name: "_".to_string()
+                            }),
+                            arrow: vec![],
+                            body: Expression::StringLit(StringLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 32, 1, 38),
+                                    ..BaseNode::default()
+                                },
+                                value: "many".to_string()
+                            }),
+                            comma: vec![],
+                        },
+                    ],
+                    rbrace: vec![],
+                })),
+                colon: vec![],
+                ty: None
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
+#[test]
+fn match_arms_on_separate_lines() {
+    let mut p = Parser::new(
+        r#"match b {
+    true => 1
+    false => 0
+}"#,
+    );
+    let parsed = p.parse_file("".to_string());
+    assert!(ast::check::check(ast::walk::Node::File(&parsed)).is_ok());
+    match &parsed.body[..] {
+        [Statement::Expr(stmt)] => match &stmt.expression {
+            Expression::Match(m) => assert_eq!(2, m.arms.len()),
+            expr => panic!("expected a match expression, got {:?}", expr),
+        },
+        body => panic!("expected an expression statement, got {:?}", body),
+    }
+}
+
+#[test]
+fn match_is_an_identifier_without_a_scrutinee() {
+    for src in [
+        "match = 1",
+        "match(x: 1)",
+        "match.a",
+        "match[0]",
+        "x = match\ny = 1",
+    ] {
+        let mut p = Parser::new(src);
+        let parsed = p.parse_file("".to_string());
+        assert!(
+            ast::check::check(ast::walk::Node::File(&parsed)).is_ok(),
+            "{}",
+            src
+        );
+        let mut has_match = false;
+        ast::walk::walk(
+            &mut |node: ast::walk::Node| {
+                has_match |= matches!(node, ast::walk::Node::MatchExpr(_));
+            },
+            ast::walk::Node::File(&parsed),
+        );
+        assert!(!has_match, "{}", src);
+    }
+}
@@ -91,7 +91,9 @@ fn map_member_expressions() {
                             }
                         ],
                         rbrace: vec![],
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -118,6 +120,7 @@ fn map_member_expressions() {
                             },
                             name: "key1".to_string()
                         }),
+                        optional: false,
                         rbrack: vec![],
                     }))
                 })),
@@ -146,6 +149,7 @@ fn map_member_expressions() {
                             },
                             value: "key2".to_string()
                         }),
+                        optional: false,
                         rbrack: vec![],
                     }))
                 }))
@@ -213,7 +217,9 @@ fn object_with_string_literal_key() {
                         comma: vec![],
                     }],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -302,7 +308,9 @@ fn object_with_mixed_keys() {
                         }
                     ],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -379,7 +387,9 @@ fn implicit_key_object_literal() {
                         }
                     ],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -458,7 +468,9 @@ fn implicit_key_object_literal_error() {
                         }
                     ],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -543,7 +555,9 @@ fn implicit_and_explicit_keys_object_literal_error() {
                         }
                     ],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -719,3 +733,116 @@ fn object_with_implicit_keys() {
         },
     )
 }
+
+#[test]
+fn spreads() {
+    let mut p = Parser::new(
+        r#"{...r, a: 1, ...s}
+[...xs, 4]"#,
+    );
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    let expressions: Vec<&Expression> = parsed
+        .body
+        .iter()
+        .map(|stmt| match stmt {
+            Statement::Expr(stmt) => &stmt.expression,
+            stmt => panic!("expected an expression statement, got {:?}", stmt),
+        })
+        .collect();
+
+    match expressions[0] {
+        Expression::Object(obj) => {
+            assert!(obj.with.is_none());
+            match &obj.properties[..] {
+                [first, Property { value: Some(_), .. }, last] => {
+                    match &first.key {
+                        PropertyKey::Spread(spread) => {
+                            assert_eq!(spread.base.location, loc.get(1, 2, 1, 6));
+                            assert!(
+                                matches!(&spread.argument, Expression::Identifier(id) if id.name == "r")
+                            );
+                        }
+                        key => panic!("expected a spread, got {:?}", key),
+                    }
+                    assert!(matches!(&last.key, PropertyKey::Spread(_)), "{:?}", last);
+                }
+                props => panic!("expected three properties, got {:?}", props),
+            }
+        }
+        expr => panic!("expected a record, got {:?}", expr),
+    }
+    match expressions[1] {
+        Expression::Array(array) => match &array.elements[..] {
+            [first, second] => {
+                match &first.expression {
+                    Expression::Spread(spread) => {
+                        assert_eq!(spread.base.location, loc.get(2, 2, 2, 7));
+                    }
+                    expr => panic!("expected a spread, got {:?}", expr),
+                }
+                assert!(matches!(second.expression, Expression::Integer(_)));
+            }
+            elements => panic!("expected two elements, got {:?}", elements),
+        },
+        expr => panic!("expected an array, got {:?}", expr),
+    }
+    ast::check::check(ast::walk::Node::File(&parsed)).unwrap_or_else(|err| panic!("{}", err));
+}
+
+#[test]
+fn destructuring_assignment() {
+    let mut p = Parser::new(r#"{a: x, "b c": y} = r"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    match &parsed.body[..] {
+        [Statement::Destructuring(assgn)] => {
+            assert_eq!(assgn.base.location, loc.get(1, 1, 1, 21));
+            let bindings: Vec<_> = assgn
+                .bindings()
+                .map(|(label, id)| (label.base().location.clone(), id.name.as_str()))
+                .collect();
+            assert_eq!(
+                bindings,
+                [(loc.get(1, 2, 1, 3), "x"), (loc.get(1, 8, 1, 13), "y")]
+            );
+            assert!(
+                matches!(&assgn.init, Expression::Identifier(id) if id.name == "r"),
+                "{:?}",
+                assgn.init
+            );
+        }
+        body => panic!("expected a single destructuring assignment, got {:?}", body),
+    }
+}
+
+#[test]
+fn destructuring_assignment_shorthand() {
+    let mut p = Parser::new("{a, b} = r\n{a, b}");
+    let parsed = p.parse_file("".to_string());
+    match &parsed.body[..] {
+        [Statement::Destructuring(assgn), Statement::Expr(_)] => {
+            let names: Vec<_> = assgn.bindings().map(|(_, id)| id.name.as_str()).collect();
+            assert_eq!(names, ["a", "b"]);
+        }
+        body => panic!(
+            "expected a destructuring assignment and a record, got {:?}",
+            body
+        ),
+    }
+}
+
+#[test]
+fn destructuring_assignment_invalid_pattern() {
+    let mut p = Parser::new(r#"{a: 1, ...r} = s"#);
+    let parsed = p.parse_file("".to_string());
+    expect_test::expect![[r#"
+        error @1:1-1:17: label a of a destructuring pattern must be bound to an identifier
+
+        error @1:1-1:17: a destructuring pattern cannot contain a spread"#]]
+    .assert_eq(
+        &ast::check::check(ast::walk::Node::File(&parsed))
+            .unwrap_err()
+            .to_string(),
+    );
+}
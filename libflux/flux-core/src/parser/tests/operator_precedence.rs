@@ -383,6 +383,68 @@ fn binary_operator_precedence_exponent() {
     )
 }
 
+#[test]
+fn binary_operator_exponentiation_is_right_associative() {
+    let mut p = Parser::new(r#"2 ** 3 ** 2"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 12),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Expr(Box::new(ExprStmt {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 12),
+                    ..BaseNode::default()
+                },
+                expression: Expression::Binary(Box::new(BinaryExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 12),
+                        ..BaseNode::default()
+                    },
+                    operator: Operator::ExponentiationOperator,
+                    left: Expression::Integer(IntegerLit {
+                        base: BaseNode {
+                            location: loc.get(1, 1, 1, 2),
+                            ..BaseNode::default()
+                        },
+                        value: 2
+                    }),
+                    right: Expression::Binary(Box::new(BinaryExpr {
+                        base: BaseNode {
+                            location: loc.get(1, 6, 1, 12),
+                            ..BaseNode::default()
+                        },
+                        operator: Operator::ExponentiationOperator,
+                        left: Expression::Integer(IntegerLit {
+                            base: BaseNode {
+                                location: loc.get(1, 6, 1, 7),
+                                ..BaseNode::default()
+                            },
+                            value: 3
+                        }),
+                        right: Expression::Integer(IntegerLit {
+                            base: BaseNode {
+                                location: loc.get(1, 11, 1, 12),
+                                ..BaseNode::default()
+                            },
+                            value: 2
+                        })
+                    })),
+                }))
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
 #[test]
 fn binary_operator_precedence_double_sum_with_parens() {
     let mut p = Parser::new(r#"1 + (2 + 3)"#);
@@ -693,6 +755,7 @@ k / l < m + n - o or p() <= q() or r >= s and not t =~ /a/ and u !~ /a/"#,
                                                         },
                                                         name: "a".to_string()
                                                     }),
+                                                    optional: false,
                                                     rbrack: vec![],
                                                 })),
                                                 right: Expression::Binary(Box::new(BinaryExpr {
@@ -728,6 +791,7 @@ k / l < m + n - o or p() <= q() or r >= s and not t =~ /a/ and u !~ /a/"#,
                                                                     name: "c".to_string()
                                                                 }
                                                             ),
+                                                            optional: false,
                                                             rbrack: vec![],
                                                         }
                                                     )),
@@ -1861,6 +1925,7 @@ fn member_expression_binary_expression() {
                             },
                             name: "_value".to_string()
                         }),
+                        optional: false,
                         rbrack: vec![],
                     })),
                     right: Expression::Float(FloatLit {
@@ -1917,7 +1982,9 @@ fn var_as_binary_expression_of_other_vars() {
                             ..BaseNode::default()
                         },
                         value: 1
-                    })
+                    }),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Variable(Box::new(VariableAssgn {
                     base: BaseNode {
@@ -1937,7 +2004,9 @@ fn var_as_binary_expression_of_other_vars() {
                             ..BaseNode::default()
                         },
                         value: 2
-                    })
+                    }),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Variable(Box::new(VariableAssgn {
                     base: BaseNode {
@@ -1971,7 +2040,9 @@ fn var_as_binary_expression_of_other_vars() {
                             },
                             name: "b".to_string()
                         })
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Variable(Box::new(VariableAssgn {
                     base: BaseNode {
@@ -1991,7 +2062,9 @@ fn var_as_binary_expression_of_other_vars() {
                             ..BaseNode::default()
                         },
                         name: "a".to_string()
-                    })
+                    }),
+                    colon: vec![],
+                    ty: None
                 }))
             ],
             eof: vec![],
@@ -2037,7 +2110,9 @@ fn var_as_unary_expression_of_other_vars() {
                             ..BaseNode::default()
                         },
                         value: 5
-                    })
+                    }),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Variable(Box::new(VariableAssgn {
                     base: BaseNode {
@@ -2064,7 +2139,9 @@ fn var_as_unary_expression_of_other_vars() {
                             },
                             name: "a".to_string()
                         })
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 }))
             ],
             eof: vec![],
@@ -2110,7 +2187,9 @@ fn var_as_both_binary_and_unary_expressions() {
                             ..BaseNode::default()
                         },
                         value: 5
-                    })
+                    }),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Variable(Box::new(VariableAssgn {
                     base: BaseNode {
@@ -2151,7 +2230,9 @@ fn var_as_both_binary_and_unary_expressions() {
                                 name: "a".to_string()
                             })
                         }))
-                    }))
+                    })),
+                    colon: vec![],
+                    ty: None
                 }))
             ],
             eof: vec![],
@@ -2197,7 +2278,9 @@ fn unary_expressions_within_logical_expression() {
                             ..BaseNode::default()
                         },
                         value: 5.0
-                    })
+                    }),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -2335,6 +2418,7 @@ fn unary_expression_with_member_expression() {
                             },
                             name: "b".to_string()
                         }),
+                        optional: false,
                         rbrack: vec![],
                     }))
                 }))
@@ -2378,6 +2462,7 @@ a = 5.0
                             location: loc.get(2, 1, 2, 2),
                             comments: vec![ast::Comment {
                                 text: String::from("// define a\n"),
+                                trailing: false,
                             }],
                             ..BaseNode::default()
                         },
@@ -2389,7 +2474,9 @@ a = 5.0
                             ..BaseNode::default()
                         },
                         value: 5.0
-                    })
+                    }),
+                    colon: vec![],
+                    ty: None
                 })),
                 Statement::Expr(Box::new(ExprStmt {
                     base: BaseNode {
@@ -2401,6 +2488,7 @@ a = 5.0
                             location: loc.get(4, 1, 6, 13),
                             comments: vec![ast::Comment {
                                 text: String::from("// or this\n"),
+                                trailing: false,
                             }],
                             ..BaseNode::default()
                         },
@@ -2422,6 +2510,7 @@ a = 5.0
                                         location: loc.get(4, 1, 4, 5),
                                         comments: vec![ast::Comment {
                                             text: String::from("// eval this\n"),
+                                            trailing: false,
                                         }],
                                         ..BaseNode::default()
                                     },
@@ -2861,3 +2950,136 @@ fn power_op() {
         },
     )
 }
+
+#[test]
+fn null_coalescing_op() {
+    let mut p = Parser::new(r#"a ?? b or c"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 12),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Expr(Box::new(ExprStmt {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 12),
+                    ..BaseNode::default()
+                },
+                expression: Expression::Binary(Box::new(BinaryExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 12),
+                        ..BaseNode::default()
+                    },
+                    operator: Operator::NullCoalescingOperator,
+                    left: Expression::Identifier(Identifier {
+                        base: BaseNode {
+                            location: loc.get(1, 1, 1, 2),
+                            ..BaseNode::default()
+                        },
+                        name: "a".to_string()
+                    }),
+                    right: Expression::Logical(Box::new(LogicalExpr {
+                        base: BaseNode {
+                            location: loc.get(1, 6, 1, 12),
+                            ..BaseNode::default()
+                        },
+                        operator: LogicalOperator::OrOperator,
+                        left: Expression::Identifier(Identifier {
+                            base: BaseNode {
+                                location: loc.get(1, 6, 1, 7),
+                                ..BaseNode::default()
+                            },
+                            name: "b".to_string()
+                        }),
+                        right: Expression::Identifier(Identifier {
+                            base: BaseNode {
+                                location: loc.get(1, 11, 1, 12),
+                                ..BaseNode::default()
+                            },
+                            name: "c".to_string()
+                        })
+                    }))
+                }))
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
+#[test]
+fn ternary_op() {
+    let mut p = Parser::new(r#"a == 1 ? b : c ? d : e"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    match &parsed.body[..] {
+        [Statement::Expr(stmt)] => match &stmt.expression {
+            Expression::Ternary(ternary) => {
+                assert_eq!(ternary.base.location, loc.get(1, 1, 1, 23));
+                assert!(
+                    matches!(ternary.test, Expression::Binary(_)),
+                    "{:?}",
+                    ternary.test
+                );
+                assert!(matches!(ternary.consequent, Expression::Identifier(_)));
+                assert!(
+                    matches!(ternary.alternate, Expression::Ternary(_)),
+                    "{:?}",
+                    ternary.alternate
+                );
+            }
+            expr => panic!("expected a ternary expression, got {:?}", expr),
+        },
+        body => panic!("expected a single expression statement, got {:?}", body),
+    }
+}
+
+#[test]
+fn guarded_member_expression() {
+    let mut p = Parser::new(r#"r?.a?.b.c"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    match &parsed.body[..] {
+        [Statement::Expr(stmt)] => match &stmt.expression {
+            Expression::Member(c) => {
+                assert_eq!(c.base.location, loc.get(1, 1, 1, 10));
+                assert!(!c.optional);
+                match &c.object {
+                    Expression::Member(b) => {
+                        assert_eq!(b.base.location, loc.get(1, 1, 1, 8));
+                        assert!(b.optional);
+                        assert!(
+                            matches!(&b.object, Expression::Member(a) if a.optional),
+                            "{:?}",
+                            b.object
+                        );
+                    }
+                    expr => panic!("expected a member expression, got {:?}", expr),
+                }
+            }
+            expr => panic!("expected a member expression, got {:?}", expr),
+        },
+        body => panic!("expected a single expression statement, got {:?}", body),
+    }
+}
+
+#[test]
+fn ternary_op_with_float_consequent() {
+    let mut p = Parser::new(r#"a ?.5 : 1.0"#);
+    let parsed = p.parse_file("".to_string());
+    match &parsed.body[..] {
+        [Statement::Expr(stmt)] => match &stmt.expression {
+            Expression::Ternary(ternary) => {
+                assert!(matches!(ternary.consequent, Expression::Float(_)));
+            }
+            expr => panic!("expected a ternary expression, got {:?}", expr),
+        },
+        body => panic!("expected a single expression statement, got {:?}", body),
+    }
+}
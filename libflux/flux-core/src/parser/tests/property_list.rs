@@ -102,7 +102,9 @@ fn property_list_missing_property() {
                         }
                     ],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -168,7 +170,9 @@ fn property_list_missing_key() {
                         comma: vec![],
                     }],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -228,7 +232,9 @@ fn property_list_missing_value() {
                         comma: vec![],
                     }],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -337,7 +343,9 @@ fn property_list_missing_comma() {
                         }
                     ],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -402,7 +410,9 @@ fn property_list_trailing_comma() {
                         comma: vec![],
                     }],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
@@ -510,7 +520,9 @@ fn property_list_bad_property() {
                         }
                     ],
                     rbrace: vec![],
-                }))
+                })),
+                colon: vec![],
+                ty: None
             }))],
             eof: vec![],
         },
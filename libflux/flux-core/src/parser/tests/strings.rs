@@ -30,6 +30,19 @@ fn parse_string_literal_invalid_string() {
     assert_eq!(errors, result.base.errors);
 }
 
+#[test]
+fn parse_raw_string_literal() {
+    let mut p = Parser::new(r#"r"C:\path\n""#);
+    let result = p.parse_string_literal();
+    assert_eq!(r"C:\path\n".to_string(), result.value);
+    assert_eq!(Vec::<String>::new(), result.base.errors);
+
+    let mut p = Parser::new(r##"r#"a "quoted" \d+"#"##);
+    let result = p.parse_string_literal();
+    assert_eq!(r#"a "quoted" \d+"#.to_string(), result.value);
+    assert_eq!(Vec::<String>::new(), result.base.errors);
+}
+
 #[test]
 fn string_interpolation_simple() {
     let mut p = Parser::new(r#""a + b = ${a + b}""#);
@@ -203,6 +216,7 @@ fn string_interpolation_array() {
                                                 },
                                                 name: "a".to_string(),
                                             }),
+                                            optional: false,
                                             rbrack: vec![],
                                         })),
                                     }),
@@ -213,6 +227,8 @@ fn string_interpolation_array() {
                     ],
                     rbrack: vec![],
                 },)),
+                colon: vec![],
+                ty: None,
             }),),],
             eof: vec![],
         }
@@ -517,6 +533,8 @@ fn bad_string_expression() {
                         parts: vec![],
                     }))),
                 })),
+                colon: vec![],
+                ty: None,
             }))],
             eof: vec![],
         },
@@ -532,7 +550,7 @@ fn string_with_utf_8() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 12),
+                location: loc.get(1, 1, 1, 6),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -541,12 +559,12 @@ fn string_with_utf_8() {
             imports: vec![],
             body: vec![Statement::Expr(Box::new(ExprStmt {
                 base: BaseNode {
-                    location: loc.get(1, 1, 1, 12),
+                    location: loc.get(1, 1, 1, 6),
                     ..BaseNode::default()
                 },
                 expression: Expression::StringLit(StringLit {
                     base: BaseNode {
-                        location: loc.get(1, 1, 1, 12),
+                        location: loc.get(1, 1, 1, 6),
                         ..BaseNode::default()
                     },
                     value: "日本語".to_string()
@@ -600,7 +618,7 @@ fn string_with_mixed_values() {
         parsed,
         File {
             base: BaseNode {
-                location: loc.get(1, 1, 1, 63),
+                location: loc.get(1, 1, 1, 59),
                 ..BaseNode::default()
             },
             name: "".to_string(),
@@ -609,12 +627,12 @@ fn string_with_mixed_values() {
             imports: vec![],
             body: vec![Statement::Expr(Box::new(ExprStmt {
                 base: BaseNode {
-                    location: loc.get(1, 1, 1, 63),
+                    location: loc.get(1, 1, 1, 59),
                     ..BaseNode::default()
                 },
                 expression: Expression::StringLit(StringLit {
                     base: BaseNode {
-                        location: loc.get(1, 1, 1, 63),
+                        location: loc.get(1, 1, 1, 59),
                         ..BaseNode::default()
                     },
                     value: "hello 日x本 日本語 µs".to_string()
@@ -696,3 +714,68 @@ string"
         },
     )
 }
+
+#[test]
+fn string_escape_sequences() {
+    for (src, value) in [
+        (r#""a\nb""#, "a\nb"),
+        (r#""a\rb""#, "a\rb"),
+        (r#""a\tb""#, "a\tb"),
+        (r#""a\"b""#, "a\"b"),
+        (r#""a\\b""#, "a\\b"),
+        (r#""a\${b}""#, "a${b}"),
+        (r#""caf\u00e9 \u00C9t\u00e9""#, "café Été"),
+        (r#""\u2603""#, "☃"),
+    ] {
+        match parse_expression(src) {
+            Expression::StringLit(lit) => {
+                assert_eq!(value, lit.value, "{}", src);
+                assert!(lit.base.errors.is_empty(), "{}: {:?}", src, lit.base.errors);
+            }
+            expr => panic!("{}: expected a string literal, got {:?}", src, expr),
+        }
+    }
+
+    match parse_expression(r#""\u2603 ${x}""#) {
+        Expression::StringExpr(expr) => match &expr.parts[..] {
+            [Text(text), Interpolated(_)] => {
+                assert_eq!("☃ ", text.value);
+                assert_eq!(
+                    (1, 2, 1, 9),
+                    (
+                        text.base.location.start.line,
+                        text.base.location.start.column,
+                        text.base.location.end.line,
+                        text.base.location.end.column,
+                    )
+                );
+            }
+            parts => panic!("expected a text and an interpolation, got {:?}", parts),
+        },
+        expr => panic!("expected a string expression, got {:?}", expr),
+    }
+}
+
+#[test]
+fn string_invalid_escape_sequences() {
+    let errors = |src: &str| {
+        ast::check::check(ast::walk::Node::from_expr(&parse_expression(src)))
+            .unwrap_err()
+            .to_string()
+    };
+
+    expect_test::expect![[r#"error @1:3-1:5: invalid escape sequence \q"#]]
+        .assert_eq(&errors(r#""a\qb""#));
+    expect_test::expect![[r#"error @2:3-2:5: invalid escape sequence \q"#]]
+        .assert_eq(&errors("\"line\n  \\q\""));
+    expect_test::expect![[
+        r#"error @1:2-1:6: invalid unicode escape \u12, expected 4 hexadecimal digits"#
+    ]]
+    .assert_eq(&errors(r#""\u12G4""#));
+    expect_test::expect![[
+        r#"error @1:2-1:8: invalid unicode escape \uD800, which is not a unicode scalar value"#
+    ]]
+    .assert_eq(&errors(r#""\uD800""#));
+    expect_test::expect![[r#"error @1:6-1:8: invalid escape sequence \q"#]]
+        .assert_eq(&errors(r#""${a}\q""#));
+}
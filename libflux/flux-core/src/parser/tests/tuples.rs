@@ -0,0 +1,166 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::ast::tests::Locator;
+
+#[test]
+fn tuple_literal() {
+    let mut p = Parser::new(r#"(1, "a")"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 9),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Expr(Box::new(ExprStmt {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 9),
+                    ..BaseNode::default()
+                },
+                expression: Expression::Tuple(Box::new(TupleExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 9),
+                        ..BaseNode::default()
+                    },
+                    lparen: vec![],
+                    elements: vec![
+                        ArrayItem {
+                            expression: Expression::Integer(IntegerLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 2, 1, 3),
+                                    ..BaseNode::default()
+                                },
+                                value: 1
+                            }),
+                            comma: vec![],
+                        },
+                        ArrayItem {
+                            expression: Expression::StringLit(StringLit {
+                                base: BaseNode {
+                                    location: loc.get(1, 5, 1, 8),
+                                    ..BaseNode::default()
+                                },
+                                value: "a".to_string()
+                            }),
+                            comma: vec![],
+                        },
+                    ],
+                    rparen: vec![],
+                }))
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
+#[test]
+fn tuple_of_identifiers() {
+    let mut p = Parser::new(r#"(a, b,)"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 8),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Expr(Box::new(ExprStmt {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 8),
+                    ..BaseNode::default()
+                },
+                expression: Expression::Tuple(Box::new(TupleExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 8),
+                        ..BaseNode::default()
+                    },
+                    lparen: vec![],
+                    elements: vec![
+                        ArrayItem {
+                            expression: Expression::Identifier(Identifier {
+                                base: BaseNode {
+                                    location: loc.get(1, 2, 1, 3),
+                                    ..BaseNode::default()
+                                },
+                                name: "a".to_string()
+                            }),
+                            comma: vec![],
+                        },
+                        ArrayItem {
+                            expression: Expression::Identifier(Identifier {
+                                base: BaseNode {
+                                    location: loc.get(1, 5, 1, 6),
+                                    ..BaseNode::default()
+                                },
+                                name: "b".to_string()
+                            }),
+                            comma: vec![],
+                        },
+                    ],
+                    rparen: vec![],
+                }))
+            }))],
+            eof: vec![],
+        },
+    )
+}
+
+#[test]
+fn tuple_index() {
+    let mut p = Parser::new(r#"t.1"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        File {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 4),
+                ..BaseNode::default()
+            },
+            name: "".to_string(),
+            metadata: "parser-type=rust".to_string(),
+            package: None,
+            imports: vec![],
+            body: vec![Statement::Expr(Box::new(ExprStmt {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 4),
+                    ..BaseNode::default()
+                },
+                expression: Expression::TupleIndex(Box::new(TupleIndexExpr {
+                    base: BaseNode {
+                        location: loc.get(1, 1, 1, 4),
+                        ..BaseNode::default()
+                    },
+                    tuple: Expression::Identifier(Identifier {
+                        base: BaseNode {
+                            location: loc.get(1, 1, 1, 2),
+                            ..BaseNode::default()
+                        },
+                        name: "t".to_string()
+                    }),
+                    dot: vec![],
+                    index: IntegerLit {
+                        base: BaseNode {
+                            location: loc.get(1, 2, 1, 4),
+                            ..BaseNode::default()
+                        },
+                        value: 1
+                    },
+                }))
+            }))],
+            eof: vec![],
+        },
+    )
+}
@@ -116,6 +116,7 @@ fn test_parse_type_expression() {
                         name: "Divisible".to_string(),
                     },
                 ],
+                labels: vec![],
             }],
         },
     )
@@ -331,6 +332,32 @@ fn test_parse_type_expression_time() {
     )
 }
 
+#[test]
+fn test_parse_type_expression_named_type_parameters() {
+    let mut p =
+        Parser::new(r#"(c: Col, v: Val, t: Table) => Val where Col: Label, Val: Equatable"#);
+    let parsed = p.parse_type_expression();
+    let func = match &parsed.monotype {
+        MonoType::Function(func) => func,
+        ty => panic!("expected a function type, found {:?}", ty),
+    };
+    let params: Vec<_> = func
+        .parameters
+        .iter()
+        .map(|param| match param {
+            ParameterType::Required { monotype, .. } => monotype,
+            param => panic!("expected a required parameter, found {:?}", param),
+        })
+        .collect();
+    assert!(matches!(params[0], MonoType::Tvar(tv) if tv.name.name == "Col"));
+    assert!(matches!(params[1], MonoType::Tvar(tv) if tv.name.name == "Val"));
+    assert!(
+        matches!(params[2], MonoType::Basic(ty) if ty.name.name == "Table"),
+        "names which are not constrained are named types"
+    );
+    assert!(matches!(&func.monotype, MonoType::Tvar(tv) if tv.name.name == "Val"));
+}
+
 #[test]
 fn test_parse_type_expression_duration() {
     let mut p = Parser::new(r#"duration"#);
@@ -581,7 +608,8 @@ fn test_parse_record_type_only_properties() {
                                 ..BaseNode::default()
                             },
                         }
-                    })
+                    }),
+                    optional: false,
                 },
                 PropertyType {
                     base: BaseNode {
@@ -608,7 +636,8 @@ fn test_parse_record_type_only_properties() {
                                 ..BaseNode::default()
                             },
                         }
-                    })
+                    }),
+                    optional: false,
                 }
             ]
         },)
@@ -727,6 +756,7 @@ fn test_parse_record_type_string_literal_property() {
                                 },
                             },
                         ),
+                        optional: false,
                     },
                     PropertyType {
                         base: BaseNode {
@@ -813,6 +843,7 @@ fn test_parse_record_type_string_literal_property() {
                                 },
                             },
                         ),
+                        optional: false,
                     },
                 ],
             },
@@ -859,12 +890,35 @@ fn test_parse_record_type_trailing_comma() {
                             ..BaseNode::default()
                         },
                     }
-                })
+                }),
+                optional: false,
             },]
         },)
     )
 }
 
+#[test]
+fn test_parse_record_type_optional_properties() {
+    let mut p = Parser::new(r#"{?a: int, b: string, ?c: bool}"#);
+    let parsed = p.parse_record_type();
+    let loc = Locator::new(&p.source[..]);
+    match parsed {
+        MonoType::Record(record) => {
+            assert_eq!(
+                record
+                    .properties
+                    .iter()
+                    .map(|p| p.optional)
+                    .collect::<Vec<_>>(),
+                vec![true, false, true]
+            );
+            assert_eq!(record.properties[0].base.location, loc.get(1, 2, 1, 9));
+            assert_eq!(record.properties[1].base.location, loc.get(1, 11, 1, 20));
+        }
+        _ => panic!("expected a record type, got {:?}", parsed),
+    }
+}
+
 #[test]
 fn test_parse_record_type_invalid() {
     let mut p = Parser::new(r#"{a b}"#);
@@ -909,7 +963,8 @@ fn test_parse_constraint_one_ident() {
                     ..BaseNode::default()
                 },
                 name: "date".to_string(),
-            }]
+            }],
+            labels: vec![],
         }],
     )
 }
@@ -1337,11 +1392,29 @@ fn test_parse_constraint_two_ident() {
                     },
                     name: "Subtractable".to_string(),
                 }
-            ]
+            ],
+            labels: vec![],
         }],
     )
 }
 
+#[test]
+fn test_parse_constraint_label_set() {
+    let mut p = Parser::new(r#"L: Label in {"start", "stop"}"#);
+    let parsed = p.parse_constraints();
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[0].base.location, loc.get(1, 1, 1, 30));
+    assert_eq!(
+        parsed[0]
+            .labels
+            .iter()
+            .map(|l| l.value.as_str())
+            .collect::<Vec<_>>(),
+        ["start", "stop"],
+    );
+}
+
 #[test]
 fn test_parse_constraint_two_con() {
     let mut p = Parser::new(r#"A: Addable, B: Subtractable"#);
@@ -1368,7 +1441,8 @@ fn test_parse_constraint_two_con() {
                         ..BaseNode::default()
                     },
                     name: "Addable".to_string(),
-                }]
+                }],
+                labels: vec![],
             },
             TypeConstraint {
                 base: BaseNode {
@@ -1388,7 +1462,8 @@ fn test_parse_constraint_two_con() {
                         ..BaseNode::default()
                     },
                     name: "Subtractable".to_string(),
-                }]
+                }],
+                labels: vec![],
             }
         ],
     )
@@ -1440,7 +1515,8 @@ fn test_parse_record_type_tvar_properties() {
                                 ..BaseNode::default()
                             },
                         }
-                    })
+                    }),
+                    optional: false,
                 },
                 PropertyType {
                     base: BaseNode {
@@ -1467,7 +1543,8 @@ fn test_parse_record_type_tvar_properties() {
                                 ..BaseNode::default()
                             },
                         }
-                    })
+                    }),
+                    optional: false,
                 }
             ]
         },)
@@ -1484,3 +1561,137 @@ fn test_parse_record_unclosed_error() {
             .to_string(),
     );
 }
+
+#[test]
+fn test_parse_type_alias() {
+    let mut p = Parser::new(r#"type Id = int"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed.body,
+        vec![Statement::TypeAlias(Box::new(TypeAliasStmt {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 14),
+                ..BaseNode::default()
+            },
+            id: Identifier {
+                base: BaseNode {
+                    location: loc.get(1, 6, 1, 8),
+                    ..BaseNode::default()
+                },
+                name: "Id".to_string(),
+            },
+            params: vec![],
+            ty: MonoType::Basic(NamedType {
+                base: BaseNode {
+                    location: loc.get(1, 11, 1, 14),
+                    ..BaseNode::default()
+                },
+                name: Identifier {
+                    base: BaseNode {
+                        location: loc.get(1, 11, 1, 14),
+                        ..BaseNode::default()
+                    },
+                    name: "int".to_string(),
+                },
+            }),
+        }))]
+    )
+}
+
+#[test]
+fn test_parse_type_alias_parameters() {
+    let mut p = Parser::new(r#"type Pair = (A, B) => {first: A, second: B}"#);
+    let parsed = p.parse_file("".to_string());
+    match &parsed.body[..] {
+        [Statement::TypeAlias(alias)] => {
+            let params: Vec<_> = alias.params.iter().map(|p| p.name.as_str()).collect();
+            assert_eq!(params, ["A", "B"]);
+            assert!(matches!(alias.ty, MonoType::Record(_)), "{:?}", alias.ty);
+        }
+        body => panic!("expected a single type alias, got {:?}", body),
+    }
+}
+
+#[test]
+fn test_parse_type_alias_function_type() {
+    let mut p = Parser::new(r#"type F = (a: A) => A"#);
+    let parsed = p.parse_file("".to_string());
+    match &parsed.body[..] {
+        [Statement::TypeAlias(alias)] => {
+            assert!(alias.params.is_empty());
+            assert!(matches!(alias.ty, MonoType::Function(_)), "{:?}", alias.ty);
+        }
+        body => panic!("expected a single type alias, got {:?}", body),
+    }
+}
+
+#[test]
+fn test_parse_type_as_identifier() {
+    let mut p = Parser::new("type = 1\ntypes.isType(v: 1, type: \"int\")");
+    let parsed = p.parse_file("".to_string());
+    assert!(matches!(parsed.body[0], Statement::Variable(_)));
+    assert!(matches!(parsed.body[1], Statement::Expr(_)));
+}
+
+#[test]
+fn test_parse_applied_type() {
+    let mut p = Parser::new(r#"Pair[int]"#);
+    let parsed = p.parse_monotype();
+    let loc = Locator::new(&p.source[..]);
+    assert_eq!(
+        parsed,
+        MonoType::Applied(Box::new(AppliedType {
+            base: BaseNode {
+                location: loc.get(1, 1, 1, 10),
+                ..BaseNode::default()
+            },
+            name: Identifier {
+                base: BaseNode {
+                    location: loc.get(1, 1, 1, 5),
+                    ..BaseNode::default()
+                },
+                name: "Pair".to_string(),
+            },
+            arguments: vec![MonoType::Basic(NamedType {
+                base: BaseNode {
+                    location: loc.get(1, 6, 1, 9),
+                    ..BaseNode::default()
+                },
+                name: Identifier {
+                    base: BaseNode {
+                        location: loc.get(1, 6, 1, 9),
+                        ..BaseNode::default()
+                    },
+                    name: "int".to_string(),
+                },
+            })],
+        }))
+    )
+}
+
+#[test]
+fn test_parse_annotated_variable() {
+    let mut p = Parser::new(r#"x : [A] where A: Numeric = [1, 2]"#);
+    let parsed = p.parse_file("".to_string());
+    let loc = Locator::new(&p.source[..]);
+    match &parsed.body[..] {
+        [Statement::Variable(assgn)] => {
+            assert_eq!(assgn.base.location, loc.get(1, 1, 1, 34));
+            assert_eq!(assgn.id.name, "x");
+            let ty = assgn.ty.as_ref().expect("expected a type annotation");
+            assert!(
+                matches!(ty.monotype, MonoType::Array(_)),
+                "{:?}",
+                ty.monotype
+            );
+            assert_eq!(ty.constraints.len(), 1);
+            assert!(
+                matches!(assgn.init, Expression::Array(_)),
+                "{:?}",
+                assgn.init
+            );
+        }
+        body => panic!("expected a single variable assignment, got {:?}", body),
+    }
+}
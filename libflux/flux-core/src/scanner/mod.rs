@@ -1,10 +1,12 @@
 //! Token scanner.
 
-use std::{collections::HashMap, str, vec::Vec};
+use std::{str, vec::Vec};
 
 use derive_more::Display;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_xid::UnicodeXID;
 
-use crate::ast::Comment;
+use crate::ast::{self, Comment};
 
 #[rustfmt::skip]
 #[allow(warnings, missing_docs)]
@@ -14,11 +16,154 @@ use scanner_generated::scan;
 mod token;
 pub use token::TokenType;
 
-use super::DefaultHasher;
-
 #[cfg(test)]
 mod tests;
 
+// Returns true if `t` is a single character which the generated scanner does not know, but which
+// may start an identifier (`XID_Start`).
+fn is_identifier_start(t: &Token) -> bool {
+    let mut chars = t.lit.chars();
+    t.tok == TokenType::Illegal
+        && matches!((chars.next(), chars.next()), (Some(c), None) if c.is_xid_start())
+}
+
+/// Returns the first character of the identifier `lit` which cannot be part of an identifier,
+/// along with its byte offset in `lit`.
+pub fn invalid_identifier_char(lit: &str) -> Option<(usize, char)> {
+    lit.char_indices()
+        .find(|(i, c)| !(c.is_xid_continue() || (*i == 0 && c.is_xid_start())))
+}
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+// Returns the byte offsets in `s` at which a column starts. Columns approximate the characters
+// that are displayed: combining marks, variation selectors and emoji modifiers are displayed as
+// part of the preceding character, as is a character joined to it by a zero width joiner.
+fn column_starts(s: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut prev = None;
+    s.char_indices().filter_map(move |(i, c)| {
+        let joined = prev == Some(ZERO_WIDTH_JOINER);
+        prev = Some(c);
+        let extends = is_combining_mark(c)
+            || c == ZERO_WIDTH_JOINER
+            || ('\u{FE00}'..='\u{FE0F}').contains(&c)
+            || ('\u{1F3FB}'..='\u{1F3FF}').contains(&c);
+        if joined || extends {
+            None
+        } else {
+            Some(i)
+        }
+    })
+}
+
+// Returns the number of columns spanned by `data`.
+pub(crate) fn columns(data: &[u8]) -> u32 {
+    if data.is_ascii() {
+        return data.len() as u32;
+    }
+    column_starts(&String::from_utf8_lossy(data)).count() as u32
+}
+
+/// Returns the byte offset in `line` at which `column` starts. Columns past the end of the line
+/// map to the length of the line.
+pub fn column_offset(line: &str, column: u32) -> usize {
+    column_starts(line)
+        .nth((column as usize).saturating_sub(1))
+        .unwrap_or(line.len())
+}
+
+const DURATION_UNITS: [&str; 11] = ["y", "mo", "w", "d", "h", "m", "s", "ms", "us", "µs", "ns"];
+
+// Returns the length of the longest numeric literal at the start of `s` and its token type. Digits
+// may be separated by single underscores and integers may be written in hexadecimal (`0x`) or
+// binary (`0b`), which the generated scanner does not know. As in the generated scanner the longest
+// match wins and ties go to valid literals, so a malformed number such as `1__000` or `0x` is
+// returned whole as an `Illegal` token instead of being split into several tokens.
+fn scan_number(s: &[u8]) -> (usize, TokenType) {
+    let decimal = digits(s, 0, u8::is_ascii_digit);
+    let int = match s {
+        [b'0', b'x' | b'X', ..] => digits(s, 2, u8::is_ascii_hexdigit).max(decimal),
+        [b'0', b'b' | b'B', ..] => digits(s, 2, |c| matches!(*c, b'0' | b'1')).max(decimal),
+        _ => decimal,
+    };
+    let float = match decimal {
+        Some(end) if s.get(end) == Some(&b'.') => {
+            Some(digits(s, end + 1, u8::is_ascii_digit).unwrap_or(end + 1))
+        }
+        None if s.first() == Some(&b'.') => digits(s, 1, u8::is_ascii_digit),
+        _ => None,
+    };
+    [
+        (int, TokenType::Int),
+        (float, TokenType::Float),
+        (duration_len(s), TokenType::Duration),
+        (bad_number_len(s), TokenType::Illegal),
+    ]
+    .into_iter()
+    .fold((0, TokenType::Illegal), |longest, (len, tok)| match len {
+        Some(len) if len > longest.0 => (len, tok),
+        _ => longest,
+    })
+}
+
+// Returns the end of the run of digits starting at `i`, in which single underscores may separate
+// the digits.
+fn digits(s: &[u8], i: usize, is_digit: fn(&u8) -> bool) -> Option<usize> {
+    if !s.get(i).map_or(false, is_digit) {
+        return None;
+    }
+    let mut end = i + 1;
+    loop {
+        match s.get(end) {
+            Some(c) if is_digit(c) => end += 1,
+            Some(b'_') if s.get(end + 1).map_or(false, is_digit) => end += 2,
+            _ => return Some(end),
+        }
+    }
+}
+
+fn duration_len(s: &[u8]) -> Option<usize> {
+    let mut len = None;
+    let mut i = 0;
+    while let Some(end) = digits(s, i, u8::is_ascii_digit) {
+        match DURATION_UNITS
+            .iter()
+            .filter(|unit| s[end..].starts_with(unit.as_bytes()))
+            .map(|unit| unit.len())
+            .max()
+        {
+            Some(unit) => {
+                i = end + unit;
+                len = Some(i);
+            }
+            None => break,
+        }
+    }
+    len
+}
+
+// Returns the length of the number at the start of `s` when underscores are allowed anywhere
+// after its first digit, and any letter after a `0x` or `0b` prefix.
+fn bad_number_len(s: &[u8]) -> Option<usize> {
+    if let [b'0', b'x' | b'X' | b'b' | b'B', rest @ ..] = s {
+        return Some(
+            2 + rest
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == b'_')
+                .count(),
+        );
+    }
+    let is_part = |c: &&u8| c.is_ascii_digit() || **c == b'_';
+    if !s.first()?.is_ascii_digit() {
+        return None;
+    }
+    let mut len = s.iter().take_while(is_part).count();
+    if s.get(len) == Some(&b'.') {
+        len += 1 + s[len + 1..].iter().take_while(is_part).count();
+    }
+    Some(len)
+}
+
 /// Represents a Flux scanner and its state during compilation.
 pub struct Scanner<'a> {
     data: &'a [u8],
@@ -32,7 +177,13 @@ pub struct Scanner<'a> {
     checkpoint_line: i32,
     checkpoint_last_newline: i32,
     token: TokenType,
-    positions: HashMap<Position, u32, DefaultHasher>,
+    // The start and end positions of the scanned tokens ordered by their byte offset. Several
+    // offsets may share a line and column, as columns do not advance over combining characters.
+    positions: Vec<Position>,
+    last_token_line: u32,
+    // The line start, byte offset and column of the last computed position, so that computing the
+    // columns of consecutive tokens on a line does not rescan the line from its start.
+    last_column: (usize, usize, u32),
 
     /// Comments
     pub comments: Vec<Comment>,
@@ -41,9 +192,12 @@ pub struct Scanner<'a> {
 /// A position in source code.
 #[derive(Debug, PartialEq, Copy, Clone, Hash)]
 pub struct Position {
+    /// Offset in bytes from the beginning of the source.
+    pub offset: u32,
     /// Line number.
     pub line: u32,
-    /// Column number.
+    /// Column number. Columns count displayed characters rather than bytes, so a multi-byte
+    /// character advances the column by one and combining characters do not advance it at all.
     pub column: u32,
 }
 
@@ -57,14 +211,10 @@ pub struct Token {
     pub tok: TokenType,
     /// String representation of token.
     pub lit: String,
-    /// Starting location of token, offset in characters from the beginning of the source.
-    pub start_offset: u32,
-    /// Ending location of token, offset in characters from the beginning of the source.
-    pub end_offset: u32,
     /// Starting position of token in the source.
-    pub start_pos: Position,
+    pub start: Position,
     /// Ending position of token in the source.
-    pub end_pos: Position,
+    pub end: Position,
     /// Comments.
     pub comments: Vec<Comment>,
 }
@@ -85,7 +235,9 @@ impl<'a> Scanner<'a> {
             checkpoint: 0,
             checkpoint_line: 1,
             checkpoint_last_newline: 0,
-            positions: HashMap::default(),
+            positions: Vec::new(),
+            last_token_line: 0,
+            last_column: (0, 0, 1),
             comments: Vec::new(),
         }
     }
@@ -126,7 +278,7 @@ impl<'a> Scanner<'a> {
                 &mut token_end_col,
             )
         };
-        let t = if error != 0 {
+        let mut t = if error != 0 {
             // Execution failed meaning we hit a pattern that we don't support and
             // doesn't produce a token. Use the unicode library to decode the next character
             // in the sequence so we don't break up any unicode tokens.
@@ -146,16 +298,12 @@ impl<'a> Scanner<'a> {
                     Token {
                         tok: TokenType::Illegal,
                         lit: nc.to_string(),
-                        start_offset: token_start as u32,
-                        end_offset: (token_start + size as i32) as u32,
-                        start_pos: Position {
-                            line: token_start_line as u32,
-                            column: token_start_col as u32,
-                        },
-                        end_pos: Position {
-                            line: token_start_line as u32,
-                            column: (token_start_col + size as i32) as u32,
-                        },
+                        start: self.position(token_start, token_start_line, token_start_col),
+                        end: self.position(
+                            token_start + size as i32,
+                            token_start_line,
+                            token_start_col + size as i32,
+                        ),
                         comments: vec![],
                     }
                 }
@@ -174,44 +322,261 @@ impl<'a> Scanner<'a> {
             Token {
                 tok: self.token,
                 lit: String::from(lit),
-                start_offset: token_start as u32,
-                end_offset: token_end as u32,
-                start_pos: Position {
-                    line: token_start_line as u32,
-                    column: token_start_col as u32,
-                },
-                end_pos: Position {
-                    line: token_end_line as u32,
-                    column: token_end_col as u32,
-                },
+                start: self.position(token_start, token_start_line, token_start_col),
+                end: self.position(token_end, token_end_line, token_end_col),
                 comments: vec![],
             }
         };
 
-        // Record mapping from position to offset so clients
-        // may later go from position to offset by calling offset()
-        self.positions.insert(t.start_pos, t.start_offset);
-        self.positions.insert(t.end_pos, t.end_offset);
+        // Identifiers are not scanned in string expressions
+        if mode != 2 && (t.tok == TokenType::Ident || is_identifier_start(&t)) {
+            t = self.finish_identifier(t);
+        }
+        if mode != 2 && t.tok == TokenType::Ident && t.lit == "r" && self.at_raw_string() {
+            t = self.finish_raw_string(t);
+        }
+        if mode == 2 && t.tok == TokenType::Illegal && t.lit == "\\" {
+            t = self.finish_escape(t);
+        }
+        if mode != 2
+            && matches!(
+                t.tok,
+                TokenType::Int | TokenType::Float | TokenType::Duration
+            )
+        {
+            t = self.finish_number(t);
+        }
+        if mode != 2 && self.at_block_comment(&t) {
+            t = self.finish_block_comment(t);
+        }
+        if t.tok == TokenType::QuestionMark && self.peek() == Some(b'?') {
+            self.extend(&mut t, 1);
+            t.tok = TokenType::NullCoalesce;
+        }
+        if t.tok == TokenType::Mul && self.peek() == Some(b'*') {
+            self.extend(&mut t, 1);
+            t.tok = TokenType::Exp;
+        }
+        if mode != 2 && t.tok == TokenType::Illegal && t.lit == ";" {
+            t.tok = TokenType::Semicolon;
+        }
+
+        // Record the positions so clients may later go from position to offset by calling
+        // offset() and end_offset()
+        self.record_position(t.start);
+        self.record_position(t.end);
+
+        t
+    }
+
+    fn record_position(&mut self, pos: Position) {
+        // Tokens are scanned in order, except when they are scanned again after `unread`
+        if let Err(i) = self
+            .positions
+            .binary_search_by_key(&pos.offset, |p| p.offset)
+        {
+            self.positions.insert(i, pos);
+        }
+    }
+
+    // Returns the position of the byte `offset`, given its line and the column in bytes computed
+    // by the generated scanner.
+    fn position(&mut self, offset: i32, line: i32, byte_column: i32) -> Position {
+        let offset = offset as usize;
+        let line_start = offset + 1 - byte_column as usize;
+        let (from, column) = match self.last_column {
+            (start, from, column) if start == line_start && from <= offset => (from, column),
+            _ => (line_start, 1),
+        };
+        let column = column + columns(&self.data[from..offset]);
+        self.last_column = (line_start, offset, column);
+        Position {
+            offset: offset as u32,
+            line: line as u32,
+            column,
+        }
+    }
+
+    // Extends the identifier `t` with the characters following it which continue an identifier
+    // (`XID_Continue`) but are unknown to the generated scanner, and normalizes it to NFC so that
+    // identifiers which look the same compare equal. An identifier containing characters which
+    // cannot be part of an identifier is returned whole as an `Illegal` token.
+    fn finish_identifier(&mut self, mut t: Token) -> Token {
+        let rest = str::from_utf8(&self.data[self.p as usize..]).unwrap_or("");
+        let mut len = 0;
+        let mut end = 0;
+        let mut invalid = false;
+        let mut pending_invalid = false;
+        for c in rest.chars() {
+            if c.is_xid_continue() {
+                invalid |= pending_invalid;
+                pending_invalid = false;
+                len += c.len_utf8();
+                end = len;
+            } else if !c.is_ascii() && !c.is_whitespace() {
+                // Only part of the identifier if more identifier characters follow
+                pending_invalid = true;
+                len += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+
+        let end_offset = t.end.offset as usize;
+        self.p += end as i32;
+        t.end.offset += end as u32;
+        t.end.column += columns(&self.data[end_offset..end_offset + end]);
+        let lit = str::from_utf8(&self.data[t.start.offset as usize..t.end.offset as usize])
+            .unwrap_or("");
+        if invalid {
+            t.tok = TokenType::Illegal;
+            t.lit = lit.to_string();
+        } else {
+            t.tok = TokenType::Ident;
+            t.lit = if unicode_normalization::is_nfc(lit) {
+                lit.to_string()
+            } else {
+                lit.nfc().collect()
+            };
+        }
+        t
+    }
+
+    // Extends the backslash `t` with the escape sequence following it, which the generated scanner
+    // does not know, and turns it into a text token. The sequence is validated when the parser
+    // decodes the text, so that an invalid sequence is reported along with its location. A
+    // unicode escape, `\u` followed by up to 4 hexadecimal digits, is kept whole.
+    fn finish_escape(&mut self, mut t: Token) -> Token {
+        let rest = str::from_utf8(&self.data[self.p as usize..]).unwrap_or("");
+        let mut chars = rest.chars();
+        let len = match chars.next() {
+            Some('u') => 1 + chars.take(4).take_while(char::is_ascii_hexdigit).count(),
+            Some(c) if c != '\n' && c != '\r' => c.len_utf8(),
+            _ => 0,
+        };
+
+        let end_offset = t.end.offset as usize;
+        self.p += len as i32;
+        t.end.offset += len as u32;
+        t.end.column += columns(&self.data[end_offset..end_offset + len]);
+        t.tok = TokenType::Text;
+        t.lit = str::from_utf8(&self.data[t.start.offset as usize..t.end.offset as usize])
+            .unwrap_or("")
+            .to_string();
+        t
+    }
 
+    // Rescans the number `t` with the forms of numeric literals which the generated scanner does
+    // not know, see `scan_number`.
+    fn finish_number(&mut self, mut t: Token) -> Token {
+        let start = t.start.offset as usize;
+        let scanned = t.end.offset as usize - start;
+        let (len, tok) = scan_number(&self.data[start..]);
+        if len > scanned {
+            self.extend(&mut t, len - scanned);
+            t.tok = tok;
+        }
         t
     }
 
+    // Returns whether a raw string, `r"..."` or `r#"..."#`, continues the `r` just scanned.
+    fn at_raw_string(&self) -> bool {
+        let rest = &self.data[self.p as usize..];
+        rest.starts_with(b"\"") || rest.starts_with(b"#\"")
+    }
+
+    // Extends the `r` in `t` with the raw string following it, which the generated scanner does
+    // not know. Every byte up to the closing `"`, or `"#` for the `#` delimited form which may
+    // contain double quotes, is taken verbatim. An unterminated raw string is returned up to the
+    // end of the input as an `Illegal` token.
+    fn finish_raw_string(&mut self, mut t: Token) -> Token {
+        let rest = &self.data[self.p as usize..];
+        let (open, close): (usize, &[u8]) = if rest.starts_with(b"#") {
+            (2, b"\"#")
+        } else {
+            (1, b"\"")
+        };
+        let (len, tok) = match rest[open..].windows(close.len()).position(|w| w == close) {
+            Some(end) => (open + end + close.len(), TokenType::String),
+            None => (rest.len(), TokenType::Illegal),
+        };
+        self.extend(&mut t, len);
+        t.tok = tok;
+        t
+    }
+
+    // Returns whether `t` is the `/` of a block comment, or a regex which the generated scanner
+    // scanned from the `/*` of a block comment.
+    fn at_block_comment(&self, t: &Token) -> bool {
+        matches!(t.tok, TokenType::Div | TokenType::Regex)
+            && self.data.get(t.start.offset as usize + 1) == Some(&b'*')
+    }
+
+    // Rescans the `/` of `t` as the start of a block comment, which the generated scanner does
+    // not know. Block comments do not nest, the first `*/` ends the comment, and as for line
+    // comments the newline directly following the comment is part of it. An unterminated block
+    // comment is returned up to the end of the input as an `Illegal` token.
+    fn finish_block_comment(&mut self, mut t: Token) -> Token {
+        // A regex never spans several lines, so only the offset and column need to be reset
+        self.p = t.start.offset as i32 + 1;
+        t.end = Position {
+            offset: t.start.offset + 1,
+            line: t.start.line,
+            column: t.start.column + 1,
+        };
+        let rest = &self.data[self.p as usize..];
+        let (len, tok) = match rest[1..].windows(2).position(|w| w == b"*/") {
+            Some(end) => {
+                let len = end + 3;
+                let newline = usize::from(rest.get(len) == Some(&b'\n'));
+                (len + newline, TokenType::Comment)
+            }
+            None => (rest.len(), TokenType::Illegal),
+        };
+        self.extend(&mut t, len);
+        t.tok = tok;
+        t
+    }
+
+    // Returns the byte following the last scanned token.
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.p as usize).copied()
+    }
+
+    // Extends `t` with the `len` bytes following it, for tokens which the generated scanner
+    // does not know but which start with a token that it does. The bytes may span several
+    // lines, the line count is then kept up to date for the generated scanner.
+    fn extend(&mut self, t: &mut Token, len: usize) {
+        let end_offset = t.end.offset as usize;
+        let bytes = &self.data[end_offset..end_offset + len];
+        self.p += len as i32;
+        t.end.offset += len as u32;
+        match bytes.iter().rposition(|&b| b == b'\n') {
+            Some(newline) => {
+                self.cur_line += bytes.iter().filter(|&&b| b == b'\n').count() as i32;
+                self.last_newline = (end_offset + newline + 1) as i32;
+                t.end.line = self.cur_line as u32;
+                t.end.column =
+                    columns(&self.data[self.last_newline as usize..t.end.offset as usize]) + 1;
+            }
+            None => t.end.column += columns(bytes),
+        }
+        t.lit = str::from_utf8(&self.data[t.start.offset as usize..t.end.offset as usize])
+            .unwrap_or("")
+            .to_string();
+    }
+
     fn get_eof_token(&self) -> Token {
-        let data_len = self.data.len() as u32;
-        let column = self.eof as u32 - self.last_newline as u32 + 1;
+        let position = Position {
+            offset: self.data.len() as u32,
+            line: self.cur_line as u32,
+            column: columns(&self.data[self.last_newline as usize..self.eof as usize]) + 1,
+        };
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: data_len,
-            end_offset: data_len,
-            start_pos: Position {
-                line: self.cur_line as u32,
-                column: column as u32,
-            },
-            end_pos: Position {
-                line: self.cur_line as u32,
-                column: column as u32,
-            },
+            start: position,
+            end: position,
             comments: vec![],
         }
     }
@@ -224,8 +589,12 @@ impl<'a> Scanner<'a> {
             if token.tok != TokenType::Comment {
                 break;
             }
-            self.comments.push(Comment { text: token.lit });
+            self.comments.push(Comment {
+                trailing: token.start.line == self.last_token_line,
+                text: token.lit,
+            });
         }
+        self.last_token_line = token.end.line;
         token.comments.append(&mut self.comments);
         token
     }
@@ -255,9 +624,30 @@ impl<'a> Scanner<'a> {
         self.last_newline = self.checkpoint_last_newline;
     }
 
-    /// Get the offset of a position.
-    pub fn offset(&self, pos: &Position) -> u32 {
-        *self.positions.get(pos).expect("position should be in map")
+    /// Get the offset of the position of a token. If several offsets have the position, such as
+    /// the offsets before and after a combining character, the first one is returned.
+    pub fn offset(&self, pos: &ast::Position) -> u32 {
+        let i = self
+            .positions
+            .partition_point(|p| (p.line, p.column) < (pos.line, pos.column));
+        self.position_offset(i, pos)
+    }
+
+    /// Get the offset of the position of the end of a token. If several offsets have the
+    /// position the last one is returned, so that the source between [`Self::offset`] and
+    /// `end_offset` includes the characters which do not advance the column.
+    pub fn end_offset(&self, pos: &ast::Position) -> u32 {
+        let i = self
+            .positions
+            .partition_point(|p| (p.line, p.column) <= (pos.line, pos.column));
+        self.position_offset(i.wrapping_sub(1), pos)
+    }
+
+    fn position_offset(&self, i: usize, pos: &ast::Position) -> u32 {
+        match self.positions.get(i) {
+            Some(p) if (p.line, p.column) == (pos.line, pos.column) => p.offset,
+            _ => panic!("position should have been scanned"),
+        }
     }
 
     /// Append a comment to the current [`Scanner`].
@@ -30,6 +30,7 @@ use crate::scanner::*;
 
     identifier = ( ualpha | "_" ) ( ualnum | "_" )*;
 
+    # Digit separators, hexadecimal and binary literals are handled by `Scanner::finish_number`.
     int_lit = digit+;
 
     float_lit = (digit+ "." digit*) | ("." digit+);
@@ -9,10 +9,16 @@ fn test_scan() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("from"),
-            start_offset: 0,
-            end_offset: 4,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 5 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 4,
+                line: 1,
+                column: 5
+            },
             comments: vec![],
         }
     );
@@ -21,10 +27,16 @@ fn test_scan() {
         Token {
             tok: TokenType::LParen,
             lit: String::from("("),
-            start_offset: 4,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 5 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 4,
+                line: 1,
+                column: 5
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -33,10 +45,13 @@ fn test_scan() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("bucket"),
-            start_offset: 5,
-            end_offset: 11,
-            start_pos: Position { line: 1, column: 6 },
-            end_pos: Position {
+            start: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
+            end: Position {
+                offset: 11,
                 line: 1,
                 column: 12
             },
@@ -48,13 +63,13 @@ fn test_scan() {
         Token {
             tok: TokenType::Colon,
             lit: String::from(":"),
-            start_offset: 11,
-            end_offset: 12,
-            start_pos: Position {
+            start: Position {
+                offset: 11,
                 line: 1,
                 column: 12
             },
-            end_pos: Position {
+            end: Position {
+                offset: 12,
                 line: 1,
                 column: 13
             },
@@ -66,13 +81,13 @@ fn test_scan() {
         Token {
             tok: TokenType::String,
             lit: String::from("\"foo\""),
-            start_offset: 12,
-            end_offset: 17,
-            start_pos: Position {
+            start: Position {
+                offset: 12,
                 line: 1,
                 column: 13
             },
-            end_pos: Position {
+            end: Position {
+                offset: 17,
                 line: 1,
                 column: 18
             },
@@ -84,13 +99,13 @@ fn test_scan() {
         Token {
             tok: TokenType::RParen,
             lit: String::from(")"),
-            start_offset: 17,
-            end_offset: 18,
-            start_pos: Position {
+            start: Position {
+                offset: 17,
                 line: 1,
                 column: 18
             },
-            end_pos: Position {
+            end: Position {
+                offset: 18,
                 line: 1,
                 column: 19
             },
@@ -102,13 +117,13 @@ fn test_scan() {
         Token {
             tok: TokenType::PipeForward,
             lit: String::from("|>"),
-            start_offset: 19,
-            end_offset: 21,
-            start_pos: Position {
+            start: Position {
+                offset: 19,
                 line: 1,
                 column: 20
             },
-            end_pos: Position {
+            end: Position {
+                offset: 21,
                 line: 1,
                 column: 22
             },
@@ -120,13 +135,13 @@ fn test_scan() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("range"),
-            start_offset: 22,
-            end_offset: 27,
-            start_pos: Position {
+            start: Position {
+                offset: 22,
                 line: 1,
                 column: 23
             },
-            end_pos: Position {
+            end: Position {
+                offset: 27,
                 line: 1,
                 column: 28
             },
@@ -138,13 +153,13 @@ fn test_scan() {
         Token {
             tok: TokenType::LParen,
             lit: String::from("("),
-            start_offset: 27,
-            end_offset: 28,
-            start_pos: Position {
+            start: Position {
+                offset: 27,
                 line: 1,
                 column: 28
             },
-            end_pos: Position {
+            end: Position {
+                offset: 28,
                 line: 1,
                 column: 29
             },
@@ -156,13 +171,13 @@ fn test_scan() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("start"),
-            start_offset: 28,
-            end_offset: 33,
-            start_pos: Position {
+            start: Position {
+                offset: 28,
                 line: 1,
                 column: 29
             },
-            end_pos: Position {
+            end: Position {
+                offset: 33,
                 line: 1,
                 column: 34
             },
@@ -174,13 +189,13 @@ fn test_scan() {
         Token {
             tok: TokenType::Colon,
             lit: String::from(":"),
-            start_offset: 33,
-            end_offset: 34,
-            start_pos: Position {
+            start: Position {
+                offset: 33,
                 line: 1,
                 column: 34
             },
-            end_pos: Position {
+            end: Position {
+                offset: 34,
                 line: 1,
                 column: 35
             },
@@ -192,13 +207,13 @@ fn test_scan() {
         Token {
             tok: TokenType::Sub,
             lit: String::from("-"),
-            start_offset: 35,
-            end_offset: 36,
-            start_pos: Position {
+            start: Position {
+                offset: 35,
                 line: 1,
                 column: 36
             },
-            end_pos: Position {
+            end: Position {
+                offset: 36,
                 line: 1,
                 column: 37
             },
@@ -210,13 +225,13 @@ fn test_scan() {
         Token {
             tok: TokenType::Duration,
             lit: String::from("1m"),
-            start_offset: 36,
-            end_offset: 38,
-            start_pos: Position {
+            start: Position {
+                offset: 36,
                 line: 1,
                 column: 37
             },
-            end_pos: Position {
+            end: Position {
+                offset: 38,
                 line: 1,
                 column: 39
             },
@@ -228,13 +243,13 @@ fn test_scan() {
         Token {
             tok: TokenType::RParen,
             lit: String::from(")"),
-            start_offset: 38,
-            end_offset: 39,
-            start_pos: Position {
+            start: Position {
+                offset: 38,
                 line: 1,
                 column: 39
             },
-            end_pos: Position {
+            end: Position {
+                offset: 39,
                 line: 1,
                 column: 40
             },
@@ -246,13 +261,13 @@ fn test_scan() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 39,
-            end_offset: 39,
-            start_pos: Position {
+            start: Position {
+                offset: 39,
                 line: 1,
                 column: 40
             },
-            end_pos: Position {
+            end: Position {
+                offset: 39,
                 line: 1,
                 column: 40
             },
@@ -270,10 +285,16 @@ fn scan_invalid_unicode_single_quotes() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("‛"),
-            start_offset: 0,
-            end_offset: 3,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 4 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 3,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -282,10 +303,16 @@ fn scan_invalid_unicode_single_quotes() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("some"),
-            start_offset: 3,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 4 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 3,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -294,12 +321,15 @@ fn scan_invalid_unicode_single_quotes() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("string"),
-            start_offset: 8,
-            end_offset: 14,
-            start_pos: Position { line: 1, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 8,
                 line: 1,
-                column: 15
+                column: 7
+            },
+            end: Position {
+                offset: 14,
+                line: 1,
+                column: 13
             },
             comments: vec![],
         }
@@ -309,15 +339,15 @@ fn scan_invalid_unicode_single_quotes() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("‛"),
-            start_offset: 14,
-            end_offset: 17,
-            start_pos: Position {
+            start: Position {
+                offset: 14,
                 line: 1,
-                column: 15
+                column: 13
             },
-            end_pos: Position {
+            end: Position {
+                offset: 17,
                 line: 1,
-                column: 18
+                column: 14
             },
             comments: vec![],
         }
@@ -327,15 +357,15 @@ fn scan_invalid_unicode_single_quotes() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 17,
-            end_offset: 17,
-            start_pos: Position {
+            start: Position {
+                offset: 17,
                 line: 1,
-                column: 18
+                column: 14
             },
-            end_pos: Position {
+            end: Position {
+                offset: 17,
                 line: 1,
-                column: 18
+                column: 14
             },
             comments: vec![],
         }
@@ -351,10 +381,16 @@ fn scan_invalid_unicode_double_quotes() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("“"),
-            start_offset: 0,
-            end_offset: 3,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 4 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 3,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -363,10 +399,16 @@ fn scan_invalid_unicode_double_quotes() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("some"),
-            start_offset: 3,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 4 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 3,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -375,12 +417,15 @@ fn scan_invalid_unicode_double_quotes() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("string"),
-            start_offset: 8,
-            end_offset: 14,
-            start_pos: Position { line: 1, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 8,
                 line: 1,
-                column: 15
+                column: 7
+            },
+            end: Position {
+                offset: 14,
+                line: 1,
+                column: 13
             },
             comments: vec![],
         }
@@ -390,15 +435,15 @@ fn scan_invalid_unicode_double_quotes() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("”"),
-            start_offset: 14,
-            end_offset: 17,
-            start_pos: Position {
+            start: Position {
+                offset: 14,
                 line: 1,
-                column: 15
+                column: 13
             },
-            end_pos: Position {
+            end: Position {
+                offset: 17,
                 line: 1,
-                column: 18
+                column: 14
             },
             comments: vec![],
         }
@@ -408,15 +453,15 @@ fn scan_invalid_unicode_double_quotes() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 17,
-            end_offset: 17,
-            start_pos: Position {
+            start: Position {
+                offset: 17,
                 line: 1,
-                column: 18
+                column: 14
             },
-            end_pos: Position {
+            end: Position {
+                offset: 17,
                 line: 1,
-                column: 18
+                column: 14
             },
             comments: vec![],
         }
@@ -432,10 +477,16 @@ fn scan_invalid_unicode_register() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("®"),
-            start_offset: 0,
-            end_offset: 2,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 3 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 2,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -444,10 +495,16 @@ fn scan_invalid_unicode_register() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("some"),
-            start_offset: 2,
-            end_offset: 6,
-            start_pos: Position { line: 1, column: 3 },
-            end_pos: Position { line: 1, column: 7 },
+            start: Position {
+                offset: 2,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 6,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -456,12 +513,15 @@ fn scan_invalid_unicode_register() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("string"),
-            start_offset: 7,
-            end_offset: 13,
-            start_pos: Position { line: 1, column: 8 },
-            end_pos: Position {
+            start: Position {
+                offset: 7,
                 line: 1,
-                column: 14
+                column: 7
+            },
+            end: Position {
+                offset: 13,
+                line: 1,
+                column: 13
             },
             comments: vec![],
         }
@@ -471,15 +531,15 @@ fn scan_invalid_unicode_register() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("®"),
-            start_offset: 13,
-            end_offset: 15,
-            start_pos: Position {
+            start: Position {
+                offset: 13,
                 line: 1,
-                column: 14
+                column: 13
             },
-            end_pos: Position {
+            end: Position {
+                offset: 15,
                 line: 1,
-                column: 16
+                column: 14
             },
             comments: vec![],
         }
@@ -489,15 +549,15 @@ fn scan_invalid_unicode_register() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 15,
-            end_offset: 15,
-            start_pos: Position {
+            start: Position {
+                offset: 15,
                 line: 1,
-                column: 16
+                column: 14
             },
-            end_pos: Position {
+            end: Position {
+                offset: 15,
                 line: 1,
-                column: 16
+                column: 14
             },
             comments: vec![],
         }
@@ -513,10 +573,16 @@ fn test_scan_with_regex() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("a"),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -525,10 +591,16 @@ fn test_scan_with_regex() {
         Token {
             tok: TokenType::Add,
             lit: String::from("+"),
-            start_offset: 2,
-            end_offset: 3,
-            start_pos: Position { line: 1, column: 3 },
-            end_pos: Position { line: 1, column: 4 },
+            start: Position {
+                offset: 2,
+                line: 1,
+                column: 3
+            },
+            end: Position {
+                offset: 3,
+                line: 1,
+                column: 4
+            },
             comments: vec![],
         }
     );
@@ -537,10 +609,16 @@ fn test_scan_with_regex() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("b"),
-            start_offset: 4,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 5 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 4,
+                line: 1,
+                column: 5
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -549,10 +627,16 @@ fn test_scan_with_regex() {
         Token {
             tok: TokenType::RegexEq,
             lit: String::from("=~"),
-            start_offset: 6,
-            end_offset: 8,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 9 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 8,
+                line: 1,
+                column: 9
+            },
             comments: vec![],
         }
     );
@@ -561,13 +645,13 @@ fn test_scan_with_regex() {
         Token {
             tok: TokenType::Regex,
             lit: String::from("/.*[0-9]/"),
-            start_offset: 9,
-            end_offset: 18,
-            start_pos: Position {
+            start: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
-            end_pos: Position {
+            end: Position {
+                offset: 18,
                 line: 1,
                 column: 19
             },
@@ -579,13 +663,13 @@ fn test_scan_with_regex() {
         Token {
             tok: TokenType::Div,
             lit: String::from("/"),
-            start_offset: 19,
-            end_offset: 20,
-            start_pos: Position {
+            start: Position {
+                offset: 19,
                 line: 1,
                 column: 20
             },
-            end_pos: Position {
+            end: Position {
+                offset: 20,
                 line: 1,
                 column: 21
             },
@@ -597,13 +681,13 @@ fn test_scan_with_regex() {
         Token {
             tok: TokenType::Int,
             lit: String::from("2"),
-            start_offset: 21,
-            end_offset: 22,
-            start_pos: Position {
+            start: Position {
+                offset: 21,
                 line: 1,
                 column: 22
             },
-            end_pos: Position {
+            end: Position {
+                offset: 22,
                 line: 1,
                 column: 23
             },
@@ -615,13 +699,13 @@ fn test_scan_with_regex() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 22,
-            end_offset: 22,
-            start_pos: Position {
+            start: Position {
+                offset: 22,
                 line: 1,
                 column: 23
             },
-            end_pos: Position {
+            end: Position {
+                offset: 22,
                 line: 1,
                 column: 23
             },
@@ -639,10 +723,16 @@ fn test_scan_string_expr_simple() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -651,10 +741,16 @@ fn test_scan_string_expr_simple() {
         Token {
             tok: TokenType::StringExpr,
             lit: String::from("${"),
-            start_offset: 1,
-            end_offset: 3,
-            start_pos: Position { line: 1, column: 2 },
-            end_pos: Position { line: 1, column: 4 },
+            start: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 3,
+                line: 1,
+                column: 4
+            },
             comments: vec![],
         }
     );
@@ -663,10 +759,13 @@ fn test_scan_string_expr_simple() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + b}"),
-            start_offset: 3,
-            end_offset: 9,
-            start_pos: Position { line: 1, column: 4 },
-            end_pos: Position {
+            start: Position {
+                offset: 3,
+                line: 1,
+                column: 4
+            },
+            end: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
@@ -678,13 +777,13 @@ fn test_scan_string_expr_simple() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 9,
-            end_offset: 10,
-            start_pos: Position {
+            start: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
-            end_pos: Position {
+            end: Position {
+                offset: 10,
                 line: 1,
                 column: 11
             },
@@ -702,10 +801,16 @@ fn test_scan_string_expr_start_with_text() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -714,10 +819,13 @@ fn test_scan_string_expr_start_with_text() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + b = "),
-            start_offset: 1,
-            end_offset: 9,
-            start_pos: Position { line: 1, column: 2 },
-            end_pos: Position {
+            start: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
@@ -729,13 +837,13 @@ fn test_scan_string_expr_start_with_text() {
         Token {
             tok: TokenType::StringExpr,
             lit: String::from("${"),
-            start_offset: 9,
-            end_offset: 11,
-            start_pos: Position {
+            start: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
-            end_pos: Position {
+            end: Position {
+                offset: 11,
                 line: 1,
                 column: 12
             },
@@ -747,13 +855,13 @@ fn test_scan_string_expr_start_with_text() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + b}"),
-            start_offset: 11,
-            end_offset: 17,
-            start_pos: Position {
+            start: Position {
+                offset: 11,
                 line: 1,
                 column: 12
             },
-            end_pos: Position {
+            end: Position {
+                offset: 17,
                 line: 1,
                 column: 18
             },
@@ -765,13 +873,13 @@ fn test_scan_string_expr_start_with_text() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 17,
-            end_offset: 18,
-            start_pos: Position {
+            start: Position {
+                offset: 17,
                 line: 1,
                 column: 18
             },
-            end_pos: Position {
+            end: Position {
+                offset: 18,
                 line: 1,
                 column: 19
             },
@@ -789,10 +897,16 @@ fn test_scan_string_expr_multiple() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -801,10 +915,13 @@ fn test_scan_string_expr_multiple() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + b = "),
-            start_offset: 1,
-            end_offset: 9,
-            start_pos: Position { line: 1, column: 2 },
-            end_pos: Position {
+            start: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
@@ -816,13 +933,13 @@ fn test_scan_string_expr_multiple() {
         Token {
             tok: TokenType::StringExpr,
             lit: String::from("${"),
-            start_offset: 9,
-            end_offset: 11,
-            start_pos: Position {
+            start: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
-            end_pos: Position {
+            end: Position {
+                offset: 11,
                 line: 1,
                 column: 12
             },
@@ -834,13 +951,13 @@ fn test_scan_string_expr_multiple() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + b} and a - b = "),
-            start_offset: 11,
-            end_offset: 30,
-            start_pos: Position {
+            start: Position {
+                offset: 11,
                 line: 1,
                 column: 12
             },
-            end_pos: Position {
+            end: Position {
+                offset: 30,
                 line: 1,
                 column: 31
             },
@@ -852,13 +969,13 @@ fn test_scan_string_expr_multiple() {
         Token {
             tok: TokenType::StringExpr,
             lit: String::from("${"),
-            start_offset: 30,
-            end_offset: 32,
-            start_pos: Position {
+            start: Position {
+                offset: 30,
                 line: 1,
                 column: 31
             },
-            end_pos: Position {
+            end: Position {
+                offset: 32,
                 line: 1,
                 column: 33
             },
@@ -870,13 +987,13 @@ fn test_scan_string_expr_multiple() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a - b}"),
-            start_offset: 32,
-            end_offset: 38,
-            start_pos: Position {
+            start: Position {
+                offset: 32,
                 line: 1,
                 column: 33
             },
-            end_pos: Position {
+            end: Position {
+                offset: 38,
                 line: 1,
                 column: 39
             },
@@ -888,13 +1005,13 @@ fn test_scan_string_expr_multiple() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 38,
-            end_offset: 39,
-            start_pos: Position {
+            start: Position {
+                offset: 38,
                 line: 1,
                 column: 39
             },
-            end_pos: Position {
+            end: Position {
+                offset: 39,
                 line: 1,
                 column: 40
             },
@@ -912,10 +1029,16 @@ fn test_scan_string_expr_end_with_text() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -924,10 +1047,13 @@ fn test_scan_string_expr_end_with_text() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + b = "),
-            start_offset: 1,
-            end_offset: 9,
-            start_pos: Position { line: 1, column: 2 },
-            end_pos: Position {
+            start: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
@@ -939,13 +1065,13 @@ fn test_scan_string_expr_end_with_text() {
         Token {
             tok: TokenType::StringExpr,
             lit: String::from("${"),
-            start_offset: 9,
-            end_offset: 11,
-            start_pos: Position {
+            start: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
-            end_pos: Position {
+            end: Position {
+                offset: 11,
                 line: 1,
                 column: 12
             },
@@ -957,13 +1083,13 @@ fn test_scan_string_expr_end_with_text() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + b} and a - b = ?"),
-            start_offset: 11,
-            end_offset: 31,
-            start_pos: Position {
+            start: Position {
+                offset: 11,
                 line: 1,
                 column: 12
             },
-            end_pos: Position {
+            end: Position {
+                offset: 31,
                 line: 1,
                 column: 32
             },
@@ -975,13 +1101,13 @@ fn test_scan_string_expr_end_with_text() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 31,
-            end_offset: 32,
-            start_pos: Position {
+            start: Position {
+                offset: 31,
                 line: 1,
                 column: 32
             },
-            end_pos: Position {
+            end: Position {
+                offset: 32,
                 line: 1,
                 column: 33
             },
@@ -999,10 +1125,16 @@ fn test_scan_string_expr_with_trailing_dollar() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -1011,10 +1143,16 @@ fn test_scan_string_expr_with_trailing_dollar() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + "),
-            start_offset: 1,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 2 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -1023,10 +1161,16 @@ fn test_scan_string_expr_with_trailing_dollar() {
         Token {
             tok: TokenType::StringExpr,
             lit: String::from("${"),
-            start_offset: 5,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 6 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1035,10 +1179,13 @@ fn test_scan_string_expr_with_trailing_dollar() {
         Token {
             tok: TokenType::Text,
             lit: String::from("a + b} "),
-            start_offset: 7,
-            end_offset: 14,
-            start_pos: Position { line: 1, column: 8 },
-            end_pos: Position {
+            start: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
+            end: Position {
+                offset: 14,
                 line: 1,
                 column: 15
             },
@@ -1050,13 +1197,13 @@ fn test_scan_string_expr_with_trailing_dollar() {
         Token {
             tok: TokenType::Text,
             lit: String::from("$"),
-            start_offset: 14,
-            end_offset: 15,
-            start_pos: Position {
+            start: Position {
+                offset: 14,
                 line: 1,
                 column: 15
             },
-            end_pos: Position {
+            end: Position {
+                offset: 15,
                 line: 1,
                 column: 16
             },
@@ -1068,13 +1215,13 @@ fn test_scan_string_expr_with_trailing_dollar() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 15,
-            end_offset: 16,
-            start_pos: Position {
+            start: Position {
+                offset: 15,
                 line: 1,
                 column: 16
             },
-            end_pos: Position {
+            end: Position {
+                offset: 16,
                 line: 1,
                 column: 17
             },
@@ -1092,10 +1239,16 @@ fn test_scan_string_expr_escaped_quotes() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -1104,10 +1257,13 @@ fn test_scan_string_expr_escaped_quotes() {
         Token {
             tok: TokenType::Text,
             lit: String::from(r#"these \"\" are escaped quotes"#),
-            start_offset: 1,
-            end_offset: 30,
-            start_pos: Position { line: 1, column: 2 },
-            end_pos: Position {
+            start: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 30,
                 line: 1,
                 column: 31
             },
@@ -1119,13 +1275,13 @@ fn test_scan_string_expr_escaped_quotes() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 30,
-            end_offset: 31,
-            start_pos: Position {
+            start: Position {
+                offset: 30,
                 line: 1,
                 column: 31
             },
-            end_pos: Position {
+            end: Position {
+                offset: 31,
                 line: 1,
                 column: 32
             },
@@ -1143,10 +1299,16 @@ fn test_scan_string_expr_not_escaped_quotes() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -1155,10 +1317,16 @@ fn test_scan_string_expr_not_escaped_quotes() {
         Token {
             tok: TokenType::Text,
             lit: String::from("this "),
-            start_offset: 1,
-            end_offset: 6,
-            start_pos: Position { line: 1, column: 2 },
-            end_pos: Position { line: 1, column: 7 },
+            start: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
+            end: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
             comments: vec![],
         }
     );
@@ -1167,10 +1335,16 @@ fn test_scan_string_expr_not_escaped_quotes() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1179,10 +1353,13 @@ fn test_scan_string_expr_not_escaped_quotes() {
         Token {
             tok: TokenType::Text,
             lit: String::from(" is not an escaped quote"),
-            start_offset: 7,
-            end_offset: 31,
-            start_pos: Position { line: 1, column: 8 },
-            end_pos: Position {
+            start: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
+            end: Position {
+                offset: 31,
                 line: 1,
                 column: 32
             },
@@ -1194,13 +1371,13 @@ fn test_scan_string_expr_not_escaped_quotes() {
         Token {
             tok: TokenType::Quote,
             lit: String::from("\""),
-            start_offset: 31,
-            end_offset: 32,
-            start_pos: Position {
+            start: Position {
+                offset: 31,
                 line: 1,
                 column: 32
             },
-            end_pos: Position {
+            end: Position {
+                offset: 32,
                 line: 1,
                 column: 33
             },
@@ -1218,10 +1395,16 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Int,
             lit: String::from("1"),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -1231,10 +1414,16 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Int,
             lit: String::from("1"),
-            start_offset: 0,
-            end_offset: 1,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 2 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 1,
+                line: 1,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -1244,10 +1433,16 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Regex,
             lit: String::from("/ 2 /"),
-            start_offset: 2,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 3 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 2,
+                line: 1,
+                column: 3
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1257,10 +1452,16 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Div,
             lit: String::from("/"),
-            start_offset: 2,
-            end_offset: 3,
-            start_pos: Position { line: 1, column: 3 },
-            end_pos: Position { line: 1, column: 4 },
+            start: Position {
+                offset: 2,
+                line: 1,
+                column: 3
+            },
+            end: Position {
+                offset: 3,
+                line: 1,
+                column: 4
+            },
             comments: vec![],
         }
     );
@@ -1269,10 +1470,16 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Int,
             lit: String::from("2"),
-            start_offset: 4,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 5 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 4,
+                line: 1,
+                column: 5
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -1281,10 +1488,16 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Div,
             lit: String::from("/"),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1293,10 +1506,13 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Int,
             lit: String::from("3"),
-            start_offset: 8,
-            end_offset: 9,
-            start_pos: Position { line: 1, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 8,
+                line: 1,
+                column: 9
+            },
+            end: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
@@ -1314,10 +1530,13 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Int,
             lit: String::from("3"),
-            start_offset: 8,
-            end_offset: 9,
-            start_pos: Position { line: 1, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 8,
+                line: 1,
+                column: 9
+            },
+            end: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
@@ -1329,13 +1548,13 @@ fn test_scan_unread() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 9,
-            end_offset: 9,
-            start_pos: Position {
+            start: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
-            end_pos: Position {
+            end: Position {
+                offset: 9,
                 line: 1,
                 column: 10
             },
@@ -1356,10 +1575,16 @@ fn test_scan_unread_with_newlines() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("regex"),
-            start_offset: 0,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -1368,10 +1593,16 @@ fn test_scan_unread_with_newlines() {
         Token {
             tok: TokenType::Assign,
             lit: String::from("="),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1380,10 +1611,16 @@ fn test_scan_unread_with_newlines() {
         Token {
             tok: TokenType::Div,
             lit: String::from("/"),
-            start_offset: 10,
-            end_offset: 11,
-            start_pos: Position { line: 4, column: 1 },
-            end_pos: Position { line: 4, column: 2 },
+            start: Position {
+                offset: 10,
+                line: 4,
+                column: 1
+            },
+            end: Position {
+                offset: 11,
+                line: 4,
+                column: 2
+            },
             comments: vec![],
         }
     );
@@ -1393,10 +1630,16 @@ fn test_scan_unread_with_newlines() {
         Token {
             tok: TokenType::Regex,
             lit: String::from("/foo/"),
-            start_offset: 10,
-            end_offset: 15,
-            start_pos: Position { line: 4, column: 1 },
-            end_pos: Position { line: 4, column: 6 },
+            start: Position {
+                offset: 10,
+                line: 4,
+                column: 1
+            },
+            end: Position {
+                offset: 15,
+                line: 4,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -1426,58 +1669,88 @@ fn test_scan_with_regex_unread() {
             Token {
                 tok: TokenType::Int,
                 lit: String::from("3"),
-                start_offset: 0,
-                end_offset: 1,
-                start_pos: Position { line: 1, column: 1 },
-                end_pos: Position { line: 1, column: 2 },
+                start: Position {
+                    offset: 0,
+                    line: 1,
+                    column: 1
+                },
+                end: Position {
+                    offset: 1,
+                    line: 1,
+                    column: 2
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Mul,
                 lit: String::from("*"),
-                start_offset: 2,
-                end_offset: 3,
-                start_pos: Position { line: 1, column: 3 },
-                end_pos: Position { line: 1, column: 4 },
+                start: Position {
+                    offset: 2,
+                    line: 1,
+                    column: 3
+                },
+                end: Position {
+                    offset: 3,
+                    line: 1,
+                    column: 4
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Div,
                 lit: String::from("/"),
-                start_offset: 4,
-                end_offset: 5,
-                start_pos: Position { line: 1, column: 5 },
-                end_pos: Position { line: 1, column: 6 },
+                start: Position {
+                    offset: 4,
+                    line: 1,
+                    column: 5
+                },
+                end: Position {
+                    offset: 5,
+                    line: 1,
+                    column: 6
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Div,
                 lit: String::from("/"),
-                start_offset: 4,
-                end_offset: 5,
-                start_pos: Position { line: 1, column: 5 },
-                end_pos: Position { line: 1, column: 6 },
+                start: Position {
+                    offset: 4,
+                    line: 1,
+                    column: 5
+                },
+                end: Position {
+                    offset: 5,
+                    line: 1,
+                    column: 6
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Int,
                 lit: String::from("1"),
-                start_offset: 6,
-                end_offset: 7,
-                start_pos: Position { line: 1, column: 7 },
-                end_pos: Position { line: 1, column: 8 },
+                start: Position {
+                    offset: 6,
+                    line: 1,
+                    column: 7
+                },
+                end: Position {
+                    offset: 7,
+                    line: 1,
+                    column: 8
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Ident,
                 lit: String::from("y"),
-                start_offset: 17,
-                end_offset: 18,
-                start_pos: Position {
+                start: Position {
+                    offset: 17,
                     line: 2,
                     column: 10
                 },
-                end_pos: Position {
+                end: Position {
+                    offset: 18,
                     line: 2,
                     column: 11
                 },
@@ -1486,10 +1759,16 @@ fn test_scan_with_regex_unread() {
             Token {
                 tok: TokenType::Eof,
                 lit: String::new(),
-                start_offset: 23,
-                end_offset: 23,
-                start_pos: Position { line: 3, column: 5 },
-                end_pos: Position { line: 3, column: 5 },
+                start: Position {
+                    offset: 23,
+                    line: 3,
+                    column: 5
+                },
+                end: Position {
+                    offset: 23,
+                    line: 3,
+                    column: 5
+                },
                 comments: vec![],
             },
         ],
@@ -1516,73 +1795,103 @@ fn test_unclosed_quote() {
             Token {
                 tok: TokenType::Ident,
                 lit: String::from("x"),
-                start_offset: 0,
-                end_offset: 1,
-                start_pos: Position { line: 1, column: 1 },
-                end_pos: Position { line: 1, column: 2 },
+                start: Position {
+                    offset: 0,
+                    line: 1,
+                    column: 1
+                },
+                end: Position {
+                    offset: 1,
+                    line: 1,
+                    column: 2
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Assign,
                 lit: String::from("="),
-                start_offset: 2,
-                end_offset: 3,
-                start_pos: Position { line: 1, column: 3 },
-                end_pos: Position { line: 1, column: 4 },
+                start: Position {
+                    offset: 2,
+                    line: 1,
+                    column: 3
+                },
+                end: Position {
+                    offset: 3,
+                    line: 1,
+                    column: 4
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Quote,
                 lit: String::from("\""),
-                start_offset: 4,
-                end_offset: 5,
-                start_pos: Position { line: 1, column: 5 },
-                end_pos: Position { line: 1, column: 6 },
+                start: Position {
+                    offset: 4,
+                    line: 1,
+                    column: 5
+                },
+                end: Position {
+                    offset: 5,
+                    line: 1,
+                    column: 6
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Ident,
                 lit: String::from("foo"),
-                start_offset: 5,
-                end_offset: 8,
-                start_pos: Position { line: 1, column: 6 },
-                end_pos: Position { line: 1, column: 9 },
+                start: Position {
+                    offset: 5,
+                    line: 1,
+                    column: 6
+                },
+                end: Position {
+                    offset: 8,
+                    line: 1,
+                    column: 9
+                },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Ident,
                 lit: String::from("bar"),
-                start_offset: 17,
-                end_offset: 20,
-                start_pos: Position { line: 2, column: 9 },
-                end_pos: Position {
+                start: Position {
+                    offset: 17,
+                    line: 2,
+                    column: 9
+                },
+                end: Position {
+                    offset: 20,
                     line: 2,
-                    column: 12,
+                    column: 12
                 },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Ident,
                 lit: String::from("baz"),
-                start_offset: 29,
-                end_offset: 32,
-                start_pos: Position { line: 3, column: 9 },
-                end_pos: Position {
+                start: Position {
+                    offset: 29,
+                    line: 3,
+                    column: 9
+                },
+                end: Position {
+                    offset: 32,
                     line: 3,
-                    column: 12,
+                    column: 12
                 },
                 comments: vec![],
             },
             Token {
                 tok: TokenType::Eof,
                 lit: String::from(""),
-                start_offset: 32,
-                end_offset: 32,
-                start_pos: Position {
+                start: Position {
+                    offset: 32,
                     line: 3,
                     column: 12
                 },
-                end_pos: Position {
+                end: Position {
+                    offset: 32,
                     line: 3,
                     column: 12
                 },
@@ -1608,12 +1917,19 @@ a
         Token {
             tok: TokenType::Ident,
             lit: String::from("a"),
-            start_offset: 22,
-            end_offset: 23,
-            start_pos: Position { line: 2, column: 1 },
-            end_pos: Position { line: 2, column: 2 },
+            start: Position {
+                offset: 22,
+                line: 2,
+                column: 1
+            },
+            end: Position {
+                offset: 23,
+                line: 2,
+                column: 2
+            },
             comments: vec![Comment {
                 text: String::from("// this is a comment.\n"),
+                trailing: false,
             }],
         }
     );
@@ -1622,19 +1938,28 @@ a
         Token {
             tok: TokenType::Int,
             lit: String::from("1"),
-            start_offset: 95,
-            end_offset: 96,
-            start_pos: Position { line: 6, column: 1 },
-            end_pos: Position { line: 6, column: 2 },
+            start: Position {
+                offset: 95,
+                line: 6,
+                column: 1
+            },
+            end: Position {
+                offset: 96,
+                line: 6,
+                column: 2
+            },
             comments: vec![
                 Comment {
                     text: String::from("// comment with // nested comment.\n"),
+                    trailing: false,
                 },
                 Comment {
                     text: String::from("// one more.\n"),
+                    trailing: false,
                 },
                 Comment {
                     text: String::from("// last but not least.\n"),
+                    trailing: false,
                 },
             ]
         }
@@ -1644,18 +1969,19 @@ a
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 114,
-            end_offset: 114,
-            start_pos: Position {
+            start: Position {
+                offset: 114,
                 line: 7,
                 column: 18
             },
-            end_pos: Position {
+            end: Position {
+                offset: 114,
                 line: 7,
                 column: 18
             },
             comments: vec![Comment {
                 text: String::from("// ok, that\'s it."),
+                trailing: false,
             }]
         }
     );
@@ -1667,12 +1993,19 @@ a
         Token {
             tok: TokenType::Ident,
             lit: String::from("a"),
-            start_offset: 22,
-            end_offset: 23,
-            start_pos: Position { line: 2, column: 1 },
-            end_pos: Position { line: 2, column: 2 },
+            start: Position {
+                offset: 22,
+                line: 2,
+                column: 1
+            },
+            end: Position {
+                offset: 23,
+                line: 2,
+                column: 2
+            },
             comments: vec![Comment {
                 text: String::from("// this is a comment.\n"),
+                trailing: false,
             }],
         }
     );
@@ -1681,19 +2014,28 @@ a
         Token {
             tok: TokenType::Int,
             lit: String::from("1"),
-            start_offset: 95,
-            end_offset: 96,
-            start_pos: Position { line: 6, column: 1 },
-            end_pos: Position { line: 6, column: 2 },
+            start: Position {
+                offset: 95,
+                line: 6,
+                column: 1
+            },
+            end: Position {
+                offset: 96,
+                line: 6,
+                column: 2
+            },
             comments: vec![
                 Comment {
                     text: String::from("// comment with // nested comment.\n"),
+                    trailing: false,
                 },
                 Comment {
                     text: String::from("// one more.\n"),
+                    trailing: false,
                 },
                 Comment {
                     text: String::from("// last but not least.\n"),
+                    trailing: false,
                 },
             ]
         }
@@ -1703,49 +2045,160 @@ a
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 114,
-            end_offset: 114,
-            start_pos: Position {
+            start: Position {
+                offset: 114,
                 line: 7,
                 column: 18
             },
-            end_pos: Position {
+            end: Position {
+                offset: 114,
                 line: 7,
                 column: 18
             },
             comments: vec![Comment {
                 text: String::from("// ok, that\'s it."),
+                trailing: false,
             }]
         }
     );
 }
 
 #[test]
-fn test_scan_eof() {
-    let text = r#""#;
+fn test_scan_block_comments() {
+    let text = "/* one\ntwo */\na /* b */ \"/* c */\"";
     let mut s = Scanner::new(text);
-    // idempotence with and without regex.
     assert_eq!(
         s.scan(),
+        Token {
+            tok: TokenType::Ident,
+            lit: String::from("a"),
+            start: Position {
+                offset: 14,
+                line: 3,
+                column: 1
+            },
+            end: Position {
+                offset: 15,
+                line: 3,
+                column: 2
+            },
+            comments: vec![Comment {
+                text: String::from("/* one\ntwo */\n"),
+                trailing: false,
+            }],
+        }
+    );
+    // `/*` does not start a comment inside of a string
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::String,
+            lit: String::from("\"/* c */\""),
+            start: Position {
+                offset: 24,
+                line: 3,
+                column: 11
+            },
+            end: Position {
+                offset: 33,
+                line: 3,
+                column: 20
+            },
+            comments: vec![Comment {
+                text: String::from("/* b */"),
+                trailing: true,
+            }],
+        }
+    );
+
+    // with regex
+    let mut s = Scanner::new("/* a */ /b/");
+    assert_eq!(
+        s.scan_with_regex(),
+        Token {
+            tok: TokenType::Regex,
+            lit: String::from("/b/"),
+            start: Position {
+                offset: 8,
+                line: 1,
+                column: 9
+            },
+            end: Position {
+                offset: 11,
+                line: 1,
+                column: 12
+            },
+            comments: vec![Comment {
+                text: String::from("/* a */"),
+                trailing: false,
+            }],
+        }
+    );
+}
+
+#[test]
+fn test_scan_unterminated_block_comment() {
+    let text = "a\n/* b\nc";
+    let mut s = Scanner::new(text);
+    s.scan();
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Illegal,
+            lit: String::from("/* b\nc"),
+            start: Position {
+                offset: 2,
+                line: 2,
+                column: 1
+            },
+            end: Position {
+                offset: 8,
+                line: 3,
+                column: 2
+            },
+            comments: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_scan_eof() {
+    let text = r#""#;
+    let mut s = Scanner::new(text);
+    // idempotence with and without regex.
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Eof,
+            lit: String::from(""),
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            comments: vec![],
+        }
+    );
+    assert_eq!(
+        s.scan_with_regex(),
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 0,
-            end_offset: 0,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 1 },
-            comments: vec![],
-        }
-    );
-    assert_eq!(
-        s.scan_with_regex(),
-        Token {
-            tok: TokenType::Eof,
-            lit: String::from(""),
-            start_offset: 0,
-            end_offset: 0,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 1 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
             comments: vec![],
         }
     );
@@ -1754,10 +2207,16 @@ fn test_scan_eof() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 0,
-            end_offset: 0,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 1 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
             comments: vec![],
         }
     );
@@ -1766,10 +2225,16 @@ fn test_scan_eof() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 0,
-            end_offset: 0,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 1 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
             comments: vec![],
         }
     );
@@ -1778,10 +2243,16 @@ fn test_scan_eof() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 0,
-            end_offset: 0,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 1 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
             comments: vec![],
         }
     );
@@ -1790,10 +2261,16 @@ fn test_scan_eof() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 0,
-            end_offset: 0,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 1 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
             comments: vec![],
         }
     );
@@ -1815,10 +2292,16 @@ fn test_scan_eof_trailing_spaces() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 7,
-            end_offset: 7,
-            start_pos: Position { line: 2, column: 5 },
-            end_pos: Position { line: 2, column: 5 },
+            start: Position {
+                offset: 7,
+                line: 2,
+                column: 5
+            },
+            end: Position {
+                offset: 7,
+                line: 2,
+                column: 5
+            },
             comments: vec![],
         }
     );
@@ -1829,15 +2312,260 @@ fn test_scan_eof_trailing_spaces() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 7,
-            end_offset: 7,
-            start_pos: Position { line: 2, column: 5 },
-            end_pos: Position { line: 2, column: 5 },
+            start: Position {
+                offset: 7,
+                line: 2,
+                column: 5
+            },
+            end: Position {
+                offset: 7,
+                line: 2,
+                column: 5
+            },
+            comments: vec![],
+        }
+    );
+}
+
+#[test]
+fn scan_unicode_identifiers() {
+    let text = "été = 1";
+    let mut s = Scanner::new(text);
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Ident,
+            lit: String::from("été"),
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 4
+            },
+            comments: vec![],
+        }
+    );
+}
+
+#[test]
+fn scan_identifiers_normalized_to_nfc() {
+    // `e` followed by a combining acute accent
+    let text = "e\u{301}te";
+    let mut s = Scanner::new(text);
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Ident,
+            lit: String::from("\u{e9}te"),
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 4
+            },
+            comments: vec![],
+        }
+    );
+}
+
+#[test]
+fn scan_identifier_with_invalid_character() {
+    let text = "ab\u{200b}cd = 1";
+    let mut s = Scanner::new(text);
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Illegal,
+            lit: String::from("ab\u{200b}cd"),
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 6
+            },
+            comments: vec![],
+        }
+    );
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Assign,
+            lit: String::from("="),
+            start: Position {
+                offset: 8,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 9,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
 }
 
+#[test]
+fn scan_columns_count_characters() {
+    let text = "\"😀\" \"e\u{301}\" x";
+    let mut s = Scanner::new(text);
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::String,
+            lit: String::from("\"😀\""),
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 6,
+                line: 1,
+                column: 4
+            },
+            comments: vec![],
+        }
+    );
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::String,
+            lit: String::from("\"e\u{301}\""),
+            start: Position {
+                offset: 7,
+                line: 1,
+                column: 5
+            },
+            end: Position {
+                offset: 12,
+                line: 1,
+                column: 8
+            },
+            comments: vec![],
+        }
+    );
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Ident,
+            lit: String::from("x"),
+            start: Position {
+                offset: 13,
+                line: 1,
+                column: 9
+            },
+            end: Position {
+                offset: 14,
+                line: 1,
+                column: 10
+            },
+            comments: vec![],
+        }
+    );
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Eof,
+            lit: String::from(""),
+            start: Position {
+                offset: 14,
+                line: 1,
+                column: 10
+            },
+            end: Position {
+                offset: 14,
+                line: 1,
+                column: 10
+            },
+            comments: vec![],
+        }
+    );
+}
+
+#[test]
+fn scan_columns_of_emoji_sequences() {
+    let text = "\"👍🏽\"\n\"👨\u{200d}👩\u{200d}👧\" x";
+    let mut s = Scanner::new(text);
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::String,
+            lit: String::from("\"👍🏽\""),
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 10,
+                line: 1,
+                column: 4
+            },
+            comments: vec![],
+        }
+    );
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::String,
+            lit: String::from("\"👨\u{200d}👩\u{200d}👧\""),
+            start: Position {
+                offset: 11,
+                line: 2,
+                column: 1
+            },
+            end: Position {
+                offset: 31,
+                line: 2,
+                column: 4
+            },
+            comments: vec![],
+        }
+    );
+    assert_eq!(
+        s.scan(),
+        Token {
+            tok: TokenType::Ident,
+            lit: String::from("x"),
+            start: Position {
+                offset: 32,
+                line: 2,
+                column: 5
+            },
+            end: Position {
+                offset: 33,
+                line: 2,
+                column: 6
+            },
+            comments: vec![],
+        }
+    );
+}
+
+#[test]
+fn column_offsets() {
+    let line = "\"e\u{301}\" + \"👍🏽\"";
+    assert_eq!(column_offset(line, 1), 0);
+    assert_eq!(column_offset(line, 3), 4);
+    assert_eq!(column_offset(line, 8), 9);
+    assert_eq!(column_offset(line, 9), 17);
+    assert_eq!(column_offset(line, 10), line.len());
+}
+
 #[test]
 fn test_illegal() {
     let text = r#"legal @ illegal"#;
@@ -1847,10 +2575,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("legal"),
-            start_offset: 0,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -1859,10 +2593,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("@"),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1871,10 +2611,13 @@ fn test_illegal() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("illegal"),
-            start_offset: 8,
-            end_offset: 15,
-            start_pos: Position { line: 1, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 8,
+                line: 1,
+                column: 9
+            },
+            end: Position {
+                offset: 15,
                 line: 1,
                 column: 16
             },
@@ -1889,10 +2632,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("legal"),
-            start_offset: 0,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -1901,10 +2650,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("@"),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1914,10 +2669,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("@"),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1926,10 +2687,13 @@ fn test_illegal() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("illegal"),
-            start_offset: 8,
-            end_offset: 15,
-            start_pos: Position { line: 1, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 8,
+                line: 1,
+                column: 9
+            },
+            end: Position {
+                offset: 15,
                 line: 1,
                 column: 16
             },
@@ -1944,10 +2708,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("legal"),
-            start_offset: 0,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -1956,10 +2726,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("@"),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -1968,10 +2744,13 @@ fn test_illegal() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("illegal"),
-            start_offset: 8,
-            end_offset: 15,
-            start_pos: Position { line: 1, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 8,
+                line: 1,
+                column: 9
+            },
+            end: Position {
+                offset: 15,
                 line: 1,
                 column: 16
             },
@@ -1986,10 +2765,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("legal"),
-            start_offset: 0,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -1998,10 +2783,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("@"),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -2011,10 +2802,16 @@ fn test_illegal() {
         Token {
             tok: TokenType::Illegal,
             lit: String::from("@"),
-            start_offset: 6,
-            end_offset: 7,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position { line: 1, column: 8 },
+            start: Position {
+                offset: 6,
+                line: 1,
+                column: 7
+            },
+            end: Position {
+                offset: 7,
+                line: 1,
+                column: 8
+            },
             comments: vec![],
         }
     );
@@ -2023,10 +2820,13 @@ fn test_illegal() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("illegal"),
-            start_offset: 8,
-            end_offset: 15,
-            start_pos: Position { line: 1, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 8,
+                line: 1,
+                column: 9
+            },
+            end: Position {
+                offset: 15,
                 line: 1,
                 column: 16
             },
@@ -2044,10 +2844,16 @@ fn test_scan_duration() {
         Token {
             tok: TokenType::Ident,
             lit: String::from("dur"),
-            start_offset: 0,
-            end_offset: 3,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 4 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 3,
+                line: 1,
+                column: 4
+            },
             comments: vec![],
         }
     );
@@ -2056,10 +2862,16 @@ fn test_scan_duration() {
         Token {
             tok: TokenType::Assign,
             lit: String::from("="),
-            start_offset: 4,
-            end_offset: 5,
-            start_pos: Position { line: 1, column: 5 },
-            end_pos: Position { line: 1, column: 6 },
+            start: Position {
+                offset: 4,
+                line: 1,
+                column: 5
+            },
+            end: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
             comments: vec![],
         }
     );
@@ -2068,12 +2880,15 @@ fn test_scan_duration() {
         Token {
             tok: TokenType::Duration,
             lit: String::from("01y3mo2w1d4h1m30s1ms2µs70ns"),
-            start_offset: 6,
-            end_offset: 34,
-            start_pos: Position { line: 1, column: 7 },
-            end_pos: Position {
+            start: Position {
+                offset: 6,
                 line: 1,
-                column: 35
+                column: 7
+            },
+            end: Position {
+                offset: 34,
+                line: 1,
+                column: 34
             },
             comments: vec![],
         }
@@ -2083,15 +2898,15 @@ fn test_scan_duration() {
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 34,
-            end_offset: 34,
-            start_pos: Position {
+            start: Position {
+                offset: 34,
                 line: 1,
-                column: 35
+                column: 34
             },
-            end_pos: Position {
+            end: Position {
+                offset: 34,
                 line: 1,
-                column: 35
+                column: 34
             },
             comments: vec![],
         }
@@ -2118,123 +2933,169 @@ c = 1 + 2
         Token {
             tok: TokenType::Ident,
             lit: String::from("ms"),
-            start_offset: 0,
-            end_offset: 2,
-            start_pos: Position { line: 1, column: 1 },
-            end_pos: Position { line: 1, column: 3 },
+            start: Position {
+                offset: 0,
+                line: 1,
+                column: 1
+            },
+            end: Position {
+                offset: 2,
+                line: 1,
+                column: 3
+            },
             comments: vec![],
         }
     );
-    assert_eq!(0, s.offset(&Position { line: 1, column: 1 }));
+    assert_eq!(0, s.offset(&ast::Position { line: 1, column: 1 }));
     assert_eq!(
         s.scan(),
         Token {
             tok: TokenType::Assign,
             lit: String::from("="),
-            start_offset: 3,
-            end_offset: 4,
-            start_pos: Position { line: 1, column: 4 },
-            end_pos: Position { line: 1, column: 5 },
+            start: Position {
+                offset: 3,
+                line: 1,
+                column: 4
+            },
+            end: Position {
+                offset: 4,
+                line: 1,
+                column: 5
+            },
             comments: vec![],
         }
     );
-    assert_eq!(3, s.offset(&Position { line: 1, column: 4 }));
+    assert_eq!(3, s.offset(&ast::Position { line: 1, column: 4 }));
     assert_eq!(
         s.scan(),
         Token {
             tok: TokenType::String,
             lit: String::from("\"multiline\nstring\n\""),
-            start_offset: 5,
-            end_offset: 24,
-            start_pos: Position { line: 1, column: 6 },
-            end_pos: Position { line: 3, column: 2 },
+            start: Position {
+                offset: 5,
+                line: 1,
+                column: 6
+            },
+            end: Position {
+                offset: 24,
+                line: 3,
+                column: 2
+            },
             comments: vec![],
         }
     );
-    assert_eq!(5, s.offset(&Position { line: 1, column: 6 }));
-    assert_eq!(24, s.offset(&Position { line: 3, column: 2 }));
+    assert_eq!(5, s.offset(&ast::Position { line: 1, column: 6 }));
+    assert_eq!(24, s.offset(&ast::Position { line: 3, column: 2 }));
     assert_eq!(
         s.scan(),
         Token {
             tok: TokenType::Ident,
             lit: String::from("c"),
-            start_offset: 38,
-            end_offset: 39,
-            start_pos: Position { line: 7, column: 1 },
-            end_pos: Position { line: 7, column: 2 },
+            start: Position {
+                offset: 38,
+                line: 7,
+                column: 1
+            },
+            end: Position {
+                offset: 39,
+                line: 7,
+                column: 2
+            },
             comments: vec![Comment {
                 text: String::from("// comment\n"),
+                trailing: false,
             }]
         }
     );
-    assert_eq!(38, s.offset(&Position { line: 7, column: 1 }));
+    assert_eq!(38, s.offset(&ast::Position { line: 7, column: 1 }));
     assert_eq!(
         s.scan(),
         Token {
             tok: TokenType::Assign,
             lit: String::from("="),
-            start_offset: 40,
-            end_offset: 41,
-            start_pos: Position { line: 7, column: 3 },
-            end_pos: Position { line: 7, column: 4 },
+            start: Position {
+                offset: 40,
+                line: 7,
+                column: 3
+            },
+            end: Position {
+                offset: 41,
+                line: 7,
+                column: 4
+            },
             comments: vec![],
         }
     );
-    assert_eq!(40, s.offset(&Position { line: 7, column: 3 }));
+    assert_eq!(40, s.offset(&ast::Position { line: 7, column: 3 }));
     assert_eq!(
         s.scan(),
         Token {
             tok: TokenType::Int,
             lit: String::from("1"),
-            start_offset: 42,
-            end_offset: 43,
-            start_pos: Position { line: 7, column: 5 },
-            end_pos: Position { line: 7, column: 6 },
+            start: Position {
+                offset: 42,
+                line: 7,
+                column: 5
+            },
+            end: Position {
+                offset: 43,
+                line: 7,
+                column: 6
+            },
             comments: vec![],
         }
     );
-    assert_eq!(42, s.offset(&Position { line: 7, column: 5 }));
+    assert_eq!(42, s.offset(&ast::Position { line: 7, column: 5 }));
     assert_eq!(
         s.scan(),
         Token {
             tok: TokenType::Add,
             lit: String::from("+"),
-            start_offset: 44,
-            end_offset: 45,
-            start_pos: Position { line: 7, column: 7 },
-            end_pos: Position { line: 7, column: 8 },
+            start: Position {
+                offset: 44,
+                line: 7,
+                column: 7
+            },
+            end: Position {
+                offset: 45,
+                line: 7,
+                column: 8
+            },
             comments: vec![],
         }
     );
-    assert_eq!(44, s.offset(&Position { line: 7, column: 7 }));
+    assert_eq!(44, s.offset(&ast::Position { line: 7, column: 7 }));
     assert_eq!(
         s.scan(),
         Token {
             tok: TokenType::Int,
             lit: String::from("2"),
-            start_offset: 46,
-            end_offset: 47,
-            start_pos: Position { line: 7, column: 9 },
-            end_pos: Position {
+            start: Position {
+                offset: 46,
+                line: 7,
+                column: 9
+            },
+            end: Position {
+                offset: 47,
                 line: 7,
                 column: 10
             },
             comments: vec![],
         }
     );
-    assert_eq!(46, s.offset(&Position { line: 7, column: 9 }));
+    assert_eq!(46, s.offset(&ast::Position { line: 7, column: 9 }));
     assert_eq!(
         s.scan(),
         Token {
             tok: TokenType::Eof,
             lit: String::from(""),
-            start_offset: 52,
-            end_offset: 52,
-            start_pos: Position {
+            start: Position {
+                offset: 52,
                 line: 12,
                 column: 1
             },
-            end_pos: Position {
+            end: Position {
+                offset: 52,
                 line: 12,
                 column: 1
             },
@@ -2243,14 +3104,14 @@ c = 1 + 2
     );
     assert_eq!(
         47,
-        s.offset(&Position {
+        s.offset(&ast::Position {
             line: 7,
             column: 10
         })
     );
     assert_eq!(
         52,
-        s.offset(&Position {
+        s.offset(&ast::Position {
             line: 12,
             column: 1
         })
@@ -2258,20 +3119,188 @@ c = 1 + 2
 
     // Ok, now re-assert every offset without scanning.
     // The scanner should keep the position unchanged.
-    assert_eq!(0, s.offset(&Position { line: 1, column: 1 }));
-    assert_eq!(3, s.offset(&Position { line: 1, column: 4 }));
-    assert_eq!(5, s.offset(&Position { line: 1, column: 6 }));
-    assert_eq!(24, s.offset(&Position { line: 3, column: 2 }));
-    assert_eq!(38, s.offset(&Position { line: 7, column: 1 }));
-    assert_eq!(40, s.offset(&Position { line: 7, column: 3 }));
-    assert_eq!(42, s.offset(&Position { line: 7, column: 5 }));
-    assert_eq!(44, s.offset(&Position { line: 7, column: 7 }));
-    assert_eq!(46, s.offset(&Position { line: 7, column: 9 }));
+    assert_eq!(0, s.offset(&ast::Position { line: 1, column: 1 }));
+    assert_eq!(3, s.offset(&ast::Position { line: 1, column: 4 }));
+    assert_eq!(5, s.offset(&ast::Position { line: 1, column: 6 }));
+    assert_eq!(24, s.offset(&ast::Position { line: 3, column: 2 }));
+    assert_eq!(38, s.offset(&ast::Position { line: 7, column: 1 }));
+    assert_eq!(40, s.offset(&ast::Position { line: 7, column: 3 }));
+    assert_eq!(42, s.offset(&ast::Position { line: 7, column: 5 }));
+    assert_eq!(44, s.offset(&ast::Position { line: 7, column: 7 }));
+    assert_eq!(46, s.offset(&ast::Position { line: 7, column: 9 }));
     assert_eq!(
         52,
-        s.offset(&Position {
+        s.offset(&ast::Position {
             line: 12,
             column: 1,
         })
     );
 }
+
+#[test]
+fn test_scan_offset_of_combining_characters() {
+    // A combining acute accent which is not part of an identifier does not advance the column
+    let mut s = Scanner::new("\u{301} a");
+    assert_eq!(s.scan().tok, TokenType::Illegal);
+    assert_eq!(s.scan().tok, TokenType::Ident);
+    let pos = ast::Position { line: 1, column: 1 };
+    assert_eq!(0, s.offset(&pos));
+    assert_eq!(2, s.end_offset(&pos));
+    let pos = ast::Position { line: 1, column: 2 };
+    assert_eq!(3, s.offset(&pos));
+    assert_eq!(3, s.end_offset(&pos));
+}
+
+#[test]
+fn scan_null_coalesce() {
+    let text = "a ?? b ? c";
+    let mut s = Scanner::new(text);
+    let tokens: Vec<_> = std::iter::repeat_with(|| s.scan())
+        .take_while(|t| t.tok != TokenType::Eof)
+        .map(|t| (t.tok, t.lit, t.start.column, t.end.column))
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenType::Ident, String::from("a"), 1, 2),
+            (TokenType::NullCoalesce, String::from("??"), 3, 5),
+            (TokenType::Ident, String::from("b"), 6, 7),
+            (TokenType::QuestionMark, String::from("?"), 8, 9),
+            (TokenType::Ident, String::from("c"), 10, 11),
+        ]
+    );
+}
+
+fn scan_all(text: &str) -> Vec<(TokenType, String)> {
+    let mut s = Scanner::new(text);
+    std::iter::repeat_with(|| s.scan())
+        .take_while(|t| t.tok != TokenType::Eof)
+        .map(|t| (t.tok, t.lit))
+        .collect()
+}
+
+#[test]
+fn scan_digit_separators() {
+    assert_eq!(
+        scan_all("1_000 1_000.000_1 .5_0 1_0h3_0m"),
+        vec![
+            (TokenType::Int, String::from("1_000")),
+            (TokenType::Float, String::from("1_000.000_1")),
+            (TokenType::Float, String::from(".5_0")),
+            (TokenType::Duration, String::from("1_0h3_0m")),
+        ]
+    );
+    assert_eq!(
+        scan_all("1__0 1_ 1._5 _1"),
+        vec![
+            (TokenType::Illegal, String::from("1__0")),
+            (TokenType::Illegal, String::from("1_")),
+            (TokenType::Illegal, String::from("1._5")),
+            (TokenType::Ident, String::from("_1")),
+        ]
+    );
+}
+
+#[test]
+fn scan_hex_and_binary_literals() {
+    assert_eq!(
+        scan_all("0xFF 0Xdead_BEEF 0b1010 0B1_0 0 0h"),
+        vec![
+            (TokenType::Int, String::from("0xFF")),
+            (TokenType::Int, String::from("0Xdead_BEEF")),
+            (TokenType::Int, String::from("0b1010")),
+            (TokenType::Int, String::from("0B1_0")),
+            (TokenType::Int, String::from("0")),
+            (TokenType::Duration, String::from("0h")),
+        ]
+    );
+    assert_eq!(
+        scan_all("0x 0xG 0b102 0x_1"),
+        vec![
+            (TokenType::Illegal, String::from("0x")),
+            (TokenType::Illegal, String::from("0xG")),
+            (TokenType::Illegal, String::from("0b102")),
+            (TokenType::Illegal, String::from("0x_1")),
+        ]
+    );
+}
+
+#[test]
+fn scan_raw_strings() {
+    assert_eq!(
+        scan_all(r###"r"C:\path" r#"say "hi""# r x"###),
+        vec![
+            (TokenType::String, String::from(r#"r"C:\path""#)),
+            (TokenType::String, String::from(r##"r#"say "hi""#"##)),
+            (TokenType::Ident, String::from("r")),
+            (TokenType::Ident, String::from("x")),
+        ]
+    );
+    assert_eq!(
+        scan_all(r#"r"unterminated"#),
+        vec![(TokenType::Illegal, String::from(r#"r"unterminated"#))]
+    );
+}
+
+#[test]
+fn scan_multiline_raw_string() {
+    let mut s = Scanner::new("a = r\"one\ntwo\" + b");
+    let tokens: Vec<_> = std::iter::repeat_with(|| s.scan())
+        .take_while(|t| t.tok != TokenType::Eof)
+        .map(|t| {
+            (
+                t.tok,
+                t.lit,
+                t.start.line,
+                t.start.column,
+                t.end.line,
+                t.end.column,
+            )
+        })
+        .collect();
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenType::Ident, String::from("a"), 1, 1, 1, 2),
+            (TokenType::Assign, String::from("="), 1, 3, 1, 4),
+            (TokenType::String, String::from("r\"one\ntwo\""), 1, 5, 2, 5),
+            (TokenType::Add, String::from("+"), 2, 6, 2, 7),
+            (TokenType::Ident, String::from("b"), 2, 8, 2, 9),
+        ]
+    );
+}
+
+#[test]
+fn scan_exponentiation() {
+    assert_eq!(
+        scan_all("a ** b * c ^ d"),
+        vec![
+            (TokenType::Ident, String::from("a")),
+            (TokenType::Exp, String::from("**")),
+            (TokenType::Ident, String::from("b")),
+            (TokenType::Mul, String::from("*")),
+            (TokenType::Ident, String::from("c")),
+            (TokenType::Pow, String::from("^")),
+            (TokenType::Ident, String::from("d")),
+        ]
+    );
+}
+
+#[test]
+fn scan_semicolons() {
+    assert_eq!(
+        scan_all(r#"{ a = 1; a + 1 } "b;c""#),
+        vec![
+            (TokenType::LBrace, String::from("{")),
+            (TokenType::Ident, String::from("a")),
+            (TokenType::Assign, String::from("=")),
+            (TokenType::Int, String::from("1")),
+            (TokenType::Semicolon, String::from(";")),
+            (TokenType::Ident, String::from("a")),
+            (TokenType::Add, String::from("+")),
+            (TokenType::Int, String::from("1")),
+            (TokenType::RBrace, String::from("}")),
+            (TokenType::String, String::from(r#""b;c""#)),
+        ]
+    );
+}
@@ -72,6 +72,8 @@ pub enum TokenType {
     Mod,
     #[display(fmt = "POW")]
     Pow,
+    #[display(fmt = "EXP")]
+    Exp,
     #[display(fmt = "EQ")]
     Eq,
     #[display(fmt = "LT")]
@@ -88,6 +90,8 @@ pub enum TokenType {
     RegexEq,
     #[display(fmt = "REGEXNEQ")]
     RegexNeq,
+    #[display(fmt = "NULL_COALESCE")]
+    NullCoalesce,
     #[display(fmt = "ASSIGN")]
     Assign,
     #[display(fmt = "ARROW")]
@@ -106,6 +110,8 @@ pub enum TokenType {
     RBrace,
     #[display(fmt = "COMMA")]
     Comma,
+    #[display(fmt = "SEMICOLON")]
+    Semicolon,
     #[display(fmt = "DOT")]
     Dot,
     #[display(fmt = "COLON")]
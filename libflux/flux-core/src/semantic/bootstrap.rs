@@ -3,7 +3,7 @@
 //! This package does not assume a location of the source code but does assume which packages are
 //! part of the prelude.
 
-use std::{cell::RefCell, env::consts, fs, io, io::Write, path::Path};
+use std::{cell::RefCell, env::consts, fmt, fs, io, io::Write, path::Path};
 
 use anyhow::{anyhow, bail, Result};
 use libflate::gzip::Encoder;
@@ -11,6 +11,7 @@ use walkdir::WalkDir;
 
 use crate::{
     ast,
+    errors::Errors,
     map::{HashMap, HashSet},
     parser,
     semantic::{
@@ -118,32 +119,44 @@ fn imports(pkg: &ast::Package) -> impl Iterator<Item = &str> {
 // Determines the dependencies of a package. That is, all packages
 // that must be evaluated before the package in question. Each
 // dependency is added to the `deps` vector in evaluation order.
+// `path` holds the chain of imports leading to `name` and is used to
+// report the full cycle when a package ends up depending on itself.
 #[allow(clippy::type_complexity)]
 fn dependencies<'a>(
     name: &'a str,
     pkgs: &'a ASTPackageMap,
     mut deps: Vec<&'a str>,
-    mut seen: HashSet<&'a str>,
+    mut path: Vec<&'a str>,
     mut done: HashSet<&'a str>,
-) -> Result<(Vec<&'a str>, HashSet<&'a str>, HashSet<&'a str>)> {
-    if seen.contains(name) && !done.contains(name) {
-        Err(anyhow!(r#"package "{}" depends on itself"#, name))
+) -> Result<(Vec<&'a str>, Vec<&'a str>, HashSet<&'a str>)> {
+    if path.contains(&name) {
+        let start = path.iter().position(|pkg| *pkg == name).unwrap_or_default();
+        let mut cycle = path[start..].to_vec();
+        cycle.push(name);
+        Err(anyhow!(
+            r#"package "{}" depends on itself: {}"#,
+            name,
+            cycle.join(" -> ")
+        ))
+    } else if done.contains(name) {
+        Ok((deps, path, done))
     } else {
-        seen.insert(name);
         match pkgs.get(name) {
             None => Err(anyhow!(r#"package "{}" not found"#, name)),
             Some(pkg) => {
+                path.push(name);
                 for name in imports(pkg) {
-                    let (x, y, z) = dependencies(name, pkgs, deps, seen, done)?;
+                    let (x, y, z) = dependencies(name, pkgs, deps, path, done)?;
                     deps = x;
-                    seen = y;
+                    path = y;
                     done = z;
                     if !deps.contains(&name) {
                         deps.push(name);
                     }
                 }
+                path.pop();
                 done.insert(name);
-                Ok((deps, seen, done))
+                Ok((deps, path, done))
             }
         }
     }
@@ -205,13 +218,8 @@ impl InferState {
         Package,        // semantic graph
     )> {
         // Determine the order in which we must infer dependencies
-        let (deps, _, _) = dependencies(
-            name,
-            ast_packages,
-            Vec::new(),
-            HashSet::new(),
-            HashSet::new(),
-        )?;
+        let (deps, _, _) =
+            dependencies(name, ast_packages, Vec::new(), Vec::new(), HashSet::new())?;
 
         // Infer all dependencies
         for pkg in deps {
@@ -325,6 +333,9 @@ fn add_record_to_map(
             PolyType {
                 vars: new_vars,
                 cons: new_cons,
+                label_sets: Default::default(),
+                aliases: Vec::new(),
+                names: Default::default(),
                 expr: field.v.clone(),
             },
         );
@@ -369,6 +380,67 @@ pub fn compile_stdlib(srcdir: &Path, outdir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// The declared types of builtins, keyed by package path and then by the name of the builtin.
+pub type BuiltinRegistry = SemanticMap<String, SemanticMap<String, PolyType>>;
+
+/// A builtin whose declared type does not match the standard library, see [`validate_builtins`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltinMismatch {
+    /// The path of the package declaring the builtin.
+    pub package: String,
+    /// The name of the builtin.
+    pub name: String,
+    /// The type declared in the registry.
+    pub declared: PolyType,
+    /// The type of the builtin in the standard library, `None` if the package does not export it.
+    pub actual: Option<PolyType>,
+}
+
+impl fmt::Display for BuiltinMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.actual {
+            Some(actual) => write!(
+                f,
+                "builtin {}.{} is declared as `{}` but has type `{}`",
+                self.package, self.name, self.declared, actual
+            ),
+            None => write!(
+                f,
+                "builtin {}.{} is declared as `{}` but does not exist",
+                self.package, self.name, self.declared
+            ),
+        }
+    }
+}
+
+/// Checks that every builtin of `registry` is exported by `packages` with the declared type or a
+/// more general one, so that the declared signatures can not drift from the standard library.
+pub fn validate_builtins(
+    registry: &BuiltinRegistry,
+    packages: &Packages,
+) -> Result<(), Errors<BuiltinMismatch>> {
+    let mut errors = Errors::new();
+    for (package, builtins) in registry {
+        let exports = packages.get(package);
+        for (name, declared) in builtins {
+            let actual = exports.and_then(|exports| exports.lookup(name));
+            if !actual.map_or(false, |actual| actual.subsumes(declared)) {
+                errors.push(BuiltinMismatch {
+                    package: package.clone(),
+                    name: name.clone(),
+                    declared: declared.clone(),
+                    actual: actual.cloned(),
+                });
+            }
+        }
+    }
+    if errors.has_errors() {
+        Err(errors)
+    } else {
+        Ok(())
+    }
+}
+
 /// Module represenets the result of compiling Flux source code.
 ///
 /// The polytype represents the type of the entire package as a record type.
@@ -476,6 +548,65 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn validate_builtin_types() -> Result<()> {
+        let a = r#"
+            builtin f : (x: int) => int
+            builtin h : (v: A) => A
+
+            g = (x) => x + 1
+        "#;
+        let ast_packages: ASTPackageMap = semantic_map! {
+            String::from("a") => parse_string("a.flux".to_string(), a).into(),
+        };
+        let mut infer_state = InferState::default();
+        let (types, _) = infer_state.infer_pkg("a", &ast_packages, &PackageExports::new())?;
+        let packages: Packages = semantic_map! { String::from("a") => types };
+
+        let typ = |src: &str| crate::semantic::parse_polytype(src).unwrap();
+        let registry: BuiltinRegistry = semantic_map! {
+            String::from("a") => semantic_map! {
+                String::from("f") => typ("(x: string) => string"),
+                String::from("g") => typ("(x: int) => int"),
+                String::from("h") => typ("(v: int) => int"),
+                String::from("i") => typ("int"),
+            },
+            String::from("b") => semantic_map! {
+                String::from("x") => typ("string"),
+            },
+        };
+        assert_eq!(
+            validate_builtins(&registry, &packages)
+                .unwrap_err()
+                .to_string(),
+            "builtin a.f is declared as `(x: string) => string` but has type `(x: int) => int`\n\n\
+             builtin a.i is declared as `int` but does not exist\n\n\
+             builtin b.x is declared as `string` but does not exist",
+        );
+
+        let registry: BuiltinRegistry = semantic_map! {
+            String::from("a") => semantic_map! {
+                String::from("f") => typ("(x: int) => int"),
+                String::from("h") => typ("(v: A) => A"),
+            },
+        };
+        validate_builtins(&registry, &packages).unwrap();
+        Ok(())
+    }
+
+    fn dependency_error(pkgs: &[(&str, &str)], name: &str) -> String {
+        let ast_packages: ASTPackageMap = pkgs
+            .iter()
+            .map(|(path, src)| {
+                let pkg: ast::Package = parse_string(format!("{}.flux", path), src).into();
+                (path.to_string(), pkg)
+            })
+            .collect();
+        dependencies(name, &ast_packages, Vec::new(), Vec::new(), HashSet::new())
+            .expect_err("expected cyclic dependency error")
+            .to_string()
+    }
+
     #[test]
     fn cyclic_dependency() {
         let a = r#"
@@ -484,22 +615,60 @@ mod tests {
         let b = r#"
             import "a"
         "#;
+        assert_eq!(
+            r#"package "b" depends on itself: b -> a -> b"#,
+            dependency_error(&[("a", a), ("b", b)], "b"),
+        );
+    }
+
+    #[test]
+    fn cyclic_dependency_three_packages() {
+        let a = r#"
+            import "b"
+        "#;
+        let b = r#"
+            import "c"
+        "#;
+        let c = r#"
+            import "a"
+        "#;
+        let d = r#"
+            import "a"
+        "#;
+        assert_eq!(
+            r#"package "a" depends on itself: a -> b -> c -> a"#,
+            dependency_error(&[("a", a), ("b", b), ("c", c), ("d", d)], "d"),
+        );
+    }
+
+    #[test]
+    fn self_import() {
+        let a = r#"
+            import "a"
+        "#;
+        assert_eq!(
+            r#"package "a" depends on itself: a -> a"#,
+            dependency_error(&[("a", a)], "a"),
+        );
+    }
+
+    #[test]
+    fn shared_dependency_is_not_a_cycle() {
+        let a = r#"
+            import "b"
+            import "c"
+        "#;
+        let b = r#"
+            import "c"
+        "#;
+        let c = "";
         let ast_packages: ASTPackageMap = semantic_map! {
             String::from("a") => parse_string("a.flux".to_string(), a).into(),
             String::from("b") => parse_string("b.flux".to_string(), b).into(),
+            String::from("c") => parse_string("c.flux".to_string(), c).into(),
         };
-        let got_err = dependencies(
-            "b",
-            &ast_packages,
-            Vec::new(),
-            HashSet::new(),
-            HashSet::new(),
-        )
-        .expect_err("expected cyclic dependency error");
-
-        assert_eq!(
-            r#"package "b" depends on itself"#.to_string(),
-            got_err.to_string(),
-        );
+        let (deps, _, _) =
+            dependencies("a", &ast_packages, Vec::new(), Vec::new(), HashSet::new()).unwrap();
+        assert_eq!(vec!["c", "b"], deps);
     }
 }
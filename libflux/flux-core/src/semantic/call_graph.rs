@@ -0,0 +1,103 @@
+//! Computing the functions called by the functions of a package.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::semantic::{
+    nodes::{Expression, Package, Statement, Symbol},
+    walk::{self, Node},
+};
+
+/// Returns the names of the functions called by every function assigned to a top-level variable
+/// of `pkg`, including the calls made by the functions nested inside of it.
+///
+/// A called function is named after the variable it is bound to, a function of an imported
+/// package is named after the path of the package and its member, e.g. `strings.toUpper`. Calls
+/// through variables which are assigned another function, such as `f = strings.toUpper`, are
+/// resolved to the function they refer to. Calls whose callee can't be known statically, such as
+/// calls to a function parameter or to the result of another call, are omitted.
+pub fn call_graph(pkg: &Package) -> HashMap<String, BTreeSet<String>> {
+    let mut resolver = Resolver::default();
+    walk::walk(&mut |node: Node| resolver.visit(node), Node::Package(pkg));
+
+    let mut graph = HashMap::new();
+    for file in &pkg.files {
+        for stmt in &file.body {
+            if let Statement::Variable(assgn) = stmt {
+                if let Expression::Function(_) = assgn.init {
+                    let mut calls = BTreeSet::new();
+                    walk::walk(
+                        &mut |node: Node| {
+                            if let Node::CallExpr(call) = node {
+                                calls.extend(resolver.resolve(&call.callee));
+                            }
+                        },
+                        Node::from_expr(&assgn.init),
+                    );
+                    graph.insert(assgn.id.name.name().to_string(), calls);
+                }
+            }
+        }
+    }
+    graph
+}
+
+#[derive(Default)]
+struct Resolver {
+    // The paths of the imported packages
+    imports: HashMap<Symbol, String>,
+    // The functions referred to by the variables which are bound to a known function
+    functions: HashMap<Symbol, String>,
+    // The variables and parameters whose value is not known statically
+    unknown: HashSet<Symbol>,
+}
+
+impl Resolver {
+    fn visit(&mut self, node: Node) {
+        match node {
+            Node::ImportDeclaration(import) => {
+                self.imports
+                    .insert(import.import_symbol.clone(), import.path.value.clone());
+            }
+            Node::VariableAssgn(assgn) => {
+                let function = match &assgn.init {
+                    Expression::Function(_) => Some(assgn.id.name.name().to_string()),
+                    init => self.resolve(init),
+                };
+                match function {
+                    Some(function) => {
+                        self.functions.insert(assgn.id.name.clone(), function);
+                    }
+                    None => {
+                        self.unknown.insert(assgn.id.name.clone());
+                    }
+                }
+            }
+            Node::FunctionParameter(param) => {
+                self.unknown.insert(param.key.name.clone());
+            }
+            _ => (),
+        }
+    }
+
+    // Returns the name of the function `expr` evaluates to, if it is known. Identifiers which are
+    // not bound in the package refer to builtins of the prelude.
+    fn resolve(&self, expr: &Expression) -> Option<String> {
+        match expr {
+            Expression::Identifier(id) => match self.functions.get(&id.name) {
+                Some(function) => Some(function.clone()),
+                None if self.unknown.contains(&id.name) || self.imports.contains_key(&id.name) => {
+                    None
+                }
+                None => Some(id.name.name().to_string()),
+            },
+            Expression::Member(member) => match &member.object {
+                Expression::Identifier(object) => self
+                    .imports
+                    .get(&object.name)
+                    .map(|path| format!("{}.{}", path, member.property)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
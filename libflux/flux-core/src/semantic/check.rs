@@ -1,7 +1,7 @@
 //! Checking the semantic graph.
 
 use crate::{
-    errors::{located, AsDiagnostic, Located},
+    errors::{located, AsDiagnostic, Located, Severity},
     map::HashMap,
     semantic::{
         nodes,
@@ -41,6 +41,8 @@ pub enum ErrorKind {
     TestCase,
 }
 
+impl Severity for ErrorKind {}
+
 impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -186,7 +188,9 @@ impl<'a> walk::Visitor<'a> for VarVisitor<'a> {
                 // These can only be inside option statements
                 self.in_option = false;
             }
-            walk::Node::FunctionExpr(_) => self.vars_stack.push(VariableAssignMap::new()),
+            walk::Node::FunctionExpr(_) | walk::Node::BlockExpr(_) => {
+                self.vars_stack.push(VariableAssignMap::new())
+            }
             walk::Node::FunctionParameter(fp) => {
                 let name = fp.key.name.as_str();
                 self.vars_stack.last_mut().unwrap().insert(name, None);
@@ -220,7 +224,7 @@ impl<'a> walk::Visitor<'a> for VarVisitor<'a> {
     }
 
     fn done(&mut self, node: Node<'a>) {
-        if let walk::Node::FunctionExpr(_) = node {
+        if let walk::Node::FunctionExpr(_) | walk::Node::BlockExpr(_) = node {
             self.vars_stack.pop();
         }
     }
@@ -268,7 +272,9 @@ impl<'a> walk::Visitor<'a> for OptionDepVisitor<'a> {
         }
 
         match node {
-            Node::FunctionExpr(_) => self.vars_stack.push(VariableAssignMap::new()),
+            Node::FunctionExpr(_) | Node::BlockExpr(_) => {
+                self.vars_stack.push(VariableAssignMap::new())
+            }
             Node::FunctionParameter(fp) => {
                 let name = fp.key.name.as_str();
                 self.vars_stack.last_mut().unwrap().insert(name, None);
@@ -298,7 +304,7 @@ impl<'a> walk::Visitor<'a> for OptionDepVisitor<'a> {
     }
 
     fn done(&mut self, node: Node<'a>) {
-        if let Node::FunctionExpr(_) = node {
+        if let Node::FunctionExpr(_) | Node::BlockExpr(_) = node {
             self.vars_stack.pop();
         }
     }
@@ -1,7 +1,7 @@
 //! Various conversions from AST nodes to their associated
 //! types in the semantic graph.
 
-use std::{collections::BTreeMap, fmt, sync::Arc};
+use std::{collections::BTreeMap, fmt, mem, sync::Arc};
 
 use codespan_reporting::diagnostic;
 use serde::{Serialize, Serializer};
@@ -9,12 +9,14 @@ use thiserror::Error;
 
 use crate::{
     ast,
-    errors::{located, AsDiagnostic, Errors, Located},
+    errors::{located, AsDiagnostic, Errors, Located, Severity},
     semantic::{
         env::Environment,
         nodes::*,
-        sub::Substitution,
-        types::{self, BuiltinType, MonoType, MonoTypeMap, SemanticMap},
+        source_map::{Desugaring, SourceMap},
+        sub::{Substitutable, Substituter, Substitution},
+        types::{self, BuiltinType, MonoType, MonoTypeMap, SemanticMap, SubstitutionMap},
+        Feature,
     },
 };
 
@@ -31,6 +33,8 @@ pub enum ErrorKind {
     AtMostOnePipe,
     #[error("invalid constraint {0}")]
     InvalidConstraint(String),
+    #[error("only a Label constraint may restrict the allowed labels")]
+    InvalidLabelSet,
     #[error("a pipe literal may only be used as a default value for an argument in a function definition")]
     InvalidPipeLit,
     #[error("function parameters must be identifiers")]
@@ -39,14 +43,54 @@ pub enum ErrorKind {
     MissingReturn,
     #[error("invalid {0} statement in function block")]
     InvalidFunctionStatement(&'static str),
+    #[error("invalid {0} statement in block expression")]
+    InvalidBlockStatement(&'static str),
+    #[error("block has no value")]
+    BlockWithoutValue,
+    #[error("invalid match pattern, expected a literal or `_`")]
+    InvalidMatchPattern,
+    #[error("match has no arms")]
+    MatchWithoutArms,
+    #[error("ternary expressions are not enabled")]
+    TernaryOperatorDisabled,
+    #[error("spread expressions are not enabled")]
+    SpreadOperatorDisabled,
+    #[error("spread expressions are only allowed in array and record literals")]
+    UnexpectedSpread,
+    #[error("only identifiers can be spread into a record")]
+    InvalidRecordSpread,
+    #[error("guarded access `?.` is not enabled")]
+    GuardedAccessDisabled,
+    #[error("destructuring assignments are not enabled")]
+    DestructuringAssignmentDisabled,
+    #[error("named type parameters are not enabled, found type parameter `{0}`")]
+    NamedTypeParametersDisabled(String),
     #[error("function parameters is not a record expression")]
     ParametersNotRecord,
     #[error("function parameters are more than one record expression")]
     ExtraParameterRecord,
+    #[error("invalid tuple index {0}")]
+    InvalidTupleIndex(i64),
     #[error("invalid duration, {0}")]
     InvalidDuration(String),
+    #[error("type alias {0} is already defined")]
+    DuplicateTypeAlias(String),
+    #[error("import name {name} is already used by the import of \"{path}\"")]
+    DuplicateImport { name: String, path: String },
+    #[error("type alias {} is recursive: {}", .0[0], .0.join(" -> "))]
+    RecursiveTypeAlias(Vec<String>),
+    #[error("type variable {var} is not a parameter of type alias {alias}")]
+    UnboundTypeAliasVariable { alias: String, var: String },
+    #[error("type alias {name} expects {expected} type arguments, got {actual}")]
+    TypeAliasArity {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
 }
 
+impl Severity for ErrorKind {}
+
 impl AsDiagnostic for ErrorKind {
     fn as_diagnostic(&self, _source: &dyn crate::semantic::Source) -> diagnostic::Diagnostic<()> {
         diagnostic::Diagnostic::error().with_message(self.to_string())
@@ -305,9 +349,102 @@ impl<'a> Symbols<'a> {
     }
 }
 
+// A type alias declared with `type Name = ...`, converted the first time it is used.
+struct TypeAlias {
+    stmt: ast::TypeAliasStmt,
+    resolved: Option<ResolvedTypeAlias>,
+}
+
+#[derive(Clone)]
+struct ResolvedTypeAlias {
+    // The type variables standing in for the parameters of the alias.
+    params: Vec<types::Tvar>,
+    // The type the alias expands to.
+    expr: MonoType,
+    // The aliases applied within `expr`.
+    aliases: Vec<types::TypeAlias>,
+}
+
+// Replaces the parameters of a type alias with the arguments it is applied to.
+struct TypeArguments<'a>(&'a SubstitutionMap);
+
+impl Substituter for TypeArguments<'_> {
+    fn try_apply(&self, _var: types::Tvar) -> Option<MonoType> {
+        None
+    }
+
+    fn try_apply_bound(&self, var: types::Tvar) -> Option<MonoType> {
+        self.0.get(&var).cloned()
+    }
+}
+
+// A statement of a block, before the block is assembled from its last statement backwards.
+enum TempBlock {
+    Variable(Box<VariableAssgn>),
+    Expr(ExprStmt),
+    Return(ReturnStmt),
+}
+
+impl TempBlock {
+    fn loc(&self) -> &ast::SourceLocation {
+        match self {
+            TempBlock::Variable(dec) => &dec.loc,
+            TempBlock::Expr(stmt) => &stmt.loc,
+            TempBlock::Return(s) => &s.loc,
+        }
+    }
+}
+
+// Names the variable holding the record of the destructuring assignment at `loc`. Identifiers
+// can't start with `@`, so the variable can't clash with the variables of the source.
+fn destructured_record_name(loc: &ast::SourceLocation) -> String {
+    format!("@destructured{}:{}", loc.start.line, loc.start.column)
+}
+
+/// Returns `true` if `name` is a variable which the conversion introduced rather than one
+/// declared in the source. Such variables are not exported.
+pub(crate) fn is_synthesized(name: &str) -> bool {
+    name.starts_with('@')
+}
+
+// The patterns of the arms of a match expression are literals, possibly negated, and the bools
+// `true` and `false`.
+fn is_match_pattern(pattern: &ast::Expression) -> bool {
+    match pattern {
+        ast::Expression::Integer(_)
+        | ast::Expression::Uint(_)
+        | ast::Expression::Float(_)
+        | ast::Expression::StringLit(_)
+        | ast::Expression::Duration(_)
+        | ast::Expression::DateTime(_) => true,
+        ast::Expression::Identifier(id) => id.name == "true" || id.name == "false",
+        ast::Expression::Unary(expr) => {
+            expr.operator == ast::Operator::SubtractionOperator
+                && matches!(
+                    expr.argument,
+                    ast::Expression::Integer(_)
+                        | ast::Expression::Float(_)
+                        | ast::Expression::Duration(_)
+                )
+        }
+        _ => false,
+    }
+}
+
 pub(crate) struct Converter<'a> {
     sub: &'a mut Substitution,
     symbols: Symbols<'a>,
+    type_aliases: BTreeMap<String, TypeAlias>,
+    // The aliases currently being resolved, used to detect aliases which refer to themselves.
+    resolving_type_aliases: Vec<String>,
+    // The aliases applied within the type being converted, see `types::PolyType::aliases`.
+    applied_type_aliases: Vec<types::TypeAlias>,
+    // The features which enable syntax the converter otherwise rejects.
+    features: &'a [Feature],
+    // The type parameters with names longer than one letter of the type being converted.
+    named_type_parameters: Vec<String>,
+    // Only set when the nodes synthesized while desugaring are recorded.
+    source_map: Option<SourceMap>,
     errors: Errors<Error>,
 }
 
@@ -316,6 +453,12 @@ impl<'a> Converter<'a> {
         Converter {
             sub,
             symbols: Symbols::default(),
+            type_aliases: BTreeMap::new(),
+            resolving_type_aliases: Vec::new(),
+            applied_type_aliases: Vec::new(),
+            features: &[],
+            named_type_parameters: Vec::new(),
+            source_map: None,
             errors: Errors::new(),
         }
     }
@@ -324,10 +467,42 @@ impl<'a> Converter<'a> {
         Converter {
             sub,
             symbols: Symbols::with_env(env),
+            type_aliases: BTreeMap::new(),
+            resolving_type_aliases: Vec::new(),
+            applied_type_aliases: Vec::new(),
+            features: &[],
+            named_type_parameters: Vec::new(),
+            source_map: None,
             errors: Errors::new(),
         }
     }
 
+    pub(crate) fn with_features(mut self, features: &'a [Feature]) -> Self {
+        self.features = features;
+        self
+    }
+
+    pub(crate) fn with_source_map(mut self) -> Self {
+        self.source_map = Some(SourceMap::default());
+        self
+    }
+
+    // Returns the nodes synthesized so far, if they are recorded.
+    pub(crate) fn take_source_map(&mut self) -> SourceMap {
+        self.source_map.take().unwrap_or_default()
+    }
+
+    fn desugared(
+        &mut self,
+        loc: &ast::SourceLocation,
+        origin: &ast::SourceLocation,
+        desugaring: Desugaring,
+    ) {
+        if let Some(source_map) = &mut self.source_map {
+            source_map.insert(loc, origin, desugaring);
+        }
+    }
+
     pub(crate) fn finish<R>(self, r: R) -> Result<R, Errors<Error>> {
         if self.errors.has_errors() {
             Err(self.errors)
@@ -341,6 +516,11 @@ impl<'a> Converter<'a> {
 
         self.symbols.enter_scope();
 
+        // Type aliases may be used anywhere in the package, including before they are declared.
+        for file in &pkg.files {
+            self.declare_type_aliases(&file.body);
+        }
+
         let files = pkg
             .files
             .iter()
@@ -358,16 +538,29 @@ impl<'a> Converter<'a> {
 
     fn convert_file(&mut self, package_name: &str, file: &ast::File) -> File {
         let package = self.convert_package_clause(file.package.as_ref());
+        let mut import_names = BTreeMap::new();
         let imports = file
             .imports
             .iter()
-            .map(|i| self.convert_import_declaration(i))
+            .map(|i| {
+                let dec = self.convert_import_declaration(i);
+                let name = dec.import_symbol.name().to_string();
+                if let Some(path) = import_names.insert(name.clone(), dec.path.value.clone()) {
+                    self.errors.push(located(
+                        dec.alias
+                            .as_ref()
+                            .map_or(&dec.loc, |alias| &alias.loc)
+                            .clone(),
+                        ErrorKind::DuplicateImport { name, path },
+                    ));
+                }
+                dec
+            })
             .collect::<Vec<ImportDeclaration>>();
-        let body = file
-            .body
-            .iter()
-            .map(|s| self.convert_statement(package_name, s))
-            .collect::<Vec<Statement>>();
+        let mut body = Vec::with_capacity(file.body.len());
+        for s in &file.body {
+            self.convert_statement(package_name, s, &mut body);
+        }
 
         File {
             loc: file.base.location.clone(),
@@ -411,8 +604,13 @@ impl<'a> Converter<'a> {
         }
     }
 
-    fn convert_statement(&mut self, package: &str, stmt: &ast::Statement) -> Statement {
-        match stmt {
+    fn convert_statement(
+        &mut self,
+        package: &str,
+        stmt: &ast::Statement,
+        body: &mut Vec<Statement>,
+    ) {
+        let stmt = match stmt {
             ast::Statement::Option(s) => {
                 Statement::Option(Box::new(self.convert_option_statement(s)))
             }
@@ -432,8 +630,183 @@ impl<'a> Converter<'a> {
             ast::Statement::Variable(s) => {
                 Statement::Variable(Box::new(self.convert_variable_assignment(Some(package), s)))
             }
+            // A destructuring assignment declares a variable for every label of its pattern
+            ast::Statement::Destructuring(s) => {
+                let assignments = self.convert_destructuring_assignment(Some(package), s);
+                body.extend(
+                    assignments
+                        .into_iter()
+                        .map(|assgn| Statement::Variable(Box::new(assgn))),
+                );
+                return;
+            }
+            ast::Statement::TypeAlias(s) => Statement::TypeAlias(self.convert_type_alias(s)),
+            // Which bindings are exported is decided when the exports of the package are collected
+            ast::Statement::Export(s) => {
+                return self.convert_statement(package, &s.statement, body)
+            }
             ast::Statement::Bad(s) => Statement::Error(s.base.location.clone()),
+        };
+        body.push(stmt);
+    }
+
+    fn declare_type_aliases(&mut self, body: &[ast::Statement]) {
+        for stmt in body {
+            if let ast::Statement::TypeAlias(alias) = stmt {
+                let name = &alias.id.name;
+                if self.type_aliases.contains_key(name) {
+                    self.errors.push(located(
+                        alias.id.base.location.clone(),
+                        ErrorKind::DuplicateTypeAlias(name.clone()),
+                    ));
+                } else {
+                    self.type_aliases.insert(
+                        name.clone(),
+                        TypeAlias {
+                            stmt: (**alias).clone(),
+                            resolved: None,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn convert_type_alias(&mut self, stmt: &ast::TypeAliasStmt) -> TypeAliasStmt {
+        let declared = self.type_aliases.get(&stmt.id.name).map_or(false, |alias| {
+            alias.stmt.base.location == stmt.base.location
+        });
+        let resolved = if declared {
+            self.resolve_type_alias(&stmt.id.name, &stmt.id.base.location)
+                .expect("declared type aliases can be resolved")
+        } else {
+            // Duplicates have already been reported, they are converted without being registered
+            self.resolve_type_alias_stmt(stmt)
+        };
+        TypeAliasStmt {
+            loc: stmt.base.location.clone(),
+            id: Identifier {
+                loc: stmt.id.base.location.clone(),
+                name: Symbol::from(stmt.id.name.as_str()),
+            },
+            typ: types::PolyType {
+                vars: resolved.params,
+                cons: SemanticMap::new(),
+                label_sets: Default::default(),
+                aliases: resolved.aliases,
+                names: Default::default(),
+                expr: resolved.expr,
+            },
+        }
+    }
+
+    // Returns the parameters and the expansion of the alias called `name`, or `None` if there is
+    // no such alias.
+    fn resolve_type_alias(
+        &mut self,
+        name: &str,
+        loc: &ast::SourceLocation,
+    ) -> Option<ResolvedTypeAlias> {
+        let alias = self.type_aliases.get(name)?;
+        if let Some(resolved) = &alias.resolved {
+            return Some(resolved.clone());
+        }
+        let stmt = alias.stmt.clone();
+
+        if let Some(i) = self.resolving_type_aliases.iter().position(|n| n == name) {
+            let mut cycle = self.resolving_type_aliases[i..].to_vec();
+            cycle.push(name.to_string());
+            self.errors
+                .push(located(loc.clone(), ErrorKind::RecursiveTypeAlias(cycle)));
+            return Some(ResolvedTypeAlias {
+                params: stmt.params.iter().map(|_| self.sub.fresh()).collect(),
+                expr: MonoType::Error,
+                aliases: Vec::new(),
+            });
+        }
+
+        self.resolving_type_aliases.push(name.to_string());
+        let resolved = self.resolve_type_alias_stmt(&stmt);
+        self.resolving_type_aliases.pop();
+
+        if let Some(alias) = self.type_aliases.get_mut(name) {
+            alias.resolved = Some(resolved.clone());
         }
+        Some(resolved)
+    }
+
+    fn resolve_type_alias_stmt(&mut self, stmt: &ast::TypeAliasStmt) -> ResolvedTypeAlias {
+        let mut tvars = BTreeMap::<String, types::Tvar>::new();
+        let vars = stmt
+            .params
+            .iter()
+            .map(|param| {
+                *tvars
+                    .entry(param.name.clone())
+                    .or_insert_with(|| self.sub.fresh())
+            })
+            .collect();
+        let outer_aliases = mem::take(&mut self.applied_type_aliases);
+        let expr = self.convert_monotype(&stmt.ty, &mut tvars);
+        let aliases = mem::replace(&mut self.applied_type_aliases, outer_aliases);
+        for var in tvars.keys() {
+            if !stmt.params.iter().any(|param| param.name == *var) {
+                self.errors.push(located(
+                    stmt.base.location.clone(),
+                    ErrorKind::UnboundTypeAliasVariable {
+                        alias: stmt.id.name.clone(),
+                        var: var.clone(),
+                    },
+                ));
+            }
+        }
+        ResolvedTypeAlias {
+            params: vars,
+            expr,
+            aliases,
+        }
+    }
+
+    // Expands the alias called `name` with `args` substituted for its parameters, or returns
+    // `None` if there is no such alias.
+    fn expand_type_alias(
+        &mut self,
+        name: &str,
+        args: &[ast::MonoType],
+        loc: &ast::SourceLocation,
+        tvars: &mut BTreeMap<String, types::Tvar>,
+    ) -> Option<MonoType> {
+        let ResolvedTypeAlias {
+            params,
+            expr,
+            aliases,
+        } = self.resolve_type_alias(name, loc)?;
+        if params.len() != args.len() {
+            self.errors.push(located(
+                loc.clone(),
+                ErrorKind::TypeAliasArity {
+                    name: name.to_string(),
+                    expected: params.len(),
+                    actual: args.len(),
+                },
+            ));
+            return Some(MonoType::Error);
+        }
+        let args: Vec<MonoType> = args
+            .iter()
+            .map(|arg| self.convert_monotype(arg, tvars))
+            .collect();
+        let params: SubstitutionMap = params.into_iter().zip(args.iter().cloned()).collect();
+        let type_args = TypeArguments(&params);
+        let expr = expr.apply(&type_args);
+        self.applied_type_aliases
+            .extend(aliases.into_iter().map(|alias| alias.apply(&type_args)));
+        self.applied_type_aliases.push(types::TypeAlias {
+            name: name.to_string(),
+            args,
+            expr: expr.clone(),
+        });
+        Some(expr)
     }
 
     fn convert_assignment(&mut self, assign: &ast::Assignment) -> Assignment {
@@ -460,19 +833,21 @@ impl<'a> Converter<'a> {
         }
     }
     fn convert_testcase(&mut self, package: &str, stmt: &ast::TestCaseStmt) -> TestCaseStmt {
+        self.declare_type_aliases(&stmt.block.body);
+        let id = self.convert_identifier(&stmt.id);
+        let extends = stmt
+            .extends
+            .as_ref()
+            .map(|e| self.convert_string_literal(e));
+        let mut body = Vec::with_capacity(stmt.block.body.len());
+        for s in &stmt.block.body {
+            self.convert_statement(package, s, &mut body);
+        }
         TestCaseStmt {
             loc: stmt.base.location.clone(),
-            id: self.convert_identifier(&stmt.id),
-            extends: stmt
-                .extends
-                .as_ref()
-                .map(|e| self.convert_string_literal(e)),
-            body: stmt
-                .block
-                .body
-                .iter()
-                .map(|s| self.convert_statement(package, s))
-                .collect(),
+            id,
+            extends,
+            body,
         }
     }
 
@@ -512,10 +887,33 @@ impl<'a> Converter<'a> {
             ast::MonoType::Basic(basic) => match self.convert_builtintype(basic) {
                 Ok(builtin) => MonoType::from(builtin),
                 Err(err) => {
-                    self.errors.push(err);
-                    MonoType::Error
+                    match self.expand_type_alias(&basic.name.name, &[], &basic.base.location, tvars)
+                    {
+                        Some(typ) => typ,
+                        None => {
+                            self.errors.push(err);
+                            MonoType::Error
+                        }
+                    }
                 }
             },
+            ast::MonoType::Applied(app) => {
+                match self.expand_type_alias(
+                    &app.name.name,
+                    &app.arguments,
+                    &app.base.location,
+                    tvars,
+                ) {
+                    Some(typ) => typ,
+                    None => {
+                        self.errors.push(located(
+                            app.name.base.location.clone(),
+                            ErrorKind::InvalidNamedType(app.name.name.clone()),
+                        ));
+                        MonoType::Error
+                    }
+                }
+            }
             ast::MonoType::Array(arr) => MonoType::arr(self.convert_monotype(&arr.element, tvars)),
             ast::MonoType::Stream(stream) => {
                 MonoType::stream(self.convert_monotype(&stream.element, tvars))
@@ -581,7 +979,9 @@ impl<'a> Converter<'a> {
                     let property = types::Property {
                         k: match &prop.name {
                             ast::PropertyKey::Identifier(id) => {
-                                if id.name.len() == 1 && id.name.starts_with(char::is_uppercase) {
+                                if (id.name.len() == 1 && id.name.starts_with(char::is_uppercase))
+                                    || self.named_type_parameters.contains(&id.name)
+                                {
                                     let tvar = *tvars
                                         .entry(id.name.clone())
                                         .or_insert_with(|| self.sub.fresh());
@@ -593,12 +993,20 @@ impl<'a> Converter<'a> {
                             ast::PropertyKey::StringLit(lit) => {
                                 types::Label::from(self.symbols.lookup(&lit.value)).into()
                             }
+                            ast::PropertyKey::Spread(spread) => {
+                                self.errors.push(located(
+                                    spread.base.location.clone(),
+                                    ErrorKind::UnexpectedSpread,
+                                ));
+                                continue;
+                            }
                         },
                         v: self.convert_monotype(&prop.monotype, tvars),
                     };
                     r = MonoType::from(types::Record::Extension {
                         head: property,
                         tail: r,
+                        optional: prop.optional,
                     })
                 }
                 r
@@ -608,13 +1016,36 @@ impl<'a> Converter<'a> {
 
     // [`PolyType`]: types::PolyType
     fn convert_polytype(&mut self, type_expression: &ast::TypeExpression) -> types::PolyType {
+        let mut named = Vec::new();
+        for con in &type_expression.constraints {
+            let name = &con.tvar.name;
+            if name.chars().count() > 1 && !named.contains(name) {
+                if !self.features.contains(&Feature::NamedTypeParameters) {
+                    self.errors.push(located(
+                        con.tvar.base.location.clone(),
+                        ErrorKind::NamedTypeParametersDisabled(name.clone()),
+                    ));
+                }
+                named.push(name.clone());
+            }
+        }
+        let previous = mem::replace(&mut self.named_type_parameters, named);
         let mut tvars = BTreeMap::<String, types::Tvar>::new();
+        self.applied_type_aliases.clear();
         let expr = self.convert_monotype(&type_expression.monotype, &mut tvars);
+        let aliases = mem::take(&mut self.applied_type_aliases);
+        self.named_type_parameters = previous;
         let mut vars = Vec::<types::Tvar>::new();
         let mut cons = SemanticMap::<types::Tvar, Vec<types::Kind>>::new();
+        let mut label_sets = types::LabelSets::new();
+        let mut names = types::TvarNames::new();
 
         for (name, tvar) in tvars {
             vars.push(tvar);
+            // Single letter names are renamed in order when displayed, the longer ones are kept
+            if name.chars().count() > 1 {
+                names.insert(tvar, name.clone());
+            }
             let mut kinds = Vec::<types::Kind>::new();
             for con in &type_expression.constraints {
                 if con.tvar.name == name {
@@ -629,11 +1060,29 @@ impl<'a> Converter<'a> {
                             }
                         }
                     }
+                    if !con.labels.is_empty() {
+                        if kinds.contains(&types::Kind::Label) {
+                            label_sets
+                                .insert(tvar, con.labels.iter().map(|l| l.value.clone()).collect());
+                        } else {
+                            self.errors.push(located(
+                                con.base.location.clone(),
+                                ErrorKind::InvalidLabelSet,
+                            ));
+                        }
+                    }
                     cons.insert(tvar, kinds.clone());
                 }
             }
         }
-        types::PolyType { vars, cons, expr }
+        types::PolyType {
+            vars,
+            cons,
+            label_sets,
+            aliases,
+            names,
+            expr,
+        }
     }
 
     fn convert_test_statement(&mut self, stmt: &ast::TestStmt) -> TestStmt {
@@ -663,11 +1112,80 @@ impl<'a> Converter<'a> {
         stmt: &ast::VariableAssgn,
     ) -> VariableAssgn {
         let expr = self.convert_expression(&stmt.init);
-        VariableAssgn::new(
+        let mut assgn = VariableAssgn::new(
             self.define_identifier(package, &stmt.id),
             expr,
             stmt.base.location.clone(),
-        )
+        );
+        assgn.annotation = stmt.ty.as_ref().map(|ty| self.convert_polytype(ty));
+        assgn
+    }
+
+    // The record is bound to a variable of its own and every bound label becomes a member
+    // expression of that variable, so that the record is evaluated and checked once. The member
+    // expressions are all converted before the identifiers are defined so that the record can't
+    // refer to them.
+    fn convert_destructuring_assignment(
+        &mut self,
+        package: Option<&str>,
+        stmt: &ast::DestructuringAssgn,
+    ) -> Vec<VariableAssgn> {
+        if !self.features.contains(&Feature::DestructuringAssignment) {
+            self.errors.push(located(
+                stmt.base.location.clone(),
+                ErrorKind::DestructuringAssignmentDisabled,
+            ));
+        }
+        let record = ast::Identifier {
+            base: ast::BaseNode {
+                location: stmt.pattern.base.location.clone(),
+                ..ast::BaseNode::default()
+            },
+            name: destructured_record_name(&stmt.base.location),
+        };
+        let init = self.convert_expression(&stmt.init);
+        let mut assignments = vec![VariableAssgn::new(
+            self.define_identifier(package, &record),
+            init,
+            stmt.base.location.clone(),
+        )];
+        self.desugared(
+            &record.base.location,
+            &stmt.base.location,
+            Desugaring::DestructuringAssignment,
+        );
+        let members = stmt
+            .bindings()
+            .map(|(label, id)| {
+                let member = self.convert_member_expression(&ast::MemberExpr {
+                    base: ast::BaseNode {
+                        location: label.base().location.clone(),
+                        ..ast::BaseNode::default()
+                    },
+                    object: ast::Expression::Identifier(record.clone()),
+                    lbrack: Vec::new(),
+                    property: label.clone(),
+                    optional: false,
+                    rbrack: Vec::new(),
+                });
+                (id, member)
+            })
+            .collect::<Vec<_>>();
+        for (_, member) in &members {
+            self.desugared(
+                &member.loc,
+                &stmt.base.location,
+                Desugaring::DestructuringAssignment,
+            );
+        }
+        assignments.extend(members.into_iter().map(|(id, member)| {
+            VariableAssgn::new(
+                self.define_identifier(package, id),
+                Expression::Member(Box::new(member)),
+                stmt.base.location.clone(),
+            )
+        }));
+        assignments
     }
 
     fn convert_member_assignment(&mut self, stmt: &ast::MemberAssgn) -> MemberAssgn {
@@ -708,11 +1226,28 @@ impl<'a> Converter<'a> {
             ast::Expression::Conditional(expr) => {
                 Expression::Conditional(Box::new(self.convert_conditional_expression(expr)))
             }
+            ast::Expression::Ternary(expr) => {
+                Expression::Conditional(Box::new(self.convert_ternary_expression(expr)))
+            }
             ast::Expression::Object(expr) => {
                 Expression::Object(Box::new(self.convert_object_expression(expr)))
             }
-            ast::Expression::Array(expr) => {
-                Expression::Array(Box::new(self.convert_array_expression(expr)))
+            ast::Expression::Array(expr) => self.convert_array_expression(expr),
+            ast::Expression::Tuple(expr) => {
+                Expression::Tuple(Box::new(self.convert_tuple_expression(expr)))
+            }
+            ast::Expression::TupleIndex(expr) => match self.convert_tuple_index_expression(expr) {
+                Ok(expr) => Expression::TupleIndex(Box::new(expr)),
+                Err(err) => {
+                    self.errors.push(err);
+                    Expression::Error(expr.base.location.clone())
+                }
+            },
+            ast::Expression::Block(expr) => {
+                Expression::Block(Box::new(self.convert_block_expression(expr)))
+            }
+            ast::Expression::Match(expr) => {
+                Expression::Match(Box::new(self.convert_match_expression(expr)))
             }
             ast::Expression::Dict(expr) => {
                 Expression::Dict(Box::new(self.convert_dict_expression(expr)))
@@ -755,6 +1290,13 @@ impl<'a> Converter<'a> {
 
                 Expression::Error(lit.base.location.clone())
             }
+            ast::Expression::Spread(spread) => {
+                self.errors.push(located(
+                    spread.base.location.clone(),
+                    ErrorKind::UnexpectedSpread,
+                ));
+                Expression::Error(spread.base.location.clone())
+            }
             ast::Expression::Bad(bad) => Expression::Error(bad.base.location.clone()),
         }
     }
@@ -853,28 +1395,17 @@ impl<'a> Converter<'a> {
     }
 
     fn convert_block(&mut self, block: &ast::Block) -> Block {
-        enum TempBlock {
-            Variable(Box<VariableAssgn>),
-            Expr(ExprStmt),
-            Return(ReturnStmt),
-        }
-
-        impl TempBlock {
-            fn loc(&self) -> &ast::SourceLocation {
-                match self {
-                    TempBlock::Variable(dec) => &dec.loc,
-                    TempBlock::Expr(stmt) => &stmt.loc,
-                    TempBlock::Return(s) => &s.loc,
-                }
-            }
-        }
-
         let mut body = Vec::with_capacity(block.body.len());
         for s in &block.body {
             match s {
                 ast::Statement::Variable(dec) => body.push(TempBlock::Variable(Box::new(
                     self.convert_variable_assignment(None, dec),
                 ))),
+                ast::Statement::Destructuring(dec) => body.extend(
+                    self.convert_destructuring_assignment(None, dec)
+                        .into_iter()
+                        .map(|assgn| TempBlock::Variable(Box::new(assgn))),
+                ),
                 ast::Statement::Expr(stmt) => {
                     body.push(TempBlock::Expr(self.convert_expression_statement(stmt)))
                 }
@@ -930,6 +1461,66 @@ impl<'a> Converter<'a> {
         })
     }
 
+    // The trailing expression of the block becomes the `ReturnStmt` ending the semantic block.
+    fn convert_block_expression(&mut self, expr: &ast::BlockExpr) -> BlockExpr {
+        self.symbols.enter_scope();
+        let mut body = Vec::with_capacity(expr.body.len());
+        for s in &expr.body {
+            match s {
+                ast::Statement::Variable(dec) => body.push(TempBlock::Variable(Box::new(
+                    self.convert_variable_assignment(None, dec),
+                ))),
+                ast::Statement::Destructuring(dec) => body.extend(
+                    self.convert_destructuring_assignment(None, dec)
+                        .into_iter()
+                        .map(|assgn| TempBlock::Variable(Box::new(assgn))),
+                ),
+                ast::Statement::Expr(stmt) => {
+                    body.push(TempBlock::Expr(self.convert_expression_statement(stmt)))
+                }
+                _ => {
+                    self.errors.push(located(
+                        s.base().location.clone(),
+                        ErrorKind::InvalidBlockStatement(s.type_name()),
+                    ));
+                }
+            }
+        }
+        self.symbols.exit_scope();
+
+        let mut body = body.into_iter().rev();
+        let block = match body.next() {
+            Some(TempBlock::Expr(stmt)) => Block::Return(ReturnStmt {
+                loc: stmt.loc,
+                argument: stmt.expression,
+            }),
+            last => {
+                self.errors.push(located(
+                    expr.base.location.clone(),
+                    ErrorKind::BlockWithoutValue,
+                ));
+                let block = Block::Return(ReturnStmt {
+                    loc: expr.base.location.clone(),
+                    argument: Expression::Error(expr.base.location.clone()),
+                });
+                match last {
+                    Some(TempBlock::Variable(dec)) => Block::Variable(dec, Box::new(block)),
+                    _ => block,
+                }
+            }
+        };
+
+        BlockExpr {
+            loc: expr.base.location.clone(),
+            typ: MonoType::Error,
+            body: body.fold(block, |acc, s| match s {
+                TempBlock::Variable(dec) => Block::Variable(dec, Box::new(acc)),
+                TempBlock::Expr(stmt) => Block::Expr(stmt, Box::new(acc)),
+                TempBlock::Return(_) => unreachable!("block expressions have no return statements"),
+            }),
+        }
+    }
+
     fn convert_call_expression(&mut self, expr: &ast::CallExpr) -> CallExpr {
         let callee = self.convert_expression(&expr.callee);
         // TODO(affo): I'd prefer these checks to be in ast.Check().
@@ -937,7 +1528,18 @@ impl<'a> Converter<'a> {
             .arguments
             .iter()
             .map(|a| match a {
-                ast::Expression::Object(obj) => self.convert_object_expression(obj),
+                ast::Expression::Object(obj) => {
+                    // Arguments are passed by name, so there is nothing to spread a record into
+                    for prop in &obj.properties {
+                        if let ast::PropertyKey::Spread(spread) = &prop.key {
+                            self.errors.push(located(
+                                spread.base.location.clone(),
+                                ErrorKind::UnexpectedSpread,
+                            ));
+                        }
+                    }
+                    self.convert_object_expression(obj)
+                }
                 _ => {
                     self.errors.push(located(
                         a.base().location.clone(),
@@ -949,6 +1551,7 @@ impl<'a> Converter<'a> {
                         typ: MonoType::Error,
                         with: None,
                         properties: Vec::new(),
+                        spreads: Vec::new(),
                     }
                 }
             })
@@ -976,15 +1579,29 @@ impl<'a> Converter<'a> {
     fn convert_member_expression(&mut self, expr: &ast::MemberExpr) -> MemberExpr {
         let object = self.convert_expression(&expr.object);
         let property = match &expr.property {
-            ast::PropertyKey::Identifier(id) => &id.name,
-            ast::PropertyKey::StringLit(lit) => &lit.value,
+            ast::PropertyKey::Identifier(id) => id.name.as_str(),
+            ast::PropertyKey::StringLit(lit) => lit.value.as_str(),
+            ast::PropertyKey::Spread(spread) => {
+                self.errors.push(located(
+                    spread.base.location.clone(),
+                    ErrorKind::UnexpectedSpread,
+                ));
+                ""
+            }
         };
         let property = self.symbols.lookup_property_key(property);
+        if expr.optional && !self.features.contains(&Feature::GuardedAccess) {
+            self.errors.push(located(
+                expr.base.location.clone(),
+                ErrorKind::GuardedAccessDisabled,
+            ));
+        }
         MemberExpr {
             loc: expr.base.location.clone(),
             typ: MonoType::Error,
             object,
             property,
+            optional: expr.optional,
         }
     }
 
@@ -1003,6 +1620,7 @@ impl<'a> Converter<'a> {
         let mut call = self.convert_call_expression(&expr.call);
         let pipe = self.convert_expression(&expr.argument);
         call.pipe = Some(pipe);
+        self.desugared(&call.loc, &expr.base.location, Desugaring::Pipe);
         call
     }
 
@@ -1039,6 +1657,45 @@ impl<'a> Converter<'a> {
         }
     }
 
+    fn convert_match_expression(&mut self, expr: &ast::MatchExpr) -> MatchExpr {
+        let scrutinee = self.convert_expression(&expr.scrutinee);
+        if expr.arms.is_empty() {
+            self.errors.push(located(
+                expr.base.location.clone(),
+                ErrorKind::MatchWithoutArms,
+            ));
+        }
+        let arms = expr
+            .arms
+            .iter()
+            .map(|arm| {
+                let pattern = match &arm.pattern {
+                    ast::Expression::Identifier(id) if id.name == "_" => None,
+                    pattern => {
+                        if !is_match_pattern(pattern) {
+                            self.errors.push(located(
+                                pattern.base().location.clone(),
+                                ErrorKind::InvalidMatchPattern,
+                            ));
+                        }
+                        Some(self.convert_expression(pattern))
+                    }
+                };
+                MatchArm {
+                    loc: arm.base.location.clone(),
+                    pattern,
+                    body: self.convert_expression(&arm.body),
+                }
+            })
+            .collect();
+        MatchExpr {
+            loc: expr.base.location.clone(),
+            typ: MonoType::Error,
+            scrutinee,
+            arms,
+        }
+    }
+
     fn convert_conditional_expression(&mut self, expr: &ast::ConditionalExpr) -> ConditionalExpr {
         let test = self.convert_expression(&expr.test);
         let consequent = self.convert_expression(&expr.consequent);
@@ -1052,21 +1709,76 @@ impl<'a> Converter<'a> {
         }
     }
 
+    // A ternary expression is sugar for the conditional expression with the same branches.
+    fn convert_ternary_expression(&mut self, expr: &ast::TernaryExpr) -> ConditionalExpr {
+        if !self.features.contains(&Feature::TernaryOperator) {
+            self.errors.push(located(
+                expr.base.location.clone(),
+                ErrorKind::TernaryOperatorDisabled,
+            ));
+        }
+        let test = self.convert_expression(&expr.test);
+        let consequent = self.convert_expression(&expr.consequent);
+        let alternate = self.convert_expression(&expr.alternate);
+        ConditionalExpr {
+            loc: expr.base.location.clone(),
+            test,
+            consequent,
+            alternate,
+            typ: MonoType::Error,
+        }
+    }
+
+    // A record spread as the first entry of a record literal, `{...r, a: 1}`, is the base of the
+    // record, as in `{r with a: 1}`. The fields of the records spread after it are only known
+    // once their types are, so those spreads are kept until type inference.
     fn convert_object_expression(&mut self, expr: &ast::ObjectExpr) -> ObjectExpr {
-        let properties = expr
-            .properties
-            .iter()
-            .map(|p| self.convert_property(p))
-            .collect::<Vec<Property>>();
-        let with = expr
+        let mut with = expr
             .with
             .as_ref()
             .map(|with| self.convert_identifier_expression(&with.source));
+        let mut properties = Vec::new();
+        let mut spreads = Vec::new();
+        for (i, prop) in expr.properties.iter().enumerate() {
+            let spread = match &prop.key {
+                ast::PropertyKey::Spread(spread) => spread,
+                _ => {
+                    properties.push(self.convert_property(prop));
+                    continue;
+                }
+            };
+            if !self.features.contains(&Feature::SpreadOperator) {
+                self.errors.push(located(
+                    spread.base.location.clone(),
+                    ErrorKind::SpreadOperatorDisabled,
+                ));
+            }
+            let argument = match &spread.argument {
+                ast::Expression::Identifier(id) => self.convert_identifier_expression(id),
+                argument => {
+                    self.errors.push(located(
+                        argument.base().location.clone(),
+                        ErrorKind::InvalidRecordSpread,
+                    ));
+                    continue;
+                }
+            };
+            if i == 0 && with.is_none() {
+                with = Some(argument);
+            } else {
+                spreads.push(Spread {
+                    loc: spread.base.location.clone(),
+                    index: properties.len(),
+                    argument,
+                });
+            }
+        }
         ObjectExpr {
             loc: expr.base.location.clone(),
             typ: MonoType::Error,
             with,
             properties,
+            spreads,
         }
     }
 
@@ -1081,6 +1793,9 @@ impl<'a> Converter<'a> {
                     loc,
                 }
             }
+            ast::PropertyKey::Spread(_) => {
+                unreachable!("spreads are converted along with the object holding them")
+            }
         };
         let value = match &prop.value {
             Some(expr) => self.convert_expression(expr),
@@ -1100,19 +1815,131 @@ impl<'a> Converter<'a> {
         }
     }
 
-    fn convert_array_expression(&mut self, expr: &ast::ArrayExpr) -> ArrayExpr {
+    // An array literal with spreads, `[...xs, 1, ...ys]`, is the concatenation of the spread
+    // arrays and of the arrays of the elements between them, `xs + [1] + ys`. The arrays of
+    // elements are located from their first to their last element.
+    fn convert_array_expression(&mut self, expr: &ast::ArrayExpr) -> Expression {
+        let loc = &expr.base.location;
+        let array = |loc: ast::SourceLocation, elements| {
+            Expression::Array(Box::new(ArrayExpr {
+                loc,
+                typ: MonoType::Error,
+                elements,
+            }))
+        };
+
+        let mut operands = Vec::new();
+        let mut elements = Vec::new();
+        for item in &expr.elements {
+            match &item.expression {
+                ast::Expression::Spread(spread) => {
+                    if !self.features.contains(&Feature::SpreadOperator) {
+                        self.errors.push(located(
+                            spread.base.location.clone(),
+                            ErrorKind::SpreadOperatorDisabled,
+                        ));
+                    }
+                    if !elements.is_empty() {
+                        operands.push(self.synthesized_array(loc, std::mem::take(&mut elements)));
+                    }
+                    operands.push(self.convert_expression(&spread.argument));
+                }
+                element => elements.push(self.convert_expression(element)),
+            }
+        }
+        if operands.is_empty() {
+            return array(loc.clone(), elements);
+        }
+        if !elements.is_empty() {
+            operands.push(self.synthesized_array(loc, elements));
+        }
+        // Both operands of a concatenation have the type of its result, so an array literal
+        // among the first operands makes the result an array. A spread which is not followed by
+        // an array literal is therefore appended to an empty array, located at the start of the
+        // literal.
+        let starts_with_spread = matches!(
+            expr.elements.first().map(|item| &item.expression),
+            Some(ast::Expression::Spread(_))
+        );
+        if starts_with_spread && !matches!(operands.get(1), Some(Expression::Array(_))) {
+            let empty = ast::SourceLocation {
+                file: loc.file.clone(),
+                start: loc.start,
+                end: loc.start,
+                source: None,
+            };
+            self.desugared(&empty, loc, Desugaring::ArraySpread);
+            operands.insert(0, array(empty, Vec::new()));
+        }
+        operands
+            .into_iter()
+            .reduce(|left, right| {
+                Expression::Binary(Box::new(BinaryExpr {
+                    loc: loc.clone(),
+                    typ: MonoType::Error,
+                    operator: ast::Operator::AdditionOperator,
+                    left,
+                    right,
+                }))
+            })
+            .unwrap_or_else(|| array(loc.clone(), Vec::new()))
+    }
+
+    // Returns the array of the elements between the spreads of the array literal at `literal`.
+    fn synthesized_array(
+        &mut self,
+        literal: &ast::SourceLocation,
+        elements: Vec<Expression>,
+    ) -> Expression {
+        let loc = match (elements.first(), elements.last()) {
+            (Some(first), Some(last)) => ast::SourceLocation {
+                file: literal.file.clone(),
+                start: first.loc().start,
+                end: last.loc().end,
+                source: None,
+            },
+            _ => literal.clone(),
+        };
+        self.desugared(&loc, literal, Desugaring::ArraySpread);
+        Expression::Array(Box::new(ArrayExpr {
+            loc,
+            typ: MonoType::Error,
+            elements,
+        }))
+    }
+
+    fn convert_tuple_expression(&mut self, expr: &ast::TupleExpr) -> TupleExpr {
         let elements = expr
             .elements
             .iter()
             .map(|e| self.convert_expression(&e.expression))
             .collect::<Vec<Expression>>();
-        ArrayExpr {
+        TupleExpr {
             loc: expr.base.location.clone(),
             typ: MonoType::Error,
             elements,
         }
     }
 
+    fn convert_tuple_index_expression(
+        &mut self,
+        expr: &ast::TupleIndexExpr,
+    ) -> Result<TupleIndexExpr> {
+        let index = usize::try_from(expr.index.value).map_err(|_| {
+            located(
+                expr.index.base.location.clone(),
+                ErrorKind::InvalidTupleIndex(expr.index.value),
+            )
+        })?;
+        let tuple = self.convert_expression(&expr.tuple);
+        Ok(TupleIndexExpr {
+            loc: expr.base.location.clone(),
+            typ: MonoType::Error,
+            tuple,
+            index,
+        })
+    }
+
     fn convert_dict_expression(&mut self, expr: &ast::DictExpr) -> DictExpr {
         let mut elements = Vec::new();
         for item in &expr.elements {
@@ -1444,6 +2271,8 @@ mod tests {
                             base: b.clone(),
                             value: true,
                         }),
+                        colon: vec![],
+                        ty: None,
                     })),
                     ast::Statement::Expr(Box::new(ast::ExprStmt {
                         base: b.clone(),
@@ -1525,6 +2354,7 @@ mod tests {
                                 value: 10,
                             }),
                         }],
+                        spreads: Vec::new(),
                     })),
                 })],
             }],
@@ -1564,6 +2394,7 @@ mod tests {
                                 value: 10,
                             }),
                         }],
+                        spreads: Vec::new(),
                     })),
                 })],
             }],
@@ -1616,6 +2447,7 @@ mod tests {
                                 }),
                             },
                         ],
+                        spreads: Vec::new(),
                     })),
                 })],
             }],
@@ -1670,6 +2502,7 @@ mod tests {
                                 }),
                             },
                         ],
+                        spreads: Vec::new(),
                     })),
                 })],
             }],
@@ -1772,6 +2605,7 @@ mod tests {
                                     }),
                                 },
                             ],
+                            spreads: Vec::new(),
                         })),
                         b.location.clone(),
                     )),
@@ -1809,6 +2643,7 @@ mod tests {
                                 name: symbols["alert"].clone(),
                             }),
                             property: symbols["state"].clone(),
+                            optional: false,
                         },
                         init: Expression::StringLit(StringLit {
                             loc: b.location.clone(),
@@ -2535,6 +3370,7 @@ mod tests {
                                 base: b.clone(),
                                 name: "b".to_string(),
                             }),
+                            optional: false,
                             rbrack: vec![],
                         })),
                         lbrack: vec![],
@@ -2542,6 +3378,7 @@ mod tests {
                             base: b.clone(),
                             name: "c".to_string(),
                         }),
+                        optional: false,
                         rbrack: vec![],
                     })),
                 }))],
@@ -2573,8 +3410,10 @@ mod tests {
                                 name: symbols["a"].clone(),
                             }),
                             property: symbols["b"].clone(),
+                            optional: false,
                         })),
                         property: symbols["c"].clone(),
+                        optional: false,
                     })),
                 })],
             }],
@@ -2616,10 +3455,12 @@ mod tests {
                                     name: symbols["a"].clone(),
                                 }),
                                 property: symbols["b"].clone(),
+                                optional: false,
                             })),
                             arguments: Vec::new(),
                         })),
                         property: symbols["c"].clone(),
+                        optional: false,
                     })),
                 })],
             }],
@@ -2698,6 +3539,7 @@ mod tests {
                 v: MonoType::INT,
             },
             tail: MonoType::BoundVar(Tvar(0)),
+            optional: false,
         });
         assert_eq!(want, got);
     }
@@ -2746,7 +3588,14 @@ mod tests {
             pipe: None,
             retn: MonoType::BoundVar(Tvar(0)),
         });
-        let want = types::PolyType { vars, cons, expr };
+        let want = types::PolyType {
+            vars,
+            cons,
+            label_sets: types::LabelSets::new(),
+            aliases: Vec::new(),
+            names: types::TvarNames::new(),
+            expr,
+        };
         assert_eq!(want, got);
     }
 
@@ -2772,7 +3621,54 @@ mod tests {
             pipe: None,
             retn: MonoType::BoundVar(Tvar(0)),
         });
-        let want = types::PolyType { vars, cons, expr };
+        let want = types::PolyType {
+            vars,
+            cons,
+            label_sets: types::LabelSets::new(),
+            aliases: Vec::new(),
+            names: types::TvarNames::new(),
+            expr,
+        };
         assert_eq!(want, got);
     }
+
+    #[test]
+    fn test_convert_preserves_locations() {
+        use crate::semantic::walk;
+
+        let pkg = parse_package(
+            r#"package foo
+import "csv"
+
+option now = () => 2020-01-01T00:00:00Z
+
+a = 1
+r = {a, b: "b", "c": 1.0}
+f = (tables=<-, fn) => tables |> map(fn: (r) => ({r with x: r._value * 2}))
+g = (x, y=1) => {
+    z = x + y
+    return if z > 0 then "${z}" else "none"
+}
+h = [1, 2, 3] |> f(fn: (r) => r)
+i = [a: "a"]
+j = csv.from(file: "data.csv")
+k = not true and -a < 0 or exists r.a
+"#,
+        );
+        let mut sub = sub::Substitution::default();
+        let mut converter = Converter::new(&mut sub);
+        let r = converter.convert_package(&pkg);
+        let pkg = converter.finish(r).unwrap();
+
+        let mut missing = Vec::new();
+        walk::walk(
+            &mut |node| {
+                if !node.loc().is_valid() {
+                    missing.push(node.to_string());
+                }
+            },
+            walk::Node::Package(&pkg),
+        );
+        assert_eq!(missing, Vec::<String>::new());
+    }
 }
@@ -1,10 +1,13 @@
 //! Type environments.
 use std::{fmt, mem};
 
+use serde_json::json;
+
 use crate::semantic::{
+    fresh::{Fresh, Fresher},
     nodes::Symbol,
     sub::{apply2, Substitutable, Substituter},
-    types::{PolyType, PolyTypeHashMap, PolyTypeMap},
+    types::{PolyType, PolyTypeHashMap, PolyTypeMap, TvarMap},
     PackageExports,
 };
 
@@ -130,6 +133,14 @@ impl Environment<'_> {
         self.parent = Some(Box::new(parent));
     }
 
+    /// Adds `root` as the outermost frame of the environment.
+    pub(crate) fn push_root(&mut self, root: Self) {
+        match &mut self.parent {
+            Some(parent) => parent.push_root(root),
+            None => self.parent = Some(Box::new(root)),
+        }
+    }
+
     /// Check whether a `PolyType` `t` given by a
     /// string identifier is in the environment. Also checks parent environments.
     /// If the type is present, returns a pointer to `t`; otherwise, returns `None`.
@@ -145,6 +156,24 @@ impl Environment<'_> {
         }
     }
 
+    /// Returns `true` if `v` is bound in a read-only frame or in the external environment. Such
+    /// bindings are never modified while inferring a package.
+    pub(crate) fn is_readonly(&self, v: &Symbol) -> bool {
+        if self.values.contains_key(v) {
+            !self.readwrite
+        } else if self
+            .external
+            .as_ref()
+            .map_or(false, |env| env.lookup(v).is_some())
+        {
+            true
+        } else if let Some(env) = &self.parent {
+            env.is_readonly(v)
+        } else {
+            false
+        }
+    }
+
     /// Check whether a `PolyType` `t` given by a
     /// string identifier is in the environment. Also checks parent environments.
     /// If the type is present, returns a pointer to `t`; otherwise, returns `None`.
@@ -217,6 +246,84 @@ impl Environment<'_> {
             .collect()
     }
 
+    /// Returns the bindings of the current stack frame (and of the external environment, if any) as
+    /// a JSON array sorted by name.
+    ///
+    /// Each binding is an object holding the `name` of the identifier, its `type` as displayed by
+    /// Flux, the quantified `vars` with their `id` and `name`, the kind `constraints` on those
+    /// vars and the serialized monotype `expr`. Type variables are renumbered from zero so that the
+    /// ids in `expr` match the names used in `type`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.bindings()
+                .into_iter()
+                .map(|(name, typ)| poly_type_json(name, typ))
+                .collect(),
+        )
+    }
+
+    /// Compares the bindings of the current stack frame (and of the external environment, if any)
+    /// with the ones of `other`, a later version of the same environment.
+    ///
+    /// A binding whose type only differs by the names of its type variables is unchanged. A
+    /// change is breaking unless the new type is a generalization of the old one, that is unless
+    /// every use of the old type is also valid for the new type.
+    pub fn diff(&self, other: &Environment) -> EnvDiff {
+        let old = self.bindings();
+        let new = other.bindings();
+        let lookup = |bindings: &[(&str, &PolyType)], name: &str| {
+            bindings
+                .binary_search_by(|(k, _)| (*k).cmp(name))
+                .ok()
+                .map(|i| bindings[i].1)
+        };
+
+        let mut diff = EnvDiff::default();
+        for (name, old_typ) in &old {
+            match lookup(&new, name) {
+                Some(new_typ) => {
+                    let breaking = !new_typ.subsumes(old_typ);
+                    if breaking || !old_typ.subsumes(new_typ) {
+                        diff.changed.push(TypeChange {
+                            name: name.to_string(),
+                            old: (*old_typ).clone(),
+                            new: new_typ.clone(),
+                            breaking,
+                        });
+                    }
+                }
+                None => diff.removed.push((name.to_string(), (*old_typ).clone())),
+            }
+        }
+        for (name, new_typ) in &new {
+            if lookup(&old, name).is_none() {
+                diff.added.push((name.to_string(), (*new_typ).clone()));
+            }
+        }
+        diff
+    }
+
+    // The bindings of the current stack frame and of the external environment, sorted by name
+    fn bindings(&self) -> Vec<(&str, &PolyType)> {
+        let mut bindings: Vec<(&str, &PolyType)> = self
+            .values
+            .iter_by(|l, r| l.name().cmp(r.name()))
+            .map(|(k, v)| (k.name(), v))
+            .chain(self.external.iter().flat_map(|external| external.iter()))
+            .collect();
+        bindings.sort_by(|l, r| l.0.cmp(r.0));
+        bindings
+    }
+
+    /// Returns the names bound in this environment, its parents and its external environment.
+    pub(crate) fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.bindings().into_iter().map(|(name, _)| name).collect();
+        if let Some(parent) = &self.parent {
+            names.extend(parent.names());
+        }
+        names
+    }
+
     fn fmt_display(&self, f: &mut fmt::DebugMap<'_, '_>) {
         f.entries(
             self.values
@@ -231,3 +338,62 @@ impl Environment<'_> {
         }
     }
 }
+
+/// The differences between the bindings of two environments, see [`Environment::diff`].
+///
+/// Each list is sorted by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvDiff {
+    /// Bindings which only exist in the new environment.
+    pub added: Vec<(String, PolyType)>,
+    /// Bindings which only exist in the old environment. Removing a binding is always breaking.
+    pub removed: Vec<(String, PolyType)>,
+    /// Bindings whose type changed.
+    pub changed: Vec<TypeChange>,
+}
+
+impl EnvDiff {
+    /// Returns `true` if a binding was removed or changed in a breaking way.
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || self.changed.iter().any(|change| change.breaking)
+    }
+}
+
+/// The change of the type of a binding, see [`EnvDiff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeChange {
+    /// The name of the binding.
+    pub name: String,
+    /// The type of the binding in the old environment.
+    pub old: PolyType,
+    /// The type of the binding in the new environment.
+    pub new: PolyType,
+    /// `true` unless `new` is a generalization of `old`.
+    pub breaking: bool,
+}
+
+fn poly_type_json(name: &str, typ: &PolyType) -> serde_json::Value {
+    let typ = typ
+        .clone()
+        .fresh(&mut Fresher::default(), &mut TvarMap::new());
+    json!({
+        "name": name,
+        "type": typ.to_string(),
+        "vars": typ
+            .vars
+            .iter()
+            .map(|var| json!({ "id": var.0, "name": var.to_string() }))
+            .collect::<Vec<_>>(),
+        "constraints": typ
+            .cons
+            .iter()
+            .map(|(var, kinds)| {
+                json!({
+                    "var": var.to_string(),
+                    "kinds": kinds.iter().map(|kind| kind.to_string()).collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>(),
+        "expr": typ.expr,
+    })
+}
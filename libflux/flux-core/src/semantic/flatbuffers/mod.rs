@@ -323,6 +323,74 @@ impl<'a, 'b> semantic::walk::Visitor<'_> for SerializingVisitor<'a, 'b> {
                 ));
             }
 
+            // The flatbuffer schema has no tuple nodes. A tuple is serialized as a record whose
+            // properties are named by position and indexing as the matching member access.
+            walk::Node::TupleExpr(tuple) => {
+                let start = v.expr_stack.len() - tuple.elements.len();
+                let elements = v.expr_stack.split_off(start);
+                let mut prop_vec = Vec::with_capacity(elements.len());
+                for (i, (value, value_type)) in elements.into_iter().enumerate() {
+                    let name = v.create_string(&i.to_string());
+                    let key = fbsemantic::Identifier::create(
+                        v.builder,
+                        &fbsemantic::IdentifierArgs { loc, name },
+                    );
+                    prop_vec.push(fbsemantic::Property::create(
+                        v.builder,
+                        &fbsemantic::PropertyArgs {
+                            loc,
+                            key: Some(key),
+                            value_type,
+                            value: Some(value),
+                        },
+                    ));
+                }
+                let properties = Some(v.builder.create_vector(prop_vec.as_slice()));
+
+                let tuple_type = tuple.typ.clone();
+                let (typ, typ_type) = types::build_type(v.builder, &tuple_type);
+
+                let obj = fbsemantic::ObjectExpression::create(
+                    v.builder,
+                    &fbsemantic::ObjectExpressionArgs {
+                        loc,
+                        with: None,
+                        properties,
+                        typ: Some(typ),
+                        typ_type,
+                    },
+                );
+                v.expr_stack.push((
+                    obj.as_union_value(),
+                    fbsemantic::Expression::ObjectExpression,
+                ));
+            }
+
+            walk::Node::TupleIndexExpr(index) => {
+                let property = v.create_string(&index.index.to_string());
+                let (object, object_type) = v.pop_expr();
+
+                let index_typ = index.typ.clone();
+                let (typ, typ_type) = types::build_type(v.builder, &index_typ);
+
+                let mem = fbsemantic::MemberExpression::create(
+                    v.builder,
+                    &fbsemantic::MemberExpressionArgs {
+                        loc,
+                        object,
+                        object_type,
+                        property,
+                        typ: Some(typ),
+                        typ_type,
+                        optional: false,
+                    },
+                );
+                v.expr_stack.push((
+                    mem.as_union_value(),
+                    fbsemantic::Expression::MemberExpression,
+                ));
+            }
+
             walk::Node::MemberExpr(member) => {
                 let property = v.create_symbol(&member.property);
                 let (object, object_type) = v.pop_expr();
@@ -339,6 +407,7 @@ impl<'a, 'b> semantic::walk::Visitor<'_> for SerializingVisitor<'a, 'b> {
                         property,
                         typ: Some(typ),
                         typ_type,
+                        optional: member.optional,
                     },
                 );
                 v.expr_stack.push((
@@ -470,43 +539,7 @@ impl<'a, 'b> semantic::walk::Visitor<'_> for SerializingVisitor<'a, 'b> {
                     Some(vec)
                 };
 
-                let mut block_len = 0;
-                let mut current = &func.body;
-                let body_start_pos = &current.loc().start;
-                let mut body_end_pos = &current.loc().end;
-                loop {
-                    block_len += 1;
-                    body_end_pos = &current.loc().end;
-                    match current {
-                        semantic::nodes::Block::Expr(_, next) => {
-                            current = next.as_ref();
-                        }
-                        semantic::nodes::Block::Variable(_, next) => {
-                            current = next.as_ref();
-                        }
-                        semantic::nodes::Block::Return(retn) => {
-                            break;
-                        }
-                    }
-                }
-                let body_loc = ast::SourceLocation {
-                    file: func.loc.file.clone(),
-                    start: *body_start_pos,
-                    end: *body_end_pos,
-                    source: None,
-                };
-                let body_loc = v.create_loc(&body_loc);
-                let body_vec = {
-                    let stmt_vec = v.create_stmt_vector(block_len);
-                    Some(v.builder.create_vector(stmt_vec.as_slice()))
-                };
-                let body = Some(fbsemantic::Block::create(
-                    v.builder,
-                    &fbsemantic::BlockArgs {
-                        loc: body_loc,
-                        body: body_vec,
-                    },
-                ));
+                let body = Some(v.create_block(&func.body, &func.loc.file));
 
                 let func_typ = func.typ.clone();
                 let (typ, typ_type) = types::build_type(v.builder, &func_typ);
@@ -533,6 +566,113 @@ impl<'a, 'b> semantic::walk::Visitor<'_> for SerializingVisitor<'a, 'b> {
                 ));
             }
 
+            // The flatbuffer schema has no block expressions. A block expression is serialized as
+            // a call of a function without parameters, whose body is the block.
+            walk::Node::BlockExpr(block) => {
+                let body = Some(v.create_block(&block.body, &block.loc.file));
+
+                let func_typ = semantic::types::MonoType::from(semantic::types::Function {
+                    req: semantic::types::MonoTypeMap::new(),
+                    opt: semantic::types::MonoTypeMap::new(),
+                    pipe: None,
+                    retn: block.typ.clone(),
+                });
+                let (typ, typ_type) = types::build_type(v.builder, &func_typ);
+                let params = Some(v.builder.create_vector::<WIPOffset<_>>(&[]));
+                let func = fbsemantic::FunctionExpression::create(
+                    v.builder,
+                    &fbsemantic::FunctionExpressionArgs {
+                        loc,
+                        params,
+                        body,
+                        typ: Some(typ),
+                        typ_type,
+                        vectorized: None,
+                    },
+                );
+
+                let call_typ = block.typ.clone();
+                let (typ, typ_type) = types::build_type(v.builder, &call_typ);
+                let arguments = Some(v.builder.create_vector::<WIPOffset<_>>(&[]));
+                let call = fbsemantic::CallExpression::create(
+                    v.builder,
+                    &fbsemantic::CallExpressionArgs {
+                        loc,
+                        callee: Some(func.as_union_value()),
+                        callee_type: fbsemantic::Expression::FunctionExpression,
+                        arguments,
+                        pipe: None,
+                        pipe_type: fbsemantic::Expression::NONE,
+                        typ: Some(typ),
+                        typ_type,
+                    },
+                );
+                v.expr_stack.push((
+                    call.as_union_value(),
+                    fbsemantic::Expression::CallExpression,
+                ));
+            }
+
+            // The flatbuffer schema has no match expressions either. A match expression is
+            // serialized as conditional expressions comparing the scrutinee with the pattern of
+            // each arm in turn. The final alternate is the body of the wildcard arm, or a call of
+            // `die` if there is none, which only happens on a bool missing one of its values.
+            walk::Node::MatchExpr(expr) => {
+                let mut arms = Vec::with_capacity(expr.arms.len());
+                for arm in expr.arms.iter().rev() {
+                    let body = v.pop_expr();
+                    let pattern = arm.pattern.as_ref().map(|_| v.pop_expr());
+                    arms.push((arm, pattern, body));
+                }
+                let (scrutinee, scrutinee_type) = v.pop_expr();
+
+                let (mut alternate, mut alternate_type) = v.create_no_match(expr);
+                for (arm, pattern, (consequent, consequent_type)) in arms {
+                    let (pattern, pattern_type) = match pattern {
+                        Some(pattern) => pattern,
+                        // Arms after a wildcard are never reached
+                        None => {
+                            alternate = consequent;
+                            alternate_type = consequent_type;
+                            continue;
+                        }
+                    };
+                    let arm_loc = v.create_loc(&arm.loc);
+                    let (typ, typ_type) =
+                        types::build_type(v.builder, &semantic::types::MonoType::BOOL);
+                    let test = fbsemantic::BinaryExpression::create(
+                        v.builder,
+                        &fbsemantic::BinaryExpressionArgs {
+                            loc: arm_loc,
+                            operator: fbsemantic::Operator::EqualOperator,
+                            left_type: scrutinee_type,
+                            left: scrutinee,
+                            right_type: pattern_type,
+                            right: pattern,
+                            typ: Some(typ),
+                            typ_type,
+                        },
+                    );
+                    let cond = fbsemantic::ConditionalExpression::create(
+                        v.builder,
+                        &fbsemantic::ConditionalExpressionArgs {
+                            loc: arm_loc,
+                            test_type: fbsemantic::Expression::BinaryExpression,
+                            test: Some(test.as_union_value()),
+                            alternate_type,
+                            alternate,
+                            consequent_type,
+                            consequent,
+                        },
+                    );
+                    alternate = Some(cond.as_union_value());
+                    alternate_type = fbsemantic::Expression::ConditionalExpression;
+                }
+                if let Some(alternate) = alternate {
+                    v.expr_stack.push((alternate, alternate_type));
+                }
+            }
+
             walk::Node::FunctionParameter(func_param) => {
                 let key = v.pop_ident();
 
@@ -793,6 +933,11 @@ impl<'a, 'b> semantic::walk::Visitor<'_> for SerializingVisitor<'a, 'b> {
                 ));
             }
 
+            // Type aliases have already been expanded and are not serialized
+            walk::Node::TypeAliasStmt(_) => {
+                v.pop_ident::<fbsemantic::Identifier>();
+            }
+
             walk::Node::OptionStmt(opt) => {
                 let (assignment, assignment_type) = {
                     match &opt.assignment {
@@ -874,7 +1019,12 @@ impl<'a, 'b> semantic::walk::Visitor<'_> for SerializingVisitor<'a, 'b> {
                 let imports = Some(v.builder.create_vector(v.import_decls.as_slice()));
                 v.import_decls.clear();
 
-                let stmt_vec = v.create_stmt_vector(file.body.len());
+                let num_of_stmts = file
+                    .body
+                    .iter()
+                    .filter(|stmt| !matches!(stmt, semantic::nodes::Statement::TypeAlias(_)))
+                    .count();
+                let stmt_vec = v.create_stmt_vector(num_of_stmts);
                 let body = Some(v.builder.create_vector(stmt_vec.as_slice()));
 
                 let file = fbsemantic::File::create(
@@ -1017,6 +1167,72 @@ impl<'a, 'b> SerializingVisitor<'a, 'b> {
         Some(self.builder.create_shared_string(symbol.full_name()))
     }
 
+    // Creates a call of `die` failing because none of the arms of `expr` matched.
+    fn create_no_match(
+        &mut self,
+        expr: &semantic::nodes::MatchExpr,
+    ) -> (Option<WIPOffset<UnionWIPOffset>>, fbsemantic::Expression) {
+        let loc = self.create_loc(&expr.loc);
+
+        let value = self.create_string("no arm of the match expression matched");
+        let msg = fbsemantic::StringLiteral::create(
+            self.builder,
+            &fbsemantic::StringLiteralArgs { loc, value },
+        );
+        let name = self.create_string("msg");
+        let key =
+            fbsemantic::Identifier::create(self.builder, &fbsemantic::IdentifierArgs { loc, name });
+        let arguments = fbsemantic::Property::create(
+            self.builder,
+            &fbsemantic::PropertyArgs {
+                loc,
+                key: Some(key),
+                value_type: fbsemantic::Expression::StringLiteral,
+                value: Some(msg.as_union_value()),
+            },
+        );
+        let arguments = Some(self.builder.create_vector(&[arguments]));
+
+        let mut req = semantic::types::MonoTypeMap::new();
+        req.insert("msg".to_string(), semantic::types::MonoType::STRING);
+        let die_typ = semantic::types::MonoType::from(semantic::types::Function {
+            req,
+            opt: semantic::types::MonoTypeMap::new(),
+            pipe: None,
+            retn: expr.typ.clone(),
+        });
+        let (typ, typ_type) = types::build_type(self.builder, &die_typ);
+        let name = self.create_string("die@universe");
+        let die = fbsemantic::IdentifierExpression::create(
+            self.builder,
+            &fbsemantic::IdentifierExpressionArgs {
+                loc,
+                name,
+                typ: Some(typ),
+                typ_type,
+            },
+        );
+
+        let (typ, typ_type) = types::build_type(self.builder, &expr.typ);
+        let call = fbsemantic::CallExpression::create(
+            self.builder,
+            &fbsemantic::CallExpressionArgs {
+                loc,
+                callee: Some(die.as_union_value()),
+                callee_type: fbsemantic::Expression::IdentifierExpression,
+                arguments,
+                pipe: None,
+                pipe_type: fbsemantic::Expression::NONE,
+                typ: Some(typ),
+                typ_type,
+            },
+        );
+        (
+            Some(call.as_union_value()),
+            fbsemantic::Expression::CallExpression,
+        )
+    }
+
     fn create_string(&mut self, string: &str) -> Option<WIPOffset<&'a str>> {
         Some(self.builder.create_string(string))
     }
@@ -1028,6 +1244,51 @@ impl<'a, 'b> SerializingVisitor<'a, 'b> {
         }
     }
 
+    // Creates a block from the statements of `block`, which are the last ones serialized.
+    fn create_block(
+        &mut self,
+        block: &semantic::nodes::Block,
+        file: &Option<String>,
+    ) -> WIPOffset<fbsemantic::Block<'a>> {
+        let mut block_len = 0;
+        let mut current = block;
+        let body_start_pos = &current.loc().start;
+        let mut body_end_pos = &current.loc().end;
+        loop {
+            block_len += 1;
+            body_end_pos = &current.loc().end;
+            match current {
+                semantic::nodes::Block::Expr(_, next) => {
+                    current = next.as_ref();
+                }
+                semantic::nodes::Block::Variable(_, next) => {
+                    current = next.as_ref();
+                }
+                semantic::nodes::Block::Return(_) => {
+                    break;
+                }
+            }
+        }
+        let body_loc = ast::SourceLocation {
+            file: file.clone(),
+            start: *body_start_pos,
+            end: *body_end_pos,
+            source: None,
+        };
+        let body_loc = self.create_loc(&body_loc);
+        let body_vec = {
+            let stmt_vec = self.create_stmt_vector(block_len);
+            Some(self.builder.create_vector(stmt_vec.as_slice()))
+        };
+        fbsemantic::Block::create(
+            self.builder,
+            &fbsemantic::BlockArgs {
+                loc: body_loc,
+                body: body_vec,
+            },
+        )
+    }
+
     fn create_stmt_vector(
         &mut self,
         num_of_stmts: usize,
@@ -1112,6 +1373,7 @@ fn fb_operator(o: &ast::Operator) -> fbsemantic::Operator {
         ast::Operator::DivisionOperator => fbsemantic::Operator::DivisionOperator,
         ast::Operator::ModuloOperator => fbsemantic::Operator::ModuloOperator,
         ast::Operator::PowerOperator => fbsemantic::Operator::PowerOperator,
+        ast::Operator::ExponentiationOperator => fbsemantic::Operator::ExponentiationOperator,
         ast::Operator::AdditionOperator => fbsemantic::Operator::AdditionOperator,
         ast::Operator::SubtractionOperator => fbsemantic::Operator::SubtractionOperator,
         ast::Operator::LessThanEqualOperator => fbsemantic::Operator::LessThanEqualOperator,
@@ -1128,6 +1390,7 @@ fn fb_operator(o: &ast::Operator) -> fbsemantic::Operator {
         ast::Operator::NotEqualOperator => fbsemantic::Operator::NotEqualOperator,
         ast::Operator::RegexpMatchOperator => fbsemantic::Operator::RegexpMatchOperator,
         ast::Operator::NotRegexpMatchOperator => fbsemantic::Operator::NotRegexpMatchOperator,
+        ast::Operator::NullCoalescingOperator => fbsemantic::Operator::NullCoalescingOperator,
         ast::Operator::InvalidOperator => fbsemantic::Operator::InvalidOperator,
     }
 }
@@ -24,13 +24,13 @@ pub mod fbsemantic {
         since = "2.0.0",
         note = "Use associated constants instead. This will no longer be generated in 2021."
     )]
-    pub const ENUM_MAX_MONO_TYPE: u8 = 6;
+    pub const ENUM_MAX_MONO_TYPE: u8 = 7;
     #[deprecated(
         since = "2.0.0",
         note = "Use associated constants instead. This will no longer be generated in 2021."
     )]
     #[allow(non_camel_case_types)]
-    pub const ENUM_VALUES_MONO_TYPE: [MonoType; 7] = [
+    pub const ENUM_VALUES_MONO_TYPE: [MonoType; 8] = [
         MonoType::NONE,
         MonoType::Basic,
         MonoType::Var,
@@ -38,6 +38,7 @@ pub mod fbsemantic {
         MonoType::Record,
         MonoType::Fun,
         MonoType::Dict,
+        MonoType::Tuple,
     ];
 
     #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -52,9 +53,10 @@ pub mod fbsemantic {
         pub const Record: Self = Self(4);
         pub const Fun: Self = Self(5);
         pub const Dict: Self = Self(6);
+        pub const Tuple: Self = Self(7);
 
         pub const ENUM_MIN: u8 = 0;
-        pub const ENUM_MAX: u8 = 6;
+        pub const ENUM_MAX: u8 = 7;
         pub const ENUM_VALUES: &'static [Self] = &[
             Self::NONE,
             Self::Basic,
@@ -63,6 +65,7 @@ pub mod fbsemantic {
             Self::Record,
             Self::Fun,
             Self::Dict,
+            Self::Tuple,
         ];
         /// Returns the variant's name or "" if unknown.
         pub fn variant_name(self) -> Option<&'static str> {
@@ -74,6 +77,7 @@ pub mod fbsemantic {
                 Self::Record => Some("Record"),
                 Self::Fun => Some("Fun"),
                 Self::Dict => Some("Dict"),
+                Self::Tuple => Some("Tuple"),
                 _ => None,
             }
         }
@@ -467,7 +471,7 @@ pub mod fbsemantic {
         note = "Use associated constants instead. This will no longer be generated in 2021."
     )]
     #[allow(non_camel_case_types)]
-    pub const ENUM_VALUES_KIND: [Kind; 13] = [
+    pub const ENUM_VALUES_KIND: [Kind; 14] = [
         Kind::Addable,
         Kind::Basic,
         Kind::Subtractable,
@@ -481,6 +485,7 @@ pub mod fbsemantic {
         Kind::Negatable,
         Kind::Timeable,
         Kind::Stringable,
+        Kind::Lengthable,
     ];
 
     #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -501,9 +506,10 @@ pub mod fbsemantic {
         pub const Negatable: Self = Self(10);
         pub const Timeable: Self = Self(11);
         pub const Stringable: Self = Self(12);
+        pub const Lengthable: Self = Self(13);
 
         pub const ENUM_MIN: u8 = 0;
-        pub const ENUM_MAX: u8 = 12;
+        pub const ENUM_MAX: u8 = 13;
         pub const ENUM_VALUES: &'static [Self] = &[
             Self::Addable,
             Self::Basic,
@@ -518,6 +524,7 @@ pub mod fbsemantic {
             Self::Negatable,
             Self::Timeable,
             Self::Stringable,
+            Self::Lengthable,
         ];
         /// Returns the variant's name or "" if unknown.
         pub fn variant_name(self) -> Option<&'static str> {
@@ -535,6 +542,7 @@ pub mod fbsemantic {
                 Self::Negatable => Some("Negatable"),
                 Self::Timeable => Some("Timeable"),
                 Self::Stringable => Some("Stringable"),
+                Self::Lengthable => Some("Lengthable"),
                 _ => None,
             }
         }
@@ -1007,13 +1015,13 @@ pub mod fbsemantic {
         since = "2.0.0",
         note = "Use associated constants instead. This will no longer be generated in 2021."
     )]
-    pub const ENUM_MAX_OPERATOR: i8 = 20;
+    pub const ENUM_MAX_OPERATOR: i8 = 22;
     #[deprecated(
         since = "2.0.0",
         note = "Use associated constants instead. This will no longer be generated in 2021."
     )]
     #[allow(non_camel_case_types)]
-    pub const ENUM_VALUES_OPERATOR: [Operator; 21] = [
+    pub const ENUM_VALUES_OPERATOR: [Operator; 23] = [
         Operator::MultiplicationOperator,
         Operator::DivisionOperator,
         Operator::ModuloOperator,
@@ -1035,6 +1043,8 @@ pub mod fbsemantic {
         Operator::RegexpMatchOperator,
         Operator::NotRegexpMatchOperator,
         Operator::InvalidOperator,
+        Operator::NullCoalescingOperator,
+        Operator::ExponentiationOperator,
     ];
 
     #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -1063,9 +1073,11 @@ pub mod fbsemantic {
         pub const RegexpMatchOperator: Self = Self(18);
         pub const NotRegexpMatchOperator: Self = Self(19);
         pub const InvalidOperator: Self = Self(20);
+        pub const NullCoalescingOperator: Self = Self(21);
+        pub const ExponentiationOperator: Self = Self(22);
 
         pub const ENUM_MIN: i8 = 0;
-        pub const ENUM_MAX: i8 = 20;
+        pub const ENUM_MAX: i8 = 22;
         pub const ENUM_VALUES: &'static [Self] = &[
             Self::MultiplicationOperator,
             Self::DivisionOperator,
@@ -1088,6 +1100,8 @@ pub mod fbsemantic {
             Self::RegexpMatchOperator,
             Self::NotRegexpMatchOperator,
             Self::InvalidOperator,
+            Self::NullCoalescingOperator,
+            Self::ExponentiationOperator,
         ];
         /// Returns the variant's name or "" if unknown.
         pub fn variant_name(self) -> Option<&'static str> {
@@ -1113,6 +1127,8 @@ pub mod fbsemantic {
                 Self::RegexpMatchOperator => Some("RegexpMatchOperator"),
                 Self::NotRegexpMatchOperator => Some("NotRegexpMatchOperator"),
                 Self::InvalidOperator => Some("InvalidOperator"),
+                Self::NullCoalescingOperator => Some("NullCoalescingOperator"),
+                Self::ExponentiationOperator => Some("ExponentiationOperator"),
                 _ => None,
             }
         }
@@ -1749,6 +1765,9 @@ pub mod fbsemantic {
             args: &'args TypeEnvironmentArgs<'args>,
         ) -> flatbuffers::WIPOffset<TypeEnvironment<'bldr>> {
             let mut builder = TypeEnvironmentBuilder::new(_fbb);
+            if let Some(x) = args.private {
+                builder.add_private(x);
+            }
             if let Some(x) = args.assignments {
                 builder.add_assignments(x);
             }
@@ -1756,6 +1775,7 @@ pub mod fbsemantic {
         }
 
         pub const VT_ASSIGNMENTS: flatbuffers::VOffsetT = 4;
+        pub const VT_PRIVATE: flatbuffers::VOffsetT = 6;
 
         #[inline]
         pub fn assignments(
@@ -1766,6 +1786,14 @@ pub mod fbsemantic {
                 flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<TypeAssignment>>,
             >>(TypeEnvironment::VT_ASSIGNMENTS, None)
         }
+        #[inline]
+        pub fn private(
+            &self,
+        ) -> Option<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<&'a str>>> {
+            self._tab.get::<flatbuffers::ForwardsUOffset<
+                flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<&'a str>>,
+            >>(TypeEnvironment::VT_PRIVATE, None)
+        }
     }
 
     impl flatbuffers::Verifiable for TypeEnvironment<'_> {
@@ -1779,6 +1807,9 @@ pub mod fbsemantic {
                 .visit_field::<flatbuffers::ForwardsUOffset<
                     flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<TypeAssignment>>,
                 >>(&"assignments", Self::VT_ASSIGNMENTS, false)?
+                .visit_field::<flatbuffers::ForwardsUOffset<
+                    flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<&'_ str>>,
+                >>(&"private", Self::VT_PRIVATE, false)?
                 .finish();
             Ok(())
         }
@@ -1789,11 +1820,17 @@ pub mod fbsemantic {
                 flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<TypeAssignment<'a>>>,
             >,
         >,
+        pub private: Option<
+            flatbuffers::WIPOffset<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<&'a str>>>,
+        >,
     }
     impl<'a> Default for TypeEnvironmentArgs<'a> {
         #[inline]
         fn default() -> Self {
-            TypeEnvironmentArgs { assignments: None }
+            TypeEnvironmentArgs {
+                assignments: None,
+                private: None,
+            }
         }
     }
     pub struct TypeEnvironmentBuilder<'a: 'b, 'b> {
@@ -1814,6 +1851,18 @@ pub mod fbsemantic {
             );
         }
         #[inline]
+        pub fn add_private(
+            &mut self,
+            private: flatbuffers::WIPOffset<
+                flatbuffers::Vector<'b, flatbuffers::ForwardsUOffset<&'b str>>,
+            >,
+        ) {
+            self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(
+                TypeEnvironment::VT_PRIVATE,
+                private,
+            );
+        }
+        #[inline]
         pub fn new(
             _fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>,
         ) -> TypeEnvironmentBuilder<'a, 'b> {
@@ -1834,6 +1883,7 @@ pub mod fbsemantic {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             let mut ds = f.debug_struct("TypeEnvironment");
             ds.field("assignments", &self.assignments());
+            ds.field("private", &self.private());
             ds.finish()
         }
     }
@@ -2064,6 +2114,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for MonoTypeHolder<'_> {
@@ -2111,6 +2171,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -2228,6 +2293,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -2543,6 +2618,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn arg_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.arg_type() == MonoType::Tuple {
+                self.arg().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for Collection<'_> {
@@ -2591,6 +2676,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -2717,6 +2807,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.arg_as_tuple() {
+                        ds.field("arg", &x)
+                    } else {
+                        ds.field(
+                            "arg",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("arg", &x)
@@ -2833,6 +2933,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn t_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.t_type() == MonoType::Tuple {
+                self.t().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for Stream<'_> {
@@ -2880,6 +2990,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -2995,6 +3110,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.t_as_tuple() {
+                        ds.field("t", &x)
+                    } else {
+                        ds.field(
+                            "t",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("t", &x)
@@ -3256,6 +3381,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn retn_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.retn_type() == MonoType::Tuple {
+                self.retn().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for Fun<'_> {
@@ -3306,6 +3441,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -3438,6 +3578,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.retn_as_tuple() {
+                        ds.field("retn", &x)
+                    } else {
+                        ds.field(
+                            "retn",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("retn", &x)
@@ -3572,6 +3722,16 @@ pub mod fbsemantic {
             }
         }
 
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn k_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.k_type() == MonoType::Tuple {
+                self.k().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
+
         #[inline]
         #[allow(non_snake_case)]
         pub fn v_as_basic(&self) -> Option<Basic<'a>> {
@@ -3631,6 +3791,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn v_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.v_type() == MonoType::Tuple {
+                self.v().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for Dict<'_> {
@@ -3678,6 +3848,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -3718,6 +3893,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -3847,6 +4027,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.k_as_tuple() {
+                        ds.field("k", &x)
+                    } else {
+                        ds.field(
+                            "k",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("k", &x)
@@ -3914,6 +4104,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.v_as_tuple() {
+                        ds.field("v", &x)
+                    } else {
+                        ds.field(
+                            "v",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("v", &x)
@@ -3922,6 +4122,118 @@ pub mod fbsemantic {
             ds.finish()
         }
     }
+    pub enum TupleOffset {}
+    #[derive(Copy, Clone, PartialEq)]
+
+    pub struct Tuple<'a> {
+        pub _tab: flatbuffers::Table<'a>,
+    }
+
+    impl<'a> flatbuffers::Follow<'a> for Tuple<'a> {
+        type Inner = Tuple<'a>;
+        #[inline]
+        fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            Self {
+                _tab: flatbuffers::Table { buf, loc },
+            }
+        }
+    }
+
+    impl<'a> Tuple<'a> {
+        #[inline]
+        pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+            Tuple { _tab: table }
+        }
+        #[allow(unused_mut)]
+        pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+            _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+            args: &'args TupleArgs<'args>,
+        ) -> flatbuffers::WIPOffset<Tuple<'bldr>> {
+            let mut builder = TupleBuilder::new(_fbb);
+            if let Some(x) = args.elements {
+                builder.add_elements(x);
+            }
+            builder.finish()
+        }
+
+        pub const VT_ELEMENTS: flatbuffers::VOffsetT = 4;
+
+        #[inline]
+        pub fn elements(
+            &self,
+        ) -> Option<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<MonoTypeHolder<'a>>>>
+        {
+            self._tab.get::<flatbuffers::ForwardsUOffset<
+                flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<MonoTypeHolder>>,
+            >>(Tuple::VT_ELEMENTS, None)
+        }
+    }
+
+    impl flatbuffers::Verifiable for Tuple<'_> {
+        #[inline]
+        fn run_verifier(
+            v: &mut flatbuffers::Verifier,
+            pos: usize,
+        ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+            use self::flatbuffers::Verifiable;
+            v.visit_table(pos)?
+                .visit_field::<flatbuffers::ForwardsUOffset<
+                    flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<MonoTypeHolder>>,
+                >>(&"elements", Self::VT_ELEMENTS, false)?
+                .finish();
+            Ok(())
+        }
+    }
+    pub struct TupleArgs<'a> {
+        pub elements: Option<
+            flatbuffers::WIPOffset<
+                flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<MonoTypeHolder<'a>>>,
+            >,
+        >,
+    }
+    impl<'a> Default for TupleArgs<'a> {
+        #[inline]
+        fn default() -> Self {
+            TupleArgs { elements: None }
+        }
+    }
+    pub struct TupleBuilder<'a: 'b, 'b> {
+        fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+        start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+    }
+    impl<'a: 'b, 'b> TupleBuilder<'a, 'b> {
+        #[inline]
+        pub fn add_elements(
+            &mut self,
+            elements: flatbuffers::WIPOffset<
+                flatbuffers::Vector<'b, flatbuffers::ForwardsUOffset<MonoTypeHolder<'b>>>,
+            >,
+        ) {
+            self.fbb_
+                .push_slot_always::<flatbuffers::WIPOffset<_>>(Tuple::VT_ELEMENTS, elements);
+        }
+        #[inline]
+        pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> TupleBuilder<'a, 'b> {
+            let start = _fbb.start_table();
+            TupleBuilder {
+                fbb_: _fbb,
+                start_: start,
+            }
+        }
+        #[inline]
+        pub fn finish(self) -> flatbuffers::WIPOffset<Tuple<'a>> {
+            let o = self.fbb_.end_table(self.start_);
+            flatbuffers::WIPOffset::new(o.value())
+        }
+    }
+
+    impl std::fmt::Debug for Tuple<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let mut ds = f.debug_struct("Tuple");
+            ds.field("elements", &self.elements());
+            ds.finish()
+        }
+    }
     pub enum ArgumentOffset {}
     #[derive(Copy, Clone, PartialEq)]
 
@@ -4055,6 +4367,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn t_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.t_type() == MonoType::Tuple {
+                self.t().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for Argument<'_> {
@@ -4103,6 +4425,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -4241,6 +4568,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.t_as_tuple() {
+                        ds.field("t", &x)
+                    } else {
+                        ds.field(
+                            "t",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("t", &x)
@@ -4382,6 +4719,7 @@ pub mod fbsemantic {
             if let Some(x) = args.k {
                 builder.add_k(x);
             }
+            builder.add_optional(args.optional);
             builder.add_v_type(args.v_type);
             builder.add_k_type(args.k_type);
             builder.finish()
@@ -4391,6 +4729,7 @@ pub mod fbsemantic {
         pub const VT_K: flatbuffers::VOffsetT = 6;
         pub const VT_V_TYPE: flatbuffers::VOffsetT = 8;
         pub const VT_V: flatbuffers::VOffsetT = 10;
+        pub const VT_OPTIONAL: flatbuffers::VOffsetT = 12;
 
         #[inline]
         pub fn k_type(&self) -> RecordLabel {
@@ -4415,6 +4754,12 @@ pub mod fbsemantic {
                 .get::<flatbuffers::ForwardsUOffset<flatbuffers::Table<'a>>>(Prop::VT_V, None)
         }
         #[inline]
+        pub fn optional(&self) -> bool {
+            self._tab
+                .get::<bool>(Prop::VT_OPTIONAL, Some(false))
+                .unwrap()
+        }
+        #[inline]
         #[allow(non_snake_case)]
         pub fn k_as_concrete(&self) -> Option<Concrete<'a>> {
             if self.k_type() == RecordLabel::Concrete {
@@ -4493,6 +4838,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn v_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.v_type() == MonoType::Tuple {
+                self.v().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for Prop<'_> {
@@ -4560,9 +4915,15 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
+                .visit_field::<bool>(&"optional", Self::VT_OPTIONAL, false)?
                 .finish();
             Ok(())
         }
@@ -4572,6 +4933,7 @@ pub mod fbsemantic {
         pub k: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
         pub v_type: MonoType,
         pub v: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
+        pub optional: bool,
     }
     impl<'a> Default for PropArgs {
         #[inline]
@@ -4581,6 +4943,7 @@ pub mod fbsemantic {
                 k: None,
                 v_type: MonoType::NONE,
                 v: None,
+                optional: false,
             }
         }
     }
@@ -4610,6 +4973,11 @@ pub mod fbsemantic {
                 .push_slot_always::<flatbuffers::WIPOffset<_>>(Prop::VT_V, v);
         }
         #[inline]
+        pub fn add_optional(&mut self, optional: bool) {
+            self.fbb_
+                .push_slot::<bool>(Prop::VT_OPTIONAL, optional, false);
+        }
+        #[inline]
         pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> PropBuilder<'a, 'b> {
             let start = _fbb.start_table();
             PropBuilder {
@@ -4716,11 +5084,22 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.v_as_tuple() {
+                        ds.field("v", &x)
+                    } else {
+                        ds.field(
+                            "v",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("v", &x)
                 }
             };
+            ds.field("optional", &self.optional());
             ds.finish()
         }
     }
@@ -4752,6 +5131,12 @@ pub mod fbsemantic {
             args: &'args PolyTypeArgs<'args>,
         ) -> flatbuffers::WIPOffset<PolyType<'bldr>> {
             let mut builder = PolyTypeBuilder::new(_fbb);
+            if let Some(x) = args.names {
+                builder.add_names(x);
+            }
+            if let Some(x) = args.label_sets {
+                builder.add_label_sets(x);
+            }
             if let Some(x) = args.expr {
                 builder.add_expr(x);
             }
@@ -4769,6 +5154,8 @@ pub mod fbsemantic {
         pub const VT_CONS: flatbuffers::VOffsetT = 6;
         pub const VT_EXPR_TYPE: flatbuffers::VOffsetT = 8;
         pub const VT_EXPR: flatbuffers::VOffsetT = 10;
+        pub const VT_LABEL_SETS: flatbuffers::VOffsetT = 12;
+        pub const VT_NAMES: flatbuffers::VOffsetT = 14;
 
         #[inline]
         pub fn vars(
@@ -4801,6 +5188,22 @@ pub mod fbsemantic {
                 )
         }
         #[inline]
+        pub fn label_sets(
+            &self,
+        ) -> Option<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<LabelSet<'a>>>> {
+            self._tab.get::<flatbuffers::ForwardsUOffset<
+                flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<LabelSet>>,
+            >>(PolyType::VT_LABEL_SETS, None)
+        }
+        #[inline]
+        pub fn names(
+            &self,
+        ) -> Option<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<TvarName<'a>>>> {
+            self._tab.get::<flatbuffers::ForwardsUOffset<
+                flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<TvarName>>,
+            >>(PolyType::VT_NAMES, None)
+        }
+        #[inline]
         #[allow(non_snake_case)]
         pub fn expr_as_basic(&self) -> Option<Basic<'a>> {
             if self.expr_type() == MonoType::Basic {
@@ -4859,6 +5262,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn expr_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.expr_type() == MonoType::Tuple {
+                self.expr().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for PolyType<'_> {
@@ -4912,9 +5325,20 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
+                .visit_field::<flatbuffers::ForwardsUOffset<
+                    flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<LabelSet>>,
+                >>(&"label_sets", Self::VT_LABEL_SETS, false)?
+                .visit_field::<flatbuffers::ForwardsUOffset<
+                    flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<TvarName>>,
+                >>(&"names", Self::VT_NAMES, false)?
                 .finish();
             Ok(())
         }
@@ -4930,6 +5354,16 @@ pub mod fbsemantic {
         >,
         pub expr_type: MonoType,
         pub expr: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
+        pub label_sets: Option<
+            flatbuffers::WIPOffset<
+                flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<LabelSet<'a>>>,
+            >,
+        >,
+        pub names: Option<
+            flatbuffers::WIPOffset<
+                flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<TvarName<'a>>>,
+            >,
+        >,
     }
     impl<'a> Default for PolyTypeArgs<'a> {
         #[inline]
@@ -4939,6 +5373,8 @@ pub mod fbsemantic {
                 cons: None,
                 expr_type: MonoType::NONE,
                 expr: None,
+                label_sets: None,
+                names: None,
             }
         }
     }
@@ -4978,6 +5414,26 @@ pub mod fbsemantic {
                 .push_slot_always::<flatbuffers::WIPOffset<_>>(PolyType::VT_EXPR, expr);
         }
         #[inline]
+        pub fn add_label_sets(
+            &mut self,
+            label_sets: flatbuffers::WIPOffset<
+                flatbuffers::Vector<'b, flatbuffers::ForwardsUOffset<LabelSet<'b>>>,
+            >,
+        ) {
+            self.fbb_
+                .push_slot_always::<flatbuffers::WIPOffset<_>>(PolyType::VT_LABEL_SETS, label_sets);
+        }
+        #[inline]
+        pub fn add_names(
+            &mut self,
+            names: flatbuffers::WIPOffset<
+                flatbuffers::Vector<'b, flatbuffers::ForwardsUOffset<TvarName<'b>>>,
+            >,
+        ) {
+            self.fbb_
+                .push_slot_always::<flatbuffers::WIPOffset<_>>(PolyType::VT_NAMES, names);
+        }
+        #[inline]
         pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> PolyTypeBuilder<'a, 'b> {
             let start = _fbb.start_table();
             PolyTypeBuilder {
@@ -5059,23 +5515,280 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.expr_as_tuple() {
+                        ds.field("expr", &x)
+                    } else {
+                        ds.field(
+                            "expr",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("expr", &x)
                 }
             };
+            ds.field("label_sets", &self.label_sets());
+            ds.field("names", &self.names());
+            ds.finish()
+        }
+    }
+    pub enum ConstraintOffset {}
+    #[derive(Copy, Clone, PartialEq)]
+
+    pub struct Constraint<'a> {
+        pub _tab: flatbuffers::Table<'a>,
+    }
+
+    impl<'a> flatbuffers::Follow<'a> for Constraint<'a> {
+        type Inner = Constraint<'a>;
+        #[inline]
+        fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            Self {
+                _tab: flatbuffers::Table { buf, loc },
+            }
+        }
+    }
+
+    impl<'a> Constraint<'a> {
+        #[inline]
+        pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+            Constraint { _tab: table }
+        }
+        #[allow(unused_mut)]
+        pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+            _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+            args: &'args ConstraintArgs<'args>,
+        ) -> flatbuffers::WIPOffset<Constraint<'bldr>> {
+            let mut builder = ConstraintBuilder::new(_fbb);
+            if let Some(x) = args.tvar {
+                builder.add_tvar(x);
+            }
+            builder.add_kind(args.kind);
+            builder.finish()
+        }
+
+        pub const VT_TVAR: flatbuffers::VOffsetT = 4;
+        pub const VT_KIND: flatbuffers::VOffsetT = 6;
+
+        #[inline]
+        pub fn tvar(&self) -> Option<Var<'a>> {
+            self._tab
+                .get::<flatbuffers::ForwardsUOffset<Var>>(Constraint::VT_TVAR, None)
+        }
+        #[inline]
+        pub fn kind(&self) -> Kind {
+            self._tab
+                .get::<Kind>(Constraint::VT_KIND, Some(Kind::Addable))
+                .unwrap()
+        }
+    }
+
+    impl flatbuffers::Verifiable for Constraint<'_> {
+        #[inline]
+        fn run_verifier(
+            v: &mut flatbuffers::Verifier,
+            pos: usize,
+        ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+            use self::flatbuffers::Verifiable;
+            v.visit_table(pos)?
+                .visit_field::<flatbuffers::ForwardsUOffset<Var>>(&"tvar", Self::VT_TVAR, false)?
+                .visit_field::<Kind>(&"kind", Self::VT_KIND, false)?
+                .finish();
+            Ok(())
+        }
+    }
+    pub struct ConstraintArgs<'a> {
+        pub tvar: Option<flatbuffers::WIPOffset<Var<'a>>>,
+        pub kind: Kind,
+    }
+    impl<'a> Default for ConstraintArgs<'a> {
+        #[inline]
+        fn default() -> Self {
+            ConstraintArgs {
+                tvar: None,
+                kind: Kind::Addable,
+            }
+        }
+    }
+    pub struct ConstraintBuilder<'a: 'b, 'b> {
+        fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+        start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+    }
+    impl<'a: 'b, 'b> ConstraintBuilder<'a, 'b> {
+        #[inline]
+        pub fn add_tvar(&mut self, tvar: flatbuffers::WIPOffset<Var<'b>>) {
+            self.fbb_
+                .push_slot_always::<flatbuffers::WIPOffset<Var>>(Constraint::VT_TVAR, tvar);
+        }
+        #[inline]
+        pub fn add_kind(&mut self, kind: Kind) {
+            self.fbb_
+                .push_slot::<Kind>(Constraint::VT_KIND, kind, Kind::Addable);
+        }
+        #[inline]
+        pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> ConstraintBuilder<'a, 'b> {
+            let start = _fbb.start_table();
+            ConstraintBuilder {
+                fbb_: _fbb,
+                start_: start,
+            }
+        }
+        #[inline]
+        pub fn finish(self) -> flatbuffers::WIPOffset<Constraint<'a>> {
+            let o = self.fbb_.end_table(self.start_);
+            flatbuffers::WIPOffset::new(o.value())
+        }
+    }
+
+    impl std::fmt::Debug for Constraint<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let mut ds = f.debug_struct("Constraint");
+            ds.field("tvar", &self.tvar());
+            ds.field("kind", &self.kind());
+            ds.finish()
+        }
+    }
+    pub enum LabelSetOffset {}
+    #[derive(Copy, Clone, PartialEq)]
+
+    pub struct LabelSet<'a> {
+        pub _tab: flatbuffers::Table<'a>,
+    }
+
+    impl<'a> flatbuffers::Follow<'a> for LabelSet<'a> {
+        type Inner = LabelSet<'a>;
+        #[inline]
+        fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            Self {
+                _tab: flatbuffers::Table { buf, loc },
+            }
+        }
+    }
+
+    impl<'a> LabelSet<'a> {
+        #[inline]
+        pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+            LabelSet { _tab: table }
+        }
+        #[allow(unused_mut)]
+        pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+            _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+            args: &'args LabelSetArgs<'args>,
+        ) -> flatbuffers::WIPOffset<LabelSet<'bldr>> {
+            let mut builder = LabelSetBuilder::new(_fbb);
+            if let Some(x) = args.labels {
+                builder.add_labels(x);
+            }
+            if let Some(x) = args.tvar {
+                builder.add_tvar(x);
+            }
+            builder.finish()
+        }
+
+        pub const VT_TVAR: flatbuffers::VOffsetT = 4;
+        pub const VT_LABELS: flatbuffers::VOffsetT = 6;
+
+        #[inline]
+        pub fn tvar(&self) -> Option<Var<'a>> {
+            self._tab
+                .get::<flatbuffers::ForwardsUOffset<Var>>(LabelSet::VT_TVAR, None)
+        }
+        #[inline]
+        pub fn labels(
+            &self,
+        ) -> Option<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<&'a str>>> {
+            self._tab.get::<flatbuffers::ForwardsUOffset<
+                flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<&'a str>>,
+            >>(LabelSet::VT_LABELS, None)
+        }
+    }
+
+    impl flatbuffers::Verifiable for LabelSet<'_> {
+        #[inline]
+        fn run_verifier(
+            v: &mut flatbuffers::Verifier,
+            pos: usize,
+        ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+            use self::flatbuffers::Verifiable;
+            v.visit_table(pos)?
+                .visit_field::<flatbuffers::ForwardsUOffset<Var>>(&"tvar", Self::VT_TVAR, false)?
+                .visit_field::<flatbuffers::ForwardsUOffset<
+                    flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<&'_ str>>,
+                >>(&"labels", Self::VT_LABELS, false)?
+                .finish();
+            Ok(())
+        }
+    }
+    pub struct LabelSetArgs<'a> {
+        pub tvar: Option<flatbuffers::WIPOffset<Var<'a>>>,
+        pub labels: Option<
+            flatbuffers::WIPOffset<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<&'a str>>>,
+        >,
+    }
+    impl<'a> Default for LabelSetArgs<'a> {
+        #[inline]
+        fn default() -> Self {
+            LabelSetArgs {
+                tvar: None,
+                labels: None,
+            }
+        }
+    }
+    pub struct LabelSetBuilder<'a: 'b, 'b> {
+        fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+        start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+    }
+    impl<'a: 'b, 'b> LabelSetBuilder<'a, 'b> {
+        #[inline]
+        pub fn add_tvar(&mut self, tvar: flatbuffers::WIPOffset<Var<'b>>) {
+            self.fbb_
+                .push_slot_always::<flatbuffers::WIPOffset<Var>>(LabelSet::VT_TVAR, tvar);
+        }
+        #[inline]
+        pub fn add_labels(
+            &mut self,
+            labels: flatbuffers::WIPOffset<
+                flatbuffers::Vector<'b, flatbuffers::ForwardsUOffset<&'b str>>,
+            >,
+        ) {
+            self.fbb_
+                .push_slot_always::<flatbuffers::WIPOffset<_>>(LabelSet::VT_LABELS, labels);
+        }
+        #[inline]
+        pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> LabelSetBuilder<'a, 'b> {
+            let start = _fbb.start_table();
+            LabelSetBuilder {
+                fbb_: _fbb,
+                start_: start,
+            }
+        }
+        #[inline]
+        pub fn finish(self) -> flatbuffers::WIPOffset<LabelSet<'a>> {
+            let o = self.fbb_.end_table(self.start_);
+            flatbuffers::WIPOffset::new(o.value())
+        }
+    }
+
+    impl std::fmt::Debug for LabelSet<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let mut ds = f.debug_struct("LabelSet");
+            ds.field("tvar", &self.tvar());
+            ds.field("labels", &self.labels());
             ds.finish()
         }
     }
-    pub enum ConstraintOffset {}
+    pub enum TvarNameOffset {}
     #[derive(Copy, Clone, PartialEq)]
 
-    pub struct Constraint<'a> {
+    pub struct TvarName<'a> {
         pub _tab: flatbuffers::Table<'a>,
     }
 
-    impl<'a> flatbuffers::Follow<'a> for Constraint<'a> {
-        type Inner = Constraint<'a>;
+    impl<'a> flatbuffers::Follow<'a> for TvarName<'a> {
+        type Inner = TvarName<'a>;
         #[inline]
         fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
             Self {
@@ -5084,41 +5797,42 @@ pub mod fbsemantic {
         }
     }
 
-    impl<'a> Constraint<'a> {
+    impl<'a> TvarName<'a> {
         #[inline]
         pub fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
-            Constraint { _tab: table }
+            TvarName { _tab: table }
         }
         #[allow(unused_mut)]
         pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
             _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
-            args: &'args ConstraintArgs<'args>,
-        ) -> flatbuffers::WIPOffset<Constraint<'bldr>> {
-            let mut builder = ConstraintBuilder::new(_fbb);
+            args: &'args TvarNameArgs<'args>,
+        ) -> flatbuffers::WIPOffset<TvarName<'bldr>> {
+            let mut builder = TvarNameBuilder::new(_fbb);
+            if let Some(x) = args.name {
+                builder.add_name(x);
+            }
             if let Some(x) = args.tvar {
                 builder.add_tvar(x);
             }
-            builder.add_kind(args.kind);
             builder.finish()
         }
 
         pub const VT_TVAR: flatbuffers::VOffsetT = 4;
-        pub const VT_KIND: flatbuffers::VOffsetT = 6;
+        pub const VT_NAME: flatbuffers::VOffsetT = 6;
 
         #[inline]
         pub fn tvar(&self) -> Option<Var<'a>> {
             self._tab
-                .get::<flatbuffers::ForwardsUOffset<Var>>(Constraint::VT_TVAR, None)
+                .get::<flatbuffers::ForwardsUOffset<Var>>(TvarName::VT_TVAR, None)
         }
         #[inline]
-        pub fn kind(&self) -> Kind {
+        pub fn name(&self) -> Option<&'a str> {
             self._tab
-                .get::<Kind>(Constraint::VT_KIND, Some(Kind::Addable))
-                .unwrap()
+                .get::<flatbuffers::ForwardsUOffset<&str>>(TvarName::VT_NAME, None)
         }
     }
 
-    impl flatbuffers::Verifiable for Constraint<'_> {
+    impl flatbuffers::Verifiable for TvarName<'_> {
         #[inline]
         fn run_verifier(
             v: &mut flatbuffers::Verifier,
@@ -5127,59 +5841,59 @@ pub mod fbsemantic {
             use self::flatbuffers::Verifiable;
             v.visit_table(pos)?
                 .visit_field::<flatbuffers::ForwardsUOffset<Var>>(&"tvar", Self::VT_TVAR, false)?
-                .visit_field::<Kind>(&"kind", Self::VT_KIND, false)?
+                .visit_field::<flatbuffers::ForwardsUOffset<&str>>(&"name", Self::VT_NAME, false)?
                 .finish();
             Ok(())
         }
     }
-    pub struct ConstraintArgs<'a> {
+    pub struct TvarNameArgs<'a> {
         pub tvar: Option<flatbuffers::WIPOffset<Var<'a>>>,
-        pub kind: Kind,
+        pub name: Option<flatbuffers::WIPOffset<&'a str>>,
     }
-    impl<'a> Default for ConstraintArgs<'a> {
+    impl<'a> Default for TvarNameArgs<'a> {
         #[inline]
         fn default() -> Self {
-            ConstraintArgs {
+            TvarNameArgs {
                 tvar: None,
-                kind: Kind::Addable,
+                name: None,
             }
         }
     }
-    pub struct ConstraintBuilder<'a: 'b, 'b> {
+    pub struct TvarNameBuilder<'a: 'b, 'b> {
         fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
         start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
     }
-    impl<'a: 'b, 'b> ConstraintBuilder<'a, 'b> {
+    impl<'a: 'b, 'b> TvarNameBuilder<'a, 'b> {
         #[inline]
         pub fn add_tvar(&mut self, tvar: flatbuffers::WIPOffset<Var<'b>>) {
             self.fbb_
-                .push_slot_always::<flatbuffers::WIPOffset<Var>>(Constraint::VT_TVAR, tvar);
+                .push_slot_always::<flatbuffers::WIPOffset<Var>>(TvarName::VT_TVAR, tvar);
         }
         #[inline]
-        pub fn add_kind(&mut self, kind: Kind) {
+        pub fn add_name(&mut self, name: flatbuffers::WIPOffset<&'b str>) {
             self.fbb_
-                .push_slot::<Kind>(Constraint::VT_KIND, kind, Kind::Addable);
+                .push_slot_always::<flatbuffers::WIPOffset<_>>(TvarName::VT_NAME, name);
         }
         #[inline]
-        pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> ConstraintBuilder<'a, 'b> {
+        pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> TvarNameBuilder<'a, 'b> {
             let start = _fbb.start_table();
-            ConstraintBuilder {
+            TvarNameBuilder {
                 fbb_: _fbb,
                 start_: start,
             }
         }
         #[inline]
-        pub fn finish(self) -> flatbuffers::WIPOffset<Constraint<'a>> {
+        pub fn finish(self) -> flatbuffers::WIPOffset<TvarName<'a>> {
             let o = self.fbb_.end_table(self.start_);
             flatbuffers::WIPOffset::new(o.value())
         }
     }
 
-    impl std::fmt::Debug for Constraint<'_> {
+    impl std::fmt::Debug for TvarName<'_> {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let mut ds = f.debug_struct("Constraint");
+            let mut ds = f.debug_struct("TvarName");
             ds.field("tvar", &self.tvar());
-            ds.field("kind", &self.kind());
+            ds.field("name", &self.name());
             ds.finish()
         }
     }
@@ -10729,6 +11443,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for ArrayExpression<'_> {
@@ -10784,6 +11508,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -10931,6 +11660,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -11071,6 +11810,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for DictExpression<'_> {
@@ -11126,6 +11875,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -11273,6 +12027,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -12486,6 +13250,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for FunctionExpression<'_> {
@@ -12542,6 +13316,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -12720,6 +13499,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -14095,6 +14884,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for BinaryExpression<'_> {
@@ -14167,6 +14966,7 @@ pub mod fbsemantic {
           MonoType::Record => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Record>>("MonoType::Record", pos),
           MonoType::Fun => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Fun>>("MonoType::Fun", pos),
           MonoType::Dict => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Dict>>("MonoType::Dict", pos),
+          MonoType::Tuple => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>("MonoType::Tuple", pos),
           _ => Ok(()),
         }
      })?
@@ -14777,6 +15577,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -15377,6 +16187,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for CallExpression<'_> {
@@ -15449,6 +16269,7 @@ pub mod fbsemantic {
           MonoType::Record => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Record>>("MonoType::Record", pos),
           MonoType::Fun => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Fun>>("MonoType::Fun", pos),
           MonoType::Dict => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Dict>>("MonoType::Dict", pos),
+          MonoType::Tuple => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>("MonoType::Tuple", pos),
           _ => Ok(()),
         }
      })?
@@ -16064,6 +16885,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -18818,6 +19649,7 @@ pub mod fbsemantic {
             if let Some(x) = args.loc {
                 builder.add_loc(x);
             }
+            builder.add_optional(args.optional);
             builder.add_typ_type(args.typ_type);
             builder.add_object_type(args.object_type);
             builder.finish()
@@ -18829,6 +19661,7 @@ pub mod fbsemantic {
         pub const VT_PROPERTY: flatbuffers::VOffsetT = 10;
         pub const VT_TYP_TYPE: flatbuffers::VOffsetT = 12;
         pub const VT_TYP: flatbuffers::VOffsetT = 14;
+        pub const VT_OPTIONAL: flatbuffers::VOffsetT = 16;
 
         #[inline]
         pub fn loc(&self) -> Option<SourceLocation<'a>> {
@@ -18869,6 +19702,12 @@ pub mod fbsemantic {
                 )
         }
         #[inline]
+        pub fn optional(&self) -> bool {
+            self._tab
+                .get::<bool>(MemberExpression::VT_OPTIONAL, Some(false))
+                .unwrap()
+        }
+        #[inline]
         #[allow(non_snake_case)]
         pub fn object_as_string_expression(&self) -> Option<StringExpression<'a>> {
             if self.object_type() == Expression::StringExpression {
@@ -19137,6 +19976,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for MemberExpression<'_> {
@@ -19183,9 +20032,11 @@ pub mod fbsemantic {
           MonoType::Record => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Record>>("MonoType::Record", pos),
           MonoType::Fun => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Fun>>("MonoType::Fun", pos),
           MonoType::Dict => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Dict>>("MonoType::Dict", pos),
+          MonoType::Tuple => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>("MonoType::Tuple", pos),
           _ => Ok(()),
         }
      })?
+     .visit_field::<bool>(&"optional", Self::VT_OPTIONAL, false)?
      .finish();
             Ok(())
         }
@@ -19197,6 +20048,7 @@ pub mod fbsemantic {
         pub property: Option<flatbuffers::WIPOffset<&'a str>>,
         pub typ_type: MonoType,
         pub typ: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
+        pub optional: bool,
     }
     impl<'a> Default for MemberExpressionArgs<'a> {
         #[inline]
@@ -19208,6 +20060,7 @@ pub mod fbsemantic {
                 property: None,
                 typ_type: MonoType::NONE,
                 typ: None,
+                optional: false,
             }
         }
     }
@@ -19258,6 +20111,11 @@ pub mod fbsemantic {
                 .push_slot_always::<flatbuffers::WIPOffset<_>>(MemberExpression::VT_TYP, typ);
         }
         #[inline]
+        pub fn add_optional(&mut self, optional: bool) {
+            self.fbb_
+                .push_slot::<bool>(MemberExpression::VT_OPTIONAL, optional, false);
+        }
+        #[inline]
         pub fn new(
             _fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>,
         ) -> MemberExpressionBuilder<'a, 'b> {
@@ -19558,11 +20416,22 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
                 }
             };
+            ds.field("optional", &self.optional());
             ds.finish()
         }
     }
@@ -20146,6 +21015,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for IndexExpression<'_> {
@@ -20217,6 +21096,7 @@ pub mod fbsemantic {
           MonoType::Record => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Record>>("MonoType::Record", pos),
           MonoType::Fun => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Fun>>("MonoType::Fun", pos),
           MonoType::Dict => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Dict>>("MonoType::Dict", pos),
+          MonoType::Tuple => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>("MonoType::Tuple", pos),
           _ => Ok(()),
         }
      })?
@@ -20813,6 +21693,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -20965,6 +21855,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for ObjectExpression<'_> {
@@ -21025,6 +21925,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -21186,6 +22091,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -21555,6 +22470,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for UnaryExpression<'_> {
@@ -21601,6 +22526,7 @@ pub mod fbsemantic {
           MonoType::Record => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Record>>("MonoType::Record", pos),
           MonoType::Fun => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Fun>>("MonoType::Fun", pos),
           MonoType::Dict => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Dict>>("MonoType::Dict", pos),
+          MonoType::Tuple => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>("MonoType::Tuple", pos),
           _ => Ok(()),
         }
      })?
@@ -21979,6 +22905,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -22725,6 +23661,16 @@ pub mod fbsemantic {
                 None
             }
         }
+
+        #[inline]
+        #[allow(non_snake_case)]
+        pub fn typ_as_tuple(&self) -> Option<Tuple<'a>> {
+            if self.typ_type() == MonoType::Tuple {
+                self.typ().map(Tuple::init_from_table)
+            } else {
+                None
+            }
+        }
     }
 
     impl flatbuffers::Verifiable for IdentifierExpression<'_> {
@@ -22778,6 +23724,11 @@ pub mod fbsemantic {
                                 "MonoType::Dict",
                                 pos,
                             ),
+                        MonoType::Tuple => v
+                            .verify_union_variant::<flatbuffers::ForwardsUOffset<Tuple>>(
+                                "MonoType::Tuple",
+                                pos,
+                            ),
                         _ => Ok(()),
                     },
                 )?
@@ -22917,6 +23868,16 @@ pub mod fbsemantic {
                         )
                     }
                 }
+                MonoType::Tuple => {
+                    if let Some(x) = self.typ_as_tuple() {
+                        ds.field("typ", &x)
+                    } else {
+                        ds.field(
+                            "typ",
+                            &"InvalidFlatbuffer: Union discriminant does not match value.",
+                        )
+                    }
+                }
                 _ => {
                     let x: Option<()> = None;
                     ds.field("typ", &x)
@@ -1072,6 +1072,8 @@ fn semantic_operator(fb_op: fbsemantic::Operator) -> ast::Operator {
         fbsemantic::Operator::NotEqualOperator => ast::Operator::NotEqualOperator,
         fbsemantic::Operator::RegexpMatchOperator => ast::Operator::RegexpMatchOperator,
         fbsemantic::Operator::NotRegexpMatchOperator => ast::Operator::NotRegexpMatchOperator,
+        fbsemantic::Operator::NullCoalescingOperator => ast::Operator::NullCoalescingOperator,
+        fbsemantic::Operator::ExponentiationOperator => ast::Operator::ExponentiationOperator,
         fbsemantic::Operator::InvalidOperator => ast::Operator::InvalidOperator,
         _ => unreachable!("Unknown fbsemantic::Operator"),
     }
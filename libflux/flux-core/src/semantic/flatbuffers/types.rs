@@ -17,6 +17,7 @@ use crate::semantic::{
         Dictionary,
         Function,
         Kind,
+        LabelSets,
         MonoType,
         MonoTypeMap,
         PolyType,
@@ -25,6 +26,8 @@ use crate::semantic::{
         Record,
         Tvar,
         TvarKinds,
+        TvarNames,
+        Tuple,
         BuiltinType,
     },
     flatbuffers::serialize_pkg_into,
@@ -59,6 +62,10 @@ impl DeserializeFlatBuffer {
         &mut self,
         env: fb::TypeEnvironment<'a>,
     ) -> Option<PackageExports> {
+        let private = env
+            .private()
+            .map(|private| private.iter().map(String::from).collect())
+            .unwrap_or_default();
         let env = env.assignments()?;
         let mut types = Vec::new();
         for value in env.iter() {
@@ -72,7 +79,8 @@ impl DeserializeFlatBuffer {
                 ty,
             ));
         }
-        PackageExports::try_from(types).ok()
+        let exports = PackageExports::try_from(types).ok()?;
+        Some(PackageExports { private, ..exports })
     }
 }
 
@@ -116,9 +124,26 @@ impl From<fb::PolyType<'_>> for Option<PolyType> {
             let (tv, kind) = constraint?;
             cons.entry(tv).or_insert_with(Vec::new).push(kind);
         }
+        let mut label_sets = LabelSets::new();
+        // Types serialized before label sets were added have none
+        if let Some(l) = t.label_sets() {
+            for value in l.iter() {
+                let labels = value.labels()?.iter().map(String::from).collect();
+                label_sets.insert(value.tvar()?.into(), labels);
+            }
+        }
+        let mut names = TvarNames::new();
+        if let Some(n) = t.names() {
+            for value in n.iter() {
+                names.insert(value.tvar()?.into(), value.name()?.to_owned());
+            }
+        }
         Some(PolyType {
             vars,
             cons,
+            label_sets,
+            aliases: Vec::new(),
+            names,
             expr: from_table(t.expr()?, t.expr_type())?,
         })
     }
@@ -145,6 +170,7 @@ impl From<fb::Kind> for Kind {
             fb::Kind::Negatable => Kind::Negatable,
             fb::Kind::Timeable => Kind::Timeable,
             fb::Kind::Stringable => Kind::Stringable,
+            fb::Kind::Lengthable => Kind::Lengthable,
             fb::Kind::Basic => Kind::Basic,
             _ => unreachable!("Unknown fb::Kind"),
         }
@@ -166,6 +192,7 @@ impl From<Kind> for fb::Kind {
             Kind::Negatable => fb::Kind::Negatable,
             Kind::Timeable => fb::Kind::Timeable,
             Kind::Stringable => fb::Kind::Stringable,
+            Kind::Lengthable => fb::Kind::Lengthable,
             Kind::Basic => fb::Kind::Basic,
         }
     }
@@ -210,6 +237,10 @@ fn from_table(table: flatbuffers::Table, t: fb::MonoType) -> Option<MonoType> {
             let opt: Option<Dictionary> = fb::Dict::init_from_table(table).into();
             Some(MonoType::from(opt?))
         }
+        fb::MonoType::Tuple => {
+            let opt: Option<Tuple> = fb::Tuple::init_from_table(table).into();
+            Some(MonoType::from(opt?))
+        }
         fb::MonoType::NONE => None,
         _ => unreachable!("Unknown type from table"),
     }
@@ -261,6 +292,18 @@ impl From<fb::Dict<'_>> for Option<Dictionary> {
     }
 }
 
+impl From<fb::Tuple<'_>> for Option<Tuple> {
+    fn from(t: fb::Tuple) -> Option<Tuple> {
+        Some(Tuple {
+            elements: t
+                .elements()?
+                .iter()
+                .map(|element| from_table(element.typ()?, element.typ_type()))
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
 impl From<fb::Record<'_>> for Option<MonoType> {
     fn from(t: fb::Record) -> Option<MonoType> {
         let mut r = match t.extends() {
@@ -269,10 +312,12 @@ impl From<fb::Record<'_>> for Option<MonoType> {
         };
         let p = t.props()?;
         for value in p.iter().rev() {
+            let optional = value.optional();
             let prop: Option<Property> = value.into();
             r = MonoType::from(Record::Extension {
                 head: prop?,
                 tail: r,
+                optional,
             });
         }
         Some(r)
@@ -403,6 +448,12 @@ pub fn build_env<'a>(
     builder: &mut flatbuffers::FlatBufferBuilder<'a>,
     env: PackageExports,
 ) -> flatbuffers::WIPOffset<fb::TypeEnvironment<'a>> {
+    let private = build_vec(
+        env.private.iter().cloned().collect(),
+        builder,
+        |builder, name| builder.create_string(&name),
+    );
+    let private = builder.create_vector(private.as_slice());
     let assignments = build_vec(
         env.into_bindings().collect(),
         builder,
@@ -413,6 +464,7 @@ pub fn build_env<'a>(
         builder,
         &fb::TypeEnvironmentArgs {
             assignments: Some(assignments),
+            private: Some(private),
         },
     )
 }
@@ -460,6 +512,12 @@ pub fn build_polytype<'a>(
     let cons = build_vec(cons, builder, build_constraint);
     let cons = builder.create_vector(cons.as_slice());
 
+    let label_sets = build_vec(t.label_sets.into_iter().collect(), builder, build_label_set);
+    let label_sets = builder.create_vector(label_sets.as_slice());
+
+    let names = build_vec(t.names.into_iter().collect(), builder, build_tvar_name);
+    let names = builder.create_vector(names.as_slice());
+
     let (buf_offset, expr) = build_type(builder, &t.expr);
     fb::PolyType::create(
         builder,
@@ -468,6 +526,41 @@ pub fn build_polytype<'a>(
             cons: Some(cons),
             expr_type: expr,
             expr: Some(buf_offset),
+            label_sets: Some(label_sets),
+            names: Some(names),
+        },
+    )
+}
+
+fn build_label_set<'a>(
+    builder: &mut flatbuffers::FlatBufferBuilder<'a>,
+    (tvar, labels): (Tvar, Vec<String>),
+) -> flatbuffers::WIPOffset<fb::LabelSet<'a>> {
+    let tvar = build_var(builder, tvar);
+    let labels = build_vec(labels, builder, |builder, label| {
+        builder.create_string(&label)
+    });
+    let labels = builder.create_vector(labels.as_slice());
+    fb::LabelSet::create(
+        builder,
+        &fb::LabelSetArgs {
+            tvar: Some(tvar),
+            labels: Some(labels),
+        },
+    )
+}
+
+fn build_tvar_name<'a>(
+    builder: &mut flatbuffers::FlatBufferBuilder<'a>,
+    (tvar, name): (Tvar, String),
+) -> flatbuffers::WIPOffset<fb::TvarName<'a>> {
+    let tvar = build_var(builder, tvar);
+    let name = builder.create_string(&name);
+    fb::TvarName::create(
+        builder,
+        &fb::TvarNameArgs {
+            tvar: Some(tvar),
+            name: Some(name),
         },
     )
 }
@@ -513,6 +606,10 @@ pub fn build_type(
             let offset = build_record(builder, record);
             (offset.as_union_value(), fb::MonoType::Record)
         }
+        MonoType::Tuple(tuple) => {
+            let offset = build_tuple(builder, tuple);
+            (offset.as_union_value(), fb::MonoType::Tuple)
+        }
         MonoType::Fun(fun) => {
             let offset = build_fun(builder, fun);
             (offset.as_union_value(), fb::MonoType::Fun)
@@ -588,20 +685,57 @@ fn build_dict<'a>(
     )
 }
 
+fn build_tuple<'a>(
+    builder: &mut flatbuffers::FlatBufferBuilder<'a>,
+    tuple: &Tuple,
+) -> flatbuffers::WIPOffset<fb::Tuple<'a>> {
+    let elements = build_vec(tuple.elements.iter().collect(), builder, build_type_holder);
+    let elements = builder.create_vector(elements.as_slice());
+    fb::Tuple::create(
+        builder,
+        &fb::TupleArgs {
+            elements: Some(elements),
+        },
+    )
+}
+
+fn build_type_holder<'a>(
+    builder: &mut flatbuffers::FlatBufferBuilder<'a>,
+    t: &MonoType,
+) -> flatbuffers::WIPOffset<fb::MonoTypeHolder<'a>> {
+    let (typ, typ_type) = build_type(builder, t);
+    fb::MonoTypeHolder::create(
+        builder,
+        &fb::MonoTypeHolderArgs {
+            typ_type,
+            typ: Some(typ),
+        },
+    )
+}
+
 fn build_record<'a>(
     builder: &mut flatbuffers::FlatBufferBuilder<'a>,
     mut record: &Record,
 ) -> flatbuffers::WIPOffset<fb::Record<'a>> {
     let mut props = Vec::new();
 
-    let mut fields = record.fields();
-    for field in &mut fields {
-        props.push(field);
-    }
-    let extends = fields.tail().and_then(|typ| match typ {
-        MonoType::Var(t) | MonoType::BoundVar(t) => Some(t),
-        _ => None,
-    });
+    let extends = loop {
+        match record {
+            Record::Empty => break None,
+            Record::Extension {
+                head,
+                tail,
+                optional,
+            } => {
+                props.push((head, *optional));
+                match tail {
+                    MonoType::Record(tail) => record = tail,
+                    MonoType::Var(t) | MonoType::BoundVar(t) => break Some(t),
+                    _ => break None,
+                }
+            }
+        }
+    };
 
     let props = build_vec(props, builder, build_prop);
     let props = builder.create_vector(props.as_slice());
@@ -617,7 +751,7 @@ fn build_record<'a>(
 
 fn build_prop<'a>(
     builder: &mut flatbuffers::FlatBufferBuilder<'a>,
-    prop: &Property,
+    (prop, optional): (&Property, bool),
 ) -> flatbuffers::WIPOffset<fb::Prop<'a>> {
     let (off, v_type) = build_type(builder, &prop.v);
     let (k, k_type) = match &prop.k {
@@ -639,6 +773,7 @@ fn build_prop<'a>(
             k: Some(k),
             v_type,
             v: Some(off),
+            optional,
         },
     )
 }
@@ -791,6 +926,20 @@ mod tests {
         assert_eq!(want, got.unwrap());
     }
     #[test]
+    fn serde_type_environment_private_names() {
+        let want = PackageExports {
+            private: vec!["hidden".to_string()].into_iter().collect(),
+            ..PackageExports::new()
+        };
+
+        let mut builder = flatbuffers::FlatBufferBuilder::new();
+        let buf = serialize(&mut builder, want, build_env);
+        let got = deserialize::<fb::TypeEnvironment, Option<PackageExports>>(buf).unwrap();
+
+        assert!(got.is_private("hidden"));
+        assert!(!got.is_private("shown"));
+    }
+    #[test]
     fn serde_basic_types() {
         test_serde("bool");
         test_serde("int");
@@ -811,6 +960,9 @@ mod tests {
         let want = PolyType {
             vars: vec![],
             cons: TvarKinds::new(),
+            label_sets: Default::default(),
+            aliases: Vec::new(),
+            names: Default::default(),
             expr: MonoType::vector(MonoType::INT),
         };
 
@@ -825,10 +977,64 @@ mod tests {
         test_serde("(a: A, b: B) => bool where A: Addable, B: Divisible");
     }
     #[test]
+    fn serde_label_sets() {
+        test_serde(r#"(column: L) => int where L: Label in {"start", "stop"}"#);
+    }
+    #[test]
+    fn serde_tvar_names() {
+        let mut p = parser::Parser::new("(column: L) => L where L: Label");
+        let typ_expr = p.parse_type_expression();
+        let mut want = convert_polytype(&typ_expr, &mut Substitution::default()).unwrap();
+        want.names.insert(want.vars[0], "Col".to_string());
+
+        let mut builder = flatbuffers::FlatBufferBuilder::new();
+        let buf = serialize(&mut builder, want.clone(), build_polytype);
+        let got = deserialize::<fb::PolyType, Option<PolyType>>(buf).unwrap();
+        // Polytypes compare equal whatever their variables are named
+        assert_eq!("(column:Col) => Col where Col: Label", got.to_string());
+        assert_eq!(want.to_string(), got.to_string());
+    }
+    #[test]
     fn serde_record_types() {
         test_serde("{A with a: int , b: float , c: {d: string , d: string , d: time , d: {}}}");
     }
     #[test]
+    fn serde_optional_record_fields() {
+        let expr = "{A with a: int, ?b: string}";
+        let mut p = parser::Parser::new(expr);
+        let typ_expr = p.parse_type_expression();
+        let want = convert_polytype(&typ_expr, &mut Substitution::default()).unwrap();
+
+        let mut builder = flatbuffers::FlatBufferBuilder::new();
+        let buf = serialize(&mut builder, want.clone(), build_polytype);
+        let got = deserialize::<fb::PolyType, Option<PolyType>>(buf).unwrap();
+        // Records compare equal whether or not their fields are optional
+        assert_eq!(want.to_string(), got.to_string());
+    }
+    #[test]
+    fn serde_tuple_types() {
+        let want = PolyType {
+            vars: vec![],
+            cons: TvarKinds::new(),
+            label_sets: Default::default(),
+            aliases: Vec::new(),
+            names: Default::default(),
+            expr: MonoType::tuple(Tuple {
+                elements: vec![
+                    MonoType::INT,
+                    MonoType::arr(MonoType::tuple(Tuple {
+                        elements: vec![MonoType::BOOL, MonoType::STRING],
+                    })),
+                ],
+            }),
+        };
+
+        let mut builder = flatbuffers::FlatBufferBuilder::new();
+        let buf = serialize(&mut builder, want.clone(), build_polytype);
+        let got = deserialize::<fb::PolyType, Option<PolyType>>(buf);
+        assert_eq!(want, got.unwrap())
+    }
+    #[test]
     fn test_flatbuffers_semantic() {
         let mut builder = flatbuffers::FlatBufferBuilder::with_capacity(256);
 
@@ -0,0 +1,242 @@
+//! Constant folding of the semantic graph.
+
+use std::cmp::Ordering;
+
+use crate::{
+    ast::{LogicalOperator, Operator},
+    errors::{located, Errors},
+    semantic::{
+        env::Environment,
+        nodes::{
+            BinaryExpr, Error, ErrorKind, Expression, FloatLit, IntegerLit, Package, StringLit,
+            UintLit,
+        },
+        walk::{self, walk_mut, Node, NodeMut, VisitorMut},
+    },
+};
+
+/// Replaces every arithmetic expression on number literals and every concatenation of string
+/// literals in `pkg` with the literal it evaluates to, e.g. `1 + 2` with `3`.
+///
+/// The folded literal keeps the source location of the expression it replaces. Expressions which
+/// would fail at runtime, such as a division by zero or an integer overflow, are left as they are.
+pub fn fold_constants(pkg: &mut Package) {
+    walk_mut(&mut ConstantFolder, NodeMut::Package(pkg));
+}
+
+/// Returns a warning for every conditional expression in `pkg` whose condition folds to `true`
+/// or `false`, such as `if 1 < 2 then a else b`, located at the branch which is never evaluated.
+/// Conditions which depend on any value that is not a literal are never reported, `true` and
+/// `false` are only constant when they are the bindings of `env`, the prelude, and not a
+/// variable shadowing them.
+pub(crate) fn check_unreachable_branches(pkg: &Package, env: &Environment) -> Errors<Error> {
+    let mut warnings = Errors::new();
+    walk::walk(
+        &mut |node: Node| {
+            if let Node::ConditionalExpr(expr) = node {
+                let mut test = expr.test.clone();
+                walk_mut(&mut ConstantFolder, NodeMut::from_expr(&mut test));
+                fold(&mut test);
+                if let Some(value) = constant_condition(&test, env) {
+                    let unreachable = if value {
+                        &expr.alternate
+                    } else {
+                        &expr.consequent
+                    };
+                    warnings.push(located(
+                        unreachable.loc().clone(),
+                        ErrorKind::UnreachableBranch(value),
+                    ));
+                }
+            }
+        },
+        Node::Package(pkg),
+    );
+    warnings
+}
+
+struct ConstantFolder;
+
+impl VisitorMut for ConstantFolder {
+    fn visit(&mut self, _node: &mut NodeMut) -> bool {
+        true
+    }
+
+    // Folds the expressions directly contained in `node`. Nodes are done after their children so
+    // the operands of an expression have already been folded when the expression itself is.
+    fn done(&mut self, node: &mut NodeMut) {
+        match node {
+            NodeMut::ArrayExpr(n) => n.elements.iter_mut().for_each(fold),
+            NodeMut::DictExpr(n) => {
+                for (key, val) in &mut n.elements {
+                    fold(key);
+                    fold(val);
+                }
+            }
+            NodeMut::FunctionParameter(n) => n.default.iter_mut().for_each(fold),
+            NodeMut::LogicalExpr(n) => {
+                fold(&mut n.left);
+                fold(&mut n.right);
+            }
+            NodeMut::MemberExpr(n) => fold(&mut n.object),
+            NodeMut::IndexExpr(n) => {
+                fold(&mut n.array);
+                fold(&mut n.index);
+            }
+            NodeMut::TupleExpr(n) => n.elements.iter_mut().for_each(fold),
+            NodeMut::TupleIndexExpr(n) => fold(&mut n.tuple),
+            NodeMut::MatchExpr(n) => {
+                fold(&mut n.scrutinee);
+                for arm in &mut n.arms {
+                    fold(&mut arm.body);
+                }
+            }
+            NodeMut::BinaryExpr(n) => {
+                fold(&mut n.left);
+                fold(&mut n.right);
+            }
+            NodeMut::UnaryExpr(n) => fold(&mut n.argument),
+            NodeMut::CallExpr(n) => {
+                fold(&mut n.callee);
+                n.pipe.iter_mut().for_each(fold);
+            }
+            NodeMut::ConditionalExpr(n) => {
+                fold(&mut n.test);
+                fold(&mut n.consequent);
+                fold(&mut n.alternate);
+            }
+            NodeMut::ExprStmt(n) => fold(&mut n.expression),
+            NodeMut::ReturnStmt(n) => fold(&mut n.argument),
+            NodeMut::Property(n) => fold(&mut n.value),
+            NodeMut::InterpolatedPart(n) => fold(&mut n.expression),
+            NodeMut::VariableAssgn(n) => fold(&mut n.init),
+            NodeMut::MemberAssgn(n) => fold(&mut n.init),
+            _ => (),
+        }
+    }
+}
+
+fn fold(expr: &mut Expression) {
+    if let Expression::Binary(binary) = expr {
+        if let Some(folded) = fold_binary(binary) {
+            *expr = folded;
+        }
+    }
+}
+
+// Returns the literal `expr` evaluates to if both of its operands are literals.
+fn fold_binary(expr: &BinaryExpr) -> Option<Expression> {
+    use Operator::*;
+
+    let loc = expr.loc.clone();
+    let folded = match (&expr.left, &expr.right) {
+        (Expression::Integer(l), Expression::Integer(r)) => {
+            let (l, r) = (l.value, r.value);
+            let value = match expr.operator {
+                AdditionOperator => l.checked_add(r),
+                SubtractionOperator => l.checked_sub(r),
+                MultiplicationOperator => l.checked_mul(r),
+                DivisionOperator => l.checked_div(r),
+                ModuloOperator => l.checked_rem(r),
+                _ => None,
+            }?;
+            Expression::Integer(IntegerLit { loc, value })
+        }
+        (Expression::Uint(l), Expression::Uint(r)) => {
+            let (l, r) = (l.value, r.value);
+            let value = match expr.operator {
+                AdditionOperator => l.checked_add(r),
+                SubtractionOperator => l.checked_sub(r),
+                MultiplicationOperator => l.checked_mul(r),
+                DivisionOperator => l.checked_div(r),
+                ModuloOperator => l.checked_rem(r),
+                _ => None,
+            }?;
+            Expression::Uint(UintLit { loc, value })
+        }
+        (Expression::Float(l), Expression::Float(r)) => {
+            let (l, r) = (l.value, r.value);
+            let value = match expr.operator {
+                AdditionOperator => l + r,
+                SubtractionOperator => l - r,
+                MultiplicationOperator => l * r,
+                DivisionOperator if r != 0.0 => l / r,
+                ModuloOperator if r != 0.0 => l % r,
+                _ => return None,
+            };
+            if !value.is_finite() {
+                return None;
+            }
+            Expression::Float(FloatLit { loc, value })
+        }
+        (Expression::StringLit(l), Expression::StringLit(r))
+            if expr.operator == AdditionOperator =>
+        {
+            Expression::StringLit(StringLit {
+                loc,
+                value: format!("{}{}", l.value, r.value),
+                typ: None,
+            })
+        }
+        _ => return None,
+    };
+
+    // Operands which were given a different type than the literal, such as labels, stay as is
+    if folded.type_of() == expr.typ {
+        Some(folded)
+    } else {
+        None
+    }
+}
+
+// Returns the value of a condition whose operands have been folded, if it is constant.
+fn constant_condition(expr: &Expression, env: &Environment) -> Option<bool> {
+    match expr {
+        Expression::Boolean(lit) => Some(lit.value),
+        // Symbols are compared by identity, a variable named `true` is a different symbol
+        Expression::Identifier(id)
+            if id.name.name() == "true" && env.lookup_symbol("true") == Some(&id.name) =>
+        {
+            Some(true)
+        }
+        Expression::Identifier(id)
+            if id.name.name() == "false" && env.lookup_symbol("false") == Some(&id.name) =>
+        {
+            Some(false)
+        }
+        Expression::Unary(expr) if expr.operator == Operator::NotOperator => {
+            constant_condition(&expr.argument, env).map(|value| !value)
+        }
+        Expression::Logical(expr) => {
+            let left = constant_condition(&expr.left, env)?;
+            let right = constant_condition(&expr.right, env)?;
+            Some(match expr.operator {
+                LogicalOperator::AndOperator => left && right,
+                LogicalOperator::OrOperator => left || right,
+            })
+        }
+        Expression::Binary(expr) => compare(expr),
+        _ => None,
+    }
+}
+
+fn compare(expr: &BinaryExpr) -> Option<bool> {
+    use Operator::*;
+
+    let ordering = match (&expr.left, &expr.right) {
+        (Expression::Integer(l), Expression::Integer(r)) => l.value.cmp(&r.value),
+        (Expression::Uint(l), Expression::Uint(r)) => l.value.cmp(&r.value),
+        (Expression::Float(l), Expression::Float(r)) => l.value.partial_cmp(&r.value)?,
+        (Expression::StringLit(l), Expression::StringLit(r)) => l.value.cmp(&r.value),
+        _ => return None,
+    };
+    Some(match expr.operator {
+        EqualOperator => ordering == Ordering::Equal,
+        NotEqualOperator => ordering != Ordering::Equal,
+        LessThanOperator => ordering == Ordering::Less,
+        LessThanEqualOperator => ordering != Ordering::Greater,
+        GreaterThanOperator => ordering == Ordering::Greater,
+        GreaterThanEqualOperator => ordering != Ordering::Less,
+        _ => return None,
+    })
+}
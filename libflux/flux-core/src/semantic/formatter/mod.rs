@@ -5,7 +5,10 @@ use anyhow::{anyhow, Error, Result};
 use chrono::SecondsFormat;
 
 use crate::{
-    ast, semantic,
+    ast,
+    formatter::FormatterConfig,
+    parser::strconv,
+    semantic,
     semantic::{
         types::{CollectionType, MonoType, PolyType, Tvar, TvarKinds},
         walk,
@@ -27,6 +30,16 @@ pub fn format(pkg: &semantic::nodes::Package) -> Result<String, Error> {
     convert_to_string(pkg)
 }
 
+/// Format a semantic graph using the given configuration.
+pub fn format_with_config(
+    pkg: &semantic::nodes::Package,
+    config: &FormatterConfig,
+) -> Result<String, Error> {
+    let mut formatter = Formatter::with_config(config);
+    formatter.format_package(pkg);
+    formatter.output()
+}
+
 /// Format a `Node`
 pub fn format_node(node: walk::Node) -> Result<String, Error> {
     let mut formatter = Formatter::default();
@@ -37,17 +50,34 @@ pub fn format_node(node: walk::Node) -> Result<String, Error> {
 /// Struct to hold data related to formatting such as formatted code,
 /// options, and errors.
 /// Provides methods for formatting files and strings of source code.
-#[derive(Default)]
 pub struct Formatter {
     builder: String,
     indentation: u32,
+    indent_bytes: String,
     err: Option<Error>,
 }
 
-// INDENT_BYTES is 4 spaces as a constant byte slice
-const INDENT_BYTES: &str = "    ";
+impl Default for Formatter {
+    fn default() -> Self {
+        Formatter::with_config(&FormatterConfig::default())
+    }
+}
 
 impl Formatter {
+    /// Creates a formatter which indents according to `config`.
+    pub fn with_config(config: &FormatterConfig) -> Self {
+        Formatter {
+            builder: String::new(),
+            indentation: 0,
+            indent_bytes: if config.use_tabs {
+                "\t".to_string()
+            } else {
+                " ".repeat(config.indent)
+            },
+            err: None,
+        }
+    }
+
     /// Returns the final formatted string and error message.
     pub fn output(self) -> Result<String, Error> {
         if let Some(err) = self.err {
@@ -67,7 +97,7 @@ impl Formatter {
 
     fn write_indent(&mut self) {
         for _ in 0..self.indentation {
-            (&mut self.builder).push_str(INDENT_BYTES);
+            (&mut self.builder).push_str(&self.indent_bytes);
         }
     }
     fn indent(&mut self) {
@@ -125,6 +155,10 @@ impl Formatter {
             walk::Node::TestCaseStmt(m) => self.format_testcase_statement(m),
             walk::Node::VariableAssgn(m) => self.format_variable_assignment(m),
             walk::Node::IndexExpr(m) => self.format_index_expression(m),
+            walk::Node::TupleExpr(m) => self.format_tuple_expression(m),
+            walk::Node::TupleIndexExpr(m) => self.format_tuple_index_expression(m),
+            walk::Node::BlockExpr(m) => self.format_block_expression(m),
+            walk::Node::MatchExpr(m) => self.format_match_expression(m),
             walk::Node::MemberAssgn(m) => self.format_member_assignment(m),
             walk::Node::CallExpr(m) => self.format_call_expression(m),
             walk::Node::ConditionalExpr(m) => self.format_conditional_expression(m),
@@ -152,6 +186,7 @@ impl Formatter {
             walk::Node::ObjectExpr(m) => self.format_record_expression_braces(m, true),
             walk::Node::Package(m) => self.format_package(m),
             walk::Node::BuiltinStmt(m) => self.format_builtin(m),
+            walk::Node::TypeAliasStmt(m) => self.format_type_alias(m),
             walk::Node::Expr(m) => self.format_node(&walk::Node::reduce_expr(m)),
             _ => self.err = Some(anyhow!(format!("bad expression: {:?}", n))),
         }
@@ -187,6 +222,7 @@ impl Formatter {
             },
             MonoType::Dict(dict) => self.format_dict_type(dict),
             MonoType::Record(rec) => self.format_record_type(rec),
+            MonoType::Tuple(tuple) => self.format_tuple_type(tuple),
             MonoType::Fun(fun) => self.format_function_type(fun),
             _ => self.err = Some(anyhow!("bad expression")),
         }
@@ -199,6 +235,23 @@ impl Formatter {
         self.format_type_expression(&n.typ_expr);
     }
 
+    fn format_type_alias(&mut self, n: &semantic::nodes::TypeAliasStmt) {
+        self.write_string("type ");
+        self.format_identifier(&n.id);
+        self.write_string(" = ");
+        if !n.typ.vars.is_empty() {
+            self.write_rune('(');
+            for (i, var) in n.typ.vars.iter().enumerate() {
+                if i != 0 {
+                    self.write_string(", ");
+                }
+                self.format_tvar(var);
+            }
+            self.write_string(") => ");
+        }
+        self.format_monotype(&n.typ.expr);
+    }
+
     fn format_type_expression(&mut self, n: &PolyType) {
         self.format_monotype(&n.expr);
         if !n.vars.is_empty() {
@@ -248,6 +301,20 @@ impl Formatter {
         self.write_rune(']');
     }
 
+    fn format_tuple_type(&mut self, n: &semantic::types::Tuple) {
+        self.write_rune('(');
+        for (i, element) in n.elements.iter().enumerate() {
+            if i != 0 {
+                self.write_string(", ");
+            }
+            self.format_monotype(element);
+        }
+        if n.elements.len() == 1 {
+            self.write_rune(',');
+        }
+        self.write_rune(')');
+    }
+
     fn format_array_type(&mut self, n: &MonoType) {
         self.write_rune('[');
         self.format_monotype(n);
@@ -262,6 +329,8 @@ impl Formatter {
         }
     }
 
+    // The labels are sorted by the `Display` of the record, so a record type is printed the same
+    // way wherever it is displayed.
     fn format_record_type(&mut self, n: &semantic::types::Record) {
         self.write_string((format!("{}", n)).as_str());
     }
@@ -377,6 +446,30 @@ impl Formatter {
         self.write_string(&format!(":{}", &n.typ));
     }
 
+    fn format_tuple_expression(&mut self, n: &semantic::nodes::TupleExpr) {
+        self.write_rune('(');
+        for (i, item) in n.elements.iter().enumerate() {
+            if i != 0 {
+                self.write_string(", ");
+            }
+            self.format_node(&walk::Node::from_expr(item));
+        }
+        if n.elements.len() == 1 {
+            self.write_rune(',');
+        }
+        self.write_rune(')');
+        self.write_string(&format!(":{}", &n.typ));
+    }
+
+    fn format_tuple_index_expression(&mut self, n: &semantic::nodes::TupleIndexExpr) {
+        self.format_child_with_parens(
+            walk::Node::TupleIndexExpr(n),
+            walk::Node::from_expr(&n.tuple),
+        );
+        self.write_string(&format!(".{}", n.index));
+        self.write_string(&format!(":{}", &n.typ));
+    }
+
     fn format_identifier_expression(&mut self, n: &semantic::nodes::IdentifierExpr) {
         self.write_string(&n.name);
         self.write_string(&format!(":{}", &n.typ));
@@ -436,7 +529,7 @@ impl Formatter {
     fn format_right_child_with_parens(&mut self, parent: &walk::Node, child: &walk::Node) {
         let pvp = get_precedence(parent);
         let pvc = get_precedence(child);
-        if needs_parenthesis(pvp, pvc, true) {
+        if needs_parenthesis(pvp, pvc, true) && !(pvp == pvc && is_right_associative(parent)) {
             self.format_node_with_parens(child);
         } else {
             self.format_node(child);
@@ -447,7 +540,9 @@ impl Formatter {
     fn format_left_child_with_parens(&mut self, parent: &walk::Node, child: &walk::Node) {
         let pvp = get_precedence(parent);
         let pvc = get_precedence(child);
-        if needs_parenthesis(pvp, pvc, false) {
+        if needs_parenthesis(pvp, pvc, false)
+            || (pvp != 0 && pvp == pvc && is_right_associative(child))
+        {
             self.format_node_with_parens(child);
         } else {
             self.format_node(child);
@@ -463,6 +558,9 @@ impl Formatter {
 
     fn format_member_expression(&mut self, n: &semantic::nodes::MemberExpr) {
         self.format_child_with_parens(walk::Node::MemberExpr(n), walk::Node::from_expr(&n.object));
+        if n.optional {
+            self.write_rune('?');
+        }
         self.write_rune('.');
         self.write_string(&n.property);
         self.write_string(&format!(":{}", &n.typ));
@@ -559,6 +657,45 @@ impl Formatter {
     }
 
     fn format_block(&mut self, n: &semantic::nodes::Block) {
+        self.format_block_with(n, |f, ret| f.format_return_statement(ret))
+    }
+
+    // The trailing expression of a block expression is written without `return`
+    fn format_block_expression(&mut self, n: &semantic::nodes::BlockExpr) {
+        self.format_block_with(&n.body, |f, ret| {
+            f.format_node(&walk::Node::from_expr(&ret.argument))
+        });
+        self.write_string(&format!(":{}", &n.typ));
+    }
+
+    fn format_match_expression(&mut self, n: &semantic::nodes::MatchExpr) {
+        self.write_string("match ");
+        self.format_node(&walk::Node::from_expr(&n.scrutinee));
+        self.write_string(" {");
+        self.indent();
+        for arm in &n.arms {
+            self.write_rune('\n');
+            self.write_indent();
+            match &arm.pattern {
+                Some(pattern) => self.format_node(&walk::Node::from_expr(pattern)),
+                None => self.write_rune('_'),
+            }
+            self.write_string(" => ");
+            self.format_node(&walk::Node::from_expr(&arm.body));
+            self.write_rune(',');
+        }
+        self.write_rune('\n');
+        self.unindent();
+        self.write_indent();
+        self.write_rune('}');
+        self.write_string(&format!(":{}", &n.typ));
+    }
+
+    fn format_block_with(
+        &mut self,
+        n: &semantic::nodes::Block,
+        format_return: impl FnOnce(&mut Self, &semantic::nodes::ReturnStmt),
+    ) {
         self.write_rune('{');
         let sep = '\n';
         self.indent();
@@ -585,7 +722,7 @@ impl Formatter {
                         self.write_rune(sep);
                     }
                     self.write_indent();
-                    self.format_return_statement(ret);
+                    format_return(self, ret);
                     break;
                 }
             }
@@ -602,6 +739,11 @@ impl Formatter {
 
     fn format_variable_assignment(&mut self, n: &semantic::nodes::VariableAssgn) {
         self.format_node(&walk::Node::Identifier(&n.id));
+        if let Some(annotation) = &n.annotation {
+            // The same spacing as the annotations of the AST formatter
+            self.write_string(" : ");
+            self.format_type_expression(annotation);
+        }
         self.write_string(" = ");
         self.format_node(&walk::Node::from_expr(&n.init));
     }
@@ -864,10 +1006,23 @@ impl Formatter {
     }
 
     fn format_float_literal(&mut self, n: &semantic::nodes::FloatLit) {
-        let mut s = format!("{}", n.value);
-        if !s.contains('.') {
-            s.push_str(".0");
-        }
+        // Keep the literal as it was written (`100.`, `0.50`, `1_000.0`) as long as it is a float
+        // literal which still spells the value, otherwise fall back to the shortest form which
+        // reads back the same.
+        let src = n.loc.source.as_deref().filter(|src| {
+            let src = strconv::strip_digit_separators(src);
+            is_float_literal(&src) && src.parse::<f64>() == Ok(n.value)
+        });
+        let s = match src {
+            Some(src) => src.to_string(),
+            None => {
+                let mut s = format!("{}", n.value);
+                if !s.contains('.') {
+                    s.push_str(".0");
+                }
+                s
+            }
+        };
         // s.push_str(&format!(":{}", MonoType::Float.to_string()));
         self.write_string(&s)
     }
@@ -899,7 +1054,8 @@ fn get_precedence(node: &walk::Node) -> u32 {
         walk::Node::CallExpr(_) => 1,
         walk::Node::MemberExpr(_) => 1,
         walk::Node::IndexExpr(_) => 1,
-        walk::Node::ConditionalExpr(_) => 11,
+        walk::Node::TupleIndexExpr(_) => 1,
+        walk::Node::ConditionalExpr(_) => 12,
         walk::Node::Expr(e) => get_precedence(&walk::Node::reduce_expr(e)),
         _ => 0,
     }
@@ -932,6 +1088,7 @@ impl<'a> Operator<'a> {
         if !self.is_logical {
             return match self.op.unwrap() {
                 ast::Operator::PowerOperator => 4,
+                ast::Operator::ExponentiationOperator => 4,
                 ast::Operator::MultiplicationOperator => 5,
                 ast::Operator::DivisionOperator => 5,
                 ast::Operator::ModuloOperator => 5,
@@ -951,6 +1108,7 @@ impl<'a> Operator<'a> {
                 ast::Operator::NotRegexpMatchOperator => 7,
                 ast::Operator::NotOperator => 8,
                 ast::Operator::ExistsOperator => 8,
+                ast::Operator::NullCoalescingOperator => 11,
                 ast::Operator::InvalidOperator => 0,
             };
         }
@@ -966,3 +1124,26 @@ fn needs_parenthesis(pvp: u32, pvc: u32, is_right: bool) -> bool {
     let par = pvc != 0 && pvp != 0;
     par && ((!is_right && pvc > pvp) || (is_right && pvc >= pvp))
 }
+
+// is_float_literal reports whether `s` has the syntax of a float literal, `100.`, `0.5` or `.5`,
+// without digit separators.
+fn is_float_literal(s: &str) -> bool {
+    match s.split_once('.') {
+        Some((int, frac)) => {
+            !(int.is_empty() && frac.is_empty())
+                && int.chars().all(|c| c.is_ascii_digit())
+                && frac.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+// is_right_associative reports whether the node is a binary expression whose operator groups to
+// the right, in which case the rules for children of the same precedence are mirrored.
+fn is_right_associative(node: &walk::Node) -> bool {
+    match node {
+        walk::Node::BinaryExpr(n) => n.operator == ast::Operator::ExponentiationOperator,
+        walk::Node::Expr(e) => is_right_associative(&walk::Node::reduce_expr(e)),
+        _ => false,
+    }
+}
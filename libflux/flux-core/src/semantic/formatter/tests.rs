@@ -1,20 +1,26 @@
 use expect_test::{expect, Expect};
 
-use crate::semantic::{
-    formatter::format,
-    import::Packages,
-    types::{Function, MonoType, PolyTypeMap, SemanticMap, Tvar},
-    Analyzer,
+use crate::{
+    ast::SourceLocation,
+    semantic::{
+        formatter::{format, format_node},
+        import::Packages,
+        nodes::FloatLit,
+        types::{Function, MonoType, PolyTypeMap, SemanticMap, Tvar},
+        walk, Analyzer,
+    },
 };
 
-fn check(actual: &str, expect: Expect) {
+fn format_source(src: &str) -> String {
     let mut analyzer = Analyzer::new_with_defaults(Default::default(), Packages::new());
     let (_, mut sem_pkg) = analyzer
-        .analyze_source("main".to_string(), "main.flux".to_string(), actual)
+        .analyze_source("main".to_string(), "main.flux".to_string(), src)
         .unwrap_or_else(|err| panic!("{}", err));
-    let actual = format(&sem_pkg).unwrap_or_else(|err| panic!("{}", err));
+    format(&sem_pkg).unwrap_or_else(|err| panic!("{}", err))
+}
 
-    expect.assert_eq(&actual);
+fn check(actual: &str, expect: Expect) {
+    expect.assert_eq(&format_source(actual));
 }
 
 #[test]
@@ -41,6 +47,51 @@ fn literals() {
     )
 }
 
+#[test]
+fn float_literals() {
+    let script = r#"
+            a = 0.5
+            b = 1.0
+            c = 100.
+            d = 007.50
+            e = 1_000.0
+            "#;
+
+    check(
+        script,
+        expect![[r#"
+            package main
+            a = 0.5
+            b = 1.0
+            c = 100.
+            d = 007.50
+            e = 1_000.0"#]],
+    );
+    // Formatting the output again must not change it
+    let formatted = format_source(script);
+    assert_eq!(formatted, format_source(&formatted));
+
+    let lit = FloatLit {
+        loc: Default::default(),
+        value: 1e10,
+    };
+    let formatted = format_node(walk::Node::FloatLit(&lit)).unwrap();
+    assert_eq!("10000000000.0", formatted);
+    assert_eq!(Ok(1e10), formatted.parse::<f64>());
+
+    // A source which is not a float literal is not kept even if it spells the value
+    for src in ["100", "1e2"] {
+        let lit = FloatLit {
+            loc: SourceLocation {
+                source: Some(src.to_string()),
+                ..SourceLocation::default()
+            },
+            value: 100.0,
+        };
+        assert_eq!("100.0", format_node(walk::Node::FloatLit(&lit)).unwrap());
+    }
+}
+
 #[test]
 fn array_lit() {
     let script = r#"
@@ -200,8 +251,8 @@ fn format_member_expression() {
         script,
         expect![[r#"
             package main
-            o = {temp: 30.0, loc: "FL"}:{temp:float, loc:string}
-            t = o:{temp:float, loc:string}.temp:float"#]],
+            o = {temp: 30.0, loc: "FL"}:{loc:string, temp:float}
+            t = o:{loc:string, temp:float}.temp:float"#]],
     )
 }
 
@@ -237,6 +288,20 @@ fn format_option_statement() {
     )
 }
 
+#[test]
+fn format_annotated_variable() {
+    let script = r#"
+            x : [int] = [1, 2, 3]
+            "#;
+
+    check(
+        script,
+        expect![[r#"
+            package main
+            x : [int] = [1, 2, 3]:[int]"#]],
+    )
+}
+
 #[test]
 fn format_test_statement() {
     let script = r#"
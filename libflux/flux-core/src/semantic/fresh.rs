@@ -4,10 +4,10 @@ use std::{collections::BTreeMap, hash::Hash};
 
 use crate::semantic::{
     nodes::Symbol,
-    sub::{merge, merge3, merge4, merge_collect},
+    sub::{merge, merge4, merge_collect},
     types::{
         Collection, Dictionary, Function, Kind, Label, MonoType, MonoTypeVecMap, PolyType,
-        Property, Record, RecordLabel, SemanticMap, Tvar, TvarMap,
+        Property, Record, RecordLabel, SemanticMap, Tuple, Tvar, TvarMap, TypeAlias,
     },
 };
 
@@ -23,6 +23,11 @@ impl From<u64> for Fresher {
 }
 
 impl Fresher {
+    /// Returns a `Fresher` whose first type variable is `Tvar(seed)`.
+    pub fn with_seed(seed: u64) -> Fresher {
+        Fresher(seed)
+    }
+
     /// Takes a `Fresher` and returns an incremented [`Tvar`].
     pub fn fresh(&mut self) -> Tvar {
         let u = self.0;
@@ -136,19 +141,71 @@ impl Fresh for PolyType {
         let expr = self.expr.fresh(f, sub);
         let vars = self.vars.fresh(f, sub);
         let cons = self.cons.fresh(f, sub);
-        PolyType { vars, cons, expr }
+        let label_sets = self.label_sets.fresh(f, sub);
+        let aliases = self.aliases.fresh(f, sub);
+        let names = self.names.fresh(f, sub);
+        PolyType {
+            vars,
+            cons,
+            label_sets,
+            aliases,
+            names,
+            expr,
+        }
     }
     fn fresh_ref(&self, f: &mut Fresher, sub: &mut TvarMap) -> Option<Self> {
-        let PolyType { vars, cons, expr } = self;
-        merge3(
+        let PolyType {
+            vars,
+            cons,
+            label_sets,
+            aliases,
+            names,
+            expr,
+        } = self;
+        let poly = merge4(
             expr,
             expr.fresh_ref(f, sub),
             vars,
             vars.fresh_ref(f, sub),
             cons,
             cons.fresh_ref(f, sub),
+            label_sets,
+            label_sets.fresh_ref(f, sub),
+        );
+        // The aliases and names are freshened last so that they don't change how the other
+        // variables are numbered
+        match (poly, aliases.fresh_ref(f, sub), names.fresh_ref(f, sub)) {
+            (None, None, None) => None,
+            (poly, fresh_aliases, fresh_names) => {
+                let (expr, vars, cons, label_sets) = poly.unwrap_or_else(|| {
+                    (expr.clone(), vars.clone(), cons.clone(), label_sets.clone())
+                });
+                Some(PolyType {
+                    expr,
+                    vars,
+                    cons,
+                    label_sets,
+                    aliases: fresh_aliases.unwrap_or_else(|| aliases.clone()),
+                    names: fresh_names.unwrap_or_else(|| names.clone()),
+                })
+            }
+        }
+    }
+}
+
+impl Fresh for TypeAlias {
+    fn fresh_ref(&self, f: &mut Fresher, sub: &mut TvarMap) -> Option<Self> {
+        merge(
+            &self.args,
+            self.args.fresh_ref(f, sub),
+            &self.expr,
+            self.expr.fresh_ref(f, sub),
         )
-        .map(|(expr, vars, cons)| PolyType { expr, vars, cons })
+        .map(|(args, expr)| TypeAlias {
+            name: self.name.clone(),
+            args,
+            expr,
+        })
     }
 }
 
@@ -160,6 +217,7 @@ impl Fresh for MonoType {
             MonoType::Var(tvr) => tvr.fresh_ref(f, sub).map(MonoType::Var),
             MonoType::Collection(app) => app.fresh_ref(f, sub).map(MonoType::app),
             MonoType::Record(obj) => obj.fresh_ref(f, sub).map(MonoType::record),
+            MonoType::Tuple(tuple) => tuple.fresh_ref(f, sub).map(MonoType::tuple),
             MonoType::Fun(fun) => fun.fresh_ref(f, sub).map(MonoType::fun),
             MonoType::Dict(dict) => dict.fresh_ref(f, sub).map(MonoType::dict),
         }
@@ -196,6 +254,14 @@ impl Fresh for Dictionary {
     }
 }
 
+impl Fresh for Tuple {
+    fn fresh_ref(&self, f: &mut Fresher, sub: &mut TvarMap) -> Option<Self> {
+        self.elements
+            .fresh_ref(f, sub)
+            .map(|elements| Tuple { elements })
+    }
+}
+
 impl Fresh for Record {
     fn fresh(self, f: &mut Fresher, sub: &mut TvarMap) -> Self {
         self.fresh_ref(f, sub).unwrap_or(self)
@@ -229,6 +295,7 @@ impl Fresh for Record {
                         v: ty,
                     },
                     tail: r,
+                    optional: self.is_optional(&label),
                 };
                 r = MonoType::from(extension);
             }
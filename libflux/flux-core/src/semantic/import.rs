@@ -1,5 +1,7 @@
 //! Module import defines the abstractions for importing Flux package types from various sources.
 
+use anyhow::{anyhow, Result};
+
 use crate::semantic::{
     nodes::Symbol,
     types::{PolyType, SemanticMap},
@@ -18,6 +20,14 @@ pub trait Importer {
     fn symbol(&mut self, _package_path: &str, _symbol_name: &str) -> Option<Symbol> {
         None
     }
+
+    /// Returns true if a package declares `symbol_name` without exporting it, see
+    /// [`Feature::StrictPackageExports`].
+    ///
+    /// [`Feature::StrictPackageExports`]: crate::semantic::Feature::StrictPackageExports
+    fn is_private(&mut self, _package_path: &str, _symbol_name: &str) -> bool {
+        false
+    }
 }
 
 impl<T> Importer for &'_ mut T
@@ -30,6 +40,9 @@ where
     fn symbol(&mut self, package_path: &str, symbol_name: &str) -> Option<Symbol> {
         T::symbol(self, package_path, symbol_name)
     }
+    fn is_private(&mut self, package_path: &str, symbol_name: &str) -> bool {
+        T::is_private(self, package_path, symbol_name)
+    }
 }
 
 /// In memory storage for packages
@@ -44,6 +57,10 @@ impl Importer for Packages {
             .and_then(|exports| exports.lookup_symbol(symbol_name))
             .cloned()
     }
+    fn is_private(&mut self, package_path: &str, symbol_name: &str) -> bool {
+        self.get(package_path)
+            .map_or(false, |exports| exports.is_private(symbol_name))
+    }
 }
 
 impl Importer for &'_ Packages {
@@ -55,4 +72,70 @@ impl Importer for &'_ Packages {
             .and_then(|exports| exports.lookup_symbol(symbol_name))
             .cloned()
     }
+    fn is_private(&mut self, package_path: &str, symbol_name: &str) -> bool {
+        self.get(package_path)
+            .map_or(false, |exports| exports.is_private(symbol_name))
+    }
+}
+
+/// A package provided by an [`ImportResolver`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackageSource {
+    /// The exports of a package which was already analyzed.
+    Exports(PackageExports),
+    /// The Flux source code of a package, which is analyzed when it is imported.
+    Flux(String),
+}
+
+/// ImportResolver provides the packages imported by the source being analyzed, such as packages
+/// stored in a database or a virtual filesystem, see [`AnalyzerConfig::import_resolver`].
+///
+/// [`AnalyzerConfig::import_resolver`]: crate::semantic::AnalyzerConfig::import_resolver
+pub trait ImportResolver {
+    /// Resolves an absolute import path to the package it refers to. Paths which the resolver
+    /// returns an error for are left to the importer of the analyzer, the error is reported if
+    /// the importer doesn't know the path either.
+    fn resolve(&self, path: &str) -> Result<PackageSource>;
+}
+
+impl ImportResolver for Packages {
+    fn resolve(&self, path: &str) -> Result<PackageSource> {
+        self.get(path)
+            .cloned()
+            .map(PackageSource::Exports)
+            .ok_or_else(|| anyhow!(r#"package "{}" not found"#, path))
+    }
+}
+
+/// Imports the packages resolved by an [`ImportResolver`] ahead of an analysis and every other
+/// package from `importer`.
+pub(crate) struct Resolved<'a, I> {
+    /// The exports of each resolved package, `None` for the packages which failed to resolve and
+    /// whose error was already reported.
+    pub(crate) packages: &'a SemanticMap<String, Option<PackageExports>>,
+    pub(crate) importer: I,
+}
+
+impl<I: Importer> Importer for Resolved<'_, I> {
+    fn import(&mut self, path: &str) -> Option<PolyType> {
+        match self.packages.get(path) {
+            Some(Some(exports)) => Some(exports.typ()),
+            Some(None) => Some(PolyType::error()),
+            None => self.importer.import(path),
+        }
+    }
+    fn symbol(&mut self, package_path: &str, symbol_name: &str) -> Option<Symbol> {
+        match self.packages.get(package_path) {
+            Some(Some(exports)) => exports.lookup_symbol(symbol_name).cloned(),
+            Some(None) => None,
+            None => self.importer.symbol(package_path, symbol_name),
+        }
+    }
+    fn is_private(&mut self, package_path: &str, symbol_name: &str) -> bool {
+        match self.packages.get(package_path) {
+            Some(Some(exports)) => exports.is_private(symbol_name),
+            Some(None) => false,
+            None => self.importer.is_private(package_path, symbol_name),
+        }
+    }
 }
@@ -0,0 +1,338 @@
+//! Incremental analysis of a set of packages.
+//!
+//! [`IncrementalAnalyzer`] keeps the results of the previous analysis of each package. A package
+//! is analyzed again only if its source code changed or if the exports of one of the packages it
+//! imports changed, so editing a single file only re-analyzes that file and its dependents.
+
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use anyhow::{anyhow, Result};
+
+use crate::{
+    ast, parser,
+    semantic::{
+        env::Environment,
+        import::Importer,
+        nodes::Symbol,
+        types::{PolyType, SemanticMap},
+        Analyzer, AnalyzerConfig, FileErrors, PackageExports,
+    },
+    DefaultHasher,
+};
+
+/// Analyzes a set of packages, reusing the results of the previous analysis for the packages
+/// which are unaffected by the changes made since.
+pub struct IncrementalAnalyzer<I> {
+    prelude: PackageExports,
+    importer: I,
+    config: AnalyzerConfig,
+    packages: SemanticMap<String, CachedPackage>,
+    generation: u64,
+}
+
+struct CachedPackage {
+    // Hash of the source code the package was parsed from
+    hash: u64,
+    ast: ast::Package,
+    analysis: Option<Analysis>,
+}
+
+struct Analysis {
+    // The generation of each imported package at the time of the analysis
+    dependencies: Vec<(String, u64)>,
+    // Changes every time the exports of the package change
+    generation: u64,
+    exports: PackageExports,
+    errors: Option<FileErrors>,
+}
+
+impl<I: Importer> IncrementalAnalyzer<I> {
+    /// Creates an analyzer for packages which may use the values of `prelude` and import the
+    /// packages provided by `importer`, in addition to each other.
+    pub fn new(prelude: PackageExports, importer: I, config: AnalyzerConfig) -> Self {
+        IncrementalAnalyzer {
+            prelude,
+            importer,
+            config,
+            packages: SemanticMap::new(),
+            generation: 0,
+        }
+    }
+
+    /// Analyzes the packages of `sources`, a map from import paths to the source code of each
+    /// package.
+    ///
+    /// Returns the import paths of the packages which were analyzed, in the order they were
+    /// analyzed. Packages that are left out of `sources` are forgotten. Errors found in a
+    /// package do not stop the analysis, they are available from [`Self::errors`].
+    pub fn analyze(&mut self, sources: &SemanticMap<String, String>) -> Result<Vec<String>> {
+        self.packages.retain(|path, _| sources.contains_key(path));
+        for (path, src) in sources {
+            let hash = {
+                let mut hasher = DefaultHasher::default().build_hasher();
+                src.hash(&mut hasher);
+                hasher.finish()
+            };
+            if self.packages.get(path).map(|pkg| pkg.hash) != Some(hash) {
+                let file = parser::parse_string(path.clone(), src);
+                let ast = ast::Package {
+                    base: ast::BaseNode::default(),
+                    path: path.clone(),
+                    package: file.get_package().to_string(),
+                    files: vec![file],
+                };
+                self.packages.insert(
+                    path.clone(),
+                    CachedPackage {
+                        hash,
+                        ast,
+                        analysis: None,
+                    },
+                );
+            }
+        }
+
+        let mut order = Vec::new();
+        for path in self.packages.keys() {
+            visit(path, &self.packages, &mut order, &mut Vec::new())?;
+        }
+
+        let mut analyzed = Vec::new();
+        for path in order {
+            let pkg = &self.packages[&path];
+            let dependencies: Vec<(String, u64)> = imports(&pkg.ast)
+                .filter_map(|import| {
+                    let analysis = self.packages.get(import)?.analysis.as_ref()?;
+                    Some((import.to_string(), analysis.generation))
+                })
+                .collect();
+            if let Some(analysis) = &pkg.analysis {
+                if analysis.dependencies == dependencies {
+                    continue;
+                }
+            }
+
+            let mut analyzer = Analyzer::new(
+                Environment::new((&self.prelude).into()),
+                Imports {
+                    packages: &self.packages,
+                    importer: &mut self.importer,
+                },
+                self.config.clone(),
+            );
+            let (exports, errors) = match analyzer.analyze_ast(&pkg.ast) {
+                Ok((exports, _)) => (exports, None),
+                Err(salvage) => (
+                    salvage
+                        .value
+                        .map(|(exports, _)| exports)
+                        .unwrap_or_default(),
+                    Some(salvage.error),
+                ),
+            };
+
+            // Dependents only need to be analyzed again if the exports changed
+            let generation = match &pkg.analysis {
+                Some(previous) if previous.exports.same_bindings(&exports) => previous.generation,
+                _ => {
+                    self.generation += 1;
+                    self.generation
+                }
+            };
+            let pkg = self
+                .packages
+                .get_mut(&path)
+                .expect("package was just analyzed");
+            pkg.analysis = Some(Analysis {
+                dependencies,
+                generation,
+                exports,
+                errors,
+            });
+            analyzed.push(path);
+        }
+        Ok(analyzed)
+    }
+
+    /// Returns the exports of the package at `path` as of the last analysis.
+    pub fn exports(&self, path: &str) -> Option<&PackageExports> {
+        Some(&self.packages.get(path)?.analysis.as_ref()?.exports)
+    }
+
+    /// Returns the errors found in the package at `path` during the last analysis, if any.
+    pub fn errors(&self, path: &str) -> Option<&FileErrors> {
+        self.packages.get(path)?.analysis.as_ref()?.errors.as_ref()
+    }
+}
+
+fn imports(pkg: &ast::Package) -> impl Iterator<Item = &str> {
+    pkg.files
+        .iter()
+        .flat_map(|file| file.imports.iter().map(|import| &import.path.value[..]))
+}
+
+// Adds `path` to `order` after the packages it imports. Imports of packages which are not being
+// analyzed are left to the importer. `stack` holds the chain of imports leading to `path`.
+fn visit(
+    path: &str,
+    packages: &SemanticMap<String, CachedPackage>,
+    order: &mut Vec<String>,
+    stack: &mut Vec<String>,
+) -> Result<()> {
+    if let Some(start) = stack.iter().position(|pkg| pkg == path) {
+        let mut cycle = stack[start..].to_vec();
+        cycle.push(path.to_string());
+        return Err(anyhow!(
+            r#"package "{}" depends on itself: {}"#,
+            path,
+            cycle.join(" -> ")
+        ));
+    }
+    if order.iter().any(|pkg| pkg == path) {
+        return Ok(());
+    }
+    if let Some(pkg) = packages.get(path) {
+        stack.push(path.to_string());
+        for import in imports(&pkg.ast) {
+            visit(import, packages, order, stack)?;
+        }
+        stack.pop();
+        order.push(path.to_string());
+    }
+    Ok(())
+}
+
+// Imports the packages being analyzed from their last analysis and every other package from the
+// importer.
+struct Imports<'a, I> {
+    packages: &'a SemanticMap<String, CachedPackage>,
+    importer: &'a mut I,
+}
+
+impl<'a, I> Imports<'a, I> {
+    fn exports(&self, path: &str) -> Option<&'a PackageExports> {
+        Some(&self.packages.get(path)?.analysis.as_ref()?.exports)
+    }
+}
+
+impl<I: Importer> Importer for Imports<'_, I> {
+    fn import(&mut self, path: &str) -> Option<PolyType> {
+        match self.exports(path) {
+            Some(exports) => Some(exports.typ()),
+            None => self.importer.import(path),
+        }
+    }
+
+    fn symbol(&mut self, package_path: &str, symbol_name: &str) -> Option<Symbol> {
+        match self.exports(package_path) {
+            Some(exports) => exports.lookup_symbol(symbol_name).cloned(),
+            None => self.importer.symbol(package_path, symbol_name),
+        }
+    }
+
+    fn is_private(&mut self, package_path: &str, symbol_name: &str) -> bool {
+        match self.exports(package_path) {
+            Some(exports) => exports.is_private(symbol_name),
+            None => self.importer.is_private(package_path, symbol_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantic::import::Packages;
+
+    fn sources(srcs: &[(&str, &str)]) -> SemanticMap<String, String> {
+        srcs.iter()
+            .map(|(path, src)| (path.to_string(), src.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reanalyzes_changed_packages_and_their_dependents() -> Result<()> {
+        let mut analyzer = IncrementalAnalyzer::new(
+            PackageExports::new(),
+            Packages::default(),
+            AnalyzerConfig::default(),
+        );
+        let a = r#"package a
+x = 1"#;
+        let b = r#"package b
+import "a"
+y = a.x"#;
+        let c = r#"package c
+z = "c""#;
+
+        let analyzed = analyzer.analyze(&sources(&[("a", a), ("b", b), ("c", c)]))?;
+        assert_eq!(vec!["a", "b", "c"], analyzed);
+
+        // Nothing changed
+        let analyzed = analyzer.analyze(&sources(&[("a", a), ("b", b), ("c", c)]))?;
+        assert!(analyzed.is_empty());
+
+        // The exports of `a` are unchanged so `b` is not analyzed again
+        let a_comment = r#"package a
+// The answer
+x = 1"#;
+        let analyzed = analyzer.analyze(&sources(&[("a", a_comment), ("b", b), ("c", c)]))?;
+        assert_eq!(vec!["a"], analyzed);
+
+        let a_float = r#"package a
+x = 1.0"#;
+        let analyzed = analyzer.analyze(&sources(&[("a", a_float), ("b", b), ("c", c)]))?;
+        assert_eq!(vec!["a", "b"], analyzed);
+        assert_eq!(
+            "float",
+            analyzer
+                .exports("b")
+                .unwrap()
+                .lookup("y")
+                .unwrap()
+                .to_string()
+        );
+        assert!(analyzer.errors("b").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_errors_per_package() -> Result<()> {
+        let mut analyzer = IncrementalAnalyzer::new(
+            PackageExports::new(),
+            Packages::default(),
+            AnalyzerConfig::default(),
+        );
+        let a = r#"package a
+x = 1 + "a""#;
+        let b = r#"package b
+import "a"
+y = 2"#;
+
+        analyzer.analyze(&sources(&[("a", a), ("b", b)]))?;
+        assert!(analyzer.errors("a").is_some());
+        assert!(analyzer.errors("b").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn cyclic_imports() {
+        let mut analyzer = IncrementalAnalyzer::new(
+            PackageExports::new(),
+            Packages::default(),
+            AnalyzerConfig::default(),
+        );
+        let a = r#"package a
+import "b""#;
+        let b = r#"package b
+import "a""#;
+
+        let err = analyzer
+            .analyze(&sources(&[("a", a), ("b", b)]))
+            .unwrap_err();
+        assert_eq!(
+            r#"package "a" depends on itself: a -> b -> a"#,
+            err.to_string()
+        );
+    }
+}
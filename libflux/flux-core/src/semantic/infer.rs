@@ -8,7 +8,7 @@ use crate::{
     semantic::{
         env::Environment,
         sub::{Substitutable, Substituter, Substitution},
-        types::{self, Kind, MonoType, PolyType, SubstitutionMap, Tvar, TvarKinds},
+        types::{self, Kind, LabelSets, MonoType, PolyType, SubstitutionMap, Tvar, TvarKinds},
     },
 };
 
@@ -118,6 +118,56 @@ impl Substitutable for Error {
     }
 }
 
+/// A step taken while inferring the types of the expressions within the span given by
+/// [`AnalyzerConfig::trace`](crate::semantic::AnalyzerConfig::trace).
+///
+/// The types are recorded as they were just before the step, with everything known at that point
+/// substituted in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceStep {
+    /// `act` was constrained to be of the kind `exp`.
+    Kind {
+        /// The kind `act` must be of
+        exp: Kind,
+        /// The constrained type
+        act: MonoType,
+        /// The location the constraint comes from
+        loc: SourceLocation,
+        /// Set if `act` is not of the kind `exp`
+        error: Option<types::Error>,
+    },
+    /// `exp` and `act` were unified.
+    Equal {
+        /// The expected type
+        exp: MonoType,
+        /// The actual type
+        act: MonoType,
+        /// The location the constraint comes from
+        loc: SourceLocation,
+        /// The reasons the types could not be unified, if they could not be
+        errors: Vec<types::Error>,
+    },
+}
+
+// Collects the steps taken for the locations within `span`.
+pub(crate) struct Trace {
+    span: SourceLocation,
+    pub(crate) steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    pub(crate) fn new(span: SourceLocation) -> Self {
+        Trace {
+            span,
+            steps: Vec::new(),
+        }
+    }
+
+    pub(crate) fn covers(&self, loc: &SourceLocation) -> bool {
+        loc.file == self.span.file && self.span.start <= loc.start && loc.end <= self.span.end
+    }
+}
+
 // Solve a set of type constraints
 pub fn solve(
     cons: &[Constraint],
@@ -218,8 +268,11 @@ pub(crate) fn temporary_generalize(
         }
     }
 
+    // Like in `generalize`, the types of omitted optional labels stay monomorphic
+    let mut env_free_vars = env.free_vars();
+    env_free_vars.extend(sub.monomorphic_vars());
     let generalize = Generalize {
-        env_free_vars: env.free_vars(),
+        env_free_vars,
         vars: Default::default(),
     };
     let t = t.apply(&generalize);
@@ -227,14 +280,21 @@ pub(crate) fn temporary_generalize(
     let vars = generalize.vars.into_inner();
 
     let mut cons = TvarKinds::new();
+    let mut label_sets = LabelSets::new();
     for (tv, bound_tv) in &vars {
         if let Some(kinds) = sub.cons().get(tv) {
             cons.insert(*bound_tv, kinds.to_owned());
         }
+        if let Some(labels) = sub.label_set(*tv) {
+            label_sets.insert(*bound_tv, labels);
+        }
     }
     PolyType {
+        names: sub.declared_names(vars.iter().copied()),
         vars: vars.into_iter().map(|(_, tv)| tv).collect(),
         cons,
+        label_sets,
+        aliases: Vec::new(),
         expr: t,
     }
 }
@@ -257,20 +317,22 @@ pub fn generalize(env: &Environment, sub: &mut Substitution, t: MonoType) -> Pol
     impl Substituter for Generalize<'_> {
         fn try_apply(&self, var: Tvar) -> Option<MonoType> {
             if !self.env_free_vars.contains(&var) {
-                if (var.0 as usize) < self.sub.len() {
-                    if let Some(new_var) = self.sub.try_apply(var) {
-                        return Some(new_var);
-                    }
+                if let Some(new_var) = self.sub.try_apply(var) {
+                    return Some(new_var);
                 }
 
                 let mut vars = self.vars.borrow_mut();
+                // Variables before the seed of the substitution can't be bound to their
+                // replacement, so they are looked up here instead
+                if let Some((_, new_var)) = vars.iter().find(|(old, _)| *old == var) {
+                    return Some(MonoType::BoundVar(*new_var));
+                }
                 let new_var = Tvar(vars.len() as u64);
                 vars.push((var, new_var));
                 let new_type = MonoType::BoundVar(new_var);
-                if var.0 as usize > self.sub.len() {
-                    self.sub.mk_fresh(var.0 as usize - self.sub.len() + 1);
+                if self.sub.mk_fresh_to(var) {
+                    self.sub.union_type(var, new_type.clone()).ok()?;
                 }
-                self.sub.union_type(var, new_type.clone()).ok()?;
                 Some(new_type)
             } else {
                 None
@@ -278,8 +340,13 @@ pub fn generalize(env: &Environment, sub: &mut Substitution, t: MonoType) -> Pol
         }
     }
 
+    // The types of omitted optional labels are only known once the null value they stand for is
+    // used, so they stay monomorphic, as do the other types recorded with
+    // `Substitution::monomorphic`
+    let mut env_free_vars = env.free_vars();
+    env_free_vars.extend(sub.monomorphic_vars());
     let generalize = Generalize {
-        env_free_vars: env.free_vars(),
+        env_free_vars,
         sub,
         vars: Default::default(),
     };
@@ -288,14 +355,21 @@ pub fn generalize(env: &Environment, sub: &mut Substitution, t: MonoType) -> Pol
     let vars = generalize.vars.into_inner();
 
     let mut cons = TvarKinds::new();
+    let mut label_sets = LabelSets::new();
     for (tv, bound_tv) in &vars {
         if let Some(kinds) = sub.cons().get(tv) {
             cons.insert(*bound_tv, kinds.to_owned());
         }
+        if let Some(labels) = sub.label_set(*tv) {
+            label_sets.insert(*bound_tv, labels);
+        }
     }
     PolyType {
+        names: sub.declared_names(vars.iter().copied()),
         vars: vars.into_iter().map(|(_, tv)| tv).collect(),
         cons,
+        label_sets,
+        aliases: Vec::new(),
         expr: t,
     }
 }
@@ -311,40 +385,123 @@ pub fn instantiate(
     sub: &mut Substitution,
     loc: SourceLocation,
 ) -> (MonoType, Constraints) {
-    // Substitute fresh type variables for all quantified variables
-    let sub: SubstitutionMap = poly
-        .vars
-        .into_iter()
-        .map(|tv| (tv, MonoType::Var(sub.fresh())))
-        .collect();
-    // Generate constraints for the new fresh type variables
-    let constraints = poly
-        .cons
-        .into_iter()
-        .fold(Constraints::empty(), |cons, (tv, kinds)| {
-            cons + kinds
-                .into_iter()
-                .map(|kind| Constraint::Kind {
-                    exp: kind,
-                    act: sub.get(&tv).unwrap().clone(),
-                    loc: loc.clone(),
-                })
-                .collect::<Vec<Constraint>>()
-                .into()
-        });
-
-    // Equivalent to `SubstitutionMap` but instantiates bound variables instead of free variables
-    struct InstantiationMap(SubstitutionMap);
-
-    impl Substituter for InstantiationMap {
-        fn try_apply(&self, _var: Tvar) -> Option<MonoType> {
-            None
-        }
-        fn try_apply_bound(&self, var: Tvar) -> Option<MonoType> {
-            self.0.get(&var).cloned()
+    Instantiation::from(poly).instantiate(sub, loc)
+}
+
+/// The parts of a `PolyType` which do not change between instantiations.
+///
+/// Builtins such as `map` are instantiated at every call site, so the skeleton is computed once
+/// and shared, leaving only the allocation of fresh variables to each call of `instantiate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instantiation {
+    vars: Vec<Tvar>,
+    kinds: Vec<(Tvar, Kind)>,
+    label_sets: Vec<(Tvar, Vec<String>)>,
+    names: Vec<(Tvar, String)>,
+    has_labels: bool,
+    expr: MonoType,
+}
+
+impl From<PolyType> for Instantiation {
+    fn from(poly: PolyType) -> Self {
+        let kinds: Vec<_> = poly
+            .cons
+            .into_iter()
+            .flat_map(|(tv, kinds)| kinds.into_iter().map(move |kind| (tv, kind)))
+            .collect();
+        Instantiation {
+            has_labels: kinds.iter().any(|(_, kind)| *kind == Kind::Label),
+            vars: poly.vars,
+            kinds,
+            label_sets: poly.label_sets.into_iter().collect(),
+            names: poly.names.into_iter().collect(),
+            expr: poly.expr,
         }
     }
+}
+
+impl Instantiation {
+    /// Returns the monotype of the instantiated polytype with its quantified variables replaced
+    /// by fresh variables, along with the kind constraints on those variables.
+    ///
+    /// Every call allocates new variables so distinct call sites never share them.
+    pub fn instantiate(
+        &self,
+        sub: &mut Substitution,
+        loc: SourceLocation,
+    ) -> (MonoType, Constraints) {
+        // Monomorphic types have nothing to instantiate
+        if self.vars.is_empty() {
+            return (self.expr.clone(), Constraints::empty());
+        }
 
-    // Instantiate monotype using new fresh type variables
-    (poly.expr.apply(&InstantiationMap(sub)), constraints)
+        // Substitute fresh type variables for all quantified variables
+        let fresh_vars: SubstitutionMap = self
+            .vars
+            .iter()
+            .map(|tv| (*tv, MonoType::Var(sub.fresh())))
+            .collect();
+        for (tv, labels) in &self.label_sets {
+            if let Some(MonoType::Var(var)) = fresh_vars.get(tv) {
+                sub.restrict_labels(*var, labels.clone());
+            }
+        }
+        // Errors involving the fresh variables display them by their declared names
+        for (tv, name) in &self.names {
+            if let Some(MonoType::Var(var)) = fresh_vars.get(tv) {
+                sub.name_var(*var, name.clone());
+            }
+        }
+        // Generate constraints for the new fresh type variables
+        let constraints: Vec<Constraint> = self
+            .kinds
+            .iter()
+            .map(|(tv, kind)| Constraint::Kind {
+                exp: *kind,
+                act: fresh_vars.get(tv).unwrap().clone(),
+                loc: loc.clone(),
+            })
+            .collect();
+
+        // Equivalent to `SubstitutionMap` but instantiates bound variables instead of free variables
+        struct InstantiationMap(SubstitutionMap);
+
+        impl Substituter for InstantiationMap {
+            fn try_apply(&self, _var: Tvar) -> Option<MonoType> {
+                None
+            }
+            fn try_apply_bound(&self, var: Tvar) -> Option<MonoType> {
+                self.0.get(&var).cloned()
+            }
+        }
+
+        // Collects the labels that record variables lack, see `Record::lacked_labels`
+        struct LackedLabels<'a>(&'a Substitution);
+
+        impl Substituter for LackedLabels<'_> {
+            fn try_apply(&self, _var: Tvar) -> Option<MonoType> {
+                None
+            }
+            fn visit_type(&self, typ: &MonoType) -> Option<MonoType> {
+                if let MonoType::Record(record) = typ {
+                    if let Some((tail, labels)) = record.lacked_labels() {
+                        for label in labels {
+                            self.0.lack(tail, label);
+                        }
+                    }
+                }
+                None
+            }
+        }
+
+        // Instantiate monotype using new fresh type variables
+        let expr = self
+            .expr
+            .apply_cow(&InstantiationMap(fresh_vars))
+            .into_owned();
+        if self.has_labels {
+            expr.visit(&LackedLabels(sub));
+        }
+        (expr, constraints.into())
+    }
 }
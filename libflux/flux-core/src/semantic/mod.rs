@@ -10,13 +10,18 @@ mod vectorize;
 pub mod types;
 
 pub mod bootstrap;
+pub mod call_graph;
 pub mod check;
 pub mod env;
+pub mod fold;
 pub mod formatter;
 pub mod fresh;
 pub mod import;
+pub mod incremental;
 pub mod nodes;
+pub mod source_map;
 pub mod sub;
+pub mod to_ast;
 pub mod walk;
 
 #[cfg(test)]
@@ -25,7 +30,7 @@ mod tests;
 #[allow(unused, non_snake_case)]
 pub mod flatbuffers;
 
-use std::{fmt, ops::Range};
+use std::{collections::BTreeSet, fmt, ops::Range, sync::Arc};
 
 use codespan_reporting::{
     diagnostic,
@@ -39,16 +44,22 @@ use thiserror::Error;
 
 use crate::{
     ast,
-    errors::{AsDiagnostic, Errors, Located, Salvage, SalvageResult},
+    errors::{located, AsDiagnostic, Errors, Located, Salvage, SalvageResult, Severity},
     parser,
+    scanner::{self, TokenType},
     semantic::{
         infer::Constraints,
         nodes::Symbol,
         sub::Substitution,
-        types::{MonoType, PolyType, PolyTypeHashMap, Property, Record, RecordLabel},
+        types::{
+            MonoType, PolyType, PolyTypeHashMap, Property, Record, RecordLabel, SemanticMap,
+            TvarNaming,
+        },
     },
 };
 
+pub use self::infer::TraceStep;
+
 /// Result type for multiple semantic errors
 pub type Result<T, E = FileErrors> = std::result::Result<T, E>;
 /// Error represents any error that can occur during any step of the type analysis process.
@@ -75,7 +86,13 @@ pub enum ErrorKind {
     /// Errors that occur because of incompatible/incomplete types
     #[error("{0}")]
     Inference(nodes::ErrorKind),
+    /// Warnings reported as errors because of [`AnalyzerConfig::deny_warnings`]
+    #[error("{0}")]
+    Warning(WarningKind),
 }
+
+impl Severity for ErrorKind {}
+
 impl From<ast::check::Error> for Error {
     fn from(error: ast::check::Error) -> Self {
         Self {
@@ -114,6 +131,38 @@ impl From<Errors<nodes::Error>> for Errors<Error> {
     }
 }
 
+/// Warning represents a non-fatal diagnostic reported during the type analysis process.
+pub type Warning = Located<WarningKind>;
+
+/// WarningKind exposes details about which step of the type analysis process reported a warning.
+#[derive(Error, Debug, PartialEq)]
+pub enum WarningKind {
+    /// A function that could not be vectorized
+    #[error("{0}")]
+    Vectorize(nodes::ErrorKind),
+    /// Lints found while checking the AST
+    #[error("{0}")]
+    Lint(ast::check::WarningKind),
+    /// A match expression which does not match every value of its scrutinee
+    #[error("{0}")]
+    Match(nodes::ErrorKind),
+    /// A top-level binding whose type depends on a type variable which is never determined
+    #[error("{0}")]
+    Ambiguous(nodes::ErrorKind),
+    /// A branch of a conditional expression which is never evaluated as its condition is constant
+    #[error("{0}")]
+    Unreachable(nodes::ErrorKind),
+    /// A function which never uses the pipe parameter it declares
+    #[error("{0}")]
+    UnusedPipe(nodes::ErrorKind),
+}
+
+impl Severity for WarningKind {
+    fn severity() -> &'static str {
+        "warning"
+    }
+}
+
 /// An environment of values that are available outside of a package
 #[derive(Debug, Clone, PartialEq)]
 pub struct PackageExports {
@@ -122,6 +171,10 @@ pub struct PackageExports {
 
     /// The type representing this package
     typ: PolyType,
+
+    /// Names of the top-level bindings of the package which are not exported, see
+    /// [`Feature::StrictPackageExports`].
+    private: BTreeSet<String>,
 }
 
 impl Default for PackageExports {
@@ -130,9 +183,13 @@ impl Default for PackageExports {
             typ: PolyType {
                 vars: Default::default(),
                 cons: Default::default(),
+                label_sets: Default::default(),
+                aliases: Vec::new(),
+                names: Default::default(),
                 expr: MonoType::from(Record::Empty),
             },
             values: Default::default(),
+            private: Default::default(),
         }
     }
 }
@@ -150,6 +207,7 @@ impl TryFrom<PolyTypeHashMap<Symbol>> for PackageExports {
                 .into_iter_by(|l, r| l.name().cmp(r.name()))
                 .map(|(symbol, typ)| (symbol.to_string(), (symbol, typ)))
                 .collect(),
+            private: BTreeSet::new(),
         })
     }
 }
@@ -163,6 +221,7 @@ impl TryFrom<Vec<(Symbol, PolyType)>> for PackageExports {
                 .into_iter()
                 .map(|(symbol, typ)| (symbol.to_string(), (symbol, typ)))
                 .collect(),
+            private: BTreeSet::new(),
         })
     }
 }
@@ -195,6 +254,11 @@ impl PackageExports {
         self.values.get(k).map(|(symbol, _)| symbol)
     }
 
+    /// Check whether `k` is a top-level binding of the package which is not exported.
+    pub fn is_private(&self, k: &str) -> bool {
+        self.private.contains(k)
+    }
+
     /// Copy all the variable bindings from another environment to the current environment.
     /// This does not change the current environment's `parent` or `readwrite` flag.
     pub fn copy_bindings_from(&mut self, other: &Self) {
@@ -204,6 +268,14 @@ impl PackageExports {
         self.typ = build_polytype(self.values.values().cloned()).unwrap();
     }
 
+    /// Returns `true` if both environments bind the same names to the same types.
+    ///
+    /// Unlike `==` the symbols are not compared, each analysis of a package creates new symbols
+    /// for its bindings.
+    pub fn same_bindings(&self, other: &Self) -> bool {
+        self.private == other.private && self.iter().eq(other.iter())
+    }
+
     /// Returns an iterator over all values
     pub fn iter(&self) -> impl Iterator<Item = (&str, &PolyType)> + '_ {
         self.values.iter().map(|(k, (_, v))| (k.as_str(), v))
@@ -264,12 +336,61 @@ fn build_record(
                 v: ty,
             },
             tail: MonoType::record(r),
+            optional: false,
         };
         cons += constraints;
     }
     (r, cons)
 }
 
+/// Parses a type expression such as `(x: A) => A where A: Addable` into a [`PolyType`].
+pub fn parse_polytype(src: &str) -> Result<PolyType> {
+    let mut parser = parser::Parser::new(src);
+    let typ_expr = parser.parse_type_expression();
+
+    let mut errors = Errors::new();
+    if let Err(err) = ast::check::check(ast::walk::Node::TypeExpression(&typ_expr)) {
+        errors.extend(err.into_iter().map(Error::from));
+    }
+    let next = parser.peek();
+    if next.tok != TokenType::Eof {
+        errors.push(Error::from(ast::check::Error {
+            location: ast::SourceLocation {
+                file: None,
+                start: ast::Position::from(&next.start),
+                end: ast::Position::from(&next.end),
+                source: Some(next.lit.clone()),
+            },
+            error: ast::check::ErrorKind {
+                message: format!("expected end of type expression, got {}", next.lit),
+            },
+        }));
+    }
+    let poly = convert::convert_polytype(&typ_expr, &mut Substitution::default())
+        .map_err(|err| errors.extend(err.into_iter().map(Error::from)));
+
+    match poly {
+        Ok(poly) if !errors.has_errors() => Ok(poly),
+        _ => Err(FileErrors {
+            file: String::new(),
+            source: Some(src.to_string()),
+            errors,
+            tvar_naming: TvarNaming::default(),
+        }),
+    }
+}
+
+/// Parses a type expression into a [`PolyType`], returning the first error if the type expression
+/// is malformed. See [`parse_polytype`] to get every error.
+pub fn parse_type(src: &str) -> Result<PolyType, Error> {
+    parse_polytype(src).map_err(|err| {
+        err.errors
+            .into_iter()
+            .next()
+            .expect("a failed parse reports at least one error")
+    })
+}
+
 /// Error represents any any error that can occur during any step of the type analysis process.
 #[derive(Error, Debug, PartialEq)]
 pub struct FileErrors {
@@ -279,14 +400,53 @@ pub struct FileErrors {
     // TODO Change the API such that we must always provide the source?
     pub source: Option<String>,
     #[source]
-    /// The errors the occurred in that file
+    /// The errors the occurred in that file, ordered by their position in the source.
     pub errors: Errors<Located<ErrorKind>>,
+    /// How type variables are named when the errors are displayed.
+    pub tvar_naming: TvarNaming,
+}
+
+/// Returns the names of the top-level variables and builtins of `pkg` which are not marked with
+/// `export`, unless another statement exports the same name.
+fn private_bindings(pkg: &ast::Package) -> BTreeSet<String> {
+    let mut private = BTreeSet::new();
+    let mut exported = BTreeSet::new();
+    for stmt in pkg.files.iter().flat_map(|file| &file.body) {
+        let (names, stmt) = match stmt {
+            ast::Statement::Export(export) => (&mut exported, &export.statement),
+            _ => (&mut private, stmt),
+        };
+        match stmt {
+            ast::Statement::Variable(assgn) => {
+                names.insert(assgn.id.name.clone());
+            }
+            ast::Statement::Builtin(builtin) => {
+                names.insert(builtin.id.name.clone());
+            }
+            ast::Statement::Destructuring(assgn) => {
+                names.extend(assgn.bindings().map(|(_, id)| id.name.clone()))
+            }
+            _ => (),
+        }
+    }
+    &private - &exported
+}
+
+/// Orders `errors` by their position in the source and removes any duplicates.
+fn sort_errors(errors: Errors<Error>) -> Errors<Error> {
+    let mut errors = Vec::from(errors);
+    errors.sort_by(|l, r| {
+        let (l, r) = (&l.location, &r.location);
+        (&l.file, l.start.line, l.start.column).cmp(&(&r.file, r.start.line, r.start.column))
+    });
+    errors.dedup();
+    Errors::from(errors)
 }
 
 impl fmt::Display for FileErrors {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // TODO Use codespan's formatting for errors
-        self.errors.fmt(f)
+        types::with_tvar_naming(self.tvar_naming, || self.errors.fmt(f))
     }
 }
 
@@ -296,21 +456,18 @@ pub(crate) trait Source {
 
 impl Source for codespan_reporting::files::SimpleFile<&str, &str> {
     fn codespan_range(&self, location: &ast::SourceLocation) -> Range<usize> {
-        (|| {
-            let start = self
-                .line_range((), (location.start.line as usize).saturating_sub(1))
-                .ok()?
-                .start;
-            let end = self
-                .line_range((), (location.end.line as usize).saturating_sub(1))
-                .ok()?
-                .start;
-            Some(
-                start + (location.start.column as usize).saturating_sub(1)
-                    ..end + (location.end.column as usize).saturating_sub(1),
-            )
-        })()
-        .unwrap_or_default()
+        // Columns count characters, so they are mapped back to byte offsets within their line
+        let offset = |position: &ast::Position| {
+            let line = self
+                .line_range((), (position.line as usize).saturating_sub(1))
+                .ok()?;
+            let start = line.start;
+            Some(start + scanner::column_offset(self.source().get(line)?, position.column))
+        };
+        offset(&location.start)
+            .zip(offset(&location.end))
+            .map(|(start, end)| start..end)
+            .unwrap_or_default()
     }
 }
 
@@ -339,7 +496,7 @@ impl FileErrors {
                 // Mirror println! by ignoring errors
                 let _ = self.print_config(&term::Config::default(), source, &mut stdout);
             }
-            None => println!("{}", self.errors),
+            None => println!("{}", self),
         }
     }
 
@@ -359,10 +516,12 @@ impl FileErrors {
         writer: &mut dyn WriteColor,
     ) -> Result<(), codespan_reporting::files::Error> {
         let files = codespan_reporting::files::SimpleFile::new(&self.file[..], source);
-        for err in &self.errors {
-            err.pretty_fmt(config, &files, writer)?;
-        }
-        Ok(())
+        types::with_tvar_naming(self.tvar_naming, || {
+            for err in &self.errors {
+                err.pretty_fmt(config, &files, writer)?;
+            }
+            Ok(())
+        })
     }
 }
 
@@ -387,6 +546,17 @@ impl AsDiagnostic for ErrorKind {
             Self::Convert(err) => err.as_diagnostic(source),
             Self::InvalidSemantic(err) => err.as_diagnostic(source),
             Self::Inference(err) => err.as_diagnostic(source),
+            Self::Warning(WarningKind::Lint(err)) => diagnostic::Diagnostic {
+                severity: diagnostic::Severity::Error,
+                ..err.as_diagnostic(source)
+            },
+            Self::Warning(
+                WarningKind::Vectorize(err)
+                | WarningKind::Match(err)
+                | WarningKind::Ambiguous(err)
+                | WarningKind::Unreachable(err)
+                | WarningKind::UnusedPipe(err),
+            ) => err.as_diagnostic(source),
         }
     }
 }
@@ -397,6 +567,9 @@ pub struct Analyzer<'env, I: import::Importer> {
     env: env::Environment<'env>,
     importer: I,
     config: AnalyzerConfig,
+    warnings: Errors<Warning>,
+    trace: Vec<TraceStep>,
+    source_map: source_map::SourceMap,
 }
 
 /// Features used in the flux compiler
@@ -407,15 +580,92 @@ pub enum Feature {
     VectorizeAddition,
     /// Enables vectorization of all operators
     VectorizeOperators,
+    /// Enables vectorization of conditional expressions
+    VectorizeConditionals,
     /// Enables vectorization
     VectorizedMap,
+    /// Reports why functions could not be vectorized as warnings
+    VectorizeDiagnostics,
+
+    /// Reports top-level and local bindings which are never used as warnings. Options, tests,
+    /// exported bindings and function parameters whose name starts with an underscore are exempt
+    UnusedDiagnostics,
+
+    /// Reports bindings which shadow a binding of an enclosing scope, including the builtins of
+    /// the prelude, as warnings
+    ShadowingDiagnostics,
 
     /// Enables label polymorphism
     LabelPolymorphism,
+
+    /// Requires values read from records, which may be null, to be guarded with `exists` or
+    /// given a default with `??` before they are used in arithmetic. A property may be null if it
+    /// is optional or if its record is open, as the rows of a table. Variables bound to such a
+    /// value and functions returning one may be null as well
+    StrictNullChecks,
+
+    /// Allows `+` to merge two records, `{a: 1} + {b: 2}` has the type `{a: int, b: int}`.
+    /// Labels present in both records must have the same type, the value of the right record is
+    /// kept. The fields of one of the records may be unknown, such as those of a parameter, as
+    /// long as the other record is known. An unknown right record must not have the labels of
+    /// the left one, so a function concatenating its parameter on the right is not polymorphic
+    RecordConcatenation,
+
+    /// Reports literal labels, passed as the columns of the input stream of a function, which are
+    /// missing from the closed record type of that stream. Requires `LabelPolymorphism`
+    ExhaustiveLabelCheck,
+
+    /// Allows the argument of a piped call to be marked with the `_` placeholder, which passes the
+    /// piped value to that parameter instead of the `<-` parameter, `x |> f(a: _, b: 2)`. Calls
+    /// without a placeholder pipe into the `<-` parameter as before
+    PipeInMiddle,
+
+    /// Allows block expressions, `x = { a = 1; a + 1 }`, which evaluate their statements in a
+    /// scope of their own and have the value of their trailing expression
+    BlockExpressions,
+
+    /// Allows match expressions, `match x { 1 => "one", _ => "many" }`, which have the value of
+    /// the first arm whose literal pattern equals the scrutinee or whose pattern is `_`
+    PatternMatching,
+
+    /// Allows ternary expressions, `x > 0 ? "positive" : "negative"`, a shorthand for
+    /// `if x > 0 then "positive" else "negative"`
+    TernaryOperator,
+
+    /// Allows spreading arrays and records into array and record literals, `[...xs, 4]` and
+    /// `{...r, x: 1}`. The entries which follow a spread override the fields it spreads
+    SpreadOperator,
+
+    /// Allows guarded member access, `r?.a`, which reads a property the record may not have
+    /// instead of failing to type check. The value is null when the property is missing
+    GuardedAccess,
+
+    /// Allows type parameters with names longer than one letter, such as `Col` and `Val` in
+    /// `(table: stream[{A with Col: Val}], column: Col) => Val where Col: Label, Val: Equatable`.
+    /// A name is a type parameter when it is constrained in the `where` clause
+    NamedTypeParameters,
+
+    /// Allows integer literals where a float is expected, `1 + 2.0` or `f(x: 1)` when `x` is a
+    /// float. The literal becomes a float literal. Other integer values are never widened
+    NumericWidening,
+
+    /// Allows binding several fields of a record at once, `{a, b} = r` binds `a` to `r.a` and `b`
+    /// to `r.b` and `{a: x} = r` binds `x` to `r.a`
+    DestructuringAssignment,
+
+    /// Only exports the variables and builtins of a package which are marked with `export`,
+    /// `export x = 1`. The others can only be used within the package. Options and tests are
+    /// always exported
+    StrictPackageExports,
+
+    /// Requires both operands of `<`, `<=`, `>` and `>=` to have the same type, so that
+    /// `(a, b) => a < b` has the type `(a: A, b: A) => bool where A: Comparable`. Without it
+    /// ints and floats can be compared with each other, `1 < 2.0`
+    StrictComparisons,
 }
 
 /// A set of configuration options for the behavior of an Analyzer.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct AnalyzerConfig {
     /// If true no AST or Semantic checks are performed.
     /// Default is false.
@@ -423,16 +673,85 @@ pub struct AnalyzerConfig {
 
     /// Features used in the flux compiler
     pub features: Vec<Feature>,
+
+    /// How type variables are named in the messages of the errors returned by the analyzer.
+    /// Variables are always named in the order they first appear within each error.
+    pub tvar_naming: TvarNaming,
+
+    /// Records the steps taken to infer the types of the expressions within this location, which
+    /// are then available from [`Analyzer::trace`]. Meant for debugging why an expression got
+    /// the type it has. Default is `None`, which records nothing.
+    pub trace: Option<ast::SourceLocation>,
+
+    /// Types of builtin values which are in scope in addition to the values of the environment
+    /// given to the analyzer, see [`AnalyzerConfig::with_builtins`].
+    pub builtins: SemanticMap<String, PolyType>,
+
+    /// How deeply nested the types being unified may be before the analysis reports that a type
+    /// is too deeply nested. Default is `None`, which uses
+    /// [`sub::DEFAULT_MAX_UNIFICATION_DEPTH`].
+    pub max_unification_depth: Option<usize>,
+
+    /// If true every warning of the analysis is reported as an error instead, with the same
+    /// message and location, so that the analysis fails.
+    /// Default is false.
+    pub deny_warnings: bool,
+
+    /// The identifier of the first type variable created by the analysis, as with
+    /// [`fresh::Fresher::with_seed`]. Analyzing the same source with the same seed always
+    /// produces the same type variables, which the type variables of other types can be kept
+    /// apart from by giving a seed above them. Errors still name type variables by the order they
+    /// appear in, regardless of the seed. The seed only applies when the analysis starts from an
+    /// empty substitution.
+    /// Default is 0.
+    pub fresher_seed: u64,
+
+    /// Records the nodes synthesized while desugaring the source, such as pipes and ternary
+    /// expressions, along with the source they were synthesized from. They are then available
+    /// from [`Analyzer::source_map`].
+    /// Default is false.
+    pub source_map: bool,
+
+    /// Resolves the packages imported by the analyzed source before the importer of the
+    /// analyzer is consulted, see [`import::ImportResolver`]. Packages resolved to Flux source
+    /// code are analyzed with the environment and configuration of the analyzer. Shared so that
+    /// the configuration can be cloned and sent to other threads.
+    /// Default is `None`, which imports every package from the importer.
+    pub import_resolver: Option<Arc<dyn import::ImportResolver + Send + Sync>>,
+}
+
+impl AnalyzerConfig {
+    /// Adds the types of builtin values to the configuration, such as the ones provided by an
+    /// application embedding Flux. The types may be written with [`parse_polytype`].
+    ///
+    /// The builtins are the outermost scope of the analysis, the environment given to the
+    /// [`Analyzer`] may shadow them.
+    pub fn with_builtins(mut self, builtins: impl IntoIterator<Item = (String, PolyType)>) -> Self {
+        self.builtins.extend(builtins);
+        self
+    }
 }
 
 impl<'env, I: import::Importer> Analyzer<'env, I> {
     /// Create an analyzer with the given environment and importer.
     /// The environment represents any values in scope.
-    pub fn new(env: env::Environment<'env>, importer: I, config: AnalyzerConfig) -> Self {
+    pub fn new(mut env: env::Environment<'env>, importer: I, config: AnalyzerConfig) -> Self {
+        if !config.builtins.is_empty() {
+            env.push_root(env::Environment::from(
+                config
+                    .builtins
+                    .iter()
+                    .map(|(name, typ)| (Symbol::from(name.as_str()), typ.clone()))
+                    .collect::<PolyTypeHashMap<Symbol>>(),
+            ));
+        }
         Analyzer {
             env,
             importer,
             config,
+            warnings: Errors::new(),
+            trace: Vec::new(),
+            source_map: source_map::SourceMap::default(),
         }
     }
     /// Create an analyzer with the given environment and importer using default configuration.
@@ -476,16 +795,246 @@ impl<'env, I: import::Importer> Analyzer<'env, I> {
         ast_pkg: &ast::Package,
         sub: &mut sub::Substitution,
     ) -> SalvageResult<(PackageExports, nodes::Package), FileErrors> {
+        let (env, sem_pkg, mut errors) = self.infer_ast(ast_pkg, sub);
+
+        let mut sem_pkg = nodes::inject_pkg_types(sem_pkg, sub);
+        errors.extend(
+            nodes::check_match_wildcards(&sem_pkg, sub)
+                .into_iter()
+                .map(Error::from),
+        );
+        self.warnings.extend(
+            nodes::check_match_exhaustiveness(&sem_pkg)
+                .into_iter()
+                .map(|warning| warning.map(WarningKind::Match)),
+        );
+        self.warnings.extend(
+            nodes::check_ambiguous_types(&sem_pkg, sub)
+                .into_iter()
+                .map(|warning| warning.map(WarningKind::Ambiguous)),
+        );
+        self.warnings.extend(
+            fold::check_unreachable_branches(&sem_pkg, &self.env)
+                .into_iter()
+                .map(|warning| warning.map(WarningKind::Unreachable)),
+        );
+        self.warnings.extend(
+            nodes::check_unused_pipe_parameters(&sem_pkg)
+                .into_iter()
+                .map(|warning| warning.map(WarningKind::UnusedPipe)),
+        );
+        self.deny_warnings(&mut errors);
+
+        if errors.has_errors() {
+            return Err(Salvage {
+                error: FileErrors {
+                    file: sem_pkg.package.clone(),
+                    source: None,
+                    errors: sort_errors(errors),
+                    tvar_naming: self.config.tvar_naming,
+                },
+                value: Some((env, sem_pkg)),
+            });
+        }
+
+        if self.config.features.contains(&Feature::VectorizedMap) {
+            // Try to vectorize all the function expressions in a package. This will
+            // return an error if it finds a function can't be vectorized, but we
+            // don't expect all functions to be vectorizable. So we just let it
+            // vectorize what it can, and fail silently for all other cases unless
+            // `VectorizeDiagnostics` asks for the failures to be reported as warnings.
+            if let Err(err) = vectorize::vectorize(&self.config, &mut sem_pkg) {
+                log::debug!("{}", err);
+                if self
+                    .config
+                    .features
+                    .contains(&Feature::VectorizeDiagnostics)
+                {
+                    self.warnings
+                        .extend(err.into_iter().map(|err| err.map(WarningKind::Vectorize)));
+                }
+            }
+            self.deny_warnings(&mut errors);
+            if errors.has_errors() {
+                return Err(Salvage {
+                    error: FileErrors {
+                        file: sem_pkg.package.clone(),
+                        source: None,
+                        errors: sort_errors(errors),
+                        tvar_naming: self.config.tvar_naming,
+                    },
+                    value: Some((env, sem_pkg)),
+                });
+            }
+        }
+        Ok((env, sem_pkg))
+    }
+
+    /// Checks that Flux source code type checks, returning the same errors as
+    /// [`Self::analyze_source`] in the same order.
+    ///
+    /// The inferred types are never injected into the semantic graph, and the warnings and the
+    /// vectorization, which need the typed graph, are skipped. If the configuration denies
+    /// warnings they are errors, so the full analysis is run instead.
+    pub fn check(&mut self, file_name: String, src: &str) -> Result<(), Vec<Error>> {
+        let ast_file = parser::parse_string(file_name, src);
+        let ast_pkg = ast::Package {
+            base: ast_file.base.clone(),
+            path: String::new(),
+            package: ast_file.get_package().to_string(),
+            files: vec![ast_file],
+        };
+        if self.config.deny_warnings {
+            return match self.analyze_ast(&ast_pkg) {
+                Ok(_) => Ok(()),
+                Err(salvage) => Err(salvage.error.errors.into()),
+            };
+        }
+        let mut sub = sub::Substitution::default();
+        let (_, sem_pkg, mut errors) = self.infer_ast(&ast_pkg, &mut sub);
+        errors.extend(
+            nodes::check_match_wildcards(&sem_pkg, &sub)
+                .into_iter()
+                .map(Error::from),
+        );
+        if errors.has_errors() {
+            Err(sort_errors(errors).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Analyzes a single Flux expression, such as `a + 1`, checking that its type unifies with
+    /// `expected`. Returns the expression with the types it was inferred to have.
+    ///
+    /// The expression is analyzed as if it was the only statement of a package, located at the
+    /// start of `src`, so the locations of the errors point into `src` itself.
+    pub fn analyze_expr(
+        &mut self,
+        src: &str,
+        expected: PolyType,
+    ) -> Result<nodes::Expression, FileErrors> {
+        let expr = parser::parse_expression(src);
+        let base = expr.base().clone();
+        let ast_file = ast::File {
+            base: base.clone(),
+            name: String::new(),
+            metadata: String::new(),
+            package: None,
+            imports: Vec::new(),
+            body: vec![ast::Statement::Expr(Box::new(ast::ExprStmt {
+                base: base.clone(),
+                expression: expr,
+            }))],
+            eof: Vec::new(),
+        };
+        let ast_pkg = ast::Package {
+            base,
+            path: String::new(),
+            package: ast_file.get_package().to_string(),
+            files: vec![ast_file],
+        };
+
+        let mut sub = sub::Substitution::default();
+        let (_, sem_pkg, mut errors) = self.infer_ast(&ast_pkg, &mut sub);
+        if !errors.has_errors() {
+            if let nodes::Statement::Expr(stmt) = &sem_pkg.files[0].body[0] {
+                let loc = stmt.expression.loc();
+                let (typ, cons) = infer::instantiate(expected, &mut sub, loc.clone());
+                let unified = infer::solve(&cons, &mut sub)
+                    .map_err(Errors::<nodes::Error>::from)
+                    .and_then(|()| {
+                        infer::equal(&typ, &stmt.expression.type_of(), loc, &mut sub)
+                            .map_err(Errors::<nodes::Error>::from)
+                    });
+                if let Err(err) = unified {
+                    errors.extend(err.into_iter().map(Error::from));
+                }
+            }
+        }
+        self.deny_warnings(&mut errors);
+
+        let mut sem_pkg = nodes::inject_pkg_types(sem_pkg, &sub);
+        let expr = match sem_pkg.files.remove(0).body.remove(0) {
+            nodes::Statement::Expr(stmt) => stmt.expression,
+            stmt => unreachable!("expected an expression statement, got {:?}", stmt),
+        };
+
+        if errors.has_errors() {
+            Err(FileErrors {
+                file: String::new(),
+                source: Some(src.to_string()),
+                errors: sort_errors(errors),
+                tvar_naming: self.config.tvar_naming,
+            })
+        } else {
+            Ok(expr)
+        }
+    }
+
+    // Moves the warnings reported so far into `errors` if the configuration denies warnings.
+    fn deny_warnings(&mut self, errors: &mut Errors<Error>) {
+        if self.config.deny_warnings {
+            errors.extend(
+                self.warnings
+                    .drain(..)
+                    .map(|warning| warning.map(ErrorKind::Warning)),
+            );
+        }
+    }
+
+    // Checks and infers the types of the package without injecting the types into the semantic
+    // graph.
+    fn infer_ast(
+        &mut self,
+        ast_pkg: &ast::Package,
+        sub: &mut sub::Substitution,
+    ) -> (PackageExports, nodes::Package, Errors<Error>) {
+        self.warnings = Errors::new();
+        self.trace = Vec::new();
+        if let Some(depth) = self.config.max_unification_depth {
+            sub.set_max_unification_depth(depth);
+        }
+        if sub.is_empty() {
+            sub.set_seed(self.config.fresher_seed);
+        }
         let mut errors = Errors::new();
         if !self.config.skip_checks {
             if let Err(err) = ast::check::check(ast::walk::Node::Package(ast_pkg)) {
                 errors.extend(err.into_iter().map(Error::from));
             }
+            if self
+                .config
+                .features
+                .contains(&Feature::ShadowingDiagnostics)
+            {
+                let shadowing = ast::check::check_shadowing(
+                    ast::walk::Node::Package(ast_pkg),
+                    self.env.names(),
+                );
+                self.warnings.extend(
+                    shadowing
+                        .into_iter()
+                        .map(|warning| warning.map(WarningKind::Lint)),
+                );
+            }
+            if self.config.features.contains(&Feature::UnusedDiagnostics) {
+                self.warnings.extend(
+                    ast::check::check_unused(ast::walk::Node::Package(ast_pkg))
+                        .into_iter()
+                        .map(|warning| warning.map(WarningKind::Lint)),
+                );
+            }
         }
 
         let mut sem_pkg = {
-            let mut converter = convert::Converter::with_env(sub, &self.env);
+            let mut converter =
+                convert::Converter::with_env(sub, &self.env).with_features(&self.config.features);
+            if self.config.source_map {
+                converter = converter.with_source_map();
+            }
             let sem_pkg = converter.convert_package(ast_pkg);
+            self.source_map = converter.take_source_map();
             if let Err(err) = converter.finish(()) {
                 errors.extend(err.into_iter().map(Error::from));
             }
@@ -498,20 +1047,59 @@ impl<'env, I: import::Importer> Analyzer<'env, I> {
             }
         }
 
+        let resolved = match self.config.import_resolver.clone() {
+            Some(resolver) => {
+                let mut resolution = ImportResolution {
+                    resolver: &*resolver,
+                    env: &self.env,
+                    importer: &mut self.importer,
+                    config: &self.config,
+                    resolved: SemanticMap::new(),
+                };
+                errors.extend(
+                    resolution
+                        .resolve_imports(ast_pkg, &mut Vec::new())
+                        .into_iter()
+                        .map(Error::from),
+                );
+                resolution.resolved
+            }
+            None => SemanticMap::new(),
+        };
+
         self.env.enter_scope();
         let env = match nodes::infer_package(
             &mut sem_pkg,
             &mut self.env,
             sub,
-            &mut self.importer,
+            &mut import::Resolved {
+                packages: &resolved,
+                importer: &mut self.importer,
+            },
             &self.config,
+            &mut self.trace,
         ) {
             Ok(()) => {
-                let env = self.env.exit_scope();
-                PackageExports::try_from(env.values).unwrap_or_else(|err| {
-                    errors.extend(err);
-                    PackageExports::default()
-                })
+                let mut values = self.env.exit_scope().values;
+                values.retain(|symbol, _| !convert::is_synthesized(symbol.name()));
+                let private = if self
+                    .config
+                    .features
+                    .contains(&Feature::StrictPackageExports)
+                {
+                    let private = private_bindings(ast_pkg);
+                    values.retain(|symbol, _| !private.contains(symbol.name()));
+                    private
+                } else {
+                    BTreeSet::new()
+                };
+                match PackageExports::try_from(values) {
+                    Ok(exports) => PackageExports { private, ..exports },
+                    Err(err) => {
+                        errors.extend(err);
+                        PackageExports::default()
+                    }
+                }
             }
             Err(err) => {
                 self.env.exit_scope();
@@ -519,30 +1107,24 @@ impl<'env, I: import::Importer> Analyzer<'env, I> {
                 PackageExports::default()
             }
         };
+        (env, sem_pkg, errors)
+    }
 
-        let mut sem_pkg = nodes::inject_pkg_types(sem_pkg, sub);
+    /// Returns the warnings reported by the most recent analysis.
+    pub fn warnings(&self) -> &Errors<Warning> {
+        &self.warnings
+    }
 
-        if errors.has_errors() {
-            return Err(Salvage {
-                error: FileErrors {
-                    file: sem_pkg.package.clone(),
-                    source: None,
-                    errors,
-                },
-                value: Some((env, sem_pkg)),
-            });
-        }
+    /// Returns the steps taken to infer the types within [`AnalyzerConfig::trace`] during the most
+    /// recent analysis, in the order they were taken.
+    pub fn trace(&self) -> &[TraceStep] {
+        &self.trace
+    }
 
-        if self.config.features.contains(&Feature::VectorizedMap) {
-            // Try to vectorize all the function expressions in a package. This will
-            // return an error if it finds a function can't be vectorized, but we
-            // don't expect all functions to be vectorizable. So we just let it
-            // vectorize what it can, and fail silently for all other cases.
-            if let Err(err) = vectorize::vectorize(&self.config, &mut sem_pkg) {
-                log::debug!("{}", err);
-            }
-        }
-        Ok((env, sem_pkg))
+    /// Returns the nodes synthesized while desugaring the source of the most recent analysis.
+    /// Empty unless [`AnalyzerConfig::source_map`] is set.
+    pub fn source_map(&self) -> &source_map::SourceMap {
+        &self.source_map
     }
 
     /// Drop returns ownership of the environment and importer.
@@ -550,3 +1132,107 @@ impl<'env, I: import::Importer> Analyzer<'env, I> {
         (self.env, self.importer)
     }
 }
+
+// Resolves the packages imported by a package with the resolver of the configuration, ahead of
+// the inference of the package.
+struct ImportResolution<'a, 'env, I> {
+    resolver: &'a dyn import::ImportResolver,
+    env: &'a env::Environment<'env>,
+    importer: &'a mut I,
+    config: &'a AnalyzerConfig,
+    // The exports of the packages resolved so far, `None` for the ones which failed to resolve
+    resolved: SemanticMap<String, Option<PackageExports>>,
+}
+
+impl<I: import::Importer> ImportResolution<'_, '_, I> {
+    // Resolves the packages imported by `ast_pkg` and returns an error for each import which
+    // could not be resolved. `stack` holds the chain of imports leading to `ast_pkg`.
+    fn resolve_imports(
+        &mut self,
+        ast_pkg: &ast::Package,
+        stack: &mut Vec<String>,
+    ) -> Errors<nodes::Error> {
+        let mut errors = Errors::new();
+        for import in ast_pkg.files.iter().flat_map(|file| &file.imports) {
+            let path = &import.path.value;
+            if self.resolved.contains_key(path) {
+                continue;
+            }
+            if let Err(reason) = self.resolve(path, stack) {
+                errors.push(located(
+                    import.base.location.clone(),
+                    nodes::ErrorKind::UnresolvedImport {
+                        path: path.clone(),
+                        reason,
+                    },
+                ));
+            }
+        }
+        errors
+    }
+
+    fn resolve(&mut self, path: &str, stack: &mut Vec<String>) -> Result<(), String> {
+        if let Some(start) = stack.iter().position(|pkg| pkg == path) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(path.to_string());
+            return Err(format!(
+                r#"package "{}" depends on itself: {}"#,
+                path,
+                cycle.join(" -> ")
+            ));
+        }
+        let exports = match self.resolver.resolve(path) {
+            Ok(import::PackageSource::Exports(exports)) => Ok(exports),
+            Ok(import::PackageSource::Flux(src)) => self.analyze(path, &src, stack),
+            Err(_) if self.importer.import(path).is_some() => return Ok(()),
+            Err(err) => Err(err.to_string()),
+        };
+        let result = exports.as_ref().map(|_| ()).map_err(Clone::clone);
+        self.resolved.insert(path.to_string(), exports.ok());
+        result
+    }
+
+    // Analyzes the package at `path` from its source code, after resolving its own imports.
+    fn analyze(
+        &mut self,
+        path: &str,
+        src: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<PackageExports, String> {
+        let file = parser::parse_string(path.to_string(), src);
+        let ast_pkg = ast::Package {
+            base: file.base.clone(),
+            path: path.to_string(),
+            package: file.get_package().to_string(),
+            files: vec![file],
+        };
+
+        stack.push(path.to_string());
+        let errors = self.resolve_imports(&ast_pkg, stack);
+        stack.pop();
+        if errors.has_errors() {
+            return Err(errors.to_string());
+        }
+
+        // The importer is a trait object so that analyzing an imported package does not
+        // instantiate an analyzer of yet another importer type. The builtins of the
+        // configuration are already part of the environment.
+        let importer: &mut dyn import::Importer = &mut *self.importer;
+        let mut analyzer = Analyzer::new(
+            self.env.clone(),
+            import::Resolved {
+                packages: &self.resolved,
+                importer,
+            },
+            AnalyzerConfig {
+                builtins: SemanticMap::new(),
+                import_resolver: None,
+                ..self.config.clone()
+            },
+        );
+        analyzer
+            .analyze_ast(&ast_pkg)
+            .map(|(exports, _)| exports)
+            .map_err(|err| err.error.to_string())
+    }
+}
@@ -7,7 +7,7 @@
 //  Uncommented node types are a direct port of the AST ones.
 #![allow(clippy::match_single_binding)]
 
-use std::{fmt::Debug, vec::Vec};
+use std::{fmt::Debug, rc::Rc, vec::Vec};
 
 use anyhow::{anyhow, bail, Result as AnyhowResult};
 use chrono::{prelude::DateTime, FixedOffset};
@@ -17,18 +17,19 @@ use derive_more::Display;
 
 use crate::{
     ast,
-    errors::{located, AsDiagnostic, Errors, Located},
-    map::HashMap,
+    errors::{located, AsDiagnostic, Errors, Located, Severity},
+    map::{HashMap, HashSet},
     semantic::{
         env::Environment,
+        fresh::{Fresh, Fresher},
         import::Importer,
         infer::{self, Constraint},
         sub::{BindVars, Substitutable, Substituter, Substitution},
         types::{
-            self, Dictionary, Function, Kind, Label, MonoType, MonoTypeMap, PolyType, RecordLabel,
-            Tvar, TvarKinds,
+            self, BuiltinType, Dictionary, Function, Kind, Label, LabelSets, MonoType, MonoTypeMap,
+            PolyType, RecordLabel, Tvar, TvarKinds, TvarMap,
         },
-        AnalyzerConfig, Feature,
+        walk, AnalyzerConfig, Feature,
     },
 };
 
@@ -58,14 +59,86 @@ pub enum ErrorKind {
     InvalidUnaryOp(ast::Operator),
     #[display(fmt = "invalid import path {}", _0)]
     InvalidImportPath(String),
+    #[display(fmt = "cannot resolve import path {}: {}", path, reason)]
+    UnresolvedImport { path: String, reason: String },
+    #[display(fmt = "{} is private to package {}", name, package)]
+    PrivateMember { name: String, package: String },
     #[display(fmt = "return not valid in file block")]
     InvalidReturn,
     #[display(fmt = "can't vectorize function: {}", _0)]
     UnableToVectorize(String),
+    #[display(fmt = "expected a tuple but found {}", _0)]
+    NotATuple(MonoType),
+    #[display(fmt = "cannot interpolate {} into string", _0)]
+    InvalidInterpolation(MonoType),
+    #[display(
+        fmt = "tuple index {} is out of range for a tuple with {} elements",
+        index,
+        len
+    )]
+    TupleIndexOutOfRange { index: usize, len: usize },
+    #[display(
+        fmt = "{} may be null, check it with `exists` or provide a default with `??` before using it in arithmetic",
+        _0
+    )]
+    PossiblyNull(String),
+    #[display(fmt = "cannot concatenate two records whose fields are both unknown")]
+    UnknownRecordConcatenation,
+    #[display(fmt = "the pipe placeholder `_` may only be used once in a call")]
+    MultiplePipePlaceholders,
+    #[display(fmt = "the pipe placeholder `_` may only be used in a piped call")]
+    PipePlaceholderWithoutPipe,
+    #[display(fmt = "block expressions are not enabled")]
+    BlockExpressionsDisabled,
+    #[display(fmt = "match expressions are not enabled")]
+    PatternMatchingDisabled,
+    #[display(fmt = "match on a bool has no arm for `{}`", _0)]
+    NonExhaustiveMatch(bool),
+    #[display(fmt = "match on {} has no wildcard arm `_`", _0)]
+    MatchWithoutWildcard(MonoType),
+    #[display(
+        fmt = "the type of `{}` is ambiguous, it depends on a type constrained to {} which is never determined",
+        name,
+        kinds
+    )]
+    AmbiguousType { name: String, kinds: String },
+    #[display(
+        fmt = "cannot compute {} {} {}, the only arithmetic on times and durations is time + duration, duration + time, duration + duration, time - time, time - duration and duration - duration",
+        left,
+        operator,
+        right
+    )]
+    InvalidTimeArithmetic {
+        operator: ast::Operator,
+        left: MonoType,
+        right: MonoType,
+    },
+    #[display(
+        fmt = "cannot spread a value of type {} into a record, its fields must be known",
+        _0
+    )]
+    UnknownSpreadFields(MonoType),
+    #[display(
+        fmt = "the annotation of `{}` is more general than its type, expected {} but found {}",
+        name,
+        annotation,
+        inferred
+    )]
+    AnnotationTooGeneral {
+        name: String,
+        annotation: PolyType,
+        inferred: PolyType,
+    },
+    #[display(fmt = "this branch is unreachable, the condition is always {}", _0)]
+    UnreachableBranch(bool),
+    #[display(fmt = "the pipe parameter `{}` is never used", _0)]
+    UnusedPipeParameter(String),
     #[display(fmt = "{}. This is a bug in type inference", _0)]
     Bug(String),
 }
 
+impl Severity for ErrorKind {}
+
 impl std::error::Error for Error {}
 
 impl AsDiagnostic for ErrorKind {
@@ -81,12 +154,31 @@ impl Substitutable for ErrorKind {
     fn walk(&self, sub: &dyn Substituter) -> Option<Self> {
         match self {
             Self::Inference(err) => err.visit(sub).map(Self::Inference),
+            Self::NotATuple(typ) => typ.visit(sub).map(Self::NotATuple),
+            Self::InvalidInterpolation(typ) => typ.visit(sub).map(Self::InvalidInterpolation),
+            Self::UnknownSpreadFields(typ) => typ.visit(sub).map(Self::UnknownSpreadFields),
             Self::UndefinedBuiltin(_)
             | Self::UndefinedIdentifier(_)
             | Self::InvalidBinOp(_)
             | Self::InvalidUnaryOp(_)
             | Self::InvalidImportPath(_)
+            | Self::UnresolvedImport { .. }
+            | Self::PrivateMember { .. }
             | Self::UnableToVectorize(_)
+            | Self::TupleIndexOutOfRange { .. }
+            | Self::PossiblyNull(_)
+            | Self::MultiplePipePlaceholders
+            | Self::PipePlaceholderWithoutPipe
+            | Self::BlockExpressionsDisabled
+            | Self::PatternMatchingDisabled
+            | Self::NonExhaustiveMatch(_)
+            | Self::MatchWithoutWildcard(_)
+            | Self::AmbiguousType { .. }
+            | Self::InvalidTimeArithmetic { .. }
+            | Self::UnknownRecordConcatenation
+            | Self::AnnotationTooGeneral { .. }
+            | Self::UnreachableBranch(_)
+            | Self::UnusedPipeParameter(_)
             | Self::InvalidReturn
             | Self::Bug(_) => None,
         }
@@ -128,6 +220,15 @@ struct InferState<'a, 'env> {
     env: &'a mut Environment<'env>,
     errors: Errors<Error>,
     config: &'a AnalyzerConfig,
+    // Values which have been checked with `exists` in the expression currently being inferred.
+    null_guards: Vec<Nullable>,
+    // Variables bound to values which may be null, and functions which may return null.
+    nullable: HashSet<Nullable>,
+    // Instantiation skeletons of the read-only bindings (builtins and imports) which have been
+    // referenced so far, so frequently used functions are only prepared once.
+    instantiations: HashMap<Symbol, Rc<infer::Instantiation>>,
+    // Only set when `AnalyzerConfig::trace` asks for the steps of the inference to be recorded.
+    trace: Option<infer::Trace>,
 }
 
 impl InferState<'_, '_> {
@@ -141,14 +242,72 @@ impl InferState<'_, '_> {
         })
     }
 
+    // Instantiates the type bound to `name`, reusing the skeleton of read-only bindings.
+    fn instantiate(
+        &mut self,
+        loc: &ast::SourceLocation,
+        name: &Symbol,
+    ) -> (MonoType, infer::Constraints) {
+        if let Some(instantiation) = self.instantiations.get(name) {
+            return instantiation.instantiate(self.sub, loc.clone());
+        }
+        let poly = self.lookup(loc, name);
+        if !self.env.is_readonly(name) {
+            return infer::instantiate(poly, self.sub, loc.clone());
+        }
+        let instantiation = Rc::new(infer::Instantiation::from(poly));
+        self.instantiations
+            .insert(name.clone(), instantiation.clone());
+        instantiation.instantiate(self.sub, loc.clone())
+    }
+
     fn constrain(&mut self, exp: Kind, act: &MonoType, loc: &ast::SourceLocation) {
-        if let Err(err) = infer::constrain(exp, act, loc, self.sub) {
+        if let Err(err) = self.try_constrain(exp, act, loc) {
             self.errors.push(err.into());
         }
     }
 
+    fn try_constrain(
+        &mut self,
+        exp: Kind,
+        act: &MonoType,
+        loc: &ast::SourceLocation,
+    ) -> std::result::Result<(), Located<types::Error>> {
+        let traced = self
+            .traced(loc)
+            .then(|| act.apply_cow(self.sub).into_owned());
+        let result = infer::constrain(exp, act, loc, self.sub);
+        if let (Some(trace), Some(act)) = (&mut self.trace, traced) {
+            trace.steps.push(infer::TraceStep::Kind {
+                exp,
+                act,
+                loc: loc.clone(),
+                error: result.as_ref().err().map(|err| err.error.clone()),
+            });
+        }
+        result
+    }
+
     fn equal(&mut self, exp: &MonoType, act: &MonoType, loc: &ast::SourceLocation) -> MonoType {
-        match infer::equal(exp, act, loc, self.sub) {
+        let traced = self.traced(loc).then(|| {
+            (
+                exp.apply_cow(self.sub).into_owned(),
+                act.apply_cow(self.sub).into_owned(),
+            )
+        });
+        let result = infer::equal(exp, act, loc, self.sub);
+        if let (Some(trace), Some((exp, act))) = (&mut self.trace, traced) {
+            trace.steps.push(infer::TraceStep::Equal {
+                exp,
+                act,
+                loc: loc.clone(),
+                errors: match &result {
+                    Ok(_) => Vec::new(),
+                    Err(err) => err.error.iter().cloned().collect(),
+                },
+            });
+        }
+        match result {
             Ok(typ) => typ,
             Err(err) => {
                 self.errors
@@ -162,14 +321,188 @@ impl InferState<'_, '_> {
     }
 
     fn solve(&mut self, cons: &impl AsRef<[Constraint]>) {
-        if let Err(err) = infer::solve(cons.as_ref(), self.sub) {
+        if self.trace.is_some() {
+            // Solve the constraints one at a time so each of them can be traced
+            for constraint in cons.as_ref() {
+                match constraint {
+                    Constraint::Kind { exp, act, loc } => self.constrain(*exp, act, loc),
+                    Constraint::Equal { exp, act, loc } => {
+                        self.equal(exp, act, loc);
+                    }
+                }
+            }
+        } else if let Err(err) = infer::solve(cons.as_ref(), self.sub) {
             self.errors.extend(err.into_iter().map(Error::from));
         }
     }
 
+    fn traced(&self, loc: &ast::SourceLocation) -> bool {
+        match &self.trace {
+            Some(trace) => trace.covers(loc),
+            None => false,
+        }
+    }
+
     fn error(&mut self, loc: ast::SourceLocation, error: ErrorKind) {
         self.errors.push(located(loc, error));
     }
+
+    // Reports an error if `expr` may be null without it having been checked first. Only done
+    // with `Feature::StrictNullChecks`.
+    fn check_null_guard(&mut self, expr: &Expression) {
+        if !self.config.features.contains(&Feature::StrictNullChecks) {
+            return;
+        }
+        if let Some(nullable) = self.nullable(expr) {
+            self.error(
+                expr.loc().clone(),
+                ErrorKind::PossiblyNull(nullable.to_string()),
+            );
+        }
+    }
+
+    // Records that the variable `id` may be null, or may return null if it is a function, when the
+    // expression it is bound to does. Only done with `Feature::StrictNullChecks`.
+    fn bind_nullable(&mut self, id: &Symbol, init: &Expression) {
+        if !self.config.features.contains(&Feature::StrictNullChecks) {
+            return;
+        }
+        let nullable = match init {
+            Expression::Function(func) => self
+                .nullable_return(&func.body)
+                .map(|_| Nullable::Call(id.clone())),
+            _ => self.nullable(init).map(|_| Nullable::Variable(id.clone())),
+        };
+        if let Some(nullable) = nullable {
+            self.nullable.insert(nullable);
+        }
+    }
+
+    // Returns the value which makes `expr` possibly null, if any. A record property may be null
+    // if it is optional or if the record is open, as the record can then come from a table where
+    // the column is missing. The nullability then follows the value through variables, function
+    // calls and conditionals until it is checked with `exists` or given a default with `??`.
+    fn nullable(&mut self, expr: &Expression) -> Option<Nullable> {
+        match expr {
+            Expression::Member(member) if !self.may_be_missing(member) => None,
+            Expression::Member(_) | Expression::Identifier(_) => {
+                let nullable = nullable_source(expr)?;
+                match nullable {
+                    Nullable::Variable(_) if !self.nullable.contains(&nullable) => None,
+                    _ if self.null_guards.contains(&nullable) => None,
+                    _ => Some(nullable),
+                }
+            }
+            Expression::Call(call) => match &call.callee {
+                Expression::Identifier(callee) => {
+                    let nullable = Nullable::Call(callee.name.clone());
+                    if self.nullable.contains(&nullable) {
+                        Some(nullable)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            },
+            Expression::Conditional(cond) => {
+                let guards = self.null_guards.len();
+                collect_null_guards(&cond.test, &mut self.null_guards);
+                let consequent = self.nullable(&cond.consequent);
+                self.null_guards.truncate(guards);
+                consequent.or_else(|| self.nullable(&cond.alternate))
+            }
+            Expression::Block(block) => self.nullable_return(&block.body),
+            Expression::Match(m) => m.arms.iter().find_map(|arm| self.nullable(&arm.body)),
+            _ => None,
+        }
+    }
+
+    // Returns `true` if the record read by `member` may not have the property, see `nullable`.
+    fn may_be_missing(&self, member: &MemberExpr) -> bool {
+        match &member.object.type_of().apply(&*self.sub) {
+            MonoType::Record(record) => {
+                record.tail().is_some()
+                    || record.is_optional(&RecordLabel::from(member.property.clone()))
+            }
+            _ => true,
+        }
+    }
+
+    fn nullable_return(&mut self, body: &Block) -> Option<Nullable> {
+        match body {
+            Block::Variable(_, next) | Block::Expr(_, next) => self.nullable_return(next),
+            Block::Return(ret) => self.nullable(&ret.argument),
+        }
+    }
+
+    // Replaces `expr` with a float literal if it is an integer literal used where `expected` is a
+    // float, see `Feature::NumericWidening`. Integers which a float can't represent exactly stay
+    // integers.
+    fn widen_integer_literal(&self, expr: &mut Expression, expected: &MonoType) {
+        if !self.config.features.contains(&Feature::NumericWidening) {
+            return;
+        }
+        if let Expression::Integer(lit) = expr {
+            if lit.value.unsigned_abs() <= 1 << f64::MANTISSA_DIGITS
+                && *expected.apply_cow(&*self.sub) == MonoType::FLOAT
+            {
+                *expr = Expression::Float(FloatLit {
+                    loc: lit.loc.clone(),
+                    value: lit.value as f64,
+                });
+            }
+        }
+    }
+}
+
+// A value which may be null, see `Feature::StrictNullChecks`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Nullable {
+    // The property read by `r.a`.
+    Property(Symbol, Symbol),
+    Variable(Symbol),
+    // The result of calling the function.
+    Call(Symbol),
+}
+
+impl std::fmt::Display for Nullable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Nullable::Property(object, property) => write!(f, "{}.{}", object, property),
+            Nullable::Variable(id) => write!(f, "{}", id),
+            Nullable::Call(id) => write!(f, "the result of {}", id),
+        }
+    }
+}
+
+// Returns the value which `expr` reads and which can be checked with `exists`.
+fn nullable_source(expr: &Expression) -> Option<Nullable> {
+    match expr {
+        Expression::Member(member) => match &member.object {
+            Expression::Identifier(object) => Some(Nullable::Property(
+                object.name.clone(),
+                member.property.clone(),
+            )),
+            _ => None,
+        },
+        Expression::Identifier(id) => Some(Nullable::Variable(id.name.clone())),
+        _ => None,
+    }
+}
+
+// Collects the values that are checked with `exists` in `test`, which are then known to be
+// present when `test` is true.
+fn collect_null_guards(test: &Expression, guards: &mut Vec<Nullable>) {
+    match test {
+        Expression::Unary(unary) if unary.operator == ast::Operator::ExistsOperator => {
+            guards.extend(nullable_source(&unary.argument));
+        }
+        Expression::Logical(logical) if logical.operator == ast::LogicalOperator::AndOperator => {
+            collect_null_guards(&logical.left, guards);
+            collect_null_guards(&logical.right, guards);
+        }
+        _ => (),
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -182,6 +515,7 @@ pub enum Statement {
     Test(Box<TestStmt>),
     TestCase(Box<TestCaseStmt>),
     Builtin(BuiltinStmt),
+    TypeAlias(TypeAliasStmt),
     Error(ast::SourceLocation),
 }
 
@@ -195,6 +529,7 @@ impl Statement {
             Statement::Test(stmt) => Statement::Test(Box::new(stmt.apply(sub))),
             Statement::TestCase(stmt) => Statement::TestCase(Box::new(stmt.apply(sub))),
             Statement::Builtin(stmt) => Statement::Builtin(stmt.apply(sub)),
+            Statement::TypeAlias(stmt) => Statement::TypeAlias(stmt),
             Statement::Error(stmt) => Statement::Error(stmt),
         }
     }
@@ -227,6 +562,10 @@ pub enum Expression {
     Object(Box<ObjectExpr>),
     Member(Box<MemberExpr>),
     Index(Box<IndexExpr>),
+    Tuple(Box<TupleExpr>),
+    TupleIndex(Box<TupleIndexExpr>),
+    Block(Box<BlockExpr>),
+    Match(Box<MatchExpr>),
     Binary(Box<BinaryExpr>),
     Unary(Box<UnaryExpr>),
     Call(Box<CallExpr>),
@@ -257,6 +596,10 @@ impl Expression {
             Expression::Object(e) => e.typ.clone(),
             Expression::Member(e) => e.typ.clone(),
             Expression::Index(e) => e.typ.clone(),
+            Expression::Tuple(e) => e.typ.clone(),
+            Expression::TupleIndex(e) => e.typ.clone(),
+            Expression::Block(e) => e.typ.clone(),
+            Expression::Match(e) => e.typ.clone(),
             Expression::Binary(e) => e.typ.clone(),
             Expression::Unary(e) => e.typ.clone(),
             Expression::Call(e) => e.typ.clone(),
@@ -284,6 +627,10 @@ impl Expression {
             Expression::Object(e) => &e.loc,
             Expression::Member(e) => &e.loc,
             Expression::Index(e) => &e.loc,
+            Expression::Tuple(e) => &e.loc,
+            Expression::TupleIndex(e) => &e.loc,
+            Expression::Block(e) => &e.loc,
+            Expression::Match(e) => &e.loc,
             Expression::Binary(e) => &e.loc,
             Expression::Unary(e) => &e.loc,
             Expression::Call(e) => &e.loc,
@@ -310,6 +657,10 @@ impl Expression {
             Expression::Object(e) => e.infer(infer),
             Expression::Member(e) => e.infer(infer),
             Expression::Index(e) => e.infer(infer),
+            Expression::Tuple(e) => e.infer(infer),
+            Expression::TupleIndex(e) => e.infer(infer),
+            Expression::Block(e) => e.infer(infer),
+            Expression::Match(e) => e.infer(infer),
             Expression::Binary(e) => e.infer(infer),
             Expression::Unary(e) => e.infer(infer),
             Expression::Call(e) => e.infer(infer),
@@ -336,6 +687,10 @@ impl Expression {
             Expression::Object(e) => Expression::Object(Box::new(e.apply(sub))),
             Expression::Member(e) => Expression::Member(Box::new(e.apply(sub))),
             Expression::Index(e) => Expression::Index(Box::new(e.apply(sub))),
+            Expression::Tuple(e) => Expression::Tuple(Box::new(e.apply(sub))),
+            Expression::TupleIndex(e) => Expression::TupleIndex(Box::new(e.apply(sub))),
+            Expression::Block(e) => Expression::Block(Box::new(e.apply(sub))),
+            Expression::Match(e) => Expression::Match(Box::new(e.apply(sub))),
             Expression::Binary(e) => Expression::Binary(Box::new(e.apply(sub))),
             Expression::Unary(e) => Expression::Unary(Box::new(e.apply(sub))),
             Expression::Call(e) => Expression::Call(Box::new(e.apply(sub))),
@@ -362,6 +717,7 @@ pub fn infer_package<T>(
     sub: &mut Substitution,
     importer: &mut T,
     config: &AnalyzerConfig,
+    trace: &mut Vec<infer::TraceStep>,
 ) -> std::result::Result<(), Errors<Error>>
 where
     T: Importer,
@@ -373,16 +729,39 @@ where
         env,
         errors: Errors::new(),
         config,
+        null_guards: Vec::new(),
+        nullable: HashSet::new(),
+        instantiations: Default::default(),
+        trace: config.trace.clone().map(infer::Trace::new),
     };
-    pkg.infer(&mut infer).map_err(|err| err.apply(infer.sub))?;
+    // Keep any errors that were accumulated before a fatal error occurred
+    if let Err(err) = pkg.infer(&mut infer) {
+        let err = err.apply(infer.sub);
+        infer.errors.push(err);
+    }
 
     infer.env.apply_mut(&FinalizeTypes { sub: infer.sub });
+    if let Some(steps) = infer.trace.take().map(|trace| trace.steps) {
+        *trace = steps;
+    }
 
     if infer.errors.has_errors() {
         let sub = BindVars::new(infer.sub);
         for err in &mut infer.errors {
             err.apply_mut(&FinalizeTypes { sub: &sub });
         }
+        // Display the variables which replaced declared type parameters by their names
+        let names = infer.sub.declared_names(sub.bound_vars());
+        if !names.is_empty() {
+            for err in &mut infer.errors {
+                if let ErrorKind::Inference(error) = &mut err.error {
+                    *error = types::Error::Named {
+                        error: Box::new(error.clone()),
+                        names: names.clone(),
+                    };
+                }
+            }
+        }
         Err(infer.errors)
     } else {
         Ok(())
@@ -425,6 +804,38 @@ pub struct Package {
 }
 
 impl Package {
+    /// Returns the type of the narrowest expression whose location covers `pos`, along with
+    /// that location.
+    ///
+    /// Free type variables in the returned type are renamed in the order they appear, so
+    /// repeated queries for the same expression produce the same type.
+    pub fn type_at(&self, pos: ast::Position) -> Option<(MonoType, ast::SourceLocation)> {
+        let mut found: Option<(MonoType, ast::SourceLocation)> = None;
+        walk::walk(
+            &mut |node| {
+                let loc = node.loc();
+                if !loc.is_valid() || !loc.contains(pos) {
+                    return;
+                }
+                let narrower = match &found {
+                    Some((_, best)) => {
+                        best.start <= loc.start
+                            && loc.end <= best.end
+                            && (best.start, best.end) != (loc.start, loc.end)
+                    }
+                    None => true,
+                };
+                if narrower {
+                    if let Some(typ) = node.type_of() {
+                        found = Some((typ, loc.clone()));
+                    }
+                }
+            },
+            walk::Node::Package(self),
+        );
+        found.map(|(typ, loc)| (typ.fresh(&mut Fresher::default(), &mut TvarMap::new()), loc))
+    }
+
     fn infer(&mut self, infer: &mut InferState) -> Result {
         for file in &mut self.files {
             file.infer(infer)?;
@@ -466,6 +877,8 @@ impl File {
         for node in &mut self.body {
             match node {
                 Statement::Builtin(stmt) => stmt.infer(infer)?,
+                // Type aliases are expanded when converting from the AST
+                Statement::TypeAlias(_) => (),
                 Statement::Variable(stmt) => stmt.infer(infer)?,
                 Statement::Option(stmt) => stmt.infer(infer)?,
                 Statement::Expr(stmt) => stmt.infer(infer)?,
@@ -558,6 +971,15 @@ impl BuiltinStmt {
     }
 }
 
+/// A type alias, `typ` holds the type the alias expands to with `vars` being its parameters.
+#[derive(Debug, PartialEq, Clone)]
+#[allow(missing_docs)]
+pub struct TypeAliasStmt {
+    pub loc: ast::SourceLocation,
+    pub id: Identifier,
+    pub typ: PolyType,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 #[allow(missing_docs)]
 pub struct TestStmt {
@@ -590,6 +1012,8 @@ impl TestCaseStmt {
         for node in &mut self.body {
             match node {
                 Statement::Builtin(stmt) => stmt.infer(infer)?,
+                // Type aliases are expanded when converting from the AST
+                Statement::TypeAlias(_) => (),
                 Statement::Variable(stmt) => stmt.infer(infer)?,
                 Statement::Option(stmt) => stmt.infer(infer)?,
                 Statement::Expr(stmt) => stmt.infer(infer)?,
@@ -655,9 +1079,14 @@ pub struct VariableAssgn {
     #[derivative(PartialEq = "ignore")]
     cons: TvarKinds,
 
+    #[derivative(PartialEq = "ignore")]
+    label_sets: LabelSets,
+
     pub loc: ast::SourceLocation,
 
     pub id: Identifier,
+    /// The type the variable is annotated with, e.g. `x : [int] = []`.
+    pub annotation: Option<PolyType>,
     pub init: Expression,
 }
 
@@ -667,16 +1096,24 @@ impl VariableAssgn {
         VariableAssgn {
             vars: Vec::new(),
             cons: TvarKinds::new(),
+            label_sets: LabelSets::new(),
             loc,
             id,
+            annotation: None,
             init,
         }
     }
     #[allow(missing_docs)]
     pub fn poly_type_of(&self) -> PolyType {
+        if let Some(annotation) = &self.annotation {
+            return annotation.clone();
+        }
         PolyType {
             vars: self.vars.clone(),
             cons: self.cons.clone(),
+            label_sets: self.label_sets.clone(),
+            aliases: Vec::new(),
+            names: Default::default(),
             expr: self.init.type_of(),
         }
     }
@@ -689,14 +1126,67 @@ impl VariableAssgn {
     // the variable to its newly generalized type in the type environment
     // before inferring the rest of the program.
     //
+    // If inferring the expression reported any errors the variable is instead
+    // bound to a fresh, generalized type variable. Every use of the variable
+    // then gets its own type which lets inference continue with the rest of
+    // the program without reporting errors that cascade from the failed
+    // expression.
+    //
+    // An annotated variable is instead bound to exactly its annotation. The
+    // type of the expression is unified with the annotation, which may narrow
+    // it, and must still generalize the annotation afterwards. Otherwise the
+    // annotation claims the variable is more general than it is.
+    //
     fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result<()> {
+        let errors = infer.errors.len();
         self.init.infer(infer)?;
+        infer.bind_nullable(&self.id.name, &self.init);
+
+        if let Some(annotation) = &self.annotation {
+            if infer.errors.len() == errors {
+                let (exp, cons) =
+                    infer::instantiate(annotation.clone(), infer.sub, self.loc.clone());
+                infer.solve(&cons);
+                infer.equal(&exp, &self.init.type_of(), &self.loc);
+            }
+        }
 
         // Apply substitution to the type environment
         infer.env.apply_mut(infer.sub);
 
-        let t = self.init.type_of().apply(infer.sub);
-        let p = infer::generalize(infer.env, infer.sub, t);
+        let p = if let Some(annotation) = &self.annotation {
+            if infer.errors.len() == errors {
+                let t = self.init.type_of().apply(infer.sub);
+                let p = infer::generalize(infer.env, infer.sub, t);
+                // Types which depend on the enclosing scope can't be compared on their own
+                if p.expr.free_vars().is_empty() && !p.subsumes(annotation) {
+                    infer.error(
+                        self.loc.clone(),
+                        ErrorKind::AnnotationTooGeneral {
+                            name: self.id.name.to_string(),
+                            annotation: annotation
+                                .clone()
+                                .fresh(&mut Fresher::default(), &mut TvarMap::new()),
+                            inferred: p.fresh(&mut Fresher::default(), &mut TvarMap::new()),
+                        },
+                    );
+                }
+            }
+            annotation.clone()
+        } else if infer.errors.len() > errors {
+            let tv = infer.sub.fresh();
+            PolyType {
+                vars: vec![tv],
+                cons: TvarKinds::new(),
+                label_sets: Default::default(),
+                aliases: Vec::new(),
+                names: Default::default(),
+                expr: MonoType::Var(tv),
+            }
+        } else {
+            let t = self.init.type_of().apply(infer.sub);
+            infer::generalize(infer.env, infer.sub, t)
+        };
 
         // Update variable assignment nodes with the free vars
         // and kind constraints obtained from generalization.
@@ -705,6 +1195,7 @@ impl VariableAssgn {
         // and so it is safe to update these nodes in place.
         self.vars = p.vars.clone();
         self.cons = p.cons.clone();
+        self.label_sets = p.label_sets.clone();
 
         // Update the type environment
         infer.env.add(self.id.name.clone(), p);
@@ -746,11 +1237,18 @@ impl StringExpr {
         for p in &mut self.parts {
             if let StringExprPart::Interpolated(ref mut ip) = p {
                 ip.expression.infer(infer)?;
-                infer.constrain(
+                if let Err(err) = infer.try_constrain(
                     Kind::Stringable,
                     &ip.expression.type_of(),
                     ip.expression.loc(),
-                );
+                ) {
+                    match err.error {
+                        types::Error::CannotConstrain { act, .. } => {
+                            infer.error(err.location, ErrorKind::InvalidInterpolation(act))
+                        }
+                        error => infer.errors.push(located(err.location, error.into())),
+                    }
+                }
             }
         }
         Ok(())
@@ -813,19 +1311,39 @@ pub struct ArrayExpr {
 
 impl ArrayExpr {
     fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
-        let mut elt = None;
-        for el in &mut self.elements {
-            el.infer(infer)?;
-
-            match &elt {
+        fn unify_element(
+            infer: &mut InferState<'_, '_>,
+            elt: &mut Option<MonoType>,
+            el: &mut Expression,
+        ) {
+            match elt {
                 None => {
-                    elt = Some(el.type_of());
+                    *elt = Some(el.type_of());
                 }
                 Some(elt) => {
+                    infer.widen_integer_literal(el, elt);
                     infer.equal(elt, &el.type_of(), el.loc());
                 }
             }
         }
+
+        let widening = infer.config.features.contains(&Feature::NumericWidening);
+        let mut elt = None;
+        // Integer literals are unified last so that they are widened against the type of the
+        // other elements wherever they are in the array, see `Feature::NumericWidening`.
+        let mut literals = Vec::new();
+        for el in &mut self.elements {
+            el.infer(infer)?;
+
+            if widening && matches!(el, Expression::Integer(_)) {
+                literals.push(el);
+            } else {
+                unify_element(infer, &mut elt, el);
+            }
+        }
+        for el in literals {
+            unify_element(infer, &mut elt, el);
+        }
         let elt = elt.unwrap_or_else(|| MonoType::Var(infer.sub.fresh()));
         self.typ = MonoType::arr(elt);
         Ok(())
@@ -922,6 +1440,9 @@ impl FunctionExpr {
                     let typ = PolyType {
                         vars: Vec::new(),
                         cons: TvarKinds::new(),
+                        label_sets: Default::default(),
+                        aliases: Vec::new(),
+                        names: Default::default(),
                         expr: param_type.clone(),
                     };
                     infer.env.add(id.clone(), typ);
@@ -935,6 +1456,9 @@ impl FunctionExpr {
                     let typ = PolyType {
                         vars: Vec::new(),
                         cons: TvarKinds::new(),
+                        label_sets: Default::default(),
+                        aliases: Vec::new(),
+                        names: Default::default(),
                         expr: MonoType::Var(ftvar),
                     };
                     infer.env.add(id.clone(), typ.clone());
@@ -1163,11 +1687,159 @@ pub struct BinaryExpr {
 }
 
 impl BinaryExpr {
+    // Infers `+` on records as the record holding the labels of both, see
+    // `Feature::RecordConcatenation`. The labels present in both records must have the same type.
+    //
+    // Either operand may be one whose type is not known yet, such as a function parameter. It is
+    // then constrained to be a record and the result extends the fields of the other operand with
+    // it, the same holds for an operand whose record is open. Its labels can't be compared with
+    // those of the other operand, which must be a closed record.
+    //
+    // The fields of the right operand are kept, so an unknown right operand would override the
+    // known fields of the left one with fields of any type. It must therefore lack the labels of
+    // the left operand, which can only be checked if its type is not generalized. Returns `false`
+    // if neither operand is a record.
+    fn infer_record_concatenation(&mut self, infer: &mut InferState<'_, '_>) -> bool {
+        if !infer
+            .config
+            .features
+            .contains(&Feature::RecordConcatenation)
+        {
+            return false;
+        }
+
+        let left = self.left.type_of().apply(infer.sub);
+        let right = self.right.type_of().apply(infer.sub);
+        if !matches!(left, MonoType::Record(_)) && !matches!(right, MonoType::Record(_)) {
+            return false;
+        }
+        // The known fields of an operand along with the type of its other fields, if any
+        let split = |typ: &MonoType| match typ {
+            MonoType::Record(record) => {
+                let mut fields = record.fields();
+                let props: Vec<types::Property> = (&mut fields).cloned().collect();
+                Some((props, fields.tail().cloned()))
+            }
+            MonoType::Var(_) => Some((Vec::new(), Some(typ.clone()))),
+            _ => None,
+        };
+        let ((mut props, left_tail), (right_props, right_tail)) =
+            match (split(&left), split(&right)) {
+                (Some(left), Some(right)) => (left, right),
+                // Reported as a record which is not `Addable`
+                _ => return false,
+            };
+        let tail = match (left_tail, right_tail) {
+            (Some(_), Some(_)) => {
+                infer.error(self.loc.clone(), ErrorKind::UnknownRecordConcatenation);
+                self.typ = MonoType::Error;
+                return true;
+            }
+            (Some(tail), None) => {
+                infer.constrain(Kind::Record, &tail, &self.loc);
+                Some(tail)
+            }
+            (None, Some(tail)) => {
+                infer.constrain(Kind::Record, &tail, &self.loc);
+                if let MonoType::Var(var) = &tail {
+                    for prop in &props {
+                        infer.sub.lack(*var, prop.k.clone());
+                    }
+                    if !props.is_empty() {
+                        infer.sub.monomorphic(tail.clone());
+                    }
+                }
+                Some(tail)
+            }
+            (None, None) => None,
+        };
+
+        for prop in right_props {
+            match props.iter().find(|p| p.k == prop.k) {
+                Some(existing) => {
+                    let existing = existing.v.clone();
+                    infer.equal(&existing, &prop.v, self.right.loc());
+                }
+                None => props.push(prop),
+            }
+        }
+        self.typ = match tail {
+            Some(tail) if props.is_empty() => tail,
+            tail => MonoType::from(types::Record::new(props, tail)),
+        };
+        true
+    }
+
+    // Infers `+` and `-` when one of the operands is a time or a duration. Adding a duration to a
+    // time gives a time and adding two durations gives a duration, while subtracting two times
+    // gives a duration, subtracting a duration from a time gives a time and subtracting two
+    // durations gives a duration. Every other combination is an error.
+    //
+    // An operand whose type is not known yet is given the type making the operation valid, which
+    // is a duration next to a time in an addition and the type of the other operand otherwise.
+    // Returns `false` if neither operand is a time or a duration.
+    fn infer_time_arithmetic(&mut self, infer: &mut InferState<'_, '_>) -> bool {
+        let temporal = |typ: &MonoType| match typ {
+            MonoType::Builtin(builtin @ (BuiltinType::Time | BuiltinType::Duration)) => {
+                Some(*builtin)
+            }
+            _ => None,
+        };
+        let mut left = self.left.type_of().apply(infer.sub);
+        let mut right = self.right.type_of().apply(infer.sub);
+        if temporal(&left).is_none() && temporal(&right).is_none() {
+            return false;
+        }
+
+        let add = self.operator == ast::Operator::AdditionOperator;
+        let counterpart = |known: &MonoType| {
+            if add && temporal(known) == Some(BuiltinType::Time) {
+                MonoType::DURATION
+            } else {
+                known.clone()
+            }
+        };
+        if let MonoType::Var(_) = left {
+            left = infer.equal(&counterpart(&right), &left, self.left.loc());
+        } else if let MonoType::Var(_) = right {
+            right = infer.equal(&counterpart(&left), &right, self.right.loc());
+        }
+        infer.check_null_guard(&self.left);
+        infer.check_null_guard(&self.right);
+
+        self.typ = match (temporal(&left), temporal(&right), add) {
+            (Some(BuiltinType::Time), Some(BuiltinType::Duration), true)
+            | (Some(BuiltinType::Duration), Some(BuiltinType::Time), true)
+            | (Some(BuiltinType::Time), Some(BuiltinType::Duration), false) => MonoType::TIME,
+            (Some(BuiltinType::Duration), Some(BuiltinType::Duration), _)
+            | (Some(BuiltinType::Time), Some(BuiltinType::Time), false) => MonoType::DURATION,
+            _ => {
+                infer.error(
+                    self.loc.clone(),
+                    ErrorKind::InvalidTimeArithmetic {
+                        operator: self.operator.clone(),
+                        left,
+                        right,
+                    },
+                );
+                MonoType::Error
+            }
+        };
+        true
+    }
+
     fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
         // Compute the left and right constraints.
         // Do this first so that we can return an error if one occurs.
         self.left.infer(infer)?;
         self.right.infer(infer)?;
+        if !matches!(
+            self.operator,
+            ast::Operator::RegexpMatchOperator | ast::Operator::NotRegexpMatchOperator
+        ) {
+            infer.widen_integer_literal(&mut self.left, &self.right.type_of());
+            infer.widen_integer_literal(&mut self.right, &self.left.type_of());
+        }
 
         let binop_arithmetic_constraints =
             |this: &mut BinaryExpr, infer: &mut InferState<'_, '_>, kind| {
@@ -1175,6 +1847,8 @@ impl BinaryExpr {
 
                 this.typ = infer.equal(&left, &this.right.type_of(), this.right.loc());
                 infer.constrain(kind, &left, &this.loc);
+                infer.check_null_guard(&this.left);
+                infer.check_null_guard(&this.right);
             };
         let binop_compare_constraints =
             |this: &mut BinaryExpr, infer: &mut InferState<'_, '_>, kind| {
@@ -1194,13 +1868,37 @@ impl BinaryExpr {
                     },
                 ]);
             };
+        // Without `Feature::StrictComparisons` each operand is constrained on its own, so that ints
+        // and floats can be compared with each other as the runtime allows.
+        let binop_relational_constraints =
+            |this: &mut BinaryExpr, infer: &mut InferState<'_, '_>, kinds: &[Kind]| {
+                this.typ = MonoType::BOOL;
+                let left = this.left.type_of();
+                let right = this.right.type_of();
+                if infer.config.features.contains(&Feature::StrictComparisons) {
+                    infer.equal(&left, &right, this.right.loc());
+                    for &kind in kinds {
+                        infer.constrain(kind, &left, this.left.loc());
+                    }
+                } else {
+                    for (typ, loc) in [(&left, this.left.loc()), (&right, this.right.loc())] {
+                        for &kind in kinds {
+                            infer.constrain(kind, typ, loc);
+                        }
+                    }
+                }
+            };
         match self.operator {
             // The following operators require both sides to be equal.
             ast::Operator::AdditionOperator => {
-                binop_arithmetic_constraints(self, infer, Kind::Addable)
+                if !self.infer_record_concatenation(infer) && !self.infer_time_arithmetic(infer) {
+                    binop_arithmetic_constraints(self, infer, Kind::Addable)
+                }
             }
             ast::Operator::SubtractionOperator => {
-                binop_arithmetic_constraints(self, infer, Kind::Subtractable)
+                if !self.infer_time_arithmetic(infer) {
+                    binop_arithmetic_constraints(self, infer, Kind::Subtractable)
+                }
             }
             ast::Operator::MultiplicationOperator
             | ast::Operator::DivisionOperator
@@ -1208,38 +1906,18 @@ impl BinaryExpr {
             | ast::Operator::ModuloOperator => {
                 binop_arithmetic_constraints(self, infer, Kind::Divisible)
             }
+            ast::Operator::ExponentiationOperator => {
+                binop_arithmetic_constraints(self, infer, Kind::Numeric)
+            }
+            // Relational operators require both sides to be comparable.
             ast::Operator::GreaterThanOperator | ast::Operator::LessThanOperator => {
-                binop_compare_constraints(self, infer, Kind::Comparable)
+                binop_relational_constraints(self, infer, &[Kind::Comparable])
             }
             ast::Operator::EqualOperator | ast::Operator::NotEqualOperator => {
                 binop_compare_constraints(self, infer, Kind::Equatable)
             }
             ast::Operator::GreaterThanEqualOperator | ast::Operator::LessThanEqualOperator => {
-                self.typ = MonoType::BOOL;
-                infer.solve(&[
-                    // https://github.com/influxdata/flux/issues/2393
-                    // Constraint::Equal{self.left.type_of(), self.right.type_of()),
-                    Constraint::Kind {
-                        act: self.left.type_of(),
-                        exp: Kind::Equatable,
-                        loc: self.left.loc().clone(),
-                    },
-                    Constraint::Kind {
-                        act: self.left.type_of(),
-                        exp: Kind::Comparable,
-                        loc: self.left.loc().clone(),
-                    },
-                    Constraint::Kind {
-                        act: self.right.type_of(),
-                        exp: Kind::Equatable,
-                        loc: self.right.loc().clone(),
-                    },
-                    Constraint::Kind {
-                        act: self.right.type_of(),
-                        exp: Kind::Comparable,
-                        loc: self.right.loc().clone(),
-                    },
-                ]);
+                binop_relational_constraints(self, infer, &[Kind::Equatable, Kind::Comparable])
             }
             // Regular expression operators.
             ast::Operator::RegexpMatchOperator | ast::Operator::NotRegexpMatchOperator => {
@@ -1257,6 +1935,14 @@ impl BinaryExpr {
                     },
                 ]);
             }
+            // The fallback operand must have the same type as the value it replaces.
+            ast::Operator::NullCoalescingOperator => {
+                self.typ = infer.equal(
+                    &self.left.type_of(),
+                    &self.right.type_of(),
+                    self.right.loc(),
+                );
+            }
             _ => {
                 infer.error(
                     self.loc.clone(),
@@ -1289,8 +1975,132 @@ pub struct CallExpr {
 }
 
 impl CallExpr {
+    // Reports the literal labels which are passed as a column of the input stream of the callee but
+    // are missing from the closed record type of the stream, see `Feature::ExhaustiveLabelCheck`.
+    // Returns the missing labels.
+    fn check_label_arguments(&self, infer: &mut InferState<'_, '_>) -> Vec<String> {
+        fn record_of(typ: &MonoType) -> Option<&types::Record> {
+            match typ {
+                MonoType::Collection(c)
+                    if matches!(
+                        c.collection,
+                        types::CollectionType::Array | types::CollectionType::Stream
+                    ) =>
+                {
+                    match &c.arg {
+                        MonoType::Record(record) => Some(record),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+
+        let mut missing = Vec::new();
+        let func = match self.callee.type_of().apply(infer.sub) {
+            MonoType::Fun(func) => func,
+            _ => return missing,
+        };
+        let param = match &func.pipe {
+            Some(param) => param,
+            None => return missing,
+        };
+        // The stream is either piped or passed by the name of the pipe parameter
+        let stream = match self.pipe.as_ref().or_else(|| {
+            self.arguments
+                .iter()
+                .find(|arg| arg.key.name.name() == param.k)
+                .map(|arg| &arg.value)
+        }) {
+            Some(stream) => stream.type_of().apply(infer.sub),
+            None => return missing,
+        };
+
+        // Only the variable labels of the stream's records name columns which must exist
+        let columns: Vec<Tvar> = match record_of(&param.v) {
+            Some(record) => record
+                .fields()
+                .filter_map(|prop| match prop.k {
+                    RecordLabel::Variable(tv) => Some(tv),
+                    _ => None,
+                })
+                .collect(),
+            None => return missing,
+        };
+        let labels: Vec<String> = match record_of(&stream) {
+            Some(record) => {
+                let mut fields = record.fields();
+                let labels = (&mut fields).map(|prop| prop.k.to_string()).collect();
+                // The stream may have any other column if its record is open
+                if fields.tail().is_some() {
+                    return missing;
+                }
+                labels
+            }
+            None => return missing,
+        };
+
+        for arg in &self.arguments {
+            let name = arg.key.name.name();
+            let is_column = match func.req.get(name).or_else(|| func.opt.get(name)) {
+                Some(MonoType::Var(tv)) => columns.contains(tv),
+                _ => false,
+            };
+            if !is_column {
+                continue;
+            }
+            if let MonoType::Label(label) = arg.value.type_of().apply(infer.sub) {
+                let label = label.to_string();
+                if !labels.contains(&label) {
+                    let suggestion = types::closest_label(&label, &labels);
+                    infer.error(
+                        arg.value.loc().clone(),
+                        ErrorKind::Inference(types::Error::MissingLabel {
+                            label: label.clone(),
+                            suggestion,
+                        }),
+                    );
+                    missing.push(label);
+                }
+            }
+        }
+        missing
+    }
+
+    // Moves the piped value into the argument marked with the `_` placeholder, see
+    // `Feature::PipeInMiddle`. Misplaced placeholders are reported and replaced by errors.
+    fn bind_pipe_placeholder(&mut self, infer: &mut InferState<'_, '_>) {
+        let mut placeholders = self
+            .arguments
+            .iter_mut()
+            .filter_map(|arg| match &arg.value {
+                Expression::Identifier(id) if id.name.name() == "_" => Some(&mut arg.value),
+                _ => None,
+            });
+        let first = match placeholders.next() {
+            Some(first) => first,
+            None => return,
+        };
+        for extra in placeholders {
+            let loc = extra.loc().clone();
+            infer.error(loc.clone(), ErrorKind::MultiplePipePlaceholders);
+            *extra = Expression::Error(loc);
+        }
+        match self.pipe.take() {
+            Some(pipe) => *first = pipe,
+            None => {
+                let loc = first.loc().clone();
+                infer.error(loc.clone(), ErrorKind::PipePlaceholderWithoutPipe);
+                *first = Expression::Error(loc);
+            }
+        }
+    }
+
     fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
         self.typ = MonoType::Var(infer.sub.fresh());
+        if infer.config.features.contains(&Feature::PipeInMiddle) {
+            self.bind_pipe_placeholder(infer);
+        }
         // First, recursively infer every type of the children of this call expression,
         // update the environment and the constraints, and use the inferred types to
         // build the fields of the type for this call expression.
@@ -1304,6 +2114,11 @@ impl CallExpr {
         } in &mut self.arguments
         {
             expr.infer(infer)?;
+            if let Expression::Integer(_) = expr {
+                if let Some(param) = self.callee.type_of().apply(infer.sub).parameter(&id.name) {
+                    infer.widen_integer_literal(expr, param);
+                }
+            }
             // Every argument is required in a function call.
             req.insert(id.name.to_string(), (expr.type_of(), expr.loc()));
         }
@@ -1322,6 +2137,16 @@ impl CallExpr {
             retn: (self.typ.clone(), &self.loc),
         };
 
+        let missing_labels = if infer
+            .config
+            .features
+            .contains(&Feature::ExhaustiveLabelCheck)
+        {
+            self.check_label_arguments(infer)
+        } else {
+            Vec::new()
+        };
+
         match &*self.callee.type_of().apply_cow(infer.sub) {
             MonoType::Fun(func) => {
                 if let Err(err) = func.try_subsume_with(&act, infer.sub, |error| Located {
@@ -1334,7 +2159,22 @@ impl CallExpr {
                         act.map(|(typ, _)| typ),
                         err
                     );
-                    infer.errors.extend(err.into_iter().map(Error::from));
+                    let mut err: Vec<_> = err.into_iter().collect();
+                    if infer.config.features.contains(&Feature::LabelPolymorphism) {
+                        explain_label_arguments(&mut err);
+                    }
+                    // Missing labels have already been reported at the argument providing them
+                    infer.errors.extend(
+                        err.into_iter()
+                            .filter(|err| {
+                                !matches!(
+                                    &err.error,
+                                    types::Error::MissingLabel { label, .. }
+                                        if missing_labels.contains(label)
+                                )
+                            })
+                            .map(Error::from),
+                    );
                 }
             }
             callee => {
@@ -1379,7 +2219,12 @@ impl ConditionalExpr {
         self.test.infer(infer)?;
         infer.equal(&MonoType::BOOL, &self.test.type_of(), self.test.loc());
 
-        self.consequent.infer(infer)?;
+        // Properties checked with `exists` in the test may be used freely in the consequent.
+        let guards = infer.null_guards.len();
+        collect_null_guards(&self.test, &mut infer.null_guards);
+        let consequent = self.consequent.infer(infer);
+        infer.null_guards.truncate(guards);
+        consequent?;
         self.alternate.infer(infer)?;
 
         self.typ = infer.equal(
@@ -1443,6 +2288,8 @@ pub struct MemberExpr {
 
     pub object: Expression,
     pub property: Symbol,
+    /// `true` for a guarded access, `r?.a`, which allows `r` to not have the property.
+    pub optional: bool,
 }
 
 impl MemberExpr {
@@ -1450,7 +2297,11 @@ impl MemberExpr {
     //
     //     type_of(r) = {a: type_of(r.a) | 'r}
     //
-    // where 'r is a fresh type variable.
+    // where 'r is a fresh type variable. A guarded access such as `r?.a` instead
+    // produces `type_of(r) = {?a: type_of(r?.a) | 'r}`, which a record without
+    // `a` also satisfies. `r?.a` is then null and its type is only determined by
+    // how it is used, it is not generalized, and accesses chained on it, `r?.a?.b`,
+    // are guarded the same way.
     //
     fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
         self.object.infer(infer)?;
@@ -1458,6 +2309,15 @@ impl MemberExpr {
 
         if let Expression::Identifier(object) = &self.object {
             if let Some(package_name) = infer.imports.get(&object.name) {
+                if infer.importer.is_private(package_name, &self.property) {
+                    let error = ErrorKind::PrivateMember {
+                        name: self.property.to_string(),
+                        package: package_name.clone(),
+                    };
+                    infer.error(self.loc.clone(), error);
+                    self.typ = MonoType::Error;
+                    return Ok(());
+                }
                 if let Some(property) = infer.importer.symbol(package_name, &self.property) {
                     self.property = property;
                 }
@@ -1471,7 +2331,11 @@ impl MemberExpr {
                 v: self.typ.to_owned(),
             };
             let tail = MonoType::Var(infer.sub.fresh());
-            MonoType::from(types::Record::Extension { head, tail })
+            MonoType::from(types::Record::Extension {
+                head,
+                tail,
+                optional: self.optional,
+            })
         };
 
         infer.solve(&[Constraint::Equal {
@@ -1505,20 +2369,23 @@ impl IndexExpr {
         self.array.infer(infer)?;
         self.index.infer(infer)?;
 
-        self.typ = MonoType::Var(infer.sub.fresh());
+        infer.equal(&MonoType::INT, &self.index.type_of(), self.index.loc());
 
-        infer.solve(&[
-            Constraint::Equal {
-                act: self.index.type_of(),
-                exp: MonoType::INT,
-                loc: self.index.loc().clone(),
-            },
-            Constraint::Equal {
-                act: self.array.type_of(),
-                exp: MonoType::arr(self.typ.clone()),
-                loc: self.array.loc().clone(),
-            },
-        ]);
+        // Indexing a string returns the character at that index and indexing bytes returns
+        // the byte, anything else is indexed as an array.
+        self.typ = match self.array.type_of().apply(infer.sub) {
+            MonoType::Builtin(BuiltinType::String) => MonoType::STRING,
+            MonoType::Builtin(BuiltinType::Bytes) => MonoType::INT,
+            _ => {
+                let typ = MonoType::Var(infer.sub.fresh());
+                infer.equal(
+                    &MonoType::arr(typ.clone()),
+                    &self.array.type_of(),
+                    self.array.loc(),
+                );
+                typ
+            }
+        };
         Ok(())
     }
     fn apply(mut self, sub: &dyn Substituter) -> Self {
@@ -1529,6 +2396,343 @@ impl IndexExpr {
     }
 }
 
+#[derive(Derivative)]
+#[derivative(Debug, PartialEq, Clone)]
+#[allow(missing_docs)]
+pub struct TupleExpr {
+    pub loc: ast::SourceLocation,
+    #[derivative(PartialEq = "ignore")]
+    pub typ: MonoType,
+
+    pub elements: Vec<Expression>,
+}
+
+impl TupleExpr {
+    fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
+        for el in &mut self.elements {
+            el.infer(infer)?;
+        }
+        self.typ = MonoType::from(types::Tuple {
+            elements: self.elements.iter().map(|el| el.type_of()).collect(),
+        });
+        Ok(())
+    }
+    fn apply(mut self, sub: &dyn Substituter) -> Self {
+        self.typ = self.typ.apply(sub);
+        self.elements = self
+            .elements
+            .into_iter()
+            .map(|element| element.apply(sub))
+            .collect();
+        self
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug, PartialEq, Clone)]
+#[allow(missing_docs)]
+pub struct TupleIndexExpr {
+    pub loc: ast::SourceLocation,
+    #[derivative(PartialEq = "ignore")]
+    pub typ: MonoType,
+
+    pub tuple: Expression,
+    pub index: usize,
+}
+
+impl TupleIndexExpr {
+    // Unlike records, tuples have no notion of extension so the indexed expression must
+    // already have a known tuple type at this point. A tuple whose type is still a variable, such
+    // as a function parameter, can not be indexed since its arity is unknown.
+    fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
+        self.tuple.infer(infer)?;
+        let t = self.tuple.type_of().apply(infer.sub);
+
+        self.typ = match &t {
+            MonoType::Tuple(tuple) => match tuple.get(self.index) {
+                Some(element) => element.clone(),
+                None => {
+                    infer.error(
+                        self.loc.clone(),
+                        ErrorKind::TupleIndexOutOfRange {
+                            index: self.index,
+                            len: tuple.elements.len(),
+                        },
+                    );
+                    MonoType::Error
+                }
+            },
+            MonoType::Error => MonoType::Error,
+            _ => {
+                infer.error(self.tuple.loc().clone(), ErrorKind::NotATuple(t.clone()));
+                MonoType::Error
+            }
+        };
+        Ok(())
+    }
+    fn apply(mut self, sub: &dyn Substituter) -> Self {
+        self.typ = self.typ.apply(sub);
+        self.tuple = self.tuple.apply(sub);
+        self
+    }
+}
+
+/// A block expression, `{ a = 1; a + 1 }`, evaluates its statements in a scope of their own and
+/// has the value of its trailing expression, which is the `ReturnStmt` terminating its body.
+#[derive(Derivative)]
+#[derivative(Debug, PartialEq, Clone)]
+#[allow(missing_docs)]
+pub struct BlockExpr {
+    pub loc: ast::SourceLocation,
+    #[derivative(PartialEq = "ignore")]
+    pub typ: MonoType,
+
+    pub body: Block,
+}
+
+impl BlockExpr {
+    fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
+        if !infer.config.features.contains(&Feature::BlockExpressions) {
+            infer.error(self.loc.clone(), ErrorKind::BlockExpressionsDisabled);
+        }
+        infer.env.enter_scope();
+        self.body.infer(infer)?;
+        infer.env.exit_scope();
+        self.typ = self.body.type_of();
+        Ok(())
+    }
+    fn apply(mut self, sub: &dyn Substituter) -> Self {
+        self.typ = self.typ.apply(sub);
+        self.body = self.body.apply(sub);
+        self
+    }
+}
+
+/// A match expression, `match x { 1 => "one", _ => "many" }`, has the value of the body of the
+/// first arm whose pattern is equal to the scrutinee.
+#[derive(Derivative)]
+#[derivative(Debug, PartialEq, Clone)]
+#[allow(missing_docs)]
+pub struct MatchExpr {
+    pub loc: ast::SourceLocation,
+    #[derivative(PartialEq = "ignore")]
+    pub typ: MonoType,
+
+    pub scrutinee: Expression,
+    pub arms: Vec<MatchArm>,
+}
+
+/// An arm of a [`MatchExpr`].
+#[derive(Derivative)]
+#[derivative(Debug, PartialEq, Clone)]
+#[allow(missing_docs)]
+pub struct MatchArm {
+    pub loc: ast::SourceLocation,
+    /// The literal compared with the scrutinee, `None` for the wildcard `_`.
+    pub pattern: Option<Expression>,
+    pub body: Expression,
+}
+
+impl MatchExpr {
+    fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
+        if !infer.config.features.contains(&Feature::PatternMatching) {
+            infer.error(self.loc.clone(), ErrorKind::PatternMatchingDisabled);
+        }
+        self.scrutinee.infer(infer)?;
+        let scrutinee = self.scrutinee.type_of();
+        let mut typ = None;
+        for arm in &mut self.arms {
+            if let Some(pattern) = &mut arm.pattern {
+                pattern.infer(infer)?;
+                infer.equal(&scrutinee, &pattern.type_of(), pattern.loc());
+            }
+            arm.body.infer(infer)?;
+            typ = Some(match typ {
+                None => arm.body.type_of(),
+                Some(typ) => infer.equal(&typ, &arm.body.type_of(), arm.body.loc()),
+            });
+        }
+        self.typ = typ.unwrap_or_else(|| MonoType::Var(infer.sub.fresh()));
+        Ok(())
+    }
+    fn apply(mut self, sub: &dyn Substituter) -> Self {
+        self.typ = self.typ.apply(sub);
+        self.scrutinee = self.scrutinee.apply(sub);
+        self.arms = self
+            .arms
+            .into_iter()
+            .map(|arm| MatchArm {
+                loc: arm.loc,
+                pattern: arm.pattern.map(|pattern| pattern.apply(sub)),
+                body: arm.body.apply(sub),
+            })
+            .collect();
+        self
+    }
+
+    // Returns an error if the scrutinee is not a bool and none of the arms is a wildcard.
+    fn check_wildcard(&self, sub: &dyn Substituter) -> Option<Error> {
+        let scrutinee = self.scrutinee.type_of().apply(sub);
+        if scrutinee == MonoType::BOOL || self.arms.iter().any(|arm| arm.pattern.is_none()) {
+            return None;
+        }
+        Some(located(
+            self.loc.clone(),
+            ErrorKind::MatchWithoutWildcard(scrutinee),
+        ))
+    }
+
+    // Returns a warning if the scrutinee is a bool and one of `true` or `false` is matched by
+    // none of the arms.
+    fn check_exhaustiveness(&self) -> Option<Error> {
+        if self.scrutinee.type_of() != MonoType::BOOL {
+            return None;
+        }
+        let mut matched = (false, false);
+        for arm in &self.arms {
+            match &arm.pattern {
+                None => return None,
+                Some(Expression::Identifier(id)) if id.name.name() == "true" => matched.0 = true,
+                Some(Expression::Identifier(id)) if id.name.name() == "false" => matched.1 = true,
+                Some(_) => (),
+            }
+        }
+        let missing = match matched {
+            (false, _) => true,
+            (true, false) => false,
+            (true, true) => return None,
+        };
+        Some(located(
+            self.loc.clone(),
+            ErrorKind::NonExhaustiveMatch(missing),
+        ))
+    }
+}
+
+/// Returns an error for every match expression on a type other than bool which has no wildcard
+/// arm, as the values of those types cannot all be listed. The types of the scrutinees are
+/// resolved with `sub`, so the types need not have been injected into `pkg`.
+pub(crate) fn check_match_wildcards(pkg: &Package, sub: &dyn Substituter) -> Errors<Error> {
+    let mut errors = Errors::new();
+    walk::walk(
+        &mut |node: walk::Node| {
+            if let walk::Node::MatchExpr(expr) = node {
+                errors.extend(expr.check_wildcard(sub));
+            }
+        },
+        walk::Node::Package(pkg),
+    );
+    errors
+}
+
+/// Returns a warning for every match expression on a bool which has no arm for one of `true` and
+/// `false`. Matches on other types must have a wildcard arm, see [`check_match_wildcards`].
+pub(crate) fn check_match_exhaustiveness(pkg: &Package) -> Errors<Error> {
+    let mut warnings = Errors::new();
+    walk::walk(
+        &mut |node: walk::Node| {
+            if let walk::Node::MatchExpr(expr) = node {
+                warnings.extend(expr.check_exhaustiveness());
+            }
+        },
+        walk::Node::Package(pkg),
+    );
+    warnings
+}
+
+/// Returns a warning for every function which declares a pipe parameter, such as `tables=<-`, but
+/// never refers to it, so the tables piped into the function are ignored. The warning is located
+/// at the pipe parameter.
+pub(crate) fn check_unused_pipe_parameters(pkg: &Package) -> Errors<Error> {
+    let mut warnings = Errors::new();
+    walk::walk(
+        &mut |node: walk::Node| {
+            if let walk::Node::FunctionExpr(func) = node {
+                if let Some(pipe) = func.pipe() {
+                    let mut used = false;
+                    walk::walk(
+                        &mut |node: walk::Node| {
+                            if let walk::Node::IdentifierExpr(id) = node {
+                                used |= id.name == pipe.key.name;
+                            }
+                        },
+                        walk::Node::FunctionExpr(func),
+                    );
+                    if !used {
+                        warnings.push(located(
+                            pipe.loc.clone(),
+                            ErrorKind::UnusedPipeParameter(pipe.key.name.to_string()),
+                        ));
+                    }
+                }
+            }
+        },
+        walk::Node::Package(pkg),
+    );
+    warnings
+}
+
+/// Returns a warning for every type variable of a top-level binding which is constrained by kinds
+/// but left undetermined, making the type of the value ambiguous. Such a variable either occurs
+/// only in the types within the value, never in the type of the binding, or is the label of a
+/// value which is not a function, as when the label returned by a label polymorphic function is
+/// never given.
+pub(crate) fn check_ambiguous_types(pkg: &Package, sub: &mut Substitution) -> Errors<Error> {
+    let mut warnings = Errors::new();
+    for file in &pkg.files {
+        for stmt in &file.body {
+            let assgn = match stmt {
+                Statement::Variable(assgn) => assgn,
+                _ => continue,
+            };
+            let mut ambiguous = Vec::new();
+
+            // Every type variable of the binding has been generalized, the variables left
+            // within the value are the ones which did not make it into the type of the binding.
+            let mut vars = std::collections::BTreeSet::new();
+            walk::walk(
+                &mut |node: walk::Node| {
+                    if let walk::Node::IdentifierExpr(id) = node {
+                        vars.extend(id.typ.free_vars());
+                    }
+                },
+                walk::Node::from_expr(&assgn.init),
+            );
+            let roots: std::collections::BTreeSet<_> =
+                vars.into_iter().map(|var| sub.root(var)).collect();
+            for root in roots {
+                if let Some(kinds) = sub.cons().get(&root) {
+                    ambiguous.push(kinds.clone());
+                }
+            }
+
+            let typ = assgn.poly_type_of();
+            if !matches!(typ.expr, MonoType::Fun(_)) {
+                for kinds in typ.cons.values() {
+                    if kinds.contains(&Kind::Label) {
+                        ambiguous.push(kinds.clone());
+                    }
+                }
+            }
+
+            for kinds in ambiguous {
+                warnings.push(located(
+                    assgn.loc.clone(),
+                    ErrorKind::AmbiguousType {
+                        name: assgn.id.name.to_string(),
+                        kinds: kinds
+                            .iter()
+                            .map(|kind| kind.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" + "),
+                    },
+                ));
+            }
+        }
+    }
+    warnings
+}
+
 #[derive(Derivative)]
 #[derivative(Debug, PartialEq, Clone)]
 #[allow(missing_docs)]
@@ -1539,10 +2743,27 @@ pub struct ObjectExpr {
 
     pub with: Option<IdentifierExpr>,
     pub properties: Vec<Property>,
+    /// The records spread into the object after its first entry, `{a: 1, ...r}`. They are
+    /// replaced by properties reading their fields during type inference, once those are known.
+    pub spreads: Vec<Spread>,
+}
+
+/// A record spread into an object expression, whose fields are added to the object in place of
+/// the spread.
+#[derive(Debug, PartialEq, Clone)]
+#[allow(missing_docs)]
+pub struct Spread {
+    pub loc: ast::SourceLocation,
+    /// The number of properties of the object which precede the spread.
+    pub index: usize,
+    pub argument: IdentifierExpr,
 }
 
 impl ObjectExpr {
     fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
+        if !self.spreads.is_empty() {
+            self.expand_spreads(infer)?;
+        }
         // If record extension, infer constraints for base
         let mut r = match &mut self.with {
             Some(expr) => {
@@ -1551,6 +2772,16 @@ impl ObjectExpr {
             }
             None => MonoType::from(types::Record::Empty),
         };
+        // Properties which the base is known to have are updated, replacing their type, instead
+        // of shadowing them
+        if self.with.is_some() {
+            r = r.apply(infer.sub);
+            for prop in &self.properties {
+                if let Some(updated) = remove_field(&r, &RecordLabel::from(prop.key.name.clone())) {
+                    r = updated;
+                }
+            }
+        }
         // Infer constraints for properties
         for prop in self.properties.iter_mut().rev() {
             prop.value.infer(infer)?;
@@ -1560,11 +2791,75 @@ impl ObjectExpr {
                     v: prop.value.type_of(),
                 },
                 tail: r,
+                optional: false,
             });
         }
         self.typ = r;
         Ok(())
     }
+    // Replaces the spreads of the object by a property `k: r.k` for each field `k` of the spread
+    // record `r`. Entries override the properties of the same name which precede them.
+    fn expand_spreads(&mut self, infer: &mut InferState<'_, '_>) -> Result {
+        let mut properties = Vec::new();
+        let mut rest = std::mem::take(&mut self.properties).into_iter();
+        let mut taken = 0;
+        for spread in std::mem::take(&mut self.spreads) {
+            properties.extend((&mut rest).take(spread.index - taken));
+            taken = spread.index;
+
+            let mut argument = spread.argument;
+            argument.infer(infer)?;
+            let typ = argument.typ.apply(infer.sub);
+            let labels = match &typ {
+                MonoType::Record(record) => {
+                    let mut fields = record.fields();
+                    let labels: Option<Vec<Label>> = (&mut fields)
+                        .map(|field| match &field.k {
+                            RecordLabel::Concrete(label) => Some(label.clone()),
+                            _ => None,
+                        })
+                        .collect();
+                    labels.filter(|_| fields.tail().is_none())
+                }
+                _ => None,
+            };
+            let labels = match labels {
+                Some(labels) => labels,
+                None => {
+                    infer.error(spread.loc.clone(), ErrorKind::UnknownSpreadFields(typ));
+                    continue;
+                }
+            };
+            for label in labels {
+                let name = Symbol::from(label);
+                properties.push(Property {
+                    loc: spread.loc.clone(),
+                    key: Identifier {
+                        loc: spread.loc.clone(),
+                        name: name.clone(),
+                    },
+                    value: Expression::Member(Box::new(MemberExpr {
+                        loc: spread.loc.clone(),
+                        typ: MonoType::Error,
+                        object: Expression::Identifier(argument.clone()),
+                        property: name,
+                        optional: false,
+                    })),
+                });
+            }
+        }
+        properties.extend(rest);
+
+        let mut seen = HashSet::new();
+        let mut properties: Vec<Property> = properties
+            .into_iter()
+            .rev()
+            .filter(|prop| seen.insert(prop.key.name.name().to_owned()))
+            .collect();
+        properties.reverse();
+        self.properties = properties;
+        Ok(())
+    }
     fn apply(mut self, sub: &dyn Substituter) -> Self {
         self.typ = self.typ.apply(sub);
         if let Some(e) = self.with {
@@ -1579,6 +2874,61 @@ impl ObjectExpr {
     }
 }
 
+// Returns the record `typ` without its first field labeled `label`, or `None` if `label` is not
+// one of the fields of `typ` which are known.
+fn remove_field(typ: &MonoType, label: &RecordLabel) -> Option<MonoType> {
+    match typ {
+        MonoType::Record(record) => match &**record {
+            types::Record::Extension {
+                head,
+                tail,
+                optional,
+            } => {
+                if head.k == *label {
+                    Some(tail.clone())
+                } else {
+                    remove_field(tail, label).map(|tail| {
+                        MonoType::from(types::Record::Extension {
+                            head: head.clone(),
+                            tail,
+                            optional: *optional,
+                        })
+                    })
+                }
+            }
+            types::Record::Empty => None,
+        },
+        _ => None,
+    }
+}
+
+// Rewrites the errors of a call whose arguments are not labels where the callee expects one.
+// Strings which are not literals, such as `"a" + "b"`, have a value that is only known at
+// runtime so they are told apart from arguments of another type. The records of the callee then
+// fail to unify because of the same arguments, which is not reported again.
+fn explain_label_arguments(errors: &mut Vec<Located<types::Error>>) {
+    // The types of the label arguments, which are the types the labels of the records resolve to
+    let mut label_arguments = Vec::new();
+    for err in errors.iter_mut() {
+        if let types::Error::CannotUnifyArgument(_, cause) = &mut err.error {
+            if let types::Error::CannotConstrain {
+                exp: Kind::Label,
+                act,
+            } = &**cause
+            {
+                label_arguments.push(act.clone());
+                if *act == MonoType::STRING {
+                    **cause = types::Error::DynamicLabel;
+                }
+            }
+        }
+    }
+    errors.retain(|err| match &err.error {
+        types::Error::NotALabel(typ) => !label_arguments.contains(typ),
+        _ => true,
+    });
+}
+
 #[derive(Derivative)]
 #[derivative(Debug, PartialEq, Clone)]
 #[allow(missing_docs)]
@@ -1613,6 +2963,7 @@ impl UnaryExpr {
                     exp: Kind::Negatable,
                     loc: self.argument.loc().clone(),
                 }]);
+                infer.check_null_guard(&self.argument);
             }
             _ => {
                 infer.error(
@@ -1659,9 +3010,7 @@ pub struct IdentifierExpr {
 
 impl IdentifierExpr {
     fn infer(&mut self, infer: &mut InferState<'_, '_>) -> Result {
-        let poly = infer.lookup(&self.loc, &self.name);
-
-        let (t, cons) = infer::instantiate(poly, infer.sub, self.loc.clone());
+        let (t, cons) = infer.instantiate(&self.loc, &self.name);
         infer.solve(&cons);
         self.typ = t;
         Ok(())
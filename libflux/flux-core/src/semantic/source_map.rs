@@ -0,0 +1,88 @@
+//! Mapping the nodes synthesized while converting the AST back to the source they come from.
+//!
+//! Some constructs of the AST have no semantic node of their own and are desugared into other
+//! nodes, such as `a |> f()` into a call of `f` located at `f()`. Errors about the synthesized
+//! nodes are located at them, a [`SourceMap`] tells which construct of the source they belong to.
+//! Nodes which are located at the whole construct, such as the conditional expression of
+//! `t ? a : b`, already point at it and are not recorded.
+
+use crate::ast::SourceLocation;
+
+/// A construct of the source which is desugared into other semantic nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Desugaring {
+    /// `a |> f()`, a call to `f` with `a` as its pipe argument.
+    Pipe,
+    /// `[...xs, 4]`, the concatenation of `xs` with the array of the elements which follow it.
+    ArraySpread,
+    /// `{a, b: y} = r`, one variable assignment of a member of `r` per label.
+    DestructuringAssignment,
+}
+
+/// A semantic node synthesized while desugaring a construct of the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceMapping {
+    /// The location of the synthesized node.
+    pub loc: SourceLocation,
+    /// The location of the construct the node was synthesized from.
+    pub origin: SourceLocation,
+    /// The construct the node was synthesized from.
+    pub desugaring: Desugaring,
+}
+
+/// The semantic nodes synthesized while converting a package, in the order they were
+/// synthesized. Only recorded when [`AnalyzerConfig::source_map`] is set.
+///
+/// [`AnalyzerConfig::source_map`]: crate::semantic::AnalyzerConfig::source_map
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SourceMap {
+    mappings: Vec<SourceMapping>,
+}
+
+impl SourceMap {
+    /// Records that the node located at `loc` was synthesized from the construct at `origin`.
+    /// Recording the same node twice, or a node located at its origin, has no effect.
+    pub(crate) fn insert(
+        &mut self,
+        loc: &SourceLocation,
+        origin: &SourceLocation,
+        desugaring: Desugaring,
+    ) {
+        let mapping = SourceMapping {
+            loc: loc.clone(),
+            origin: origin.clone(),
+            desugaring,
+        };
+        if loc != origin && !self.mappings.contains(&mapping) {
+            self.mappings.push(mapping);
+        }
+    }
+
+    /// Returns the mapping of the synthesized node located at `loc`, if there is one. When
+    /// several constructs synthesized a node at the same location, the innermost one is returned,
+    /// as a construct is recorded after the constructs nested in it.
+    pub fn get(&self, loc: &SourceLocation) -> Option<&SourceMapping> {
+        self.mappings.iter().find(|mapping| &mapping.loc == loc)
+    }
+
+    /// Returns the location of the construct of the source which `loc` belongs to. That is `loc`
+    /// itself unless it is the location of a synthesized node.
+    pub fn origin<'a>(&'a self, loc: &'a SourceLocation) -> &'a SourceLocation {
+        self.get(loc).map_or(loc, |mapping| &mapping.origin)
+    }
+
+    /// Returns every mapping, in the order the nodes were synthesized.
+    pub fn iter(&self) -> impl Iterator<Item = &SourceMapping> {
+        self.mappings.iter()
+    }
+
+    /// Returns the number of synthesized nodes.
+    pub fn len(&self) -> usize {
+        self.mappings.len()
+    }
+
+    /// Returns `true` if no node was synthesized.
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+}
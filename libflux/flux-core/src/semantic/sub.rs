@@ -1,7 +1,10 @@
 //! Substitutions during type inference.
 use std::{borrow::Cow, cell::RefCell, collections::BTreeMap, fmt, iter::FusedIterator};
 
-use crate::semantic::types::{union, Error, MonoType, PolyType, SubstitutionMap, Tvar, TvarKinds};
+use crate::semantic::types::{
+    union, with_tvar_naming, Error, LabelSets, MonoType, PolyType, RecordLabel, SemanticMap,
+    SubstitutionMap, Tvar, TvarKinds, TvarNames, TvarNaming,
+};
 
 use ena::unify::UnifyKey;
 
@@ -18,8 +21,26 @@ pub struct Substitution {
     // modifications can be reverted. Then replace `temporary_generalize` with
     // `snapshot(); generalize(); rollback_to()`
     cons: RefCell<TvarKinds>,
+    // Labels which the record bound to a type variable must not contain.
+    lacks: RefCell<SemanticMap<Tvar, Vec<RecordLabel>>>,
+    // Labels which a type variable of the `Label` kind may be bound to.
+    label_sets: RefCell<LabelSets>,
+    // Types whose variables must not be generalized, such as the types of optional record labels
+    // which were omitted and stand for a null value.
+    monomorphic: RefCell<Vec<MonoType>>,
+    // Names which the type parameters of instantiated polytypes were declared with, keyed by the
+    // root of the variables that replaced them.
+    names: RefCell<TvarNames>,
+    // Overrides `DEFAULT_MAX_UNIFICATION_DEPTH`
+    max_unification_depth: Option<usize>,
+    // The variable of the first key of `table`, see `Substitution::with_seed`
+    offset: u64,
 }
 
+/// How deeply nested the types being unified may be before unification fails with
+/// [`Error::TooDeeplyNested`] instead of overflowing the stack.
+pub const DEFAULT_MAX_UNIFICATION_DEPTH: usize = 1000;
+
 impl fmt::Debug for Substitution {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut roots = BTreeMap::new();
@@ -35,22 +56,39 @@ impl fmt::Debug for Substitution {
         for i in 0..table.len() as u32 {
             let i = Tvar::from_index(i);
             let root = table.find(i);
-            let root_node = roots.entry(root).or_insert_with(|| Root {
+            let root_node = roots.entry(self.var(root)).or_insert_with(|| Root {
                 variables: Vec::new(),
                 value: table.probe_value(root),
             });
             if i != root {
-                root_node.variables.push(i);
+                root_node.variables.push(self.var(i));
             }
         }
 
         f.debug_struct("Substitution")
             .field("table", &roots)
             .field("cons", &*self.cons.borrow())
+            .field("lacks", &*self.lacks.borrow())
+            .field("label_sets", &*self.label_sets.borrow())
+            .field("monomorphic", &*self.monomorphic.borrow())
+            .field("names", &*self.names.borrow())
             .finish()
     }
 }
 
+/// Displays every bound type variable on its own line, e.g. `t0 = int`.
+impl fmt::Display for Substitution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Letters would be ambiguous here as they depend on the type they are displayed in
+        with_tvar_naming(TvarNaming::Numbered, || {
+            for (var, typ) in self.iter() {
+                writeln!(f, "{} = {}", var, typ)?;
+            }
+            Ok(())
+        })
+    }
+}
+
 /// An implementation of a
 /// (Disjoint-set](https://en.wikipedia.org/wiki/Disjoint-set_data_structure) which is used to
 /// track which type variables are them same (unified) and which type they have unified to (if any)
@@ -62,9 +100,7 @@ impl From<SubstitutionMap> for Substitution {
         let sub = Substitution::default();
         for (var, typ) in values {
             // Create any variables referenced in the input map
-            while var.0 >= sub.table.borrow().len() as u64 {
-                sub.fresh();
-            }
+            sub.mk_fresh_to(var);
             sub.union_type(var, typ).unwrap();
         }
         sub
@@ -77,6 +113,16 @@ impl Substitution {
         Substitution::default()
     }
 
+    /// Returns an empty substitution whose first fresh variable is `Tvar(seed)`, as with
+    /// [`Fresher::with_seed`](crate::semantic::fresh::Fresher::with_seed). The variables before
+    /// the seed are not part of the substitution and are never bound by it.
+    pub fn with_seed(seed: u64) -> Substitution {
+        Substitution {
+            offset: seed,
+            ..Substitution::default()
+        }
+    }
+
     /// Returns true if no variables has been created by this substitution
     pub fn is_empty(&self) -> bool {
         self.len() == 0
@@ -87,12 +133,49 @@ impl Substitution {
         self.table.borrow().len()
     }
 
+    /// Makes `Tvar(seed)` the first fresh variable of an empty substitution, see
+    /// [`Substitution::with_seed`].
+    pub fn set_seed(&mut self, seed: u64) {
+        assert!(
+            self.is_empty(),
+            "the seed of a substitution must be set before it is used"
+        );
+        self.offset = seed;
+    }
+
     /// Takes a `Substitution` and returns an incremented [`Tvar`].
     pub fn fresh(&self) -> Tvar {
-        self.table.borrow_mut().new_key(None)
+        let key = self.table.borrow_mut().new_key(None);
+        self.var(key)
+    }
+
+    // The key of `var` in `table`
+    fn key(&self, var: Tvar) -> Tvar {
+        Tvar(var.0 - self.offset)
+    }
+
+    // The variable of `key` in `table`
+    fn var(&self, key: Tvar) -> Tvar {
+        Tvar(key.0 + self.offset)
     }
 
-    /// Prepares `count` type variables for testing
+    // Whether `var` has been created by this substitution
+    fn contains_var(&self, var: Tvar) -> bool {
+        var.0 >= self.offset && var.0 - self.offset < self.len() as u64
+    }
+
+    /// Sets how deeply nested the types being unified may be, see
+    /// [`DEFAULT_MAX_UNIFICATION_DEPTH`].
+    pub fn set_max_unification_depth(&mut self, depth: usize) {
+        self.max_unification_depth = Some(depth);
+    }
+
+    pub(crate) fn max_unification_depth(&self) -> usize {
+        self.max_unification_depth
+            .unwrap_or(DEFAULT_MAX_UNIFICATION_DEPTH)
+    }
+
+    /// Creates `count` unbound type variables, so that the next fresh variable comes after them
     pub(crate) fn mk_fresh(&self, count: usize) {
         let mut sub = self.table.borrow_mut();
         for _ in 0..count {
@@ -100,10 +183,93 @@ impl Substitution {
         }
     }
 
+    /// Creates the unbound type variables up to and including `var`, so that it may be bound.
+    /// Returns `false` if `var` comes before the seed of the substitution and can't be bound.
+    pub(crate) fn mk_fresh_to(&self, var: Tvar) -> bool {
+        if var.0 < self.offset {
+            return false;
+        }
+        let mut sub = self.table.borrow_mut();
+        while var.0 - self.offset >= sub.len() as u64 {
+            sub.new_key(None);
+        }
+        true
+    }
+
     pub(crate) fn cons(&mut self) -> &mut TvarKinds {
         self.cons.get_mut()
     }
 
+    /// Records that the record bound to `var` must not contain `label`.
+    pub(crate) fn lack(&self, var: Tvar, label: RecordLabel) {
+        let root = self.root(var);
+        let mut lacks = self.lacks.borrow_mut();
+        let labels = lacks.entry(root).or_default();
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+
+    /// Records that `typ` is the type of an optional record label which was omitted.
+    pub(crate) fn absent(&self, typ: MonoType) {
+        self.monomorphic(typ);
+    }
+
+    /// Records that the variables of `typ` must not be generalized.
+    pub(crate) fn monomorphic(&self, typ: MonoType) {
+        self.monomorphic.borrow_mut().push(typ);
+    }
+
+    /// Returns the variables which must not be generalized, see [`Substitution::monomorphic`].
+    pub(crate) fn monomorphic_vars(&self) -> Vec<Tvar> {
+        let monomorphic = self.monomorphic.borrow().clone();
+        monomorphic
+            .into_iter()
+            .flat_map(|typ| typ.apply(self).free_vars())
+            .collect()
+    }
+
+    /// Records that `var` replaced a type parameter declared as `name`, see [`PolyType::names`].
+    pub(crate) fn name_var(&self, var: Tvar, name: String) {
+        let root = self.root(var);
+        self.names.borrow_mut().insert(root, name);
+    }
+
+    /// Returns the declared names of the variables of `vars` keyed by what they are replaced
+    /// with. A name shared by several of the variables is left out so that they can be told apart.
+    pub(crate) fn declared_names(&self, vars: impl IntoIterator<Item = (Tvar, Tvar)>) -> TvarNames {
+        let names = self.names.borrow();
+        let mut declared = TvarNames::new();
+        let mut shared = Vec::new();
+        for (var, new_var) in vars {
+            if let Some(name) = names.get(&self.root(var)) {
+                if declared.values().any(|declared| declared == name) {
+                    shared.push(name.clone());
+                } else {
+                    declared.insert(new_var, name.clone());
+                }
+            }
+        }
+        declared.retain(|_, name| !shared.contains(name));
+        declared
+    }
+
+    /// Records that `var` may only be bound to one of `labels`.
+    pub(crate) fn restrict_labels(&self, var: Tvar, labels: Vec<String>) {
+        let root = self.root(var);
+        let mut label_sets = self.label_sets.borrow_mut();
+        let labels = match label_sets.remove(&root) {
+            Some(existing) => intersect_labels(existing, labels),
+            None => labels,
+        };
+        label_sets.insert(root, labels);
+    }
+
+    /// Returns the labels which `var` may be bound to, if it is restricted to a set of labels.
+    pub(crate) fn label_set(&self, var: Tvar) -> Option<Vec<String>> {
+        self.label_sets.borrow().get(&self.root(var)).cloned()
+    }
+
     /// Apply a substitution to a type variable.
     pub fn apply(&self, tv: Tvar) -> MonoType {
         self.try_apply(tv).unwrap_or(MonoType::Var(tv))
@@ -112,8 +278,12 @@ impl Substitution {
     /// Apply a substitution to a type variable, returning None if there is no substitution for the
     /// variable.
     pub fn try_apply(&self, tv: Tvar) -> Option<MonoType> {
+        if !self.contains_var(tv) {
+            return None;
+        }
+        let key = self.key(tv);
         let mut sub = self.table.borrow_mut();
-        match sub.probe_value(tv) {
+        match sub.probe_value(key) {
             Some(typ) => Some(typ),
             None => {
                 // If `tv` hasn't been unified with a type we still want to see if it has been
@@ -122,7 +292,7 @@ impl Substitution {
                 // may be equal if they to contain different type variables that has been unified
                 // with each other (simplifies debugging even if it isn't strictly necessary for
                 // inference itself)
-                let root = sub.find(tv);
+                let root = self.var(sub.find(key));
                 if root == tv {
                     None
                 } else {
@@ -132,10 +302,64 @@ impl Substitution {
         }
     }
 
+    /// Returns every type variable which has been bound, to a type or to another variable, along
+    /// with the type it resolves to once the whole substitution is applied.
+    ///
+    /// The types are returned by value as they live behind a `RefCell`.
+    pub fn iter(&self) -> impl Iterator<Item = (Tvar, MonoType)> + '_ {
+        (0..self.len() as u32)
+            .map(move |key| self.var(Tvar::from_index(key)))
+            .filter_map(move |tv| self.try_apply(tv).map(|typ| (tv, typ.apply(self))))
+    }
+
+    /// Composes two substitutions into one, applying the result to a type is the same as applying
+    /// `other` and then `self`. Variables bound by both are bound as in `other`.
+    ///
+    /// The variables unified with each other form groups rather than pointing in one direction, so
+    /// `self` should not refer to the variables bound by `other`, as is the case when `self` was
+    /// built after `other` had already been applied. Kind constraints of the variables which
+    /// remain unbound are kept.
+    pub fn compose(self, other: Substitution) -> Substitution {
+        let mut values: SubstitutionMap = other
+            .iter()
+            .map(|(var, typ)| (var, typ.apply(&self)))
+            .collect();
+        for (var, typ) in self.iter() {
+            values.entry(var).or_insert(typ);
+        }
+
+        let mut sub = Substitution::with_seed(self.offset.min(other.offset));
+        for (var, typ) in values {
+            sub.mk_fresh_to(var);
+            sub.union_type(var, typ).unwrap();
+        }
+        for last in [
+            self.len() as u64 + self.offset,
+            other.len() as u64 + other.offset,
+        ] {
+            if last > sub.offset {
+                sub.mk_fresh_to(Tvar(last - 1));
+            }
+        }
+        for cons in [other.cons.into_inner(), self.cons.into_inner()] {
+            for (var, kinds) in cons {
+                if sub.try_apply(var).is_none() {
+                    let existing = sub.cons().remove(&var).unwrap_or_default();
+                    sub.cons().insert(var, union(existing, kinds));
+                }
+            }
+        }
+        sub
+    }
+
     /// Returns the "root variable" which is the variable that uniquely identifies a group of
     /// variables that were unified
     pub fn root(&self, tv: Tvar) -> Tvar {
-        self.table.borrow_mut().find(tv)
+        if !self.contains_var(tv) {
+            return tv;
+        }
+        let root = self.table.borrow_mut().find(self.key(tv));
+        self.var(root)
     }
 
     /// Unifies as a `Tvar` and a `MonoType`, recording the result in the substitution for later
@@ -144,7 +368,9 @@ impl Substitution {
         match typ {
             MonoType::Var(r) => self.union(var, r),
             _ => {
-                self.table.borrow_mut().union_value(var, Some(typ.clone()));
+                let key = self.key(var);
+                self.table.borrow_mut().union_value(key, Some(typ.clone()));
+                self.check_lacks(var, &typ)?;
 
                 let mut cons = self.cons.borrow_mut();
                 if let Some(kinds) = cons.remove(&var) {
@@ -158,6 +384,23 @@ impl Substitution {
                         cons.insert(var, kinds);
                     }
                 }
+                drop(cons);
+
+                let mut label_sets = self.label_sets.borrow_mut();
+                if let Some(labels) = label_sets.remove(&var) {
+                    match &typ {
+                        MonoType::Label(label) if !labels.iter().any(|l| **l == **label) => {
+                            return Err(Error::LabelNotInSet {
+                                label: label.to_string(),
+                                allowed: labels,
+                            });
+                        }
+                        MonoType::BoundVar(_) => {
+                            label_sets.insert(var, labels);
+                        }
+                        _ => (),
+                    }
+                }
             }
         }
         Ok(())
@@ -165,7 +408,8 @@ impl Substitution {
 
     /// Unifies two `Tvar`s, recording the result in the substitution for later.
     pub fn union(&self, l: Tvar, r: Tvar) {
-        self.table.borrow_mut().union(l, r);
+        let (l_key, r_key) = (self.key(l), self.key(r));
+        self.table.borrow_mut().union(l_key, r_key);
 
         let mut cons = self.cons.borrow_mut();
         // Kind constraints for both type variables
@@ -177,9 +421,69 @@ impl Substitution {
             let root = self.root(l);
             cons.insert(root, kinds);
         }
+
+        let mut lacks = self.lacks.borrow_mut();
+        let labels = union(
+            lacks.remove(&l).unwrap_or_default(),
+            lacks.remove(&r).unwrap_or_default(),
+        );
+        if !labels.is_empty() {
+            lacks.insert(self.root(l), labels);
+        }
+        drop(lacks);
+
+        let mut label_sets = self.label_sets.borrow_mut();
+        let labels = match (label_sets.remove(&l), label_sets.remove(&r)) {
+            (Some(l_labels), Some(r_labels)) => Some(intersect_labels(l_labels, r_labels)),
+            (Some(labels), None) | (None, Some(labels)) => Some(labels),
+            (None, None) => None,
+        };
+        if let Some(labels) = labels {
+            label_sets.insert(self.root(l), labels);
+        }
+        drop(label_sets);
+
+        let mut names = self.names.borrow_mut();
+        let (l_name, r_name) = (names.remove(&l), names.remove(&r));
+        if let Some(name) = l_name.or(r_name) {
+            names.insert(self.root(l), name);
+        }
+    }
+
+    // Checks that `typ`, which `var` was just unified with, does not contain any of the labels
+    // that `var` lacks. Lacked labels which are still unknown can't be checked and are passed on
+    // to the tail of the record instead.
+    fn check_lacks(&self, var: Tvar, typ: &MonoType) -> Result<(), Error> {
+        let lacks = match self.lacks.borrow_mut().remove(&var) {
+            Some(lacks) => lacks,
+            None => return Ok(()),
+        };
+        if let MonoType::Record(record) = &*typ.apply_cow(self) {
+            let lacks: Vec<_> = lacks.into_iter().map(|label| label.apply(self)).collect();
+            let mut fields = record.fields();
+            for prop in &mut fields {
+                let label = prop.k.apply_cow(self);
+                if let RecordLabel::Concrete(_) = &*label {
+                    if lacks.contains(&*label) {
+                        return Err(Error::ExtraLabel(label.to_string()));
+                    }
+                }
+            }
+            if let Some(MonoType::Var(tail)) = fields.tail() {
+                for label in lacks {
+                    self.lack(*tail, label);
+                }
+            }
+        }
+        Ok(())
     }
 }
 
+// The labels which are in both `l` and `r`
+fn intersect_labels(l: Vec<String>, r: Vec<String>) -> Vec<String> {
+    l.into_iter().filter(|label| r.contains(label)).collect()
+}
+
 /// A type is `Substitutable` if a substitution can be applied to it.
 pub trait Substitutable {
     /// Apply a substitution to a type variable.
@@ -335,19 +639,25 @@ impl Substituter for SubstitutionMap {
     fn visit_poly_type_spec(&self, sub: &dyn Substituter, typ: &PolyType) -> Option<PolyType> {
         // `vars` defines new distinct variables for `expr` so any substitutions applied on a
         // variable named the same must not be applied in `expr`
-        typ.expr
-            .visit(&|var| {
-                if typ.vars.contains(&var) {
-                    None
-                } else {
-                    sub.try_apply(var)
-                }
-            })
-            .map(|expr| PolyType {
-                vars: typ.vars.clone(),
-                cons: typ.cons.clone(),
-                expr,
-            })
+        let free = |var| {
+            if typ.vars.contains(&var) {
+                None
+            } else {
+                sub.try_apply(var)
+            }
+        };
+        typ.expr.visit(&free).map(|expr| PolyType {
+            vars: typ.vars.clone(),
+            cons: typ.cons.clone(),
+            label_sets: typ.label_sets.clone(),
+            aliases: typ
+                .aliases
+                .iter()
+                .map(|alias| alias.apply_cow(&free).into_owned())
+                .collect(),
+            names: typ.names.clone(),
+            expr,
+        })
     }
 }
 
@@ -359,19 +669,25 @@ impl Substituter for Substitution {
     fn visit_poly_type_spec(&self, sub: &dyn Substituter, typ: &PolyType) -> Option<PolyType> {
         // `vars` defines new distinct variables for `expr` so any substitutions applied on a
         // variable named the same must not be applied in `expr`
-        typ.expr
-            .visit(&|var| {
-                if typ.vars.contains(&var) {
-                    None
-                } else {
-                    sub.try_apply(var)
-                }
-            })
-            .map(|expr| PolyType {
-                vars: typ.vars.clone(),
-                cons: typ.cons.clone(),
-                expr,
-            })
+        let free = |var| {
+            if typ.vars.contains(&var) {
+                None
+            } else {
+                sub.try_apply(var)
+            }
+        };
+        typ.expr.visit(&free).map(|expr| PolyType {
+            vars: typ.vars.clone(),
+            cons: typ.cons.clone(),
+            label_sets: typ.label_sets.clone(),
+            aliases: typ
+                .aliases
+                .iter()
+                .map(|alias| alias.apply_cow(&free).into_owned())
+                .collect(),
+            names: typ.names.clone(),
+            expr,
+        })
     }
 }
 
@@ -389,6 +705,20 @@ impl<'a> BindVars<'a> {
     }
 }
 
+impl BindVars<'_> {
+    /// Returns the variables which were left unbound along with the variables they were bound to.
+    pub fn bound_vars(&self) -> Vec<(Tvar, Tvar)> {
+        self.unbound_vars
+            .borrow()
+            .iter()
+            .filter_map(|(var, typ)| match typ {
+                MonoType::BoundVar(bound) => Some((*var, *bound)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
 impl Substituter for BindVars<'_> {
     fn try_apply(&self, var: Tvar) -> Option<MonoType> {
         Some(if let Some(typ) = self.sub.try_apply(var) {
@@ -657,3 +987,107 @@ where
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::semantic::types::Kind;
+
+    const VARS: u64 = 5;
+
+    // Builds a substitution with `VARS` variables from `values`
+    fn sub(values: Vec<(u64, MonoType)>) -> Substitution {
+        let sub = Substitution::from(
+            values
+                .into_iter()
+                .map(|(var, typ)| (Tvar(var), typ))
+                .collect::<SubstitutionMap>(),
+        );
+        sub.mk_fresh(VARS as usize - sub.len());
+        sub
+    }
+
+    // Substitutions binding `t0`, `t1` and `t2`, possibly to types containing `t3` and `t4`
+    fn substitutions() -> Vec<Substitution> {
+        let var = |var| MonoType::Var(Tvar(var));
+        vec![
+            sub(vec![]),
+            sub(vec![(0, MonoType::INT)]),
+            sub(vec![(0, MonoType::arr(var(3)))]),
+            sub(vec![(1, MonoType::STRING), (2, MonoType::arr(var(4)))]),
+            sub(vec![
+                (0, MonoType::arr(MonoType::arr(var(4)))),
+                (2, MonoType::INT),
+            ]),
+            sub(vec![(1, MonoType::arr(var(3))), (2, MonoType::arr(var(3)))]),
+        ]
+    }
+
+    fn types() -> Vec<MonoType> {
+        let mut types: Vec<_> = (0..VARS).map(|var| MonoType::Var(Tvar(var))).collect();
+        types.push(MonoType::arr(MonoType::Var(Tvar(1))));
+        types.push(MonoType::arr(MonoType::arr(MonoType::Var(Tvar(2)))));
+        types
+    }
+
+    #[test]
+    fn compose_applies_other_then_self() {
+        for s in substitutions() {
+            for other in substitutions() {
+                let composed = s.clone().compose(other.clone());
+                for typ in types() {
+                    assert_eq!(
+                        typ.clone().apply(&composed),
+                        typ.clone().apply(&other).apply(&s),
+                        "{} composed with {}",
+                        s,
+                        other,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compose_is_associative() {
+        for a in substitutions() {
+            for b in substitutions() {
+                for c in substitutions() {
+                    let left = a.clone().compose(b.clone()).compose(c.clone());
+                    let right = a.clone().compose(b.clone().compose(c.clone()));
+                    for typ in types() {
+                        assert_eq!(typ.clone().apply(&left), typ.clone().apply(&right));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compose_with_empty_is_identity() {
+        for s in substitutions() {
+            let left = Substitution::empty().compose(s.clone());
+            let right = s.clone().compose(Substitution::empty());
+            for typ in types() {
+                assert_eq!(typ.clone().apply(&left), typ.clone().apply(&s));
+                assert_eq!(typ.clone().apply(&right), typ.clone().apply(&s));
+            }
+        }
+    }
+
+    #[test]
+    fn compose_keeps_kinds_of_unbound_variables() {
+        let mut s = sub(vec![(0, MonoType::INT)]);
+        s.cons().insert(Tvar(3), vec![Kind::Addable]);
+        let mut other = sub(vec![(1, MonoType::arr(MonoType::Var(Tvar(3))))]);
+        other.cons().insert(Tvar(3), vec![Kind::Equatable]);
+        other.cons().insert(Tvar(4), vec![Kind::Stringable]);
+
+        let mut composed = s.compose(other);
+        assert_eq!(
+            composed.cons().get(&Tvar(3)),
+            Some(&vec![Kind::Equatable, Kind::Addable])
+        );
+        assert_eq!(composed.cons().get(&Tvar(4)), Some(&vec![Kind::Stringable]));
+    }
+}
@@ -33,13 +33,14 @@ use crate::{
     semantic::{
         self,
         convert::convert_polytype,
-        env::Environment,
-        fresh::Fresher,
+        env::{EnvDiff, Environment},
+        fresh::{Fresh, Fresher},
         import::Packages,
         nodes::Symbol,
+        parse_polytype, parse_type,
         sub::Substitution,
-        types::{MonoType, PolyType, PolyTypeHashMap, SemanticMap, TvarKinds},
-        Analyzer, AnalyzerConfig, PackageExports,
+        types::{MonoType, PolyType, PolyTypeHashMap, SemanticMap, TvarKinds, TvarMap, TvarNaming},
+        Analyzer, AnalyzerConfig, PackageExports, TraceStep,
     },
 };
 
@@ -331,7 +332,7 @@ macro_rules! test_error_msg {
         }
     }};
 
-    ( $(test: $test: ident,)? $(imp: $imp:expr,)? $(env: $env:expr,)? src: $src:expr $(,)?, expect_short: $expect:expr $(,)? ) => {
+    ( $(config: $config:expr,)? $(test: $test: ident,)? $(imp: $imp:expr,)? $(env: $env:expr,)? src: $src:expr $(,)?, expect_short: $expect:expr $(,)? ) => {
         $(#[test] fn $test() )? {
 
         #[allow(unused_mut, unused_assignments)]
@@ -344,12 +345,17 @@ macro_rules! test_error_msg {
         $(
             env = $env;
         )?
+        #[allow(unused_mut, unused_assignments)]
+        let mut config = AnalyzerConfig::default();
+        $(
+            config = $config;
+        )?
         match infer_types(
             $src,
             env,
             imp,
             None,
-            AnalyzerConfig::default(),
+            config,
         ) {
             Err(e) => {
                 let got = e.pretty_short($src);
@@ -405,7 +411,19 @@ macro_rules! package {
     }}
 }
 
+mod arrays;
+mod blocks;
+mod call_graph;
+mod diff;
+mod fold;
+mod imports;
 mod labels;
+mod matches;
+mod nulls;
+mod pipes;
+mod records;
+mod source_map;
+mod time;
 
 #[test]
 fn dictionary_literals() {
@@ -593,6 +611,44 @@ fn imports() {
         "#,
     }
 }
+
+#[test]
+fn duplicate_import_names() {
+    test_error_msg! {
+        imp: map![
+            "path/to/foo" => package![
+                "a" => "int",
+            ],
+            "path/to/bar" => package![
+                "a" => "string",
+            ],
+        ],
+        src: r#"
+            import foo "path/to/foo"
+            import foo "path/to/bar"
+
+            a = foo.a
+        "#,
+        err: "error @3:20-3:23: import name foo is already used by the import of \"path/to/foo\"",
+    }
+    test_error_msg! {
+        imp: map![
+            "path/to/foo" => package![
+                "a" => "int",
+            ],
+            "other/foo" => package![
+                "a" => "string",
+            ],
+        ],
+        src: r#"
+            import "path/to/foo"
+            import "other/foo"
+
+            a = foo.a
+        "#,
+        err: "error @3:13-3:31: import name foo is already used by the import of \"path/to/foo\"",
+    }
+}
 #[test]
 fn literals() {
     test_infer! {
@@ -737,6 +793,60 @@ fn string_interpolation() {
     }
 }
 #[test]
+fn tuple_lit() {
+    test_infer! {
+        src: r#"
+            x = (1, "a").0
+            y = (1, "a").1
+            z = ((1, "a"), 2.0).0.1
+            w = ((a, b) => (b, a))(a: 1, b: "s").0
+        "#,
+        exp: map![
+            "x" => "int",
+            "y" => "string",
+            "z" => "string",
+            "w" => "string",
+        ],
+    }
+}
+#[test]
+fn tuple_errors() {
+    test_error_msg! {
+        src: r#"
+            t = (1, "a")
+            x = t.2
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:17: error: tuple index 2 is out of range for a tuple with 2 elements
+        "#]]
+    }
+    test_error_msg! {
+        src: r#"
+            a = [(1, "a"), (1, "a", 2)]
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:28: error: expected (int, string) (tuple) but found (int, string, int) (tuple)
+        "#]]
+    }
+    test_error_msg! {
+        src: r#"
+            r = {a: 1}
+            x = r.0
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:17: error: expected a tuple but found {a:int}
+        "#]]
+    }
+    test_error_msg! {
+        src: r#"
+            f = (t) => t.0
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:24: error: expected a tuple but found A
+        "#]]
+    }
+}
+#[test]
 fn array_lit() {
     test_infer! {
         src: "a = []",
@@ -1863,6 +1973,99 @@ fn binary_expr_regex_op() {
     }
 }
 #[test]
+fn binary_expr_regex_literal() {
+    test_infer! {
+        src: r#"
+            r = /a/
+            a = "abc" =~ /a/
+            b = "abc" !~ r
+        "#,
+        exp: map![
+            "r" => "regexp",
+            "a" => "bool",
+            "b" => "bool",
+        ],
+    }
+    test_error_msg! {
+        src: r#"
+            a = 1 =~ /a/
+            b = "abc" !~ "a"
+        "#,
+        expect: expect_test::expect![[r#"
+            error: expected string but found int
+              ┌─ main:2:17
+              │
+            2 │             a = 1 =~ /a/
+              │                 ^
+
+            error: expected regexp but found string
+              ┌─ main:3:26
+              │
+            3 │             b = "abc" !~ "a"
+              │                          ^^^
+
+        "#]],
+    }
+}
+#[test]
+fn binary_expr_null_coalescing() {
+    test_infer! {
+        env: map![
+            "a" => "int",
+            "b" => "int",
+        ],
+        src: "c = a ?? b",
+        exp: map![
+            "c" => "int",
+        ],
+    }
+    test_infer! {
+        src: r#"
+            f = (x, y) => x ?? y
+            r = {a: "x"}
+            c = r.a ?? "default"
+        "#,
+        exp: map![
+            "f" => "(x: A, y: A) => A",
+            "r" => "{a: string}",
+            "c" => "string",
+        ],
+    }
+    test_error_msg! {
+        src: r#"
+            x = 1 ?? "a"
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:22: error: expected int but found string
+        "#]]
+    }
+}
+#[test]
+fn ternary_expr() {
+    test_infer! {
+        config: AnalyzerConfig {
+            features: vec![semantic::Feature::TernaryOperator],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = (x) => x > 0 ? "positive" : x < 0 ? "negative" : "zero"
+            y = f(x: 1)
+        "#,
+        exp: map![
+            "f" => "(x: int) => string",
+            "y" => "string",
+        ],
+    }
+    test_error_msg! {
+        src: r#"
+            x = true ? 1 : 2
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:17: error: ternary expressions are not enabled
+        "#]]
+    }
+}
+#[test]
 fn conditional_expr() {
     test_infer! {
         env: map![
@@ -2320,12 +2523,32 @@ fn index_expr() {
         ],
         src: src,
     }
-    test_infer_err! {
+    test_infer! {
         env: map![
             "a" => "string",
             "b" => "int",
         ],
         src: src,
+        exp: map![
+            "c" => "string",
+        ],
+    }
+    test_infer! {
+        env: map![
+            "a" => "bytes",
+            "b" => "int",
+        ],
+        src: src,
+        exp: map![
+            "c" => "int",
+        ],
+    }
+    test_infer_err! {
+        env: map![
+            "a" => "string",
+            "b" => "float",
+        ],
+        src: src,
     }
     test_infer_err! {
         env: map![
@@ -2570,6 +2793,47 @@ fn unary_sub() {
     }
 }
 #[test]
+fn unary_sub_polymorphic() {
+    test_infer! {
+        src: r#"
+            f = (x) => -x
+            a = f(x: 1)
+            b = f(x: 1.0)
+            c = f(x: 1h)
+            d = -1h
+        "#,
+        exp: map![
+            "f" => "(x: A) => A where A: Negatable",
+            "a" => "int",
+            "b" => "float",
+            "c" => "duration",
+            "d" => "duration",
+        ],
+    }
+}
+#[test]
+fn exponentiation() {
+    test_infer! {
+        src: r#"
+            f = (a, b) => a ** b
+            a = 2 ** 10
+            b = 2.0 ** 0.5
+            c = f(a: 3, b: 2)
+        "#,
+        exp: map![
+            "f" => "(a: A, b: A) => A where A: Numeric",
+            "a" => "int",
+            "b" => "float",
+            "c" => "int",
+        ],
+    }
+    test_infer_err! {
+        src: r#"
+            a = "a" ** "b"
+        "#,
+    }
+}
+#[test]
 fn exists() {
     test_infer! {
         env: map![
@@ -2817,9 +3081,9 @@ fn record_with_scoped_labels() {
             w = {r with b: x}
         "#,
         exp: map![
-            "u" => "{A with a: int   , a: int , b: float }",
-            "v" => "{A with b: float , a: int , a: float }",
-            "w" => "{A with b: float   , a: int , b: int }",
+            "u" => "{A with a: int , b: float }",
+            "v" => "{A with a: float , b: float }",
+            "w" => "{A with a: int , b: int }",
         ],
     }
 }
@@ -3191,8 +3455,6 @@ fn constrained_generics_divisible() {
 }
 #[test]
 fn constrained_generics_comparable() {
-    // TODO(algow): re-introduce equality constraints for binary comparison operators
-    // https://github.com/influxdata/flux/issues/2466
     test_infer! {
         src: r#"
             f = (a, b) => a < b
@@ -3203,7 +3465,7 @@ fn constrained_generics_comparable() {
             e = f(a: 2019-10-30T00:00:00Z, b: 2019-10-31T00:00:00Z)
         "#,
         exp: map![
-            "f" => "(a: A, b: B) => bool where A: Comparable, B: Comparable ",
+            "f" => "(a: A, b: B) => bool where A: Comparable, B: Comparable",
             "a" => "bool",
             "b" => "bool",
             "c" => "bool",
@@ -3211,6 +3473,18 @@ fn constrained_generics_comparable() {
             "e" => "bool",
         ],
     }
+    test_infer! {
+        src: r#"
+            f = (a, b) => a < b
+            a = f(a: 1, b: 1.0)
+            b = 1.5 > 0
+        "#,
+        exp: map![
+            "f" => "(a: A, b: B) => bool where A: Comparable, B: Comparable",
+            "a" => "bool",
+            "b" => "bool",
+        ],
+    }
     test_infer_err! {
         env: map![
             "true" => "bool",
@@ -3241,6 +3515,40 @@ fn constrained_generics_comparable() {
     }
 }
 #[test]
+fn strict_comparisons() {
+    let config = || AnalyzerConfig {
+        features: vec![semantic::Feature::StrictComparisons],
+        ..AnalyzerConfig::default()
+    };
+    test_infer! {
+        config: config(),
+        src: r#"
+            f = (a, b) => a < b
+            g = (a, b) => a >= b
+            x = f(a: 1, b: 2)
+        "#,
+        exp: map![
+            "f" => "(a: A, b: A) => bool where A: Comparable",
+            "g" => "(a: A, b: A) => bool where A: Comparable + Equatable",
+            "x" => "bool",
+        ],
+    }
+    test_error_msg! {
+        config: config(),
+        src: r#"
+            1 < 1.0
+        "#,
+        expect: expect![[r#"
+            error: expected int but found float
+              ┌─ main:2:17
+              │
+            2 │             1 < 1.0
+              │                 ^^^
+
+        "#]],
+    }
+}
+#[test]
 fn constrained_generics_equatable() {
     // TODO(algow): re-introduce equality constraints for binary comparison operators
     // https://github.com/influxdata/flux/issues/2466
@@ -3289,8 +3597,6 @@ fn constrained_generics_equatable() {
 }
 #[test]
 fn multiple_constraints() {
-    // TODO(algow): re-introduce equality constraints for binary comparison operators
-    // https://github.com/influxdata/flux/issues/2466
     test_infer! {
         src: r#"
             f = (a, b) => a <= b
@@ -3301,7 +3607,7 @@ fn multiple_constraints() {
             e = f(a: 2019-10-30T00:00:00Z, b: 2019-10-31T00:00:00Z)
         "#,
         exp: map![
-            "f" => "(a: A, b: B) => bool where A: Comparable + Equatable, B: Comparable + Equatable ",
+            "f" => "(a: A, b: B) => bool where A: Comparable + Equatable, B: Comparable + Equatable",
             "a" => "bool",
             "b" => "bool",
             "c" => "bool",
@@ -3367,49 +3673,130 @@ fn constrained_generics_timeable() {
     }
 }
 #[test]
-fn function_instantiation_and_generalization() {
+fn constrained_generics_stringable() {
     test_infer! {
+        env: map![
+            "display" => "(v: A) => string where A: Stringable",
+            "b" => "bool",
+            "t" => "time",
+        ],
         src: r#"
-            r = ((o) => o)(o: 0)
-            x = r
-            s = ((o) => o)(o: {list: [0,1]})
-            y = s.list
-            t = ((o) => ({list: o}))(o: [0,1])
-            z = t.list
-            f = (x) => {
-                y = x
-                g = (a) => {
-                    z = y
-                    return z(b: a)
-                }
-                return g(a: [0])
-            }
-            a = f(x: (b) => b)
-            b = f(x: (b) => b[0])
-            c = f(x: (b) => ({b: b}))
+            a = display(v: 1)
+            c = display(v: 1.0)
+            d = display(v: b)
+            e = display(v: t)
+            f = display(v: 1m)
+            g = display(v: "str")
+            h = (v) => display(v)
         "#,
         exp: map![
-            "r" => "int",
-            "x" => "int",
-            "s" => "{list: [int]}",
-            "y" => "[int]",
-            "t" => "{list: [int]}",
-            "z" => "[int]",
-            "f" => "(x: (b: [int]) => A) => A",
-            "a" => "[int]",
-            "b" => "int",
-            "c" => "{b: [int]}",
+            "a" => "string",
+            "c" => "string",
+            "d" => "string",
+            "e" => "string",
+            "f" => "string",
+            "g" => "string",
+            "h" => "(v: A) => string where A: Stringable",
         ],
     }
     test_infer_err! {
+        env: map![
+            "display" => "(v: A) => string where A: Stringable",
+        ],
         src: r#"
-            r = ((o) => o)(o: 0)
-            x = r
-            s = ((o) => o)(o: {list: [0,1]})
-            y = s.list
-            t = ((o) => ({list: o}))(o: [0,1])
-            z = t.list
-            f = (x) => {
+            display(v: [1])
+        "#,
+    }
+    test_infer_err! {
+        env: map![
+            "display" => "(v: A) => string where A: Stringable",
+        ],
+        src: r#"
+            display(v: () => 1)
+        "#,
+    }
+    test_infer_err! {
+        env: map![
+            "display" => "(v: A) => string where A: Stringable",
+        ],
+        src: r#"
+            display(v: ["a": 1])
+        "#,
+    }
+}
+#[test]
+fn constrained_generics_lengthable() {
+    test_infer! {
+        env: map![
+            "length" => "(v: A) => int where A: Lengthable",
+            "bs" => "bytes",
+        ],
+        src: r#"
+            a = length(v: "str")
+            b = length(v: bs)
+            c = length(v: [1, 2, 3])
+            d = (v) => length(v)
+        "#,
+        exp: map![
+            "a" => "int",
+            "b" => "int",
+            "c" => "int",
+            "d" => "(v: A) => int where A: Lengthable",
+        ],
+    }
+    test_infer_err! {
+        env: map![
+            "length" => "(v: A) => int where A: Lengthable",
+        ],
+        src: r#"
+            length(v: ["a": 1])
+        "#,
+    }
+}
+#[test]
+fn function_instantiation_and_generalization() {
+    test_infer! {
+        src: r#"
+            r = ((o) => o)(o: 0)
+            x = r
+            s = ((o) => o)(o: {list: [0,1]})
+            y = s.list
+            t = ((o) => ({list: o}))(o: [0,1])
+            z = t.list
+            f = (x) => {
+                y = x
+                g = (a) => {
+                    z = y
+                    return z(b: a)
+                }
+                return g(a: [0])
+            }
+            a = f(x: (b) => b)
+            b = f(x: (b) => b[0])
+            c = f(x: (b) => ({b: b}))
+        "#,
+        exp: map![
+            "r" => "int",
+            "x" => "int",
+            "s" => "{list: [int]}",
+            "y" => "[int]",
+            "t" => "{list: [int]}",
+            "z" => "[int]",
+            "f" => "(x: (b: [int]) => A) => A",
+            "a" => "[int]",
+            "b" => "int",
+            "c" => "{b: [int]}",
+        ],
+    }
+    test_infer_err! {
+        src: r#"
+            r = ((o) => o)(o: 0)
+            x = r
+            s = ((o) => o)(o: {list: [0,1]})
+            y = s.list
+            t = ((o) => ({list: o}))(o: [0,1])
+            z = t.list
+            f = (x) => {
                 y = x
                 g = (a) => {
                     z = y
@@ -3507,6 +3894,41 @@ fn function_default_arguments_polymorphic() {
     }
 }
 
+#[test]
+fn function_default_arguments_before_required() {
+    test_infer! {
+        src: r#"
+            f = (x=1, y) => x + y + 1
+            a = f(y: 2)
+            b = f(x: 3, y: 2)
+        "#,
+        exp: map![
+            "f" => "(?x: int, y: int) => int",
+            "a" => "int",
+            "b" => "int",
+        ],
+    }
+}
+
+#[test]
+fn function_default_arguments_wrong_type() {
+    test_error_msg! {
+        src: r#"
+            f = (x=1, y) => x + y + 1
+            a = f(y: 2)
+            b = f(x: "a", y: 2)
+        "#,
+        expect: expect![[r#"
+            error: expected int but found string (argument x)
+              ┌─ main:4:22
+              │
+            4 │             b = f(x: "a", y: 2)
+              │                      ^^^
+
+        "#]],
+    }
+}
+
 #[test]
 fn issue_4051() {
     test_infer! {
@@ -3539,6 +3961,9 @@ fn copy_bindings_from_other_env() {
         PolyType {
             vars: Vec::new(),
             cons: TvarKinds::new(),
+            label_sets: Default::default(),
+            aliases: Vec::new(),
+            names: Default::default(),
             expr: MonoType::BOOL,
         },
     );
@@ -3549,6 +3974,9 @@ fn copy_bindings_from_other_env() {
         PolyType {
             vars: Vec::new(),
             cons: TvarKinds::new(),
+            label_sets: Default::default(),
+            aliases: Vec::new(),
+            names: Default::default(),
             expr: MonoType::Var(f.fresh()),
         },
     );
@@ -3563,11 +3991,17 @@ fn copy_bindings_from_other_env() {
                 b => PolyType {
                     vars: Vec::new(),
                     cons: TvarKinds::new(),
+                    label_sets: Default::default(),
+                    aliases: Vec::new(),
+                    names: Default::default(),
                     expr: MonoType::Var(f.fresh()),
                 },
                 a => PolyType {
                     vars: Vec::new(),
                     cons: TvarKinds::new(),
+                    label_sets: Default::default(),
+                    aliases: Vec::new(),
+                    names: Default::default(),
                     expr: MonoType::BOOL,
                 }
             )
@@ -3607,18 +4041,115 @@ test_error_msg! {
 
     "#]],
 }
+test_error_msg! {
+    test: negate_bool_error,
+    src: r#"
+            -true
+        "#,
+    expect: expect![[r#"
+        error: bool is not Negatable
+          ┌─ main:2:14
+          │
+        2 │             -true
+          │              ^^^^
+
+    "#]],
+}
+test_error_msg! {
+    test: exponentiation_of_mixed_types_error,
+    src: r#"
+            2 ** 2.0
+        "#,
+    expect: expect![[r#"
+        error: expected int but found float
+          ┌─ main:2:18
+          │
+        2 │             2 ** 2.0
+          │                  ^^^
+
+    "#]],
+}
+test_error_msg! {
+    test: compare_records_error,
+    src: r#"
+            {a: 1} < {a: 2}
+        "#,
+    expect: expect![[r#"
+        error: {a:int} (record) is not Comparable
+          ┌─ main:2:13
+          │
+        2 │             {a: 1} < {a: 2}
+          │             ^^^^^^
+
+        error: {a:int} (record) is not Comparable
+          ┌─ main:2:22
+          │
+        2 │             {a: 1} < {a: 2}
+          │                      ^^^^^^
+
+    "#]],
+}
+test_error_msg! {
+    test: compare_functions_error,
+    src: r#"
+            f = () => 1
+            g = () => 2
+            f == g
+        "#,
+    expect: expect![[r#"
+        error: () => int (function) is not Equatable
+          ┌─ main:4:13
+          │
+        4 │             f == g
+          │             ^
+
+        error: () => int (function) is not Equatable
+          ┌─ main:4:18
+          │
+        4 │             f == g
+          │                  ^
+
+    "#]],
+}
+#[test]
+fn equality_requires_equatable() {
+    test_infer! {
+        src: r#"
+            f = (a) => a == a
+        "#,
+        exp: map![
+            "f" => "(a: A) => bool where A: Equatable",
+        ],
+    }
+}
+test_error_msg! {
+    test: compare_records_with_function_fields_error,
+    src: r#"
+            f = () => 1
+            neq = (a) => a != 0
+            neq(a: {g: f})
+        "#,
+    expect: expect![[r#"
+        error: () => int (function) is not Equatable (argument a)
+          ┌─ main:4:20
+          │
+        4 │             neq(a: {g: f})
+          │                    ^^^^^^
+
+    "#]],
+}
 test_error_msg! {
     test: location_points_to_entire_binary_error,
     src: r#"
-            1h + 2h
+            true + false
         "#,
     // Location points to entire binary expression
     expect: expect![[r#"
-        error: duration is not Addable
+        error: bool is not Addable
           ┌─ main:2:13
           │
-        2 │             1h + 2h
-          │             ^^^^^^^
+        2 │             true + false
+          │             ^^^^^^^^^^^^
 
     "#]],
 }
@@ -3631,7 +4162,7 @@ test_error_msg! {
         "#,
     // Location points to second interpolated expression
     expect: expect![[r#"
-        error: {b:float, a:int} (record) is not Stringable
+        error: cannot interpolate {a:int, b:float} into string
           ┌─ main:4:35
           │
         4 │             "Hey ${bob} it's me ${joe}!"
@@ -3639,6 +4170,21 @@ test_error_msg! {
 
     "#]],
 }
+test_error_msg! {
+    test: nested_interpolation_error,
+    src: r#"
+            r = {c: 1}
+            "outer ${"inner ${r}"}"
+        "#,
+    expect: expect![[r#"
+        error: cannot interpolate {c:int} into string
+          ┌─ main:3:31
+          │
+        3 │             "outer ${"inner ${r}"}"
+          │                               ^
+
+    "#]],
+}
 test_error_msg! {
     test: location_points_to_if_error,
     src: r#"
@@ -3748,6 +4294,32 @@ test_error_msg! {
 
     "#]],
 }
+test_error_msg! {
+    test: numbered_tvar_names_are_scoped_per_error,
+    config: AnalyzerConfig {
+        tvar_naming: TvarNaming::Numbered,
+        ..AnalyzerConfig::default()
+    },
+    src: r#"
+            a = [1, 2, 3]
+            a.x
+            a.y
+        "#,
+    expect: expect![[r#"
+        error: expected {t0 with x:t1} (record) but found [int] (array)
+          ┌─ main:3:13
+          │
+        3 │             a.x
+          │             ^
+
+        error: expected {t0 with y:t1} (record) but found [int] (array)
+          ┌─ main:4:13
+          │
+        4 │             a.y
+          │             ^
+
+    "#]],
+}
 test_error_msg! {
     test: location_points_to_entire_call_error,
     src: r#"
@@ -3796,6 +4368,20 @@ test_error_msg! {
 
     "#]],
 }
+test_error_msg! {
+    test: location_columns_count_characters,
+    src: r#"
+            x = "été" + y
+        "#,
+    expect: expect![[r#"
+        error: undefined identifier y
+          ┌─ main:2:25
+          │
+        2 │             x = "été" + y
+          │                         ^
+
+    "#]],
+}
 test_error_msg! {
     test: location_points_to_call_error,
     src: r#"
@@ -3804,18 +4390,18 @@ test_error_msg! {
         "#,
     // Location points to call expression `match(r)`
     expect: expect![[r#"
-            error: found unexpected argument r
-              ┌─ main:3:31
-              │
-            3 │             fn = (r) => match(r)
-              │                               ^
-
             error: missing required argument o
               ┌─ main:3:25
               │
             3 │             fn = (r) => match(r)
               │                         ^^^^^^^^
 
+            error: found unexpected argument r
+              ┌─ main:3:31
+              │
+            3 │             fn = (r) => match(r)
+              │                               ^
+
         "#]],
 }
 test_error_msg! {
@@ -3826,18 +4412,18 @@ test_error_msg! {
         "#,
     // Location points to call expression `f(a: 0, c: 1)`
     expect: expect![[r#"
-            error: found unexpected argument c
-              ┌─ main:3:24
-              │
-            3 │             f(a: 0, c: 1)
-              │                        ^
-
             error: missing required argument b
               ┌─ main:3:13
               │
             3 │             f(a: 0, c: 1)
               │             ^^^^^^^^^^^^^
 
+            error: found unexpected argument c
+              ┌─ main:3:24
+              │
+            3 │             f(a: 0, c: 1)
+              │                        ^
+
         "#]],
 }
 
@@ -3965,42 +4551,281 @@ fn primitive_kind_short_errors() {
 }
 
 #[test]
-fn invalid_mono_type() {
+fn missing_label_suggestions() {
     test_error_msg! {
         src: r#"
-            builtin x : abc
+            r = {host: "a", port: 1}
+            x = r.hsot
         "#,
-        expect: expect_test::expect![[r#"
-            error: invalid named type abc
-              ┌─ main:2:25
-              │
-            2 │             builtin x : abc
-              │                         ^^^
+        expect_short: expect_test::expect![[r#"
+            main:3:17: error: record is missing label hsot, did you mean "host"?
+        "#]]
+    }
+    test_error_msg! {
+        src: r#"
+            r = {host: "a", port: 1}
+            x = r.region
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:17: error: record is missing label region
+        "#]]
+    }
+    test_error_msg! {
+        src: r#"
+            r = {}
+            x = r.hsot
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:17: error: record is missing label hsot
+        "#]]
+    }
+}
 
+#[test]
+fn multiple_independent_errors() {
+    test_error_msg! {
+        src: r#"
+            r = {a: 1}
+            x = r.0
+            y = x + "a"
+            z = x - 1
+            w = t.0
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:17: error: expected a tuple but found {a:int}
+            main:6:17: error: undefined identifier t
         "#]]
     }
 }
 
 #[test]
-fn missing_return() {
+fn stringable_kind_errors() {
     test_error_msg! {
+        env: map![
+            "display" => "(v: A) => string where A: Stringable",
+        ],
         src: r#"
-            () => { }
+            display(v: {a: 1})
         "#,
         expect: expect_test::expect![[r#"
-            error: missing return statement in block
-              ┌─ main:2:19
+            error: {a:int} (record) is not Stringable (argument v)
+              ┌─ main:2:24
               │
-            2 │             () => { }
-              │                   ^^^
+            2 │             display(v: {a: 1})
+              │                        ^^^^^^
 
         "#]]
     }
 }
 
 #[test]
-fn symbol_resolution() {
-    let imp = map![
+fn lengthable_kind_errors() {
+    test_error_msg! {
+        env: map![
+            "length" => "(v: A) => int where A: Lengthable",
+        ],
+        src: r#"
+            length(v: 1)
+        "#,
+        expect: expect_test::expect![[r#"
+            error: int is not Lengthable (argument v)
+              ┌─ main:2:23
+              │
+            2 │             length(v: 1)
+              │                       ^
+
+        "#]]
+    }
+}
+
+#[test]
+fn invalid_mono_type() {
+    test_error_msg! {
+        src: r#"
+            builtin x : abc
+        "#,
+        expect: expect_test::expect![[r#"
+            error: invalid named type abc
+              ┌─ main:2:25
+              │
+            2 │             builtin x : abc
+              │                         ^^^
+
+        "#]]
+    }
+}
+
+#[test]
+fn type_alias() {
+    test_infer! {
+        src: r#"
+            builtin origin : Point
+            type Point = {x: float, y: float}
+            p = origin
+        "#,
+        exp: map![
+            "origin" => "{x: float, y: float}",
+            "p" => "{x: float, y: float}",
+        ],
+    }
+}
+
+#[test]
+fn type_alias_parameters() {
+    test_infer! {
+        src: r#"
+            type Pair = (A) => {first: A, second: A}
+            builtin pair : (v: A) => Pair[A]
+            p = pair(v: 1)
+        "#,
+        exp: map![
+            "pair" => "(v: A) => {first: A, second: A}",
+            "p" => "{first: int, second: int}",
+        ],
+    }
+}
+
+#[test]
+fn type_alias_display() {
+    let src = r#"
+            type Point = {x: float, y: float}
+            type Pair = (A) => {first: A, second: A}
+            builtin origin : Point
+            builtin pair : (v: A) => Pair[A]
+            builtin segment : Pair[Point]
+            builtin distance : (to: Point) => float
+        "#;
+    let (env, _) = infer_types(
+        src,
+        HashMap::default(),
+        HashMap::default(),
+        None,
+        AnalyzerConfig::default(),
+    )
+    .unwrap_or_else(|err| panic!("{}", err.pretty(src)));
+    let types: Vec<_> = ["origin", "pair", "segment", "distance"]
+        .iter()
+        .map(|name| {
+            let typ = env.lookup(name).expect("builtin is exported").clone();
+            format!(
+                "{}: {}",
+                name,
+                typ.fresh(&mut Fresher::default(), &mut TvarMap::new())
+            )
+        })
+        .collect();
+    expect![[r#"
+        origin: Point
+        pair: (v:A) => Pair[A]
+        segment: Pair[Point]
+        distance: (to:Point) => float"#]]
+    .assert_eq(&types.join("\n"));
+}
+
+#[test]
+fn recursive_type_alias() {
+    test_error_msg! {
+        src: r#"
+            type Tree = {value: int, children: [Tree]}
+            builtin t : Tree
+        "#,
+        expect: expect_test::expect![[r#"
+            error: type alias Tree is recursive: Tree -> Tree
+              ┌─ main:2:49
+              │
+            2 │             type Tree = {value: int, children: [Tree]}
+              │                                                 ^^^^
+
+        "#]]
+    }
+}
+
+#[test]
+fn type_alias_arity() {
+    test_error_msg! {
+        src: r#"
+            type Pair = (A) => {first: A, second: A}
+            builtin p : Pair[int, string]
+        "#,
+        expect: expect_test::expect![[r#"
+            error: type alias Pair expects 1 type arguments, got 2
+              ┌─ main:3:25
+              │
+            3 │             builtin p : Pair[int, string]
+              │                         ^^^^^^^^^^^^^^^^^
+
+        "#]]
+    }
+}
+
+#[test]
+fn annotated_variable() {
+    test_infer! {
+        src: r#"
+            f : (x: int) => int = (x) => x
+            xs : [int] = []
+            g = f
+            ys = xs
+        "#,
+        exp: map![
+            "f" => "(x: int) => int",
+            "xs" => "[int]",
+            "g" => "(x: int) => int",
+            "ys" => "[int]",
+        ],
+    }
+}
+
+#[test]
+fn annotated_variable_with_constraints() {
+    test_infer! {
+        src: r#"
+            double : (x: A) => A where A: Addable = (x) => x + x
+            i = double(x: 1)
+        "#,
+        exp: map![
+            "double" => "(x: A) => A where A: Addable",
+            "i" => "int",
+        ],
+    }
+}
+
+#[test]
+fn invalid_annotated_variable() {
+    test_error_msg! {
+        src: r#"
+            xs : [int] = [1.0]
+            f : (x: A) => A = (x) => x + 1
+            g : (x: A) => A = (x) => x + x
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:13: error: expected int but found float
+            main:3:13: error: the annotation of `f` is more general than its type, expected (x: A) => A but found (x: int) => int
+            main:4:13: error: the annotation of `g` is more general than its type, expected (x: A) => A but found (x: A) => A where A: Addable
+        "#]]
+    }
+}
+
+#[test]
+fn missing_return() {
+    test_error_msg! {
+        src: r#"
+            () => { }
+        "#,
+        expect: expect_test::expect![[r#"
+            error: missing return statement in block
+              ┌─ main:2:19
+              │
+            2 │             () => { }
+              │                   ^^^
+
+        "#]]
+    }
+}
+
+#[test]
+fn symbol_resolution() {
+    let imp = map![
         "types" => package![
             "isType" => "(v: A, type: string) => bool } where A: Basic",
         ],
@@ -4060,6 +4885,54 @@ fn symbol_resolution() {
     assert_eq!(member_expr_3.expect("member expression").property, "isType");
 }
 
+#[test]
+fn type_at_position() {
+    let src = r#"
+            f = (x) => x
+            r = {a: 1, b: "s"}
+            y = r.b
+            z = f(x: r)
+        "#;
+    let (_, pkg) = infer_types(
+        src,
+        Default::default(),
+        Default::default(),
+        None,
+        Default::default(),
+    )
+    .unwrap_or_else(|err| panic!("{}", err));
+
+    let type_at = |line, column| {
+        pkg.type_at(ast::Position { line, column })
+            .map(|(typ, loc)| {
+                (
+                    typ.to_string(),
+                    loc.start.line,
+                    loc.start.column,
+                    loc.end.column,
+                )
+            })
+    };
+
+    // Identifiers
+    assert_eq!(
+        type_at(4, 17),
+        Some(("{a:int, b:string}".to_string(), 4, 17, 18))
+    );
+    // Member expressions
+    assert_eq!(type_at(4, 19), Some(("string".to_string(), 4, 17, 20)));
+    // Call results
+    assert_eq!(
+        type_at(5, 18),
+        Some(("{a:int, b:string}".to_string(), 5, 17, 24))
+    );
+    // Free type variables are canonicalized
+    assert_eq!(type_at(2, 18), Some(("(x:A) => A".to_string(), 2, 17, 25)));
+    assert_eq!(type_at(2, 18), type_at(2, 18));
+    // Nothing covers positions outside of any expression
+    assert_eq!(type_at(2, 1), None);
+}
+
 #[test]
 fn multiple_errors_in_function_call() {
     test_error_msg! {
@@ -4070,11 +4943,11 @@ fn multiple_errors_in_function_call() {
             f(a: 1, b: "record", d: {})
         "#,
         expect: expect![[r#"
-            error: found unexpected argument d
-              ┌─ main:2:37
+            error: missing required argument c
+              ┌─ main:2:13
               │
             2 │             f(a: 1, b: "record", d: {})
-              │                                     ^^
+              │             ^^^^^^^^^^^^^^^^^^^^^^^^^^^
 
             error: expected float but found int (argument a)
               ┌─ main:2:18
@@ -4088,12 +4961,566 @@ fn multiple_errors_in_function_call() {
             2 │             f(a: 1, b: "record", d: {})
               │                        ^^^^^^^^
 
-            error: missing required argument c
-              ┌─ main:2:13
+            error: found unexpected argument d
+              ┌─ main:2:37
               │
             2 │             f(a: 1, b: "record", d: {})
-              │             ^^^^^^^^^^^^^^^^^^^^^^^^^^^
+              │                                     ^^
 
         "#]]
     }
 }
+
+#[test]
+fn environment_to_json() {
+    let src = r#"
+        f = (x, y) => x + y
+        s = "a"
+    "#;
+    let mut analyzer = Analyzer::new_with_defaults(Environment::default(), Packages::default());
+    let (exports, _) = analyzer
+        .analyze_source("main".into(), "main.flux".into(), src)
+        .unwrap_or_else(|err| panic!("{}", err.error.pretty(src)));
+
+    expect![[r#"
+        [
+          {
+            "constraints": [
+              {
+                "kinds": [
+                  "Addable"
+                ],
+                "var": "A"
+              }
+            ],
+            "expr": {
+              "Fun": {
+                "opt": {},
+                "pipe": null,
+                "req": {
+                  "x": {
+                    "Var": 0
+                  },
+                  "y": {
+                    "Var": 0
+                  }
+                },
+                "retn": {
+                  "Var": 0
+                }
+              }
+            },
+            "name": "f",
+            "type": "(x: A, y: A) => A where A: Addable",
+            "vars": [
+              {
+                "id": 0,
+                "name": "A"
+              }
+            ]
+          },
+          {
+            "constraints": [],
+            "expr": "String",
+            "name": "s",
+            "type": "string",
+            "vars": []
+          }
+        ]"#]]
+    .assert_eq(&serde_json::to_string_pretty(&Environment::from(&exports).to_json()).unwrap());
+}
+
+#[test]
+fn analyze_recovered_package() {
+    let (pkg, errors) = crate::parser::parse_with_recovery("a = 1\nb = @ 2\nc = a + 1\n");
+    assert_eq!(1, errors.len());
+
+    let mut analyzer = Analyzer::new_with_defaults(Environment::default(), Packages::default());
+    let err = analyzer.analyze_ast(&pkg).unwrap_err();
+    let (exports, _) = err.value.expect("the package is salvaged");
+    assert_eq!("int", exports.lookup("a").unwrap().to_string());
+    assert_eq!("int", exports.lookup("c").unwrap().to_string());
+}
+
+#[test]
+fn trace_inference_of_expression() {
+    let src = "a = 1\nb = a + 1.0\nc = a + 2\n";
+    let span = ast::SourceLocation {
+        file: Some("main.flux".to_string()),
+        start: ast::Position { line: 2, column: 1 },
+        end: ast::Position {
+            line: 2,
+            column: 12,
+        },
+        source: None,
+    };
+    let mut analyzer = Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        AnalyzerConfig {
+            trace: Some(span.clone()),
+            ..AnalyzerConfig::default()
+        },
+    );
+    let _ = analyzer.analyze_source("main".to_string(), "main.flux".to_string(), src);
+
+    let steps = analyzer.trace();
+    assert!(!steps.is_empty());
+    for step in steps {
+        let loc = match step {
+            TraceStep::Kind { loc, .. } | TraceStep::Equal { loc, .. } => loc,
+        };
+        assert_eq!(2, loc.start.line, "{:?}", step);
+    }
+    assert!(
+        steps.iter().any(|step| matches!(
+            step,
+            TraceStep::Equal { exp, act, errors, .. }
+                if *exp == MonoType::INT && *act == MonoType::FLOAT && !errors.is_empty()
+        )),
+        "{:#?}",
+        steps
+    );
+
+    // Nothing is recorded by default
+    let mut analyzer = Analyzer::new_with_defaults(Environment::default(), Packages::default());
+    let _ = analyzer.analyze_source("main".to_string(), "main.flux".to_string(), src);
+    assert!(analyzer.trace().is_empty());
+}
+
+#[test]
+fn builtins_from_config() {
+    let config = AnalyzerConfig::default().with_builtins(vec![(
+        "double".to_string(),
+        parse_polytype("(x: A) => A where A: Numeric").unwrap(),
+    )]);
+    let mut analyzer = Analyzer::new(Environment::default(), Packages::default(), config);
+    let (exports, _) = analyzer
+        .analyze_source(
+            "main".to_string(),
+            "main.flux".to_string(),
+            "x = double(x: 2.0)",
+        )
+        .map_err(|err| err.error.pretty("x = double(x: 2.0)"))
+        .unwrap();
+    assert_eq!("float", exports.lookup("x").unwrap().to_string());
+
+    // Values of the environment shadow the builtins
+    let config = AnalyzerConfig::default()
+        .with_builtins(vec![("x".to_string(), parse_polytype("int").unwrap())]);
+    let mut analyzer = Analyzer::new(
+        Environment::from(parse_map(None, map!["x" => "string"])),
+        Packages::default(),
+        config,
+    );
+    let (exports, _) = analyzer
+        .analyze_source("main".to_string(), "main.flux".to_string(), "y = x")
+        .map_err(|err| err.error.pretty("y = x"))
+        .unwrap();
+    assert_eq!("string", exports.lookup("y").unwrap().to_string());
+}
+
+#[test]
+fn parse_polytype_errors() {
+    let err = parse_polytype("(x: A) =>").unwrap_err();
+    assert!(err.errors.has_errors(), "{}", err);
+
+    let err = parse_polytype("int int").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("expected end of type expression, got int"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn parse_type_errors() {
+    assert_eq!(
+        "(x: A, y: A) => A where A: Addable",
+        parse_type("(x: A, y: A) => A where A: Addable")
+            .unwrap()
+            .to_string()
+    );
+
+    // Unbalanced parentheses
+    assert!(parse_type("(x: A, y: A => A").is_err());
+    assert!(parse_type("(x: A) => A)").is_err());
+
+    let err = parse_type("(x: A) => A where A: Sortable").unwrap_err();
+    assert_eq!("invalid constraint Sortable", err.error.to_string());
+}
+
+#[test]
+fn check_returns_the_errors_of_analyze() {
+    let src = r#"
+        x = 1 + "a"
+        f = (r) => r.a
+        y = f(r: {b: 1})
+        z = undefined
+        m = match 1 { 1 => 2 }
+    "#;
+    for deny_warnings in [false, true] {
+        let mut analyzer = Analyzer::new(
+            Environment::default(),
+            Packages::default(),
+            AnalyzerConfig {
+                features: vec![semantic::Feature::PatternMatching],
+                deny_warnings,
+                ..AnalyzerConfig::default()
+            },
+        );
+        let errors = analyzer.check("main.flux".to_string(), src).unwrap_err();
+        assert!(errors.len() > 1, "{:?}", errors);
+
+        let analyzed = analyzer
+            .analyze_source("main".to_string(), "main.flux".to_string(), src)
+            .unwrap_err();
+        assert_eq!(Vec::from(analyzed.error.errors), errors);
+
+        assert_eq!(
+            Ok(()),
+            analyzer.check("main.flux".to_string(), "x = 1\ny = x + 2")
+        );
+    }
+}
+
+#[test]
+fn deny_warnings() {
+    let src = r#"
+        x = 1
+        f = (x) => x
+    "#;
+    let mut analyzer = Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        AnalyzerConfig::default(),
+    );
+    analyzer
+        .analyze_source("main".to_string(), "main.flux".to_string(), src)
+        .unwrap();
+    // Shadowing is only reported with `Feature::ShadowingDiagnostics`
+    assert!(analyzer.warnings().is_empty());
+
+    let mut analyzer = Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        AnalyzerConfig {
+            features: vec![semantic::Feature::ShadowingDiagnostics],
+            ..AnalyzerConfig::default()
+        },
+    );
+    analyzer
+        .analyze_source("main".to_string(), "main.flux".to_string(), src)
+        .unwrap();
+    assert_eq!(analyzer.warnings().len(), 1);
+
+    let mut analyzer = Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        AnalyzerConfig {
+            features: vec![semantic::Feature::ShadowingDiagnostics],
+            deny_warnings: true,
+            ..AnalyzerConfig::default()
+        },
+    );
+    let err = analyzer
+        .analyze_source("main".to_string(), "main.flux".to_string(), src)
+        .unwrap_err();
+    assert!(analyzer.warnings().is_empty());
+    expect_test::expect![[r#"
+        error: x shadows the binding declared at 2:9
+          ┌─ main:3:14
+          │
+        2 │         x = 1
+          │         - originally declared here
+        3 │         f = (x) => x
+          │              ^
+
+    "#]]
+    .assert_eq(&err.error.pretty(src));
+}
+
+#[test]
+fn deny_unused_warnings() {
+    let src = r#"
+        x = 1
+        y = 2
+        z = y
+    "#;
+    let config = AnalyzerConfig {
+        features: vec![semantic::Feature::UnusedDiagnostics],
+        ..AnalyzerConfig::default()
+    };
+    let mut analyzer = Analyzer::new(Environment::default(), Packages::default(), config.clone());
+    analyzer
+        .analyze_source("main".to_string(), "main.flux".to_string(), src)
+        .unwrap();
+    assert_eq!(analyzer.warnings().len(), 2);
+
+    let mut analyzer = Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        AnalyzerConfig {
+            deny_warnings: true,
+            ..config
+        },
+    );
+    let err = analyzer
+        .analyze_source("main".to_string(), "main.flux".to_string(), src)
+        .unwrap_err();
+    assert!(analyzer.warnings().is_empty());
+    expect_test::expect![[r#"
+        error: x is declared but never used
+          ┌─ main:2:9
+          │
+        2 │         x = 1
+          │         ^
+
+        error: z is declared but never used
+          ┌─ main:4:9
+          │
+        4 │         z = y
+          │         ^
+
+    "#]]
+    .assert_eq(&err.error.pretty(src));
+}
+
+#[test]
+fn ambiguous_types_are_reported_as_warnings() {
+    let src = r#"
+            x = describe(v: [][0])
+            y = describe(v: 1)
+            z = [{ a: 1 }] |> fill(value: "x")
+            f = (r) => r |> fill(value: describe(v: r))
+        "#;
+    let mut analyzer = Analyzer::new(
+        Environment::from(parse_map(
+            None,
+            map![
+                "describe" => "(v: A) => string where A: Stringable",
+                "fill" => "(<-tables: [{ A with B: C }], ?value: D) => [{ A with B: D }]
+                    where B: Label",
+            ],
+        )),
+        Packages::default(),
+        AnalyzerConfig {
+            features: vec![semantic::Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+    );
+    analyzer
+        .analyze_source("main".into(), "".into(), src)
+        .map_err(|err| err.error.pretty(src))
+        .unwrap();
+
+    expect![[r#"
+        warning @2:13-2:35: the type of `x` is ambiguous, it depends on a type constrained to Stringable which is never determined
+
+        warning @4:13-4:47: the type of `z` is ambiguous, it depends on a type constrained to Label which is never determined"#]]
+    .assert_eq(&analyzer.warnings().to_string());
+}
+
+#[test]
+fn unused_bindings_are_reported_as_warnings() {
+    let src = r#"
+            x = 1
+            f = (a, _b) => {
+                c = a
+                return 0
+            }
+        "#;
+    let mut analyzer = Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        AnalyzerConfig {
+            features: vec![semantic::Feature::UnusedDiagnostics],
+            ..AnalyzerConfig::default()
+        },
+    );
+    analyzer
+        .analyze_source("main".into(), "".into(), src)
+        .map_err(|err| err.error.pretty(src))
+        .unwrap();
+
+    expect![[r#"
+        warning @4:17-4:18: c is declared but never used
+
+        warning @2:13-2:14: x is declared but never used
+
+        warning @3:13-3:14: f is declared but never used"#]]
+    .assert_eq(&analyzer.warnings().to_string());
+}
+
+#[test]
+fn unused_pipe_parameters_are_reported_as_warnings() {
+    let src = r#"
+            f = (tables=<-) => tables
+            g = (tables=<-, n) => n + 1
+            h = (rows=<-) => 0
+            k = (tables=<-) => () => tables
+        "#;
+    let mut analyzer = Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        AnalyzerConfig::default(),
+    );
+    analyzer
+        .analyze_source("main".into(), "".into(), src)
+        .map_err(|err| err.error.pretty(src))
+        .unwrap();
+
+    expect![[r#"
+        warning @3:18-3:27: the pipe parameter `tables` is never used
+
+        warning @4:18-4:25: the pipe parameter `rows` is never used"#]]
+    .assert_eq(&analyzer.warnings().to_string());
+}
+
+#[test]
+fn numeric_widening() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![semantic::Feature::NumericWidening],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "sqrt" => "(x: float) => float",
+        ],
+        src: r#"
+            a = 1 + 2.0
+            b = 2.0 * 3
+            c = 1.5 < 2
+            d = [1.5, 2, 3]
+            e = sqrt(x: 4)
+            f = 1 + 2
+            g = [2, 1.5]
+            h = [1.5, 2]
+        "#,
+        exp: map![
+            "a" => "float",
+            "b" => "float",
+            "c" => "bool",
+            "d" => "[float]",
+            "e" => "float",
+            "f" => "int",
+            "g" => "[float]",
+            "h" => "[float]",
+        ],
+    }
+}
+
+#[test]
+fn numeric_widening_only_widens_literals() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![semantic::Feature::NumericWidening],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            x = 1
+            a = x + 2.0
+            b = 2.0 + x
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:21: error: expected int but found float
+            main:4:23: error: expected float but found int
+        "#]]
+    }
+}
+
+#[test]
+fn numeric_widening_disabled() {
+    test_error_msg! {
+        src: r#"
+            a = 1 + 2.0
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:21: error: expected int but found float
+        "#]]
+    }
+}
+
+#[test]
+fn fresher_seed() {
+    let src = "f = (x, y) => ({x: x, y: y})";
+    let analyze = |seed| {
+        let mut analyzer = Analyzer::new(
+            Environment::default(),
+            Packages::default(),
+            AnalyzerConfig {
+                fresher_seed: seed,
+                ..AnalyzerConfig::default()
+            },
+        );
+        let (exports, pkg) = analyzer
+            .analyze_source("main".into(), "".into(), src)
+            .map_err(|err| err.error.pretty(src))
+            .unwrap();
+        let typ = match &pkg.files[0].body[0] {
+            semantic::nodes::Statement::Variable(assgn) => assgn.init.type_of(),
+            stmt => panic!("expected a variable assignment, got {:?}", stmt),
+        };
+        (exports, typ)
+    };
+
+    let (exports, typ) = analyze(1000);
+    let vars = typ.free_vars();
+    assert!(!vars.is_empty());
+    assert!(
+        vars.iter().all(|var| var.0 >= 1000),
+        "expected variables starting at the seed, got {}",
+        typ
+    );
+    assert_eq!(
+        typ,
+        analyze(1000).1,
+        "the same seed gives the same variables"
+    );
+    assert_ne!(typ, analyze(0).1);
+
+    // The variables before the seed are not created, so large seeds are as cheap as small ones
+    let seed = 1 << 40;
+    let (_, typ) = analyze(seed);
+    assert!(
+        typ.free_vars().iter().all(|var| var.0 >= seed),
+        "expected variables starting at the seed, got {}",
+        typ
+    );
+
+    // Type variables are still displayed in the order they appear
+    assert_eq!(
+        "(x: A, y: B) => {x: A, y: B}",
+        exports.lookup("f").unwrap().to_string()
+    );
+}
+
+#[test]
+fn analyze_expr() {
+    let mut analyzer = Analyzer::new(
+        Environment::from(parse_map(None, map!["x" => "int"])),
+        Packages::default(),
+        AnalyzerConfig::default(),
+    );
+
+    let expr = analyzer
+        .analyze_expr("x + 1", parse_polytype("int").unwrap())
+        .unwrap();
+    assert_eq!(MonoType::INT, expr.type_of());
+
+    let expr = analyzer
+        .analyze_expr("(v) => v", parse_polytype("(v: int) => int").unwrap())
+        .unwrap();
+    assert_eq!("(v: int) => int", expr.type_of().to_string());
+
+    let err = analyzer
+        .analyze_expr("x + 1", parse_polytype("string").unwrap())
+        .unwrap_err();
+    expect![[r#"error @1:1-1:6: expected string but found int"#]].assert_eq(&err.to_string());
+
+    let err = analyzer
+        .analyze_expr("\n  x + y", parse_polytype("int").unwrap())
+        .unwrap_err();
+    expect![[r#"error @2:7-2:8: undefined identifier y"#]].assert_eq(&err.to_string());
+}
@@ -0,0 +1,135 @@
+use super::*;
+
+use crate::semantic::Feature;
+
+#[test]
+fn array_concatenation() {
+    test_infer! {
+        env: map![],
+        src: r#"
+            a = [1, 2] + [3]
+            b = [[1], [2]] + [[3, 4]]
+            c = [] + ["a"]
+            f = (x) => x + [1.0]
+            g = (x, y) => [x] + y
+            h = (x, y) => x + y
+            i = h(x: [1], y: [2])
+        "#,
+        exp: map![
+            "a" => "[int]",
+            "b" => "[[int]]",
+            "c" => "[string]",
+            "f" => "(x: [float]) => [float]",
+            "g" => "(x: A, y: [A]) => [A]",
+            "h" => "(x: A, y: A) => A where A: Addable",
+            "i" => "[int]",
+        ],
+    }
+}
+
+#[test]
+fn array_concatenation_with_labels() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "columns" => "[string]",
+        ],
+        src: r#"
+            x = columns + ["_value"]
+        "#,
+        exp: map![
+            "x" => "[string]",
+        ],
+    }
+}
+
+#[test]
+fn invalid_array_concatenation() {
+    test_error_msg! {
+        src: r#"
+            x = [1] + ["a"]
+            y = [[1]] + [1]
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:23: error: expected int but found string
+            main:3:25: error: expected [int] but found int
+        "#]]
+    }
+}
+
+// `x` is generalized to `[A]` like any other binding, so each use of it gets its own element
+// type and `y` and `z` don't conflict. The element types only conflict when the same instance of
+// the empty array is used with both, as in `empty_array_used_with_different_element_types`.
+#[test]
+fn empty_array() {
+    test_infer! {
+        env: map![
+            "sum" => "(<-values: [int]) => int",
+        ],
+        src: r#"
+            x = []
+            y = x + [1]
+            z = x + ["a"]
+            s = [] |> sum()
+        "#,
+        exp: map![
+            "x" => "[A]",
+            "y" => "[int]",
+            "z" => "[string]",
+            "s" => "int",
+        ],
+    }
+}
+
+#[test]
+fn empty_array_used_with_different_element_types() {
+    test_error_msg! {
+        src: r#"
+            f = (x) => {
+                y = x + [1]
+                return x + ["a"]
+            }
+            z = [] + [1] + ["a"]
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:4:28: error: expected int but found string
+            main:6:28: error: expected int but found string
+        "#]]
+    }
+}
+
+#[test]
+fn array_spread() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::SpreadOperator],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "xs" => "[int]",
+        ],
+        src: r#"
+            a = [...xs, 4]
+            b = [0, ...xs, ...xs]
+            f = (ys) => [...ys]
+            g = (ys, zs) => [...ys, 1, ...zs]
+        "#,
+        exp: map![
+            "a" => "[int]",
+            "b" => "[int]",
+            "f" => "(ys: [A]) => [A]",
+            "g" => "(ys: [int], zs: [int]) => [int]",
+        ],
+    }
+    test_error_msg! {
+        src: r#"
+            x = [...[1], 2]
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:18: error: spread expressions are not enabled
+        "#]]
+    }
+}
@@ -0,0 +1,104 @@
+use super::*;
+
+use crate::semantic::Feature;
+
+fn config() -> AnalyzerConfig {
+    AnalyzerConfig {
+        features: vec![Feature::BlockExpressions],
+        ..AnalyzerConfig::default()
+    }
+}
+
+#[test]
+fn block_expression_has_the_type_of_its_trailing_expression() {
+    test_infer! {
+        config: config(),
+        env: map![],
+        src: r#"
+            x = { a = 1; a + 1 }
+            y = {
+                s = "a"
+                t = s + "b"
+                t == "ab"
+            }
+        "#,
+        exp: map![
+            "x" => "int",
+            "y" => "bool",
+        ],
+    }
+}
+
+#[test]
+fn block_expression_bindings_are_local() {
+    test_infer! {
+        config: config(),
+        env: map![],
+        src: r#"
+            a = "outer"
+            x = { a = 1; a + 1 }
+            y = a
+        "#,
+        exp: map![
+            "a" => "string",
+            "x" => "int",
+            "y" => "string",
+        ],
+    }
+}
+
+#[test]
+fn block_expression_checks_its_statements() {
+    test_error_msg! {
+        config: config(),
+        env: map![],
+        src: r#"
+            x = { a = 1 + "s"; 2 }
+        "#,
+        expect: expect_test::expect![[r#"
+            error: expected int but found string
+              ┌─ main:2:27
+              │
+            2 │             x = { a = 1 + "s"; 2 }
+              │                           ^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn block_expression_without_value() {
+    test_error_msg! {
+        config: config(),
+        env: map![],
+        src: r#"
+            x = { a = 1 }
+        "#,
+        expect: expect_test::expect![[r#"
+            error: block has no value
+              ┌─ main:2:17
+              │
+            2 │             x = { a = 1 }
+              │                 ^^^^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn block_expressions_disabled() {
+    test_error_msg! {
+        env: map![],
+        src: r#"
+            x = { a = 1; a + 1 }
+        "#,
+        expect: expect_test::expect![[r#"
+            error: block expressions are not enabled
+              ┌─ main:2:17
+              │
+            2 │             x = { a = 1; a + 1 }
+              │                 ^^^^^^^^^^^^^^^^
+
+        "#]],
+    }
+}
@@ -0,0 +1,51 @@
+use super::*;
+use crate::semantic::call_graph::call_graph;
+
+#[test]
+fn call_graph_of_top_level_functions() {
+    let src = r#"
+import "strings"
+
+double = (x) => x * 2
+upper = strings.toUpper
+shout = (v) => upper(v: v) + strings.repeat(v: "!", i: double(x: 2))
+apply = (f, v) => f(v: v)
+nested = (table=<-) => {
+    inner = (r) => ({r with _value: double(x: r._value)})
+
+    return table |> map(fn: inner)
+}
+x = 1
+"#;
+    let (_, pkg) = infer_types(
+        src,
+        map![
+            "map" => "(<-tables: [A], fn: (r: A) => B) => [B]",
+        ],
+        map![
+            "strings" => package![
+                "toUpper" => "(v: string) => string",
+                "repeat" => "(v: string, i: int) => string",
+            ],
+        ],
+        None,
+        AnalyzerConfig::default(),
+    )
+    .unwrap_or_else(|err| panic!("{}", err.pretty(src)));
+
+    let graph = call_graph(&pkg);
+    let calls = |name: &str| graph[name].iter().map(|s| s.as_str()).collect::<Vec<_>>();
+
+    assert_eq!(4, graph.len());
+    assert_eq!(Vec::<&str>::new(), calls("double"));
+    assert_eq!(
+        vec!["double", "strings.repeat", "strings.toUpper"],
+        calls("shout")
+    );
+    assert_eq!(
+        Vec::<&str>::new(),
+        calls("apply"),
+        "calls to parameters are not resolved"
+    );
+    assert_eq!(vec!["double", "map"], calls("nested"));
+}
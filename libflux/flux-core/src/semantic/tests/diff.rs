@@ -0,0 +1,79 @@
+use super::*;
+
+fn env(bindings: &[(&str, &str)]) -> Environment<'static> {
+    Environment::from(
+        bindings
+            .iter()
+            .map(|(name, typ)| (name.to_string(), parse_type(typ).unwrap()))
+            .collect::<SemanticMap<String, PolyType>>(),
+    )
+}
+
+fn names(bindings: &[(String, PolyType)]) -> Vec<&str> {
+    bindings.iter().map(|(name, _)| &name[..]).collect()
+}
+
+#[test]
+fn diff_added_and_removed() {
+    let old = env(&[("a", "int"), ("b", "string")]);
+    let new = env(&[("b", "string"), ("c", "float")]);
+
+    let diff = old.diff(&new);
+    assert_eq!(vec!["c"], names(&diff.added));
+    assert_eq!(vec!["a"], names(&diff.removed));
+    assert!(diff.changed.is_empty());
+    assert!(diff.is_breaking());
+
+    let diff = new.diff(&old);
+    assert_eq!(vec!["a"], names(&diff.added));
+    assert_eq!(vec!["c"], names(&diff.removed));
+}
+
+#[test]
+fn diff_renamed_type_variables() {
+    let old = env(&[("f", "(x: A, y: B) => A where A: Addable")]);
+    let new = env(&[("f", "(x: T, y: U) => T where T: Addable")]);
+
+    let diff = old.diff(&new);
+    assert_eq!(EnvDiff::default(), diff);
+    assert!(!diff.is_breaking());
+}
+
+#[test]
+fn diff_changed_types() {
+    let old = env(&[
+        ("generalized", "(x: int) => int"),
+        ("unconstrained", "(x: A) => A where A: Addable"),
+        ("specialized", "(x: A) => A"),
+        ("merged", "(x: A, y: B) => A"),
+        ("constrained", "(x: A) => A"),
+        ("retyped", "int"),
+    ]);
+    let new = env(&[
+        ("generalized", "(x: A) => A"),
+        ("unconstrained", "(x: A) => A"),
+        ("specialized", "(x: int) => int"),
+        ("merged", "(x: A, y: A) => A"),
+        ("constrained", "(x: A) => A where A: Addable"),
+        ("retyped", "string"),
+    ]);
+
+    let diff = old.diff(&new);
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(
+        vec![
+            ("constrained", true),
+            ("generalized", false),
+            ("merged", true),
+            ("retyped", true),
+            ("specialized", true),
+            ("unconstrained", false),
+        ],
+        diff.changed
+            .iter()
+            .map(|change| (&change.name[..], change.breaking))
+            .collect::<Vec<_>>()
+    );
+    assert!(diff.is_breaking());
+}
@@ -0,0 +1,141 @@
+use super::*;
+use crate::semantic::{
+    fold::fold_constants,
+    import::Packages,
+    nodes::{Block, Expression, Statement},
+    WarningKind,
+};
+
+// Returns the initializers of the top level variables of `src` after folding constants.
+fn fold(src: &str) -> Vec<Expression> {
+    let mut analyzer = Analyzer::new(
+        Default::default(),
+        Packages::default(),
+        AnalyzerConfig::default(),
+    );
+    let (_, mut pkg) = analyzer
+        .analyze_source("main".into(), "".into(), src)
+        .unwrap();
+    fold_constants(&mut pkg);
+
+    pkg.files
+        .remove(0)
+        .body
+        .into_iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Variable(assgn) => Some(assgn.init),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn fold_literal_arithmetic() {
+    let inits = fold(
+        r#"
+a = 1 + 2 * 3
+b = "a" + "b"
+c = 2.0 * 1.5
+d = (x) => x + (10 - 4)
+"#,
+    );
+
+    match &inits[0] {
+        Expression::Integer(lit) => {
+            assert_eq!(lit.value, 7);
+            assert_eq!(
+                (lit.loc.start.column, lit.loc.end.column),
+                (5, 14),
+                "the folded literal keeps the location of the expression"
+            );
+        }
+        expr => panic!("expected an integer, got {:?}", expr),
+    }
+    assert!(matches!(&inits[1], Expression::StringLit(lit) if lit.value == "ab"));
+    assert!(matches!(&inits[2], Expression::Float(lit) if lit.value == 3.0));
+
+    let body = match &inits[3] {
+        Expression::Function(function) => &function.body,
+        expr => panic!("expected a function, got {:?}", expr),
+    };
+    match body {
+        Block::Return(ret) => match &ret.argument {
+            Expression::Binary(binary) => {
+                assert!(matches!(binary.left, Expression::Identifier(_)));
+                assert!(matches!(&binary.right, Expression::Integer(lit) if lit.value == 6));
+            }
+            expr => panic!("expected a binary expression, got {:?}", expr),
+        },
+        block => panic!("expected a return statement, got {:?}", block),
+    }
+}
+
+#[test]
+fn fold_leaves_failing_expressions() {
+    let inits = fold(
+        r#"
+a = 1 / 0
+b = 9223372036854775807 + 1
+c = 1.0 % 0.0
+"#,
+    );
+
+    for init in &inits {
+        assert!(
+            matches!(init, Expression::Binary(_)),
+            "expected the expression to be left unfolded, got {:?}",
+            init
+        );
+    }
+}
+
+#[test]
+fn unreachable_branches_are_reported_as_warnings() {
+    let src = r#"
+a = if true then 1 else 2
+b = if 1 + 1 < 2 then "x" else "y"
+c = (x) => if x > 0 then x else 0
+d = (x) => if not false and x then 1 else 0
+"#;
+    let mut analyzer = Analyzer::new(
+        Environment::from(parse_map(None, map!["true" => "bool", "false" => "bool"])),
+        Packages::default(),
+        AnalyzerConfig::default(),
+    );
+    analyzer
+        .analyze_source("main".into(), "".into(), src)
+        .map_err(|err| err.error.pretty(src))
+        .unwrap();
+
+    expect_test::expect![[r#"
+        warning @2:25-2:26: this branch is unreachable, the condition is always true
+
+        warning @3:23-3:26: this branch is unreachable, the condition is always false"#]]
+    .assert_eq(&analyzer.warnings().to_string());
+}
+
+#[test]
+fn shadowed_booleans_are_not_constant() {
+    let src = r#"
+f = (x) => {
+    true = x
+    return if true then 1 else 2
+}
+"#;
+    let mut analyzer = Analyzer::new(
+        Environment::from(parse_map(None, map!["true" => "bool", "false" => "bool"])),
+        Packages::default(),
+        AnalyzerConfig::default(),
+    );
+    analyzer
+        .analyze_source("main".into(), "".into(), src)
+        .map_err(|err| err.error.pretty(src))
+        .unwrap();
+
+    let unreachable: Vec<_> = analyzer
+        .warnings()
+        .iter()
+        .filter(|warning| matches!(warning.error, WarningKind::Unreachable(_)))
+        .collect();
+    assert!(unreachable.is_empty(), "{:?}", unreachable);
+}
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+
+use super::*;
+use crate::semantic::{
+    import::{ImportResolver, PackageSource, Packages},
+    Feature,
+};
+
+// Resolves packages from their source code, as if they were read from a virtual filesystem.
+struct Sources(SemanticMap<String, String>);
+
+impl ImportResolver for Sources {
+    fn resolve(&self, path: &str) -> Result<PackageSource> {
+        self.0
+            .get(path)
+            .cloned()
+            .map(PackageSource::Flux)
+            .ok_or_else(|| anyhow!("no such file"))
+    }
+}
+
+fn strict_config(sources: &[(&str, &str)]) -> AnalyzerConfig {
+    AnalyzerConfig {
+        features: vec![Feature::StrictPackageExports],
+        ..config(sources)
+    }
+}
+
+fn config(sources: &[(&str, &str)]) -> AnalyzerConfig {
+    AnalyzerConfig {
+        import_resolver: Some(Arc::new(Sources(
+            sources
+                .iter()
+                .map(|(path, src)| (path.to_string(), src.to_string()))
+                .collect(),
+        ))),
+        ..AnalyzerConfig::default()
+    }
+}
+
+#[test]
+fn imports_are_resolved_from_source() {
+    test_infer! {
+        config: config(&[
+            ("a", "package a\nimport \"b\"\nx = b.y + 1"),
+            ("b", "package b\ny = 2"),
+        ]),
+        imp: map![
+            "strings" => package![
+                "toUpper" => "(v: string) => string",
+            ],
+        ],
+        src: r#"
+            import "a"
+            import "strings"
+
+            x = a.x
+            s = strings.toUpper(v: "a")
+        "#,
+        exp: map![
+            "x" => "int",
+            "s" => "string",
+        ],
+    }
+}
+
+#[test]
+fn resolved_exports_take_precedence_over_the_importer() {
+    let resolver: Packages = vec![(
+        "strings".to_string(),
+        PackageExports::try_from(parse_map(Some("strings"), map!["toUpper" => "int"])).unwrap(),
+    )]
+    .into_iter()
+    .collect();
+    test_infer! {
+        config: AnalyzerConfig {
+            import_resolver: Some(Arc::new(resolver)),
+            ..AnalyzerConfig::default()
+        },
+        imp: map![
+            "strings" => package![
+                "toUpper" => "(v: string) => string",
+            ],
+        ],
+        src: r#"
+            import "strings"
+
+            x = strings.toUpper
+        "#,
+        exp: map![
+            "x" => "int",
+        ],
+    }
+}
+
+#[test]
+fn unresolved_imports() {
+    test_error_msg! {
+        config: config(&[]),
+        src: r#"
+            import "c"
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:13: error: cannot resolve import path c: no such file
+        "#]]
+    }
+}
+
+#[test]
+fn cyclic_imports_are_unresolved() {
+    let src = r#"import "a""#;
+    let mut analyzer = Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        config(&[
+            ("a", "package a\nimport \"b\""),
+            ("b", "package b\nimport \"a\""),
+        ]),
+    );
+    let err = analyzer
+        .analyze_source("main".into(), "".into(), src)
+        .unwrap_err();
+    assert_eq!(1, err.error.errors.len(), "{}", err.error);
+    let msg = err.error.to_string();
+    assert!(
+        msg.contains(r#"package "a" depends on itself: a -> b -> a"#),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn strict_package_exports() {
+    test_infer! {
+        config: AnalyzerConfig {
+            features: vec![
+                Feature::StrictPackageExports,
+                Feature::DestructuringAssignment,
+            ],
+            ..config(&[(
+                "a",
+                "package a\ny = 2\nexport x = y + 1\nexport {z} = {z: \"z\"}",
+            )])
+        },
+        src: r#"
+            import "a"
+
+            export x = a.x
+            export z = a.z
+            hidden = 1
+            option now = () => 2020-01-01T00:00:00Z
+        "#,
+        exp: map![
+            "x" => "int",
+            "z" => "string",
+            "now" => "() => time",
+        ],
+    }
+}
+
+#[test]
+fn everything_is_exported_by_default() {
+    test_infer! {
+        config: config(&[("a", "package a\ny = 2\nexport x = y + 1")]),
+        src: r#"
+            import "a"
+
+            export x = a.x
+            y = a.y
+        "#,
+        exp: map![
+            "x" => "int",
+            "y" => "int",
+        ],
+    }
+}
+
+#[test]
+fn private_members_are_not_importable() {
+    test_error_msg! {
+        config: strict_config(&[("a", "package a\ny = 2\nexport x = y + 1")]),
+        src: r#"
+            import "a"
+
+            y = a.y
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:4:17: error: y is private to package a
+        "#]]
+    }
+}
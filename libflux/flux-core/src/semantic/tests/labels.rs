@@ -65,19 +65,27 @@ fn labels_dynamic_string() {
         src: r#"
             column = "" + "a"
             x = [{ a: 1 }] |> fill(column: column, value: "x")
+            y = [{ a: 1 }] |> fill(column: "a" + "b", value: "x")
+            z = [{ a: 1 }] |> fill(column: 1, value: "x")
         "#,
         expect: expect![[r#"
-            error: string is not Label (argument column)
+            error: expected a literal label but found a string computed at runtime, column names must be literal labels: use a literal such as "_value" or a function which accepts dynamic columns (argument column)
               ┌─ main:3:44
               │
             3 │             x = [{ a: 1 }] |> fill(column: column, value: "x")
               │                                            ^^^^^^
 
-            error: string is not a label
-              ┌─ main:3:31
+            error: expected a literal label but found a string computed at runtime, column names must be literal labels: use a literal such as "_value" or a function which accepts dynamic columns (argument column)
+              ┌─ main:4:44
               │
-            3 │             x = [{ a: 1 }] |> fill(column: column, value: "x")
-              │                               ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+            4 │             y = [{ a: 1 }] |> fill(column: "a" + "b", value: "x")
+              │                                            ^^^^^^^^^
+
+            error: int is not Label (argument column)
+              ┌─ main:5:44
+              │
+            5 │             z = [{ a: 1 }] |> fill(column: 1, value: "x")
+              │                                            ^
 
         "#]],
     }
@@ -109,6 +117,82 @@ fn undefined_field() {
     }
 }
 
+#[test]
+fn exhaustive_label_check() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism, Feature::ExhaustiveLabelCheck],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "fill" => "(<-tables: [{ A with B: C }], ?column: B, ?value: D) => [{ A with B: D }]
+                where B: Label
+                "
+        ],
+        src: r#"
+            x = [{ host: 1 }] |> fill(column: "hots", value: "x")
+        "#,
+        expect: expect![[r#"
+            error: record is missing label hots, did you mean "host"?
+              ┌─ main:2:47
+              │
+            2 │             x = [{ host: 1 }] |> fill(column: "hots", value: "x")
+              │                                               ^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn exhaustive_label_check_stream() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism, Feature::ExhaustiveLabelCheck],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "tables" => "stream[{ host: int }]",
+            "fill" => "(<-tables: stream[{ A with B: C }], ?column: B, ?value: D) => stream[{ A with B: D }]
+                where B: Label
+                "
+        ],
+        src: r#"
+            x = tables |> fill(column: "hots", value: "x")
+        "#,
+        expect: expect![[r#"
+            error: record is missing label hots, did you mean "host"?
+              ┌─ main:2:40
+              │
+            2 │             x = tables |> fill(column: "hots", value: "x")
+              │                                        ^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn exhaustive_label_check_open_record() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism, Feature::ExhaustiveLabelCheck],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "fill" => "(<-tables: [{ A with B: C }], ?column: B, ?value: D) => [{ A with B: D }]
+                where B: Label
+                "
+        ],
+        src: r#"
+            f = (tables) => tables |> fill(column: "a", value: "x")
+            x = [{ a: 1 }] |> fill(column: "a", value: "x")
+        "#,
+        exp: map![
+            "f" => "(tables: [{A with a: B}]) => [{A with a: string}]",
+            "x" => "[{ a: string }]",
+        ],
+    }
+}
+
 #[test]
 fn merge_labels_to_string() {
     test_infer! {
@@ -234,3 +318,274 @@ fn optional_label() {
         "#]],
     }
 }
+
+#[test]
+fn drop_label() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "drop" => "(<-tables: [{ A with B: C }], column: B) => [A] where B: Label",
+            "sum" => "(<-tables: [{ b: int }]) => int",
+        ],
+        src: r#"
+            x = [{ a: 1, b: 2 }] |> drop(column: "a")
+            y = x |> sum()
+            f = (r) => [r] |> drop(column: "a")
+        "#,
+        exp: map![
+            "x" => "[{ b: int }]",
+            "y" => "int",
+            "f" => "(r: { A with a: B }) => [A]",
+        ],
+    }
+}
+
+#[test]
+fn access_dropped_label() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "drop" => "(<-tables: [{ A with B: C }], column: B) => [A] where B: Label",
+        ],
+        src: r#"
+            f = (r) => ([r] |> drop(column: "a"))[0].a
+        "#,
+        expect: expect![[r#"
+            error: found unexpected label a
+              ┌─ main:2:24
+              │
+            2 │             f = (r) => ([r] |> drop(column: "a"))[0].a
+              │                        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn rename_label() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "rename" => "(<-tables: [{ A with F: T }], from: F, to: G) => [{ A with G: T }]
+                where F: Label, G: Label
+                ",
+            "same" => "(x: L, y: L) => { L: int } where L: Label",
+        ],
+        src: r#"
+            x = [{ a: 1, c: "c" }] |> rename(from: "a", to: "b")
+            to = "d"
+            y = x |> rename(from: "c", to: to)
+            z = same(x: "a", y: "a")
+        "#,
+        exp: map![
+            "x" => "[{ b: int, c: string }]",
+            "to" => "string",
+            "y" => "[{ b: int, d: string }]",
+            "z" => "{ a: int }",
+        ],
+    }
+
+    // `same` requires both arguments to be the same label
+    let result = infer_types(
+        r#"z = same(x: "a", y: "b")"#,
+        map![
+            "same" => "(x: L, y: L) => { L: int } where L: Label",
+        ],
+        HashMap::default(),
+        None,
+        AnalyzerConfig {
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+    );
+    assert!(
+        result.is_err(),
+        "expected an error, instead program passed type checking"
+    );
+}
+
+#[test]
+fn access_renamed_label() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "rename" => "(<-tables: [{ A with F: T }], from: F, to: G) => [{ A with G: T }]
+                where F: Label, G: Label
+                ",
+        ],
+        src: r#"
+            x = [{ a: 1, c: "c" }] |> rename(from: "a", to: "b")
+            y = x[0].a
+        "#,
+        expect: expect![[r#"
+            error: record is missing label a
+              ┌─ main:3:17
+              │
+            3 │             y = x[0].a
+              │                 ^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn nested_interpolation_as_label() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "fill" => "(<-tables: [{ A with B: C }], ?column: B, ?value: D) => [{ A with B: D }]
+                where B: Label
+                "
+        ],
+        src: r#"
+            r = {c: 1}
+            x = [{ a: 1 }] |> fill(column: "${"${r}"}", value: "x")
+        "#,
+        expect: expect![[r#"
+            error: cannot interpolate {c:int} into string
+              ┌─ main:3:50
+              │
+            3 │             x = [{ a: 1 }] |> fill(column: "${"${r}"}", value: "x")
+              │                                                  ^
+
+            error: expected a literal label but found a string computed at runtime, column names must be literal labels: use a literal such as "_value" or a function which accepts dynamic columns (argument column)
+              ┌─ main:3:44
+              │
+            3 │             x = [{ a: 1 }] |> fill(column: "${"${r}"}", value: "x")
+              │                                            ^^^^^^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn label_set() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "f" => r#"(column: L) => int where L: Label in {"start", "stop", "value"}"#,
+        ],
+        src: r#"
+            x = f(column: "start")
+            y = f(column: "value")
+        "#,
+        exp: map![
+            "x" => "int",
+            "y" => "int",
+        ],
+    }
+}
+
+#[test]
+fn label_not_in_set() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "f" => r#"(column: L) => int where L: Label in {"start", "stop", "value"}"#,
+        ],
+        src: r#"
+            x = f(column: "start")
+            y = f(column: "foo")
+        "#,
+        expect: expect![[r#"
+            error: "foo" is not one of the allowed labels "start", "stop", "value" (argument column)
+              ┌─ main:3:27
+              │
+            3 │             y = f(column: "foo")
+              │                           ^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn named_type_parameters() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism, Feature::NamedTypeParameters],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            builtin fill : (<-tables: [{Row with Col: C}], ?column: Col, ?value: Val) => [{Row with Col: Val}]
+                where Col: Label, Val: Equatable
+            x = [{ a: 1 }] |> fill(column: "a", value: "x")
+        "#,
+        exp: map![
+            "fill" => "(<-tables: [{A with B: C}], ?column: B, ?value: D) => [{A with B: D}] where B: Label, D: Equatable",
+            "x" => "[{ a: string }]",
+        ],
+    }
+}
+
+#[test]
+fn named_type_parameters_are_displayed() {
+    let src = r#"
+        builtin get : (r: {Row with Col: Val}, column: Col) => Val where Col: Label, Val: Equatable
+    "#;
+    let (env, _) = infer_types(
+        src,
+        HashMap::default(),
+        HashMap::default(),
+        None,
+        AnalyzerConfig {
+            features: vec![Feature::LabelPolymorphism, Feature::NamedTypeParameters],
+            ..AnalyzerConfig::default()
+        },
+    )
+    .unwrap_or_else(|err| panic!("{}", err.pretty(src)));
+
+    expect_test::expect![
+        "(column:Col, r:{Row with Col:Val}) => Val where Col: Label, Val: Equatable"
+    ]
+    .assert_eq(&env.lookup("get").unwrap().to_string());
+}
+
+#[test]
+fn named_type_parameters_in_errors() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::LabelPolymorphism, Feature::NamedTypeParameters],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            builtin get : (r: {Row with Col: Val}, column: Col) => Val where Col: Label, Val: Equatable
+            f = (c) => get(r: 1, column: c)
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:31: error: expected {Row with Col:Val} (record) but found int (argument r)
+        "#]]
+    }
+}
+
+#[test]
+fn named_type_parameters_disabled() {
+    test_error_msg! {
+        src: r#"
+            builtin f : (v: Val) => Val where Val: Equatable
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:47: error: named type parameters are not enabled, found type parameter `Val`
+        "#]]
+    }
+}
@@ -0,0 +1,118 @@
+use super::*;
+
+use crate::semantic::Feature;
+
+fn config() -> AnalyzerConfig {
+    AnalyzerConfig {
+        features: vec![Feature::PatternMatching],
+        ..AnalyzerConfig::default()
+    }
+}
+
+#[test]
+fn match_expression_has_the_type_of_its_arms() {
+    test_infer! {
+        config: config(),
+        env: map![
+            "true" => "bool",
+            "false" => "bool",
+        ],
+        src: r#"
+            f = (x) => match x { 1 => "one", 2 => "two", _ => "many" }
+            s = match "a" { "a" => 1.0, "b" => -1.0, _ => 0.0 }
+            g = (b) => match b {
+                true => 1h
+                false => -1h
+            }
+            h = (x, y) => match x { _ => y }
+        "#,
+        exp: map![
+            "f" => "(x: int) => string",
+            "s" => "float",
+            "g" => "(b: bool) => duration",
+            "h" => "(x: A, y: B) => B",
+        ],
+    }
+}
+
+#[test]
+fn match_expression_errors() {
+    test_error_msg! {
+        config: config(),
+        env: map![],
+        src: r#"
+            x = match 1 { 1 => "one", _ => 2 }
+            y = match 1 { "a" => 1, _ => 2 }
+            z = match 1 { 1 + 1 => 1, _ => 0 }
+        "#,
+        expect: expect_test::expect![[r#"
+            error: expected string but found int
+              ┌─ main:2:44
+              │
+            2 │             x = match 1 { 1 => "one", _ => 2 }
+              │                                            ^
+
+            error: expected int but found string
+              ┌─ main:3:27
+              │
+            3 │             y = match 1 { "a" => 1, _ => 2 }
+              │                           ^^^
+
+            error: invalid match pattern, expected a literal or `_`
+              ┌─ main:4:27
+              │
+            4 │             z = match 1 { 1 + 1 => 1, _ => 0 }
+              │                           ^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn match_expressions_are_opt_in() {
+    test_error_msg! {
+        src: r#"
+            x = match 1 { _ => 2 }
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:17: error: match expressions are not enabled
+        "#]]
+    }
+}
+
+#[test]
+fn non_exhaustive_match_on_bool() {
+    let src = r#"
+            a = (b) => match b { true => 1 }
+            c = (b) => match b { true => 1, false => 0 }
+            d = (b) => match b { false => 1, _ => 0 }
+        "#;
+    let mut analyzer = Analyzer::new(
+        Environment::from(parse_map(None, map!["true" => "bool", "false" => "bool"])),
+        Packages::default(),
+        config(),
+    );
+    analyzer
+        .analyze_source("main".into(), "".into(), src)
+        .map_err(|err| err.error.pretty(src))
+        .unwrap();
+
+    expect_test::expect![[r#"warning @2:24-2:45: match on a bool has no arm for `false`"#]]
+        .assert_eq(&analyzer.warnings().to_string());
+}
+
+#[test]
+fn match_on_other_types_needs_a_wildcard() {
+    test_error_msg! {
+        config: config(),
+        env: map![],
+        src: r#"
+            x = match 1 { 1 => "one", 2 => "two" }
+            f = (s) => match s { "a" => 1 }
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:17: error: match on int has no wildcard arm `_`
+            main:3:24: error: match on string has no wildcard arm `_`
+        "#]]
+    }
+}
@@ -0,0 +1,151 @@
+use super::*;
+
+use crate::semantic::Feature;
+
+#[test]
+fn nulls_unchecked_by_default() {
+    test_infer! {
+        src: r#"
+            f = (r) => r._value * 2
+        "#,
+        exp: map![
+            "f" => "(r: {A with _value: int}) => int",
+        ],
+    }
+}
+
+#[test]
+fn nulls_unguarded_arithmetic() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::StrictNullChecks],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = (r) => r._value * 2
+        "#,
+        expect: expect_test::expect![[r#"
+            error: r._value may be null, check it with `exists` or provide a default with `??` before using it in arithmetic
+              ┌─ main:2:24
+              │
+            2 │             f = (r) => r._value * 2
+              │                        ^^^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn nulls_unguarded_negation() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::StrictNullChecks],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = (r) => if exists r.a then -r.b else 0
+        "#,
+        expect: expect_test::expect![[r#"
+            error: r.b may be null, check it with `exists` or provide a default with `??` before using it in arithmetic
+              ┌─ main:2:44
+              │
+            2 │             f = (r) => if exists r.a then -r.b else 0
+              │                                            ^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn nulls_unguarded_variable() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::StrictNullChecks],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = (r) => {
+                v = r._value
+                return v * 2
+            }
+        "#,
+        expect: expect_test::expect![[r#"
+            error: v may be null, check it with `exists` or provide a default with `??` before using it in arithmetic
+              ┌─ main:4:24
+              │
+            4 │                 return v * 2
+              │                        ^
+
+        "#]],
+    }
+}
+
+#[test]
+fn nulls_unguarded_call() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::StrictNullChecks],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            getValue = (r) => if exists r._value then r._value else r._default
+            f = (r) => getValue(r) + 1
+        "#,
+        expect: expect_test::expect![[r#"
+            error: the result of getValue may be null, check it with `exists` or provide a default with `??` before using it in arithmetic
+              ┌─ main:3:24
+              │
+            3 │             f = (r) => getValue(r) + 1
+              │                        ^^^^^^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn nulls_guarded() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::StrictNullChecks],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = (r) => if exists r._value then r._value * 2 else 0
+            g = (r) => (r._value ?? 0) * 2
+            h = (r) => if exists r.a and exists r.b then r.a + r.b else 0
+            i = (r) => {
+                v = r._value
+                return if exists v then v * 2 else 0
+            }
+            j = (r) => {
+                v = r._value ?? 0
+                return v * 2
+            }
+        "#,
+        exp: map![
+            "f" => "(r: {A with _value: int}) => int",
+            "g" => "(r: {A with _value: int}) => int",
+            "h" => "(r: {A with a: int, b: int}) => int",
+            "i" => "(r: {A with _value: int}) => int",
+            "j" => "(r: {A with _value: int}) => int",
+        ],
+    }
+}
+
+#[test]
+fn nulls_known_property() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::StrictNullChecks],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            x = {a: 1}
+            y = x.a + 1
+        "#,
+        exp: map![
+            "x" => "{a: int}",
+            "y" => "int",
+        ],
+    }
+}
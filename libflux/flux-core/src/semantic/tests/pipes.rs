@@ -0,0 +1,94 @@
+use super::*;
+
+use crate::semantic::Feature;
+
+fn config() -> AnalyzerConfig {
+    AnalyzerConfig {
+        features: vec![Feature::PipeInMiddle],
+        ..AnalyzerConfig::default()
+    }
+}
+
+#[test]
+fn pipe_into_placeholder() {
+    test_infer! {
+        config: config(),
+        env: map![
+            "f" => "(a: A, b: string) => A",
+            "g" => "(<-t: A, b: string) => A",
+        ],
+        src: r#"
+            x = 1 |> f(a: _, b: "s")
+            y = 2.0 |> f(b: "s", a: _)
+            z = true |> g(b: "s")
+        "#,
+        exp: map![
+            "x" => "int",
+            "y" => "float",
+            "z" => "bool",
+        ],
+    }
+}
+
+#[test]
+fn pipe_placeholder_mismatch() {
+    test_error_msg! {
+        config: config(),
+        env: map![
+            "f" => "(a: int, b: string) => int",
+        ],
+        src: r#"
+            x = "s" |> f(a: _, b: "s")
+        "#,
+        expect: expect_test::expect![[r#"
+            error: expected int but found string (argument a)
+              ┌─ main:2:17
+              │
+            2 │             x = "s" |> f(a: _, b: "s")
+              │                 ^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn multiple_pipe_placeholders() {
+    test_error_msg! {
+        config: config(),
+        env: map![
+            "f" => "(a: A, b: string) => A",
+        ],
+        src: r#"
+            1 |> f(a: _, b: _)
+        "#,
+        expect: expect_test::expect![[r#"
+            error: the pipe placeholder `_` may only be used once in a call
+              ┌─ main:2:29
+              │
+            2 │             1 |> f(a: _, b: _)
+              │                             ^
+
+        "#]],
+    }
+}
+
+#[test]
+fn pipe_placeholder_without_pipe() {
+    test_error_msg! {
+        config: config(),
+        env: map![
+            "f" => "(a: A, b: string) => A",
+        ],
+        src: r#"
+            f(a: _, b: "s")
+        "#,
+        expect: expect_test::expect![[r#"
+            error: the pipe placeholder `_` may only be used in a piped call
+              ┌─ main:2:18
+              │
+            2 │             f(a: _, b: "s")
+              │                  ^
+
+        "#]],
+    }
+}
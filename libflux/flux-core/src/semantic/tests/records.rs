@@ -0,0 +1,375 @@
+use super::*;
+
+use crate::semantic::Feature;
+
+#[test]
+fn record_concatenation() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::RecordConcatenation],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            x = {a: 1} + {b: "b"}
+            y = {a: 1, b: 2.0} + {b: 3.0, c: true}
+        "#,
+        exp: map![
+            "x" => "{a: int, b: string}",
+            "y" => "{a: int, b: float, c: bool}",
+        ],
+    }
+}
+
+#[test]
+fn record_concatenation_conflicting_labels() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::RecordConcatenation],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            x = {a: 1} + {a: "s"}
+        "#,
+        expect: expect_test::expect![[r#"
+            error: expected int but found string
+              ┌─ main:2:26
+              │
+            2 │             x = {a: 1} + {a: "s"}
+              │                          ^^^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn record_concatenation_unknown_operand() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::RecordConcatenation],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = (r) => r + {b: 2}
+            x = f(r: {a: 1})
+            y = {b: 2} + {a: 1}
+        "#,
+        exp: map![
+            "f" => "(r: A) => {A with b: int} where A: Record",
+            "x" => "{a: int, b: int}",
+            "y" => "{a: int, b: int}",
+        ],
+    }
+}
+
+#[test]
+fn record_concatenation_unknown_right_operand() {
+    // The fields of `r` would override `b`, so `r` must not have it
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::RecordConcatenation],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            g = (r) => {b: 2} + r
+            y = g(r: {a: 1, b: "s"})
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:22: error: found unexpected label b (argument r)
+        "#]]
+    }
+}
+
+#[test]
+fn record_concatenation_both_unknown() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::RecordConcatenation],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = (a, b) => a + {x: 1} + b
+        "#,
+        expect: expect_test::expect![[r#"
+            error: cannot concatenate two records whose fields are both unknown
+              ┌─ main:2:27
+              │
+            2 │             f = (a, b) => a + {x: 1} + b
+              │                           ^^^^^^^^^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn record_optional_fields() {
+    test_infer! {
+        env: map![
+            "f" => "(r: {a: int, ?b: string}) => int",
+        ],
+        src: r#"
+            x = f(r: {a: 1})
+            y = f(r: {a: 1, b: "b"})
+        "#,
+        exp: map![
+            "x" => "int",
+            "y" => "int",
+        ],
+    }
+}
+
+#[test]
+fn record_optional_field_type() {
+    test_error_msg! {
+        env: map![
+            "f" => "(r: {a: int, ?b: string}) => int",
+        ],
+        src: r#"
+            x = f(r: {a: 1, b: 2})
+        "#,
+        expect: expect_test::expect![[r#"
+            error: expected string but found int (argument r)
+              ┌─ main:2:22
+              │
+            2 │             x = f(r: {a: 1, b: 2})
+              │                      ^^^^^^^^^^^^
+
+        "#]],
+    }
+}
+
+#[test]
+fn record_update() {
+    test_infer! {
+        env: map![
+            "r" => "{a: int, b: float}",
+            "s" => "{A with a: int, b: float}",
+        ],
+        src: r#"
+            x = {r with a: "a"}
+            y = {s with a: "a"}
+            z = {r with a: 1.0, c: true}
+            f = (t) => {
+                u = {t with a: 1}
+                return {u with a: "a"}
+            }
+        "#,
+        exp: map![
+            "x" => "{a: string, b: float}",
+            "y" => "{A with a: string, b: float}",
+            "z" => "{a: float, c: bool, b: float}",
+            "f" => "(t: A) => {A with a: string}",
+        ],
+    }
+}
+
+#[test]
+fn record_spread() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::SpreadOperator],
+            ..AnalyzerConfig::default()
+        },
+        env: map![
+            "r" => "{a: int, b: float}",
+            "s" => "{b: string, c: bool}",
+        ],
+        src: r#"
+            x = {...r, a: "a"}
+            y = {...r, ...s}
+            z = {a: 1, ...s, c: 1}
+            f = (t) => ({...t, x: 1})
+        "#,
+        exp: map![
+            "x" => "{a: string, b: float}",
+            "y" => "{b: string, c: bool, a: int}",
+            "z" => "{a: int, b: string, c: int}",
+            "f" => "(t: A) => {A with x: int}",
+        ],
+    }
+}
+
+#[test]
+fn invalid_record_spread() {
+    test_error_msg! {
+        src: r#"
+            r = {a: 1}
+            x = {...r, b: 2}
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:18: error: spread expressions are not enabled
+        "#]]
+    }
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::SpreadOperator],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = () => ({a: 1})
+            x = {b: 1, ...f()}
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:27: error: only identifiers can be spread into a record
+        "#]]
+    }
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::SpreadOperator],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            n = 1
+            x = {b: 1, ...n}
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:24: error: cannot spread a value of type int into a record, its fields must be known
+        "#]]
+    }
+}
+
+#[test]
+fn guarded_access() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::GuardedAccess],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            x = {a: 1}?.a
+            f = (r) => r?.a
+            g = (r) => r?.a?.b
+        "#,
+        exp: map![
+            "x" => "int",
+            "f" => "(r: {A with ?a: B}) => B",
+            "g" => "(r: {A with ?a: {B with ?b: C}}) => C",
+        ],
+    }
+}
+
+#[test]
+fn guarded_access_of_missing_label_is_monomorphic() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::GuardedAccess],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            f = (r) => r?.a
+            y = {b: 1}?.a
+            z = f(r: {b: 1})
+            a = y * 2
+            b = y * 2.0
+            c = z * 2
+            d = z * 2.0
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:6:21: error: expected int but found float
+            main:8:21: error: expected int but found float
+        "#]]
+    }
+}
+
+#[test]
+fn guarded_access_disabled() {
+    test_error_msg! {
+        src: r#"
+            r = {a: 1}
+            x = r?.a
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:17: error: guarded access `?.` is not enabled
+        "#]]
+    }
+}
+
+#[test]
+fn unify_constrained_record_extensions() {
+    test_infer! {
+        env: map![
+            "f" => "(r: {A with x: int}) => {A with x: int} where A: Record",
+            "g" => "(r: {B with y: string}) => {B with y: string} where B: Record",
+        ],
+        src: r#"
+            h = (r) => g(r: f(r: r))
+        "#,
+        exp: map![
+            "h" => "(r: {C with x: int, y: string}) => {C with x: int, y: string} where C: Record",
+        ],
+    }
+}
+
+#[test]
+fn destructuring_assignment() {
+    test_infer! {
+        config: AnalyzerConfig{
+            features: vec![Feature::DestructuringAssignment],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            s = {a: 1, b: "b", c: 2.0}
+            {a, b} = s
+            {a: x, "c": y} = s
+            f = (r) => {
+                {a: p, b: q} = r
+
+                return p + q + 1
+            }
+        "#,
+        exp: map![
+            "s" => "{a: int, b: string, c: float}",
+            "a" => "int",
+            "b" => "string",
+            "x" => "int",
+            "y" => "float",
+            "f" => "(r: {A with a: int, b: int}) => int",
+        ],
+    }
+}
+
+#[test]
+fn destructuring_assignment_missing_label() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::DestructuringAssignment],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            r = {host: "a", port: 1}
+            {host, region} = r
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:20: error: record is missing label region
+        "#]]
+    }
+}
+
+#[test]
+fn destructuring_assignment_checks_the_record_once() {
+    test_error_msg! {
+        config: AnalyzerConfig{
+            features: vec![Feature::DestructuringAssignment],
+            ..AnalyzerConfig::default()
+        },
+        src: r#"
+            {a, b} = {a: 1 + "s", b: 2}
+            {} = 1 + "s"
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:30: error: expected int but found string
+            main:3:22: error: expected int but found string
+        "#]]
+    }
+}
+
+#[test]
+fn destructuring_assignment_disabled() {
+    test_error_msg! {
+        src: r#"
+            {a} = {a: 1}
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:2:13: error: destructuring assignments are not enabled
+        "#]]
+    }
+}
@@ -0,0 +1,77 @@
+use super::*;
+use crate::{ast::SourceLocation, semantic::import::Packages};
+
+const SRC: &str = r#"
+f = (tables=<-) => tables
+a = 1 |> f()
+b = 1 > 0 ? "yes" : "no"
+c = [...[1], 2]
+{d} = {d: 1}
+e = [...[1]]
+"#;
+
+fn analyzer(source_map: bool) -> Analyzer<'static, Packages> {
+    Analyzer::new(
+        Environment::default(),
+        Packages::default(),
+        AnalyzerConfig {
+            features: vec![
+                semantic::Feature::TernaryOperator,
+                semantic::Feature::SpreadOperator,
+                semantic::Feature::DestructuringAssignment,
+            ],
+            source_map,
+            ..AnalyzerConfig::default()
+        },
+    )
+}
+
+fn span(loc: &SourceLocation) -> String {
+    format!(
+        "{}:{}-{}:{}",
+        loc.start.line, loc.start.column, loc.end.line, loc.end.column
+    )
+}
+
+#[test]
+fn desugared_nodes_are_mapped_to_their_source() {
+    let mut analyzer = analyzer(true);
+    analyzer
+        .analyze_source("main".into(), "".into(), SRC)
+        .map_err(|err| err.error.pretty(SRC))
+        .unwrap();
+
+    let source_map = analyzer.source_map();
+    let mappings = source_map
+        .iter()
+        .map(|mapping| {
+            format!(
+                "{} {:?} at {}",
+                span(&mapping.loc),
+                mapping.desugaring,
+                span(&mapping.origin)
+            )
+        })
+        .collect::<Vec<_>>();
+    expect_test::expect![[r#"
+        3:10-3:13 Pipe at 3:5-3:13
+        5:14-5:15 ArraySpread at 5:5-5:16
+        6:1-6:4 DestructuringAssignment at 6:1-6:13
+        6:2-6:3 DestructuringAssignment at 6:1-6:13
+        7:5-7:5 ArraySpread at 7:5-7:13"#]]
+    .assert_eq(&mappings.join("\n"));
+
+    let member = &source_map.iter().nth(3).unwrap().loc;
+    assert_eq!(span(source_map.origin(member)), "6:1-6:13");
+}
+
+#[test]
+fn source_map_is_opt_in() {
+    let mut analyzer = analyzer(false);
+    analyzer
+        .analyze_source("main".into(), "".into(), SRC)
+        .map_err(|err| err.error.pretty(SRC))
+        .unwrap();
+
+    assert!(analyzer.source_map().is_empty());
+}
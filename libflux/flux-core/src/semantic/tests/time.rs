@@ -0,0 +1,72 @@
+use super::*;
+
+#[test]
+fn time_arithmetic() {
+    test_infer! {
+        env: map![],
+        src: r#"
+            t = 2020-01-01T00:00:00Z
+            a = t + 1h
+            b = 1h + t
+            c = 1h + 2m
+            d = t - t
+            e = t - 1h
+            f = 1h - 30m
+            g = (x) => x + 1h
+            h = (x) => t - x
+            i = (x) => t + x
+        "#,
+        exp: map![
+            "t" => "time",
+            "a" => "time",
+            "b" => "time",
+            "c" => "duration",
+            "d" => "duration",
+            "e" => "time",
+            "f" => "duration",
+            "g" => "(x: duration) => duration",
+            "h" => "(x: time) => duration",
+            "i" => "(x: duration) => time",
+        ],
+    }
+}
+
+#[test]
+fn negative_durations() {
+    test_infer! {
+        env: map![
+            "now" => "() => time",
+        ],
+        src: r#"
+            a = -1h
+            b = -1h30m
+            c = now() - 30m
+            d = now() + -1h30m
+            e = (x) => now() + -x
+        "#,
+        exp: map![
+            "a" => "duration",
+            "b" => "duration",
+            "c" => "time",
+            "d" => "time",
+            "e" => "(x: duration) => time",
+        ],
+    }
+}
+
+#[test]
+fn invalid_time_arithmetic() {
+    test_error_msg! {
+        src: r#"
+            t = 2020-01-01T00:00:00Z
+            a = t + t
+            b = 1h - t
+            c = t + 1
+        "#,
+        expect_short: expect_test::expect![[r#"
+            main:3:17: error: cannot compute time + time, the only arithmetic on times and durations is time + duration, duration + time, duration + duration, time - time, time - duration and duration - duration
+            main:4:17: error: cannot compute duration - time, the only arithmetic on times and durations is time + duration, duration + time, duration + duration, time - time, time - duration and duration - duration
+            main:5:17: error: cannot compute time + int, the only arithmetic on times and durations is time + duration, duration + time, duration + duration, time - time, time - duration and duration - duration
+        "#]]
+    }
+}
@@ -1,6 +1,7 @@
 use super::*;
 use crate::semantic::{
     import::Packages,
+    nodes::{ConditionalExpr, Expression},
     nodes::{FunctionExpr, Package},
     walk::{walk, Node},
     AnalyzerConfig, Feature,
@@ -12,6 +13,7 @@ fn analyzer_config() -> AnalyzerConfig {
             Feature::VectorizedMap,
             Feature::VectorizeAddition,
             Feature::VectorizeOperators,
+            Feature::VectorizeConditionals,
         ],
         ..AnalyzerConfig::default()
     }
@@ -49,8 +51,8 @@ fn vectorize_field_access() -> anyhow::Result<()> {
 
     expect_test::expect![[r##"
         (r) => {
-            return {a: r:{F with b:v[#B], a:v[#D]}.a:v[#D], b: r:{F with b:v[#B], a:v[#D]}.b:v[#B]}:{a:v[#D], b:v[#B]}
-        }:(r:{F with b:v[#B], a:v[#D]}) => {a:v[#D], b:v[#B]}"##]].assert_eq(&crate::semantic::formatter::format_node(
+            return {a: r:{F with a:v[#D], b:v[#B]}.a:v[#D], b: r:{F with a:v[#D], b:v[#B]}.b:v[#B]}:{a:v[#D], b:v[#B]}
+        }:(r:{F with a:v[#D], b:v[#B]}) => {a:v[#D], b:v[#B]}"##]].assert_eq(&crate::semantic::formatter::format_node(
             Node::FunctionExpr(function),
     )?);
 
@@ -65,8 +67,8 @@ fn vectorize_with_construction() -> anyhow::Result<()> {
 
     expect_test::expect![[r##"
         (r) => {
-            return {r:{C with a:v[#B]} with b: r:{C with a:v[#B]}.a:v[#B]}:{C with b:v[#B], a:v[#B]}
-        }:(r:{C with a:v[#B]}) => {C with b:v[#B], a:v[#B]}"##]]
+            return {r:{C with a:v[#B]} with b: r:{C with a:v[#B]}.a:v[#B]}:{C with a:v[#B], b:v[#B]}
+        }:(r:{C with a:v[#B]}) => {C with a:v[#B], b:v[#B]}"##]]
     .assert_eq(&crate::semantic::formatter::format_node(
         Node::FunctionExpr(function),
     )?);
@@ -87,8 +89,8 @@ fn vectorize_even_when_another_function_fails_to_vectorize() -> anyhow::Result<(
 
     expect_test::expect![[r##"
         (r) => {
-            return {r:{I with a:v[#G], b:v[#G]} with x: r:{I with a:v[#G], b:v[#G]}.a:v[#G] +:v[#G] r:{I with a:v[#G], b:v[#G]}.b:v[#G]}:{I with x:v[#G], a:v[#G], b:v[#G]}
-        }:(r:{I with a:v[#G], b:v[#G]}) => {I with x:v[#G], a:v[#G], b:v[#G]}"##]]
+            return {r:{I with a:v[#G], b:v[#G]} with x: r:{I with a:v[#G], b:v[#G]}.a:v[#G] +:v[#G] r:{I with a:v[#G], b:v[#G]}.b:v[#G]}:{I with a:v[#G], b:v[#G], x:v[#G]}
+        }:(r:{I with a:v[#G], b:v[#G]}) => {I with a:v[#G], b:v[#G], x:v[#G]}"##]]
     .assert_eq(&crate::semantic::formatter::format_node(
         Node::FunctionExpr(function),
     )?);
@@ -157,3 +159,118 @@ fn vectorizing_non_vector_variables_are_not_implemented() {
     ]]
     .assert_eq(&err.to_string());
 }
+
+fn get_conditionals(function: &FunctionExpr) -> Vec<&ConditionalExpr> {
+    let mut conditionals = Vec::new();
+    walk(
+        &mut |node| {
+            if let Node::ConditionalExpr(cond) = node {
+                conditionals.push(cond);
+            }
+        },
+        Node::FunctionExpr(function),
+    );
+    conditionals
+}
+
+fn is_vector(typ: &MonoType) -> bool {
+    typ.to_string().starts_with("v[")
+}
+
+#[test]
+fn vectorize_conditional() -> anyhow::Result<()> {
+    let pkg = vectorize(r#"(r) => ({ x: if r.a then r.b else r.c })"#)?;
+
+    let function = get_vectorized_function(&pkg);
+    let conditionals = get_conditionals(function);
+    assert_eq!(conditionals.len(), 1);
+
+    let cond = conditionals[0];
+    assert_eq!(cond.test.type_of().to_string(), "v[bool]");
+    assert!(is_vector(&cond.typ), "{}", cond.typ);
+    assert!(is_vector(&cond.consequent.type_of()));
+    assert!(is_vector(&cond.alternate.type_of()));
+
+    Ok(())
+}
+
+#[test]
+fn vectorize_conditional_with_constant_branch() -> anyhow::Result<()> {
+    let pkg = vectorize(r#"(r) => ({ x: if r.a then r.b + 1 else 0 })"#)?;
+
+    let function = get_vectorized_function(&pkg);
+    let conditionals = get_conditionals(function);
+    assert_eq!(conditionals.len(), 1);
+
+    let cond = conditionals[0];
+    assert_eq!(cond.typ.to_string(), "v[int]");
+    assert_eq!(cond.consequent.type_of().to_string(), "v[int]");
+    // The constant is left as a scalar and broadcast by the select
+    assert!(matches!(cond.alternate, Expression::Integer(_)));
+
+    Ok(())
+}
+
+#[test]
+fn vectorize_nested_conditional() -> anyhow::Result<()> {
+    let pkg = vectorize(r#"(r) => ({ x: if r.a then r.b else if r.c then "c" else r.d })"#)?;
+
+    let function = get_vectorized_function(&pkg);
+    let conditionals = get_conditionals(function);
+    assert_eq!(conditionals.len(), 2);
+
+    for cond in conditionals {
+        assert_eq!(cond.test.type_of().to_string(), "v[bool]");
+        assert_eq!(cond.typ.to_string(), "v[string]");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn vectorize_conditional_with_call_falls_back() {
+    let mut pkg = vectorize(r#"(r) => ({ x: if r.a then r.b else r.f() })"#).unwrap();
+
+    let err = semantic::vectorize::vectorize(&analyzer_config(), &mut pkg).unwrap_err();
+
+    expect_test::expect![[
+        r#"error @1:35-1:40: can't vectorize function: Unable to vectorize call to non-vectorizable function `f`"#
+    ]]
+    .assert_eq(&err.to_string());
+}
+
+#[test]
+fn vectorize_diagnostics() {
+    let mut config = analyzer_config();
+    config.features.push(Feature::VectorizeDiagnostics);
+    let mut analyzer = Analyzer::new(Default::default(), Packages::default(), config);
+    analyzer
+        .analyze_source(
+            "main".into(),
+            "".into(),
+            r#"
+            a = (r) => ({ x: r.f(v: r.a) })
+            b = (r) => ({ x: not r.a })
+            c = (r) => ({ x: r.a[0] })
+        "#,
+        )
+        .unwrap();
+
+    expect_test::expect![[r#"
+        warning @2:30-2:41: can't vectorize function: Unable to vectorize call to non-vectorizable function `f`
+
+        warning @3:30-3:37: can't vectorize function: Unable to vectorize unsupported operator `not`
+
+        warning @4:30-4:36: can't vectorize function: Unable to vectorize dynamic field access"#]]
+    .assert_eq(&analyzer.warnings().to_string());
+}
+
+#[test]
+fn vectorize_diagnostics_are_opt_in() {
+    let mut analyzer = Analyzer::new(Default::default(), Packages::default(), analyzer_config());
+    analyzer
+        .analyze_source("main".into(), "".into(), r#"(r) => ({ x: r.f() })"#)
+        .unwrap();
+
+    assert!(analyzer.warnings().is_empty());
+}
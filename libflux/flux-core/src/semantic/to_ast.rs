@@ -0,0 +1,858 @@
+//! Lowering of the semantic graph back into an AST.
+//!
+//! [`to_ast`] is the inverse of [`convert`](crate::semantic::convert): the resulting package can be
+//! given to the [`formatter`](crate::formatter) to write out a semantic graph which was
+//! modified by a code transformation. Source locations are kept wherever the semantic graph has
+//! them, but comments are lost as the semantic graph does not keep them.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+
+use crate::{
+    ast,
+    parser::strconv,
+    scanner::{Scanner, TokenType},
+    semantic::{
+        nodes::*,
+        types::{self, CollectionType, MonoType, PolyType, RecordLabel, Tvar},
+    },
+};
+
+/// Lowers a semantic package into an AST package.
+///
+/// Fails if the package contains types which cannot be written in Flux, such as the vector types
+/// introduced by vectorization.
+pub fn to_ast(pkg: &Package) -> Result<ast::Package> {
+    Ok(ast::Package {
+        base: base(&pkg.loc),
+        path: String::new(),
+        package: pkg.package.clone(),
+        files: pkg.files.iter().map(file).collect::<Result<_>>()?,
+    })
+}
+
+fn base(loc: &ast::SourceLocation) -> ast::BaseNode {
+    ast::BaseNode {
+        location: loc.clone(),
+        ..ast::BaseNode::default()
+    }
+}
+
+fn identifier(id: &Identifier) -> ast::Identifier {
+    ast::Identifier {
+        base: base(&id.loc),
+        name: id.name.name().to_string(),
+    }
+}
+
+fn file(file: &File) -> Result<ast::File> {
+    Ok(ast::File {
+        base: base(&file.loc),
+        name: file.loc.file.clone().unwrap_or_default(),
+        metadata: String::new(),
+        package: file.package.as_ref().map(|pkg| ast::PackageClause {
+            base: base(&pkg.loc),
+            name: identifier(&pkg.name),
+        }),
+        imports: file
+            .imports
+            .iter()
+            .map(|import| ast::ImportDeclaration {
+                base: base(&import.loc),
+                alias: import.alias.as_ref().map(identifier),
+                path: string_literal(&import.path),
+            })
+            .collect(),
+        body: statements(&file.body)?,
+        eof: Vec::new(),
+    })
+}
+
+fn statements(body: &[Statement]) -> Result<Vec<ast::Statement>> {
+    body.iter().map(statement).collect()
+}
+
+fn statement(stmt: &Statement) -> Result<ast::Statement> {
+    Ok(match stmt {
+        Statement::Expr(stmt) => ast::Statement::Expr(Box::new(expression_statement(stmt)?)),
+        Statement::Variable(stmt) => ast::Statement::Variable(Box::new(variable_assignment(stmt)?)),
+        Statement::Option(stmt) => ast::Statement::Option(Box::new(ast::OptionStmt {
+            base: base(&stmt.loc),
+            assignment: match &stmt.assignment {
+                Assignment::Variable(assign) => {
+                    ast::Assignment::Variable(Box::new(variable_assignment(assign)?))
+                }
+                Assignment::Member(assign) => ast::Assignment::Member(Box::new(ast::MemberAssgn {
+                    base: base(&assign.loc),
+                    member: member_expression(&assign.member)?,
+                    init: expression(&assign.init)?,
+                })),
+            },
+        })),
+        Statement::Return(stmt) => ast::Statement::Return(Box::new(return_statement(stmt)?)),
+        Statement::Test(stmt) => ast::Statement::Test(Box::new(ast::TestStmt {
+            base: base(&stmt.loc),
+            assignment: variable_assignment(&stmt.assignment)?,
+        })),
+        Statement::TestCase(stmt) => ast::Statement::TestCase(Box::new(ast::TestCaseStmt {
+            base: base(&stmt.loc),
+            id: identifier(&stmt.id),
+            extends: stmt.extends.as_ref().map(string_literal),
+            block: ast::Block {
+                base: base(&stmt.loc),
+                lbrace: Vec::new(),
+                body: statements(&stmt.body)?,
+                rbrace: Vec::new(),
+            },
+        })),
+        Statement::Builtin(stmt) => ast::Statement::Builtin(Box::new(ast::BuiltinStmt {
+            base: base(&stmt.loc),
+            colon: Vec::new(),
+            id: identifier(&stmt.id),
+            ty: TypeNames::default().type_expression(&stmt.typ_expr)?,
+        })),
+        Statement::TypeAlias(stmt) => {
+            let mut names = TypeNames::default();
+            let params = stmt
+                .typ
+                .vars
+                .iter()
+                .map(|var| names.tvar(*var))
+                .collect::<Result<_>>()?;
+            ast::Statement::TypeAlias(Box::new(ast::TypeAliasStmt {
+                base: base(&stmt.loc),
+                id: identifier(&stmt.id),
+                params,
+                ty: names.monotype(&stmt.typ.expr)?,
+            }))
+        }
+        Statement::Error(loc) => ast::Statement::Bad(Box::new(ast::BadStmt {
+            base: base(loc),
+            text: String::new(),
+        })),
+    })
+}
+
+fn expression_statement(stmt: &ExprStmt) -> Result<ast::ExprStmt> {
+    Ok(ast::ExprStmt {
+        base: base(&stmt.loc),
+        expression: expression(&stmt.expression)?,
+    })
+}
+
+fn return_statement(stmt: &ReturnStmt) -> Result<ast::ReturnStmt> {
+    Ok(ast::ReturnStmt {
+        base: base(&stmt.loc),
+        argument: expression(&stmt.argument)?,
+    })
+}
+
+fn variable_assignment(assign: &VariableAssgn) -> Result<ast::VariableAssgn> {
+    Ok(ast::VariableAssgn {
+        base: base(&assign.loc),
+        id: identifier(&assign.id),
+        init: expression(&assign.init)?,
+        colon: vec![],
+        ty: assign
+            .annotation
+            .as_ref()
+            .map(|annotation| TypeNames::default().type_expression(annotation))
+            .transpose()?,
+    })
+}
+
+// Flattens a function body into its statements, the last one being the `return` statement.
+fn block_statements(mut block: &Block) -> Result<(Vec<ast::Statement>, &ReturnStmt)> {
+    let mut body = Vec::new();
+    loop {
+        match block {
+            Block::Variable(assign, next) => {
+                body.push(ast::Statement::Variable(Box::new(variable_assignment(
+                    assign,
+                )?)));
+                block = next;
+            }
+            Block::Expr(stmt, next) => {
+                body.push(ast::Statement::Expr(Box::new(expression_statement(stmt)?)));
+                block = next;
+            }
+            Block::Return(stmt) => return Ok((body, stmt)),
+        }
+    }
+}
+
+fn expression(expr: &Expression) -> Result<ast::Expression> {
+    Ok(match expr {
+        Expression::Identifier(id) => ast::Expression::Identifier(ast::Identifier {
+            base: base(&id.loc),
+            name: id.name.name().to_string(),
+        }),
+        Expression::Array(expr) => ast::Expression::Array(Box::new(ast::ArrayExpr {
+            base: base(&expr.loc),
+            lbrack: Vec::new(),
+            elements: array_items(&expr.elements)?,
+            rbrack: Vec::new(),
+        })),
+        Expression::Dict(expr) => ast::Expression::Dict(Box::new(ast::DictExpr {
+            base: base(&expr.loc),
+            lbrack: Vec::new(),
+            elements: expr
+                .elements
+                .iter()
+                .map(|(key, val)| {
+                    Ok(ast::DictItem {
+                        key: expression(key)?,
+                        val: expression(val)?,
+                        comma: Vec::new(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+            rbrack: Vec::new(),
+        })),
+        Expression::Function(expr) => {
+            ast::Expression::Function(Box::new(function_expression(expr)?))
+        }
+        Expression::Logical(expr) => ast::Expression::Logical(Box::new(ast::LogicalExpr {
+            base: base(&expr.loc),
+            operator: expr.operator.clone(),
+            left: expression(&expr.left)?,
+            right: expression(&expr.right)?,
+        })),
+        Expression::Object(expr) => ast::Expression::Object(Box::new(object_expression(expr)?)),
+        Expression::Member(expr) => ast::Expression::Member(Box::new(member_expression(expr)?)),
+        Expression::Index(expr) => ast::Expression::Index(Box::new(ast::IndexExpr {
+            base: base(&expr.loc),
+            array: expression(&expr.array)?,
+            lbrack: Vec::new(),
+            index: expression(&expr.index)?,
+            rbrack: Vec::new(),
+        })),
+        Expression::Tuple(expr) => ast::Expression::Tuple(Box::new(ast::TupleExpr {
+            base: base(&expr.loc),
+            lparen: Vec::new(),
+            elements: array_items(&expr.elements)?,
+            rparen: Vec::new(),
+        })),
+        Expression::TupleIndex(expr) => {
+            ast::Expression::TupleIndex(Box::new(ast::TupleIndexExpr {
+                base: base(&expr.loc),
+                tuple: expression(&expr.tuple)?,
+                dot: Vec::new(),
+                index: ast::IntegerLit {
+                    base: ast::BaseNode::default(),
+                    value: expr.index as i64,
+                },
+            }))
+        }
+        Expression::Block(expr) => {
+            // The value of the block is its trailing expression
+            let (mut body, value) = block_statements(&expr.body)?;
+            body.push(ast::Statement::Expr(Box::new(ast::ExprStmt {
+                base: base(&value.loc),
+                expression: expression(&value.argument)?,
+            })));
+            ast::Expression::Block(Box::new(ast::BlockExpr {
+                base: base(&expr.loc),
+                lbrace: Vec::new(),
+                body,
+                rbrace: Vec::new(),
+            }))
+        }
+        Expression::Match(expr) => ast::Expression::Match(Box::new(ast::MatchExpr {
+            base: base(&expr.loc),
+            scrutinee: expression(&expr.scrutinee)?,
+            lbrace: Vec::new(),
+            arms: expr
+                .arms
+                .iter()
+                .map(|arm| {
+                    Ok(ast::MatchArm {
+                        base: base(&arm.loc),
+                        pattern: match &arm.pattern {
+                            Some(pattern) => expression(pattern)?,
+                            None => ast::Expression::Identifier(ast::Identifier {
+                                base: ast::BaseNode::default(),
+                                name: "_".to_string(),
+                            }),
+                        },
+                        arrow: Vec::new(),
+                        body: expression(&arm.body)?,
+                        comma: Vec::new(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+            rbrace: Vec::new(),
+        })),
+        Expression::Binary(expr) => ast::Expression::Binary(Box::new(ast::BinaryExpr {
+            base: base(&expr.loc),
+            operator: expr.operator.clone(),
+            left: expression(&expr.left)?,
+            right: expression(&expr.right)?,
+        })),
+        Expression::Unary(expr) => ast::Expression::Unary(Box::new(ast::UnaryExpr {
+            base: base(&expr.loc),
+            operator: expr.operator.clone(),
+            argument: expression(&expr.argument)?,
+        })),
+        Expression::Call(expr) => call_expression(expr)?,
+        Expression::Conditional(expr) => {
+            ast::Expression::Conditional(Box::new(ast::ConditionalExpr {
+                base: base(&expr.loc),
+                tk_if: Vec::new(),
+                test: expression(&expr.test)?,
+                tk_then: Vec::new(),
+                consequent: expression(&expr.consequent)?,
+                tk_else: Vec::new(),
+                alternate: expression(&expr.alternate)?,
+            }))
+        }
+        Expression::StringExpr(expr) => ast::Expression::StringExpr(Box::new(ast::StringExpr {
+            base: base(&expr.loc),
+            parts: expr
+                .parts
+                .iter()
+                .map(|part| {
+                    Ok(match part {
+                        StringExprPart::Text(text) => ast::StringExprPart::Text(ast::TextPart {
+                            base: base(&text.loc),
+                            value: text.value.clone(),
+                        }),
+                        StringExprPart::Interpolated(part) => {
+                            ast::StringExprPart::Interpolated(ast::InterpolatedPart {
+                                base: base(&part.loc),
+                                expression: expression(&part.expression)?,
+                            })
+                        }
+                    })
+                })
+                .collect::<Result<_>>()?,
+        })),
+
+        Expression::Integer(lit) => ast::Expression::Integer(ast::IntegerLit {
+            base: base(&lit.loc),
+            value: lit.value,
+        }),
+        Expression::Float(lit) => ast::Expression::Float(ast::FloatLit {
+            base: base(&lit.loc),
+            value: lit.value,
+        }),
+        Expression::StringLit(lit) => ast::Expression::StringLit(string_literal(lit)),
+        Expression::Duration(lit) => duration_literal(lit),
+        Expression::Uint(lit) => ast::Expression::Uint(ast::UintLit {
+            base: base(&lit.loc),
+            value: lit.value,
+        }),
+        Expression::Boolean(lit) => ast::Expression::Boolean(ast::BooleanLit {
+            base: base(&lit.loc),
+            value: lit.value,
+        }),
+        Expression::DateTime(lit) => ast::Expression::DateTime(ast::DateTimeLit {
+            base: base(&lit.loc),
+            value: lit.value,
+        }),
+        Expression::Regexp(lit) => ast::Expression::Regexp(ast::RegexpLit {
+            base: base(&lit.loc),
+            value: lit.value.clone(),
+        }),
+
+        Expression::Error(loc) => ast::Expression::Bad(Box::new(ast::BadExpr {
+            base: base(loc),
+            text: String::new(),
+            expression: None,
+        })),
+    })
+}
+
+fn array_items(elements: &[Expression]) -> Result<Vec<ast::ArrayItem>> {
+    elements
+        .iter()
+        .map(|element| {
+            Ok(ast::ArrayItem {
+                expression: expression(element)?,
+                comma: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn function_expression(expr: &FunctionExpr) -> Result<ast::FunctionExpr> {
+    let params = expr
+        .params
+        .iter()
+        .map(|param| {
+            let value = if param.is_pipe {
+                Some(ast::Expression::PipeLit(ast::PipeLit {
+                    base: base(&param.loc),
+                }))
+            } else {
+                param.default.as_ref().map(expression).transpose()?
+            };
+            Ok(ast::Property {
+                base: base(&param.loc),
+                key: ast::PropertyKey::Identifier(identifier(&param.key)),
+                separator: Vec::new(),
+                value,
+                comma: Vec::new(),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    // `(x) => x` is the short form of `(x) => { return x }`
+    let (body, ret) = block_statements(&expr.body)?;
+    let body = if body.is_empty() && ret.loc == *ret.argument.loc() {
+        ast::FunctionBody::Expr(expression(&ret.argument)?)
+    } else {
+        let mut body = body;
+        body.push(ast::Statement::Return(Box::new(return_statement(ret)?)));
+        ast::FunctionBody::Block(ast::Block {
+            base: base(&expr.loc),
+            lbrace: Vec::new(),
+            body,
+            rbrace: Vec::new(),
+        })
+    };
+
+    Ok(ast::FunctionExpr {
+        base: base(&expr.loc),
+        lparen: Vec::new(),
+        params,
+        rparen: Vec::new(),
+        arrow: Vec::new(),
+        body,
+    })
+}
+
+fn call_expression(expr: &CallExpr) -> Result<ast::Expression> {
+    let arguments = if expr.arguments.is_empty() {
+        Vec::new()
+    } else {
+        vec![ast::Expression::Object(Box::new(ast::ObjectExpr {
+            base: base(&expr.loc),
+            lbrace: Vec::new(),
+            with: None,
+            properties: properties(&expr.arguments)?,
+            rbrace: Vec::new(),
+        }))]
+    };
+    let call = ast::CallExpr {
+        base: base(&expr.loc),
+        callee: expression(&expr.callee)?,
+        lparen: Vec::new(),
+        arguments,
+        rparen: Vec::new(),
+    };
+    Ok(match &expr.pipe {
+        Some(pipe) => ast::Expression::PipeExpr(Box::new(ast::PipeExpr {
+            base: base(&expr.loc),
+            argument: expression(pipe)?,
+            call,
+        })),
+        None => ast::Expression::Call(Box::new(call)),
+    })
+}
+
+fn member_expression(expr: &MemberExpr) -> Result<ast::MemberExpr> {
+    Ok(ast::MemberExpr {
+        base: base(&expr.loc),
+        object: expression(&expr.object)?,
+        lbrack: Vec::new(),
+        property: property_key(expr.property.name(), &expr.loc),
+        optional: expr.optional,
+        rbrack: Vec::new(),
+    })
+}
+
+fn object_expression(expr: &ObjectExpr) -> Result<ast::ObjectExpr> {
+    Ok(ast::ObjectExpr {
+        base: base(&expr.loc),
+        lbrace: Vec::new(),
+        with: expr.with.as_ref().map(|with| ast::WithSource {
+            source: ast::Identifier {
+                base: base(&with.loc),
+                name: with.name.name().to_string(),
+            },
+            with: Vec::new(),
+        }),
+        properties: properties(&expr.properties)?,
+        rbrace: Vec::new(),
+    })
+}
+
+fn properties(props: &[Property]) -> Result<Vec<ast::Property>> {
+    props
+        .iter()
+        .map(|prop| {
+            // `{a}` was converted into `{a: a}` with the identifier at the location of the key
+            let value = match &prop.value {
+                Expression::Identifier(id)
+                    if id.loc == prop.key.loc && id.name.name() == prop.key.name.name() =>
+                {
+                    None
+                }
+                value => Some(expression(value)?),
+            };
+            Ok(ast::Property {
+                base: base(&prop.loc),
+                key: property_key(prop.key.name.name(), &prop.key.loc),
+                separator: Vec::new(),
+                value,
+                comma: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn property_key(name: &str, loc: &ast::SourceLocation) -> ast::PropertyKey {
+    if is_identifier(name) {
+        ast::PropertyKey::Identifier(ast::Identifier {
+            base: base(loc),
+            name: name.to_string(),
+        })
+    } else {
+        ast::PropertyKey::StringLit(ast::StringLit {
+            base: ast::BaseNode::default(),
+            value: name.to_string(),
+        })
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut scanner = Scanner::new(name);
+    let t = scanner.scan();
+    t.tok == TokenType::Ident && t.lit == name && scanner.scan().tok == TokenType::Eof
+}
+
+fn string_literal(lit: &StringLit) -> ast::StringLit {
+    let mut base = base(&lit.loc);
+    // The formatter writes out the source of string literals, which is only correct as long as it
+    // still spells the value
+    if let Some(src) = &base.location.source {
+        if strconv::parse_string(src).as_ref() != Ok(&lit.value) {
+            base.location.source = None;
+        }
+    }
+    ast::StringLit {
+        base,
+        value: lit.value.clone(),
+    }
+}
+
+fn duration_literal(lit: &DurationLit) -> ast::Expression {
+    let mut values = Vec::new();
+    let mut push = |magnitude: i64, unit: &str| {
+        if magnitude != 0 {
+            values.push(ast::Duration {
+                magnitude,
+                unit: unit.to_string(),
+            })
+        }
+    };
+    push(lit.value.months / 12, "y");
+    push(lit.value.months % 12, "mo");
+    let nanoseconds = lit.value.nanoseconds;
+    push(nanoseconds / (7 * 24 * 3600 * 1_000_000_000), "w");
+    push(nanoseconds / (24 * 3600 * 1_000_000_000) % 7, "d");
+    push(nanoseconds / (3600 * 1_000_000_000) % 24, "h");
+    push(nanoseconds / (60 * 1_000_000_000) % 60, "m");
+    push(nanoseconds / 1_000_000_000 % 60, "s");
+    push(nanoseconds / 1_000_000 % 1000, "ms");
+    push(nanoseconds / 1000 % 1000, "us");
+    push(nanoseconds % 1000, "ns");
+    if values.is_empty() {
+        values.push(ast::Duration {
+            magnitude: 0,
+            unit: "ns".to_string(),
+        });
+    }
+
+    let duration = ast::Expression::Duration(ast::DurationLit {
+        base: base(&lit.loc),
+        values,
+    });
+    // Negative durations are written by negating the positive literal
+    if lit.value.negative {
+        ast::Expression::Unary(Box::new(ast::UnaryExpr {
+            base: base(&lit.loc),
+            operator: ast::Operator::SubtractionOperator,
+            argument: duration,
+        }))
+    } else {
+        duration
+    }
+}
+
+// Names the type variables of a type in the order they are encountered, as type variables are
+// written as a single upper case letter.
+#[derive(Default)]
+struct TypeNames {
+    names: BTreeMap<Tvar, String>,
+}
+
+impl TypeNames {
+    fn tvar(&mut self, tv: Tvar) -> Result<ast::Identifier> {
+        let next = self.names.len();
+        if !self.names.contains_key(&tv) && next >= 26 {
+            bail!("too many type variables to write them as flux");
+        }
+        let name = self
+            .names
+            .entry(tv)
+            .or_insert_with(|| char::from(b'A' + next as u8).to_string());
+        Ok(type_identifier(name))
+    }
+
+    fn type_expression(&mut self, typ: &PolyType) -> Result<ast::TypeExpression> {
+        for var in &typ.vars {
+            self.tvar(*var)?;
+        }
+        let monotype = self.monotype(&typ.expr)?;
+        let constraints = typ
+            .cons
+            .iter()
+            .map(|(tv, kinds)| {
+                Ok(ast::TypeConstraint {
+                    base: ast::BaseNode::default(),
+                    tvar: self.tvar(*tv)?,
+                    kinds: kinds
+                        .iter()
+                        .map(|kind| type_identifier(&kind.to_string()))
+                        .collect(),
+                    labels: typ
+                        .label_sets
+                        .get(tv)
+                        .into_iter()
+                        .flatten()
+                        .map(|label| ast::StringLit {
+                            base: ast::BaseNode::default(),
+                            value: label.clone(),
+                        })
+                        .collect(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(ast::TypeExpression {
+            base: ast::BaseNode::default(),
+            monotype,
+            constraints,
+        })
+    }
+
+    fn monotype(&mut self, typ: &MonoType) -> Result<ast::MonoType> {
+        Ok(match typ {
+            MonoType::Builtin(builtin) => ast::MonoType::Basic(ast::NamedType {
+                base: ast::BaseNode::default(),
+                name: type_identifier(&builtin.to_string()),
+            }),
+            MonoType::Var(tv) | MonoType::BoundVar(tv) => ast::MonoType::Tvar(ast::TvarType {
+                base: ast::BaseNode::default(),
+                name: self.tvar(*tv)?,
+            }),
+            MonoType::Collection(collection) => {
+                let element = self.monotype(&collection.arg)?;
+                match collection.collection {
+                    CollectionType::Array => ast::MonoType::Array(Box::new(ast::ArrayType {
+                        base: ast::BaseNode::default(),
+                        element,
+                    })),
+                    CollectionType::Stream => ast::MonoType::Stream(Box::new(ast::StreamType {
+                        base: ast::BaseNode::default(),
+                        element,
+                    })),
+                    CollectionType::Vector => bail!("type `{}` cannot be written as flux", typ),
+                }
+            }
+            MonoType::Dict(dict) => ast::MonoType::Dict(Box::new(ast::DictType {
+                base: ast::BaseNode::default(),
+                key: self.monotype(&dict.key)?,
+                val: self.monotype(&dict.val)?,
+            })),
+            MonoType::Record(record) => self.record(record)?,
+            MonoType::Fun(fun) => self.function(fun)?,
+            MonoType::Error | MonoType::Label(_) | MonoType::Tuple(_) => {
+                bail!("type `{}` cannot be written as flux", typ)
+            }
+        })
+    }
+
+    fn record(&mut self, mut record: &types::Record) -> Result<ast::MonoType> {
+        let mut properties = Vec::new();
+        let tvar = loop {
+            match record {
+                types::Record::Empty => break None,
+                types::Record::Extension {
+                    head,
+                    tail,
+                    optional,
+                } => {
+                    let name = match &head.k {
+                        // A single upper case letter would be read back as a type variable
+                        RecordLabel::Concrete(label)
+                            if label.len() == 1 && label.starts_with(char::is_uppercase) =>
+                        {
+                            ast::PropertyKey::StringLit(ast::StringLit {
+                                base: ast::BaseNode::default(),
+                                value: label.to_string(),
+                            })
+                        }
+                        RecordLabel::Concrete(label) => property_key(label, &Default::default()),
+                        RecordLabel::Variable(tv) | RecordLabel::BoundVariable(tv) => {
+                            ast::PropertyKey::Identifier(self.tvar(*tv)?)
+                        }
+                        RecordLabel::Error => {
+                            bail!("record label errors cannot be written as flux")
+                        }
+                    };
+                    properties.push(ast::PropertyType {
+                        base: ast::BaseNode::default(),
+                        name,
+                        monotype: self.monotype(&head.v)?,
+                        optional: *optional,
+                    });
+                    match tail {
+                        MonoType::Record(tail) => record = tail,
+                        MonoType::Var(tv) | MonoType::BoundVar(tv) => break Some(self.tvar(*tv)?),
+                        _ => bail!("record type `{}` cannot be written as flux", tail),
+                    }
+                }
+            }
+        };
+        // The last property of a record type is the outermost extension
+        properties.reverse();
+        Ok(ast::MonoType::Record(ast::RecordType {
+            base: ast::BaseNode::default(),
+            tvar,
+            properties,
+        }))
+    }
+
+    fn function(&mut self, fun: &types::Function) -> Result<ast::MonoType> {
+        let mut parameters = Vec::new();
+        if let Some(pipe) = &fun.pipe {
+            parameters.push(ast::ParameterType::Pipe {
+                base: ast::BaseNode::default(),
+                name: if pipe.k == "<-" {
+                    None
+                } else {
+                    Some(type_identifier(&pipe.k))
+                },
+                monotype: self.monotype(&pipe.v)?,
+            });
+        }
+        for (name, typ) in &fun.req {
+            parameters.push(ast::ParameterType::Required {
+                base: ast::BaseNode::default(),
+                name: type_identifier(name),
+                monotype: self.monotype(typ)?,
+            });
+        }
+        for (name, typ) in &fun.opt {
+            parameters.push(ast::ParameterType::Optional {
+                base: ast::BaseNode::default(),
+                name: type_identifier(name),
+                monotype: self.monotype(typ)?,
+            });
+        }
+        Ok(ast::MonoType::Function(Box::new(ast::FunctionType {
+            base: ast::BaseNode::default(),
+            parameters,
+            monotype: self.monotype(&fun.retn)?,
+        })))
+    }
+}
+
+fn type_identifier(name: &str) -> ast::Identifier {
+    ast::Identifier {
+        base: ast::BaseNode::default(),
+        name: name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        formatter,
+        semantic::{self, env::Environment, import::Packages, Analyzer, AnalyzerConfig, Feature},
+    };
+
+    fn analyze(src: &str, config: AnalyzerConfig) -> Package {
+        let mut analyzer = Analyzer::new(Environment::default(), Packages::new(), config);
+        let (_, pkg) = analyzer
+            .analyze_source("main".to_string(), "main.flux".to_string(), src)
+            .unwrap_or_else(|err| panic!("{}", err));
+        pkg
+    }
+
+    fn format(pkg: &Package) -> String {
+        let ast = to_ast(pkg).unwrap();
+        formatter::convert_to_string(&ast.files[0]).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let src = r#"package main
+
+builtin sum : (<-tables: [A], ?column: string) => A where A: Addable
+
+type Point = {x: int, y: int}
+
+option now = () => 2021-01-01T00:00:00Z
+
+add = (a, b=1) => a + b
+double = (x=<-) => x * 2
+total = 1 |> double() |> add(b: 2)
+name = "flux"
+greeting = "hello ${name}!"
+r = {name, "my key": 1, size: 10}
+v = r["my key"] + r.size
+d = -1h30m
+f = (x) => {
+    y = x + 1
+
+    return if y > 2 and y < 10 then y else 0
+}
+m = ["a": 1, "b": 2]
+"#;
+        let pkg = analyze(src, AnalyzerConfig::default());
+        let formatted = format(&pkg);
+        assert_eq!(formatter::format(src).unwrap(), formatted);
+
+        // The lowered package means the same thing as the original one
+        let reanalyzed = analyze(&formatted, AnalyzerConfig::default());
+        assert_eq!(
+            semantic::formatter::format(&pkg).unwrap(),
+            semantic::formatter::format(&reanalyzed).unwrap(),
+        );
+    }
+
+    #[test]
+    fn modified_literals_are_written_from_their_value() {
+        let mut pkg = analyze(r#"s = "a""#, AnalyzerConfig::default());
+        match &mut pkg.files[0].body[0] {
+            Statement::Variable(assign) => match &mut assign.init {
+                Expression::StringLit(lit) => lit.value = "b".to_string(),
+                init => panic!("expected a string literal, found {:?}", init),
+            },
+            stmt => panic!("expected a variable assignment, found {:?}", stmt),
+        }
+        assert_eq!(r#"s = "b""#, format(&pkg));
+    }
+
+    #[test]
+    fn bound_pipe_placeholder() {
+        let config = AnalyzerConfig {
+            features: vec![Feature::PipeInMiddle],
+            ..AnalyzerConfig::default()
+        };
+        let pkg = analyze(
+            r#"sub = (a, b) => a - b
+x = 1 |> sub(a: 2, b: _)"#,
+            config,
+        );
+        expect_test::expect![[r#"
+            sub = (a, b) => a - b
+            x = sub(a: 2, b: 1)"#]]
+        .assert_eq(&format(&pkg));
+    }
+}
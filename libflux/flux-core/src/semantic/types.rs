@@ -2,7 +2,7 @@
 
 use std::{
     borrow::Cow,
-    cell::Cell,
+    cell::{Cell, RefCell},
     cmp,
     collections::{BTreeMap, BTreeSet},
     fmt::{self, Write},
@@ -15,12 +15,15 @@ use derive_more::Display;
 use serde::ser::{Serialize, Serializer};
 
 use crate::{
-    errors::{Errors, Located},
+    errors::{Errors, Located, Severity},
     map::HashMap,
     semantic::{
         fresh::{Fresh, Fresher},
+        infer,
         nodes::Symbol,
-        sub::{apply2, apply3, apply4, merge_collect, Substitutable, Substituter, Substitution},
+        sub::{
+            apply2, apply3, apply4, merge, merge_collect, Substitutable, Substituter, Substitution,
+        },
     },
 };
 
@@ -120,8 +123,13 @@ struct Unifier<'a, E = Error> {
     // We must delay the inference of records with label variables until we have inferred
     // the remaining context.
     delayed_records: Vec<(Record, Record)>,
+    // Labels of actual records that were passed over while looking for an expected label.
+    // Used to suggest a similar label if the expected label turns out to be missing.
+    skipped_labels: BTreeMap<String, Vec<String>>,
     errors: Errors<E>,
     matcher: &'a dyn Matcher<Error>,
+    // How many types enclose the types being unified
+    depth: usize,
 }
 
 impl<'a, E> Unifier<'a, E> {
@@ -129,8 +137,10 @@ impl<'a, E> Unifier<'a, E> {
         Unifier {
             sub,
             delayed_records: Vec::new(),
+            skipped_labels: BTreeMap::new(),
             errors: Errors::new(),
             matcher: &Unify,
+            depth: 0,
         }
     }
 
@@ -138,8 +148,10 @@ impl<'a, E> Unifier<'a, E> {
         Unifier {
             sub,
             delayed_records: Vec::new(),
+            skipped_labels: BTreeMap::new(),
             errors: Errors::new(),
             matcher: &Subsume,
+            depth: 0,
         }
     }
 
@@ -147,8 +159,10 @@ impl<'a, E> Unifier<'a, E> {
         Unifier {
             sub: self.sub,
             delayed_records: Vec::new(),
+            skipped_labels: BTreeMap::new(),
             errors: Errors::new(),
             matcher: self.matcher,
+            depth: self.depth,
         }
     }
 
@@ -184,10 +198,80 @@ pub struct PolyType {
     pub vars: Vec<Tvar>,
     /// The list of kind constraints on any of the free variables.
     pub cons: TvarKinds,
+    /// The labels which variables of the `Label` kind are restricted to, written
+    /// `where L: Label in {"a", "b"}`. Variables without an entry may be any label.
+    pub label_sets: LabelSets,
+    /// The type aliases applied within `expr`, which are displayed in place of the types they
+    /// expand to.
+    pub aliases: Vec<TypeAlias>,
+    /// The names which the type parameters were declared with when they are longer than one
+    /// letter, e.g. `Col` in `(column: Col) => Col where Col: Label`. They are displayed in place
+    /// of the generated names.
+    pub names: TvarNames,
     /// The underlying monotype.
     pub expr: MonoType,
 }
 
+/// A type alias applied within the type of a [`PolyType`], e.g. `Pair[int]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAlias {
+    /// Name of the alias.
+    pub name: String,
+    /// The types the parameters of the alias are applied to.
+    pub args: Vec<MonoType>,
+    /// The type the alias expands to.
+    pub expr: MonoType,
+}
+
+impl fmt::Display for TypeAlias {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if !self.args.is_empty() {
+            write!(
+                f,
+                "[{}]",
+                self.args
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Substitutable for TypeAlias {
+    fn walk(&self, sub: &dyn Substituter) -> Option<Self> {
+        let args = merge_collect(
+            &mut (),
+            &self.args,
+            |_, arg| arg.visit(sub),
+            |_, arg| arg.clone(),
+        );
+        merge(&self.args, args, &self.expr, self.expr.visit(sub)).map(|(args, expr)| TypeAlias {
+            name: self.name.clone(),
+            args,
+            expr,
+        })
+    }
+}
+
+/// Map from type variables to the labels they are restricted to, see [`PolyType::label_sets`].
+pub type LabelSets = SemanticMap<Tvar, Vec<String>>;
+
+/// Map from type variables to the names they were declared with, see [`PolyType::names`].
+pub type TvarNames = SemanticMap<Tvar, String>;
+
+// Displays `labels` quoted and separated by commas, e.g. `"a", "b"`
+fn display_labels(labels: &[String]) -> String {
+    labels
+        .iter()
+        .map(|label| format!("{:?}", label))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Map of identifier to a polytype that preserves a sorted order when iterating.
 pub type PolyTypeMap<T = String> = SemanticMap<T, PolyType>;
 /// Nested map of polytypes that preserves a sorted order when iterating
@@ -204,11 +288,17 @@ macro_rules! semantic_map {
 
 impl fmt::Display for PolyType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.expr)?;
-        if !self.cons.is_empty() {
-            write!(f, " where {}", PolyType::display_constraints(&self.cons),)?;
-        }
-        Ok(())
+        with_tvar_names(&self.names, || {
+            with_type_aliases(&self.aliases, || write!(f, "{}", self.expr))?;
+            if !self.cons.is_empty() {
+                write!(
+                    f,
+                    " where {}",
+                    PolyType::display_constraints(&self.cons, &self.label_sets),
+                )?;
+            }
+            Ok(())
+        })
     }
 }
 
@@ -235,8 +325,11 @@ impl PartialEq for PolyType {
         for kinds in b.cons.values_mut() {
             kinds.sort();
         }
+        for labels in a.label_sets.values_mut().chain(b.label_sets.values_mut()) {
+            labels.sort();
+        }
 
-        a.vars == b.vars && a.cons == b.cons && a.expr == b.expr
+        a.vars == b.vars && a.cons == b.cons && a.label_sets == b.label_sets && a.expr == b.expr
     }
 }
 
@@ -251,6 +344,13 @@ impl Substitutable for PolyType {
         self.expr.visit(sub).map(|expr| PolyType {
             vars: self.vars.clone(),
             cons: self.cons.clone(),
+            label_sets: self.label_sets.clone(),
+            aliases: self
+                .aliases
+                .iter()
+                .map(|alias| alias.apply_cow(sub).into_owned())
+                .collect(),
+            names: self.names.clone(),
             expr,
         })
     }
@@ -261,17 +361,108 @@ impl PolyType {
         PolyType {
             vars: Vec::new(),
             cons: BTreeMap::new(),
+            label_sets: LabelSets::new(),
+            aliases: Vec::new(),
+            names: TvarNames::new(),
             expr: MonoType::Error,
         }
     }
 
-    fn display_constraints(cons: &TvarKinds) -> String {
+    /// Writes the type with its quantifiers made explicit, e.g.
+    /// `forall [A, B] where A: Addable . (x: A, y: B) => A`.
+    ///
+    /// The compact form, without the quantifiers, is used by `Display`.
+    pub fn fmt_verbose(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut vars = self.vars.clone();
+        vars.sort();
+        with_tvar_names(&self.names, || {
+            write!(
+                f,
+                "forall [{}]",
+                vars.iter()
+                    .map(|tv| tv.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+            if !self.cons.is_empty() {
+                write!(
+                    f,
+                    " where {}",
+                    PolyType::display_constraints(&self.cons, &self.label_sets)
+                )?;
+            }
+            write!(f, " . {}", self.expr)
+        })
+    }
+
+    /// Returns a value which displays the type using [`PolyType::fmt_verbose`].
+    pub fn verbose(&self) -> impl fmt::Display + '_ {
+        struct Verbose<'a>(&'a PolyType);
+        impl fmt::Display for Verbose<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt_verbose(f)
+            }
+        }
+        Verbose(self)
+    }
+
+    /// Returns `true` if `self` is at least as general as `other`, that is if every instance of
+    /// `other` is also an instance of `self`. `(x: A) => A` subsumes `(x: int) => int`, but not
+    /// the other way around.
+    ///
+    /// The type variables of `other` are skolemized: unifying an instance of `self` with `other`
+    /// may rename them but not bind them to a type, merge them or add kinds to them.
+    pub fn subsumes(&self, other: &PolyType) -> bool {
+        let mut other_vars = TvarMap::new();
+        let other = other
+            .clone()
+            .fresh(&mut Fresher::default(), &mut other_vars);
+
+        // Keep the variables of `other` apart from the ones `self` is instantiated with
+        let mut sub = Substitution::default();
+        sub.mk_fresh(other_vars.len());
+        for (var, kinds) in &other.cons {
+            sub.cons().insert(*var, kinds.clone());
+        }
+
+        let (typ, cons) = infer::instantiate(
+            self.clone(),
+            &mut sub,
+            crate::ast::SourceLocation::default(),
+        );
+        if infer::solve(&cons, &mut sub).is_err() || typ.try_unify(&other.expr, &mut sub).is_err() {
+            return false;
+        }
+
+        let mut renamed = Vec::new();
+        other_vars
+            .values()
+            .all(|var| match MonoType::Var(*var).apply(&sub) {
+                MonoType::Var(root) if !renamed.contains(&root) => {
+                    renamed.push(root);
+                    let mut kinds = sub.cons().get(&root).cloned().unwrap_or_default();
+                    let mut other_kinds = other.cons.get(var).cloned().unwrap_or_default();
+                    kinds.sort();
+                    other_kinds.sort();
+                    kinds == other_kinds
+                }
+                _ => false,
+            })
+    }
+
+    fn display_constraints(cons: &TvarKinds, label_sets: &LabelSets) -> String {
         cons.iter()
             // A BTree produces a sorted iterator for
             // deterministic display output
             .collect::<BTreeMap<_, _>>()
             .iter()
-            .map(|(&&tv, &kinds)| format!("{}: {}", tv, PolyType::display_kinds(kinds)))
+            .map(|(&&tv, &kinds)| {
+                let mut constraint = format!("{}: {}", tv, PolyType::display_kinds(kinds));
+                if let Some(labels) = label_sets.get(&tv) {
+                    constraint.push_str(&format!(" in {{{}}}", display_labels(labels)));
+                }
+                constraint
+            })
             .collect::<Vec<_>>()
             .join(", ")
     }
@@ -317,7 +508,10 @@ pub enum Error {
         act: MonoType,
     },
     OccursCheck(Tvar, MonoType),
-    MissingLabel(String),
+    MissingLabel {
+        label: String,
+        suggestion: Option<String>,
+    },
     ExtraLabel(String),
     CannotUnifyLabel {
         lab: String,
@@ -339,65 +533,127 @@ pub enum Error {
         act: String,
     },
     NotALabel(MonoType),
+    DynamicLabel,
+    TooDeeplyNested,
+    LabelNotInSet {
+        label: String,
+        allowed: Vec<String>,
+    },
+    // `error` with the variables that replaced declared type parameters, see `PolyType::names`
+    Named {
+        error: Box<Error>,
+        names: TvarNames,
+    },
 }
 
+impl Severity for Error {}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut fresh = Fresher::from(0);
+        self.fmt_canonical(f, &mut Fresher::from(0), &mut TvarMap::new())
+    }
+}
+
+impl Error {
+    // Type variables are renamed in the order they first appear in the error (including any
+    // causes) so that the same variable always gets the same name within a single error.
+    fn fmt_canonical(
+        &self,
+        f: &mut fmt::Formatter,
+        fresh: &mut Fresher,
+        vars: &mut TvarMap,
+    ) -> fmt::Result {
         match self {
             Error::CannotUnify { exp, act } => write!(
                 f,
                 "expected {exp}{exp_info} but found {act}{act_info}",
-                exp = exp.clone().fresh(&mut fresh, &mut TvarMap::new()),
+                exp = exp.clone().fresh(fresh, vars),
                 exp_info = exp.type_info(),
-                act = act.clone().fresh(&mut fresh, &mut TvarMap::new()),
+                act = act.clone().fresh(fresh, vars),
                 act_info = act.type_info(),
             ),
             Error::CannotConstrain { exp, act } => write!(
                 f,
                 "{act}{act_info} is not {exp}",
-                act = act.clone().fresh(&mut fresh, &mut TvarMap::new()),
+                act = act.clone().fresh(fresh, vars),
                 act_info = act.type_info(),
                 exp = exp,
             ),
             Error::OccursCheck(tv, ty) => {
                 write!(f, "recursive types not supported {} != {}", tv, ty)
             }
-            Error::MissingLabel(a) => write!(f, "record is missing label {}", a),
+            Error::MissingLabel { label, suggestion } => {
+                write!(f, "record is missing label {}", label)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, ", did you mean \"{}\"?", suggestion)?;
+                }
+                Ok(())
+            }
             Error::ExtraLabel(a) => write!(f, "found unexpected label {}", a),
             Error::CannotUnifyLabel {
                 lab,
                 exp,
                 act,
                 cause,
-            } => write!(
-                f,
-                "expected {exp}{exp_info} but found {act}{act_info} for label {lab} caused by {cause}",
-                exp = exp.clone().fresh(&mut fresh, &mut TvarMap::new()),
-                exp_info = exp.type_info(),
-                act = act.clone().fresh(&mut fresh, &mut TvarMap::new()),
-                act_info = act.type_info(),
-                lab = lab,
-                cause = cause
-            ),
+            } => {
+                write!(
+                    f,
+                    "expected {exp}{exp_info} but found {act}{act_info} for label {lab} caused by ",
+                    exp = exp.clone().fresh(fresh, vars),
+                    exp_info = exp.type_info(),
+                    act = act.clone().fresh(fresh, vars),
+                    act_info = act.type_info(),
+                    lab = lab,
+                )?;
+                cause.fmt_canonical(f, fresh, vars)
+            }
             Error::MissingArgument(x) => write!(f, "missing required argument {}", x),
             Error::ExtraArgument(x) => write!(f, "found unexpected argument {}", x),
-            Error::CannotUnifyArgument(x, e) => write!(f, "{} (argument {})", e, x),
-            Error::CannotUnifyReturn { exp, act, cause } => write!(
-                f,
-                "expected {exp}{exp_info} but found {act}{act_info} for return type caused by {cause}",
-                exp = exp.clone().fresh(&mut fresh, &mut TvarMap::new()),
-                exp_info = exp.type_info(),
-                act = act.clone().fresh(&mut fresh, &mut TvarMap::new()),
-                act_info = act.type_info(),
-                cause = cause
-            ),
+            Error::CannotUnifyArgument(x, e) => {
+                e.fmt_canonical(f, fresh, vars)?;
+                write!(f, " (argument {})", x)
+            }
+            Error::CannotUnifyReturn { exp, act, cause } => {
+                write!(
+                    f,
+                    "expected {exp}{exp_info} but found {act}{act_info} for return type caused by ",
+                    exp = exp.clone().fresh(fresh, vars),
+                    exp_info = exp.type_info(),
+                    act = act.clone().fresh(fresh, vars),
+                    act_info = act.type_info(),
+                )?;
+                cause.fmt_canonical(f, fresh, vars)
+            }
             Error::MissingPipeArgument => write!(f, "missing pipe argument"),
             Error::MultiplePipeArguments { exp, act } => {
                 write!(f, "expected pipe argument {} but found {}", exp, act)
             }
-            Error::NotALabel(typ)  => {
-                write!(f, "{} is not a label", typ)
+            Error::NotALabel(typ) => {
+                write!(f, "{} is not a label", typ.clone().fresh(fresh, vars))
+            }
+            Error::DynamicLabel => write!(
+                f,
+                "expected a literal label but found a string computed at runtime, column names \
+                 must be literal labels: use a literal such as \"_value\" or a function which \
+                 accepts dynamic columns"
+            ),
+            Error::TooDeeplyNested => write!(f, "type too deeply nested"),
+            Error::LabelNotInSet { label, allowed } => write!(
+                f,
+                "{:?} is not one of the allowed labels {}",
+                label,
+                display_labels(allowed)
+            ),
+            Error::Named { error, names } => {
+                // The named variables are renamed to the end of the range, apart from the
+                // variables renamed in order, and then displayed by their names
+                let mut display_names = TvarNames::new();
+                for (i, (var, name)) in names.iter().enumerate() {
+                    let display_var = Tvar(u64::MAX - i as u64);
+                    vars.insert(*var, display_var);
+                    display_names.insert(display_var, name.clone());
+                }
+                with_tvar_names(&display_names, || error.fmt_canonical(f, fresh, vars))
             }
         }
     }
@@ -430,12 +686,19 @@ impl Substitutable for Error {
             Error::CannotUnifyReturn { exp, act, cause } => apply3(exp, act, cause, sub)
                 .map(|(exp, act, cause)| Error::CannotUnifyReturn { exp, act, cause }),
             Error::NotALabel(t) => t.visit(sub).map(Error::NotALabel),
-            Error::MissingLabel(_)
+            Error::Named { error, names } => error.visit(sub).map(|error| Error::Named {
+                error,
+                names: names.clone(),
+            }),
+            Error::MissingLabel { .. }
             | Error::ExtraLabel(_)
             | Error::MissingArgument(_)
             | Error::ExtraArgument(_)
             | Error::MissingPipeArgument
-            | Error::MultiplePipeArguments { .. } => None,
+            | Error::MultiplePipeArguments { .. }
+            | Error::DynamicLabel
+            | Error::TooDeeplyNested
+            | Error::LabelNotInSet { .. } => None,
         }
     }
 }
@@ -446,6 +709,44 @@ impl Error {
     }
 }
 
+/// Returns the candidate closest to `label` if it is within a small edit distance of it.
+pub(crate) fn closest_label(label: &str, candidates: &[String]) -> Option<String> {
+    let len = label.chars().count();
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(label, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2 && *distance < len)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+/// Computes the number of insertions, deletions, substitutions and transpositions of adjacent
+/// characters needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = cmp::min(
+                cmp::min(d[i - 1][j] + 1, d[i][j - 1] + 1),
+                d[i - 1][j - 1] + cost,
+            );
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = cmp::min(d[i][j], d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
 /// Represents a constraint on a type variable to a specific kind (*i.e.*, a type class).
 #[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(missing_docs)]
@@ -457,6 +758,7 @@ pub enum Kind {
     Divisible,
     Equatable,
     Label,
+    Lengthable,
     Negatable,
     Nullable,
     Numeric,
@@ -484,6 +786,7 @@ impl FromStr for Kind {
             "Record" => Kind::Record,
             "Basic" => Kind::Basic,
             "Stringable" => Kind::Stringable,
+            "Lengthable" => Kind::Lengthable,
             _ => return Err(()),
         })
     }
@@ -526,37 +829,77 @@ pub enum BuiltinType {
 
 /// Represents a Flux type. The type may be unknown, represented as a type variable,
 /// or may be a known concrete type.
-#[derive(Debug, Display, Clone, PartialEq)]
+///
+/// Compound types are stored behind a [`Ptr`] so cloning a `MonoType` is O(1) and applying a
+/// substitution only allocates the parts of a type that actually changed, the rest is shared.
+/// Types are not interned: equal types which were built separately are separate allocations.
+#[derive(Debug, Clone)]
 #[allow(missing_docs)]
 pub enum MonoType {
-    #[display(fmt = "<error>")]
     Error,
-
-    #[display(fmt = "{}", _0)]
     Builtin(BuiltinType),
-
-    #[display(fmt = "\"{}\"", _0)]
     Label(Label),
-    #[display(fmt = "#{}", _0)]
     Var(Tvar),
-
     /// A type variable that is bound to to a `PolyType` that this variable is contained in.
-    #[display(fmt = "{}", _0)]
     BoundVar(Tvar),
-
-    #[display(fmt = "{}", _0)]
     Collection(Ptr<Collection>),
-
-    #[display(fmt = "{}", _0)]
     Dict(Ptr<Dictionary>),
-
-    #[display(fmt = "{}", _0)]
     Record(Ptr<Record>),
-
-    #[display(fmt = "{}", _0)]
+    Tuple(Ptr<Tuple>),
     Fun(Ptr<Function>),
 }
 
+impl fmt::Display for MonoType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(alias) = find_type_alias(self) {
+            return write!(f, "{}", alias);
+        }
+        match self {
+            MonoType::Error => f.write_str("<error>"),
+            MonoType::Builtin(typ) => write!(f, "{}", typ),
+            MonoType::Label(label) => write!(f, "\"{}\"", label),
+            MonoType::Var(tv) => write!(f, "#{}", tv),
+            MonoType::BoundVar(tv) => write!(f, "{}", tv),
+            MonoType::Collection(collection) => write!(f, "{}", collection),
+            MonoType::Dict(dict) => write!(f, "{}", dict),
+            MonoType::Record(record) => write!(f, "{}", record),
+            MonoType::Tuple(tuple) => write!(f, "{}", tuple),
+            MonoType::Fun(fun) => write!(f, "{}", fun),
+        }
+    }
+}
+
+// Types which share the same allocation are equal without having to compare their contents. This
+// is only a fast path for the clones of a type, such as the parts a substitution left unchanged,
+// types which were built separately are still compared by their contents.
+impl PartialEq for MonoType {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MonoType::Error, MonoType::Error) => true,
+            (MonoType::Builtin(l), MonoType::Builtin(r)) => l == r,
+            (MonoType::Label(l), MonoType::Label(r)) => l == r,
+            (MonoType::Var(l), MonoType::Var(r)) => l == r,
+            (MonoType::BoundVar(l), MonoType::BoundVar(r)) => l == r,
+            (MonoType::Collection(l), MonoType::Collection(r)) => Ptr::ptr_eq(l, r) || l == r,
+            (MonoType::Dict(l), MonoType::Dict(r)) => Ptr::ptr_eq(l, r) || l == r,
+            (MonoType::Record(l), MonoType::Record(r)) => Ptr::ptr_eq(l, r) || l == r,
+            (MonoType::Tuple(l), MonoType::Tuple(r)) => Ptr::ptr_eq(l, r) || l == r,
+            (MonoType::Fun(l), MonoType::Fun(r)) => Ptr::ptr_eq(l, r) || l == r,
+            // Listed explicitly so that adding a variant forces this impl to be revisited
+            (MonoType::Error, _)
+            | (MonoType::Builtin(_), _)
+            | (MonoType::Label(_), _)
+            | (MonoType::Var(_), _)
+            | (MonoType::BoundVar(_), _)
+            | (MonoType::Collection(_), _)
+            | (MonoType::Dict(_), _)
+            | (MonoType::Record(_), _)
+            | (MonoType::Tuple(_), _)
+            | (MonoType::Fun(_), _) => false,
+        }
+    }
+}
+
 impl Serialize for MonoType {
     fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
     where
@@ -580,6 +923,7 @@ impl Serialize for MonoType {
             Arr(&'a MonoType),
             Dict(&'a Ptr<Dictionary>),
             Record(&'a Ptr<Record>),
+            Tuple(&'a Ptr<Tuple>),
             Fun(&'a Ptr<Function>),
             Vector(&'a MonoType),
             Stream(&'a MonoType),
@@ -609,6 +953,7 @@ impl Serialize for MonoType {
             Self::Label(p) => MonoTypeSer::Label(p),
             Self::Dict(p) => MonoTypeSer::Dict(p),
             Self::Record(p) => MonoTypeSer::Record(p),
+            Self::Tuple(p) => MonoTypeSer::Tuple(p),
             Self::Fun(p) => MonoTypeSer::Fun(p),
         }
         .serialize(serializer)
@@ -726,7 +1071,8 @@ impl BuiltinType {
                 | Kind::Equatable
                 | Kind::Nullable
                 | Kind::Basic
-                | Kind::Stringable => Ok(()),
+                | Kind::Stringable
+                | Kind::Lengthable => Ok(()),
                 _ => Err(Error::CannotConstrain {
                     act: self.into(),
                     exp: with,
@@ -765,7 +1111,7 @@ impl BuiltinType {
                 }),
             },
             BuiltinType::Bytes => match with {
-                Kind::Equatable | Kind::Basic => Ok(()),
+                Kind::Equatable | Kind::Basic | Kind::Lengthable => Ok(()),
                 _ => Err(Error::CannotConstrain {
                     act: self.into(),
                     exp: with,
@@ -793,6 +1139,7 @@ impl Substitutable for MonoType {
             MonoType::Collection(app) => app.visit(sub).map(MonoType::app),
             MonoType::Dict(dict) => dict.visit(sub).map(MonoType::dict),
             MonoType::Record(obj) => obj.visit(sub).map(MonoType::record),
+            MonoType::Tuple(tuple) => tuple.visit(sub).map(MonoType::tuple),
             MonoType::Fun(fun) => fun.visit(sub).map(MonoType::fun),
         }
     }
@@ -828,6 +1175,12 @@ impl From<Record> for MonoType {
     }
 }
 
+impl From<Tuple> for MonoType {
+    fn from(t: Tuple) -> MonoType {
+        MonoType::Tuple(Ptr::new(t))
+    }
+}
+
 impl From<Function> for MonoType {
     fn from(f: Function) -> MonoType {
         MonoType::Fun(Ptr::new(f))
@@ -892,6 +1245,11 @@ impl MonoType {
         Self::Record(r.into())
     }
 
+    /// Creates a tuple type
+    pub fn tuple(t: impl Into<Ptr<Tuple>>) -> Self {
+        Self::Tuple(t.into())
+    }
+
     /// Performs unification on the type with another type.
     /// If successful, results in a solution to the unification problem,
     /// in the form of a substitution. If there is no solution to the
@@ -931,7 +1289,16 @@ impl MonoType {
     ) -> MonoType {
         log::debug!("Unify {} <=> {}", self, actual);
 
-        unifier.matcher.match_types(unifier, self, actual)
+        // Unification recurses into the types, so it must stop before pathologically nested types
+        // overflow the stack
+        if unifier.depth >= unifier.sub.max_unification_depth() {
+            unifier.errors.push(Error::TooDeeplyNested);
+            return MonoType::Error;
+        }
+        unifier.depth += 1;
+        let typ = unifier.matcher.match_types(unifier, self, actual);
+        unifier.depth -= 1;
+        typ
     }
 
     fn unify_inner(
@@ -981,6 +1348,8 @@ impl MonoType {
 
             (MonoType::Record(t), MonoType::Record(s)) => t.unify(s, unifier),
 
+            (MonoType::Tuple(t), MonoType::Tuple(s)) => t.unify(s, unifier),
+
             (MonoType::Fun(t), MonoType::Fun(s)) => t.unify(s, unifier),
 
             (exp, act) => unifier.errors.push(Error::CannotUnify {
@@ -1019,11 +1388,20 @@ impl MonoType {
             MonoType::Collection(app) => app.constrain(with, cons),
             MonoType::Dict(dict) => dict.constrain(with, cons),
             MonoType::Record(obj) => obj.constrain(with, cons),
+            MonoType::Tuple(tuple) => tuple.constrain(with, cons),
             MonoType::Fun(fun) => fun.constrain(with, cons),
         }
     }
 
-    fn contains(&self, tv: Tvar) -> bool {
+    /// Returns the free type variables of this type, the same variables the solver finds with
+    /// [`Substitutable::free_vars`].
+    pub fn free_vars(&self) -> BTreeSet<Tvar> {
+        Substitutable::free_vars(self).into_iter().collect()
+    }
+
+    /// Returns true if the type variable `tv` occurs in this type, within any of its arrays,
+    /// records or functions.
+    pub fn contains(&self, tv: Tvar) -> bool {
         match self {
             MonoType::Error | MonoType::Builtin(_) | MonoType::Label(_) | MonoType::BoundVar(_) => {
                 false
@@ -1032,6 +1410,7 @@ impl MonoType {
             MonoType::Collection(app) => app.contains(tv),
             MonoType::Dict(dict) => dict.contains(tv),
             MonoType::Record(row) => row.contains(tv),
+            MonoType::Tuple(tuple) => tuple.contains(tv),
             MonoType::Fun(fun) => fun.contains(tv),
         }
     }
@@ -1048,6 +1427,14 @@ impl MonoType {
         }
     }
 
+    /// Returns the type of the elements if `self` is an array type
+    pub fn array_element(&self) -> Option<&MonoType> {
+        match self {
+            MonoType::Collection(app) if app.collection == CollectionType::Array => Some(&app.arg),
+            _ => None,
+        }
+    }
+
     /// Returns the type of `field` if `self` is a record type
     pub fn field(&self, field: &str) -> Option<&Property> {
         match self {
@@ -1073,6 +1460,7 @@ impl MonoType {
             MonoType::Fun(_) => " (function)",
             MonoType::Dict(_) => " (dictionary)",
             MonoType::Record(_) => " (record)",
+            MonoType::Tuple(_) => " (tuple)",
             MonoType::Collection(app) => match app.collection {
                 CollectionType::Array => " (array)",
                 CollectionType::Vector => " (vector)",
@@ -1114,8 +1502,92 @@ pub type TvarMap = SemanticMap<Tvar, Tvar>;
 #[allow(missing_docs)]
 pub type SubstitutionMap = SemanticMap<Tvar, MonoType>;
 
+/// Controls how type variables are named when types are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TvarNaming {
+    /// Name the first ten variables `A` through `J` and the remaining ones `t10`, `t11`, ...
+    Letters,
+    /// Name every variable `t0`, `t1`, ...
+    Numbered,
+}
+
+impl Default for TvarNaming {
+    fn default() -> Self {
+        Self::Letters
+    }
+}
+
+thread_local! {
+    static TVAR_NAMING: Cell<TvarNaming> = Cell::new(TvarNaming::Letters);
+    // The declared names of the variables of the `PolyType` being displayed.
+    static TVAR_NAMES: RefCell<TvarNames> = RefCell::new(TvarNames::new());
+    // The type aliases applied within the `PolyType` being displayed.
+    static TYPE_ALIASES: RefCell<Vec<TypeAlias>> = RefCell::new(Vec::new());
+}
+
+/// Runs `f` with type variables displayed according to `naming`.
+pub fn with_tvar_naming<R>(naming: TvarNaming, f: impl FnOnce() -> R) -> R {
+    let previous = TVAR_NAMING.with(|current| current.replace(naming));
+    let result = f();
+    TVAR_NAMING.with(|current| current.set(previous));
+    result
+}
+
+// Runs `f` with the type variables in `names` displayed by the names they were declared with.
+fn with_tvar_names<R>(names: &TvarNames, f: impl FnOnce() -> R) -> R {
+    if names.is_empty() {
+        return f();
+    }
+    let previous = TVAR_NAMES.with(|current| current.replace(names.clone()));
+    let result = f();
+    TVAR_NAMES.with(|current| current.replace(previous));
+    result
+}
+
+// Runs `f` with the types which `aliases` expand to displayed by the aliases. An alias applied to
+// another alias expands to a larger type, so the outermost alias of a type is displayed.
+fn with_type_aliases<R>(aliases: &[TypeAlias], f: impl FnOnce() -> R) -> R {
+    if aliases.is_empty() {
+        return f();
+    }
+    let previous = TYPE_ALIASES.with(|current| current.replace(aliases.to_vec()));
+    let result = f();
+    TYPE_ALIASES.with(|current| current.replace(previous));
+    result
+}
+
+// Returns the alias which `typ` is displayed by, see `with_type_aliases`.
+fn find_type_alias(typ: &MonoType) -> Option<TypeAlias> {
+    match typ {
+        // Other occurrences of a builtin type or a variable can't be told apart from an aliased
+        // one, so only aliases of composite types are displayed
+        MonoType::Error
+        | MonoType::Builtin(_)
+        | MonoType::Label(_)
+        | MonoType::Var(_)
+        | MonoType::BoundVar(_) => None,
+        MonoType::Collection(_)
+        | MonoType::Dict(_)
+        | MonoType::Record(_)
+        | MonoType::Tuple(_)
+        | MonoType::Fun(_) => TYPE_ALIASES.with(|aliases| {
+            aliases
+                .borrow()
+                .iter()
+                .find(|alias| alias.expr == *typ)
+                .cloned()
+        }),
+    }
+}
+
 impl fmt::Display for Tvar {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if TVAR_NAMING.with(Cell::get) == TvarNaming::Numbered {
+            return write!(f, "t{}", self.0);
+        }
+        if let Some(name) = TVAR_NAMES.with(|names| names.borrow().get(self).cloned()) {
+            return f.write_str(&name);
+        }
         match self.0 {
             0 => write!(f, "A"),
             1 => write!(f, "B"),
@@ -1212,6 +1684,10 @@ impl Collection {
         match self.collection {
             CollectionType::Array | CollectionType::Stream => match with {
                 Kind::Equatable => self.arg.constrain(with, cons),
+                // Adding two arrays concatenates them
+                Kind::Addable | Kind::Lengthable if self.collection == CollectionType::Array => {
+                    Ok(())
+                }
                 _ => Err(Error::CannotConstrain {
                     act: MonoType::app(self.clone()),
                     exp: with,
@@ -1259,6 +1735,80 @@ impl Dictionary {
     }
 }
 
+/// A fixed-size, heterogeneous sequence of types such as `(int, string)`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Tuple {
+    /// Types of the elements, in positional order.
+    pub elements: Vec<MonoType>,
+}
+
+impl fmt::Display for Tuple {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("(")?;
+        for (i, element) in self.elements.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", element)?;
+        }
+        // A trailing comma distinguishes a single element tuple from a parenthesized type
+        if self.elements.len() == 1 {
+            f.write_str(",")?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl Substitutable for Tuple {
+    fn walk(&self, sub: &dyn Substituter) -> Option<Self> {
+        merge_collect(
+            &mut (),
+            &self.elements,
+            |_, element| element.visit(sub),
+            |_, element| element.clone(),
+        )
+        .map(|elements| Tuple { elements })
+    }
+}
+
+impl Tuple {
+    /// Returns the type of the element at `index`, if the tuple has one.
+    pub fn get(&self, index: usize) -> Option<&MonoType> {
+        self.elements.get(index)
+    }
+
+    // self represents the expected type.
+    fn unify(&self, actual: &Self, unifier: &mut Unifier<'_>) {
+        if self.elements.len() != actual.elements.len() {
+            unifier.errors.push(Error::CannotUnify {
+                exp: MonoType::from(self.clone()),
+                act: MonoType::from(actual.clone()),
+            });
+            return;
+        }
+        for (exp, act) in self.elements.iter().zip(&actual.elements) {
+            exp.unify(act, unifier);
+        }
+    }
+
+    fn constrain(&self, with: Kind, cons: &mut TvarKinds) -> Result<(), Error> {
+        match with {
+            Kind::Equatable => self
+                .elements
+                .iter()
+                .try_for_each(|element| element.constrain(with, cons)),
+            _ => Err(Error::CannotConstrain {
+                act: MonoType::tuple(self.clone()),
+                exp: with,
+            }),
+        }
+    }
+
+    fn contains(&self, tv: Tvar) -> bool {
+        self.elements.iter().any(|element| element.contains(tv))
+    }
+}
+
 /// An extensible record type.
 ///
 /// A record is either `Empty`, meaning it has no properties,
@@ -1278,6 +1828,9 @@ pub enum Record {
         head: Property,
         /// `tail` is the record variable.
         tail: MonoType,
+        /// Whether records may omit `head`, written `?label: type` in type annotations.
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        optional: bool,
     },
 }
 
@@ -1330,9 +1883,15 @@ impl Substitutable for Record {
     fn walk(&self, sub: &dyn Substituter) -> Option<Self> {
         match self {
             Record::Empty => None,
-            Record::Extension { head, tail } => {
-                apply2(head, tail, sub).map(|(head, tail)| Record::Extension { head, tail })
-            }
+            Record::Extension {
+                head,
+                tail,
+                optional,
+            } => apply2(head, tail, sub).map(|(head, tail)| Record::Extension {
+                head,
+                tail,
+                optional: *optional,
+            }),
         }
     }
 }
@@ -1353,13 +1912,18 @@ impl Record {
                     .next()
                     .expect("extensible records must have at least one field");
 
-                Record::Extension { head, tail }
+                Record::Extension {
+                    head,
+                    tail,
+                    optional: false,
+                }
             }
         };
 
         props.fold(ret, |ret, head| Record::Extension {
             head,
             tail: MonoType::record(ret),
+            optional: false,
         })
     }
 
@@ -1409,10 +1973,12 @@ impl Record {
                 Record::Extension {
                     head: Property { k: a, v: t },
                     tail: MonoType::Var(l),
+                    ..
                 },
                 Record::Extension {
                     head: Property { k: b, v: u },
                     tail: MonoType::Var(r),
+                    ..
                 },
             ) if a == b && l == r => unify_in_context(t, u, unifier, |e| Error::CannotUnifyLabel {
                 lab: a.to_string(),
@@ -1424,10 +1990,12 @@ impl Record {
                 Record::Extension {
                     head: Property { k: a, .. },
                     tail: MonoType::Var(l),
+                    ..
                 },
                 Record::Extension {
                     head: Property { k: b, .. },
                     tail: MonoType::Var(r),
+                    ..
                 },
             ) if a != b && l == r => {
                 unifier.errors.push(Error::CannotUnify {
@@ -1439,25 +2007,36 @@ impl Record {
                 Record::Extension {
                     head: Property { k: a, v: t },
                     tail: l,
+                    ..
                 },
                 Record::Extension {
                     head: Property { k: b, v: u },
                     tail: r,
+                    ..
                 },
             ) if a == b => {
                 t.unify(u, unifier);
-                l.unify(r, unifier);
+                Record::unify_tail(l, r, unifier);
             }
             (
                 Record::Extension {
                     head: Property { k: a, v: t },
                     tail: l,
+                    optional: a_optional,
                 },
                 Record::Extension {
                     head: Property { k: b, v: u },
                     tail: r,
+                    optional: b_optional,
                 },
             ) if a != b => {
+                if let RecordLabel::Concrete(_) = *b.apply_cow(unifier.sub) {
+                    unifier
+                        .skipped_labels
+                        .entry(a.to_string())
+                        .or_default()
+                        .push(b.to_string());
+                }
                 let var = unifier.sub.fresh();
                 let exp = MonoType::from(Record::Extension {
                     head: Property {
@@ -1465,6 +2044,7 @@ impl Record {
                         v: t.clone(),
                     },
                     tail: MonoType::Var(var),
+                    optional: *a_optional,
                 });
                 let act = MonoType::from(Record::Extension {
                     head: Property {
@@ -1472,9 +2052,23 @@ impl Record {
                         v: u.clone(),
                     },
                     tail: MonoType::Var(var),
+                    optional: *b_optional,
                 });
-                l.unify(&act, unifier);
-                exp.unify(r, unifier);
+                Record::unify_tail(l, &act, unifier);
+                Record::unify_tail(&exp, r, unifier);
+            }
+            // If we are expecting {?a: u | r} but find {}, the optional label `a` may be omitted.
+            // `u` is then the type of a null value and is not known any further.
+            (
+                Record::Extension {
+                    head: Property { v: u, .. },
+                    tail,
+                    optional: true,
+                },
+                Record::Empty,
+            ) => {
+                unifier.sub.absent(u.clone());
+                Record::unify_tail(tail, &MonoType::from(Record::Empty), unifier)
             }
             // If we are expecting {a: u | r} but find {}, label `a` is missing.
             (
@@ -1484,7 +2078,16 @@ impl Record {
                 },
                 Record::Empty,
             ) => match *a.apply_cow(unifier.sub) {
-                RecordLabel::Concrete(_) => unifier.errors.push(Error::MissingLabel(a.to_string())),
+                RecordLabel::Concrete(_) => {
+                    let label = a.to_string();
+                    let suggestion = unifier
+                        .skipped_labels
+                        .get(&label)
+                        .and_then(|candidates| closest_label(&label, candidates));
+                    unifier
+                        .errors
+                        .push(Error::MissingLabel { label, suggestion });
+                }
                 RecordLabel::BoundVariable(v) | RecordLabel::Variable(v) => {
                     let t = unifier.sub.apply(v);
                     t.unify(&MonoType::Error, unifier);
@@ -1511,9 +2114,23 @@ impl Record {
         }
     }
 
+    // The tail of a record holds its remaining fields rather than a nested type, so it is unified
+    // at the depth of the record itself. Otherwise a record with more fields than
+    // `max_unification_depth` could never be unified.
+    fn unify_tail(exp: &MonoType, act: &MonoType, unifier: &mut Unifier<'_>) {
+        let depth = unifier.depth;
+        unifier.depth = depth.saturating_sub(1);
+        exp.unify(act, unifier);
+        unifier.depth = depth;
+    }
+
     fn constrain(&self, with: Kind, cons: &mut TvarKinds) -> Result<(), Error> {
         match with {
-            Kind::Record => Ok(()),
+            // The record this record extends must be a record as well
+            Kind::Record => match self.tail() {
+                Some(t) => t.constrain(with, cons),
+                None => Ok(()),
+            },
             Kind::Equatable => {
                 let mut fields = self.fields();
                 for head in &mut fields {
@@ -1534,25 +2151,103 @@ impl Record {
     fn contains(&self, tv: Tvar) -> bool {
         match self {
             Record::Empty => false,
-            Record::Extension { head, tail } => head.v.contains(tv) || tail.contains(tv),
+            Record::Extension { head, tail, .. } => head.v.contains(tv) || tail.contains(tv),
         }
     }
 
     fn format(&self, f: &mut String) -> Result<Option<Tvar>, fmt::Error> {
-        let mut fields = self.fields();
-        for head in &mut fields {
-            write!(f, "{}, ", head)?;
+        let mut props = Vec::new();
+        let mut record = self;
+        let tvar = loop {
+            match record {
+                Record::Empty => break None,
+                Record::Extension {
+                    head,
+                    tail,
+                    optional,
+                } => {
+                    props.push((head, *optional));
+                    match tail {
+                        MonoType::Record(tail) => record = tail,
+                        MonoType::BoundVar(tv) | MonoType::Var(tv) => break Some(*tv),
+                        _ => break None,
+                    }
+                }
+            }
+        };
+        // Records which only differ in the order of their labels are equal, so the labels are
+        // sorted to display them the same way. The sort is stable, properties sharing a label
+        // keep their order.
+        props.sort_by(|(l, _), (r, _)| l.k.cmp(&r.k));
+        for (head, optional) in props {
+            write!(f, "{}{}, ", if optional { "?" } else { "" }, head)?;
         }
-        Ok(match fields.tail() {
-            Some(MonoType::BoundVar(tv)) | Some(MonoType::Var(tv)) => Some(*tv),
-            _ => None,
-        })
+        Ok(tvar)
+    }
+
+    /// Returns `true` if the record may omit the property with the label `label`.
+    pub fn is_optional(&self, label: &RecordLabel) -> bool {
+        let mut record = self;
+        while let Record::Extension {
+            head,
+            tail,
+            optional,
+        } = record
+        {
+            if head.k == *label {
+                return *optional;
+            }
+            match tail {
+                MonoType::Record(tail) => record = tail,
+                _ => break,
+            }
+        }
+        false
     }
 
     /// Returns an iterator over the fields in the record
     pub fn fields(&self) -> FieldIter<'_> {
         FieldIter::Record(self)
     }
+
+    /// Returns an iterator over the fields in the record, same as [`Record::fields`].
+    pub fn iter(&self) -> FieldIter<'_> {
+        self.fields()
+    }
+
+    /// Returns the type of the field labeled `label`, or `None` if the record has no such field.
+    /// If several fields share the label, the type of the outermost one is returned.
+    pub fn get(&self, label: &str) -> Option<&MonoType> {
+        self.fields()
+            .find(|prop| prop.k == label)
+            .map(|prop| &prop.v)
+    }
+
+    /// Returns the type that the record extends, usually a type variable, or `None` if the record
+    /// is closed.
+    pub fn tail(&self) -> Option<&MonoType> {
+        let mut fields = self.fields();
+        for _ in &mut fields {}
+        fields.tail()
+    }
+
+    /// Returns the record variable that this record extends along with the variable labels it is
+    /// extended with.
+    ///
+    /// The record variable of `{A with B: C}` is never bound to a record containing the label
+    /// that `B` resolves to, which is what allows a signature such as
+    /// `(<-tables: [{A with B: C}], column: B) => [A] where B: Label` to remove a label.
+    pub(crate) fn lacked_labels(&self) -> Option<(Tvar, Vec<RecordLabel>)> {
+        let mut fields = self.fields();
+        let labels: Vec<RecordLabel> = (&mut fields)
+            .filter(|prop| matches!(prop.k, RecordLabel::Variable(_)))
+            .map(|prop| prop.k.clone())
+            .collect();
+        match fields.tail() {
+            Some(MonoType::Var(tv)) if !labels.is_empty() => Some((*tv, labels)),
+            _ => None,
+        }
+    }
 }
 
 /// An iterator over a records fields
@@ -1578,7 +2273,7 @@ impl<'a> Iterator for FieldIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         match self {
-            FieldIter::Record(Record::Extension { head, tail }) => {
+            FieldIter::Record(Record::Extension { head, tail, .. }) => {
                 match tail {
                     MonoType::Record(tail) => *self = FieldIter::Record(tail),
                     _ => *self = FieldIter::Tail(tail),
@@ -1624,14 +2319,16 @@ fn unify_in_context<T>(
     T: TypeLike,
 {
     let mut sub_unifier = Unifier::new(unifier.sub);
+    sub_unifier.depth = unifier.depth;
     exp.unify(act.typ(), &mut sub_unifier);
 
-    unifier.errors.extend(
-        sub_unifier
-            .errors
-            .into_iter()
-            .map(|e| act.error(context(e))),
-    );
+    unifier
+        .errors
+        .extend(sub_unifier.errors.into_iter().map(|e| match e {
+            // Adding the context of every enclosing type would make the error as deep as the types
+            Error::TooDeeplyNested => act.error(e),
+            e => act.error(context(e)),
+        }));
 
     unifier.delayed_records.extend(sub_unifier.delayed_records);
 }
@@ -2288,6 +2985,53 @@ mod tests {
         convert_monotype(&typ_expr.monotype, tvars, sub).unwrap()
     }
 
+    #[test]
+    fn free_vars_of_nested_functions() {
+        let mut tvars = BTreeMap::new();
+        let mut sub = Substitution::default();
+        let typ = parse_type(
+            "(f: (x: A) => (y: [B]) => C, ?g: (<-r: {D with a: A}) => C) => int",
+            &mut tvars,
+            &mut sub,
+        );
+        assert_eq!(typ.free_vars(), tvars.values().copied().collect());
+        assert_eq!(typ.free_vars().len(), 4);
+        for tv in tvars.values() {
+            assert!(typ.contains(*tv), "{} is not in {}", tv, typ);
+        }
+        assert!(!typ.contains(sub.fresh()));
+
+        let typ = parse_type("(x: int) => (y: [string]) => bool", &mut tvars, &mut sub);
+        assert!(typ.free_vars().is_empty());
+    }
+
+    #[test]
+    fn record_accessors() {
+        let mut tvars = BTreeMap::new();
+        let mut sub = Substitution::default();
+        let mut record = |src: &str, tvars: &mut BTreeMap<String, Tvar>| match parse_type(
+            src, tvars, &mut sub,
+        ) {
+            MonoType::Record(record) => record,
+            typ => panic!("expected a record, got {}", typ),
+        };
+
+        let open = record("{A with a: int, b: string}", &mut tvars);
+        assert_eq!(
+            open.iter()
+                .map(|prop| prop.k.to_string())
+                .collect::<Vec<_>>(),
+            ["a", "b"],
+        );
+        assert_eq!(open.get("b"), Some(&MonoType::STRING));
+        assert_eq!(open.get("c"), None);
+        assert_eq!(open.tail(), Some(&MonoType::Var(tvars["A"])));
+
+        let closed = record("{a: int, a: float}", &mut tvars);
+        assert_eq!(closed.get("a"), Some(&MonoType::INT));
+        assert_eq!(closed.tail(), None);
+    }
+
     #[test]
     fn display_kind_addable() {
         assert!(Kind::Addable.to_string() == "Addable");
@@ -2411,6 +3155,41 @@ mod tests {
         );
     }
     #[test]
+    fn display_type_record_sorts_labels() {
+        let record = |labels: &[&str]| {
+            Record::new(
+                labels.iter().map(|label| Property {
+                    k: RecordLabel::from(*label),
+                    v: MonoType::INT,
+                }),
+                Some(MonoType::BoundVar(Tvar(0))),
+            )
+        };
+        let (l, r) = (record(&["b", "a", "c"]), record(&["c", "b", "a"]));
+        assert_eq!(l, r);
+        assert_eq!(l.to_string(), r.to_string());
+        assert_eq!("{A with a:int, b:int, c:int}", l.to_string());
+
+        // Properties sharing a label keep their order
+        assert_eq!(
+            "{A with a:int, a:string}",
+            Record::new(
+                [
+                    Property {
+                        k: RecordLabel::from("a"),
+                        v: MonoType::INT,
+                    },
+                    Property {
+                        k: RecordLabel::from("a"),
+                        v: MonoType::STRING,
+                    }
+                ],
+                Some(MonoType::BoundVar(Tvar(0))),
+            )
+            .to_string()
+        );
+    }
+    #[test]
     fn display_type_function() {
         assert_eq!(
             "() => int",
@@ -2540,6 +3319,9 @@ mod tests {
             PolyType {
                 vars: Vec::new(),
                 cons: TvarKinds::new(),
+                label_sets: LabelSets::new(),
+                aliases: Vec::new(),
+                names: TvarNames::new(),
                 expr: MonoType::INT,
             }
             .to_string(),
@@ -2549,6 +3331,9 @@ mod tests {
             PolyType {
                 vars: vec![Tvar(0)],
                 cons: TvarKinds::new(),
+                label_sets: LabelSets::new(),
+                aliases: Vec::new(),
+                names: TvarNames::new(),
                 expr: MonoType::from(Function {
                     req: semantic_map! {
                         String::from("x") => MonoType::BoundVar(Tvar(0)),
@@ -2565,6 +3350,9 @@ mod tests {
             PolyType {
                 vars: vec![Tvar(0), Tvar(1)],
                 cons: TvarKinds::new(),
+                label_sets: LabelSets::new(),
+                aliases: Vec::new(),
+                names: TvarNames::new(),
                 expr: MonoType::from(Function {
                     req: semantic_map! {
                         String::from("x") => MonoType::BoundVar(Tvar(0)),
@@ -2594,6 +3382,9 @@ mod tests {
             PolyType {
                 vars: vec![Tvar(0)],
                 cons: semantic_map! {Tvar(0) => vec![Kind::Addable]},
+                label_sets: LabelSets::new(),
+                aliases: Vec::new(),
+                names: TvarNames::new(),
                 expr: MonoType::from(Function {
                     req: semantic_map! {
                         String::from("a") => MonoType::BoundVar(Tvar(0)),
@@ -2614,6 +3405,9 @@ mod tests {
                     Tvar(0) => vec![Kind::Addable],
                     Tvar(1) => vec![Kind::Divisible],
                 },
+                label_sets: LabelSets::new(),
+                aliases: Vec::new(),
+                names: TvarNames::new(),
                 expr: MonoType::from(Function {
                     req: semantic_map! {
                         String::from("x") => MonoType::BoundVar(Tvar(0)),
@@ -2646,6 +3440,9 @@ mod tests {
                     Tvar(0) => vec![Kind::Comparable, Kind::Equatable],
                     Tvar(1) => vec![Kind::Addable, Kind::Divisible],
                 },
+                label_sets: LabelSets::new(),
+                aliases: Vec::new(),
+                names: TvarNames::new(),
                 expr: MonoType::from(Function {
                     req: semantic_map! {
                         String::from("x") => MonoType::BoundVar(Tvar(0)),
@@ -2672,6 +3469,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn display_polytype_verbose() {
+        expect_test::expect!["forall [] . int"].assert_eq(&polytype("int").verbose().to_string());
+        expect_test::expect!["forall [A] . (x:A) => A"]
+            .assert_eq(&polytype("(x: A) => A").verbose().to_string());
+        expect_test::expect!["forall [A, B] where A: Addable + Divisible . (x:A, y:B) => A"]
+            .assert_eq(
+                &polytype("(x: A, y: B) => A where A: Divisible + Addable")
+                    .verbose()
+                    .to_string(),
+            );
+        // The compact form is still used by `Display`
+        expect_test::expect!["(x:A) => A where A: Addable"]
+            .assert_eq(&polytype("(x: A) => A where A: Addable").to_string());
+    }
+
     #[test]
     fn compare_records() {
         assert_eq!(
@@ -2834,6 +3647,7 @@ mod tests {
                     v: MonoType::INT,
                 },
                 tail: MonoType::from(Record::Empty),
+                optional: false,
             }),
             // {A with a:int}
             MonoType::from(Record::Extension {
@@ -2842,6 +3656,7 @@ mod tests {
                     v: MonoType::INT,
                 },
                 tail: MonoType::BoundVar(Tvar(0)),
+                optional: false,
             }),
         );
         assert_ne!(
@@ -2852,6 +3667,7 @@ mod tests {
                     v: MonoType::INT,
                 },
                 tail: MonoType::BoundVar(Tvar(0)),
+                optional: false,
             }),
             // {B with a:int}
             MonoType::from(Record::Extension {
@@ -2860,6 +3676,7 @@ mod tests {
                     v: MonoType::INT,
                 },
                 tail: MonoType::BoundVar(Tvar(1)),
+                optional: false,
             }),
         );
     }
@@ -2898,6 +3715,39 @@ mod tests {
         );
     }
     #[test]
+    fn constrain_stringable() {
+        for t in [
+            MonoType::INT,
+            MonoType::UINT,
+            MonoType::FLOAT,
+            MonoType::BOOL,
+            MonoType::TIME,
+            MonoType::DURATION,
+            MonoType::STRING,
+        ] {
+            t.constrain(Kind::Stringable, &mut TvarKinds::new()).unwrap();
+        }
+
+        for t in [
+            MonoType::from(Record::Empty),
+            MonoType::arr(MonoType::INT),
+            MonoType::from(Function {
+                req: MonoTypeMap::new(),
+                opt: MonoTypeMap::new(),
+                pipe: None,
+                retn: MonoType::INT,
+            }),
+        ] {
+            assert_eq!(
+                Err(Error::CannotConstrain {
+                    act: t.clone(),
+                    exp: Kind::Stringable,
+                }),
+                t.constrain(Kind::Stringable, &mut TvarKinds::new())
+            );
+        }
+    }
+    #[test]
     fn constrain_rows() {
         Record::Empty
             .constrain(Kind::Record, &mut TvarKinds::new())
@@ -2998,6 +3848,59 @@ mod tests {
         );
     }
     #[test]
+    fn closest_labels() {
+        let candidates = vec!["host".to_string(), "port".to_string()];
+        assert_eq!(closest_label("hsot", &candidates), Some("host".to_string()));
+        assert_eq!(closest_label("prt", &candidates), Some("port".to_string()));
+        assert_eq!(closest_label("region", &candidates), None);
+        assert_eq!(closest_label("a", &["b".to_string()]), None);
+        assert_eq!(closest_label("host", &[]), None);
+    }
+    #[test]
+    fn subsumes() {
+        let identity = polytype("(x: A) => A");
+        assert!(identity.subsumes(&polytype("(x: int) => int")));
+        assert!(!polytype("(x: int) => int").subsumes(&identity));
+        assert!(identity.subsumes(&polytype("(x: B) => B")));
+
+        let addable = polytype("(x: A) => A where A: Addable");
+        assert!(identity.subsumes(&addable));
+        assert!(!addable.subsumes(&identity));
+        assert!(addable.subsumes(&polytype("(x: int) => int")));
+        assert!(!addable.subsumes(&polytype("(x: bool) => bool")));
+
+        let pair = polytype("(x: A, y: B) => A");
+        assert!(pair.subsumes(&polytype("(x: A, y: A) => A")));
+        assert!(!polytype("(x: A, y: A) => A").subsumes(&pair));
+    }
+    #[test]
+    fn unify_tuples() {
+        let mut sub = Substitution::default();
+        sub.mk_fresh(1);
+        let exp = MonoType::from(Tuple {
+            elements: vec![MonoType::INT, MonoType::Var(Tvar(0))],
+        });
+        let act = MonoType::from(Tuple {
+            elements: vec![MonoType::INT, MonoType::STRING],
+        });
+        exp.try_unify(&act, &mut sub).unwrap();
+        assert_eq!(sub.apply(Tvar(0)), MonoType::STRING);
+        assert_eq!(act.to_string(), "(int, string)");
+
+        let err = act
+            .try_unify(
+                &MonoType::from(Tuple {
+                    elements: vec![MonoType::INT],
+                }),
+                &mut Substitution::default(),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            String::from("expected (int, string) (tuple) but found (int,) (tuple)"),
+        );
+    }
+    #[test]
     fn unify_tvars() {
         let mut sub = Substitution::default();
         sub.mk_fresh(2);
@@ -3007,6 +3910,34 @@ mod tests {
         assert_eq!(sub.apply(Tvar(0)), sub.apply(Tvar(1)));
     }
     #[test]
+    fn substitution_iter() {
+        let mut sub = Substitution::default();
+        sub.mk_fresh(4);
+        MonoType::Var(Tvar(0))
+            .try_unify(&MonoType::Var(Tvar(1)), &mut sub)
+            .unwrap();
+        MonoType::Var(Tvar(1))
+            .try_unify(&MonoType::INT, &mut sub)
+            .unwrap();
+        MonoType::Var(Tvar(2))
+            .try_unify(&MonoType::arr(MonoType::Var(Tvar(3))), &mut sub)
+            .unwrap();
+        assert_eq!(
+            sub.iter().collect::<Vec<_>>(),
+            vec![
+                (Tvar(0), MonoType::INT),
+                (Tvar(1), MonoType::INT),
+                (Tvar(2), MonoType::arr(MonoType::Var(Tvar(3)))),
+            ],
+        );
+        expect_test::expect![[r#"
+            t0 = int
+            t1 = int
+            t2 = [t3]
+        "#]]
+        .assert_eq(&sub.to_string());
+    }
+    #[test]
     fn unify_constrained_tvars() {
         let mut sub = Substitution::default();
         sub.cons()
@@ -3030,11 +3961,17 @@ mod tests {
             PolyType {
                 vars: _,
                 cons: f_cons,
+                label_sets: _,
+                aliases: _,
+                names: _,
                 expr: MonoType::Fun(f),
             },
             PolyType {
                 vars: _,
                 cons: g_cons,
+                label_sets: _,
+                aliases: _,
+                names: _,
                 expr: MonoType::Fun(g),
             },
         ) = (f, g)
@@ -3057,11 +3994,17 @@ mod tests {
             PolyType {
                 vars: _,
                 cons: f_cons,
+                label_sets: _,
+                aliases: _,
+                names: _,
                 expr: MonoType::Fun(f),
             },
             PolyType {
                 vars: _,
                 cons: g_cons,
+                label_sets: _,
+                aliases: _,
+                names: _,
                 expr: MonoType::Fun(g),
             },
         ) = (f, g)
@@ -3109,6 +4052,99 @@ mod tests {
         }
     }
     #[test]
+    fn unify_functions_ignores_optional_argument_order() {
+        let mut sub = Substitution::default();
+        let mut tvars = BTreeMap::new();
+        let f = parse_type("(?a: int, ?b: string) => int", &mut tvars, &mut sub);
+        let g = parse_type("(?b: string, ?a: int) => int", &mut tvars, &mut sub);
+        assert_eq!(f, g);
+        f.try_unify(&g, &mut sub).unwrap();
+        g.try_unify(&f, &mut sub).unwrap();
+
+        // The set of required arguments must still be the same
+        let f = parse_type("(a: int, ?b: string) => int", &mut tvars, &mut sub);
+        let g = parse_type("(b: string, ?a: int) => int", &mut tvars, &mut sub);
+        assert!(f.try_unify(&g, &mut sub).is_err());
+        assert!(g.try_unify(&f, &mut sub).is_err());
+    }
+    #[test]
+    fn unify_deeply_nested_records() {
+        fn nested(depth: usize, typ: MonoType) -> MonoType {
+            (0..depth).fold(typ, |typ, _| {
+                MonoType::from(Record::new(
+                    [Property {
+                        k: RecordLabel::from("a"),
+                        v: typ,
+                    }],
+                    None,
+                ))
+            })
+        }
+
+        // Building and dropping the types recurses through every level, which needs more stack
+        // than test threads get by default
+        std::thread::Builder::new()
+            .stack_size(16 * 1024 * 1024)
+            .spawn(|| {
+                let mut sub = Substitution::default();
+                let exp = nested(10_000, MonoType::INT);
+                let act = nested(10_000, MonoType::STRING);
+                let errors = exp.try_unify(&act, &mut sub).unwrap_err();
+                assert_eq!(
+                    vec!["type too deeply nested".to_string()],
+                    errors.iter().map(|err| err.to_string()).collect::<Vec<_>>()
+                );
+
+                let mut sub = Substitution::default();
+                sub.set_max_unification_depth(3);
+                let exp = nested(2, MonoType::INT);
+                assert!(exp.try_unify(&exp, &mut sub).is_ok());
+                let exp = nested(3, MonoType::INT);
+                assert!(exp.try_unify(&exp, &mut sub).is_err());
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+    #[test]
+    fn unify_wide_records() {
+        fn wide(width: usize, typ: MonoType) -> MonoType {
+            MonoType::from(Record::new(
+                (0..width).map(|i| Property {
+                    k: RecordLabel::from(format!("a{}", i)),
+                    v: typ.clone(),
+                }),
+                None,
+            ))
+        }
+
+        // Each field is a level of the row, not of nesting, so wide records are not limited by
+        // the unification depth
+        let mut sub = Substitution::default();
+        sub.set_max_unification_depth(3);
+        let exp = wide(200, MonoType::INT);
+        assert!(exp.try_unify(&wide(200, MonoType::INT), &mut sub).is_ok());
+        let act = wide(200, MonoType::STRING);
+        assert!(exp.try_unify(&act, &mut sub).is_err());
+    }
+    #[test]
+    fn instantiation_uses_fresh_variables() {
+        let instantiation =
+            infer::Instantiation::from(polytype("(a: A, b: B) => A where A: Addable"));
+
+        let mut sub = Substitution::default();
+        let (f, f_cons) = instantiation.instantiate(&mut sub, Default::default());
+        let (g, g_cons) = instantiation.instantiate(&mut sub, Default::default());
+        assert_eq!(f.to_string(), "(a:A, b:B) => A");
+        assert_eq!(g.to_string(), "(a:C, b:D) => C");
+        assert_ne!(f_cons, g_cons);
+        infer::solve(&(f_cons + g_cons), &mut sub).unwrap();
+        assert_eq!(
+            sub.cons(),
+            &semantic_map! {Tvar(0) => vec![Kind::Addable], Tvar(2) => vec![Kind::Addable]}
+        );
+    }
+    #[test]
     fn unify_higher_order_functions() {
         let mut sub = Substitution::default();
 
@@ -3204,6 +4240,7 @@ mod tests {
             Kind::Timeable,
             Kind::Basic,
             Kind::Stringable,
+            Kind::Lengthable,
         ];
         let mut str_kinds: Vec<_> = kinds.iter().map(|k| k.to_string()).collect();
         str_kinds.sort();
@@ -3216,4 +4253,14 @@ mod tests {
             "Expected that `Kind`s were specified in lexical order"
         );
     }
+
+    #[test]
+    fn array_element() {
+        assert_eq!(
+            Some(&MonoType::INT),
+            MonoType::arr(MonoType::INT).array_element()
+        );
+        assert_eq!(None, MonoType::stream(MonoType::INT).array_element());
+        assert_eq!(None, MonoType::INT.array_element());
+    }
 }
@@ -5,8 +5,8 @@ use crate::{
     errors::{located, Errors},
     semantic::{
         nodes::{
-            BinaryExpr, Block, Error, ErrorKind, Expression, FunctionExpr, IdentifierExpr,
-            MemberExpr, ObjectExpr, Package, Property, Result, ReturnStmt,
+            BinaryExpr, Block, ConditionalExpr, Error, ErrorKind, Expression, FunctionExpr,
+            IdentifierExpr, MemberExpr, ObjectExpr, Package, Property, Result, ReturnStmt,
         },
         types::{self, Function, Label, MonoType},
         AnalyzerConfig, Feature, Symbol,
@@ -81,6 +81,7 @@ impl Expression {
                         .clone(),
                     object,
                     property: member.property.clone(),
+                    optional: member.optional,
                 }))
             }
             Expression::Binary(binary) => {
@@ -93,6 +94,14 @@ impl Expression {
                             ),
                         ));
                     }
+                } else if binary.operator == ast::Operator::ExponentiationOperator {
+                    // The runtime has no vectorized implementation of `**`
+                    return Err(located(
+                        self.loc().clone(),
+                        ErrorKind::UnableToVectorize(
+                            "Unable to vectorize the `**` operator".into(),
+                        ),
+                    ));
                 } else if !env.config.features.contains(&Feature::VectorizeOperators) {
                     return Err(located(
                         self.loc().clone(),
@@ -112,6 +121,74 @@ impl Expression {
                     right,
                 }))
             }
+            Expression::Conditional(conditional) => {
+                if !env
+                    .config
+                    .features
+                    .contains(&Feature::VectorizeConditionals)
+                {
+                    return Err(located(
+                        self.loc().clone(),
+                        ErrorKind::UnableToVectorize(
+                            "Vectorization of conditional expressions is not enabled".into(),
+                        ),
+                    ));
+                }
+
+                // The conditional becomes a select over the vectorized test, so only the test
+                // must be a vector. Constant branches are broadcast to the length of the test.
+                let test = conditional.test.vectorize(env)?;
+                let consequent = conditional.consequent.vectorize_branch(env)?;
+                let alternate = conditional.alternate.vectorize_branch(env)?;
+                Expression::Conditional(Box::new(ConditionalExpr {
+                    loc: conditional.loc.clone(),
+                    typ: MonoType::vector(conditional.typ.clone()),
+                    test,
+                    consequent,
+                    alternate,
+                }))
+            }
+            Expression::Call(call) => {
+                let reason = match &call.callee {
+                    Expression::Identifier(identifier) => format!(
+                        "Unable to vectorize call to non-vectorizable function `{}`",
+                        identifier.name
+                    ),
+                    Expression::Member(member) => format!(
+                        "Unable to vectorize call to non-vectorizable function `{}`",
+                        member.property
+                    ),
+                    _ => "Unable to vectorize call to non-vectorizable function".into(),
+                };
+                return Err(located(
+                    self.loc().clone(),
+                    ErrorKind::UnableToVectorize(reason),
+                ));
+            }
+            Expression::Logical(logical) => {
+                return Err(located(
+                    self.loc().clone(),
+                    ErrorKind::UnableToVectorize(format!(
+                        "Unable to vectorize unsupported operator `{}`",
+                        logical.operator
+                    )),
+                ));
+            }
+            Expression::Unary(unary) => {
+                return Err(located(
+                    self.loc().clone(),
+                    ErrorKind::UnableToVectorize(format!(
+                        "Unable to vectorize unsupported operator `{}`",
+                        unary.operator
+                    )),
+                ));
+            }
+            Expression::Index(_) => {
+                return Err(located(
+                    self.loc().clone(),
+                    ErrorKind::UnableToVectorize("Unable to vectorize dynamic field access".into()),
+                ));
+            }
             _ => {
                 return Err(located(
                     self.loc().clone(),
@@ -120,6 +197,19 @@ impl Expression {
             }
         })
     }
+
+    fn vectorize_branch(&self, env: &VectorizeEnv<'_>) -> Result<Self> {
+        match self {
+            Expression::Integer(_)
+            | Expression::Float(_)
+            | Expression::StringLit(_)
+            | Expression::Duration(_)
+            | Expression::Uint(_)
+            | Expression::Boolean(_)
+            | Expression::DateTime(_) => Ok(self.clone()),
+            _ => self.vectorize(env),
+        }
+    }
 }
 
 impl IdentifierExpr {
@@ -154,12 +244,17 @@ impl FunctionExpr {
                 match record {
                     MonoType::Record(record) => MonoType::from(match &**record {
                         Record::Empty => Record::Empty,
-                        Record::Extension { head, tail } => Record::Extension {
+                        Record::Extension {
+                            head,
+                            tail,
+                            optional,
+                        } => Record::Extension {
                             head: types::Property {
                                 k: head.k.clone(),
                                 v: MonoType::vector(head.v.clone()),
                             },
                             tail: vectorize_fields(tail),
+                            optional: *optional,
                         },
                     }),
                     _ => record.clone(),
@@ -224,6 +319,7 @@ impl FunctionExpr {
                                 )),
                                 with,
                                 properties,
+                                spreads: Vec::new(),
                             }))
                         }
                         _ => {
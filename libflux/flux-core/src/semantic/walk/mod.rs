@@ -31,6 +31,10 @@ macro_rules! mk_node {
             ObjectExpr(&'a $($mut)? ObjectExpr),
             MemberExpr(&'a $($mut)? MemberExpr),
             IndexExpr(&'a $($mut)? IndexExpr),
+            TupleExpr(&'a $($mut)? TupleExpr),
+            TupleIndexExpr(&'a $($mut)? TupleIndexExpr),
+            BlockExpr(&'a $($mut)? BlockExpr),
+            MatchExpr(&'a $($mut)? MatchExpr),
             BinaryExpr(&'a $($mut)? BinaryExpr),
             UnaryExpr(&'a $($mut)? UnaryExpr),
             CallExpr(&'a $($mut)? CallExpr),
@@ -53,6 +57,7 @@ macro_rules! mk_node {
             TestStmt(&'a $($mut)? TestStmt),
             TestCaseStmt(&'a $($mut)? TestCaseStmt),
             BuiltinStmt(&'a $($mut)? BuiltinStmt),
+            TypeAliasStmt(&'a $($mut)? TypeAliasStmt),
             ErrorStmt(&'a $($mut)? SourceLocation),
 
             // StringExprPart.
@@ -82,6 +87,10 @@ macro_rules! mk_node {
                     Self::ObjectExpr(_) => write!(f, "ObjectExpr"),
                     Self::MemberExpr(_) => write!(f, "MemberExpr"),
                     Self::IndexExpr(_) => write!(f, "IndexExpr"),
+                    Self::TupleExpr(_) => write!(f, "TupleExpr"),
+                    Self::TupleIndexExpr(_) => write!(f, "TupleIndexExpr"),
+                    Self::BlockExpr(_) => write!(f, "BlockExpr"),
+                    Self::MatchExpr(_) => write!(f, "MatchExpr"),
                     Self::BinaryExpr(_) => write!(f, "BinaryExpr"),
                     Self::UnaryExpr(_) => write!(f, "UnaryExpr"),
                     Self::CallExpr(_) => write!(f, "CallExpr"),
@@ -102,6 +111,7 @@ macro_rules! mk_node {
                     Self::TestStmt(_) => write!(f, "TestStmt"),
                     Self::TestCaseStmt(_) => write!(f, "TestCaseStmt"),
                     Self::BuiltinStmt(_) => write!(f, "BuiltinStmt"),
+                    Self::TypeAliasStmt(_) => write!(f, "TypeAliasStmt"),
                     Self::ErrorStmt(_) => write!(f, "ErrorStmt"),
                     Self::Block(n) => match n {
                         Block::Variable(_, _) => write!(f, "Block::Variable"),
@@ -135,6 +145,10 @@ macro_rules! mk_node {
                     Self::ObjectExpr(n) => &n.loc,
                     Self::MemberExpr(n) => &n.loc,
                     Self::IndexExpr(n) => &n.loc,
+                    Self::TupleExpr(n) => &n.loc,
+                    Self::TupleIndexExpr(n) => &n.loc,
+                    Self::BlockExpr(n) => &n.loc,
+                    Self::MatchExpr(n) => &n.loc,
                     Self::BinaryExpr(n) => &n.loc,
                     Self::UnaryExpr(n) => &n.loc,
                     Self::CallExpr(n) => &n.loc,
@@ -154,6 +168,7 @@ macro_rules! mk_node {
                     Self::TestStmt(n) => &n.loc,
                     Self::TestCaseStmt(n) => &n.loc,
                     Self::BuiltinStmt(n) => &n.loc,
+                    Self::TypeAliasStmt(n) => &n.loc,
                     Self::ErrorStmt(loc) => loc,
                     Self::Block(n) => n.loc(),
                     Self::Property(n) => &n.loc,
@@ -184,6 +199,12 @@ macro_rules! mk_node {
                         Some(Expression::Member(Box::new((*n).clone())).type_of())
                     }
                     Self::IndexExpr(n) => Some(Expression::Index(Box::new((*n).clone())).type_of()),
+                    Self::TupleExpr(n) => Some(Expression::Tuple(Box::new((*n).clone())).type_of()),
+                    Self::TupleIndexExpr(n) => {
+                        Some(Expression::TupleIndex(Box::new((*n).clone())).type_of())
+                    }
+                    Self::BlockExpr(n) => Some(Expression::Block(Box::new((*n).clone())).type_of()),
+                    Self::MatchExpr(n) => Some(Expression::Match(Box::new((*n).clone())).type_of()),
                     Self::BinaryExpr(n) => {
                         Some(Expression::Binary(Box::new((*n).clone())).type_of())
                     }
@@ -224,6 +245,10 @@ macro_rules! mk_node {
                     Expression::Object(e) => Self::ObjectExpr(e),
                     Expression::Member(e) => Self::MemberExpr(e),
                     Expression::Index(e) => Self::IndexExpr(e),
+                    Expression::Tuple(e) => Self::TupleExpr(e),
+                    Expression::TupleIndex(e) => Self::TupleIndexExpr(e),
+                    Expression::Block(e) => Self::BlockExpr(e),
+                    Expression::Match(e) => Self::MatchExpr(e),
                     Expression::Binary(e) => Self::BinaryExpr(e),
                     Expression::Unary(e) => Self::UnaryExpr(e),
                     Expression::Call(e) => Self::CallExpr(e),
@@ -249,6 +274,7 @@ macro_rules! mk_node {
                     Statement::Test(s) => Self::TestStmt(s),
                     Statement::TestCase(s) => Self::TestCaseStmt(s),
                     Statement::Builtin(s) => Self::BuiltinStmt(s),
+                    Statement::TypeAlias(s) => Self::TypeAliasStmt(s),
                     Statement::Error(s) => Self::ErrorStmt(s),
                 }
             }
@@ -341,6 +367,9 @@ macro_rules! mk_node {
                         for prop in &$($mut)? n.properties {
                             $walk(v, $name::Property(prop));
                         }
+                        for spread in &$($mut)? n.spreads {
+                            $walk(v, $name::IdentifierExpr(& $($mut)? spread.argument));
+                        }
                     }
                     $name::MemberExpr(n) => {
                         $walk(v, $name::from_expr(& $($mut)? n.object));
@@ -349,6 +378,26 @@ macro_rules! mk_node {
                         $walk(v, $name::from_expr(& $($mut)? n.array));
                         $walk(v, $name::from_expr(& $($mut)? n.index));
                     }
+                    $name::TupleExpr(n) => {
+                        for element in &$($mut)? n.elements {
+                            $walk(v, $name::from_expr(element));
+                        }
+                    }
+                    $name::TupleIndexExpr(n) => {
+                        $walk(v, $name::from_expr(& $($mut)? n.tuple));
+                    }
+                    $name::BlockExpr(n) => {
+                        $walk(v, $name::Block(& $($mut)? n.body));
+                    }
+                    $name::MatchExpr(n) => {
+                        $walk(v, $name::from_expr(& $($mut)? n.scrutinee));
+                        for arm in & $($mut)? n.arms {
+                            if let Some(pattern) = & $($mut)? arm.pattern {
+                                $walk(v, $name::from_expr(pattern));
+                            }
+                            $walk(v, $name::from_expr(& $($mut)? arm.body));
+                        }
+                    }
                     $name::BinaryExpr(n) => {
                         $walk(v, $name::from_expr(& $($mut)? n.left));
                         $walk(v, $name::from_expr(& $($mut)? n.right));
@@ -407,6 +456,9 @@ macro_rules! mk_node {
                     $name::BuiltinStmt(n) => {
                         $walk(v, $name::Identifier(& $($mut)? n.id));
                     }
+                    $name::TypeAliasStmt(n) => {
+                        $walk(v, $name::Identifier(& $($mut)? n.id));
+                    }
                     $name::ErrorStmt(_) => {}
                     $name::Block(n) => match n {
                         Block::Variable(assgn, next) => {
@@ -35,6 +35,10 @@ impl NodeMut<'_> {
             NodeMut::ObjectExpr(ref mut n) => n.loc = loc,
             NodeMut::MemberExpr(ref mut n) => n.loc = loc,
             NodeMut::IndexExpr(ref mut n) => n.loc = loc,
+            NodeMut::TupleExpr(ref mut n) => n.loc = loc,
+            NodeMut::TupleIndexExpr(ref mut n) => n.loc = loc,
+            NodeMut::BlockExpr(ref mut n) => n.loc = loc,
+            NodeMut::MatchExpr(ref mut n) => n.loc = loc,
             NodeMut::BinaryExpr(ref mut n) => n.loc = loc,
             NodeMut::UnaryExpr(ref mut n) => n.loc = loc,
             NodeMut::CallExpr(ref mut n) => n.loc = loc,
@@ -55,6 +59,7 @@ impl NodeMut<'_> {
             NodeMut::TestStmt(ref mut n) => n.loc = loc,
             NodeMut::TestCaseStmt(ref mut n) => n.loc = loc,
             NodeMut::BuiltinStmt(ref mut n) => n.loc = loc,
+            NodeMut::TypeAliasStmt(ref mut n) => n.loc = loc,
             NodeMut::ErrorStmt(ref mut n) => **n = loc,
             NodeMut::Block(_) => (),
             NodeMut::Property(ref mut n) => n.loc = loc,
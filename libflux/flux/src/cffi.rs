@@ -618,6 +618,9 @@ fn find_var_type(ast_pkg: &ast::Package, var_name: String) -> Result<MonoType> {
         PolyType {
             vars: Vec::new(),
             cons: TvarKinds::new(),
+            label_sets: Default::default(),
+            aliases: Vec::new(),
+            names: Default::default(),
             expr: MonoType::Var(tvar),
         },
     );
@@ -695,11 +698,16 @@ mod tests {
                     self.normalize(&mut Ptr::make_mut(app).arg);
                 }
                 MonoType::Record(r) => {
-                    if let Record::Extension { head, tail } = Ptr::make_mut(r) {
+                    if let Record::Extension { head, tail, .. } = Ptr::make_mut(r) {
                         self.normalize(&mut head.v);
                         self.normalize(tail);
                     }
                 }
+                MonoType::Tuple(tuple) => {
+                    for element in &mut Ptr::make_mut(tuple).elements {
+                        self.normalize(element);
+                    }
+                }
                 MonoType::Fun(f) => {
                     let f = Ptr::make_mut(f);
                     for (_, mut v) in f.req.iter_mut() {
@@ -768,7 +776,7 @@ vstr = v.str + "hello"
         let mut t = find_var_type(&pkg, "v".into()).expect("Should be able to get a MonoType.");
         let mut v = MonoTypeNormalizer::new();
         v.normalize(&mut t);
-        assert_eq!(format!("{}", t), "{B with int:int, sweet:A, str:string}");
+        assert_eq!(format!("{}", t), "{B with int:int, str:string, sweet:A}");
 
         expect_test::expect![[r#"
             {
@@ -837,7 +845,7 @@ p = o.ethan
         let mut t = find_var_type(&pkg, "v".into()).expect("Should be able to get a MonoType.");
         let mut v = MonoTypeNormalizer::new();
         v.normalize(&mut t);
-        assert_eq!(format!("{}", t), "{B with int:int, ethan:A}");
+        assert_eq!(format!("{}", t), "{B with ethan:A, int:int}");
 
         expect_test::expect![[r#"
             {
@@ -887,7 +895,7 @@ from(bucket: v.bucket)
         v.normalize(&mut ty);
         assert_eq!(
             format!("{}", ty),
-            "{D with measurement:A, timeRangeStart:B, timeRangeStop:C, bucket:string}"
+            "{D with bucket:string, measurement:A, timeRangeStart:B, timeRangeStop:C}"
         );
     }
 
@@ -28,6 +28,7 @@ func NewOptions(ctx context.Context) Options {
 	features = addFlag(ctx, features, feature.VectorizedMap())
 	features = addFlag(ctx, features, feature.VectorizeOperators())
 	features = addFlag(ctx, features, feature.LabelPolymorphism())
+	features = addFlag(ctx, features, feature.GuardedAccess())
 	return Options{Features: features}
 }
 
@@ -169,6 +169,7 @@ func ToAST(n Node) ast.Node {
 		return &ast.MemberExpression{
 			Object:   ToAST(n.Object).(ast.Expression),
 			Property: &ast.StringLiteral{Value: n.Property.Name()},
+			Optional: n.Optional,
 		}
 	case *IndexExpression:
 		return &ast.IndexExpression{
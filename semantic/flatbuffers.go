@@ -374,6 +374,10 @@ func fromFBOperator(o fbsemantic.Operator) (ast.OperatorKind, error) {
 		return ast.RegexpMatchOperator, nil
 	case fbsemantic.OperatorNotRegexpMatchOperator:
 		return ast.NotRegexpMatchOperator, nil
+	case fbsemantic.OperatorNullCoalescingOperator:
+		return ast.NullCoalescingOperator, nil
+	case fbsemantic.OperatorExponentiationOperator:
+		return ast.ExponentiationOperator, nil
 	default:
 		if name, ok := fbsemantic.EnumNamesOperator[o]; ok {
 			return 0, errors.Newf(codes.Internal, "unsupported operator %v", name)
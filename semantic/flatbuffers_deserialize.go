@@ -455,6 +455,7 @@ func (rcv *MemberExpression) FromBuf(fb *fbsemantic.MemberExpression) error {
 	}
 
 	rcv.Property = NewSymbol(string(fb.Property()))
+	rcv.Optional = fb.Optional()
 
 	if rcv.typ, err = getMonoType(fb); err != nil {
 		return errors.Wrap(err, codes.Inherit, "MemberExpression.typ")
@@ -788,6 +788,8 @@ type MemberExpression struct {
 
 	Object   Expression
 	Property Symbol
+	// Optional is true for a guarded access, r?.a.
+	Optional bool
 
 	typ MonoType
 }
@@ -42,6 +42,8 @@ func NewMonoType(tbl flatbuffers.Table, t fbsemantic.MonoType) (MonoType, error)
 		tbler = new(fbsemantic.Fun)
 	case fbsemantic.MonoTypeDict:
 		tbler = new(fbsemantic.Dict)
+	case fbsemantic.MonoTypeTuple:
+		tbler = new(fbsemantic.Tuple)
 	default:
 		return MonoType{}, errors.Newf(codes.Internal, "unknown type (%v)", t)
 	}
@@ -103,6 +105,9 @@ func nature(tbl flatbuffers.Table, t fbsemantic.MonoType) Nature {
 		return Function
 	case fbsemantic.MonoTypeDict:
 		return Dictionary
+	case fbsemantic.MonoTypeTuple:
+		// Tuples are evaluated as objects keyed by element position.
+		return Object
 	case fbsemantic.MonoTypeNONE,
 		fbsemantic.MonoTypeVar:
 		fallthrough
@@ -122,6 +127,7 @@ const (
 	Record     = Kind(fbsemantic.MonoTypeRecord)
 	Fun        = Kind(fbsemantic.MonoTypeFun)
 	Dict       = Kind(fbsemantic.MonoTypeDict)
+	Tuple      = Kind(fbsemantic.MonoTypeTuple)
 )
 
 // Kind returns what kind of monotype the receiver is.
@@ -480,6 +486,43 @@ func (mt MonoType) ValueType() (MonoType, error) {
 	return NewMonoType(tbl, dict.VType())
 }
 
+func getTuple(tbl fbTabler) (*fbsemantic.Tuple, error) {
+	tuple, ok := tbl.(*fbsemantic.Tuple)
+	if !ok {
+		return nil, errors.New(codes.Internal, "MonoType is not a tuple")
+	}
+	return tuple, nil
+}
+
+// NumElements returns the number of elements in a Tuple.
+func (mt MonoType) NumElements() (int, error) {
+	tuple, err := getTuple(mt.tbl)
+	if err != nil {
+		return 0, err
+	}
+	return tuple.ElementsLength(), nil
+}
+
+// Element returns the type of the element at the given position in a Tuple.
+func (mt MonoType) Element(i int) (MonoType, error) {
+	tuple, err := getTuple(mt.tbl)
+	if err != nil {
+		return MonoType{}, err
+	}
+	if i < 0 || i >= tuple.ElementsLength() {
+		return MonoType{}, errors.Newf(codes.Internal, "tuple element index out of bounds: %v", i)
+	}
+	var holder fbsemantic.MonoTypeHolder
+	if !tuple.Elements(&holder, i) {
+		return MonoType{}, errors.New(codes.Internal, "missing tuple element")
+	}
+	var tbl flatbuffers.Table
+	if !holder.Typ(&tbl) {
+		return MonoType{}, errors.New(codes.Internal, "missing tuple element type")
+	}
+	return NewMonoType(tbl, holder.TypType())
+}
+
 // Argument represents a function argument.
 type Argument struct {
 	*fbsemantic.Argument
@@ -531,6 +574,11 @@ func (p *RecordProperty) Name() string {
 	return string(tbler.Id())
 }
 
+// Optional reports whether records may omit the property.
+func (p *RecordProperty) Optional() bool {
+	return p.fb.Optional()
+}
+
 // TypeOf returns the type of the property.
 func (p *RecordProperty) TypeOf() (MonoType, error) {
 	var tbl flatbuffers.Table
@@ -615,6 +663,20 @@ func (mt MonoType) getCanonicalMapping(counter *uint64, tvm map[uint64]uint64) e
 		if err := rt.getCanonicalMapping(counter, tvm); err != nil {
 			return err
 		}
+	case Tuple:
+		n, err := mt.NumElements()
+		if err != nil {
+			return err
+		}
+		for i := 0; i < n; i++ {
+			et, err := mt.Element(i)
+			if err != nil {
+				return err
+			}
+			if err := et.getCanonicalMapping(counter, tvm); err != nil {
+				return err
+			}
+		}
 	}
 
 	return nil
@@ -710,6 +772,9 @@ func (mt MonoType) string(m map[uint64]uint64) string {
 				needBar = true
 			}
 			field := NewSymbol(prop.Name())
+			if prop.Optional() {
+				sb.WriteString("?")
+			}
 			sb.WriteString(field.Name() + ": ")
 			ty, err := prop.TypeOf()
 			if err != nil {
@@ -762,6 +827,28 @@ func (mt MonoType) string(m map[uint64]uint64) string {
 			return "<" + err.Error() + ">"
 		}
 		return "[" + kt.string(m) + ": " + vt.string(m) + "]"
+	case Tuple:
+		n, err := mt.NumElements()
+		if err != nil {
+			return "<" + err.Error() + ">"
+		}
+		var sb strings.Builder
+		sb.WriteString("(")
+		for i := 0; i < n; i++ {
+			if i > 0 {
+				sb.WriteString(", ")
+			}
+			et, err := mt.Element(i)
+			if err != nil {
+				return "<" + err.Error() + ">"
+			}
+			sb.WriteString(et.string(m))
+		}
+		if n == 1 {
+			sb.WriteString(",")
+		}
+		sb.WriteString(")")
+		return sb.String()
 	default:
 		return "<" + fmt.Sprintf("unknown monotype (%v)", tk) + ">"
 	}
@@ -984,6 +1071,17 @@ func copyMonoType(builder *flatbuffers.Builder, t MonoType) flatbuffers.UOffsetT
 		key := monoTypeFromFunc(dict.K, dict.KType())
 		value := monoTypeFromFunc(dict.V, dict.VType())
 		return buildDictType(builder, key, value)
+	case fbsemantic.MonoTypeTuple:
+		var tuple fbsemantic.Tuple
+		tuple.Init(table.Bytes, table.Pos)
+
+		elements := make([]MonoType, tuple.ElementsLength())
+		for i := 0; i < len(elements); i++ {
+			var holder fbsemantic.MonoTypeHolder
+			tuple.Elements(&holder, i)
+			elements[i] = monoTypeFromFunc(holder.Typ, holder.TypType())
+		}
+		return buildTupleType(builder, elements)
 	default:
 		panic(fmt.Sprintf("unknown monotype (%v)", t.mt))
 	}
@@ -1125,6 +1223,30 @@ func buildDictType(builder *flatbuffers.Builder, keyType, valueType MonoType) fl
 	return fbsemantic.DictEnd(builder)
 }
 
+// buildTupleType will construct a tuple type in the builder
+// and return the offset for the type.
+func buildTupleType(builder *flatbuffers.Builder, elements []MonoType) flatbuffers.UOffsetT {
+	holderOffsets := make([]flatbuffers.UOffsetT, len(elements))
+	for i, e := range elements {
+		eOffset := copyMonoType(builder, e)
+		fbsemantic.MonoTypeHolderStart(builder)
+		if e.mt != fbsemantic.MonoTypeNONE {
+			fbsemantic.MonoTypeHolderAddTypType(builder, e.mt)
+			fbsemantic.MonoTypeHolderAddTyp(builder, eOffset)
+		}
+		holderOffsets[i] = fbsemantic.MonoTypeHolderEnd(builder)
+	}
+
+	fbsemantic.TupleStartElementsVector(builder, len(holderOffsets))
+	for i := len(holderOffsets) - 1; i >= 0; i-- {
+		builder.PrependUOffsetT(holderOffsets[i])
+	}
+	elems := builder.EndVector(len(holderOffsets))
+	fbsemantic.TupleStart(builder)
+	fbsemantic.TupleAddElements(builder, elems)
+	return fbsemantic.TupleEnd(builder)
+}
+
 func updateTVarMap(counter *uint64, m map[uint64]uint64, tv uint64) {
 	if _, ok := m[tv]; ok {
 		return
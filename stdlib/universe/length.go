@@ -2,6 +2,7 @@ package universe
 
 import (
 	"context"
+	"unicode/utf8"
 
 	"github.com/influxdata/flux/codes"
 	"github.com/influxdata/flux/internal/errors"
@@ -13,7 +14,7 @@ import (
 
 // MakeLengthFunc create the "length()" function.
 //
-// Length will return the length of the given arr array.
+// Length will return the length of the given arr array, string or bytes.
 func MakeLengthFunc() values.Function {
 	return values.NewFunction(
 		"length",
@@ -23,13 +24,17 @@ func MakeLengthFunc() values.Function {
 			v, err := a.GetRequired("arr")
 			if err != nil {
 				return nil, err
-			} else if got := v.Type().Nature(); got != semantic.Array {
-				return nil, errors.Newf(codes.Invalid, "arr must be an array, got %s", got)
 			}
-
-			arr := v.Array()
-			l := arr.Len()
-			return values.NewInt(int64(l)), nil
+			switch got := v.Type().Nature(); got {
+			case semantic.Array:
+				return values.NewInt(int64(v.Array().Len())), nil
+			case semantic.String:
+				return values.NewInt(int64(utf8.RuneCountInString(v.Str()))), nil
+			case semantic.Bytes:
+				return values.NewInt(int64(len(v.Bytes()))), nil
+			default:
+				return nil, errors.Newf(codes.Invalid, "arr must be an array, string or bytes, got %s", got)
+			}
 		}, false,
 	)
 }
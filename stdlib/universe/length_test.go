@@ -85,6 +85,47 @@ func lengthTestHelper(t *testing.T, tc lengthCase) {
 	}
 }
 
+func TestLength_StringAndBytes(t *testing.T) {
+	for _, tc := range []struct {
+		name     string
+		arr      values.Value
+		expected int64
+	}{
+		{
+			name:     "string",
+			arr:      values.NewString("abcd"),
+			expected: 4,
+		},
+		{
+			name:     "chinese string",
+			arr:      values.NewString("汉汉"),
+			expected: 2,
+		},
+		{
+			name:     "bytes",
+			arr:      values.NewBytes([]byte("汉汉")),
+			expected: 6,
+		},
+	} {
+		t.Run(tc.name, func(t *testing.T) {
+			length := universe.MakeLengthFunc()
+			ctx, deps := dependency.Inject(context.Background(), dependenciestest.Default())
+			defer deps.Finish()
+			result, err := length.Call(
+				ctx,
+				values.NewObjectWithValues(map[string]values.Value{
+					"arr": tc.arr,
+				}),
+			)
+			if err != nil {
+				t.Fatal(err)
+			} else if got := result.Int(); got != tc.expected {
+				t.Errorf("expected %d, got %d", tc.expected, got)
+			}
+		})
+	}
+}
+
 func TestLength_ReceiveTableObjectIsError(t *testing.T) {
 	src := `import "array"
 			length(arr: array.from(rows: [{}]))`
@@ -93,7 +134,7 @@ func TestLength_ReceiveTableObjectIsError(t *testing.T) {
 		t.Fatal("expected error, got none")
 	}
 
-	if want, got := "error @2:16-2:38: expected [{}] (array) but found stream[{}] (argument arr)", err.Error(); want != got {
+	if want, got := "error @2:16-2:38: stream[{}] is not Lengthable (argument arr)", err.Error(); want != got {
 		t.Errorf("wanted error %q, got %q", want, got)
 	}
 }
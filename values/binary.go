@@ -100,6 +100,43 @@ var binaryFuncLookup = map[BinaryFuncSignature]BinaryFunction{
 		d := ConvertDurationNsecs(l.Duration() + r.Duration())
 		return NewDuration(d), nil
 	},
+	{Operator: ast.AdditionOperator, Left: semantic.Time, Right: semantic.Duration}: func(lv, rv Value) (Value, error) {
+		l := lv.Time()
+		r := rv.Duration()
+		return NewTime(l.Add(r)), nil
+	},
+	{Operator: ast.AdditionOperator, Left: semantic.Duration, Right: semantic.Time}: func(lv, rv Value) (Value, error) {
+		l := lv.Duration()
+		r := rv.Time()
+		return NewTime(r.Add(l)), nil
+	},
+	{Operator: ast.AdditionOperator, Left: semantic.Array, Right: semantic.Array}: func(lv, rv Value) (Value, error) {
+		l := lv.Array()
+		r := rv.Array()
+		elements := make([]Value, 0, l.Len()+r.Len())
+		l.Range(func(i int, v Value) {
+			elements = append(elements, v)
+		})
+		r.Range(func(i int, v Value) {
+			elements = append(elements, v)
+		})
+		return NewArrayWithBacking(l.Type(), elements), nil
+	},
+	{Operator: ast.AdditionOperator, Left: semantic.Object, Right: semantic.Object}: func(lv, rv Value) (Value, error) {
+		// The merged record holds the labels of the left record followed by the new labels of
+		// the right one. A label present in both takes the value of the right record.
+		l := lv.Object()
+		r := rv.Object()
+		obj, err := BuildObjectWithSize(l.Len()+r.Len(), func(set ObjectSetter) error {
+			l.Range(set)
+			r.Range(set)
+			return nil
+		})
+		if err != nil {
+			return nil, err
+		}
+		return obj, nil
+	},
 
 	{Operator: ast.SubtractionOperator, Left: semantic.Int, Right: semantic.Int}: func(lv, rv Value) (Value, error) {
 		l := lv.Int()
@@ -122,6 +159,16 @@ var binaryFuncLookup = map[BinaryFuncSignature]BinaryFunction{
 		d := ConvertDurationNsecs(l.Duration() - r.Duration())
 		return NewDuration(d), nil
 	},
+	{Operator: ast.SubtractionOperator, Left: semantic.Time, Right: semantic.Time}: func(lv, rv Value) (Value, error) {
+		l := lv.Time()
+		r := rv.Time()
+		return NewDuration(l.Sub(r)), nil
+	},
+	{Operator: ast.SubtractionOperator, Left: semantic.Time, Right: semantic.Duration}: func(lv, rv Value) (Value, error) {
+		l := lv.Time()
+		r := rv.Duration()
+		return NewTime(l.Add(r.Mul(-1))), nil
+	},
 	{Operator: ast.MultiplicationOperator, Left: semantic.Int, Right: semantic.Int}: func(lv, rv Value) (Value, error) {
 		l := lv.Int()
 		r := rv.Int()
@@ -194,6 +241,25 @@ var binaryFuncLookup = map[BinaryFuncSignature]BinaryFunction{
 		r := rv.Float()
 		return NewFloat(math.Pow(float64(l), float64(r))), nil
 	},
+	// Unlike ^, ** keeps the type of its operands.
+	{Operator: ast.ExponentiationOperator, Left: semantic.Int, Right: semantic.Int}: func(lv, rv Value) (Value, error) {
+		l := lv.Int()
+		r := rv.Int()
+		if r < 0 {
+			return nil, errors.Newf(codes.FailedPrecondition, "cannot raise an integer to a negative power")
+		}
+		return NewInt(powInt(l, uint64(r))), nil
+	},
+	{Operator: ast.ExponentiationOperator, Left: semantic.UInt, Right: semantic.UInt}: func(lv, rv Value) (Value, error) {
+		l := lv.UInt()
+		r := rv.UInt()
+		return NewUInt(uint64(powInt(int64(l), r))), nil
+	},
+	{Operator: ast.ExponentiationOperator, Left: semantic.Float, Right: semantic.Float}: func(lv, rv Value) (Value, error) {
+		l := lv.Float()
+		r := rv.Float()
+		return NewFloat(math.Pow(l, r)), nil
+	},
 	//---------------------
 	// Comparison Operators
 	//---------------------
@@ -645,3 +711,16 @@ var binaryVectorFuncLookup = map[BinaryFuncSignature]BinaryVectorFunction{
 		return vectorPow(l, r, mem)
 	},
 }
+
+// powInt raises base to the power exp by squaring, wrapping around on overflow
+// like the other integer operators.
+func powInt(base int64, exp uint64) int64 {
+	result := int64(1)
+	for ; exp > 0; exp >>= 1 {
+		if exp&1 == 1 {
+			result *= base
+		}
+		base *= base
+	}
+	return result
+}
@@ -49,6 +49,25 @@ func TestBinaryOperator(t *testing.T) {
 		// duration + duration
 		{lhs: values.ConvertDurationNsecs(1), op: "+", rhs: values.ConvertDurationNsecs(2), want: values.ConvertDurationNsecs(3)},
 		{lhs: values.ConvertDurationNsecs(1), op: "+", rhs: durationNullValue, want: durationNullValue},
+		// time + duration
+		{lhs: values.Time(10), op: "+", rhs: values.ConvertDurationNsecs(2), want: values.Time(12)},
+		{lhs: values.Time(10), op: "+", rhs: durationNullValue, want: timeNullValue},
+		// duration + time
+		{lhs: values.ConvertDurationNsecs(2), op: "+", rhs: values.Time(10), want: values.Time(12)},
+		// array + array
+		{
+			lhs:  values.NewArrayWithBacking(semantic.NewArrayType(semantic.BasicInt), []values.Value{values.NewInt(1), values.NewInt(2)}),
+			op:   "+",
+			rhs:  values.NewArrayWithBacking(semantic.NewArrayType(semantic.BasicInt), []values.Value{values.NewInt(3)}),
+			want: values.NewArrayWithBacking(semantic.NewArrayType(semantic.BasicInt), []values.Value{values.NewInt(1), values.NewInt(2), values.NewInt(3)}),
+		},
+		// record + record
+		{
+			lhs:  values.NewObjectWithValues(map[string]values.Value{"a": values.NewInt(1), "b": values.NewInt(2)}),
+			op:   "+",
+			rhs:  values.NewObjectWithValues(map[string]values.Value{"b": values.NewInt(3), "c": values.NewString("c")}),
+			want: values.NewObjectWithValues(map[string]values.Value{"a": values.NewInt(1), "b": values.NewInt(3), "c": values.NewString("c")}),
+		},
 		// int - int
 		{lhs: int64(6), op: "-", rhs: int64(4), want: int64(2)},
 		{lhs: int64(6), op: "-", rhs: intNullValue, want: intNullValue},
@@ -61,6 +80,11 @@ func TestBinaryOperator(t *testing.T) {
 		// duration - duration
 		{lhs: values.ConvertDurationNsecs(5), op: "-", rhs: values.ConvertDurationNsecs(3), want: values.ConvertDurationNsecs(2)},
 		{lhs: values.ConvertDurationNsecs(5), op: "-", rhs: durationNullValue, want: durationNullValue},
+		// time - time
+		{lhs: values.Time(10), op: "-", rhs: values.Time(4), want: values.ConvertDurationNsecs(6)},
+		{lhs: values.Time(10), op: "-", rhs: timeNullValue, want: durationNullValue},
+		// time - duration
+		{lhs: values.Time(10), op: "-", rhs: values.ConvertDurationNsecs(4), want: values.Time(6)},
 		// int * int
 		{lhs: int64(6), op: "*", rhs: int64(4), want: int64(24)},
 		{lhs: int64(6), op: "*", rhs: intNullValue, want: intNullValue},
@@ -101,6 +125,18 @@ func TestBinaryOperator(t *testing.T) {
 		// float * float
 		{lhs: 3.8, op: "^", rhs: 2.0, want: 14.44},
 		{lhs: 4.5, op: "^", rhs: floatNullValue, want: floatNullValue},
+		// int ** int
+		{lhs: int64(2), op: "**", rhs: int64(10), want: int64(1024)},
+		{lhs: int64(-3), op: "**", rhs: int64(3), want: int64(-27)},
+		{lhs: int64(5), op: "**", rhs: int64(0), want: int64(1)},
+		{lhs: int64(6), op: "**", rhs: intNullValue, want: intNullValue},
+		{lhs: int64(2), op: "**", rhs: int64(-1), want: nil, wantErr: errors.New(codes.FailedPrecondition, "cannot raise an integer to a negative power")},
+		// uint ** uint
+		{lhs: uint64(3), op: "**", rhs: uint64(2), want: uint64(9)},
+		{lhs: uint64(7), op: "**", rhs: uintNullValue, want: uintNullValue},
+		// float ** float
+		{lhs: 4.0, op: "**", rhs: 0.5, want: 2.0},
+		{lhs: 4.5, op: "**", rhs: floatNullValue, want: floatNullValue},
 		// int <= int
 		{lhs: int64(6), op: "<=", rhs: int64(4), want: false},
 		{lhs: int64(4), op: "<=", rhs: int64(4), want: true},
@@ -628,6 +664,8 @@ func Value(v interface{}) values.Value {
 			return values.NewNull(semantic.BasicDuration)
 		}
 		return values.NewDuration(*v)
+	case values.Value:
+		return v
 	}
 	return values.New(v)
 }